@@ -0,0 +1,138 @@
+// Time-of-day math on minutes since midnight
+
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Parse "HH:MM" (24-hour, surrounding whitespace ignored) into minutes
+/// since midnight. A one-digit hour is accepted, like "9:05"; minutes
+/// always have two digits ("9:5" is refused).
+pub fn parse_time(text: &str) -> Option<u32> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let hour = digits(hour, 1..=2)?;
+    let minute = digits(minute, 2..=2)?;
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+fn digits(text: &str, len: core::ops::RangeInclusive<usize>) -> Option<u32> {
+    if !len.contains(&text.len()) || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Whether `minute` falls in the window from `start` up to (not including)
+/// `end`. A window whose end is before its start runs past midnight; equal
+/// ends make an empty window.
+pub fn in_window(start: u32, end: u32, minute: u32) -> bool {
+    if start <= end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// Value `elapsed` into a linear ramp from `from` to `to` lasting `length`
+/// (any unit, as long as both agree). Before the ramp is `from`, after it
+/// `to`.
+pub fn ramp(from: f32, to: f32, elapsed: i64, length: i64) -> f32 {
+    if length <= 0 || elapsed >= length {
+        return to;
+    }
+    if elapsed <= 0 {
+        return from;
+    }
+    from + (to - from) * (elapsed as f32 / length as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("00:00"), Some(0));
+        assert_eq!(parse_time("9:05"), Some(9 * 60 + 5));
+        assert_eq!(parse_time(" 21:30 "), Some(21 * 60 + 30));
+        assert_eq!(parse_time("23:59"), Some(MINUTES_PER_DAY - 1));
+    }
+
+    #[test]
+    fn refuses_malformed_times() {
+        for text in [
+            "",
+            ":",
+            "24:00",
+            "9:5",
+            "12:60",
+            "123:00",
+            "12:345",
+            "12",
+            "12:",
+            ":30",
+            "-1:00",
+            "+1:00",
+            "1a:00",
+            "12:3b",
+            "12-30",
+            "12:30:00",
+            "١٢:٣٠",
+        ] {
+            assert_eq!(parse_time(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn windows_within_a_day() {
+        let (start, end) = (8 * 60, 17 * 60);
+        assert!(!in_window(start, end, start - 1));
+        assert!(in_window(start, end, start));
+        assert!(in_window(start, end, end - 1));
+        assert!(!in_window(start, end, end));
+    }
+
+    #[test]
+    fn windows_that_wrap_midnight() {
+        let (start, end) = (22 * 60, 6 * 60);
+        assert!(in_window(start, end, start));
+        assert!(in_window(start, end, MINUTES_PER_DAY - 1));
+        assert!(in_window(start, end, 0));
+        assert!(in_window(start, end, end - 1));
+        assert!(!in_window(start, end, end));
+        assert!(!in_window(start, end, 12 * 60));
+        assert!(!in_window(start, end, start - 1));
+    }
+
+    #[test]
+    fn equal_ends_make_an_empty_window() {
+        for at in [0, 12 * 60, MINUTES_PER_DAY - 1] {
+            for minute in [0, at, at + 1, MINUTES_PER_DAY - 1] {
+                assert!(!in_window(at, at, minute), "{} {}", at, minute);
+            }
+        }
+    }
+
+    #[test]
+    fn ramps_between_levels() {
+        assert_eq!(ramp(0.2, 0.6, -5, 10), 0.2);
+        assert_eq!(ramp(0.2, 0.6, 0, 10), 0.2);
+        assert!((ramp(0.2, 0.6, 5, 10) - 0.4).abs() < 1e-6);
+        assert_eq!(ramp(0.2, 0.6, 10, 10), 0.6);
+        assert_eq!(ramp(0.2, 0.6, 20, 10), 0.6);
+        // Down as well as up
+        assert!((ramp(0.6, 0.2, 5, 10) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ramp_without_length_is_at_its_end() {
+        for length in [0, -1, i64::MIN] {
+            for elapsed in [i64::MIN, -1, 0, 1, i64::MAX] {
+                assert_eq!(
+                    ramp(0.2, 0.6, elapsed, length),
+                    0.6,
+                    "{} {}",
+                    elapsed,
+                    length
+                );
+            }
+        }
+    }
+}
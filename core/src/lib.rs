@@ -0,0 +1,13 @@
+// Pure logic for SaveMyEyes (no_std, no dependencies)
+//
+// Everything here is plain arithmetic on the values passed in: no clock,
+// no files, no network, no allocation. Time comes in as arguments, so the
+// same code runs in the apps, in tools, and on any CI machine without
+// Windows or macOS frameworks. The shared crate builds on it and re-exports
+// what the platforms use, so they never depend on this crate directly.
+
+#![no_std]
+
+pub mod clock;
+pub mod pause;
+pub mod version;
@@ -0,0 +1,99 @@
+// Timed pause state machine
+//
+// Idle, or paused until a deadline. The caller supplies the clock, so `T`
+// is whatever instant type it has (std::time::Instant in the apps, plain
+// numbers anywhere else).
+
+/// A pause that is either off or running until some instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pause<T> {
+    until: Option<T>,
+}
+
+impl<T: Copy + PartialOrd> Pause<T> {
+    pub const fn new() -> Self {
+        Self { until: None }
+    }
+
+    /// Pause until `until`, replacing any running pause
+    pub fn start(&mut self, until: T) {
+        self.until = Some(until);
+    }
+
+    /// End the pause early. Returns false if there was none.
+    pub fn end(&mut self) -> bool {
+        self.until.take().is_some()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.until.is_some()
+    }
+
+    /// When the running pause ends
+    pub fn until(&self) -> Option<T> {
+        self.until
+    }
+
+    /// True (and the pause is over) if it has run out by `now`, false if
+    /// there is none or it hasn't
+    pub fn take_expired(&mut self, now: T) -> bool {
+        match self.until {
+            Some(at) if at <= now => {
+                self.until = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_pause_never_expires() {
+        let mut pause = Pause::<u64>::new();
+        assert!(!pause.is_active());
+        assert!(!pause.take_expired(u64::MAX));
+        assert!(!pause.end());
+    }
+
+    #[test]
+    fn expires_at_its_deadline_once() {
+        let mut pause = Pause::new();
+        pause.start(100u64);
+        assert!(!pause.take_expired(99));
+        assert!(pause.is_active());
+        assert!(pause.take_expired(100));
+        assert!(!pause.is_active());
+        assert_eq!(pause.until(), None);
+        assert!(!pause.take_expired(200));
+    }
+
+    #[test]
+    fn late_check_still_expires() {
+        let mut pause = Pause::new();
+        pause.start(100u64);
+        assert!(pause.take_expired(10_000));
+    }
+
+    #[test]
+    fn restart_replaces_the_deadline() {
+        let mut pause = Pause::new();
+        pause.start(100u64);
+        pause.start(300);
+        assert!(!pause.take_expired(200));
+        assert_eq!(pause.until(), Some(300));
+        assert!(pause.end());
+        assert!(!pause.take_expired(300));
+    }
+
+    #[test]
+    fn works_with_float_instants() {
+        let mut pause = Pause::new();
+        pause.start(1.5f64);
+        assert!(!pause.take_expired(1.0));
+        assert!(pause.take_expired(1.5));
+    }
+}
@@ -0,0 +1,133 @@
+// Release version comparison
+
+use core::cmp::Ordering;
+
+/// Whether version `a` is newer than `b`. The dotted release numbers are
+/// compared first: missing parts count as 0 ("1.2" == "1.2.0") and parts
+/// that aren't numbers are skipped. A pre-release ("1.2.0-beta.2") is older
+/// than its release and ordered by its dot-separated identifiers as in
+/// semver; build metadata ("+build.5") is ignored.
+pub fn newer(a: &str, b: &str) -> bool {
+    compare(a, b) == Ordering::Greater
+}
+
+fn compare(a: &str, b: &str) -> Ordering {
+    let (release_a, pre_a) = split(a);
+    let (release_b, pre_b) = split(b);
+    compare_release(release_a, release_b).then_with(|| match (pre_a, pre_b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(pre_a), Some(pre_b)) => compare_pre(pre_a, pre_b),
+    })
+}
+
+/// Release numbers and pre-release of a version, without build metadata
+fn split(version: &str) -> (&str, Option<&str>) {
+    let version = version.trim();
+    let version = version.split_once('+').map_or(version, |(v, _)| v);
+    match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    }
+}
+
+fn compare_release(a: &str, b: &str) -> Ordering {
+    let mut va = a.split('.').filter_map(|s| s.parse::<u32>().ok());
+    let mut vb = b.split('.').filter_map(|s| s.parse::<u32>().ok());
+    loop {
+        match (va.next(), vb.next()) {
+            (None, None) => return Ordering::Equal,
+            (ca, cb) => {
+                let (ca, cb) = (ca.unwrap_or(0), cb.unwrap_or(0));
+                if ca != cb {
+                    return ca.cmp(&cb);
+                }
+            }
+        }
+    }
+}
+
+/// Semver pre-release order: numeric identifiers numerically and below
+/// alphanumeric ones, which compare as text; more identifiers is newer
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut ia = a.split('.');
+    let mut ib = b.split('.');
+    loop {
+        let order = match (ia.next(), ib.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_release_numbers() {
+        assert!(newer("0.9.6", "0.9.5"));
+        assert!(newer("0.10.0", "0.9.5"));
+        assert!(newer("1.0.0", "0.99.99"));
+        assert!(!newer("0.9.5", "0.9.5"));
+        assert!(!newer("0.9.4", "0.9.5"));
+    }
+
+    #[test]
+    fn missing_parts_count_as_zero() {
+        assert!(!newer("1.2", "1.2.0"));
+        assert!(!newer("1.2.0", "1.2"));
+        assert!(newer("1.2.0.1", "1.2"));
+        assert!(!newer("1.2", "1.2.0.1"));
+        assert!(newer("2", "1.9.9"));
+        assert!(!newer("", ""));
+        assert!(newer("0.0.1", ""));
+    }
+
+    #[test]
+    fn pre_release_is_older_than_its_release() {
+        assert!(!newer("1.2.0-beta.1", "1.2.0"));
+        assert!(newer("1.2.0", "1.2.0-beta.1"));
+        assert!(newer("1.2.0-rc.1", "1.1.9"));
+        assert!(!newer("1.2-rc.1", "1.2.0"));
+    }
+
+    #[test]
+    fn orders_pre_releases() {
+        // The semver spec's example order
+        let order = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for (i, older) in order.iter().enumerate() {
+            for newer_one in &order[i + 1..] {
+                assert!(newer(newer_one, older), "{} > {}", newer_one, older);
+                assert!(!newer(older, newer_one), "{} < {}", older, newer_one);
+            }
+        }
+    }
+
+    #[test]
+    fn build_metadata_is_ignored() {
+        assert!(!newer("1.2.0+build.7", "1.2.0"));
+        assert!(!newer("1.2.0", "1.2.0+build.7"));
+        assert!(newer("1.2.1+build.1", "1.2.0+build.9"));
+    }
+}
@@ -0,0 +1,265 @@
+// The X11 app: startup, the event loop and the hotkey actions. Wayland
+// sessions run wayland.rs instead.
+//
+// Everything runs on the main thread around one X connection. Startup loads
+// config, lists the monitors, grabs the hotkeys and shows the overlays if
+// dimming is on; the loop then waits for key presses (hotkeys.rs) and RandR
+// screen changes, after which the monitors are listed again and the
+// overlays rebuilt. Only the update check runs on its own thread.
+//
+// The hotkeys do what they do on the other platforms: toggle turns dimming
+// off and back on at the last level, increase and decrease change the level
+// (of the monitor under the pointer in multi-monitor mode, and of the
+// monitors grouped with it), turning dimming on first if it is off. Every
+// change is saved to config. There is no tray icon or settings window yet;
+// config.json (monitor groups included) is edited by hand and read at
+// startup.
+
+use std::time::Instant;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::{ConnectionExt as _, NotifyMask};
+use x11rb::protocol::xproto::{ConnectionExt as _, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use savemyeyes_shared::adjust_ramp::HoldRamp;
+use savemyeyes_shared::dimming::{self, MAX_OPACITY};
+use savemyeyes_shared::displays::{self, DisplayInfo};
+use savemyeyes_shared::hotkeys::HotkeyAction;
+use savemyeyes_shared::monitor_groups;
+use savemyeyes_shared::monitor_id::MonitorId;
+
+use crate::config::{self, AppConfig};
+use crate::hotkeys::Hotkeys;
+use crate::monitors::{self, MonitorInfo};
+use crate::overlay::Overlays;
+use crate::updater;
+
+struct App {
+    conn: RustConnection,
+    root: Window,
+    config: AppConfig,
+    monitors: Vec<MonitorInfo>,
+    overlays: Overlays,
+    hotkeys: Hotkeys,
+    ramp: HoldRamp,
+}
+
+/// Run until the X connection closes. Returns why the app couldn't start
+/// or stopped.
+pub fn run() -> Result<(), String> {
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|e| format!("Cannot open the X display: {}", e))?;
+    let root = conn.setup().roots[screen_num].root;
+
+    let mut cfg = config::load_config();
+    let fixes = config::load_fixes();
+    if !fixes.is_empty() {
+        eprintln!("{}", config::describe_load_fixes(&fixes));
+    }
+    updater::set_endpoint(cfg.update_endpoint.as_deref());
+
+    let monitors = monitors::list(&conn, root).map_err(|e| e.to_string())?;
+    let infos: Vec<DisplayInfo> = monitors.iter().map(|m| m.display_info()).collect();
+    // Settings saved by index or name now follow the monitor id
+    if cfg.adopt_monitor_ids(&infos) {
+        config::save_config(&cfg);
+    }
+    displays::record_layout(&infos);
+
+    let mut overlays = Overlays::new(&conn, screen_num).map_err(|e| e.to_string())?;
+    overlays.set_curve(cfg.opacity_curve);
+    conn.randr_select_input(root, NotifyMask::SCREEN_CHANGE)
+        .map_err(|e| e.to_string())?;
+
+    let mut app = App {
+        hotkeys: Hotkeys::new(root),
+        conn,
+        root,
+        config: cfg,
+        monitors,
+        overlays,
+        ramp: HoldRamp::new(),
+    };
+    app.register_hotkeys();
+    if app.config.is_enabled {
+        app.show();
+    }
+
+    updater::spawn_startup_check(&app.config);
+
+    loop {
+        let event = app.conn.wait_for_event().map_err(|e| e.to_string())?;
+        match event {
+            Event::KeyPress(press) => {
+                let state = u16::from(press.state);
+                if let Some(action) = app.hotkeys.action_for(press.detail, state) {
+                    app.run_action(action);
+                }
+            }
+            Event::RandrScreenChangeNotify(_) => app.monitors_changed(),
+            _ => {}
+        }
+    }
+}
+
+impl App {
+    fn register_hotkeys(&mut self) {
+        // Hotkey-less mode: no grabs at all
+        if self.config.disable_global_shortcuts {
+            return;
+        }
+        // Movie mode and the boost hotkey have no X11 implementation yet
+        let bindings: Vec<_> = self
+            .config
+            .key_bindings()
+            .into_iter()
+            .filter(|(_, action)| !matches!(action, HotkeyAction::MovieMode | HotkeyAction::Boost))
+            .collect();
+        match self.hotkeys.set_keys(&self.conn, &bindings) {
+            Ok(failed) => {
+                for combo in failed {
+                    eprintln!("[hotkeys] {} is taken by another app or has no key", combo);
+                }
+            }
+            Err(e) => eprintln!("[hotkeys] could not grab the shortcuts: {}", e),
+        }
+    }
+
+    /// Level of monitor `id` as configured
+    fn level_for(config: &AppConfig, id: &MonitorId) -> f32 {
+        if config.multi_monitor {
+            config.level_for(id)
+        } else {
+            config.opacity
+        }
+    }
+
+    fn show(&mut self) {
+        if self.config.designer_mode {
+            return;
+        }
+        let config = &self.config;
+        let shown = self
+            .overlays
+            .show(&self.conn, &self.monitors, |id| Self::level_for(config, id));
+        if let Err(e) = shown {
+            eprintln!("SaveMyEyes: {}", e);
+        }
+    }
+
+    fn hide(&mut self) {
+        if let Err(e) = self.overlays.hide(&self.conn) {
+            eprintln!("[overlay] hide failed: {}", e);
+        }
+    }
+
+    fn apply_levels(&mut self) {
+        if !self.overlays.is_visible() {
+            self.show();
+            return;
+        }
+        let config = &self.config;
+        if let Err(e) = self
+            .overlays
+            .set_levels(&self.conn, |id| Self::level_for(config, id))
+        {
+            eprintln!("[overlay] level change failed: {}", e);
+        }
+    }
+
+    fn run_action(&mut self, action: HotkeyAction) {
+        match action {
+            HotkeyAction::Toggle => self.toggle(),
+            HotkeyAction::Increase | HotkeyAction::Decrease => {
+                let up = action == HotkeyAction::Increase;
+                let profile = self.config.adjust_ramp.profile();
+                let delta = self.ramp.next(profile, up, Instant::now());
+                if delta != 0.0 {
+                    self.change_level(delta);
+                }
+            }
+            HotkeyAction::MovieMode | HotkeyAction::Boost => {}
+        }
+    }
+
+    /// Dimming on/off, back to the level it had before.
+    fn toggle(&mut self) {
+        let cfg = &mut self.config;
+        if cfg.is_enabled {
+            if cfg.opacity > 0.0 {
+                cfg.last_opacity = cfg.opacity;
+            }
+            cfg.is_enabled = false;
+            cfg.opacity = 0.0;
+            config::save_config(cfg);
+            self.hide();
+        } else {
+            cfg.is_enabled = true;
+            cfg.opacity = cfg.last_opacity;
+            config::save_config(cfg);
+            self.show();
+        }
+    }
+
+    /// Change the level by `delta`, turning dimming on if it is off
+    fn change_level(&mut self, delta: f32) {
+        let pointer = self
+            .conn
+            .query_pointer(self.root)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        let cfg = &mut self.config;
+        if !cfg.is_enabled {
+            cfg.is_enabled = true;
+            cfg.opacity = cfg.last_opacity;
+        }
+        let message = if cfg.multi_monitor {
+            let index = pointer.map_or(0, |p| {
+                monitors::index_at_point(&self.monitors, p.root_x, p.root_y)
+            });
+            let Some(monitor) = self.monitors.get(index) else {
+                return;
+            };
+            let level =
+                dimming::quantize((cfg.level_for(&monitor.id) + delta).clamp(0.0, MAX_OPACITY));
+            // Grouped monitors follow (monitor_groups.rs)
+            cfg.set_level_for(&monitor.id, level);
+            let name = match monitor_groups::group_of(&cfg.monitor_groups, &monitor.id) {
+                Some(group) => &group.name,
+                None => &monitor.name,
+            };
+            format!("{}: {}", name, dimming::percent_label(level))
+        } else {
+            let level = dimming::quantize((cfg.opacity + delta).clamp(0.0, MAX_OPACITY));
+            cfg.opacity = level;
+            if level > 0.0 {
+                cfg.last_opacity = level;
+            }
+            format!("Dimming: {}", dimming::percent_label(level))
+        };
+        config::save_config(cfg);
+        eprintln!("SaveMyEyes: {}", message);
+        self.apply_levels();
+    }
+
+    /// Monitor added, removed, moved or resized: rebuild the overlays
+    fn monitors_changed(&mut self) {
+        match monitors::list(&self.conn, self.root) {
+            Ok(monitors) => self.monitors = monitors,
+            Err(e) => {
+                eprintln!("[monitors] could not list monitors: {}", e);
+                return;
+            }
+        }
+        let infos: Vec<DisplayInfo> = self.monitors.iter().map(|m| m.display_info()).collect();
+        if self.config.adopt_monitor_ids(&infos) {
+            config::save_config(&self.config);
+        }
+        displays::record_layout(&infos);
+        if self.overlays.is_visible() {
+            self.show();
+        }
+    }
+}
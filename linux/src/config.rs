@@ -0,0 +1,3 @@
+// Re-export shared config types and functions.
+
+pub use savemyeyes_shared::config::*;
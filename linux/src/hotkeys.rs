@@ -0,0 +1,159 @@
+// Global hotkeys on X11 via passive key grabs on the root window.
+//
+// Each shortcut from config (shared hotkeys.rs) is turned into a keysym,
+// then into the keycode that produces it in the current keyboard mapping,
+// and grabbed with XGrabKey. Caps Lock and Num Lock change the modifier
+// state X reports, so every combo is grabbed four times, with and without
+// each of them, and both are masked off again when a key press comes in.
+// A grab fails with BadAccess when another client holds the same combo;
+// that shortcut is reported and left out.
+//
+// Ctrl, Alt (Mod1), Shift and Win/Super (Mod4) map to the shared modifiers.
+// Keys the mapping has no keycode for (e.g. F13–F24 on most keyboards) are
+// skipped. Holding increase or decrease sends auto-repeat presses, which go
+// through the shared hold ramp (adjust_ramp.rs) as on the other platforms.
+
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::{ConnectionExt as _, GrabMode, Keycode, ModMask, Window};
+
+use savemyeyes_shared::hotkeys::{HotkeyAction, Key, KeyCombo, Modifiers, NamedKey};
+
+/// Lock modifier bits grabbed along with every combo: none, Caps Lock
+/// (Lock), Num Lock (Mod2) and both
+const LOCKS: [u16; 4] = [0, 1 << 1, 1 << 4, (1 << 1) | (1 << 4)];
+
+/// The combos grabbed on one root window
+pub struct Hotkeys {
+    root: Window,
+    grabbed: Vec<(Keycode, ModMask, HotkeyAction)>,
+}
+
+impl Hotkeys {
+    pub fn new(root: Window) -> Self {
+        Self {
+            root,
+            grabbed: Vec::new(),
+        }
+    }
+
+    /// Grab `bindings`, replacing the grabs made before. Returns the combos
+    /// that couldn't be grabbed, for the log.
+    pub fn set_keys(
+        &mut self,
+        conn: &impl Connection,
+        bindings: &[(KeyCombo, HotkeyAction)],
+    ) -> Result<Vec<KeyCombo>, ReplyError> {
+        self.unregister_all(conn)?;
+        let setup = conn.setup();
+        let (first, last) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn
+            .get_keyboard_mapping(first, last - first + 1)?
+            .reply()?;
+        let per_keycode = mapping.keysyms_per_keycode.max(1) as usize;
+
+        let mut failed = Vec::new();
+        for &(combo, action) in bindings {
+            let keysym = keysym(combo.key);
+            let keycode = mapping
+                .keysyms
+                .chunks(per_keycode)
+                .position(|syms| syms.iter().take(2).any(|&sym| sym == keysym))
+                .map(|offset| first + offset as u8);
+            let Some(keycode) = keycode else {
+                failed.push(combo);
+                continue;
+            };
+            let modifiers = mod_mask(combo.modifiers);
+            let mut taken = false;
+            for lock in LOCKS {
+                let grab = conn.grab_key(
+                    true,
+                    self.root,
+                    modifiers | lock,
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )?;
+                taken |= grab.check().is_err();
+            }
+            if taken {
+                // Release whatever part of it we did get
+                for lock in LOCKS {
+                    conn.ungrab_key(keycode, self.root, modifiers | lock)?;
+                }
+                failed.push(combo);
+            } else {
+                self.grabbed.push((keycode, modifiers, action));
+            }
+        }
+        conn.flush()?;
+        Ok(failed)
+    }
+
+    /// Release every grab
+    pub fn unregister_all(&mut self, conn: &impl Connection) -> Result<(), ReplyError> {
+        for (keycode, modifiers, _) in self.grabbed.drain(..) {
+            for lock in LOCKS {
+                conn.ungrab_key(keycode, self.root, modifiers | lock)?;
+            }
+        }
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// The action of a key press with modifier `state` (KeyPress event)
+    pub fn action_for(&self, keycode: Keycode, state: u16) -> Option<HotkeyAction> {
+        let relevant = ModMask::CONTROL | ModMask::M1 | ModMask::SHIFT | ModMask::M4;
+        let state = relevant & state;
+        self.grabbed
+            .iter()
+            .find(|(code, modifiers, _)| *code == keycode && *modifiers == state)
+            .map(|(_, _, action)| *action)
+    }
+}
+
+fn mod_mask(modifiers: Modifiers) -> ModMask {
+    let mut mask = ModMask::default();
+    if modifiers.ctrl {
+        mask |= ModMask::CONTROL;
+    }
+    if modifiers.alt {
+        mask |= ModMask::M1;
+    }
+    if modifiers.shift {
+        mask |= ModMask::SHIFT;
+    }
+    if modifiers.meta {
+        mask |= ModMask::M4;
+    }
+    mask
+}
+
+/// X keysym of `key` (keysymdef.h)
+fn keysym(key: Key) -> u32 {
+    match key {
+        // Letters by their lower-case keysym, which the mapping lists first
+        Key::Char(c) => c.to_ascii_lowercase() as u32,
+        Key::Function(n) => 0xffbe + n as u32 - 1,
+        Key::Named(key) => match key {
+            NamedKey::Up => 0xff52,
+            NamedKey::Down => 0xff54,
+            NamedKey::Left => 0xff51,
+            NamedKey::Right => 0xff53,
+            NamedKey::Home => 0xff50,
+            NamedKey::End => 0xff57,
+            NamedKey::PageUp => 0xff55,
+            NamedKey::PageDown => 0xff56,
+            NamedKey::Insert => 0xff63,
+            NamedKey::Delete => 0xffff,
+            NamedKey::Space => 0x0020,
+            NamedKey::Tab => 0xff09,
+            NamedKey::Enter => 0xff0d,
+            NamedKey::Escape => 0xff1b,
+            NamedKey::Backspace => 0xff08,
+            NamedKey::Period => 0x002e,
+            NamedKey::Comma => 0x002c,
+        },
+    }
+}
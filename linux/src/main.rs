@@ -0,0 +1,28 @@
+// SaveMyEyes — screen dimmer for Linux
+// X11 sessions get override-redirect ARGB overlays per monitor via x11rb,
+// Wayland sessions gamma ramps via wlr-gamma-control; config, hotkeys and
+// the update check come from the shared crate.
+
+mod app;
+mod config;
+mod hotkeys;
+mod monitors;
+mod overlay;
+mod self_test;
+mod updater;
+mod wayland;
+
+fn main() {
+    if let Some(code) = self_test::run_from_args() {
+        std::process::exit(code);
+    }
+    let result = if wayland::session() {
+        wayland::run()
+    } else {
+        app::run()
+    };
+    if let Err(e) = result {
+        eprintln!("SaveMyEyes: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,109 @@
+// Monitor enumeration over RandR.
+//
+// `list` returns a MonitorInfo per RandR monitor (RRGetMonitors, RandR 1.5)
+// in the server's order, which is the order the overlays are created in.
+// Names are the RandR monitor names ("DP-1", "eDP-1"). The MonitorId
+// per-monitor settings are keyed by (shared monitor_id.rs) is hashed from
+// the EDID manufacturer, product code and serial number of the monitor's
+// first output, or from its name and position when the output has no EDID
+// (virtual and some docked outputs). Nothing is kept between calls.
+
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window};
+
+use savemyeyes_shared::displays::DisplayInfo;
+use savemyeyes_shared::monitor_id::{self, MonitorId};
+
+/// One connected monitor
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    /// Key of the monitor's settings in config
+    pub id: MonitorId,
+    /// RandR monitor name, e.g. "DP-1"
+    pub name: String,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    pub fn display_info(&self) -> DisplayInfo {
+        DisplayInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            x: self.x as i32,
+            y: self.y as i32,
+            width: self.width as i32,
+            height: self.height as i32,
+            // X11 has no per-monitor scale; toolkits pick their own
+            scale: 1.0,
+            primary: self.primary,
+            hdr: false,
+        }
+    }
+}
+
+/// Active monitors of the screen whose root window is `root`
+pub fn list(conn: &impl Connection, root: Window) -> Result<Vec<MonitorInfo>, ReplyError> {
+    let edid_atom = conn.intern_atom(false, b"EDID")?.reply()?.atom;
+    let mut found = Vec::new();
+    for monitor in conn.randr_get_monitors(root, true)?.reply()?.monitors {
+        let name = conn.get_atom_name(monitor.name)?.reply()?.name;
+        let name = String::from_utf8_lossy(&name).into_owned();
+        let edid = match monitor.outputs.first() {
+            Some(&output) => {
+                conn.randr_get_output_property(
+                    output,
+                    edid_atom,
+                    AtomEnum::ANY,
+                    0,
+                    32,
+                    false,
+                    false,
+                )?
+                .reply()?
+                .data
+            }
+            None => Vec::new(),
+        };
+        let id = match edid.get(8..16) {
+            // Manufacturer, product code and serial number
+            Some(identity) if identity.iter().any(|&b| b != 0) => {
+                let hex: String = identity.iter().map(|b| format!("{:02x}", b)).collect();
+                MonitorId::from_hardware(&[&hex])
+            }
+            _ => MonitorId::from_name_position(&name, monitor.x as i32, monitor.y as i32),
+        };
+        found.push(MonitorInfo {
+            id,
+            name,
+            x: monitor.x,
+            y: monitor.y,
+            width: monitor.width,
+            height: monitor.height,
+            primary: monitor.primary,
+        });
+    }
+    let mut ids: Vec<MonitorId> = found.iter().map(|m| m.id.clone()).collect();
+    monitor_id::assign(&mut ids);
+    for (monitor, id) in found.iter_mut().zip(ids) {
+        monitor.id = id;
+    }
+    Ok(found)
+}
+
+/// Index of the monitor containing the point, or 0
+pub fn index_at_point(monitors: &[MonitorInfo], x: i16, y: i16) -> usize {
+    monitors
+        .iter()
+        .position(|m| {
+            let (left, top) = (m.x as i32, m.y as i32);
+            let (x, y) = (x as i32, y as i32);
+            x >= left && x < left + m.width as i32 && y >= top && y < top + m.height as i32
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,282 @@
+// Screen dimmer using ARGB overlay windows on X11.
+//
+// One override-redirect window per RandR monitor, covering it, with a 32-bit
+// ARGB visual and a background of premultiplied black at the level's alpha,
+// so the compositor blends it over everything below. The window manager
+// never sees override-redirect windows (no decorations, no taskbar entry,
+// no focus), and an empty SHAPE input region lets every click through.
+//
+// Alpha needs a compositing manager (the _NET_WM_CM_Sn selection has an
+// owner): without one the X server would draw the overlay as solid black,
+// so `show` refuses and says why instead. There is no capture exclusion on
+// X11: screenshots and recordings include the overlay.
+//
+// The overlays are raised after every change; nothing else fights for the
+// top of the stack except other override-redirect windows (menus, tooltips,
+// notifications), which are short-lived.
+
+use x11rb::connection::Connection;
+use x11rb::errors::{ReplyError, ReplyOrIdError};
+use x11rb::protocol::shape::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{
+    ChangeWindowAttributesAux, ClipOrdering, ColormapAlloc, ConfigureWindowAux, ConnectionExt as _,
+    CreateWindowAux, StackMode, VisualClass, Visualid, Window, WindowClass,
+};
+
+use savemyeyes_shared::backend::OverlayBackend;
+use savemyeyes_shared::dimming::{self, OpacityCurve};
+use savemyeyes_shared::displays::DisplayInfo;
+use savemyeyes_shared::monitor_id::MonitorId;
+
+use crate::monitors::MonitorInfo;
+
+/// One monitor's overlay
+struct Overlay {
+    window: Window,
+    id: MonitorId,
+    /// Level shown, before the curve
+    level: f32,
+}
+
+/// Overlays of one X screen
+pub struct Overlays {
+    root: Window,
+    screen_num: usize,
+    /// 32-bit TrueColor visual and a colormap for it, None if the server
+    /// has no ARGB visual
+    argb: Option<(Visualid, u32)>,
+    windows: Vec<Overlay>,
+    curve: OpacityCurve,
+}
+
+impl Overlays {
+    pub fn new(conn: &impl Connection, screen_num: usize) -> Result<Self, ReplyOrIdError> {
+        let screen = &conn.setup().roots[screen_num];
+        let visual = screen
+            .allowed_depths
+            .iter()
+            .filter(|depth| depth.depth == 32)
+            .flat_map(|depth| depth.visuals.iter())
+            .find(|visual| visual.class == VisualClass::TRUE_COLOR)
+            .map(|visual| visual.visual_id);
+        let argb = match visual {
+            Some(visual) => {
+                let colormap = conn.generate_id()?;
+                conn.create_colormap(ColormapAlloc::NONE, colormap, screen.root, visual)?;
+                Some((visual, colormap))
+            }
+            None => None,
+        };
+        Ok(Self {
+            root: screen.root,
+            screen_num,
+            argb,
+            windows: Vec::new(),
+            curve: OpacityCurve::Linear,
+        })
+    }
+
+    /// Select the opacity curve used by subsequent levels.
+    pub fn set_curve(&mut self, curve: OpacityCurve) {
+        self.curve = curve;
+    }
+
+    /// Whether a compositing manager runs, without which alpha can't work
+    pub fn compositing(&self, conn: &impl Connection) -> Result<bool, ReplyError> {
+        let name = format!("_NET_WM_CM_S{}", self.screen_num);
+        let atom = conn.intern_atom(false, name.as_bytes())?.reply()?.atom;
+        Ok(conn.get_selection_owner(atom)?.reply()?.owner != x11rb::NONE)
+    }
+
+    /// Cover every monitor at its level (`level(monitor id)`), replacing
+    /// the overlays shown before. Returns why nothing could be shown.
+    pub fn show(
+        &mut self,
+        conn: &impl Connection,
+        monitors: &[MonitorInfo],
+        level: impl Fn(&MonitorId) -> f32,
+    ) -> Result<(), String> {
+        self.hide(conn).map_err(|e| e.to_string())?;
+        let Some((visual, colormap)) = self.argb else {
+            return Err("The X server has no 32-bit visual for transparent windows".into());
+        };
+        if !self.compositing(conn).map_err(|e| e.to_string())? {
+            return Err(
+                "Dimming needs a compositing window manager or compositor (e.g. picom)".into(),
+            );
+        }
+        self.create(conn, monitors, visual, colormap, level)
+            .map_err(|e| format!("Could not create the overlays: {}", e))?;
+        eprintln!("[overlay] dimming {} monitor(s)", self.windows.len());
+        Ok(())
+    }
+
+    fn create(
+        &mut self,
+        conn: &impl Connection,
+        monitors: &[MonitorInfo],
+        visual: Visualid,
+        colormap: u32,
+        level: impl Fn(&MonitorId) -> f32,
+    ) -> Result<(), ReplyOrIdError> {
+        for monitor in monitors {
+            let window = conn.generate_id()?;
+            let monitor_level = level(&monitor.id);
+            let aux = CreateWindowAux::new()
+                .background_pixel(pixel(self.curve, monitor_level))
+                .border_pixel(0)
+                .override_redirect(1)
+                .colormap(colormap);
+            conn.create_window(
+                32,
+                window,
+                self.root,
+                monitor.x,
+                monitor.y,
+                monitor.width,
+                monitor.height,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                visual,
+                &aux,
+            )?;
+            // Empty input region: clicks go to the windows below
+            conn.shape_rectangles(
+                shape::SO::SET,
+                shape::SK::INPUT,
+                ClipOrdering::UNSORTED,
+                window,
+                0,
+                0,
+                &[],
+            )?;
+            conn.map_window(window)?;
+            self.windows.push(Overlay {
+                window,
+                id: monitor.id.clone(),
+                level: monitor_level,
+            });
+        }
+        self.raise(conn)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Remove every overlay
+    pub fn hide(&mut self, conn: &impl Connection) -> Result<(), ReplyOrIdError> {
+        for overlay in self.windows.drain(..) {
+            conn.destroy_window(overlay.window)?;
+        }
+        conn.flush()?;
+        Ok(())
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.windows.is_empty()
+    }
+
+    /// Level of each overlay, in monitor order
+    pub fn levels(&self) -> Vec<f32> {
+        self.windows.iter().map(|o| o.level).collect()
+    }
+
+    /// Change every overlay's level to `level(monitor id)` in place
+    pub fn set_levels(
+        &mut self,
+        conn: &impl Connection,
+        level: impl Fn(&MonitorId) -> f32,
+    ) -> Result<(), ReplyOrIdError> {
+        let curve = self.curve;
+        for overlay in &mut self.windows {
+            overlay.level = level(&overlay.id);
+            let aux =
+                ChangeWindowAttributesAux::new().background_pixel(pixel(curve, overlay.level));
+            conn.change_window_attributes(overlay.window, &aux)?;
+            // Repaint with the new background
+            conn.clear_area(false, overlay.window, 0, 0, 0, 0)?;
+        }
+        self.raise(conn)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Change the level of the overlay of monitor `index` (overlays are
+    /// created in monitor order)
+    pub fn set_level(
+        &mut self,
+        conn: &impl Connection,
+        index: usize,
+        level: f32,
+    ) -> Result<(), ReplyOrIdError> {
+        let curve = self.curve;
+        let Some(overlay) = self.windows.get_mut(index) else {
+            return Ok(());
+        };
+        overlay.level = level;
+        let aux = ChangeWindowAttributesAux::new().background_pixel(pixel(curve, level));
+        conn.change_window_attributes(overlay.window, &aux)?;
+        conn.clear_area(false, overlay.window, 0, 0, 0, 0)?;
+        self.raise(conn)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    fn raise(&self, conn: &impl Connection) -> Result<(), ReplyOrIdError> {
+        let aux = ConfigureWindowAux::new().stack_mode(StackMode::ABOVE);
+        for overlay in &self.windows {
+            conn.configure_window(overlay.window, &aux)?;
+        }
+        Ok(())
+    }
+}
+
+/// Premultiplied black at the alpha of `level`
+fn pixel(curve: OpacityCurve, level: f32) -> u32 {
+    (dimming::alpha(curve.to_applied(level)) as u32) << 24
+}
+
+/// The overlays of one screen behind the shared `OverlayBackend`
+/// (backend.rs), with the connection and monitors they're shown on
+pub struct X11Backend<'a, C: Connection> {
+    pub conn: &'a C,
+    pub overlays: &'a mut Overlays,
+    pub monitors: &'a [MonitorInfo],
+}
+
+impl<C: Connection> OverlayBackend for X11Backend<'_, C> {
+    fn enumerate(&self) -> Vec<DisplayInfo> {
+        self.monitors.iter().map(|m| m.display_info()).collect()
+    }
+
+    fn show(&mut self, level: f32) -> Result<(), String> {
+        self.overlays.show(self.conn, self.monitors, |_| level)
+    }
+
+    fn hide(&mut self) {
+        if let Err(e) = self.overlays.hide(self.conn) {
+            eprintln!("[overlay] hide failed: {}", e);
+        }
+    }
+
+    fn set_opacity(&mut self, level: f32) {
+        if let Err(e) = self.overlays.set_levels(self.conn, |_| level) {
+            eprintln!("[overlay] level change failed: {}", e);
+        }
+    }
+
+    fn set_monitor_opacity(&mut self, monitor: usize, level: f32) {
+        if let Err(e) = self.overlays.set_level(self.conn, monitor, level) {
+            eprintln!("[overlay] level change failed: {}", e);
+        }
+    }
+
+    fn applied(&self) -> Vec<f32> {
+        let mut levels = self.overlays.levels();
+        levels.resize(self.monitors.len(), 0.0);
+        levels
+    }
+
+    fn is_visible(&self) -> bool {
+        self.overlays.is_visible()
+    }
+}
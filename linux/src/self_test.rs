@@ -0,0 +1,63 @@
+// `--self-test`: drive the real X11 overlays and check the result.
+//
+// Runs in place of the app (no hotkeys) and leaves config.json alone. The
+// script is the shared one (backend.rs) through overlay::X11Backend: show
+// at 30%, adjust every monitor to 60%, one monitor back to 30%, then hide,
+// reading the levels back from the overlays after each step. It needs a
+// compositor like the app does. Wayland sessions skip it: gamma control
+// only applies once the compositor answers, which the script doesn't wait
+// for.
+
+use x11rb::connection::Connection;
+
+use savemyeyes_shared::backend;
+use savemyeyes_shared::self_test::{self, Report};
+
+use crate::monitors;
+use crate::overlay::{Overlays, X11Backend};
+use crate::wayland;
+
+/// Run the self-test if `--self-test` was passed. Returns the process exit
+/// code, or None to start the app normally.
+pub fn run_from_args() -> Option<i32> {
+    self_test::requested().then(run)
+}
+
+fn run() -> i32 {
+    let mut report = Report::new();
+    if wayland::session() {
+        report.skip("backend", "Wayland session, the script drives X11 overlays");
+        return report.finish();
+    }
+    let (conn, screen_num) = match x11rb::connect(None) {
+        Ok(connected) => connected,
+        Err(e) => {
+            report.check("X display opened", false, e.to_string());
+            return report.finish();
+        }
+    };
+    let root = conn.setup().roots[screen_num].root;
+    let monitors = match monitors::list(&conn, root) {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            report.check("monitors listed", false, e.to_string());
+            return report.finish();
+        }
+    };
+    let mut overlays = match Overlays::new(&conn, screen_num) {
+        Ok(overlays) => overlays,
+        Err(e) => {
+            report.check("ARGB colormap", false, e.to_string());
+            return report.finish();
+        }
+    };
+    backend::self_test(
+        &mut X11Backend {
+            conn: &conn,
+            overlays: &mut overlays,
+            monitors: &monitors,
+        },
+        &mut report,
+    );
+    report.finish()
+}
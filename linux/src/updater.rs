@@ -0,0 +1,45 @@
+// Linux-specific update logic, delegates to shared crate
+//
+// There is no installer to run: a newer release is announced on stderr with
+// its download link, and the user unpacks it over the old binary.
+
+use std::time::Duration;
+
+use savemyeyes_shared::shutdown;
+use savemyeyes_shared::updater::now_secs;
+
+use crate::config::AppConfig;
+
+pub use savemyeyes_shared::updater::set_endpoint;
+pub use savemyeyes_shared::updater::UpdateResult;
+pub use savemyeyes_shared::updater::APP_VERSION;
+pub use savemyeyes_shared::updater::ENABLED;
+
+/// Check for updates (looks for the .tar.gz asset)
+pub fn check_for_update(current_version: &str) -> UpdateResult {
+    savemyeyes_shared::updater::check_for_update(current_version, "-linux-x86_64.tar.gz")
+}
+
+/// Announce a newer release on stderr a few seconds after startup, unless
+/// the update check is off or the user snoozed that version
+pub fn spawn_startup_check(cfg: &AppConfig) {
+    if !ENABLED || !cfg.auto_update {
+        return;
+    }
+    let snooze = cfg.update_snooze.clone();
+    shutdown::spawn("auto-update-check", move || {
+        if !shutdown::sleep(Duration::from_secs(5)) {
+            return;
+        }
+        if let UpdateResult::UpdateAvailable {
+            version,
+            download_url,
+            ..
+        } = check_for_update(APP_VERSION)
+        {
+            if snooze.should_prompt(&version, now_secs()) {
+                eprintln!("SaveMyEyes v{} is available: {}", version, download_url);
+            }
+        }
+    });
+}
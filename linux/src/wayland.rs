@@ -0,0 +1,367 @@
+// Screen dimmer for Wayland sessions using wlr-gamma-control.
+//
+// Wayland gives clients no way to keep a window above every other app's
+// windows, so the ARGB overlays of overlay.rs can't work there. Instead each
+// output's gamma ramps are scaled down, like gammastep and wlsunset do,
+// through wlr-gamma-control-unstable-v1: Sway, Hyprland, river, Wayfire,
+// labwc and other wlroots-style compositors offer it. GNOME and KDE Plasma
+// don't, and `run` says so instead of dimming nothing.
+//
+// A gamma control holds the output's ramps only while it exists: destroying
+// it, or the app exiting for whatever reason, makes the compositor restore
+// the originals. Dimming off destroys the controls; only one client can
+// hold an output's control at a time, so gammastep or a night-light daemon
+// running alongside makes ours fail (reported on stderr, the output stays
+// undimmed).
+//
+// Outputs are listed from the registry and followed as they come and go.
+// Their MonitorId (shared monitor_id.rs) hashes the make and model wl_output
+// reports, or the connector name and position when the compositor reports
+// neither. Wayland lets no client grab global shortcuts, so there are no
+// hotkeys here: config.json is read at startup, as on X11.
+
+use std::io::{Seek, Write};
+use std::os::fd::AsFd;
+
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use wayland_protocols_wlr::gamma_control::v1::client::zwlr_gamma_control_manager_v1::ZwlrGammaControlManagerV1;
+use wayland_protocols_wlr::gamma_control::v1::client::zwlr_gamma_control_v1::{
+    self, ZwlrGammaControlV1,
+};
+
+use savemyeyes_shared::dimming::MAX_OPACITY;
+use savemyeyes_shared::displays::{self, DisplayInfo};
+use savemyeyes_shared::monitor_id::{self, MonitorId};
+
+use crate::config::{self, AppConfig};
+use crate::updater;
+
+/// Whether the session is a Wayland one (the X11 overlays are used otherwise)
+pub fn session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
+
+/// One wl_output and its gamma control
+struct Output {
+    output: WlOutput,
+    /// Registry name of the wl_output global, to find it when it goes away
+    global: u32,
+    /// Connector name, e.g. "DP-1" (wl_output v4)
+    name: String,
+    make: String,
+    model: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    id: MonitorId,
+    gamma: Option<ZwlrGammaControlV1>,
+    /// Entries per ramp, 0 until the compositor has said
+    gamma_size: u32,
+    /// The compositor refused or revoked our control; not asked again
+    /// until the output is reconnected
+    failed: bool,
+}
+
+impl Output {
+    fn display_info(&self) -> DisplayInfo {
+        DisplayInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            // Scale applies to surfaces, the ramps don't care
+            scale: 1.0,
+            primary: false,
+            hdr: false,
+        }
+    }
+}
+
+struct Gamma {
+    config: AppConfig,
+    manager: Option<ZwlrGammaControlManagerV1>,
+    outputs: Vec<Output>,
+    /// An output was added, removed or described anew, or a gamma size
+    /// arrived: the ramps are applied again after the dispatch
+    changed: bool,
+}
+
+/// Run until the compositor disconnects. Returns why the app couldn't start
+/// or stopped.
+pub fn run() -> Result<(), String> {
+    let conn = Connection::connect_to_env()
+        .map_err(|e| format!("Cannot connect to the Wayland compositor: {}", e))?;
+    let mut queue = conn.new_event_queue();
+    let qh = queue.handle();
+    conn.display().get_registry(&qh, ());
+
+    let cfg = config::load_config();
+    let fixes = config::load_fixes();
+    if !fixes.is_empty() {
+        eprintln!("{}", config::describe_load_fixes(&fixes));
+    }
+    updater::set_endpoint(cfg.update_endpoint.as_deref());
+
+    let mut gamma = Gamma {
+        config: cfg,
+        manager: None,
+        outputs: Vec::new(),
+        changed: false,
+    };
+    // The globals, then the outputs' descriptions
+    queue.roundtrip(&mut gamma).map_err(|e| e.to_string())?;
+    queue.roundtrip(&mut gamma).map_err(|e| e.to_string())?;
+    if gamma.manager.is_none() {
+        return Err("This Wayland compositor doesn't offer wlr-gamma-control, \
+                    which dimming needs (Sway, Hyprland and other wlroots-based \
+                    compositors do)"
+            .into());
+    }
+    gamma.outputs_changed(&qh);
+
+    updater::spawn_startup_check(&gamma.config);
+
+    loop {
+        queue
+            .blocking_dispatch(&mut gamma)
+            .map_err(|e| e.to_string())?;
+        if gamma.changed {
+            gamma.outputs_changed(&qh);
+        }
+    }
+}
+
+impl Gamma {
+    /// Level of `output` as configured, 0 while dimming is off
+    fn level_for(&self, output: &Output) -> f32 {
+        let cfg = &self.config;
+        if !cfg.is_enabled || cfg.designer_mode {
+            return 0.0;
+        }
+        let level = if cfg.multi_monitor {
+            cfg.level_for(&output.id)
+        } else {
+            cfg.opacity
+        };
+        cfg.opacity_curve.to_applied(level)
+    }
+
+    /// Outputs changed: key them anew and apply every level
+    fn outputs_changed(&mut self, qh: &QueueHandle<Self>) {
+        self.changed = false;
+        let mut ids: Vec<MonitorId> = self
+            .outputs
+            .iter()
+            .map(|o| {
+                if o.make.is_empty() && o.model.is_empty() {
+                    MonitorId::from_name_position(&o.name, o.x, o.y)
+                } else {
+                    MonitorId::from_hardware(&[&o.make, &o.model])
+                }
+            })
+            .collect();
+        monitor_id::assign(&mut ids);
+        for (output, id) in self.outputs.iter_mut().zip(ids) {
+            output.id = id;
+        }
+        let infos: Vec<DisplayInfo> = self.outputs.iter().map(|o| o.display_info()).collect();
+        // Settings saved by index or name now follow the monitor id
+        if self.config.adopt_monitor_ids(&infos) {
+            config::save_config(&self.config);
+        }
+        displays::record_layout(&infos);
+        self.apply(qh);
+    }
+
+    /// Give every output its level: a gamma control with scaled ramps while
+    /// dimmed, none (the original ramps) otherwise
+    fn apply(&mut self, qh: &QueueHandle<Self>) {
+        let Some(manager) = self.manager.clone() else {
+            return;
+        };
+        let levels: Vec<f32> = self.outputs.iter().map(|o| self.level_for(o)).collect();
+        for (output, level) in self.outputs.iter_mut().zip(levels) {
+            if level <= 0.0 || output.failed {
+                if let Some(control) = output.gamma.take() {
+                    control.destroy();
+                }
+                output.gamma_size = 0;
+                continue;
+            }
+            let Some(control) = &output.gamma else {
+                // The ramps are set once the gamma size arrives
+                output.gamma = Some(manager.get_gamma_control(&output.output, qh, output.global));
+                continue;
+            };
+            if output.gamma_size == 0 {
+                continue;
+            }
+            if let Err(e) = set_ramps(control, output.gamma_size, level) {
+                eprintln!("[gamma] could not set the ramps of {}: {}", output.name, e);
+            }
+        }
+    }
+}
+
+/// Hand `control` ramps of `size` entries scaled so white maps to
+/// `1.0 - level` brightness
+fn set_ramps(control: &ZwlrGammaControlV1, size: u32, level: f32) -> std::io::Result<()> {
+    let scale = 1.0 - level.clamp(0.0, MAX_OPACITY);
+    let last = (size.max(2) - 1) as f32;
+    let ramp: Vec<u8> = (0..size)
+        .flat_map(|i| (((i as f32 / last) * 65535.0 * scale).round() as u16).to_ne_bytes())
+        .collect();
+    // Red, green and blue one after another in a file the compositor reads
+    let mut file = tempfile::tempfile()?;
+    for _ in 0..3 {
+        file.write_all(&ramp)?;
+    }
+    file.flush()?;
+    file.rewind()?;
+    control.set_gamma(file.as_fd());
+    Ok(())
+}
+
+impl Dispatch<WlRegistry, ()> for Gamma {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => match &interface[..] {
+                "zwlr_gamma_control_manager_v1" => {
+                    state.manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                "wl_output" => {
+                    let output = registry.bind(name, version.min(4), qh, name);
+                    state.outputs.push(Output {
+                        output,
+                        global: name,
+                        name: format!("Output {}", name),
+                        make: String::new(),
+                        model: String::new(),
+                        x: 0,
+                        y: 0,
+                        width: 0,
+                        height: 0,
+                        id: MonitorId::from_name_position("", 0, 0),
+                        gamma: None,
+                        gamma_size: 0,
+                        failed: false,
+                    });
+                }
+                _ => {}
+            },
+            wl_registry::Event::GlobalRemove { name } => {
+                if let Some(index) = state.outputs.iter().position(|o| o.global == name) {
+                    let output = state.outputs.remove(index);
+                    if let Some(control) = output.gamma {
+                        control.destroy();
+                    }
+                    if output.output.version() >= 3 {
+                        output.output.release();
+                    }
+                    state.changed = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlOutput, u32> for Gamma {
+    fn event(
+        state: &mut Self,
+        _: &WlOutput,
+        event: wl_output::Event,
+        global: &u32,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(output) = state.outputs.iter_mut().find(|o| o.global == *global) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Geometry {
+                x, y, make, model, ..
+            } => {
+                output.x = x;
+                output.y = y;
+                output.make = make;
+                output.model = model;
+            }
+            wl_output::Event::Mode {
+                flags: WEnum::Value(flags),
+                width,
+                height,
+                ..
+            } if flags.contains(wl_output::Mode::Current) => {
+                output.width = width;
+                output.height = height;
+            }
+            wl_output::Event::Name { name } => output.name = name,
+            // Everything about the output has been sent
+            wl_output::Event::Done => state.changed = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrGammaControlManagerV1, ()> for Gamma {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrGammaControlManagerV1,
+        _: <ZwlrGammaControlManagerV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrGammaControlV1, u32> for Gamma {
+    fn event(
+        state: &mut Self,
+        control: &ZwlrGammaControlV1,
+        event: zwlr_gamma_control_v1::Event,
+        global: &u32,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(output) = state.outputs.iter_mut().find(|o| o.global == *global) else {
+            return;
+        };
+        match event {
+            zwlr_gamma_control_v1::Event::GammaSize { size } => {
+                output.gamma_size = size;
+                state.changed = true;
+            }
+            zwlr_gamma_control_v1::Event::Failed => {
+                eprintln!(
+                    "[gamma] the compositor refused gamma control of {} \
+                     (another app such as gammastep may hold it)",
+                    output.name
+                );
+                control.destroy();
+                output.gamma = None;
+                output.gamma_size = 0;
+                output.failed = true;
+            }
+            _ => {}
+        }
+    }
+}
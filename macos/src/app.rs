@@ -5,7 +5,7 @@
 //   • Sets up the app delegate
 //   • Installs the status bar item (tray icon)
 //   • Registers global hotkeys
-//   • Dispatches hotkey actions on the main thread via GCD
+//   • Dispatches hotkey actions and external commands on the main thread via GCD
 //   • Runs auto-update check on launch
 //   • Starts the run loop
 
@@ -13,14 +13,35 @@ use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
 use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
 use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy, NSApplicationDelegate};
-use objc2_foundation::{NSNotification, NSObject, NSObjectProtocol, NSString};
+use objc2_foundation::{NSArray, NSNotification, NSObject, NSObjectProtocol, NSString, NSURL};
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
+use savemyeyes_shared::custom_actions::{self, RunReport};
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::{self, Cause, Explanation, Fix, Live};
+use savemyeyes_shared::hotkeys::{chord_prompt, ChordStep};
+use savemyeyes_shared::{
+    builtin_profiles, dimming, displays, journal, monitor_groups, motion, pause, presentation,
+    profiles, shutdown, time_change,
+};
+
+use crate::autostart;
+use crate::brightness;
 use crate::config;
+use crate::contrast_guard;
+use crate::events;
+use crate::focus_boost;
 use crate::hotkeys;
 use crate::hotkeys::HotkeyAction;
+use crate::hud;
+use crate::movie_mode;
+use crate::night_boost;
 use crate::overlay;
+use crate::pen_pause;
+use crate::spaces;
+use crate::system_theme;
 use crate::tray;
 use crate::updater;
 
@@ -31,10 +52,34 @@ pub struct AppState {
 
 static APP_STATE: OnceLock<Arc<Mutex<AppState>>> = OnceLock::new();
 
+/// Movie mode is on because the browser reported a playing video
+static VIDEO_MOVIE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// savemyeyes:// links and command lines that arrived before launch finished
+static PENDING_LINKS: Mutex<Vec<Command>> = Mutex::new(Vec::new());
+
 pub fn state() -> Arc<Mutex<AppState>> {
     APP_STATE.get().expect("AppState not initialized").clone()
 }
 
+/// Config save failure hook: alert on the main thread.
+fn on_save_failure() {
+    run_on_main(|| {
+        if let Some(failure) = config::last_save_failure() {
+            let folder = failure.path.parent().unwrap_or(&failure.path);
+            let text = format!(
+                "{}\n\nTo fix this, check in Finder (Get Info \u{2192} Sharing & Permissions) \
+                 that your account can write to {}. On a managed Mac, ask your administrator; \
+                 the folder may be redirected or locked by a profile. Restart SaveMyEyes \
+                 afterwards.",
+                config::describe_save_failure(&failure),
+                folder.display()
+            );
+            crate::ui::show_alert("Settings Could Not Be Saved", &text);
+        }
+    });
+}
+
 // ── GCD dispatch helpers ────────────────────────────────────────────────────
 
 #[allow(non_camel_case_types)]
@@ -71,9 +116,19 @@ pub fn run_on_main<F: FnOnce() + Send + 'static>(f: F) {
 
 /// Called from the hotkey event tap thread — dispatches to the main thread via GCD.
 pub fn dispatch_hotkey(action: HotkeyAction) {
+    dispatch_action(action, Cause::Hotkey);
+}
+
+/// A tray entry that does what a hotkey does
+pub fn dispatch_tray(action: HotkeyAction) {
+    dispatch_action(action, Cause::Tray);
+}
+
+fn dispatch_action(action: HotkeyAction, cause: Cause) {
     run_on_main(move || {
         let mtm = MainThreadMarker::new().unwrap();
-        eprintln!("SaveMyEyes: dispatch_hotkey({:?})", action);
+        eprintln!("SaveMyEyes: dispatch_action({:?}, {:?})", action, cause);
+        explain::note(cause);
 
         {
             let st = state();
@@ -81,80 +136,27 @@ pub fn dispatch_hotkey(action: HotkeyAction) {
 
             match action {
                 HotkeyAction::Toggle => {
-                    if s.config.is_enabled {
-                        s.config.last_opacity = s.config.opacity;
-                        s.config.is_enabled = false;
-                        config::save_config(&s.config);
-                        overlay::hide();
-                    } else {
-                        s.config.opacity = s.config.last_opacity;
-                        s.config.is_enabled = true;
-                        config::save_config(&s.config);
-                        overlay::show(
-                            mtm,
-                            s.config.opacity,
-                            s.config.multi_monitor,
-                            &s.config.per_display_opacity,
-                        );
+                    toggle(mtm, &mut s.config);
+                    if s.config.level_chord {
+                        hotkeys::start_level_chord();
+                        hud::show(mtm, &chord_prompt(None));
                     }
                 }
-                HotkeyAction::Increase => {
-                    // Find which monitor the cursor is on
-                    let mouse_loc: objc2_foundation::NSPoint = unsafe {
-                        objc2::msg_send![objc2::runtime::AnyClass::get(c"NSEvent").unwrap(), mouseLocation]
-                    };
-                    let active_idx = if s.config.multi_monitor {
-                        overlay::screen_index_at_point(mtm, mouse_loc.x, mouse_loc.y)
-                    } else {
-                        0
-                    };
-                    let names = overlay::screen_names(mtm);
-                    let display_name = names.get(active_idx as usize).cloned().unwrap_or_default();
-                    let cur = s.config.per_display_opacity.get(&display_name).copied().unwrap_or(s.config.opacity);
-                    let new_op = (cur + 0.1).clamp(0.0, 0.9);
-                    s.config.per_display_opacity.insert(display_name, new_op);
-                    if active_idx == 0 {
-                        s.config.opacity = new_op;
-                    }
-                    s.config.is_enabled = true;
-                    config::save_config(&s.config);
-                    if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.per_display_opacity) {
-                        overlay::show(
-                            mtm,
-                            s.config.opacity,
-                            s.config.multi_monitor,
-                            &s.config.per_display_opacity,
-                        );
+                HotkeyAction::Increase | HotkeyAction::Decrease => {
+                    let up = action == HotkeyAction::Increase;
+                    let delta = hotkeys::ramp_step(&s.config.adjust_ramp, up);
+                    if delta != 0.0 {
+                        let message = change_level(mtm, &mut s.config, |cur| cur + delta);
+                        hud::show(mtm, &message);
                     }
                 }
-                HotkeyAction::Decrease => {
-                    // Find which monitor the cursor is on
-                    let mouse_loc: objc2_foundation::NSPoint = unsafe {
-                        objc2::msg_send![objc2::runtime::AnyClass::get(c"NSEvent").unwrap(), mouseLocation]
-                    };
-                    let active_idx = if s.config.multi_monitor {
-                        overlay::screen_index_at_point(mtm, mouse_loc.x, mouse_loc.y)
-                    } else {
-                        0
-                    };
-                    let names = overlay::screen_names(mtm);
-                    let display_name = names.get(active_idx as usize).cloned().unwrap_or_default();
-                    let cur = s.config.per_display_opacity.get(&display_name).copied().unwrap_or(s.config.opacity);
-                    let new_op = (cur - 0.1).clamp(0.0, 0.9);
-                    s.config.per_display_opacity.insert(display_name, new_op);
-                    if active_idx == 0 {
-                        s.config.opacity = new_op;
-                    }
-                    s.config.is_enabled = true;
-                    config::save_config(&s.config);
-                    if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.per_display_opacity) {
-                        overlay::show(
-                            mtm,
-                            s.config.opacity,
-                            s.config.multi_monitor,
-                            &s.config.per_display_opacity,
-                        );
-                    }
+                HotkeyAction::MovieMode => {
+                    let message = movie_mode::toggle(mtm, &s.config);
+                    hud::show(mtm, &message);
+                }
+                HotkeyAction::Boost => {
+                    let message = focus_boost::boost(&s.config);
+                    hud::show(mtm, &message);
                 }
             }
         } // <-- APP_STATE lock is dropped here, BEFORE update_menu
@@ -167,27 +169,528 @@ pub fn dispatch_hotkey(action: HotkeyAction) {
     });
 }
 
+/// Run a command on the main thread: from another process (events.rs,
+/// Cause::Command) or from the tray.
+pub fn dispatch_command(command: Command, cause: Cause) {
+    run_on_main(move || {
+        let mtm = MainThreadMarker::new().unwrap();
+        eprintln!("SaveMyEyes: dispatch_command({:?})", command);
+        explain::note(cause);
+        if let Some(message) = run_command(mtm, command) {
+            hud::show(mtm, &message);
+        }
+        tray::update_menu(mtm);
+        crate::ui::update_ui();
+    });
+}
+
+/// Carry out `command`. Main thread only. Returns the HUD message.
+fn run_command(mtm: MainThreadMarker, command: Command) -> Option<String> {
+    let message = {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        match command {
+            Command::Toggle => {
+                toggle(mtm, &mut s.config);
+                None
+            }
+            Command::SetEnabled { enabled } => {
+                if s.config.is_enabled != enabled {
+                    toggle(mtm, &mut s.config);
+                }
+                None
+            }
+            Command::SetLevel { level } => Some(change_level(mtm, &mut s.config, |_| level)),
+            Command::SetMonitorLevel { .. } if !s.config.multi_monitor => {
+                Some("Per-monitor levels are off".to_string())
+            }
+            Command::SetMonitorLevel { monitor, .. }
+                if monitor as usize >= overlay::screen_ids(mtm).len() =>
+            {
+                Some(format!("No monitor {}", monitor + 1))
+            }
+            Command::SetMonitorLevel { monitor, level } => {
+                Some(change_display_level(mtm, &mut s.config, monitor, |_| level))
+            }
+            Command::SetMonitorCapture { .. } => {
+                Some("Captures can only be set per monitor on Windows".to_string())
+            }
+            Command::VideoPlaying { playing } => video_playing(mtm, &s.config, playing),
+            Command::Pause { .. } if s.config.designer_mode => {
+                Some("Designer mode is on".to_string())
+            }
+            // Below, with the state unlocked
+            Command::Pause { .. } | Command::OpenSettings | Command::ApplyProfile { .. } => None,
+        }
+    };
+    if message.is_none() {
+        if let Command::Pause { minutes } = command {
+            start_pause(minutes);
+        } else if command == Command::OpenSettings {
+            crate::ui::show_settings(mtm);
+        } else if let Command::ApplyProfile { name } = &command {
+            apply_profile(name);
+        }
+    }
+    message
+}
+
+/// Run custom action `index`, step by step, and show how it went.
+pub fn dispatch_custom_action(index: usize) {
+    run_on_main(move || {
+        let mtm = MainThreadMarker::new().unwrap();
+        let action = state()
+            .lock()
+            .unwrap()
+            .config
+            .custom_actions
+            .get(index)
+            .cloned();
+        let Some(action) = action else {
+            return;
+        };
+        eprintln!("SaveMyEyes: running custom action {:?}", action.name);
+        explain::note(Cause::Hotkey);
+        let mut report = RunReport::new(&action);
+        for (i, step) in action.steps.iter().enumerate() {
+            let error = {
+                let monitors = overlay::screen_ids(mtm).len() as u32;
+                let st = state();
+                let s = st.lock().unwrap();
+                custom_actions::step_error(&s.config, step, monitors)
+            };
+            if error.is_none() {
+                run_command(mtm, step.clone());
+            }
+            report.record(i, step, error);
+        }
+        hud::show(mtm, &report.message());
+        tray::update_menu(mtm);
+        crate::ui::update_ui();
+    });
+}
+
+/// Dimming on/off, back to the level it had before.
+fn toggle(mtm: MainThreadMarker, config: &mut config::AppConfig) {
+    movie_mode::forget();
+    if config.is_enabled {
+        config.last_opacity = config.opacity;
+        config.is_enabled = false;
+        config::save_config(config);
+        overlay::fade_out(&config.fade);
+    } else {
+        config.opacity = config.last_opacity;
+        config.is_enabled = true;
+        config::save_config(config);
+        overlay::fade_in(
+            mtm,
+            config.opacity,
+            config.multi_monitor,
+            &config.monitor_opacity,
+            &config.fade,
+        );
+    }
+}
+
+/// Movie mode follows browser video when movie_mode.on_browser_video is set.
+/// Only a movie mode started here is ended here. Returns the HUD message.
+fn video_playing(mtm: MainThreadMarker, config: &config::AppConfig, playing: bool) -> Option<String> {
+    if !config.movie_mode.on_browser_video {
+        return None;
+    }
+    if playing && !movie_mode::is_active() {
+        let message = movie_mode::toggle(mtm, config);
+        VIDEO_MOVIE_MODE.store(movie_mode::is_active(), Ordering::SeqCst);
+        Some(message)
+    } else if !playing && VIDEO_MOVIE_MODE.swap(false, Ordering::SeqCst) && movie_mode::is_active() {
+        Some(movie_mode::toggle(mtm, config))
+    } else {
+        None
+    }
+}
+
+/// Called from the hotkey layer for each level chord digit.
+pub fn dispatch_chord_step(step: ChordStep) {
+    run_on_main(move || {
+        let mtm = MainThreadMarker::new().unwrap();
+        match step {
+            ChordStep::Partial(first) => hud::show(mtm, &chord_prompt(Some(first))),
+            ChordStep::Complete(percent) => {
+                let message = {
+                    let st = state();
+                    let mut s = st.lock().unwrap();
+                    change_level(mtm, &mut s.config, |_| percent as f32 / 100.0)
+                };
+                hud::show(mtm, &message);
+                tray::update_menu(mtm);
+                crate::ui::update_ui();
+            }
+            ChordStep::Inactive => {}
+        }
+    });
+}
+
+/// Set the level of the display under the cursor (the primary display when
+/// multi-monitor is off) from its current level, turning dimming on.
+/// Returns the HUD message, e.g. "DELL U2720Q: 60%".
+fn change_level(
+    mtm: MainThreadMarker,
+    config: &mut config::AppConfig,
+    level: impl Fn(f32) -> f32,
+) -> String {
+    // Find which monitor the cursor is on
+    let mouse_loc: objc2_foundation::NSPoint = unsafe {
+        objc2::msg_send![objc2::runtime::AnyClass::get(c"NSEvent").unwrap(), mouseLocation]
+    };
+    let active_idx = if config.multi_monitor {
+        overlay::screen_index_at_point(mtm, mouse_loc.x, mouse_loc.y)
+    } else {
+        0
+    };
+    change_display_level(mtm, config, active_idx, level)
+}
+
+/// Set the level of display `active_idx` from its current level, turning
+/// dimming on. Returns the HUD message.
+fn change_display_level(
+    mtm: MainThreadMarker,
+    config: &mut config::AppConfig,
+    active_idx: u32,
+    level: impl Fn(f32) -> f32,
+) -> String {
+    let names = overlay::screen_names(mtm);
+    let display_name = names.get(active_idx as usize).cloned().unwrap_or_default();
+    let ids = overlay::screen_ids(mtm);
+    let id = ids.get(active_idx as usize).cloned().unwrap_or_default();
+    let new_op = dimming::quantize(level(config.level_for(&id)).clamp(0.0, 0.9));
+    // Grouped displays follow (monitor_groups.rs)
+    let linked = config.set_level_for(&id, new_op);
+    if active_idx == 0 || ids.first().is_some_and(|primary| linked.contains(primary)) {
+        config.opacity = new_op;
+    }
+    config.is_enabled = true;
+    config::save_config(config);
+    // Leaving movie mode needs every display re-applied, not just updated
+    let was_movie = movie_mode::is_active();
+    movie_mode::forget();
+    if was_movie
+        || !overlay::update_opacity(mtm, config.opacity, config.multi_monitor, &config.monitor_opacity)
+    {
+        overlay::show(
+            mtm,
+            config.opacity,
+            config.multi_monitor,
+            &config.monitor_opacity,
+        );
+    }
+
+    let group = monitor_groups::group_of(&config.monitor_groups, &id);
+    let label = if let Some(group) = group.filter(|_| config.multi_monitor) {
+        group.name.clone()
+    } else if config.multi_monitor && !display_name.is_empty() {
+        display_name
+    } else {
+        "Dimming".to_string()
+    };
+    format!("{}: {}", label, dimming::percent_label(new_op))
+}
+
+/// Enter or leave designer mode: restore all displays and block anything from
+/// re-applying dimming until it is turned off, then restore the configured state.
+pub fn set_designer_mode(on: bool) {
+    let mtm = MainThreadMarker::new().unwrap();
+    {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        movie_mode::forget();
+        s.config.designer_mode = on;
+        config::save_config(&s.config);
+        // Designer mode takes over from a timed pause or presentation
+        pause::end();
+        presentation::end();
+        overlay::set_suppressed(on);
+        if !on {
+            show_configured(mtm, &s.config);
+        }
+    }
+    eprintln!("SaveMyEyes: Designer mode {}", if on { "on" } else { "off" });
+    tray::update_menu(mtm);
+    crate::ui::update_ui();
+}
+
+/// Apply the profile mapped to the OS theme (if the automation is on), save,
+/// and push it to the displays. Returns the profile name.
+pub fn apply_theme_profile(dark: bool) -> Option<String> {
+    let mtm = MainThreadMarker::new().unwrap();
+    let name = {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        // Designer mode: nothing may change the dimming meanwhile
+        if s.config.designer_mode {
+            return None;
+        }
+        let name = profiles::apply_for_theme(&mut s.config, dark)?;
+        config::save_config(&s.config);
+        show_configured(mtm, &s.config);
+        name
+    };
+    eprintln!("SaveMyEyes: Applied profile {} for {} mode", name, if dark { "dark" } else { "light" });
+    tray::update_menu(mtm);
+    crate::ui::update_ui();
+    Some(name)
+}
+
+/// Apply the profile at `index` in profiles::listed (tray menu) and show it
+/// in the HUD.
+pub fn apply_profile_at(index: usize) {
+    let name = {
+        let st = state();
+        let s = st.lock().unwrap();
+        profiles::listed(&s.config)
+            .get(index)
+            .map(|p| p.name.clone())
+    };
+    if let Some(name) = name {
+        apply_profile(&name);
+    }
+}
+
+/// Apply the profile called `name` (tray menu, external command) and show
+/// it in the HUD.
+pub fn apply_profile(name: &str) {
+    let mtm = MainThreadMarker::new().unwrap();
+    let message = {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        if s.config.designer_mode {
+            "Designer mode is on".to_string()
+        } else if let Some(name) = profiles::find(&s.config, name).map(|p| p.name.clone()) {
+            movie_mode::forget();
+            profiles::apply(&mut s.config, &name);
+            config::save_config(&s.config);
+            show_configured(mtm, &s.config);
+            format!("Profile: {}", name)
+        } else {
+            format!("No profile named \"{}\"", name)
+        }
+    };
+    hud::show(mtm, &message);
+    tray::update_menu(mtm);
+    crate::ui::update_ui();
+}
+
+/// What the "Why is my screen dark?" explainer says right now.
+pub fn explanation(mtm: MainThreadMarker) -> Explanation {
+    let live = Live {
+        dimming: overlay::is_visible(),
+        monitors: overlay::monitor_levels(mtm),
+        night_boost: night_boost::current(),
+        movie_mode: movie_mode::is_active(),
+    };
+    let config = state().lock().unwrap().config.clone();
+    explain::explain(&config, &explain::last(), &live)
+}
+
+/// Apply a fix picked in the explainer and show it in the HUD.
+pub fn apply_fix(fix: Fix) {
+    let mtm = MainThreadMarker::new().unwrap();
+    explain::note(Cause::Tray);
+    {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        match fix {
+            Fix::TurnOff => {
+                if s.config.is_enabled {
+                    toggle(mtm, &mut s.config);
+                }
+            }
+            Fix::EndMovieMode => {
+                if movie_mode::is_active() {
+                    movie_mode::toggle(mtm, &s.config);
+                }
+            }
+            _ => {
+                if fix.apply(&mut s.config) {
+                    config::save_config(&s.config);
+                    night_boost::apply(&s.config.night_boost);
+                }
+            }
+        }
+    }
+    hud::show(mtm, fix.done_message());
+    tray::update_menu(mtm);
+    crate::ui::update_ui();
+}
+
+/// Copy the built-in profile at `index` into config (tray menu).
+pub fn duplicate_builtin(index: usize) {
+    let mtm = MainThreadMarker::new().unwrap();
+    let name = {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        let Some(name) = builtin_profiles::duplicate(&mut s.config, index) else {
+            return;
+        };
+        config::save_config(&s.config);
+        name
+    };
+    hud::show(mtm, &format!("Saved \"{}\"; edit it in config.json", name));
+    tray::update_menu(mtm);
+    crate::ui::update_ui();
+}
+
+/// Switch the theme automation to the example at `index` (tray menu) and
+/// apply it for the current appearance.
+pub fn use_theme_example(index: usize) {
+    let mtm = MainThreadMarker::new().unwrap();
+    let example = {
+        let st = state();
+        let mut s = st.lock().unwrap();
+        let Some(example) = builtin_profiles::use_example(&mut s.config, index) else {
+            return;
+        };
+        config::save_config(&s.config);
+        example
+    };
+    if apply_theme_profile(system_theme::is_dark(mtm)).is_none() {
+        tray::update_menu(mtm);
+        crate::ui::update_ui();
+    }
+    hud::show(mtm, &format!("Follow System Theme: {}", example));
+}
+
+/// Put the configured levels on screen (or take dimming off).
+fn show_configured(mtm: MainThreadMarker, config: &config::AppConfig) {
+    if config.is_enabled {
+        overlay::show(
+            mtm,
+            config.opacity,
+            config.multi_monitor,
+            &config.monitor_opacity,
+        );
+    } else {
+        overlay::hide();
+    }
+}
+
+/// Lift dimming for `minutes` (tray menu). The displays are suppressed as in
+/// designer mode, with config untouched, until the timer resumes them.
+pub fn start_pause(minutes: u64) {
+    let mtm = MainThreadMarker::new().unwrap();
+    let duration = pause::start(minutes);
+    overlay::set_suppressed(true);
+    shutdown::spawn("pause-timer", move || {
+        if shutdown::sleep(duration) {
+            run_on_main(|| {
+                if pause::take_expired() {
+                    resume_from_pause();
+                }
+            });
+        }
+    });
+    hud::show(mtm, &pause::started_message(minutes));
+    tray::update_menu(mtm);
+}
+
+/// End a timed pause early (tray menu).
+pub fn end_pause() {
+    if pause::end() {
+        resume_from_pause();
+    }
+}
+
+/// Bring back the configured dimming after a timed pause or presentation
+/// mode (unless designer mode or presentation mode still holds it off).
+fn resume_from_pause() {
+    let mtm = MainThreadMarker::new().unwrap();
+    {
+        let st = state();
+        let s = st.lock().unwrap();
+        if s.config.designer_mode || presentation::is_active() {
+            return;
+        }
+        overlay::set_suppressed(false);
+        show_configured(mtm, &s.config);
+    }
+    hud::show(mtm, "Dimming resumed");
+    tray::update_menu(mtm);
+}
+
+/// Lift dimming and keep quiet for `minutes` (None = until ended, tray
+/// menu). Replaces a timed pause; the displays stay suppressed until
+/// the timer or the tray ends it.
+pub fn start_presentation(minutes: Option<u64>) {
+    let mtm = MainThreadMarker::new().unwrap();
+    pause::end();
+    if let Some(duration) = presentation::start(minutes) {
+        shutdown::spawn("presentation-timer", move || {
+            if shutdown::sleep(duration) {
+                run_on_main(|| {
+                    if presentation::take_expired() {
+                        resume_from_presentation();
+                    }
+                });
+            }
+        });
+    }
+    overlay::set_suppressed(true);
+    eprintln!("SaveMyEyes: presentation mode on for {:?} min", minutes);
+    tray::update_menu(mtm);
+}
+
+/// End presentation mode early (tray menu).
+pub fn end_presentation() {
+    if presentation::end() {
+        resume_from_presentation();
+    }
+}
+
+/// Bring back dimming and notifications after presentation mode.
+fn resume_from_presentation() {
+    eprintln!("SaveMyEyes: presentation mode over");
+    resume_from_pause();
+}
+
 /// Run the auto-update check after a delay in a background thread.
 pub fn schedule_update_check() {
-    std::thread::spawn(|| {
-        std::thread::sleep(std::time::Duration::from_secs(5));
+    if !updater::ENABLED {
+        return;
+    }
+    shutdown::spawn("auto-update-check", || {
+        if !shutdown::sleep(std::time::Duration::from_secs(5)) {
+            return;
+        }
         let st = state();
         let auto = st.lock().unwrap().config.auto_update;
         if !auto {
             return;
         }
         let result = updater::check_for_update(updater::APP_VERSION);
-        match result {
-            updater::UpdateResult::UpdateAvailable {
-                version,
-                download_url,
-                ..
-            } => {
-                run_on_main(move || {
-                    crate::ui::prompt_update(&version, &download_url);
-                });
+        if shutdown::is_stopping() {
+            return;
+        }
+        if let updater::UpdateResult::UpdateAvailable {
+            version,
+            download_url,
+            ..
+        } = result
+        {
+            // Skipped or snoozed versions aren't offered again here
+            let now = savemyeyes_shared::updater::now_secs();
+            if !st.lock().unwrap().config.update_snooze.should_prompt(&version, now) {
+                eprintln!("SaveMyEyes: update v{} available, prompt snoozed", version);
+                return;
             }
-            _ => {}
+            if presentation::is_active() {
+                eprintln!(
+                    "SaveMyEyes: update v{} available, not prompting while presenting",
+                    version
+                );
+                return;
+            }
+            run_on_main(move || {
+                crate::ui::prompt_update(&version, &download_url, None);
+            });
         }
     });
 }
@@ -204,25 +707,86 @@ define_class!(
         #[unsafe(method(applicationDidFinishLaunching:))]
         fn did_finish_launching(&self, _notification: &NSNotification) {
             let mtm = MainThreadMarker::from(self);
-
-            let cfg = config::load_config();
+            explain::note(Cause::Startup);
+
+            let mut cfg = config::load_config();
+            // Pick up screen state that was applied but never saved (crash mid-drag)
+            journal::reconcile(&mut cfg);
+            // Settings saved by display name now follow the monitor id
+            if cfg.adopt_monitor_ids(&overlay::display_infos(mtm)) {
+                config::save_config(&cfg);
+            }
+            updater::set_endpoint(cfg.update_endpoint.as_deref());
+            // Managed installs: apply the seeded login item choice once
+            if config::is_first_deployed_run() {
+                if cfg.launch_on_login {
+                    autostart::enable();
+                } else {
+                    autostart::disable();
+                }
+            }
+            // The OS theme may have flipped while we weren't running
+            if !cfg.designer_mode
+                && profiles::apply_if_theme_changed(&mut cfg, system_theme::is_dark(mtm)).is_some()
+            {
+                config::save_config(&cfg);
+            }
+            // Start on the levels of the Space we launched on
+            if spaces::start(&mut cfg) {
+                config::save_config(&cfg);
+            }
             let state = Arc::new(Mutex::new(AppState { config: cfg.clone() }));
             APP_STATE.set(state.clone()).ok();
 
+            // Remember the monitor layout for troubleshooting reports
+            displays::record_layout(&overlay::display_infos(mtm));
+
             // Setup system tray (status bar item)
             tray::setup(mtm);
 
+            // Tell the user (once) if settings can't be written
+            config::set_save_failure_hook(on_save_failure);
+
+            // Report values load_config had to correct
+            let fixes = config::load_fixes();
+            if !fixes.is_empty() {
+                run_on_main(move || {
+                    crate::ui::show_alert("Settings Corrected", &config::describe_load_fixes(&fixes));
+                });
+            }
+
             // Ensure accessibility permission is truly granted.
             // This probes whether the TCC entry is functional (not stale)
             // and resets + re-prompts if the binary hash has changed.
             hotkeys::request_accessibility_if_needed();
 
-            // Register global hotkeys
-            hotkeys::register_all();
+            // Register global hotkeys (none at all in hotkey-less mode)
+            hotkeys::set_keys(cfg.key_bindings());
+            hotkeys::set_custom_actions(&cfg.custom_actions);
+            hotkeys::set_global_input_disabled(cfg.disable_global_shortcuts, &cfg.mouse_hotkeys);
+
+            // Follow OS light/dark changes (theme automation)
+            system_theme::watch(mtm);
+
+            // Commands from the browser extension bridge
+            events::listen();
+
+            overlay::set_curve(cfg.opacity_curve);
+            motion::set(cfg.motion);
+            pen_pause::apply(&cfg.pen_pause);
+            night_boost::apply(&cfg.night_boost);
+            brightness::apply(&cfg.brightness_rule);
+            contrast_guard::apply(&cfg.contrast_guard);
+            crate::scheduler::start(&cfg.schedule);
+
+            // Designer mode survives restarts until explicitly turned off
+            if cfg.designer_mode {
+                overlay::set_suppressed(true);
+            }
 
             // Show overlay if enabled
             if cfg.is_enabled {
-                overlay::show(mtm, cfg.opacity, cfg.multi_monitor, &cfg.per_display_opacity);
+                overlay::show(mtm, cfg.opacity, cfg.multi_monitor, &cfg.monitor_opacity);
             }
 
             // Register for screen configuration changes (monitor connect/disconnect)
@@ -236,6 +800,20 @@ define_class!(
                     Some(&name),
                     None,
                 );
+
+                // Clock set, synced or moved to another timezone
+                for name in [
+                    "NSSystemClockDidChangeNotification",
+                    "NSSystemTimeZoneDidChangeNotification",
+                ] {
+                    let name = NSString::from_str(name);
+                    center.addObserver_selector_name_object(
+                        self,
+                        sel!(systemClockChanged:),
+                        Some(&name),
+                        None,
+                    );
+                }
             }
 
             // Register for active-Space changes so we can re-apply gamma
@@ -269,6 +847,44 @@ define_class!(
 
             // Schedule auto-update check
             schedule_update_check();
+
+            // The link or command line SaveMyEyes was launched with
+            for command in std::mem::take(&mut *PENDING_LINKS.lock().unwrap()) {
+                dispatch_command(command, Cause::Command);
+            }
+        }
+
+        #[unsafe(method(application:openURLs:))]
+        fn open_urls(&self, _app: &NSApplication, urls: &NSArray<NSURL>) {
+            // savemyeyes:// links (shared events.rs); Launch Services hands
+            // them to the running instance, or launches one first
+            for url in urls.iter() {
+                let Some(text) = url.absoluteString() else {
+                    continue;
+                };
+                let Some(command) = Command::from_url(&text.to_string()) else {
+                    continue;
+                };
+                if APP_STATE.get().is_some() {
+                    dispatch_command(command, Cause::Command);
+                } else {
+                    PENDING_LINKS.lock().unwrap().push(command);
+                }
+            }
+        }
+
+        #[unsafe(method(applicationWillTerminate:))]
+        fn will_terminate(&self, _notification: &NSNotification) {
+            // Quitting (shared shutdown.rs): stop the background threads
+            // first, so none of them puts gamma back once it is restored
+            shutdown::begin();
+            shutdown::join(shutdown::TIMEOUT);
+            // Then the screen, the status item and state, in that order
+            overlay::hide();
+            tray::remove();
+            spaces::remember(&mut state().lock().unwrap().config);
+            // Clean exit: config is up to date, the crash journal isn't needed
+            journal::clear();
         }
 
         #[unsafe(method(applicationShouldHandleReopen:hasVisibleWindows:))]
@@ -282,6 +898,21 @@ define_class!(
 
         #[unsafe(method(activeSpaceChanged:))]
         fn active_space_changed(&self, _notification: &NSNotification) {
+            let mtm = MainThreadMarker::from(self);
+            let changed = {
+                let st = state();
+                let mut s = st.lock().unwrap();
+                let changed = spaces::switched(&mut s.config);
+                if changed && s.config.is_enabled && !movie_mode::is_active() {
+                    show_configured(mtm, &s.config);
+                }
+                changed
+            };
+            if changed {
+                tray::update_menu(mtm);
+                crate::ui::update_ui();
+                return;
+            }
             // Re-apply gamma when the user switches Spaces
             // (macOS may reset gamma tables during Space transitions).
             if overlay::is_visible() {
@@ -299,16 +930,30 @@ define_class!(
             }
         }
 
+        #[unsafe(method(systemClockChanged:))]
+        fn system_clock_changed(&self, _notification: &NSNotification) {
+            time_change::notify();
+        }
+
         #[unsafe(method(screenParametersChanged:))]
         fn screen_parameters_changed(&self, _notification: &NSNotification) {
             let mtm = MainThreadMarker::from(self);
             eprintln!("SaveMyEyes: Screen configuration changed (monitor connect/disconnect)");
+            overlay::forget_calibrations();
+            let layout = overlay::display_infos(mtm);
+            displays::record_layout(&layout);
 
             // Refresh overlays if visible
             let st = state();
-            let cfg = st.lock().unwrap().config.clone();
+            let cfg = {
+                let mut st = st.lock().unwrap();
+                if st.config.adopt_monitor_ids(&layout) {
+                    config::save_config(&st.config);
+                }
+                st.config.clone()
+            };
             if cfg.is_enabled {
-                overlay::show(mtm, cfg.opacity, cfg.multi_monitor, &cfg.per_display_opacity);
+                overlay::show(mtm, cfg.opacity, cfg.multi_monitor, &cfg.monitor_opacity);
             }
 
             // Rebuild the settings UI if it is open
@@ -324,6 +969,12 @@ impl AppDelegate {
     }
 }
 
+/// Run `command` once the app has launched (a command line that found no
+/// running instance)
+pub fn run_when_launched(command: Command) {
+    PENDING_LINKS.lock().unwrap().push(command);
+}
+
 pub fn run() {
     let mtm = MainThreadMarker::new()
         .expect("SaveMyEyes must be run on the main thread");
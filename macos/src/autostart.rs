@@ -43,17 +43,26 @@ pub fn enable() -> bool {
     );
 
     let path = plist_path();
-    if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
+    let written = match path.parent() {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
     }
-    fs::write(&path, plist_content).is_ok()
+    .and_then(|()| fs::write(&path, plist_content));
+    if let Err(e) = &written {
+        eprintln!("SaveMyEyes: could not write {}: {}", path.display(), e);
+    }
+    written.is_ok()
 }
 
 /// Disable autostart by removing the plist.
 pub fn disable() -> bool {
     let path = plist_path();
     if path.exists() {
-        fs::remove_file(&path).is_ok()
+        let removed = fs::remove_file(&path);
+        if let Err(e) = &removed {
+            eprintln!("SaveMyEyes: could not remove {}: {}", path.display(), e);
+        }
+        removed.is_ok()
     } else {
         true
     }
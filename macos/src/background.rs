@@ -0,0 +1,36 @@
+// Scheduling hygiene for background work.
+//
+// The threads started through shutdown::spawn (brightness, night boost and
+// pen polls, pause timers, update checks) run at the utility QoS class
+// instead of inheriting the main thread's user-interactive one. macOS then
+// keeps them off the performance cores when it can and coalesces their
+// timer wake-ups, so SaveMyEyes stays out of Activity Monitor's energy
+// column. The hotkey and event tap threads keep their default class.
+
+use savemyeyes_shared::shutdown;
+
+/// QOS_CLASS_UTILITY from <sys/qos.h>
+const QOS_CLASS_UTILITY: u32 = 0x11;
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn pthread_set_qos_class_self_np(qos_class: u32, relative_priority: i32) -> i32;
+}
+
+/// Have every background thread lower itself.
+pub fn init() {
+    shutdown::set_thread_start(lower_current_thread);
+}
+
+/// Utility QoS for the calling thread
+#[cfg(target_os = "macos")]
+fn lower_current_thread() {
+    let result = unsafe { pthread_set_qos_class_self_np(QOS_CLASS_UTILITY, 0) };
+    if result != 0 {
+        eprintln!("SaveMyEyes: could not lower thread QoS (error {})", result);
+    }
+}
+
+/// Headless builds leave their threads as they are
+#[cfg(not(target_os = "macos"))]
+fn lower_current_thread() {}
@@ -0,0 +1,117 @@
+// Only dim bright panels (see shared brightness_rule.rs).
+//
+// The built-in display's backlight is read with DisplayServicesGetBrightness
+// from the private DisplayServices framework, looked up at runtime so a
+// missing framework or symbol only means no display reports a brightness.
+// External displays don't report one and stay dimmed. A poll thread
+// re-evaluates the rule every POLL_INTERVAL on the main thread (display names
+// come from NSScreen) and hands the displays it leaves undimmed to the gamma
+// backend. The thread only runs while the rule is on.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use objc2::MainThreadMarker;
+
+use savemyeyes_shared::brightness_rule::BrightnessRule;
+use savemyeyes_shared::shutdown;
+
+use crate::overlay;
+
+/// How often the poll thread re-evaluates the rule
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const DISPLAY_SERVICES: &CStr =
+    c"/System/Library/PrivateFrameworks/DisplayServices.framework/DisplayServices";
+const RTLD_LAZY: i32 = 0x1;
+
+extern "C" {
+    fn dlopen(path: *const c_char, mode: i32) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+/// DisplayServicesGetBrightness(display, &brightness) -> error
+type GetBrightness = unsafe extern "C" fn(u32, *mut f32) -> i32;
+
+static RULE: Mutex<Option<BrightnessRule>> = Mutex::new(None);
+/// Displays the rule left undimmed at the last check
+static UNDIMMED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+fn get_brightness() -> Option<GetBrightness> {
+    static FUNCTION: OnceLock<Option<GetBrightness>> = OnceLock::new();
+    *FUNCTION.get_or_init(|| unsafe {
+        let handle = dlopen(DISPLAY_SERVICES.as_ptr(), RTLD_LAZY);
+        if handle.is_null() {
+            eprintln!("SaveMyEyes: DisplayServices unavailable, panel brightness unknown.");
+            return None;
+        }
+        let symbol = dlsym(handle, c"DisplayServicesGetBrightness".as_ptr());
+        (!symbol.is_null()).then(|| std::mem::transmute::<*mut c_void, GetBrightness>(symbol))
+    })
+}
+
+/// Backlight level (0.0–1.0) of `display`, None if it has none
+fn panel_brightness(display: u32) -> Option<f32> {
+    let function = get_brightness()?;
+    let mut brightness = 0.0f32;
+    let err = unsafe { function(display, &mut brightness) };
+    (err == 0).then(|| brightness.clamp(0.0, 1.0))
+}
+
+/// Apply the rule from config. Main thread only.
+pub fn apply(rule: &BrightnessRule) {
+    *RULE.lock().unwrap() = Some(rule.clone());
+    if rule.enabled && !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("brightness-poll", poll);
+    }
+    check();
+}
+
+fn poll() {
+    loop {
+        if !shutdown::sleep(POLL_INTERVAL) {
+            // Quitting: every display is about to be undimmed anyway
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+        if !RULE.lock().unwrap().as_ref().is_some_and(|r| r.enabled) {
+            break;
+        }
+        crate::app::run_on_main(check);
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    crate::app::run_on_main(check);
+}
+
+/// Work out which displays the rule leaves undimmed. Main thread only.
+fn check() {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let rule = RULE.lock().unwrap().clone().filter(|r| r.enabled);
+    let previous = UNDIMMED.lock().unwrap().clone();
+    let mut undimmed = Vec::new();
+    if let Some(rule) = rule {
+        for (display, id) in overlay::identified_displays(mtm) {
+            let threshold = rule.threshold_for(&id);
+            if !rule.allows(
+                threshold,
+                panel_brightness(display),
+                !previous.contains(&display),
+            ) {
+                undimmed.push(display);
+            }
+        }
+    }
+    if undimmed != previous {
+        eprintln!(
+            "SaveMyEyes: displays left undimmed by brightness: {:?}",
+            undimmed
+        );
+        *UNDIMMED.lock().unwrap() = undimmed.clone();
+        overlay::set_brightness_gate(&undimmed);
+    }
+}
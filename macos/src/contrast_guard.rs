@@ -0,0 +1,172 @@
+// Adaptive contrast guard (see shared contrast_guard.rs).
+//
+// While the rule is on, a poll thread samples the display holding the
+// frontmost app's key window: CGDisplayCreateImage drawn into a tiny bitmap
+// context, off the main thread. Gamma is applied after the framebuffer, so
+// the sample shows the content without our dimming. Window contents are
+// only in the image with Screen Recording permission; turning the guard on
+// asks for it once, and nothing is sampled until it is granted. The focused
+// display is read on the main thread (NSScreen) and the cap is applied
+// there, like the other level caps.
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use objc2::MainThreadMarker;
+use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+use savemyeyes_shared::contrast_guard::{
+    average_luminance, ContrastGuard, SAMPLE_HEIGHT, SAMPLE_WIDTH,
+};
+use savemyeyes_shared::shutdown;
+
+use crate::overlay;
+
+/// kCGImageAlphaNoneSkipLast: RGBX
+const IMAGE_ALPHA_NONE_SKIP_LAST: u32 = 5;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+    fn CGDisplayCreateImage(display: u32) -> *mut c_void;
+    fn CGImageRelease(image: *mut c_void);
+    fn CGColorSpaceCreateDeviceRGB() -> *mut c_void;
+    fn CGColorSpaceRelease(space: *mut c_void);
+    fn CGBitmapContextCreate(
+        data: *mut c_void,
+        width: usize,
+        height: usize,
+        bits_per_component: usize,
+        bytes_per_row: usize,
+        space: *mut c_void,
+        bitmap_info: u32,
+    ) -> *mut c_void;
+    fn CGContextDrawImage(context: *mut c_void, rect: NSRect, image: *mut c_void);
+    fn CGContextRelease(context: *mut c_void);
+}
+
+static RULE: Mutex<Option<ContrastGuard>> = Mutex::new(None);
+/// Display and cap from the last sample
+static CAP: Mutex<Option<(u32, f32)>> = Mutex::new(None);
+/// Display holding the key window as of the last look (0 = unknown)
+static FOCUSED: AtomicU32 = AtomicU32::new(0);
+static POLLING: AtomicBool = AtomicBool::new(false);
+/// Screen Recording permission was asked for this run
+static ASKED: AtomicBool = AtomicBool::new(false);
+
+/// Apply the rule from config. Main thread only.
+pub fn apply(rule: &ContrastGuard) {
+    *RULE.lock().unwrap() = Some(rule.clone());
+    if !rule.enabled {
+        set_cap(None);
+        return;
+    }
+    if !unsafe { CGPreflightScreenCaptureAccess() } && !ASKED.swap(true, Ordering::SeqCst) {
+        eprintln!("SaveMyEyes: Contrast guard needs Screen Recording permission.");
+        unsafe { CGRequestScreenCaptureAccess() };
+    }
+    remember_focus();
+    if !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("contrast-guard", poll);
+    }
+}
+
+fn poll() {
+    let mut wait = None;
+    loop {
+        let rule = RULE.lock().unwrap().clone();
+        let Some(rule) = rule.filter(|r| r.enabled) else {
+            break;
+        };
+        let started = Instant::now();
+        check(&rule);
+        let wait_now = rule.next_wait(wait.unwrap_or(rule.interval()), started.elapsed());
+        if wait.is_some_and(|w| w < wait_now) {
+            eprintln!(
+                "SaveMyEyes: Contrast guard sampling slow, next in {:?}",
+                wait_now
+            );
+        }
+        wait = Some(wait_now);
+        if !shutdown::sleep(wait_now) {
+            // Quitting: every display is about to be undimmed anyway
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+        crate::app::run_on_main(remember_focus);
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    crate::app::run_on_main(|| set_cap(None));
+}
+
+/// Note which display has the key window. Main thread only.
+fn remember_focus() {
+    if let Some(mtm) = MainThreadMarker::new() {
+        FOCUSED.store(overlay::focused_display(mtm).unwrap_or(0), Ordering::SeqCst);
+    }
+}
+
+/// Sample the focused display and cap it if it is dark (poll thread)
+fn check(rule: &ContrastGuard) {
+    let display = FOCUSED.load(Ordering::SeqCst);
+    if display == 0 || !unsafe { CGPreflightScreenCaptureAccess() } {
+        return;
+    }
+    if !overlay::is_dimmed(display) {
+        // Not dimmed: nothing to guard, and no need to look
+        crate::app::run_on_main(|| set_cap(None));
+        return;
+    }
+    let Some(luminance) = sample(display) else {
+        return;
+    };
+    let capped = CAP.lock().unwrap().is_some_and(|(d, _)| d == display);
+    let cap = rule.cap(luminance, capped).map(|cap| (display, cap));
+    crate::app::run_on_main(move || set_cap(cap));
+}
+
+/// Hand a changed cap to the gamma backend. Main thread only.
+fn set_cap(cap: Option<(u32, f32)>) {
+    let changed = std::mem::replace(&mut *CAP.lock().unwrap(), cap) != cap;
+    if changed {
+        eprintln!("SaveMyEyes: Contrast cap now {:?}", cap);
+        overlay::set_contrast_cap(cap);
+    }
+}
+
+/// Average luminance of what `display` shows
+fn sample(display: u32) -> Option<f32> {
+    let mut pixels = vec![0u8; SAMPLE_WIDTH * SAMPLE_HEIGHT * 4];
+    unsafe {
+        let image = CGDisplayCreateImage(display);
+        if image.is_null() {
+            return None;
+        }
+        let space = CGColorSpaceCreateDeviceRGB();
+        let context = CGBitmapContextCreate(
+            pixels.as_mut_ptr() as *mut c_void,
+            SAMPLE_WIDTH,
+            SAMPLE_HEIGHT,
+            8,
+            SAMPLE_WIDTH * 4,
+            space,
+            IMAGE_ALPHA_NONE_SKIP_LAST,
+        );
+        let drawn = !context.is_null();
+        if drawn {
+            // Drawing into the small context does the downscale
+            let rect = NSRect::new(
+                NSPoint::new(0.0, 0.0),
+                NSSize::new(SAMPLE_WIDTH as f64, SAMPLE_HEIGHT as f64),
+            );
+            CGContextDrawImage(context, rect, image);
+            CGContextRelease(context);
+        }
+        CGColorSpaceRelease(space);
+        CGImageRelease(image);
+        drawn.then(|| average_luminance(&pixels, [0, 1, 2]))
+    }
+}
@@ -0,0 +1,112 @@
+// External commands (shared events.rs) on macOS.
+//
+// Another process (the native messaging host, or the app binary run again
+// with `--toggle`, `--opacity 40` and the like) posts the command as JSON in
+// the object of a distributed notification. The app observes it from launch
+// and hands it to app::dispatch_command, which runs it on the main thread
+// like a hotkey. Senders check that the app is running first, since nothing
+// answers a distributed notification.
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2::{define_class, msg_send, sel, AllocAnyThread};
+use objc2_app_kit::NSRunningApplication;
+use objc2_foundation::{NSNotification, NSObject, NSObjectProtocol, NSString};
+
+use std::sync::Mutex;
+
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::Cause;
+
+/// Distributed notification carrying a command
+const NOTIFICATION: &str = "com.kdspl.savemyeyes.command";
+/// Bundle identifier of the app (Info.plist)
+const BUNDLE_ID: &str = "com.kdspl.savemyeyes";
+
+static OBSERVER: Mutex<Option<Retained<CommandObserver>>> = Mutex::new(None);
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "CommandObserver"]
+    #[thread_kind = AllocAnyThread]
+    struct CommandObserver;
+
+    unsafe impl NSObjectProtocol for CommandObserver {}
+
+    impl CommandObserver {
+        #[unsafe(method(externalCommand:))]
+        fn external_command(&self, notification: &NSNotification) {
+            let Some(object) = notification.object() else {
+                return;
+            };
+            let Ok(text) = object.downcast::<NSString>() else {
+                return;
+            };
+            match Command::from_json(&text.to_string()) {
+                Some(command) => crate::app::dispatch_command(command, Cause::Command),
+                None => eprintln!("SaveMyEyes: ignoring malformed command {:?}", text.to_string()),
+            }
+        }
+    }
+);
+
+impl CommandObserver {
+    fn new() -> Retained<Self> {
+        let alloc = Self::alloc();
+        unsafe { msg_send![alloc, init] }
+    }
+}
+
+fn distributed_center() -> *mut AnyObject {
+    unsafe {
+        msg_send![
+            AnyClass::get(c"NSDistributedNotificationCenter").unwrap(),
+            defaultCenter
+        ]
+    }
+}
+
+/// Start accepting commands for the rest of the session.
+pub fn listen() {
+    let mut guard = OBSERVER.lock().unwrap();
+    if guard.is_some() {
+        return;
+    }
+    let observer = CommandObserver::new();
+    let name = NSString::from_str(NOTIFICATION);
+    unsafe {
+        let _: () = msg_send![
+            distributed_center(),
+            addObserver: &*observer,
+            selector: sel!(externalCommand:),
+            name: &*name,
+            object: std::ptr::null::<AnyObject>()
+        ];
+    }
+    *guard = Some(observer);
+}
+
+/// Deliver `command` to the running app. False if it isn't running.
+pub fn forward(command: &Command) -> bool {
+    let own_pid = std::process::id() as i32;
+    let running = NSRunningApplication::runningApplicationsWithBundleIdentifier(
+        &NSString::from_str(BUNDLE_ID),
+    )
+    .iter()
+    .any(|app| app.processIdentifier() != own_pid);
+    if !running {
+        return false;
+    }
+    let name = NSString::from_str(NOTIFICATION);
+    let json = NSString::from_str(&command.to_json());
+    unsafe {
+        let _: () = msg_send![
+            distributed_center(),
+            postNotificationName: &*name,
+            object: &*json,
+            userInfo: std::ptr::null::<AnyObject>(),
+            deliverImmediately: true
+        ];
+    }
+    true
+}
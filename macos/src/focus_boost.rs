@@ -0,0 +1,75 @@
+// "Boost here": less dimming while the app in front keeps the focus.
+//
+// Cmd+Shift+B takes `focus_boost.step` off every level (see shared
+// focus_boost.rs), and again on each further press. macOS only shows other
+// apps' window titles to apps holding the Accessibility or Screen Recording
+// permission, so a boost is tied to the frontmost app, its process id and
+// name, rather than to one of its windows: switching apps ends it, another
+// document in the same app doesn't. While a boost is on, a poll thread has
+// the main thread compare the frontmost app twice a second; the overrides
+// themselves live in overlay.rs. Nothing is saved.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use objc2_app_kit::NSWorkspace;
+
+use savemyeyes_shared::focus_boost::{self, FocusedWindow};
+use savemyeyes_shared::shutdown;
+
+use crate::config::AppConfig;
+use crate::overlay;
+
+/// How often the poll thread checks the frontmost app
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// The frontmost app, None for none or ourselves (menu bar, settings)
+pub fn frontmost() -> Option<FocusedWindow> {
+    let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+    let pid = app.processIdentifier();
+    if pid == std::process::id() as i32 {
+        return None;
+    }
+    Some(FocusedWindow {
+        handle: pid as u64,
+        title: app
+            .localizedName()
+            .map(|name| name.to_string())
+            .unwrap_or_default(),
+    })
+}
+
+/// The boost hotkey: lift the dimming one step while the frontmost app
+/// keeps the focus. Main thread only. Returns the HUD message.
+pub fn boost(cfg: &AppConfig) -> String {
+    if cfg.designer_mode {
+        return "Designer mode is on \u{2014} dimming paused".to_string();
+    }
+    if !cfg.is_enabled {
+        return "Dimming is off".to_string();
+    }
+    let Some(window) = frontmost() else {
+        return "No app to boost".to_string();
+    };
+    let title = window.title.clone();
+    let lift = overlay::push_focus_boost(window, cfg.focus_boost.step);
+    if !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("focus-boost-poll", poll);
+    }
+    let message = focus_boost::message(lift, &title);
+    eprintln!("SaveMyEyes: {}", message);
+    message
+}
+
+fn poll() {
+    while shutdown::sleep(POLL_INTERVAL) && overlay::focus_boost_active() {
+        crate::app::run_on_main(|| {
+            if overlay::focus_moved(frontmost().as_ref()) {
+                eprintln!("SaveMyEyes: focus moved, boost over");
+            }
+        });
+    }
+    POLLING.store(false, Ordering::SeqCst);
+}
@@ -6,25 +6,89 @@
 //
 // Fallback: CGEventTap (handles events when our app is active)
 //
-// Hotkeys:
+// Hotkeys (toggle, increase and decrease come from config `hotkey_toggle`
+// etc., parsed by shared hotkeys.rs; `set_keys` swaps them at any time):
 //   Cmd+Shift+D       -> Toggle dimmer
 //   Cmd+Shift+>  (.)  -> Increase dimming
 //   Cmd+Shift+<  (,)  -> Decrease dimming
-
-use std::sync::atomic::{AtomicBool, Ordering};
+//   Cmd+Shift+M       -> Movie mode (fixed)
+//   Cmd+Shift+B       -> Boost here (fixed, focus_boost.rs)
+//
+// Like custom actions they match on the key code and the exact modifiers.
+// The settings window's shortcut recorder unregisters everything while it
+// listens and reads the pressed key back with `combo_for_key`. The key
+// codes of shortcut keys are in key_codes.rs.
+//
+// Mouse-button hotkeys (side buttons + modifiers, off by default) use a
+// separate, active CGEventTap for other-mouse events so the matching clicks
+// can be swallowed. It dispatches through the same app::dispatch_hotkey.
+//
+// Level chord (off by default): after the toggle hotkey, two digit keys
+// within 1.5s set an exact level. Digits are read by the keyboard tap when
+// it runs (else by the NSEvent monitor, never both); both are listen-only,
+// so the digits still reach the focused app.
+//
+// Custom actions (shared custom_actions.rs) are matched in the same two
+// places, by key code and the exact modifiers, and run through
+// app::dispatch_custom_action. Like chord digits they are read by the
+// keyboard tap when it runs, else by the NSEvent monitor, so an action
+// never runs twice for one key press.
+//
+// Holding increase/decrease delivers key auto-repeat, which `ramp_step`
+// turns into an accelerating change (shared adjust_ramp.rs).
+//
+// "Disable Global Shortcuts" (hotkey-less mode) installs none of this: no
+// NSEvent monitor and no event taps. unregister_all() tears down what is
+// installed: the monitor is removed and the taps are invalidated and their
+// run loops stopped, rather than left running and ignored.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::Mutex;
 use std::ptr::NonNull;
+use std::time::Instant;
+
+use savemyeyes_shared::adjust_ramp::{AdjustRamp, HoldRamp};
+use savemyeyes_shared::custom_actions::CustomAction;
+use savemyeyes_shared::hotkeys::{
+    ChordStep, KeyCombo, LevelChord, Modifiers, MouseBinding, MouseButton, MouseHotkeys,
+};
+
+pub use savemyeyes_shared::hotkeys::HotkeyAction;
+
+use crate::key_codes::{chord_digit, key_code};
+pub use crate::key_codes::combo_for_key;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 
 static REGISTERED: AtomicBool = AtomicBool::new(false);
+/// Hotkey-less mode: register_all and the mouse tap install nothing
+static GLOBAL_INPUT_DISABLED: AtomicBool = AtomicBool::new(false);
+/// Keyboard tap and the run loop serving it, while installed
+static KEY_TAP: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+static KEY_TAP_RUN_LOOP: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+thread_local! {
+    // NSEvent monitor token (main thread only)
+    static KEY_MONITOR: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
 
-/// Key codes (macOS virtual key codes)
-const KEY_D: u16 = 0x02;
-const KEY_PERIOD: u16 = 0x2F;
-const KEY_COMMA: u16 = 0x2B;
+/// Level chord in progress (started by the toggle hotkey)
+static CHORD: Mutex<LevelChord> = Mutex::new(LevelChord::new());
+/// The keyboard tap is running and reads chord digits itself
+static TAP_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Hold-to-adjust state of the increase/decrease hotkeys
+static RAMP: Mutex<HoldRamp> = Mutex::new(HoldRamp::new());
+/// Shortcuts of the hotkey actions (config hotkey_toggle etc.)
+static KEYS: Mutex<Vec<(KeyCombo, HotkeyAction)>> = Mutex::new(Vec::new());
+/// Hotkey of each custom action, in config order (None: no usable hotkey)
+static CUSTOM: Mutex<Vec<Option<KeyCombo>>> = Mutex::new(Vec::new());
 
 /// Register global hotkeys via NSEvent global monitor.
 /// Must be called from the main thread.
 pub fn register_all() {
-    if REGISTERED.swap(true, Ordering::SeqCst) {
+    if GLOBAL_INPUT_DISABLED.load(Ordering::SeqCst) || REGISTERED.swap(true, Ordering::SeqCst) {
         return;
     }
 
@@ -39,51 +103,170 @@ pub fn register_all() {
     });
 }
 
-#[allow(dead_code)]
+/// Remove the NSEvent monitor and tear down the keyboard tap.
+/// Must be called from the main thread.
 pub fn unregister_all() {
     REGISTERED.store(false, Ordering::SeqCst);
+    end_level_chord();
+    KEY_MONITOR.with(|monitor| {
+        if let Some(token) = monitor.borrow_mut().take() {
+            unsafe { objc2_app_kit::NSEvent::removeMonitor(&token) };
+        }
+    });
+    let tap = KEY_TAP.swap(std::ptr::null_mut(), Ordering::SeqCst);
+    let run_loop = KEY_TAP_RUN_LOOP.swap(std::ptr::null_mut(), Ordering::SeqCst);
+    unsafe { remove_tap(tap, run_loop) };
+}
+
+/// Enter or leave hotkey-less mode: no hotkeys, monitors or event taps at all.
+/// Main thread only.
+pub fn set_global_input_disabled(disabled: bool, mouse: &MouseHotkeys) {
+    GLOBAL_INPUT_DISABLED.store(disabled, Ordering::SeqCst);
+    if disabled {
+        unregister_all();
+    } else {
+        register_all();
+    }
+    apply_mouse_hotkeys(mouse);
+}
+
+/// True in hotkey-less mode
+pub fn global_input_disabled() -> bool {
+    GLOBAL_INPUT_DISABLED.load(Ordering::SeqCst)
+}
+
+/// Global input hooks installed right now, for the diagnostics report
+pub fn installed_hooks() -> Vec<&'static str> {
+    let mut hooks = Vec::new();
+    if KEY_MONITOR.with(|m| m.borrow().is_some()) {
+        hooks.push("NSEvent key monitor");
+    }
+    if !KEY_TAP.load(Ordering::SeqCst).is_null() {
+        hooks.push("keyboard CGEventTap");
+    }
+    if !MOUSE_TAP.load(Ordering::SeqCst).is_null() {
+        hooks.push("mouse CGEventTap");
+    }
+    hooks
+}
+
+/// Disable and invalidate an event tap and stop the run loop serving it,
+/// which ends its thread.
+unsafe fn remove_tap(tap: CFMachPortRef, run_loop: *mut std::ffi::c_void) {
+    if !tap.is_null() {
+        CGEventTapEnable(tap, false);
+        CFMachPortInvalidate(tap);
+    }
+    if !run_loop.is_null() {
+        CFRunLoopStop(run_loop);
+    }
+}
+
+/// Level change for an increase (`up`) or decrease hotkey event, following
+/// the configured ramp while the key is held. 0.0 until a slow hold adds up
+/// to a whole step.
+pub fn ramp_step(ramp: &AdjustRamp, up: bool) -> f32 {
+    let now = Instant::now();
+    RAMP.lock().unwrap().next(ramp.profile(), up, now)
 }
 
-/// Hotkey actions dispatched to the app module
-#[derive(Debug, Clone, Copy)]
-pub enum HotkeyAction {
-    Toggle,
-    Increase,
-    Decrease,
+/// Listen for level digits after the toggle hotkey
+pub fn start_level_chord() {
+    CHORD.lock().unwrap().start(Instant::now());
+}
+
+/// Stop listening for level digits
+pub fn end_level_chord() {
+    CHORD.lock().unwrap().cancel();
+}
+
+/// Feed a key to a running level chord. Returns true if it was a chord digit.
+fn handle_chord_key(keycode: u16) -> bool {
+    let Some(digit) = chord_digit(keycode) else {
+        return false;
+    };
+    let now = Instant::now();
+    let step = {
+        let mut chord = CHORD.lock().unwrap();
+        if !chord.is_active(now) {
+            return false;
+        }
+        chord.push_digit(digit, now)
+    };
+    if step == ChordStep::Inactive {
+        return false;
+    }
+    crate::app::dispatch_chord_step(step);
+    true
+}
+
+/// Take the hotkeys of the custom actions in config. Any thread.
+pub fn set_custom_actions(actions: &[CustomAction]) {
+    *CUSTOM.lock().unwrap() = actions.iter().map(CustomAction::combo).collect();
+}
+
+/// Take the shortcuts of the hotkey actions (config `key_bindings`). Any
+/// thread; the next key press already uses them.
+pub fn set_keys(keys: Vec<(KeyCombo, HotkeyAction)>) {
+    *KEYS.lock().unwrap() = keys;
+}
+
+/// Modifiers held during a key event
+pub fn event_modifiers(event: &objc2_app_kit::NSEvent) -> Modifiers {
+    use objc2_app_kit::NSEventModifierFlags;
+
+    let flags = event.modifierFlags();
+    Modifiers {
+        ctrl: flags.contains(NSEventModifierFlags::Control),
+        alt: flags.contains(NSEventModifierFlags::Option),
+        shift: flags.contains(NSEventModifierFlags::Shift),
+        meta: flags.contains(NSEventModifierFlags::Command),
+    }
+}
+
+/// Run the hotkey action whose shortcut this is. True if there was one.
+fn handle_action_key(keycode: u16, modifiers: Modifiers, source: &str) -> bool {
+    let action = KEYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(c, _)| c.modifiers == modifiers && key_code(c.key) == Some(keycode))
+        .map(|(_, action)| *action);
+    if let Some(action) = action {
+        eprintln!("SaveMyEyes: [{}] {:?} hotkey detected", source, action);
+        crate::app::dispatch_hotkey(action);
+    }
+    action.is_some()
+}
+
+/// Run the custom action whose hotkey this is. True if there was one.
+fn handle_custom_key(keycode: u16, modifiers: Modifiers) -> bool {
+    let index = CUSTOM.lock().unwrap().iter().position(|combo| {
+        combo.is_some_and(|c| c.modifiers == modifiers && key_code(c.key) == Some(keycode))
+    });
+    if let Some(index) = index {
+        crate::app::dispatch_custom_action(index);
+    }
+    index.is_some()
 }
 
 // ---- NSEvent global monitor ------------------------------------------------
 
 fn install_ns_event_monitor() {
-    use objc2_app_kit::{NSEvent, NSEventMask, NSEventModifierFlags};
+    use objc2_app_kit::{NSEvent, NSEventMask};
 
     let handler = block2::RcBlock::new(move |event: NonNull<NSEvent>| {
         let event: &NSEvent = unsafe { event.as_ref() };
-        let flags = event.modifierFlags();
-        let has_cmd = flags.contains(NSEventModifierFlags::Command);
-        let has_shift = flags.contains(NSEventModifierFlags::Shift);
-
-        if !(has_cmd && has_shift) {
+        if !TAP_RUNNING.load(Ordering::SeqCst) && handle_chord_key(event.keyCode()) {
             return;
         }
 
-        let keycode = event.keyCode();
-
-        match keycode {
-            KEY_D => {
-                eprintln!("SaveMyEyes: [NSEvent] Cmd+Shift+D detected");
-                crate::app::dispatch_hotkey(HotkeyAction::Toggle);
-            }
-            KEY_PERIOD => {
-                eprintln!("SaveMyEyes: [NSEvent] Cmd+Shift+> detected");
-                crate::app::dispatch_hotkey(HotkeyAction::Increase);
-            }
-            KEY_COMMA => {
-                eprintln!("SaveMyEyes: [NSEvent] Cmd+Shift+< detected");
-                crate::app::dispatch_hotkey(HotkeyAction::Decrease);
-            }
-            _ => {}
+        let modifiers = event_modifiers(event);
+        if !TAP_RUNNING.load(Ordering::SeqCst) && handle_custom_key(event.keyCode(), modifiers) {
+            return;
         }
+
+        handle_action_key(event.keyCode(), modifiers, "NSEvent");
     });
 
     let monitor = NSEvent::addGlobalMonitorForEventsMatchingMask_handler(
@@ -97,8 +280,8 @@ fn install_ns_event_monitor() {
         eprintln!("SaveMyEyes: Failed to install NSEvent global monitor.");
     }
 
-    // Keep the monitor alive for the lifetime of the app by leaking it
-    std::mem::forget(monitor);
+    // Kept until unregister_all()
+    KEY_MONITOR.with(|m| *m.borrow_mut() = monitor);
 }
 
 // ---- CGEventTap fallback ----------------------------------------------------
@@ -110,13 +293,19 @@ type CGEventTapProxy = *mut std::ffi::c_void;
 
 const K_CG_SESSION_EVENT_TAP: u32 = 1;
 const K_CG_HEAD_INSERT_EVENT_TAP: u32 = 0;
+const K_CG_EVENT_TAP_OPTION_DEFAULT: u32 = 0;
 const K_CG_EVENT_TAP_OPTION_LISTEN_ONLY: u32 = 1;
 const K_CG_EVENT_KEY_DOWN: u32 = 10;
+const K_CG_EVENT_OTHER_MOUSE_DOWN: u32 = 25;
+const K_CG_EVENT_OTHER_MOUSE_UP: u32 = 26;
 const K_CG_EVENT_TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFFFFFE;
 
-const K_CG_EVENT_FLAG_MASK_COMMAND: u64 = 1 << 20;
 const K_CG_EVENT_FLAG_MASK_SHIFT: u64 = 1 << 17;
+const K_CG_EVENT_FLAG_MASK_CONTROL: u64 = 1 << 18;
+const K_CG_EVENT_FLAG_MASK_ALTERNATE: u64 = 1 << 19;
+const K_CG_EVENT_FLAG_MASK_COMMAND: u64 = 1 << 20;
 const K_CG_KEYBOARD_EVENT_KEYCODE: u32 = 9;
+const K_CG_MOUSE_EVENT_BUTTON_NUMBER: u32 = 3;
 
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
@@ -147,7 +336,9 @@ extern "C" {
         order: i64,
     ) -> CFRunLoopSourceRef;
 
+    fn CFMachPortInvalidate(port: CFMachPortRef);
     fn CFRunLoopGetCurrent() -> *mut std::ffi::c_void;
+    fn CFRunLoopStop(rl: *mut std::ffi::c_void);
     fn CFRunLoopAddSource(
         rl: *mut std::ffi::c_void,
         source: CFRunLoopSourceRef,
@@ -175,32 +366,23 @@ unsafe extern "C" fn event_tap_callback(
         return event;
     }
 
-    let flags = CGEventGetFlags(event);
-    let has_cmd = flags & K_CG_EVENT_FLAG_MASK_COMMAND != 0;
-    let has_shift = flags & K_CG_EVENT_FLAG_MASK_SHIFT != 0;
-
-    if !(has_cmd && has_shift) {
+    let keycode = CGEventGetIntegerValueField(event, K_CG_KEYBOARD_EVENT_KEYCODE) as u16;
+    if handle_chord_key(keycode) {
         return event;
     }
 
-    let keycode = CGEventGetIntegerValueField(event, K_CG_KEYBOARD_EVENT_KEYCODE) as u16;
-
-    match keycode {
-        KEY_D => {
-            eprintln!("SaveMyEyes: [CGEventTap] Cmd+Shift+D detected");
-            crate::app::dispatch_hotkey(HotkeyAction::Toggle);
-        }
-        KEY_PERIOD => {
-            eprintln!("SaveMyEyes: [CGEventTap] Cmd+Shift+> detected");
-            crate::app::dispatch_hotkey(HotkeyAction::Increase);
-        }
-        KEY_COMMA => {
-            eprintln!("SaveMyEyes: [CGEventTap] Cmd+Shift+< detected");
-            crate::app::dispatch_hotkey(HotkeyAction::Decrease);
-        }
-        _ => {}
+    let flags = CGEventGetFlags(event);
+    let modifiers = Modifiers {
+        ctrl: flags & K_CG_EVENT_FLAG_MASK_CONTROL != 0,
+        alt: flags & K_CG_EVENT_FLAG_MASK_ALTERNATE != 0,
+        shift: flags & K_CG_EVENT_FLAG_MASK_SHIFT != 0,
+        meta: flags & K_CG_EVENT_FLAG_MASK_COMMAND != 0,
+    };
+    if handle_custom_key(keycode, modifiers) {
+        return event;
     }
 
+    handle_action_key(keycode, modifiers, "CGEventTap");
     event // listen-only, always pass through
 }
 
@@ -212,6 +394,9 @@ fn install_event_tap() {
     let mut attempt = 0;
 
     loop {
+        if !REGISTERED.load(Ordering::SeqCst) {
+            return; // unregistered while waiting for permission
+        }
         attempt += 1;
         let tap = unsafe {
             CGEventTapCreate(
@@ -233,8 +418,25 @@ fn install_event_tap() {
                 let source = CFMachPortCreateRunLoopSource(std::ptr::null(), tap, 0);
                 let run_loop = CFRunLoopGetCurrent();
                 CFRunLoopAddSource(run_loop, source, kCFRunLoopCommonModes);
+                KEY_TAP.store(tap, Ordering::SeqCst);
+                KEY_TAP_RUN_LOOP.store(run_loop, Ordering::SeqCst);
+                if !REGISTERED.load(Ordering::SeqCst) {
+                    // unregister_all ran before the tap was published
+                    remove_tap(tap, std::ptr::null_mut());
+                    KEY_TAP.store(std::ptr::null_mut(), Ordering::SeqCst);
+                    KEY_TAP_RUN_LOOP.store(std::ptr::null_mut(), Ordering::SeqCst);
+                    return;
+                }
+                TAP_RUNNING.store(true, Ordering::SeqCst);
                 CFRunLoopRun();
             }
+            TAP_RUNNING.store(false, Ordering::SeqCst);
+            if !REGISTERED.load(Ordering::SeqCst) {
+                eprintln!("SaveMyEyes: CGEventTap removed.");
+                return;
+            }
+            KEY_TAP.store(std::ptr::null_mut(), Ordering::SeqCst);
+            KEY_TAP_RUN_LOOP.store(std::ptr::null_mut(), Ordering::SeqCst);
             eprintln!("SaveMyEyes: CGEventTap run loop exited, retrying...");
             continue;
         }
@@ -258,6 +460,152 @@ fn install_event_tap() {
     }
 }
 
+// ---- Mouse-button hotkeys ---------------------------------------------------
+
+/// Current mouse hotkey settings (read by the tap callback)
+static MOUSE_SETTINGS: Mutex<Option<MouseHotkeys>> = Mutex::new(None);
+/// The mouse event tap once created (enabled/disabled as settings change)
+static MOUSE_TAP: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+static MOUSE_TAP_RUN_LOOP: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
+static MOUSE_TAP_STARTED: AtomicBool = AtomicBool::new(false);
+/// Buttons whose press we swallowed, so the release is swallowed too
+static SWALLOWED_MOUSE4: AtomicBool = AtomicBool::new(false);
+static SWALLOWED_MOUSE5: AtomicBool = AtomicBool::new(false);
+
+fn mouse_hotkeys_active() -> bool {
+    MOUSE_SETTINGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|s| s.enabled && !s.bindings().is_empty())
+}
+
+/// Start, enable or disable the mouse tap to match `settings`.
+/// In hotkey-less mode the tap is removed instead.
+pub fn apply_mouse_hotkeys(settings: &MouseHotkeys) {
+    *MOUSE_SETTINGS.lock().unwrap() = Some(settings.clone());
+    let active = mouse_hotkeys_active();
+
+    if global_input_disabled() {
+        let tap = MOUSE_TAP.swap(std::ptr::null_mut(), Ordering::SeqCst);
+        let run_loop = MOUSE_TAP_RUN_LOOP.swap(std::ptr::null_mut(), Ordering::SeqCst);
+        unsafe { remove_tap(tap, run_loop) };
+        return;
+    }
+
+    let tap = MOUSE_TAP.load(Ordering::SeqCst);
+    if !tap.is_null() {
+        unsafe { CGEventTapEnable(tap, active) };
+    } else if active && !MOUSE_TAP_STARTED.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(install_mouse_tap);
+    }
+}
+
+unsafe extern "C" fn mouse_tap_callback(
+    _proxy: CGEventTapProxy,
+    event_type: u32,
+    event: CGEventRef,
+    _user_info: *mut std::ffi::c_void,
+) -> CGEventRef {
+    if event_type == K_CG_EVENT_TAP_DISABLED_BY_TIMEOUT {
+        eprintln!("SaveMyEyes: mouse CGEventTap disabled by timeout, re-enabling");
+        CGEventTapEnable(MOUSE_TAP.load(Ordering::SeqCst), mouse_hotkeys_active());
+        return event;
+    }
+
+    let button = match CGEventGetIntegerValueField(event, K_CG_MOUSE_EVENT_BUTTON_NUMBER) {
+        3 => MouseButton::Mouse4,
+        4 => MouseButton::Mouse5,
+        _ => return event,
+    };
+    let swallowed = match button {
+        MouseButton::Mouse4 => &SWALLOWED_MOUSE4,
+        MouseButton::Mouse5 => &SWALLOWED_MOUSE5,
+    };
+
+    if event_type == K_CG_EVENT_OTHER_MOUSE_UP {
+        return if swallowed.swap(false, Ordering::SeqCst) {
+            std::ptr::null_mut()
+        } else {
+            event
+        };
+    }
+    if event_type != K_CG_EVENT_OTHER_MOUSE_DOWN {
+        return event;
+    }
+
+    let flags = CGEventGetFlags(event);
+    let modifiers = Modifiers {
+        ctrl: flags & K_CG_EVENT_FLAG_MASK_CONTROL != 0,
+        alt: flags & K_CG_EVENT_FLAG_MASK_ALTERNATE != 0,
+        shift: flags & K_CG_EVENT_FLAG_MASK_SHIFT != 0,
+        meta: flags & K_CG_EVENT_FLAG_MASK_COMMAND != 0,
+    };
+    let action = MOUSE_SETTINGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| s.action_for(button, modifiers));
+
+    match action {
+        Some(action) => {
+            eprintln!("SaveMyEyes: [mouse] {} detected", MouseBinding { modifiers, button });
+            crate::app::dispatch_hotkey(action);
+            swallowed.store(true, Ordering::SeqCst);
+            std::ptr::null_mut() // consumed: no back/forward in the focused app
+        }
+        None => event,
+    }
+}
+
+fn install_mouse_tap() {
+    let event_mask: u64 = (1 << K_CG_EVENT_OTHER_MOUSE_DOWN) | (1 << K_CG_EVENT_OTHER_MOUSE_UP);
+    let max_attempts = 60;
+
+    for attempt in 1..=max_attempts {
+        if global_input_disabled() {
+            MOUSE_TAP_STARTED.store(false, Ordering::SeqCst);
+            return;
+        }
+        let tap = unsafe {
+            CGEventTapCreate(
+                K_CG_SESSION_EVENT_TAP,
+                K_CG_HEAD_INSERT_EVENT_TAP,
+                K_CG_EVENT_TAP_OPTION_DEFAULT,
+                event_mask,
+                mouse_tap_callback,
+                std::ptr::null_mut(),
+            )
+        };
+
+        if !tap.is_null() {
+            eprintln!("SaveMyEyes: mouse CGEventTap created (attempt {}).", attempt);
+            unsafe {
+                // Settings may have been switched off while we waited
+                CGEventTapEnable(tap, mouse_hotkeys_active());
+                let source = CFMachPortCreateRunLoopSource(std::ptr::null(), tap, 0);
+                let run_loop = CFRunLoopGetCurrent();
+                CFRunLoopAddSource(run_loop, source, kCFRunLoopCommonModes);
+                MOUSE_TAP_RUN_LOOP.store(run_loop, Ordering::SeqCst);
+                MOUSE_TAP.store(tap, Ordering::SeqCst);
+                CFRunLoopRun();
+            }
+            // Removed by hotkey-less mode; a later apply starts a new tap
+            eprintln!("SaveMyEyes: mouse CGEventTap removed.");
+            MOUSE_TAP_STARTED.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        // Needs Accessibility permission, same as the keyboard tap
+        std::thread::sleep(std::time::Duration::from_secs(2));
+    }
+    eprintln!(
+        "SaveMyEyes: mouse CGEventTap unavailable after {} attempts; mouse hotkeys disabled.",
+        max_attempts
+    );
+    MOUSE_TAP_STARTED.store(false, Ordering::SeqCst);
+}
+
 // ---- Accessibility permission -----------------------------------------------
 
 #[link(name = "ApplicationServices", kind = "framework")]
@@ -293,8 +641,8 @@ fn request_accessibility_permission() {
             keys.as_ptr(),
             values.as_ptr(),
             1,
-            &kCFTypeDictionaryKeyCallBacks as *const _ as *const std::ffi::c_void,
-            &kCFTypeDictionaryValueCallBacks as *const _ as *const std::ffi::c_void,
+            &kCFTypeDictionaryKeyCallBacks as *const _,
+            &kCFTypeDictionaryValueCallBacks as *const _,
         );
         let trusted = AXIsProcessTrustedWithOptions(options);
         if trusted {
@@ -346,9 +694,18 @@ fn is_accessibility_functional() -> bool {
 /// we prompt the user again.
 pub fn reset_accessibility() {
     eprintln!("SaveMyEyes: Resetting Accessibility TCC entry…");
-    let _ = std::process::Command::new("tccutil")
+    match std::process::Command::new("tccutil")
         .args(["reset", "Accessibility", "com.kdspl.savemyeyes"])
-        .output();
+        .output()
+    {
+        Ok(out) if out.status.success() => {}
+        Ok(out) => eprintln!(
+            "SaveMyEyes: tccutil reset failed ({}): {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ),
+        Err(e) => eprintln!("SaveMyEyes: could not run tccutil: {}", e),
+    }
 }
 
 /// Ensure accessibility permission is truly granted.
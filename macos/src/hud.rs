@@ -0,0 +1,216 @@
+// On-screen HUD for hotkey feedback.
+//
+// A small rounded pill near the bottom of the screen under the mouse that
+// shows the level a hotkey just set (or the digits of a level chord being
+// typed). The panel is borderless, click-through and never becomes key, so
+// it doesn't steal focus from the app in front. One panel is created lazily
+// and reused; a generation counter lets only the latest show() hide it. It
+// fades out unless animations are off (shared motion.rs). Nothing is shown
+// during presentation mode.
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2::{msg_send, MainThreadMarker};
+use objc2_app_kit::{
+    NSBackingStoreType, NSColor, NSFont, NSPanel, NSScreen, NSTextAlignment, NSTextField,
+    NSWindowCollectionBehavior, NSWindowStyleMask, NSWorkspace,
+};
+use objc2_foundation::{NSPoint, NSRect, NSSize, NSString};
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use savemyeyes_shared::motion;
+use savemyeyes_shared::presentation;
+use savemyeyes_shared::trace::Mark;
+
+use crate::trace;
+use crate::ui::theme::{CLR_FG, CLR_SECONDARY};
+
+/// How long the HUD stays up after the last update
+const SHOW_MS: u64 = 1200;
+/// Length of the fade-out
+const FADE_MS: u64 = 200;
+const FONT_SIZE: f64 = 16.0;
+const PAD_X: f64 = 20.0;
+const PAD_Y: f64 = 10.0;
+/// Distance from the bottom of the visible frame
+const BOTTOM_MARGIN: f64 = 96.0;
+const ALPHA: f64 = 0.92;
+/// NSStatusWindowLevel — above the floating Settings window
+const HUD_LEVEL: isize = 25;
+
+thread_local! {
+    // Main-thread only, like every other AppKit object
+    static HUD: RefCell<Option<(Retained<NSPanel>, Retained<NSTextField>)>> =
+        const { RefCell::new(None) };
+}
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn create(mtm: MainThreadMarker) -> (Retained<NSPanel>, Retained<NSTextField>) {
+    let frame = NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(120.0, 40.0));
+    let panel = NSPanel::initWithContentRect_styleMask_backing_defer(
+        mtm.alloc::<NSPanel>(),
+        frame,
+        NSWindowStyleMask::Borderless | NSWindowStyleMask::NonactivatingPanel,
+        NSBackingStoreType::Buffered,
+        false,
+    );
+    unsafe { panel.setReleasedWhenClosed(false) };
+    panel.setLevel(HUD_LEVEL);
+    panel.setOpaque(false);
+    panel.setHasShadow(false);
+    panel.setIgnoresMouseEvents(true);
+    panel.setBackgroundColor(Some(&NSColor::clearColor()));
+    panel.setCollectionBehavior(
+        NSWindowCollectionBehavior::CanJoinAllSpaces | NSWindowCollectionBehavior::Stationary,
+    );
+
+    if let Some(content) = panel.contentView() {
+        content.setWantsLayer(true);
+        if let Some(layer) = content.layer() {
+            let bg = NSColor::colorWithRed_green_blue_alpha(
+                CLR_SECONDARY.0,
+                CLR_SECONDARY.1,
+                CLR_SECONDARY.2,
+                ALPHA,
+            );
+            unsafe {
+                let cg: *const AnyObject = msg_send![&*bg, CGColor];
+                let _: () = msg_send![&*layer, setBackgroundColor: cg];
+                let _: () = msg_send![&*layer, setMasksToBounds: true];
+            }
+        }
+    }
+
+    let label = NSTextField::labelWithString(&NSString::from_str(""), mtm);
+    label.setBezeled(false);
+    label.setDrawsBackground(false);
+    label.setEditable(false);
+    label.setSelectable(false);
+    label.setAlignment(NSTextAlignment::Center);
+    label.setFont(Some(&NSFont::systemFontOfSize_weight(FONT_SIZE, 0.3)));
+    label.setTextColor(Some(&NSColor::colorWithRed_green_blue_alpha(
+        CLR_FG.0, CLR_FG.1, CLR_FG.2, 1.0,
+    )));
+    if let Some(content) = panel.contentView() {
+        content.addSubview(&label);
+    }
+
+    (panel, label)
+}
+
+/// "Reduce motion" is on in Accessibility settings
+pub fn os_reduces_motion() -> bool {
+    let workspace = NSWorkspace::sharedWorkspace();
+    unsafe { msg_send![&*workspace, accessibilityDisplayShouldReduceMotion] }
+}
+
+/// Animate the panel's alpha to `alpha` over `ms` (0 sets it at once and
+/// cancels a running fade)
+fn animate_alpha(panel: &NSPanel, alpha: f64, ms: u64) {
+    let class = AnyClass::get(c"NSAnimationContext").unwrap();
+    unsafe {
+        let _: () = msg_send![class, beginGrouping];
+        let context: *mut AnyObject = msg_send![class, currentContext];
+        let _: () = msg_send![context, setDuration: ms as f64 / 1000.0];
+        let animator: *mut AnyObject = msg_send![panel, animator];
+        let _: () = msg_send![animator, setAlphaValue: alpha];
+        let _: () = msg_send![class, endGrouping];
+    }
+}
+
+/// Visible frame of the screen under the mouse (falls back to the main screen)
+fn screen_frame_at_mouse(mtm: MainThreadMarker) -> Option<NSRect> {
+    let mouse: NSPoint = unsafe {
+        msg_send![objc2::runtime::AnyClass::get(c"NSEvent").unwrap(), mouseLocation]
+    };
+    let screens = NSScreen::screens(mtm);
+    screens
+        .iter()
+        .find(|s| {
+            let f = s.frame();
+            mouse.x >= f.origin.x
+                && mouse.x < f.origin.x + f.size.width
+                && mouse.y >= f.origin.y
+                && mouse.y < f.origin.y + f.size.height
+        })
+        .or_else(|| NSScreen::mainScreen(mtm))
+        .map(|s| s.visibleFrame())
+}
+
+/// Show `message` on the screen under the mouse (main thread only).
+/// Calling again while visible updates the text and restarts the timer.
+pub fn show(mtm: MainThreadMarker, message: &str) {
+    if presentation::is_active() {
+        return;
+    }
+    HUD.with(|hud| {
+        let mut hud = hud.borrow_mut();
+        let (panel, label) = hud.get_or_insert_with(|| create(mtm));
+
+        // Size the pill to the text
+        label.setStringValue(&NSString::from_str(message));
+        label.sizeToFit();
+        let text = label.frame().size;
+        let width = (text.width + PAD_X * 2.0).ceil();
+        let height = (text.height + PAD_Y * 2.0).ceil();
+        label.setFrame(NSRect::new(
+            NSPoint::new(0.0, PAD_Y),
+            NSSize::new(width, text.height),
+        ));
+        if let Some(layer) = panel.contentView().and_then(|c| c.layer()) {
+            let _: () = unsafe { msg_send![&*layer, setCornerRadius: height / 2.0] };
+        }
+
+        // Bottom center of the screen under the mouse
+        let Some(screen) = screen_frame_at_mouse(mtm) else {
+            return;
+        };
+        let x = screen.origin.x + (screen.size.width - width) / 2.0;
+        let y = screen.origin.y + BOTTOM_MARGIN;
+        panel.setFrame_display(
+            NSRect::new(NSPoint::new(x, y), NSSize::new(width, height)),
+            true,
+        );
+        animate_alpha(panel, 1.0, 0);
+        panel.orderFrontRegardless();
+    });
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    after(SHOW_MS, generation, move || {
+        if motion::animate(os_reduces_motion) {
+            HUD.with(|hud| {
+                if let Some((panel, _)) = hud.borrow().as_ref() {
+                    trace::mark(Mark::FadeFrame);
+                    animate_alpha(panel, 0.0, FADE_MS);
+                }
+            });
+            after(FADE_MS, generation, hide);
+        } else {
+            hide();
+        }
+    });
+}
+
+/// Run `f` on the main thread after `ms`, unless show() was called meanwhile
+fn after(ms: u64, generation: u64, f: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(ms));
+        crate::app::run_on_main(move || {
+            if GENERATION.load(Ordering::SeqCst) == generation {
+                f();
+            }
+        });
+    });
+}
+
+fn hide() {
+    HUD.with(|hud| {
+        if let Some((panel, _)) = hud.borrow().as_ref() {
+            panel.orderOut(None);
+            panel.setAlphaValue(1.0);
+        }
+    });
+}
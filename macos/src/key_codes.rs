@@ -0,0 +1,141 @@
+// Virtual key codes of shortcut keys for hotkeys.rs.
+//
+// The event monitor and taps see key codes (kVK_* in Carbon's Events.h),
+// which name a key position rather than a character; the tables below are
+// the ANSI layout. Shortcuts (shared hotkeys.rs) map to a key code here, and
+// the settings window's shortcut recorder maps a pressed key code back.
+
+use savemyeyes_shared::hotkeys::{Key, KeyCombo, Modifiers, NamedKey};
+
+/// Top-row digit keys 0–9
+const DIGIT_KEYS: [u16; 10] = [0x1D, 0x12, 0x13, 0x14, 0x15, 0x17, 0x16, 0x1A, 0x1C, 0x19];
+/// Keypad digit keys 0–9
+const KEYPAD_KEYS: [u16; 10] = [0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5B, 0x5C];
+/// Letter keys A–Z (ANSI layout)
+const LETTER_KEYS: [u16; 26] = [
+    0x00, 0x0B, 0x08, 0x02, 0x0E, 0x03, 0x05, 0x04, 0x22, 0x26, 0x28, 0x25, 0x2E, 0x2D, 0x1F, 0x23,
+    0x0C, 0x0F, 0x01, 0x11, 0x20, 0x09, 0x0D, 0x07, 0x10, 0x06,
+];
+/// Function keys F1–F20
+const FUNCTION_KEYS: [u16; 20] = [
+    0x7A, 0x78, 0x63, 0x76, 0x60, 0x61, 0x62, 0x64, 0x65, 0x6D, 0x67, 0x6F, 0x69, 0x6B, 0x71,
+    0x6A, 0x40, 0x4F, 0x50, 0x5A,
+];
+
+/// Digit of a top-row or keypad digit key (level chord)
+pub fn chord_digit(keycode: u16) -> Option<u8> {
+    DIGIT_KEYS
+        .iter()
+        .chain(KEYPAD_KEYS.iter())
+        .position(|&k| k == keycode)
+        .map(|i| (i % 10) as u8)
+}
+
+/// Key code of a shortcut's key, if there is one
+pub fn key_code(key: Key) -> Option<u16> {
+    match key {
+        Key::Char(c @ 'A'..='Z') => Some(LETTER_KEYS[(c as u8 - b'A') as usize]),
+        Key::Char(c) => c.to_digit(10).map(|d| DIGIT_KEYS[d as usize]),
+        Key::Function(n) => FUNCTION_KEYS.get(n as usize - 1).copied(),
+        Key::Named(key) => Some(named_key_code(key)),
+    }
+}
+
+fn named_key_code(key: NamedKey) -> u16 {
+    match key {
+        NamedKey::Up => 0x7E,
+        NamedKey::Down => 0x7D,
+        NamedKey::Left => 0x7B,
+        NamedKey::Right => 0x7C,
+        NamedKey::Home => 0x73,
+        NamedKey::End => 0x77,
+        NamedKey::PageUp => 0x74,
+        NamedKey::PageDown => 0x79,
+        // Help sits where Insert is on PC keyboards
+        NamedKey::Insert => 0x72,
+        // Forward delete
+        NamedKey::Delete => 0x75,
+        NamedKey::Space => 0x31,
+        NamedKey::Tab => 0x30,
+        NamedKey::Enter => 0x24,
+        NamedKey::Escape => 0x35,
+        NamedKey::Backspace => 0x33,
+        NamedKey::Period => 0x2F,
+        NamedKey::Comma => 0x2B,
+    }
+}
+
+/// The shortcut of a key press (the settings window's shortcut recorder).
+/// None for keys a shortcut can't use.
+pub fn combo_for_key(keycode: u16, modifiers: Modifiers) -> Option<KeyCombo> {
+    let position = |keys: &[u16]| keys.iter().position(|&k| k == keycode).map(|i| i as u8);
+    let key = if let Some(i) = position(&LETTER_KEYS) {
+        Key::Char((b'A' + i) as char)
+    } else if let Some(d) = position(&DIGIT_KEYS) {
+        Key::Char((b'0' + d) as char)
+    } else if let Some(n) = position(&FUNCTION_KEYS) {
+        Key::Function(n + 1)
+    } else {
+        Key::Named(NamedKey::ALL.into_iter().find(|key| named_key_code(*key) == keycode)?)
+    };
+    Some(KeyCombo { modifiers, key })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_key_maps_back() {
+        let modifiers = Modifiers {
+            meta: true,
+            ..Modifiers::default()
+        };
+        let mut keys: Vec<Key> = ('A'..='Z').chain('0'..='9').map(Key::Char).collect();
+        keys.extend((1..=20).map(Key::Function));
+        keys.extend(NamedKey::ALL.into_iter().map(Key::Named));
+        for key in keys {
+            let code = key_code(key).unwrap();
+            assert_eq!(
+                combo_for_key(code, modifiers),
+                Some(KeyCombo { modifiers, key }),
+                "{:?}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn key_codes_are_distinct() {
+        let mut codes: Vec<u16> = DIGIT_KEYS
+            .iter()
+            .chain(&KEYPAD_KEYS)
+            .chain(&LETTER_KEYS)
+            .chain(&FUNCTION_KEYS)
+            .copied()
+            .chain(NamedKey::ALL.into_iter().map(named_key_code))
+            .collect();
+        let count = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), count);
+    }
+
+    #[test]
+    fn function_keys_past_f20_have_no_code() {
+        assert_eq!(key_code(Key::Function(20)), Some(0x5A));
+        assert_eq!(key_code(Key::Function(21)), None);
+    }
+
+    #[test]
+    fn chord_digits_from_row_and_keypad() {
+        assert_eq!(chord_digit(0x1D), Some(0));
+        assert_eq!(chord_digit(0x15), Some(4));
+        assert_eq!(chord_digit(0x5C), Some(9));
+        assert_eq!(chord_digit(0x52), Some(0));
+        // The letter A
+        assert_eq!(chord_digit(0x00), None);
+        // Keypad keys aren't shortcut keys
+        assert_eq!(combo_for_key(0x52, Modifiers::default()), None);
+    }
+}
@@ -0,0 +1,52 @@
+// Regional number and time format (shared locale.rs) on macOS.
+//
+// Read once at launch from the current NSLocale: its decimal separator, and
+// whether its preferred hour format for the "j" skeleton uses a day period
+// ("h a") or not ("HH"), which follows the 24-Hour Time switch in System
+// Settings. A change there shows after a restart.
+
+#[cfg(target_os = "macos")]
+use objc2_foundation::{NSDateFormatter, NSLocale, NSString};
+
+use savemyeyes_shared::locale::{self, Locale};
+
+/// Read the regional format and hand it to the shared formatter.
+pub fn init() {
+    let fixed = Locale::FIXED;
+    let (decimal_separator, hour24) = regional_format(&fixed);
+    let current = Locale {
+        decimal_separator,
+        hour24,
+        rtl: locale::rtl_requested(),
+    };
+    if current != fixed {
+        eprintln!("SaveMyEyes: Locale {:?}", current);
+    }
+    locale::set(current);
+}
+
+/// Decimal separator and 24-hour clock of the current NSLocale, each
+/// falling back to `fixed`
+#[cfg(target_os = "macos")]
+fn regional_format(fixed: &Locale) -> (char, bool) {
+    let current = NSLocale::currentLocale();
+    let decimal_separator = current
+        .decimalSeparator()
+        .to_string()
+        .chars()
+        .next()
+        .unwrap_or(fixed.decimal_separator);
+    let hour_format = NSDateFormatter::dateFormatFromTemplate_options_locale(
+        &NSString::from_str("j"),
+        0,
+        Some(&current),
+    );
+    let hour24 = hour_format.map_or(fixed.hour24, |format| !format.to_string().contains('a'));
+    (decimal_separator, hour24)
+}
+
+/// Headless builds: no NSLocale, so the fixed format
+#[cfg(not(target_os = "macos"))]
+fn regional_format(fixed: &Locale) -> (char, bool) {
+    (fixed.decimal_separator, fixed.hour24)
+}
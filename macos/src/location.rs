@@ -0,0 +1,75 @@
+// Device location for the sunset/sunrise schedule (shared scheduler.rs) on
+// macOS.
+//
+// CoreLocation delivers fixes on the run loop of the thread that made the
+// CLLocationManager, so the manager lives on the main thread. The schedule
+// thread asks for the latest fix through `run_on_main` and waits briefly
+// for the answer. The first ask starts updates, which shows the permission
+// prompt (NSLocationUsageDescription in Info.plist); once a fix is in,
+// updates stop and the manager keeps it. Without permission there is no
+// location, and `schedule.latitude`/`longitude` in config work without it.
+
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use objc2::encode::{Encode, Encoding};
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+
+use crate::app;
+
+/// How long the schedule thread waits for the main thread's answer
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[link(name = "CoreLocation", kind = "framework")]
+extern "C" {}
+
+/// CLLocationCoordinate2D
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Coordinate {
+    latitude: f64,
+    longitude: f64,
+}
+
+unsafe impl Encode for Coordinate {
+    const ENCODING: Encoding =
+        Encoding::Struct("CLLocationCoordinate2D", &[f64::ENCODING, f64::ENCODING]);
+}
+
+thread_local! {
+    // Main-thread only, like every other AppKit object
+    static MANAGER: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
+
+/// Latitude and longitude of the device, if macOS has a fix and will tell.
+/// Schedule thread.
+pub fn locate() -> Option<(f64, f64)> {
+    let (tx, rx) = mpsc::channel();
+    app::run_on_main(move || {
+        let _ = tx.send(latest());
+    });
+    rx.recv_timeout(ANSWER_TIMEOUT).ok().flatten()
+}
+
+/// Main thread: the manager's last fix, starting updates on first use
+fn latest() -> Option<(f64, f64)> {
+    MANAGER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let manager = cell.get_or_insert_with(|| unsafe {
+            let class = AnyClass::get(c"CLLocationManager").unwrap();
+            let manager: Retained<AnyObject> = msg_send![class, new];
+            let _: () = msg_send![&*manager, requestWhenInUseAuthorization];
+            let _: () = msg_send![&*manager, startUpdatingLocation];
+            manager
+        });
+        let location: Option<Retained<AnyObject>> = unsafe { msg_send![&**manager, location] };
+        let coordinate: Coordinate = unsafe { msg_send![&*location?, coordinate] };
+        unsafe {
+            let _: () = msg_send![&**manager, stopUpdatingLocation];
+        }
+        Some((coordinate.latitude, coordinate.longitude))
+    })
+}
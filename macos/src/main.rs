@@ -1,15 +1,123 @@
 // SaveMyEyes — native macOS screen dimmer
 // Uses Cocoa/AppKit via objc2 for a fully native experience.
+//
+// Built for another OS with feature "headless" (CI checking the workspace on
+// one host), the binary is the shared no-op backend's main (shared
+// backend.rs), but the modules that need no Cocoa, or only a call or two
+// that sits behind cfg(target_os = "macos") with a headless stand-in, still
+// build and run their tests. Everything else is macOS-only.
 
+// Headless builds compile modules whose callers are macOS-only
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+#[cfg(all(not(target_os = "macos"), not(feature = "headless")))]
+compile_error!("savemyeyes-macos targets macOS; build it elsewhere with --features headless");
+
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    std::process::exit(savemyeyes_shared::backend::headless_main("macOS"));
+}
+
+#[cfg(target_os = "macos")]
 mod app;
 mod autostart;
+mod background;
+#[cfg(target_os = "macos")]
+mod brightness;
+#[cfg(target_os = "macos")]
 mod config;
+#[cfg(target_os = "macos")]
+mod contrast_guard;
+#[cfg(target_os = "macos")]
+mod events;
+#[cfg(target_os = "macos")]
+mod focus_boost;
+#[cfg(target_os = "macos")]
 mod hotkeys;
+#[cfg(target_os = "macos")]
+mod hud;
+mod key_codes;
+mod locale;
+#[cfg(target_os = "macos")]
+mod location;
+#[cfg(target_os = "macos")]
+mod main_thread;
+#[cfg(target_os = "macos")]
+mod movie_mode;
+#[cfg(target_os = "macos")]
+mod native_host;
+#[cfg(target_os = "macos")]
+mod night_boost;
+#[cfg(target_os = "macos")]
 mod overlay;
+#[cfg(target_os = "macos")]
+mod pen_pause;
+#[cfg(target_os = "macos")]
+mod scheduler;
+#[cfg(target_os = "macos")]
+mod self_test;
+#[cfg(target_os = "macos")]
+mod spaces;
+#[cfg(target_os = "macos")]
+mod system_theme;
+#[cfg(target_os = "macos")]
+mod trace;
+#[cfg(target_os = "macos")]
 mod tray;
+#[cfg(target_os = "macos")]
 mod ui;
+#[cfg(not(target_os = "macos"))]
+mod ui {
+    pub mod theme;
+}
+#[cfg(target_os = "macos")]
 mod updater;
 
+#[cfg(target_os = "macos")]
+use savemyeyes_shared::api_schema;
+#[cfg(target_os = "macos")]
+use savemyeyes_shared::events::{Command, USAGE};
+
+#[cfg(target_os = "macos")]
 fn main() {
+    // Backend smoke test on a real session (CI); never starts the app
+    if let Some(code) = self_test::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // API schema for third-party clients; never starts the app
+    if let Some(code) = api_schema::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // Browser extension bridge; never starts the app
+    if let Some(code) = native_host::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // Command line (--toggle, --opacity 40, ...): hand the command to the
+    // running app. If there is none, start normally and run it once the
+    // app is up. A bad command line exits with the usage instead.
+    match Command::from_args() {
+        Ok(Some(command)) => {
+            if events::forward(&command) {
+                return;
+            }
+            app::run_when_launched(command);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    // Background threads run at utility QoS
+    background::init();
+    // Decimal separator and clock for everything shown from here on
+    locale::init();
+    // Signposts for Instruments, before the first dimming
+    trace::init();
+
     app::run();
 }
@@ -0,0 +1,50 @@
+// Main-thread-only values in statics
+//
+// AppKit objects may only be touched on the main thread, yet the settings
+// window and the tray keep theirs in statics so every action handler can
+// reach them. A MainThreadCell holds such a value. `lock` hands it out on
+// the main thread only, and debug builds assert that on every access, so a
+// background subsystem reaching in fails loudly in testing instead of
+// racing AppKit. Other threads change the value through `update`, which
+// runs the change on the main thread (`app::run_on_main`).
+
+use std::sync::{Mutex, MutexGuard};
+
+use objc2::MainThreadMarker;
+
+use crate::app;
+
+pub struct MainThreadCell<T>(Mutex<T>);
+
+// Safety: the value is only reached through `lock`, on the main thread, and
+// `update` moves nothing but the change itself to the main thread.
+unsafe impl<T> Send for MainThreadCell<T> {}
+unsafe impl<T> Sync for MainThreadCell<T> {}
+
+impl<T> MainThreadCell<T> {
+    pub const fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+
+    /// The value. Main thread only.
+    #[track_caller]
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        debug_assert!(
+            MainThreadMarker::new().is_some(),
+            "main-thread-only value used off the main thread"
+        );
+        self.0.lock().unwrap()
+    }
+}
+
+impl<T: 'static> MainThreadCell<T> {
+    /// Change the value from any thread: right away on the main thread,
+    /// otherwise queued to it
+    pub fn update(&'static self, change: impl FnOnce(&mut T) + Send + 'static) {
+        if MainThreadMarker::new().is_some() {
+            change(&mut self.lock());
+        } else {
+            app::run_on_main(move || change(&mut self.lock()));
+        }
+    }
+}
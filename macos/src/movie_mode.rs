@@ -0,0 +1,84 @@
+// Movie mode: undim the display showing the focused video, dim the rest more.
+//
+// Toggled from the menu bar or Cmd+Shift+M. The display is NSScreen's main
+// screen, the one holding the key window of the app in front. Levels come
+// from shared::movie_mode and are only applied to the gamma tables, never
+// saved; turning it off re-applies the config. Toggling the dimmer or
+// changing a level ends it too.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use objc2::MainThreadMarker;
+use objc2_app_kit::NSScreen;
+use savemyeyes_shared::monitor_id::MonitorId;
+
+use crate::config::AppConfig;
+use crate::overlay;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// True while movie mode levels are applied
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Movie mode is over because something else is applying levels.
+pub fn forget() {
+    ACTIVE.store(false, Ordering::SeqCst);
+}
+
+/// Turn movie mode on or off. Returns the HUD message.
+pub fn toggle(mtm: MainThreadMarker, cfg: &AppConfig) -> String {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        if cfg.is_enabled {
+            overlay::show(mtm, cfg.opacity, cfg.multi_monitor, &cfg.monitor_opacity);
+        } else {
+            overlay::hide();
+        }
+        eprintln!("SaveMyEyes: Movie mode off");
+        return "Movie mode off".to_string();
+    }
+    if cfg.designer_mode {
+        return "Designer mode is on \u{2014} dimming paused".to_string();
+    }
+
+    let names = overlay::screen_names(mtm);
+    let focused = focused_screen(mtm);
+    let levels: HashMap<MonitorId, f32> = overlay::screen_ids(mtm)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            let configured = if !cfg.is_enabled {
+                0.0
+            } else if cfg.multi_monitor {
+                cfg.level_for(&id)
+            } else if idx == 0 {
+                cfg.opacity
+            } else {
+                // Single-monitor mode only dims the primary display
+                0.0
+            };
+            (id, cfg.movie_mode.level(configured, idx as u32 == focused))
+        })
+        .collect();
+    overlay::show(mtm, cfg.opacity, true, &levels);
+    ACTIVE.store(true, Ordering::SeqCst);
+
+    let name = names.get(focused as usize).cloned().unwrap_or_default();
+    eprintln!("SaveMyEyes: Movie mode on, watching on {}", name);
+    format!("Movie mode: {}", name)
+}
+
+/// Index of the screen holding the frontmost app's key window
+fn focused_screen(mtm: MainThreadMarker) -> u32 {
+    let Some(screen) = NSScreen::mainScreen(mtm) else {
+        return 0;
+    };
+    let frame = screen.frame();
+    overlay::screen_index_at_point(
+        mtm,
+        frame.origin.x + frame.size.width / 2.0,
+        frame.origin.y + frame.size.height / 2.0,
+    )
+}
@@ -0,0 +1,47 @@
+// Browser extension bridge on macOS (see shared native_messaging.rs).
+//
+// When the browser starts us as its native messaging host we serve its port
+// and forward commands to the running app (events::forward) instead of
+// starting it. `--install-native-host` writes the manifest into the folder
+// the browser searches for the current user.
+
+use savemyeyes_shared::native_messaging::{self, Browser, Launch};
+
+use crate::events;
+
+/// Serve the browser or install the manifest if the command line asks for
+/// it; returns the exit code. None for a normal launch.
+pub fn run_from_args() -> Option<i32> {
+    Some(match native_messaging::from_args()? {
+        Launch::Host => native_messaging::serve(events::forward),
+        Launch::Install {
+            browser,
+            extension_id,
+        } => install(browser, &extension_id),
+        Launch::Usage => {
+            eprintln!("{}", native_messaging::usage());
+            2
+        }
+    })
+}
+
+fn install(browser: Browser, extension_id: &str) -> i32 {
+    let Some(support) = dirs::data_dir() else {
+        eprintln!("SaveMyEyes: no Application Support folder");
+        return 1;
+    };
+    let dir = match browser {
+        Browser::Chrome => support.join("Google/Chrome/NativeMessagingHosts"),
+        Browser::Firefox => support.join("Mozilla/NativeMessagingHosts"),
+    };
+    match native_messaging::write_manifest(browser, &dir, extension_id) {
+        Ok(path) => {
+            println!("Wrote {}", path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("SaveMyEyes: could not write manifest: {}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Night boost: dim more late at night (see shared night_boost.rs).
+//
+// A poll thread re-evaluates the rule every 30 seconds and hands a changed
+// boost to the gamma backend on the main thread, then refreshes the settings
+// window and status menu so the level reads "45% (+10% night boost)". The
+// thread only runs while the rule is on.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use objc2::MainThreadMarker;
+
+use savemyeyes_shared::night_boost::NightBoost;
+use savemyeyes_shared::shutdown;
+
+use crate::overlay;
+
+/// How often the poll thread re-evaluates the rule
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+static RULE: Mutex<Option<NightBoost>> = Mutex::new(None);
+/// Boost applied at the last check
+static BOOST: Mutex<Option<f32>> = Mutex::new(None);
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// Apply the rule from config.
+pub fn apply(rule: &NightBoost) {
+    *RULE.lock().unwrap() = Some(rule.clone());
+    if rule.enabled && !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("night-boost-poll", poll);
+    }
+    check();
+}
+
+/// Boost added to every level right now, or None
+pub fn current() -> Option<f32> {
+    *BOOST.lock().unwrap()
+}
+
+fn poll() {
+    loop {
+        if !shutdown::sleep(POLL_INTERVAL) {
+            // Quitting: the boost goes away with the gamma
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+        if !RULE.lock().unwrap().as_ref().is_some_and(|r| r.enabled) {
+            break;
+        }
+        check();
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    check();
+}
+
+/// Hand a changed boost to the overlay on the main thread
+fn check() {
+    let boost = RULE.lock().unwrap().as_ref().and_then(|rule| rule.current());
+    let changed = std::mem::replace(&mut *BOOST.lock().unwrap(), boost) != boost;
+    if changed {
+        eprintln!("SaveMyEyes: night boost now {:?}", boost);
+        crate::app::run_on_main(move || {
+            overlay::set_level_boost(boost);
+            crate::ui::update_ui();
+            crate::tray::update_menu(MainThreadMarker::new().unwrap());
+        });
+    }
+}
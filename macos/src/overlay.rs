@@ -2,7 +2,11 @@
 //
 // Strategy:
 //   • Manipulate each display's gamma transfer formula via
-//     CGSetDisplayTransferByFormula to reduce luminance.
+//     CGSetDisplayTransferByFormula to reduce luminance. Displays whose
+//     ICC profile carries a calibration curve ('vcgt', shared
+//     calibration.rs) get that curve scaled down through
+//     CGSetDisplayTransferByTable instead, so dimming keeps the
+//     calibration rather than replacing it with a straight line.
 //   • Works everywhere — including full-screen apps, screen saver,
 //     and login window — because gamma is applied at the GPU output
 //     stage, not via window layering.
@@ -11,15 +15,38 @@
 //
 // Multi-monitor:
 //   Each display is identified by CGDirectDisplayID and mapped to
-//   the user-facing NSScreen.localizedName(). Per-display opacity is
-//   stored in config keyed by display name.
+//   the user-facing NSScreen.localizedName() and to a MonitorId (shared
+//   monitor_id.rs) hashed from its EDID vendor, model and serial number.
+//   Per-display opacity is stored in config keyed by MonitorId, so two
+//   identical models keep separate levels.
+//
+// Toggle fade:
+//   Turning dimming on or off eases every display's gamma maximum between
+//   1.0 and its level (shared fade.rs). A ticker thread hands each step to
+//   the main thread; a newer fade, `show` or `hide` retires it.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{LazyLock, Mutex};
 
 use objc2::MainThreadMarker;
 use objc2_app_kit::NSScreen;
 use objc2_foundation::NSUInteger;
+use savemyeyes_shared::backend::OverlayBackend;
+use savemyeyes_shared::calibration::Calibration;
+use savemyeyes_shared::capabilities::Capabilities;
+use savemyeyes_shared::dimming::OpacityCurve;
+use savemyeyes_shared::displays::DisplayInfo;
+use savemyeyes_shared::explain::MonitorLevel;
+use savemyeyes_shared::fade::{self, Fade, FadeSettings};
+use savemyeyes_shared::focus_boost::{FocusedWindow, OverrideStack};
+use savemyeyes_shared::journal::{self, AppliedState};
+use savemyeyes_shared::monitor_id::{self, MonitorId};
+use savemyeyes_shared::motion;
+use savemyeyes_shared::night_boost;
+use savemyeyes_shared::trace::Mark;
+
+use crate::{app, hud, trace};
 
 // ── Core Graphics FFI ───────────────────────────────────────────────────────
 
@@ -48,8 +75,38 @@ extern "C" {
         blue_gamma: CGGammaValue,
     ) -> CGError;
     fn CGDisplayRestoreColorSyncSettings();
+    fn CGDisplayVendorNumber(display: CGDirectDisplayID) -> u32;
+    fn CGDisplayModelNumber(display: CGDirectDisplayID) -> u32;
+    fn CGDisplaySerialNumber(display: CGDirectDisplayID) -> u32;
+    fn CGGetDisplayTransferByTable(
+        display: CGDirectDisplayID,
+        capacity: u32,
+        red_table: *mut CGGammaValue,
+        green_table: *mut CGGammaValue,
+        blue_table: *mut CGGammaValue,
+        sample_count: *mut u32,
+    ) -> CGError;
+    fn CGSetDisplayTransferByTable(
+        display: CGDirectDisplayID,
+        table_size: u32,
+        red_table: *const CGGammaValue,
+        green_table: *const CGGammaValue,
+        blue_table: *const CGGammaValue,
+    ) -> CGError;
+    fn CGDisplayCopyColorSpace(display: CGDirectDisplayID) -> *const std::ffi::c_void;
+    fn CGColorSpaceCopyICCData(space: *const std::ffi::c_void) -> *const std::ffi::c_void;
 }
 
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFDataGetLength(data: *const std::ffi::c_void) -> isize;
+    fn CFDataGetBytePtr(data: *const std::ffi::c_void) -> *const u8;
+    fn CFRelease(cf: *const std::ffi::c_void);
+}
+
+/// Samples read back per channel by `white_levels`
+const TABLE_CAPACITY: u32 = 256;
+
 // ── State ───────────────────────────────────────────────────────────────────
 
 struct DimState {
@@ -65,8 +122,108 @@ static DIM_STATE: LazyLock<Mutex<DimState>> = LazyLock::new(|| {
     })
 });
 
+/// Designer mode: gamma stays untouched no matter who asks.
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Mapping from the configured level to the gamma reduction applied
+static CURVE: Mutex<OpacityCurve> = Mutex::new(OpacityCurve::Linear);
+
+/// Temporary ceiling on every level (pen pause), never saved
+static LEVEL_CAP: Mutex<Option<f32>> = Mutex::new(None);
+
+/// Added to every dimmed level (night boost), never saved
+static LEVEL_BOOST: Mutex<Option<f32>> = Mutex::new(None);
+
+/// Boost hotkey overrides, dropped when their app loses the focus
+/// (focus_boost.rs), never saved
+static FOCUS_BOOST: Mutex<OverrideStack> = Mutex::new(OverrideStack::new());
+
+/// Displays the brightness rule keeps undimmed, never saved
+static BRIGHTNESS_GATE: Mutex<Vec<CGDirectDisplayID>> = Mutex::new(Vec::new());
+
+/// Ceiling the contrast guard put on one display, never saved
+static CONTRAST_CAP: Mutex<Option<(CGDirectDisplayID, f32)>> = Mutex::new(None);
+
+/// Toggle fade in progress, and the factor it has reached (1 = full level)
+static FADE: Mutex<Option<Fade>> = Mutex::new(None);
+static FADE_FACTOR: Mutex<f32> = Mutex::new(1.0);
+/// Bumped to retire the running fade's ticker thread
+static FADE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Calibration of each display read so far (None = none, formula dimming).
+/// Cleared on display changes, see `forget_calibrations`.
+static CALIBRATIONS: LazyLock<Mutex<HashMap<CGDirectDisplayID, Option<Calibration>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 // ── Public API ──────────────────────────────────────────────────────────────
 
+/// Enter or leave designer mode. Entering restores all displays; while
+/// suppressed, `show` and `set_monitor_opacity` do nothing.
+pub fn set_suppressed(suppressed: bool) {
+    SUPPRESSED.store(suppressed, Ordering::SeqCst);
+    if suppressed {
+        hide();
+    }
+}
+
+/// Select the opacity curve used by subsequent applies.
+pub fn set_curve(curve: OpacityCurve) {
+    *CURVE.lock().unwrap() = curve;
+}
+
+/// Cap every display's level (None lifts it) and re-apply at once.
+pub fn set_level_cap(cap: Option<f32>) {
+    *LEVEL_CAP.lock().unwrap() = cap;
+    reorder_front();
+}
+
+/// Add `boost` to every dimmed level (None removes it) and re-apply at once.
+pub fn set_level_boost(boost: Option<f32>) {
+    *LEVEL_BOOST.lock().unwrap() = boost;
+    reorder_front();
+}
+
+/// Lift every level by `lift` while `window` keeps the focus, on top of the
+/// boosts already on it, and re-apply at once. Returns the whole lift.
+pub fn push_focus_boost(window: FocusedWindow, lift: f32) -> f32 {
+    let lift = FOCUS_BOOST.lock().unwrap().push(window, lift);
+    reorder_front();
+    lift
+}
+
+/// The focus moved to `window`: drop the boosts tied to another one and
+/// re-apply. Returns whether any were dropped.
+pub fn focus_moved(window: Option<&FocusedWindow>) -> bool {
+    let dropped = FOCUS_BOOST.lock().unwrap().focus_changed(window);
+    if dropped {
+        reorder_front();
+    }
+    dropped
+}
+
+/// True while a boost hotkey override is applied
+pub fn focus_boost_active() -> bool {
+    FOCUS_BOOST.lock().unwrap().is_active()
+}
+
+/// Keep `undimmed` displays at full brightness and re-apply at once.
+pub fn set_brightness_gate(undimmed: &[CGDirectDisplayID]) {
+    *BRIGHTNESS_GATE.lock().unwrap() = undimmed.to_vec();
+    reorder_front();
+}
+
+/// Cap one display's level for the contrast guard (None lifts it) and
+/// re-apply at once.
+pub fn set_contrast_cap(cap: Option<(CGDirectDisplayID, f32)>) {
+    *CONTRAST_CAP.lock().unwrap() = cap;
+    reorder_front();
+}
+
+/// True while designer mode suppresses all dimming.
+pub fn is_suppressed() -> bool {
+    SUPPRESSED.load(Ordering::SeqCst)
+}
+
 /// Show (apply) dimming on screens.
 /// When multi_monitor is false, only the primary display is dimmed.
 /// When multi_monitor is true, all displays are dimmed with per-display opacity.
@@ -74,10 +231,65 @@ pub fn show(
     mtm: MainThreadMarker,
     global_opacity: f32,
     multi_monitor: bool,
-    per_display: &HashMap<String, f32>,
+    levels: &HashMap<MonitorId, f32>,
 ) {
+    stop_fade();
+    dim(mtm, global_opacity, multi_monitor, levels);
+}
+
+/// `show`, fading in from undimmed unless fades are off. Turning around a
+/// fade out starts where it got to.
+pub fn fade_in(
+    mtm: MainThreadMarker,
+    global_opacity: f32,
+    multi_monitor: bool,
+    levels: &HashMap<MonitorId, f32>,
+    settings: &FadeSettings,
+) {
+    if !fades(settings) {
+        show(mtm, global_opacity, multi_monitor, levels);
+        return;
+    }
+    let from = match FADE.lock().unwrap().take() {
+        Some(_) => *FADE_FACTOR.lock().unwrap(),
+        None => 0.0,
+    };
+    *FADE_FACTOR.lock().unwrap() = from;
+    dim(mtm, global_opacity, multi_monitor, levels);
+    if is_visible() {
+        start_fade(Fade::new(from, 1.0, settings));
+    } else {
+        stop_fade();
+    }
+}
+
+/// `hide`, fading out first unless fades are off. Turning around a fade in
+/// starts where it got to.
+pub fn fade_out(settings: &FadeSettings) {
+    let from = match FADE.lock().unwrap().take() {
+        Some(_) => *FADE_FACTOR.lock().unwrap(),
+        None => 1.0,
+    };
+    if !fades(settings) || !is_visible() {
+        hide();
+        return;
+    }
+    start_fade(Fade::new(from, 0.0, settings));
+}
+
+fn dim(
+    mtm: MainThreadMarker,
+    global_opacity: f32,
+    multi_monitor: bool,
+    levels: &HashMap<MonitorId, f32>,
+) {
+    if is_suppressed() {
+        return;
+    }
+    let _trace = trace::span(Mark::OverlayCreate);
     let displays = active_displays();
     let names = screen_names(mtm);
+    let ids = screen_ids(mtm);
     let display_ids = display_ids_for_screens(mtm);
     let main_id = unsafe { CGMainDisplayID() };
 
@@ -88,6 +300,7 @@ pub fn show(
         CGDisplayRestoreColorSyncSettings();
     }
     state.applied.clear();
+    let mut applied = AppliedState::new(true, global_opacity);
 
     for &did in displays.iter() {
         // Single-monitor mode: only dim the primary display
@@ -96,14 +309,20 @@ pub fn show(
         }
 
         let opacity = if multi_monitor {
-            // Try to find by display name
-            let name = display_ids
+            let screen = display_ids
                 .iter()
                 .find(|(id, _)| *id == did)
-                .and_then(|(_, idx)| names.get(*idx as usize));
-            name.and_then(|n| per_display.get(n))
-                .copied()
-                .unwrap_or(global_opacity)
+                .map(|(_, idx)| *idx as usize);
+            let saved = screen.and_then(|i| Some((ids.get(i)?, names.get(i)?)));
+            let saved = saved.and_then(|(id, name)| Some((id, name, *levels.get(id)?)));
+            match saved {
+                Some((id, name, opacity)) => {
+                    applied.monitor_opacity.insert(id.clone(), opacity);
+                    applied.per_display_opacity.insert(name.clone(), opacity);
+                    opacity
+                }
+                None => global_opacity,
+            }
         } else {
             global_opacity
         };
@@ -117,10 +336,13 @@ pub fn show(
         "SaveMyEyes: Gamma dimming applied to {} display(s).",
         state.applied.len()
     );
+
+    journal::record(applied);
 }
 
 /// Remove dimming from all displays.
 pub fn hide() {
+    stop_fade();
     let mut state = DIM_STATE.lock().unwrap();
     if state.active {
         unsafe {
@@ -129,6 +351,9 @@ pub fn hide() {
         state.applied.clear();
         state.active = false;
         eprintln!("SaveMyEyes: Gamma restored on all displays.");
+        if !is_suppressed() {
+            journal::record(AppliedState::new(false, 0.0));
+        }
     }
 }
 
@@ -138,27 +363,31 @@ pub fn update_opacity(
     mtm: MainThreadMarker,
     global_opacity: f32,
     multi_monitor: bool,
-    per_display: &HashMap<String, f32>,
+    levels: &HashMap<MonitorId, f32>,
 ) -> bool {
     let state = DIM_STATE.lock().unwrap();
-    if !state.active {
+    // A fade out would take the new level off again when it ends
+    let fading_out = FADE.lock().unwrap().is_some_and(|fade| fade.fading_out());
+    if !state.active || fading_out {
         return false;
     }
-    drop(state); // Release lock before calling show()
+    drop(state); // Release lock before calling dim()
 
-    // Re-apply with new values
-    show(mtm, global_opacity, multi_monitor, per_display);
+    // Re-apply with new values, keeping a fade going
+    dim(mtm, global_opacity, multi_monitor, levels);
     true
 }
 
 /// Set opacity on all dimmed displays (single-monitor shorthand).
-#[allow(dead_code)]
 pub fn set_opacity(opacity: f32) {
-    let state = DIM_STATE.lock().unwrap();
+    let mut state = DIM_STATE.lock().unwrap();
     if !state.active {
         return;
     }
     let applied_clone: Vec<CGDirectDisplayID> = state.applied.keys().copied().collect();
+    for did in &applied_clone {
+        state.applied.insert(*did, opacity);
+    }
     drop(state);
     for did in applied_clone {
         apply_gamma(did, opacity);
@@ -166,10 +395,17 @@ pub fn set_opacity(opacity: f32) {
 }
 
 /// Set opacity on a specific monitor by index.
-#[allow(dead_code)]
 pub fn set_monitor_opacity(monitor_index: u32, opacity: f32) {
+    if is_suppressed() {
+        return;
+    }
     let displays = active_displays();
     if let Some(&did) = displays.get(monitor_index as usize) {
+        let mut state = DIM_STATE.lock().unwrap();
+        if state.active {
+            state.applied.insert(did, opacity);
+        }
+        drop(state);
         apply_gamma(did, opacity);
     }
 }
@@ -179,6 +415,83 @@ pub fn is_visible() -> bool {
     DIM_STATE.lock().unwrap().active
 }
 
+/// The gamma tables behind the shared `OverlayBackend` (backend.rs), every
+/// display dimmed at one level as in multi-monitor mode
+pub struct Backend {
+    pub mtm: MainThreadMarker,
+}
+
+impl OverlayBackend for Backend {
+    fn enumerate(&self) -> Vec<DisplayInfo> {
+        display_infos(self.mtm)
+    }
+
+    fn show(&mut self, level: f32) -> Result<(), String> {
+        show(self.mtm, level, true, &HashMap::new());
+        if is_visible() {
+            Ok(())
+        } else {
+            Err("no display was dimmed".to_string())
+        }
+    }
+
+    fn hide(&mut self) {
+        hide();
+    }
+
+    fn set_opacity(&mut self, level: f32) {
+        set_opacity(level);
+    }
+
+    fn set_monitor_opacity(&mut self, monitor: usize, level: f32) {
+        set_monitor_opacity(monitor as u32, level);
+    }
+
+    /// Level per active display, 0 for those not dimmed
+    fn applied(&self) -> Vec<f32> {
+        let state = DIM_STATE.lock().unwrap();
+        active_displays()
+            .iter()
+            .map(|did| state.applied.get(did).copied().unwrap_or(0.0))
+            .collect()
+    }
+
+    fn is_visible(&self) -> bool {
+        is_visible()
+    }
+}
+
+/// Whether `display` is dimmed right now.
+pub fn is_dimmed(display: CGDirectDisplayID) -> bool {
+    let state = DIM_STATE.lock().unwrap();
+    state.active
+        && state
+            .applied
+            .get(&display)
+            .is_some_and(|&level| level > 0.0)
+}
+
+/// What gamma dimming can do, for the settings window. Captures never see
+/// it, so there is nothing to exclude them from.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        capture_exclusion: false,
+        per_monitor: true,
+        warmth: false,
+        regions: false,
+        ddc: false,
+        gamma: true,
+    }
+}
+
+/// Display of the screen holding the frontmost app's key window
+pub fn focused_display(mtm: MainThreadMarker) -> Option<CGDirectDisplayID> {
+    let screen = NSScreen::mainScreen(mtm)?;
+    let key = objc2_foundation::NSString::from_str("NSScreenNumber");
+    let number = screen.deviceDescription().objectForKey(&key)?;
+    Some(unsafe { objc2::msg_send![&*number, unsignedIntValue] })
+}
+
 /// Re-apply gamma after a Space change or wake from sleep.
 /// macOS can reset gamma tables during Space transitions.
 pub fn reorder_front() {
@@ -189,6 +502,7 @@ pub fn reorder_front() {
     let snapshot: Vec<(CGDirectDisplayID, f32)> =
         state.applied.iter().map(|(&k, &v)| (k, v)).collect();
     drop(state);
+    let _trace = trace::span(Mark::Reassert);
     for (did, opacity) in snapshot {
         apply_gamma(did, opacity);
     }
@@ -198,7 +512,7 @@ pub fn reorder_front() {
 #[allow(dead_code)]
 pub fn screen_index_at_point(mtm: MainThreadMarker, x: f64, y: f64) -> u32 {
     let screens = NSScreen::screens(mtm);
-    let count = screens.count() as usize;
+    let count = screens.count();
     for i in 0..count {
         let screen = screens.objectAtIndex(i as NSUInteger);
         let frame = screen.frame();
@@ -222,7 +536,7 @@ pub fn screen_count(mtm: MainThreadMarker) -> u32 {
 /// Get display names for all connected screens.
 pub fn screen_names(mtm: MainThreadMarker) -> Vec<String> {
     let screens = NSScreen::screens(mtm);
-    let count = screens.count() as usize;
+    let count = screens.count();
     let mut names = Vec::with_capacity(count);
     for i in 0..count {
         let screen = screens.objectAtIndex(i as NSUInteger);
@@ -237,25 +551,245 @@ pub fn screen_names(mtm: MainThreadMarker) -> Vec<String> {
     names
 }
 
+/// MonitorId of every screen, in NSScreen order. Displays that report no
+/// EDID vendor or model (AirPlay, some adapters) fall back to name and
+/// position.
+pub fn screen_ids(mtm: MainThreadMarker) -> Vec<MonitorId> {
+    let screens = NSScreen::screens(mtm);
+    let display_ids = display_ids_for_screens(mtm);
+    let count = screens.count();
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        let screen = screens.objectAtIndex(i as NSUInteger);
+        let did = display_ids
+            .iter()
+            .find(|(_, idx)| *idx as usize == i)
+            .map(|(did, _)| *did);
+        let hardware = did.map(|did| unsafe {
+            (
+                CGDisplayVendorNumber(did),
+                CGDisplayModelNumber(did),
+                CGDisplaySerialNumber(did),
+            )
+        });
+        let id = match hardware {
+            Some((vendor, model, serial)) if vendor != 0 || model != 0 => {
+                let parts = [vendor, model, serial].map(|n| format!("{:x}", n));
+                MonitorId::from_hardware(&[&parts[0], &parts[1], &parts[2]])
+            }
+            _ => {
+                let frame = screen.frame();
+                let name = screen.localizedName().to_string();
+                MonitorId::from_name_position(&name, frame.origin.x as i32, frame.origin.y as i32)
+            }
+        };
+        ids.push(id);
+    }
+    monitor_id::assign(&mut ids);
+    ids
+}
+
+/// Display id and MonitorId of every screen (as in `screen_ids`).
+pub fn identified_displays(mtm: MainThreadMarker) -> Vec<(CGDirectDisplayID, MonitorId)> {
+    let ids = screen_ids(mtm);
+    display_ids_for_screens(mtm)
+        .into_iter()
+        .filter_map(|(did, idx)| Some((did, ids.get(idx as usize)?.clone())))
+        .collect()
+}
+
+/// Describe connected screens for the diagnostics report / layout snapshot.
+/// Coordinates are AppKit screen coordinates (origin at bottom-left of the
+/// primary display); the first screen is the primary one.
+pub fn display_infos(mtm: MainThreadMarker) -> Vec<DisplayInfo> {
+    let screens = NSScreen::screens(mtm);
+    let names = screen_names(mtm);
+    let ids = screen_ids(mtm);
+    let count = screens.count();
+    let mut infos = Vec::with_capacity(count);
+    for i in 0..count {
+        let screen = screens.objectAtIndex(i as NSUInteger);
+        let frame = screen.frame();
+        infos.push(DisplayInfo {
+            id: ids.get(i).cloned().unwrap_or_default(),
+            name: names.get(i).cloned().unwrap_or_default(),
+            x: frame.origin.x as i32,
+            y: frame.origin.y as i32,
+            width: frame.size.width as i32,
+            height: frame.size.height as i32,
+            scale: screen.backingScaleFactor() as f32,
+            primary: i == 0,
+            hdr: false,
+        });
+    }
+    infos
+}
+
+/// Level each dimmed screen has now, before night boost and movie mode
+/// (the "Why is my screen dark?" explainer).
+pub fn monitor_levels(mtm: MainThreadMarker) -> Vec<MonitorLevel> {
+    let names = screen_names(mtm);
+    let state = DIM_STATE.lock().unwrap();
+    let gate = BRIGHTNESS_GATE.lock().unwrap();
+    display_ids_for_screens(mtm)
+        .into_iter()
+        .filter_map(|(did, idx)| {
+            Some(MonitorLevel {
+                name: names.get(idx as usize).cloned().unwrap_or_default(),
+                level: *state.applied.get(&did)?,
+                gated: gate.contains(&did),
+            })
+        })
+        .collect()
+}
+
 /// Refresh dimming (e.g. after screen config changes).
 #[allow(dead_code)]
 pub fn refresh(
     mtm: MainThreadMarker,
     global_opacity: f32,
     multi_monitor: bool,
-    per_display: &HashMap<String, f32>,
+    levels: &HashMap<MonitorId, f32>,
 ) {
     if is_visible() {
-        show(mtm, global_opacity, multi_monitor, per_display);
+        dim(mtm, global_opacity, multi_monitor, levels);
     }
 }
 
+/// Output for full white each active display is expected to show at `level`
+/// (`--self-test`).
+pub fn expected_white(display: CGDirectDisplayID, level: f32) -> f32 {
+    let calibrated = calibration(display).map_or(1.0, |calibration| calibration.white());
+    white_point(level) * calibrated
+}
+
+/// Forget the calibrations read so far (display configuration changed).
+pub fn forget_calibrations() {
+    CALIBRATIONS.lock().unwrap().clear();
+}
+
+/// Number of active displays and how many of them dimming was applied to
+/// (`--self-test`).
+pub fn display_counts() -> (usize, usize) {
+    (active_displays().len(), DIM_STATE.lock().unwrap().applied.len())
+}
+
+/// Output for full white per active display, read back from the live
+/// transfer table (brightest sample of the three channels). None if the
+/// table couldn't be read.
+pub fn white_levels() -> Vec<(u32, Option<f32>)> {
+    active_displays()
+        .into_iter()
+        .map(|did| {
+            let mut red = vec![0.0; TABLE_CAPACITY as usize];
+            let mut green = vec![0.0; TABLE_CAPACITY as usize];
+            let mut blue = vec![0.0; TABLE_CAPACITY as usize];
+            let mut samples = 0u32;
+            let err = unsafe {
+                CGGetDisplayTransferByTable(
+                    did,
+                    TABLE_CAPACITY,
+                    red.as_mut_ptr(),
+                    green.as_mut_ptr(),
+                    blue.as_mut_ptr(),
+                    &mut samples,
+                )
+            };
+            let white = (err == 0 && samples > 0).then(|| {
+                let last = samples as usize - 1;
+                red[last].max(green[last]).max(blue[last])
+            });
+            (did, white)
+        })
+        .collect()
+}
+
 // ── Internal ────────────────────────────────────────────────────────────────
 
+fn fades(settings: &FadeSettings) -> bool {
+    settings.duration_ms > 0 && motion::animate(hud::os_reduces_motion)
+}
+
+/// Step `fade` on the main thread every frame until it ends or is retired
+fn start_fade(fade: Fade) {
+    *FADE.lock().unwrap() = Some(fade);
+    let generation = FADE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        while FADE_GENERATION.load(Ordering::SeqCst) == generation {
+            std::thread::sleep(fade::FRAME);
+            app::run_on_main(move || fade_step(generation));
+        }
+    });
+}
+
+/// End any fade where it is and go back to full levels. Callers apply or
+/// remove dimming right after.
+fn stop_fade() {
+    FADE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    *FADE.lock().unwrap() = None;
+    *FADE_FACTOR.lock().unwrap() = 1.0;
+}
+
+/// Apply the next factor (main thread), and restore gamma once a fade out
+/// is over
+fn fade_step(generation: u64) {
+    if FADE_GENERATION.load(Ordering::SeqCst) != generation {
+        return;
+    }
+    let Some(fade) = *FADE.lock().unwrap() else {
+        return;
+    };
+    let (factor, done) = fade.factor();
+    trace::mark(Mark::FadeFrame);
+    if done && fade.fading_out() {
+        hide();
+        return;
+    }
+    *FADE_FACTOR.lock().unwrap() = factor;
+    if done {
+        stop_fade();
+    }
+    reorder_front();
+}
+
 /// Apply gamma reduction on a single display.
-/// opacity 0.0 = no dimming, 0.9 = 90% dimmed.
+/// opacity 0.0 = no dimming, 0.9 = 90% dimmed (mapped through the
+/// configured curve, after the night boost, the boost hotkey's lift and the
+/// level caps, and eased by the toggle fade). Full brightness while the
+/// brightness rule keeps the display undimmed.
 fn apply_gamma(display: CGDirectDisplayID, opacity: f32) {
-    let max = (1.0 - opacity).clamp(0.05, 1.0); // Never go fully black
+    let _trace = trace::span(Mark::GammaUpdate);
+    let opacity = if BRIGHTNESS_GATE.lock().unwrap().contains(&display) {
+        0.0
+    } else {
+        opacity
+    };
+    let opacity = night_boost::boosted(opacity, *LEVEL_BOOST.lock().unwrap());
+    let opacity = FOCUS_BOOST.lock().unwrap().apply(opacity);
+    let opacity = match *LEVEL_CAP.lock().unwrap() {
+        Some(cap) => opacity.min(cap),
+        None => opacity,
+    };
+    let opacity = match *CONTRAST_CAP.lock().unwrap() {
+        Some((capped, cap)) if capped == display => opacity.min(cap),
+        _ => opacity,
+    };
+    // The toggle fade eases the maximum itself between 1.0 and the level's
+    let fade = *FADE_FACTOR.lock().unwrap();
+    let max = 1.0 - (1.0 - white_point(opacity)) * fade;
+    if let Some(calibration) = calibration(display) {
+        let [red, green, blue] = calibration.scaled(max, TABLE_CAPACITY as usize);
+        unsafe {
+            CGSetDisplayTransferByTable(
+                display,
+                TABLE_CAPACITY,
+                red.as_ptr(),
+                green.as_ptr(),
+                blue.as_ptr(),
+            );
+        }
+        return;
+    }
     unsafe {
         CGSetDisplayTransferByFormula(
             display,
@@ -266,6 +800,47 @@ fn apply_gamma(display: CGDirectDisplayID, opacity: f32) {
     }
 }
 
+/// Calibration curve from the display's ICC profile, read on first use
+fn calibration(display: CGDirectDisplayID) -> Option<Calibration> {
+    let mut calibrations = CALIBRATIONS.lock().unwrap();
+    calibrations
+        .entry(display)
+        .or_insert_with(|| {
+            let calibration = icc_profile(display).and_then(|icc| Calibration::from_icc(&icc));
+            if calibration.is_some() {
+                eprintln!("SaveMyEyes: Display {} has a calibration curve", display);
+            }
+            calibration
+        })
+        .clone()
+}
+
+/// Bytes of the ICC profile of the display's color space
+fn icc_profile(display: CGDirectDisplayID) -> Option<Vec<u8>> {
+    unsafe {
+        let space = CGDisplayCopyColorSpace(display);
+        if space.is_null() {
+            return None;
+        }
+        let data = CGColorSpaceCopyICCData(space);
+        CFRelease(space);
+        if data.is_null() {
+            return None;
+        }
+        let len = CFDataGetLength(data).max(0) as usize;
+        let bytes = CFDataGetBytePtr(data);
+        let profile = (!bytes.is_null()).then(|| std::slice::from_raw_parts(bytes, len).to_vec());
+        CFRelease(data);
+        profile
+    }
+}
+
+/// Transfer-formula maximum (output for full white) for a level
+fn white_point(opacity: f32) -> f32 {
+    let applied = CURVE.lock().unwrap().to_applied(opacity);
+    (1.0 - applied).clamp(0.05, 1.0) // Never go fully black
+}
+
 /// List all active (online) CGDirectDisplayIDs.
 fn active_displays() -> Vec<CGDirectDisplayID> {
     let mut count: u32 = 0;
@@ -286,7 +861,7 @@ fn active_displays() -> Vec<CGDirectDisplayID> {
 /// Map NSScreen index → CGDirectDisplayID via deviceDescription["NSScreenNumber"].
 fn display_ids_for_screens(mtm: MainThreadMarker) -> Vec<(CGDirectDisplayID, u32)> {
     let screens = NSScreen::screens(mtm);
-    let count = screens.count() as usize;
+    let count = screens.count();
     let mut result = Vec::with_capacity(count);
     for i in 0..count {
         let screen = screens.objectAtIndex(i as NSUInteger);
@@ -0,0 +1,119 @@
+// Pause dimming while a pen is drawing (see shared pen_pause.rs).
+//
+// A global NSEvent monitor timestamps tablet input: pen strokes arrive as
+// mouse events with the tablet-point subtype (or as tablet-point events from
+// some drivers), and only pressure > 0 counts, so a hovering pen doesn't lift
+// dimming. A poll thread turns the rule into a gamma level cap on the main
+// thread. The monitor is only installed while the rule is on.
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::{NSEvent, NSEventMask, NSEventSubtype, NSEventType};
+
+use std::cell::RefCell;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use savemyeyes_shared::pen_pause::PenPause;
+use savemyeyes_shared::shutdown;
+
+use crate::overlay;
+
+/// How often the poll thread re-evaluates the rule
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+static RULE: Mutex<Option<PenPause>> = Mutex::new(None);
+static LAST_PEN_INPUT: Mutex<Option<Instant>> = Mutex::new(None);
+/// Cap applied at the last poll
+static CAP: Mutex<Option<f32>> = Mutex::new(None);
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // NSEvent monitor token (main thread only)
+    static MONITOR: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
+
+fn is_pen_stroke(event: &NSEvent) -> bool {
+    let kind = event.r#type();
+    let tablet = if kind == NSEventType::TabletPoint {
+        true
+    } else {
+        // subtype is only defined for mouse events
+        (kind == NSEventType::LeftMouseDown || kind == NSEventType::LeftMouseDragged)
+            && event.subtype() == NSEventSubtype::TabletPoint
+    };
+    tablet && event.pressure() > 0.0
+}
+
+/// Apply the rule from config. Main thread only.
+pub fn apply(rule: &PenPause) {
+    // Hotkey-less mode also rules out this global monitor
+    let mut rule = rule.clone();
+    rule.enabled &= !crate::hotkeys::global_input_disabled();
+    let rule = &rule;
+    *RULE.lock().unwrap() = Some(rule.clone());
+
+    MONITOR.with(|monitor| {
+        let mut monitor = monitor.borrow_mut();
+        if rule.enabled && monitor.is_none() {
+            let handler = block2::RcBlock::new(|event: NonNull<NSEvent>| {
+                let event: &NSEvent = unsafe { event.as_ref() };
+                if is_pen_stroke(event) {
+                    *LAST_PEN_INPUT.lock().unwrap() = Some(Instant::now());
+                }
+            });
+            *monitor = NSEvent::addGlobalMonitorForEventsMatchingMask_handler(
+                NSEventMask::LeftMouseDown | NSEventMask::LeftMouseDragged | NSEventMask::TabletPoint,
+                &handler,
+            );
+            if monitor.is_none() {
+                eprintln!("SaveMyEyes: Failed to install tablet event monitor.");
+            }
+        } else if !rule.enabled {
+            if let Some(token) = monitor.take() {
+                unsafe { NSEvent::removeMonitor(&token) };
+            }
+            *LAST_PEN_INPUT.lock().unwrap() = None;
+        }
+    });
+
+    if rule.enabled && !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("pen-pause-poll", poll);
+    }
+    check();
+}
+
+fn poll() {
+    loop {
+        if !shutdown::sleep(POLL_INTERVAL) {
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+        if !RULE.lock().unwrap().as_ref().is_some_and(|r| r.enabled) {
+            break;
+        }
+        check();
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    check();
+}
+
+/// Hand a changed cap to the overlay on the main thread
+fn check() {
+    let cap = RULE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|rule| rule.cap(*LAST_PEN_INPUT.lock().unwrap(), Instant::now()));
+    let changed = std::mem::replace(&mut *CAP.lock().unwrap(), cap) != cap;
+    if changed {
+        crate::app::run_on_main(move || overlay::set_level_cap(cap));
+    }
+}
+
+/// True while the tablet event monitor is installed (diagnostics)
+pub fn is_installed() -> bool {
+    MONITOR.with(|m| m.borrow().is_some())
+}
@@ -0,0 +1,56 @@
+// Scheduled dimming (shared scheduler.rs) on macOS.
+//
+// The schedule thread's changes go through the external command path
+// (`app::dispatch_command`) on the main thread, so they turn dimming on and
+// off exactly like the toggle hotkey and a level command would. Following
+// the sun, the OS location comes from location.rs, and each fade step is a
+// level command.
+
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::Cause;
+use savemyeyes_shared::scheduler::{self, DimmingSchedule};
+
+use crate::{app, config, location};
+
+/// Hand schedule changes to the main thread and apply the rule from config.
+pub fn start(rule: &DimmingSchedule) {
+    scheduler::set_handler(changed);
+    scheduler::set_locator(location::locate);
+    scheduler::apply(rule);
+}
+
+/// Schedule thread: decide on the main thread, where the config lives
+fn changed(level: Option<f32>) {
+    app::run_on_main(move || {
+        let (designer_mode, multi_monitor) = {
+            let st = app::state();
+            let s = st.lock().unwrap();
+            (s.config.designer_mode, s.config.multi_monitor)
+        };
+        if designer_mode {
+            return;
+        }
+        let command = match level {
+            // Per-display levels stay as they are
+            Some(_) if multi_monitor => Command::SetEnabled { enabled: true },
+            Some(level) => Command::SetLevel { level },
+            None => Command::SetEnabled { enabled: false },
+        };
+        app::dispatch_command(command, Cause::Schedule);
+        if level.is_none() {
+            // Queued behind the command
+            app::run_on_main(restore_level);
+        }
+    });
+}
+
+/// A fade to off leaves its last step as the level to turn back on at;
+/// make it the scheduled level again
+fn restore_level() {
+    let st = app::state();
+    let mut s = st.lock().unwrap();
+    if s.config.schedule.follow_sun && !s.config.multi_monitor {
+        s.config.last_opacity = s.config.schedule.level;
+        config::save_config(&s.config);
+    }
+}
@@ -0,0 +1,112 @@
+// `--self-test`: drive the real gamma backend and check the result.
+//
+// Runs in place of the app (no status item, settings or hotkeys) and touches
+// neither config.json nor the crash journal. The script:
+//   0. the shared script        → show, adjust, adjust one display and hide
+//                                 through overlay::Backend (shared backend.rs)
+//   1. dim every display to 30% → each active display got a ramp, and its
+//                                 live transfer table reads back dimmed
+//   2. adjust to 60%            → every table follows
+//   3. toggle off               → dimming inactive, tables back to what they
+//                                 were before the test
+// Restoring resets ColorSync on all displays, so don't run it while the app
+// itself is dimming the screen.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use objc2::MainThreadMarker;
+use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy};
+
+use savemyeyes_shared::backend;
+use savemyeyes_shared::journal;
+use savemyeyes_shared::self_test::{self, Report, FIRST_LEVEL, SECOND_LEVEL};
+
+use crate::overlay;
+
+/// Time given to the display pipeline after each step
+const SETTLE_MS: u64 = 200;
+/// Readback differs slightly from the formula (table sampling)
+const WHITE_TOLERANCE: f32 = 0.02;
+
+/// Run the self-test if `--self-test` was passed. Returns the process exit
+/// code, or None to start the app normally.
+pub fn run_from_args() -> Option<i32> {
+    self_test::requested().then(run)
+}
+
+fn run() -> i32 {
+    let mtm = MainThreadMarker::new().expect("SaveMyEyes must be run on the main thread");
+    // Window-server connection for NSScreen, without a Dock icon
+    let app = NSApplication::sharedApplication(mtm);
+    app.setActivationPolicy(NSApplicationActivationPolicy::Prohibited);
+    journal::disable();
+    let mut report = Report::new();
+
+    let (displays, _) = overlay::display_counts();
+    if !report.check("displays detected", displays > 0, format!("{} display(s)", displays)) {
+        return report.finish();
+    }
+    let originals = overlay::white_levels();
+
+    // 0. Shared script
+    backend::self_test(&mut overlay::Backend { mtm }, &mut report);
+    settle();
+
+    // 1. Show
+    overlay::show(mtm, FIRST_LEVEL, true, &HashMap::new());
+    settle();
+    let (displays, dimmed) = overlay::display_counts();
+    report.check(
+        "show: gamma applied to every display",
+        dimmed == displays,
+        format!("{} of {} display(s)", dimmed, displays),
+    );
+    check_white(&mut report, "show", FIRST_LEVEL);
+
+    // 2. Adjust
+    let updated = overlay::update_opacity(mtm, SECOND_LEVEL, true, &HashMap::new());
+    settle();
+    report.check("adjust: dimming still active", updated, "update_opacity");
+    check_white(&mut report, "adjust", SECOND_LEVEL);
+
+    // 3. Toggle off
+    overlay::hide();
+    settle();
+    report.check("off: dimming inactive", !overlay::is_visible(), "hide");
+    let now = overlay::white_levels();
+    for (display, before) in originals {
+        let name = format!("off: display {} restored", display);
+        let after = now.iter().find(|(id, _)| *id == display).and_then(|(_, w)| *w);
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                report.check(
+                    &name,
+                    (before - after).abs() <= WHITE_TOLERANCE,
+                    format!("white {:.3}, before test {:.3}", after, before),
+                );
+            }
+            _ => report.skip(&name, "transfer table unreadable"),
+        }
+    }
+
+    report.finish()
+}
+
+/// Every display's live white point matches `level`.
+fn check_white(report: &mut Report, step: &str, level: f32) {
+    for (display, white) in overlay::white_levels() {
+        let expected = overlay::expected_white(display, level);
+        let name = format!("{}: display {}", step, display);
+        let ok = white.is_some_and(|w| (w - expected).abs() <= WHITE_TOLERANCE);
+        report.check(
+            &name,
+            ok,
+            format!("white {:?}, expected {:.3}", white, expected),
+        );
+    }
+}
+
+fn settle() {
+    std::thread::sleep(Duration::from_millis(SETTLE_MS));
+}
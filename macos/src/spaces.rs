@@ -0,0 +1,189 @@
+// Level per Space (see shared space_levels.rs).
+//
+// There is no public API that names Spaces. The CGS functions behind Mission
+// Control (CGSMainConnectionID, CGSGetActiveSpace and
+// CGSCopyManagedDisplaySpaces) are looked up at runtime like DisplayServices
+// in brightness.rs, so if a macOS release drops them the levels simply stay
+// the same on every Space. The managed display spaces list gives each Space
+// its UUID and its position on its display: the UUID is the key, the
+// position the fallback. app.rs calls `switched` from the active-Space
+// notification and applies the levels it puts into config.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::sync::{Mutex, OnceLock};
+
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_foundation::NSString;
+
+use savemyeyes_shared::explain::{self, Cause};
+use savemyeyes_shared::space_levels::{self, SpaceLevel};
+
+use crate::config;
+
+const CORE_GRAPHICS: &CStr = c"/System/Library/Frameworks/CoreGraphics.framework/CoreGraphics";
+const RTLD_LAZY: i32 = 0x1;
+
+extern "C" {
+    fn dlopen(path: *const c_char, mode: i32) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+/// CGSMainConnectionID() -> connection
+type MainConnectionId = unsafe extern "C" fn() -> i32;
+/// CGSGetActiveSpace(connection) -> space id
+type GetActiveSpace = unsafe extern "C" fn(i32) -> u64;
+/// CGSCopyManagedDisplaySpaces(connection) -> CFArray of display dictionaries
+type CopyManagedDisplaySpaces = unsafe extern "C" fn(i32) -> *mut AnyObject;
+
+struct Cgs {
+    main_connection_id: MainConnectionId,
+    get_active_space: GetActiveSpace,
+    copy_managed_display_spaces: CopyManagedDisplaySpaces,
+}
+
+/// Key of the Space whose levels are on screen
+static CURRENT: Mutex<Option<String>> = Mutex::new(None);
+
+fn cgs() -> Option<&'static Cgs> {
+    static FUNCTIONS: OnceLock<Option<Cgs>> = OnceLock::new();
+    FUNCTIONS
+        .get_or_init(|| unsafe {
+            let handle = dlopen(CORE_GRAPHICS.as_ptr(), RTLD_LAZY);
+            let lookup = |name: &CStr| {
+                let symbol = dlsym(handle, name.as_ptr());
+                (!symbol.is_null()).then_some(symbol)
+            };
+            let found = (!handle.is_null()).then(|| {
+                (
+                    lookup(c"CGSMainConnectionID"),
+                    lookup(c"CGSGetActiveSpace"),
+                    lookup(c"CGSCopyManagedDisplaySpaces"),
+                )
+            });
+            let Some((Some(connection), Some(active), Some(spaces))) = found else {
+                eprintln!("SaveMyEyes: Space functions unavailable, one level for all Spaces.");
+                return None;
+            };
+            Some(Cgs {
+                main_connection_id: std::mem::transmute::<*mut c_void, MainConnectionId>(
+                    connection,
+                ),
+                get_active_space: std::mem::transmute::<*mut c_void, GetActiveSpace>(active),
+                copy_managed_display_spaces: std::mem::transmute::<
+                    *mut c_void,
+                    CopyManagedDisplaySpaces,
+                >(spaces),
+            })
+        })
+        .as_ref()
+}
+
+/// Config key of the active Space, None if it can't be identified
+fn active_key() -> Option<String> {
+    let cgs = cgs()?;
+    unsafe {
+        let connection = (cgs.main_connection_id)();
+        let active = (cgs.get_active_space)(connection);
+        let displays: Retained<AnyObject> =
+            Retained::from_raw((cgs.copy_managed_display_spaces)(connection))?;
+        let spaces_key = NSString::from_str("Spaces");
+        let id_key = NSString::from_str("ManagedSpaceID");
+        let uuid_key = NSString::from_str("uuid");
+        let display_count: usize = msg_send![&*displays, count];
+        for d in 0..display_count {
+            let display: *mut AnyObject = msg_send![&*displays, objectAtIndex: d];
+            let spaces: *mut AnyObject = msg_send![display, objectForKey: &*spaces_key];
+            if spaces.is_null() {
+                continue;
+            }
+            let space_count: usize = msg_send![spaces, count];
+            for i in 0..space_count {
+                let space: *mut AnyObject = msg_send![spaces, objectAtIndex: i];
+                let id: *mut AnyObject = msg_send![space, objectForKey: &*id_key];
+                if id.is_null() {
+                    continue;
+                }
+                let id: u64 = msg_send![id, unsignedLongLongValue];
+                if id != active {
+                    continue;
+                }
+                let uuid: *mut AnyObject = msg_send![space, objectForKey: &*uuid_key];
+                let uuid = (!uuid.is_null()).then(|| (*(uuid as *const NSString)).to_string());
+                return Some(space_levels::key(uuid.as_deref(), i + 1));
+            }
+        }
+        None
+    }
+}
+
+/// Levels on screen now
+fn current_levels(config: &config::AppConfig) -> SpaceLevel {
+    SpaceLevel {
+        opacity: config.opacity,
+        monitor_opacity: config.monitor_opacity.clone(),
+    }
+}
+
+/// Put stored levels into config
+fn use_levels(config: &mut config::AppConfig, levels: SpaceLevel) {
+    explain::note(Cause::Space);
+    config.opacity = levels.opacity;
+    config.monitor_opacity = levels.monitor_opacity;
+}
+
+/// Note the Space active at launch and load its levels into config.
+/// Returns true if config changed.
+pub fn start(config: &mut config::AppConfig) -> bool {
+    let key = active_key();
+    *CURRENT.lock().unwrap() = key.clone();
+    let stored = key.and_then(|key| config.space_levels.levels.get(&key).cloned());
+    match stored {
+        Some(levels) if config.space_levels.enabled && levels != current_levels(config) => {
+            use_levels(config, levels);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The active Space changed: remember the levels in use for the one left
+/// and load the ones stored for the new one into config (saved). Returns
+/// true if the levels changed and need to be applied.
+pub fn switched(config: &mut config::AppConfig) -> bool {
+    let Some(to) = active_key() else {
+        return false;
+    };
+    let from = CURRENT.lock().unwrap().replace(to.clone());
+    if !config.space_levels.enabled || from.as_deref() == Some(to.as_str()) {
+        return false;
+    }
+    let current = current_levels(config);
+    let stored = config
+        .space_levels
+        .switch(from.as_deref(), &to, current.clone());
+    let changed = stored.as_ref().is_some_and(|levels| *levels != current);
+    if let Some(levels) = stored {
+        use_levels(config, levels);
+    }
+    config::save_config(config);
+    eprintln!(
+        "SaveMyEyes: Space {} active, levels {}",
+        to,
+        if changed { "restored" } else { "kept" }
+    );
+    changed
+}
+
+/// Remember the levels in use for the active Space (before quitting).
+pub fn remember(config: &mut config::AppConfig) {
+    let Some(key) = CURRENT.lock().unwrap().clone() else {
+        return;
+    };
+    if config.space_levels.enabled {
+        let current = current_levels(config);
+        config.space_levels.levels.insert(key, current);
+        config::save_config(config);
+    }
+}
@@ -0,0 +1,88 @@
+// OS light/dark mode watcher for the theme automation.
+//
+// Observes NSApp.effectiveAppearance with key-value observing. AppKit updates
+// it on the main thread whenever the user (or the Auto appearance setting)
+// switches between Light and Dark, so the callback can apply the mapped
+// profile directly via app::apply_theme_profile.
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::{define_class, msg_send, AllocAnyThread, MainThreadMarker};
+use objc2_app_kit::NSApplication;
+use objc2_foundation::{NSObject, NSObjectProtocol, NSString};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+const KEY_PATH: &str = "effectiveAppearance";
+/// NSKeyValueObservingOptionNew
+const OPTION_NEW: usize = 0x01;
+
+static OBSERVER: Mutex<Option<Retained<ThemeObserver>>> = Mutex::new(None);
+/// Last theme seen, to ignore appearance changes that keep the same mode
+static LAST_DARK: AtomicBool = AtomicBool::new(false);
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[name = "ThemeObserver"]
+    #[thread_kind = AllocAnyThread]
+    struct ThemeObserver;
+
+    unsafe impl NSObjectProtocol for ThemeObserver {}
+
+    impl ThemeObserver {
+        #[unsafe(method(observeValueForKeyPath:ofObject:change:context:))]
+        fn observe_value(
+            &self,
+            _key_path: *mut NSString,
+            _object: *mut AnyObject,
+            _change: *mut AnyObject,
+            _context: *mut std::ffi::c_void,
+        ) {
+            let Some(mtm) = MainThreadMarker::new() else {
+                return;
+            };
+            let dark = is_dark(mtm);
+            if LAST_DARK.swap(dark, Ordering::SeqCst) != dark {
+                eprintln!("SaveMyEyes: System theme is now {}", if dark { "dark" } else { "light" });
+                crate::app::apply_theme_profile(dark);
+            }
+        }
+    }
+);
+
+impl ThemeObserver {
+    fn new() -> Retained<Self> {
+        let alloc = Self::alloc();
+        unsafe { msg_send![alloc, init] }
+    }
+}
+
+/// True if the system appearance is a dark variant
+pub fn is_dark(mtm: MainThreadMarker) -> bool {
+    let app = NSApplication::sharedApplication(mtm);
+    app.effectiveAppearance().name().to_string().contains("Dark")
+}
+
+/// Start observing appearance changes for the rest of the session.
+pub fn watch(mtm: MainThreadMarker) {
+    let mut guard = OBSERVER.lock().unwrap();
+    if guard.is_some() {
+        return;
+    }
+    LAST_DARK.store(is_dark(mtm), Ordering::SeqCst);
+
+    let observer = ThemeObserver::new();
+    let app = NSApplication::sharedApplication(mtm);
+    let key = NSString::from_str(KEY_PATH);
+    unsafe {
+        let _: () = msg_send![
+            &*app,
+            addObserver: &*observer,
+            forKeyPath: &*key,
+            options: OPTION_NEW,
+            context: std::ptr::null_mut::<std::ffi::c_void>()
+        ];
+    }
+    *guard = Some(observer);
+}
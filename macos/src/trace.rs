@@ -0,0 +1,114 @@
+// Trace marks (shared trace.rs) as os_signposts on macOS.
+//
+// Signposts go to the "com.kdspl.savemyeyes" subsystem, category "Dimming",
+// and show in Instruments' os_signpost track. Timed work is an interval
+// (begin when the Span is made, end when it is dropped); single marks are
+// events. They carry only the mark's name: a signpost's arguments have to
+// be a compile-time format string, which the os_signpost macros build and
+// Rust can't. The HUD fade is one Core Animation rather than frames we
+// draw, so FadeFrame marks its start. When no Instruments session is
+// recording, a mark costs one os_signpost_enabled call.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::sync::OnceLock;
+
+use savemyeyes_shared::trace::Mark;
+
+const SUBSYSTEM: &CStr = c"com.kdspl.savemyeyes";
+const CATEGORY: &CStr = c"Dimming";
+
+// os_signpost_type_t
+const SIGNPOST_EVENT: u8 = 0;
+const SIGNPOST_INTERVAL_BEGIN: u8 = 1;
+const SIGNPOST_INTERVAL_END: u8 = 2;
+
+extern "C" {
+    /// Our image's Mach-O header; signposts record their strings relative to it
+    static __dso_handle: u8;
+    fn os_log_create(subsystem: *const c_char, category: *const c_char) -> *mut c_void;
+    fn os_signpost_enabled(log: *mut c_void) -> bool;
+    fn os_signpost_id_generate(log: *mut c_void) -> u64;
+    fn _os_signpost_emit_with_name_impl(
+        dso: *const c_void,
+        log: *mut c_void,
+        kind: u8,
+        id: u64,
+        name: *const c_char,
+        format: *const c_char,
+        buf: *mut u8,
+        size: u32,
+    );
+}
+
+/// The os_log_t, created on first use and kept for the process lifetime
+struct Log(*mut c_void);
+unsafe impl Send for Log {}
+unsafe impl Sync for Log {}
+
+static LOG: OnceLock<Log> = OnceLock::new();
+
+/// Create the log handle (startup), so the first mark doesn't pay for it.
+pub fn init() {
+    log();
+}
+
+fn log() -> *mut c_void {
+    LOG.get_or_init(|| Log(unsafe { os_log_create(SUBSYSTEM.as_ptr(), CATEGORY.as_ptr()) }))
+        .0
+}
+
+/// The log, when Instruments is recording it
+fn enabled() -> Option<*mut c_void> {
+    let log = log();
+    (!log.is_null() && unsafe { os_signpost_enabled(log) }).then_some(log)
+}
+
+fn emit(log: *mut c_void, kind: u8, id: u64, mark: Mark) {
+    // An empty argument list: summary byte, then the argument count
+    let mut buf = [0u8; 2];
+    unsafe {
+        _os_signpost_emit_with_name_impl(
+            &__dso_handle as *const u8 as *const c_void,
+            log,
+            kind,
+            id,
+            mark.name().as_ptr(),
+            c"".as_ptr(),
+            buf.as_mut_ptr(),
+            buf.len() as u32,
+        );
+    }
+}
+
+/// Emit a single mark.
+pub fn mark(mark: Mark) {
+    if let Some(log) = enabled() {
+        let id = unsafe { os_signpost_id_generate(log) };
+        emit(log, SIGNPOST_EVENT, id, mark);
+    }
+}
+
+/// Begin an interval, which ends when the returned guard is dropped.
+pub fn span(mark: Mark) -> Span {
+    let open = enabled().map(|log| {
+        let id = unsafe { os_signpost_id_generate(log) };
+        emit(log, SIGNPOST_INTERVAL_BEGIN, id, mark);
+        id
+    });
+    Span { mark, open }
+}
+
+/// An interval signpost, ended on drop
+pub struct Span {
+    mark: Mark,
+    /// Signpost id, when Instruments was recording at the start
+    open: Option<u64>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(id) = self.open.take() {
+            emit(log(), SIGNPOST_INTERVAL_END, id, self.mark);
+        }
+    }
+}
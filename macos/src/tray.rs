@@ -1,36 +1,46 @@
 // macOS system tray (menu bar status item) using NSStatusBar.
 //
-// Creates an NSStatusItem with a menu containing:
-//   • Opacity percentage display
-//   • Toggle Dimmer (Cmd+Shift+D)
+// Creates an NSStatusItem with a menu containing the opacity percentage
+// display, then the entries listed in config `tray_menu` (shared
+// tray_menu.rs). By default:
+//   • Toggle Dimmer (Cmd+Shift+D by default)
+//   • Movie Mode (Cmd+Shift+M, checkmark while on)
+//   • Designer Mode (checkmark; the status icon switches to eye.slash)
+//   • Why is my screen dark? (shared explain.rs)
 //   • Settings (Cmd+,) — opens preferences window
 //   • Check for Updates
 //   • Quit (Cmd+Q)
+// Optional: Dim Level and Profiles submenus, Pause Dimming for a while,
+// Presentation Mode.
 //
 // Menu actions are dispatched via a custom TrayTarget that implements
 // Objective-C selectors using define_class!.
 
 use objc2::rc::Retained;
-use objc2::runtime::AnyObject;
+use objc2::runtime::{AnyObject, Sel};
 use objc2::{define_class, msg_send, sel, AllocAnyThread, MainThreadMarker, MainThreadOnly};
 use objc2_app_kit::{
-    NSApplication, NSImage, NSMenu, NSMenuItem, NSStatusBar, NSStatusItem,
-    NSVariableStatusItemLength,
+    NSApplication, NSControlStateValueOff, NSControlStateValueOn, NSImage, NSMenu, NSMenuItem,
+    NSStatusBar, NSStatusItem, NSVariableStatusItemLength,
 };
 use objc2_foundation::{NSObject, NSObjectProtocol, NSString};
 
 use std::sync::Mutex;
 
-// Safety: All tray state is accessed exclusively on the main thread.
-struct Mt<T>(T);
-unsafe impl<T> Send for Mt<T> {}
-unsafe impl<T> Sync for Mt<T> {}
-impl<T> std::ops::Deref for Mt<T> {
-    type Target = T;
-    fn deref(&self) -> &T { &self.0 }
-}
+use savemyeyes_shared::builtin_profiles;
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::Cause;
+use savemyeyes_shared::hotkeys::{self, KeyCombo};
+use savemyeyes_shared::pause;
+use savemyeyes_shared::presentation;
+use savemyeyes_shared::profiles;
+use savemyeyes_shared::shutdown;
+use savemyeyes_shared::tray_menu::{self, TrayItem};
+
+use crate::main_thread::MainThreadCell;
 
-static STATUS_ITEM: Mutex<Option<Mt<Retained<NSStatusItem>>>> = Mutex::new(None);
+// The status item is main-thread only (main_thread.rs)
+static STATUS_ITEM: MainThreadCell<Option<Retained<NSStatusItem>>> = MainThreadCell::new(None);
 static TRAY_TARGET: Mutex<Option<Retained<TrayTarget>>> = Mutex::new(None);
 
 // ── TrayTarget — receives menu actions ──────────────────────────────────────
@@ -47,7 +57,70 @@ define_class!(
         #[unsafe(method(toggleDimmer:))]
         fn toggle_dimmer(&self, _sender: *mut NSObject) {
             eprintln!("SaveMyEyes: toggleDimmer called");
-            crate::app::dispatch_hotkey(crate::hotkeys::HotkeyAction::Toggle);
+            crate::app::dispatch_tray(crate::hotkeys::HotkeyAction::Toggle);
+        }
+
+        #[unsafe(method(toggleMovieMode:))]
+        fn toggle_movie_mode(&self, _sender: *mut NSObject) {
+            crate::app::dispatch_tray(crate::hotkeys::HotkeyAction::MovieMode);
+        }
+
+        #[unsafe(method(toggleDesignerMode:))]
+        fn toggle_designer_mode(&self, _sender: *mut NSObject) {
+            let on = !crate::app::state().lock().unwrap().config.designer_mode;
+            crate::app::set_designer_mode(on);
+        }
+
+        #[unsafe(method(applyPreset:))]
+        fn apply_preset(&self, sender: &NSMenuItem) {
+            if let Some(level) = tray_menu::PRESETS.get(sender.tag() as usize) {
+                crate::app::dispatch_command(Command::SetLevel { level: *level }, Cause::Tray);
+            }
+        }
+
+        #[unsafe(method(applyProfile:))]
+        fn apply_profile(&self, sender: &NSMenuItem) {
+            crate::app::apply_profile_at(sender.tag() as usize);
+        }
+
+        #[unsafe(method(duplicateProfile:))]
+        fn duplicate_profile(&self, sender: &NSMenuItem) {
+            crate::app::duplicate_builtin(sender.tag() as usize);
+        }
+
+        #[unsafe(method(useThemeExample:))]
+        fn use_theme_example(&self, sender: &NSMenuItem) {
+            crate::app::use_theme_example(sender.tag() as usize);
+        }
+
+        #[unsafe(method(pauseDimming:))]
+        fn pause_dimming(&self, sender: &NSMenuItem) {
+            if let Some(minutes) = pause::DURATIONS.get(sender.tag() as usize) {
+                crate::app::start_pause(*minutes);
+            }
+        }
+
+        #[unsafe(method(resumeDimming:))]
+        fn resume_dimming(&self, _sender: *mut NSObject) {
+            crate::app::end_pause();
+        }
+
+        #[unsafe(method(startPresentation:))]
+        fn start_presentation(&self, sender: &NSMenuItem) {
+            if let Some(minutes) = presentation::DURATIONS.get(sender.tag() as usize) {
+                crate::app::start_presentation(*minutes);
+            }
+        }
+
+        #[unsafe(method(endPresentation:))]
+        fn end_presentation(&self, _sender: *mut NSObject) {
+            crate::app::end_presentation();
+        }
+
+        #[unsafe(method(explain:))]
+        fn explain(&self, _sender: *mut NSObject) {
+            let mtm = MainThreadMarker::new().unwrap();
+            crate::ui::show_explainer(mtm);
         }
 
         #[unsafe(method(openSettings:))]
@@ -60,16 +133,17 @@ define_class!(
         #[unsafe(method(checkForUpdates:))]
         fn check_for_updates(&self, _sender: *mut NSObject) {
             eprintln!("SaveMyEyes: checkForUpdates called");
-            std::thread::spawn(|| {
-                let result = crate::updater::check_for_update(crate::updater::APP_VERSION);
+            shutdown::spawn("update-check", || {
+                let result = crate::updater::check_for_update_now(crate::updater::APP_VERSION);
                 crate::app::run_on_main(move || {
                     match result {
                         crate::updater::UpdateResult::UpdateAvailable {
                             version,
                             download_url,
+                            held_back,
                             ..
                         } => {
-                            crate::ui::prompt_update(&version, &download_url);
+                            crate::ui::prompt_update(&version, &download_url, held_back);
                         }
                         crate::updater::UpdateResult::NoUpdate => {
                             crate::ui::show_alert(
@@ -114,22 +188,8 @@ pub fn setup(mtm: MainThreadMarker) {
     let status_bar = NSStatusBar::systemStatusBar();
     let item = status_bar.statusItemWithLength(NSVariableStatusItemLength);
 
-    // Set the icon using SF Symbol for a native macOS look
-    if let Some(button) = item.button(mtm) {
-        // Try to use SF Symbol "eye.fill" for macOS 11+
-        let symbol_name = NSString::from_str("eye.fill");
-        if let Some(image) = NSImage::imageWithSystemSymbolName_accessibilityDescription(
-            &symbol_name,
-            None,
-        ) {
-            image.setTemplate(true); // Makes it adapt to menu bar theme (light/dark)
-            button.setImage(Some(&image));
-        } else {
-            // Fallback to emoji for older macOS versions
-            let title = NSString::from_str("👁");
-            button.setTitle(&title);
-        }
-    }
+    let designer_mode = crate::app::state().lock().unwrap().config.designer_mode;
+    set_icon(mtm, &item, designer_mode);
 
     // Create the target for menu actions
     let target = TrayTarget::new();
@@ -138,8 +198,7 @@ pub fn setup(mtm: MainThreadMarker) {
     // Verify class registration at runtime
     {
         use objc2::runtime::AnyClass;
-        use std::ffi::CStr;
-        let class_name = CStr::from_bytes_with_nul(b"TrayTarget\0").unwrap();
+        let class_name = c"TrayTarget";
         if let Some(cls) = AnyClass::get(class_name) {
             eprintln!("SaveMyEyes: TrayTarget class found: {:?}", cls.name());
             let responds = target.respondsToSelector(sel!(toggleDimmer:));
@@ -156,21 +215,42 @@ pub fn setup(mtm: MainThreadMarker) {
     item.setMenu(Some(&menu));
     eprintln!("SaveMyEyes: Menu set on status item");
 
-    *STATUS_ITEM.lock().unwrap() = Some(Mt(item));
+    *STATUS_ITEM.lock() = Some(item);
     *TRAY_TARGET.lock().unwrap() = Some(target);
     eprintln!("SaveMyEyes: Tray setup complete");
 }
 
-/// Remove the tray icon.
-#[allow(dead_code)]
-pub fn remove() {
-    let mut guard = STATUS_ITEM.lock().unwrap();
-    if let Some(item) = guard.take() {
-        let status_bar = NSStatusBar::systemStatusBar();
-        status_bar.removeStatusItem(&item);
+/// Set the status item icon; designer mode uses a crossed-out eye so the
+/// paused state stays visible in the menu bar.
+fn set_icon(mtm: MainThreadMarker, item: &NSStatusItem, designer_mode: bool) {
+    // Set the icon using SF Symbol for a native macOS look
+    if let Some(button) = item.button(mtm) {
+        // Try to use SF Symbol "eye.fill" for macOS 11+
+        let symbol_name = NSString::from_str(if designer_mode { "eye.slash" } else { "eye.fill" });
+        if let Some(image) = NSImage::imageWithSystemSymbolName_accessibilityDescription(
+            &symbol_name,
+            None,
+        ) {
+            image.setTemplate(true); // Makes it adapt to menu bar theme (light/dark)
+            button.setImage(Some(&image));
+        } else {
+            // Fallback to emoji for older macOS versions
+            let title = NSString::from_str(if designer_mode { "👁✕" } else { "👁" });
+            button.setTitle(&title);
+        }
     }
 }
 
+/// Remove the tray icon. Any thread.
+pub fn remove() {
+    STATUS_ITEM.update(|item| {
+        if let Some(item) = item.take() {
+            let status_bar = NSStatusBar::systemStatusBar();
+            status_bar.removeStatusItem(&item);
+        }
+    });
+}
+
 /// Update the menu to reflect current state (called after hotkey actions).
 pub fn update_menu(mtm: MainThreadMarker) {
     let target_guard = TRAY_TARGET.lock().unwrap();
@@ -179,10 +259,12 @@ pub fn update_menu(mtm: MainThreadMarker) {
         None => return,
     };
 
-    let item_guard = STATUS_ITEM.lock().unwrap();
+    let item_guard = STATUS_ITEM.lock();
     if let Some(item) = item_guard.as_ref() {
         let menu = build_menu(mtm, target);
         item.setMenu(Some(&menu));
+        let designer_mode = crate::app::state().lock().unwrap().config.designer_mode;
+        set_icon(mtm, item, designer_mode);
     }
 }
 
@@ -198,14 +280,25 @@ fn build_menu(mtm: MainThreadMarker, target: &TrayTarget) -> Retained<NSMenu> {
         // Status line: show current opacity
         let st = crate::app::state();
         let cfg = st.lock().unwrap();
-        let status_text = if cfg.config.is_enabled {
+        let designer_mode = cfg.config.designer_mode;
+        let status_text = if designer_mode {
+            "Designer Mode \u{2014} Dimming Paused".to_string()
+        } else if presentation::is_active() {
+            "Presentation Mode \u{2014} Dimming Off".to_string()
+        } else if pause::is_active() {
+            "Dimming Paused".to_string()
+        } else if cfg.config.is_enabled {
             format!(
-                "Dimming: {}%",
-                (cfg.config.opacity * 100.0).round() as i32
+                "Dimming: {}",
+                savemyeyes_shared::night_boost::level_label(
+                    cfg.config.opacity,
+                    crate::night_boost::current()
+                )
             )
         } else {
             "Dimming: Off".to_string()
         };
+        let config = cfg.config.clone();
         drop(cfg);
 
         let status_title = NSString::from_str(&status_text);
@@ -221,61 +314,256 @@ fn build_menu(mtm: MainThreadMarker, target: &TrayTarget) -> Retained<NSMenu> {
 
         menu.addItem(&NSMenuItem::separatorItem(mtm));
 
-        // Toggle Dimmer
-        let toggle_title = NSString::from_str("Toggle Dimmer");
-        let toggle_key = NSString::from_str("D"); // Cmd+Shift+D (uppercase = Shift)
-        let toggle_item = NSMenuItem::initWithTitle_action_keyEquivalent(
-            NSMenuItem::alloc(mtm),
-            &toggle_title,
-            Some(sel!(toggleDimmer:)),
-            &toggle_key,
-        );
-        toggle_item.setTarget(Some(target as &AnyObject));
-        toggle_item.setEnabled(true);
-        menu.addItem(&toggle_item);
-
-        menu.addItem(&NSMenuItem::separatorItem(mtm));
+        for item in tray_menu::items(&config) {
+            match item {
+                TrayItem::Toggle => {
+                    // Cmd+Shift+D (uppercase = Shift), unless the toggle
+                    // shortcut was changed in config
+                    let default = KeyCombo::parse(hotkeys::DEFAULT_KEYS[0]);
+                    let key = if KeyCombo::parse(&config.hotkey_toggle) == default {
+                        "D"
+                    } else {
+                        ""
+                    };
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Toggle Dimmer",
+                        sel!(toggleDimmer:),
+                        key,
+                    );
+                }
+                TrayItem::MovieMode => {
+                    let movie_item = add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Movie Mode",
+                        sel!(toggleMovieMode:),
+                        "M",
+                    );
+                    movie_item.setState(state_value(crate::movie_mode::is_active()));
+                }
+                TrayItem::DesignerMode => {
+                    let designer_item = add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Designer Mode",
+                        sel!(toggleDesignerMode:),
+                        "",
+                    );
+                    designer_item.setState(state_value(designer_mode));
+                }
+                TrayItem::Presets => {
+                    let submenu = NSMenu::new(mtm);
+                    submenu.setAutoenablesItems(false);
+                    for (i, level) in tray_menu::PRESETS.iter().enumerate() {
+                        let label = savemyeyes_shared::dimming::percent_label(*level);
+                        let preset_item =
+                            add_item(mtm, &submenu, target, &label, sel!(applyPreset:), "");
+                        preset_item.setTag(i as isize);
+                        let current = tray_menu::is_current_preset(&config, *level);
+                        preset_item.setState(state_value(current));
+                    }
+                    add_submenu(mtm, &menu, &submenu, "Dim Level");
+                }
+                TrayItem::Profiles => {
+                    let submenu = NSMenu::new(mtm);
+                    submenu.setAutoenablesItems(false);
+                    let listed = profiles::listed(&config);
+                    for (i, profile) in listed.iter().enumerate() {
+                        // Built-ins follow the saved profiles
+                        if i > 0
+                            && profiles::is_builtin(&config, &profile.name)
+                            && !profiles::is_builtin(&config, &listed[i - 1].name)
+                        {
+                            submenu.addItem(&NSMenuItem::separatorItem(mtm));
+                        }
+                        let profile_item = add_item(
+                            mtm,
+                            &submenu,
+                            target,
+                            &profile.name,
+                            sel!(applyProfile:),
+                            "",
+                        );
+                        profile_item.setTag(i as isize);
+                        let active = config
+                            .active_profile
+                            .as_deref()
+                            .is_some_and(|name| name.eq_ignore_ascii_case(&profile.name));
+                        profile_item.setState(state_value(active));
+                    }
+                    submenu.addItem(&NSMenuItem::separatorItem(mtm));
+                    let copies = NSMenu::new(mtm);
+                    copies.setAutoenablesItems(false);
+                    for (i, profile) in builtin_profiles::profiles().iter().enumerate() {
+                        let copy_item = add_item(
+                            mtm,
+                            &copies,
+                            target,
+                            &profile.name,
+                            sel!(duplicateProfile:),
+                            "",
+                        );
+                        copy_item.setTag(i as isize);
+                    }
+                    add_submenu(mtm, &submenu, &copies, "Duplicate to Customize");
+                    let examples = NSMenu::new(mtm);
+                    examples.setAutoenablesItems(false);
+                    for (i, example) in builtin_profiles::examples().iter().enumerate() {
+                        let example_item = add_item(
+                            mtm,
+                            &examples,
+                            target,
+                            &example.name,
+                            sel!(useThemeExample:),
+                            "",
+                        );
+                        example_item.setTag(i as isize);
+                    }
+                    add_submenu(mtm, &submenu, &examples, "Theme Examples");
+                    add_submenu(mtm, &menu, &submenu, "Profiles");
+                }
+                TrayItem::Pause if pause::is_active() => {
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Resume Dimming",
+                        sel!(resumeDimming:),
+                        "",
+                    );
+                }
+                TrayItem::Pause => {
+                    let submenu = NSMenu::new(mtm);
+                    submenu.setAutoenablesItems(false);
+                    for (i, minutes) in pause::DURATIONS.iter().enumerate() {
+                        let label = pause::duration_label(*minutes);
+                        let pause_item =
+                            add_item(mtm, &submenu, target, &label, sel!(pauseDimming:), "");
+                        pause_item.setTag(i as isize);
+                    }
+                    let pause_item = add_submenu(mtm, &menu, &submenu, "Pause Dimming");
+                    // Designer mode already pauses everything
+                    pause_item.setEnabled(!designer_mode);
+                }
+                TrayItem::Presentation if presentation::is_active() => {
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        &presentation::end_label(),
+                        sel!(endPresentation:),
+                        "",
+                    );
+                }
+                TrayItem::Presentation => {
+                    let submenu = NSMenu::new(mtm);
+                    submenu.setAutoenablesItems(false);
+                    for (i, minutes) in presentation::DURATIONS.iter().enumerate() {
+                        let label = presentation::duration_label(*minutes);
+                        let item =
+                            add_item(mtm, &submenu, target, &label, sel!(startPresentation:), "");
+                        item.setTag(i as isize);
+                    }
+                    let item = add_submenu(mtm, &menu, &submenu, "Presentation Mode");
+                    item.setEnabled(!designer_mode);
+                }
+                // Gamma dimming never shows in recordings; tray_menu drops it
+                TrayItem::Recording => {}
+                TrayItem::Updates => {
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Check for Updates\u{2026}",
+                        sel!(checkForUpdates:),
+                        "",
+                    );
+                }
+                TrayItem::Separator => menu.addItem(&NSMenuItem::separatorItem(mtm)),
+                TrayItem::Explain => {
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Why Is My Screen Dark?",
+                        sel!(explain:),
+                        "",
+                    );
+                }
+                TrayItem::Settings => {
+                    // Cmd+,
+                    add_item(
+                        mtm,
+                        &menu,
+                        target,
+                        "Settings\u{2026}",
+                        sel!(openSettings:),
+                        ",",
+                    );
+                }
+                TrayItem::Quit => {
+                    add_item(mtm, &menu, target, "Quit SaveMyEyes", sel!(quitApp:), "q");
+                }
+            }
+        }
 
-        // Settings
-        let settings_title = NSString::from_str("Settings\u{2026}");
-        let settings_key = NSString::from_str(","); // Cmd+,
-        let settings_item = NSMenuItem::initWithTitle_action_keyEquivalent(
-            NSMenuItem::alloc(mtm),
-            &settings_title,
-            Some(sel!(openSettings:)),
-            &settings_key,
-        );
-        settings_item.setTarget(Some(target as &AnyObject));
-        settings_item.setEnabled(true);
-        menu.addItem(&settings_item);
+        menu
+    }
+}
 
-        // Check for Updates
-        let update_title = NSString::from_str("Check for Updates\u{2026}");
-        let update_item = NSMenuItem::initWithTitle_action_keyEquivalent(
+/// Append an enabled item sending `action` to the tray target
+fn add_item(
+    mtm: MainThreadMarker,
+    menu: &NSMenu,
+    target: &TrayTarget,
+    title: &str,
+    action: Sel,
+    key: &str,
+) -> Retained<NSMenuItem> {
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(
             NSMenuItem::alloc(mtm),
-            &update_title,
-            Some(sel!(checkForUpdates:)),
-            &empty_key,
-        );
-        update_item.setTarget(Some(target as &AnyObject));
-        update_item.setEnabled(true);
-        menu.addItem(&update_item);
-
-        menu.addItem(&NSMenuItem::separatorItem(mtm));
+            &NSString::from_str(title),
+            Some(action),
+            &NSString::from_str(key),
+        )
+    };
+    unsafe { item.setTarget(Some(target as &AnyObject)) };
+    item.setEnabled(true);
+    menu.addItem(&item);
+    item
+}
 
-        // Quit
-        let quit_title = NSString::from_str("Quit SaveMyEyes");
-        let quit_key = NSString::from_str("q");
-        let quit_item = NSMenuItem::initWithTitle_action_keyEquivalent(
+/// Append an item opening `submenu`
+fn add_submenu(
+    mtm: MainThreadMarker,
+    menu: &NSMenu,
+    submenu: &NSMenu,
+    title: &str,
+) -> Retained<NSMenuItem> {
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(
             NSMenuItem::alloc(mtm),
-            &quit_title,
-            Some(sel!(quitApp:)),
-            &quit_key,
-        );
-        quit_item.setTarget(Some(target as &AnyObject));
-        quit_item.setEnabled(true);
-        menu.addItem(&quit_item);
+            &NSString::from_str(title),
+            None,
+            &NSString::from_str(""),
+        )
+    };
+    item.setSubmenu(Some(submenu));
+    item.setEnabled(true);
+    menu.addItem(&item);
+    item
+}
 
-        menu
+fn state_value(on: bool) -> isize {
+    if on {
+        NSControlStateValueOn
+    } else {
+        NSControlStateValueOff
     }
 }
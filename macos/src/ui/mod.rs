@@ -1,14 +1,18 @@
 mod settings;
-mod theme;
+pub mod theme;
 
 pub use settings::show_settings;
 pub use settings::update_ui;
 pub use settings::rebuild_settings;
 
 use objc2::MainThreadMarker;
-use objc2_app_kit::{NSAlert, NSAlertFirstButtonReturn, NSAlertStyle, NSApplication};
+use objc2_app_kit::{
+    NSAlert, NSAlertFirstButtonReturn, NSAlertStyle, NSAlertThirdButtonReturn, NSApplication,
+};
 use objc2_foundation::NSString;
 
+use savemyeyes_shared::updater::{now_secs, UpdateChoice};
+
 /// Show an informational alert dialog.
 pub fn show_alert(title: &str, message: &str) {
     let mtm = MainThreadMarker::new().unwrap();
@@ -20,9 +24,75 @@ pub fn show_alert(title: &str, message: &str) {
     alert.runModal();
 }
 
-/// Prompt the user about an available update.
-pub fn prompt_update(version: &str, download_url: &str) {
+/// "Why is my screen dark?" (tray): the explanation with one button per fix,
+/// then Close.
+pub fn show_explainer(mtm: MainThreadMarker) {
+    let explanation = crate::app::explanation(mtm);
+    let fixes = explanation.fixes();
+    let alert = NSAlert::new(mtm);
+    alert.setAlertStyle(NSAlertStyle::Informational);
+    alert.setMessageText(&NSString::from_str("Why is my screen dark?"));
+    alert.setInformativeText(&NSString::from_str(&explanation.text()));
+    for fix in &fixes {
+        alert.addButtonWithTitle(&NSString::from_str(fix.label()));
+    }
+    alert.addButtonWithTitle(&NSString::from_str("Close"));
+    NSApplication::sharedApplication(mtm).activate();
+
+    let response = alert.runModal();
+    let picked = usize::try_from(response - NSAlertFirstButtonReturn).ok();
+    if let Some(&fix) = picked.and_then(|i| fixes.get(i)) {
+        crate::app::apply_fix(fix);
+    }
+}
+
+/// Prompt the user about an available update and remember the answer
+/// (skip this version, or remind later with a growing wait). `held_back`
+/// is the rollout percentage of a staged release this Mac isn't in yet:
+/// the prompt then offers to get it now anyway, or to wait for it.
+pub fn prompt_update(version: &str, download_url: &str, held_back: Option<u8>) {
     let mtm = MainThreadMarker::new().unwrap();
+    let st = crate::app::state();
+    let choice = match held_back {
+        Some(percent) => {
+            if !prompt_held_back(mtm, version, percent) {
+                eprintln!("SaveMyEyes: update v{}: waiting for the rollout", version);
+                return;
+            }
+            UpdateChoice::Install
+        }
+        None => ask_update(mtm, version),
+    };
+    {
+        let mut s = st.lock().unwrap();
+        s.config.update_snooze.record(choice, version, now_secs());
+        crate::config::save_config(&s.config);
+    }
+    eprintln!("SaveMyEyes: update v{}: {:?}", version, choice);
+    if choice == UpdateChoice::Install {
+        install_update(download_url);
+    }
+}
+
+/// "Get it now anyway?" for a staged release this Mac isn't in yet
+fn prompt_held_back(mtm: MainThreadMarker, version: &str, percent: u8) -> bool {
+    let alert = NSAlert::new(mtm);
+    alert.setAlertStyle(NSAlertStyle::Informational);
+    alert.setMessageText(&NSString::from_str("Update Rolling Out"));
+    alert.setInformativeText(&NSString::from_str(&format!(
+        "SaveMyEyes v{} is being rolled out gradually and has reached {}% of Macs so far, \
+         not this one yet. Would you like to get it now anyway?",
+        version, percent
+    )));
+    alert.addButtonWithTitle(&NSString::from_str("Get It Now"));
+    alert.addButtonWithTitle(&NSString::from_str("Wait"));
+    alert.runModal() == NSAlertFirstButtonReturn
+}
+
+/// The usual update prompt: update now, remind later or skip the version
+fn ask_update(mtm: MainThreadMarker, version: &str) -> UpdateChoice {
+    let st = crate::app::state();
+    let remind_days = st.lock().unwrap().config.update_snooze.remind_days();
     let alert = NSAlert::new(mtm);
     alert.setAlertStyle(NSAlertStyle::Informational);
     alert.setMessageText(&NSString::from_str("Update Available"));
@@ -31,29 +101,43 @@ pub fn prompt_update(version: &str, download_url: &str) {
         version
     )));
     alert.addButtonWithTitle(&NSString::from_str("Update Now"));
-    alert.addButtonWithTitle(&NSString::from_str("Later"));
+    alert.addButtonWithTitle(&NSString::from_str(&format!(
+        "Remind Me in {} Day{}",
+        remind_days,
+        if remind_days == 1 { "" } else { "s" }
+    )));
+    alert.addButtonWithTitle(&NSString::from_str("Skip This Version"));
 
     let response = alert.runModal();
     if response == NSAlertFirstButtonReturn {
-        let url = download_url.to_string();
-        // Show progress indicator then download + install in background
-        std::thread::spawn(move || {
-            match crate::updater::download_update(&url) {
-                Ok(dmg_path) => {
-                    crate::app::run_on_main(move || {
-                        perform_update_install(&dmg_path);
-                    });
-                }
-                Err(e) => {
-                    crate::app::run_on_main(move || {
-                        show_alert("Update Failed", &format!("Download failed: {}", e));
-                    });
-                }
-            }
-        });
+        UpdateChoice::Install
+    } else if response == NSAlertThirdButtonReturn {
+        UpdateChoice::Skip
+    } else {
+        UpdateChoice::Later
     }
 }
 
+/// Download the update and install it
+fn install_update(download_url: &str) {
+    let url = download_url.to_string();
+    // Show progress indicator then download + install in background
+    std::thread::spawn(move || {
+        match crate::updater::download_update(&url) {
+            Ok(dmg_path) => {
+                crate::app::run_on_main(move || {
+                    perform_update_install(&dmg_path);
+                });
+            }
+            Err(e) => {
+                crate::app::run_on_main(move || {
+                    show_alert("Update Failed", &format!("Download failed: {}", e));
+                });
+            }
+        }
+    });
+}
+
 /// Perform the update: mount .dmg, copy .app, relaunch
 fn perform_update_install(dmg_path: &std::path::Path) {
     let dmg_str = dmg_path.to_string_lossy().to_string();
@@ -148,10 +232,19 @@ exit 0
     }
 
     // Make executable and launch detached
-    let _ = std::process::Command::new("chmod")
+    match std::process::Command::new("chmod")
         .arg("+x")
         .arg(&script_path)
-        .output();
+        .output()
+    {
+        Ok(out) if out.status.success() => {}
+        Ok(out) => eprintln!(
+            "SaveMyEyes: chmod of the update script failed ({}): {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ),
+        Err(e) => eprintln!("SaveMyEyes: could not run chmod: {}", e),
+    }
 
     match std::process::Command::new("bash")
         .arg(&script_path)
@@ -1,61 +1,104 @@
 // macOS Settings window — polished card-based dark UI matching Windows design.
 
 use objc2::rc::Retained;
-use objc2::runtime::AnyObject;
+use objc2::runtime::{AnyObject, Sel};
 use objc2::{define_class, msg_send, sel, AllocAnyThread, MainThreadMarker};
 use objc2_app_kit::*;
 use objc2_foundation::*;
 
+use std::ptr::NonNull;
 use std::sync::Mutex;
 
 use crate::app;
 use crate::autostart;
 use crate::config;
+use crate::main_thread::MainThreadCell;
 use crate::overlay;
 use crate::ui::theme::*;
+use savemyeyes_shared::capabilities::Feature;
+use savemyeyes_shared::custom_actions::{self, CustomAction, Edit, MAX_ACTIONS, MAX_STEPS};
+use savemyeyes_shared::dimming::{self, OpacityCurve};
+use savemyeyes_shared::explain::{self, Cause};
+use savemyeyes_shared::hotkeys::{self, HotkeyAction, Key, KeyCombo, NamedKey};
+use savemyeyes_shared::locale;
+use savemyeyes_shared::monitor_groups;
+use savemyeyes_shared::monitor_id::MonitorId;
+use savemyeyes_shared::night_boost;
+use savemyeyes_shared::profiles;
+use savemyeyes_shared::scheduler;
+use savemyeyes_shared::shutdown;
 
 // ---------------------------------------------------------------------------
-// Thread-safety wrapper (main-thread-only UI objects behind Mutex)
+// Static references (controls are main-thread only, see main_thread.rs)
 // ---------------------------------------------------------------------------
-struct Mt<T>(T);
-unsafe impl<T> Send for Mt<T> {}
-unsafe impl<T> Sync for Mt<T> {}
-impl<T> std::ops::Deref for Mt<T> {
-    type Target = T;
-    fn deref(&self) -> &T {
-        &self.0
-    }
-}
-impl<T> std::ops::DerefMut for Mt<T> {
-    fn deref_mut(&mut self) -> &mut T {
-        &mut self.0
-    }
-}
-
-// ---------------------------------------------------------------------------
-// Static references
-// ---------------------------------------------------------------------------
-static SETTINGS_WINDOW: Mutex<Option<Mt<Retained<NSWindow>>>> = Mutex::new(None);
-static SLIDER_REF: Mutex<Option<Mt<Retained<NSSlider>>>> = Mutex::new(None);
-static SLIDER_LABEL_REF: Mutex<Option<Mt<Retained<NSTextField>>>> = Mutex::new(None);
-static ENABLED_TOGGLE_REF: Mutex<Option<Mt<Retained<NSButton>>>> = Mutex::new(None);
+static SETTINGS_WINDOW: MainThreadCell<Option<Retained<NSWindow>>> = MainThreadCell::new(None);
+static SLIDER_REF: MainThreadCell<Option<Retained<NSSlider>>> = MainThreadCell::new(None);
+static SLIDER_LABEL_REF: MainThreadCell<Option<Retained<NSTextField>>> = MainThreadCell::new(None);
+static ENABLED_TOGGLE_REF: MainThreadCell<Option<Retained<NSButton>>> = MainThreadCell::new(None);
 static SETTINGS_TARGET: Mutex<Option<Retained<SettingsTarget>>> = Mutex::new(None);
 
 // Per-monitor slider/label refs (up to 8 monitors)
-static MONITOR_SLIDER_REFS: Mutex<Vec<Mt<Retained<NSSlider>>>> = Mutex::new(Vec::new());
-static MONITOR_LABEL_REFS: Mutex<Vec<Mt<Retained<NSTextField>>>> = Mutex::new(Vec::new());
-// Display names for current monitors (used to key per_display_opacity)
-static MONITOR_NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static MONITOR_SLIDER_REFS: MainThreadCell<Vec<Retained<NSSlider>>> =
+    MainThreadCell::new(Vec::new());
+static MONITOR_LABEL_REFS: MainThreadCell<Vec<Retained<NSTextField>>> =
+    MainThreadCell::new(Vec::new());
+// Ids of the current monitors (used to key monitor_opacity)
+static MONITOR_IDS: Mutex<Vec<MonitorId>> = Mutex::new(Vec::new());
+// Monitors ticked for grouping, and the "Group Selected" button
+static MONITOR_SELECTED: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+static GROUP_BUTTON_REF: MainThreadCell<Option<Retained<NSButton>>> = MainThreadCell::new(None);
+// Group rows: slider, badge label and group name per tag
+static GROUP_SLIDER_REFS: MainThreadCell<Vec<Retained<NSSlider>>> = MainThreadCell::new(Vec::new());
+static GROUP_LABEL_REFS: MainThreadCell<Vec<Retained<NSTextField>>> =
+    MainThreadCell::new(Vec::new());
+static GROUP_NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Theme automation controls (switch, dark popup, light popup)
+static THEME_TOGGLE_REF: MainThreadCell<Option<Retained<NSButton>>> = MainThreadCell::new(None);
+static THEME_POPUP_REFS: MainThreadCell<Option<[Retained<NSPopUpButton>; 2]>> =
+    MainThreadCell::new(None);
+
+// Custom actions card (summary label, pull-down) and the edit behind each
+// menu item's tag
+static CUSTOM_ACTIONS_LABEL_REF: MainThreadCell<Option<Retained<NSTextField>>> =
+    MainThreadCell::new(None);
+static CUSTOM_ACTIONS_POPUP_REF: MainThreadCell<Option<Retained<NSPopUpButton>>> =
+    MainThreadCell::new(None);
+static CUSTOM_ACTION_EDITS: Mutex<Vec<(usize, Edit)>> = Mutex::new(Vec::new());
+
+// Scheduled dimming summary, refreshed when the days change
+static SCHEDULE_LABEL_REF: MainThreadCell<Option<Retained<NSTextField>>> =
+    MainThreadCell::new(None);
+
+// Shortcut recorder: the key label of the toggle, increase and decrease
+// rows, and the local key monitor while one of them is recording
+static SHORTCUT_LABELS: MainThreadCell<Vec<Retained<NSTextField>>> =
+    MainThreadCell::new(Vec::new());
+static SHORTCUT_MONITOR: MainThreadCell<Option<(usize, Retained<AnyObject>)>> =
+    MainThreadCell::new(None);
+
+/// The recordable shortcut rows of the Shortcuts tab and their config keys
+const RECORDABLE: [(HotkeyAction, &str); 3] = [
+    (HotkeyAction::Toggle, "hotkey_toggle"),
+    (HotkeyAction::Increase, "hotkey_increase"),
+    (HotkeyAction::Decrease, "hotkey_decrease"),
+];
+
+/// Level badge width, and while it reads "45% (+10% night boost)"
+const BADGE_W: f64 = 48.0;
+const BADGE_W_BOOSTED: f64 = 150.0;
+/// Height of a monitor group's row
+const GROUP_CARD_H: f64 = 84.0;
 
 // Tab content views — stored so we can show/hide on tab switch
-static TAB_VIEWS: Mutex<Option<Mt<[Retained<NSView>; 3]>>> = Mutex::new(None);
+static TAB_VIEWS: MainThreadCell<Option<[Retained<NSView>; 4]>> = MainThreadCell::new(None);
 
 /// Update the settings UI to reflect current state (called after hotkey toggle).
 pub fn update_ui() {
     let st = app::state();
     let cfg = st.lock().unwrap().config.clone();
 
-    if let Some(toggle) = ENABLED_TOGGLE_REF.lock().unwrap().as_ref() {
+    if let Some(toggle) = ENABLED_TOGGLE_REF.lock().as_ref() {
         let is_on = cfg.is_enabled;
         toggle.setState(if is_on {
             NSControlStateValueOn
@@ -64,28 +107,373 @@ pub fn update_ui() {
         });
         style_toggle(toggle, is_on);
     }
-    if let Some(slider) = SLIDER_REF.lock().unwrap().as_ref() {
+    if let Some(slider) = SLIDER_REF.lock().as_ref() {
         slider.setFloatValue(cfg.opacity * 100.0);
     }
-    if let Some(label) = SLIDER_LABEL_REF.lock().unwrap().as_ref() {
-        let pct = (cfg.opacity * 100.0).round() as i32;
-        label.setStringValue(&NSString::from_str(&format!("{}%", pct)));
+    if let Some(label) = SLIDER_LABEL_REF.lock().as_ref() {
+        set_level_text(label, cfg.opacity);
     }
 
+    sync_theme_controls(&cfg);
+
     // Update per-monitor sliders
-    let sliders = MONITOR_SLIDER_REFS.lock().unwrap();
-    let labels = MONITOR_LABEL_REFS.lock().unwrap();
-    let names = MONITOR_NAMES.lock().unwrap();
+    let sliders = MONITOR_SLIDER_REFS.lock();
+    let labels = MONITOR_LABEL_REFS.lock();
+    let ids = MONITOR_IDS.lock().unwrap();
+    for (i, slider) in sliders.iter().enumerate() {
+        let opacity = ids.get(i).map_or(cfg.opacity, |id| cfg.level_for(id));
+        slider.setFloatValue(opacity * 100.0);
+        if let Some(label) = labels.get(i) {
+            set_level_text(label, opacity);
+        }
+    }
+
+    // Group rows show the level of their first member
+    let sliders = GROUP_SLIDER_REFS.lock();
+    let labels = GROUP_LABEL_REFS.lock();
+    let names = GROUP_NAMES.lock().unwrap();
     for (i, slider) in sliders.iter().enumerate() {
-        let opacity = names.get(i)
-            .and_then(|n| cfg.per_display_opacity.get(n))
-            .copied()
-            .unwrap_or(cfg.opacity);
+        let first = names
+            .get(i)
+            .and_then(|name| cfg.monitor_groups.iter().find(|g| g.name == *name))
+            .and_then(|group| group.monitors.first());
+        let opacity = first.map_or(cfg.opacity, |id| cfg.level_for(id));
         slider.setFloatValue(opacity * 100.0);
         if let Some(label) = labels.get(i) {
-            let pct = (opacity * 100.0).round() as i32;
-            label.setStringValue(&NSString::from_str(&format!("{}%", pct)));
+            set_level_text(label, opacity);
+        }
+    }
+}
+
+/// Slider level in whole percents, or half percents in fine mode (Shift held
+/// while dragging)
+fn slider_level(slider: &NSSlider) -> f32 {
+    let fine = NSEvent::modifierFlags_class().contains(NSEventModifierFlags::Shift);
+    dimming::from_steps(dimming::snap_steps(slider.floatValue() * 10.0, fine))
+}
+
+/// Show `level` in a slider badge, "45% (+10% night boost)" while the night
+/// boost is on. The badge widens to the left to fit (to the right when the
+/// layout is mirrored).
+fn set_level_text(label: &NSTextField, level: f32) {
+    let boost = crate::night_boost::current().filter(|_| overlay::is_visible());
+    label.setStringValue(&NSString::from_str(&night_boost::level_label(level, boost)));
+    // The badge container outlives its label (both owned by the card)
+    let Some(badge) = (unsafe { label.superview() }) else {
+        return;
+    };
+    let width = if boost.is_some() { BADGE_W_BOOSTED } else { BADGE_W };
+    let frame = badge.frame();
+    let x = if locale::current().rtl {
+        frame.origin.x
+    } else {
+        frame.origin.x + frame.size.width - width
+    };
+    badge.setFrame(NSRect::new(
+        NSPoint::new(x, frame.origin.y),
+        NSSize::new(width, frame.size.height),
+    ));
+    let text = label.frame();
+    label.setFrame(NSRect::new(text.origin, NSSize::new(width, text.size.height)));
+}
+
+/// Refresh the theme automation switch and profile popups from config.
+fn sync_theme_controls(cfg: &config::AppConfig) {
+    if let Some(toggle) = THEME_TOGGLE_REF.lock().as_ref() {
+        let on = cfg.theme_automation.enabled;
+        toggle.setState(if on {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        });
+        style_toggle(toggle, on);
+    }
+    if let Some(popups) = THEME_POPUP_REFS.lock().as_ref() {
+        let choices = profiles::choices(cfg);
+        for (popup, dark) in popups.iter().zip([true, false]) {
+            popup.removeAllItems();
+            for choice in &choices {
+                popup.addItemWithTitle(&NSString::from_str(choice.as_deref().unwrap_or("None")));
+            }
+            let selected = cfg.theme_automation.profile_for(dark);
+            let idx = choices
+                .iter()
+                .position(|c| c.as_deref() == selected)
+                .unwrap_or(0);
+            popup.selectItemAtIndex(idx as isize);
+        }
+    }
+}
+
+/// Refill the custom actions summary and pull-down from config
+fn sync_custom_actions(cfg: &config::AppConfig) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let Some(target) = SETTINGS_TARGET.lock().unwrap().clone() else {
+        return;
+    };
+    let summary = CUSTOM_ACTIONS_LABEL_REF.lock();
+    let popup = CUSTOM_ACTIONS_POPUP_REF.lock();
+    let (Some(summary), Some(popup)) = (summary.as_ref(), popup.as_ref()) else {
+        return;
+    };
+
+    let actions = &cfg.custom_actions;
+    let text = if actions.is_empty() {
+        "e.g. apply a profile, then pause, on one hotkey".to_string()
+    } else {
+        let names: Vec<String> = actions
+            .iter()
+            .map(|a| format!("{} ({})", a.name, hotkey_label(a)))
+            .collect();
+        names.join(", ")
+    };
+    summary.setStringValue(&NSString::from_str(&text));
+
+    let mut edits = CUSTOM_ACTION_EDITS.lock().unwrap();
+    edits.clear();
+    let mut add = |menu: &NSMenu, title: &str, edit: Option<(usize, Edit)>, enabled: bool| {
+        let action = if edit.is_some() {
+            sel!(customActionEdited:)
+        } else {
+            sel!(newCustomAction:)
+        };
+        let item = menu_item(mtm, menu, &target, title, Some(action));
+        if let Some(edit) = edit {
+            item.setTag(edits.len() as isize);
+            edits.push(edit);
         }
+        item.setEnabled(enabled);
+        item
+    };
+
+    let menu = NSMenu::new(mtm);
+    menu.setAutoenablesItems(false);
+    // A pull-down shows its first item as the button title
+    menu_item(mtm, &menu, &target, "Edit", None);
+    let groups = custom_actions::step_menu(cfg, overlay::screen_ids(mtm).len() as u32);
+    for (index, action) in actions.iter().take(MAX_ACTIONS).enumerate() {
+        let submenu = NSMenu::new(mtm);
+        submenu.setAutoenablesItems(false);
+        menu_item(mtm, &submenu, &target, &action.summary(), None).setEnabled(false);
+        submenu.addItem(&NSMenuItem::separatorItem(mtm));
+
+        let steps = NSMenu::new(mtm);
+        steps.setAutoenablesItems(false);
+        for group in &groups {
+            let group_menu = NSMenu::new(mtm);
+            group_menu.setAutoenablesItems(false);
+            for (label, step) in &group.steps {
+                add(
+                    &group_menu,
+                    label,
+                    Some((index, Edit::AddStep(step.clone()))),
+                    true,
+                );
+            }
+            menu_item(mtm, &steps, &target, &group.label, None).setSubmenu(Some(&group_menu));
+        }
+        let add_step = menu_item(mtm, &submenu, &target, "Add Step", None);
+        add_step.setSubmenu(Some(&steps));
+        add_step.setEnabled(action.steps.len() < MAX_STEPS);
+        let removable = !action.steps.is_empty();
+        add(
+            &submenu,
+            "Remove Last Step",
+            Some((index, Edit::RemoveLastStep)),
+            removable,
+        );
+
+        let hotkeys = NSMenu::new(mtm);
+        hotkeys.setAutoenablesItems(false);
+        for hotkey in custom_actions::hotkey_choices() {
+            let current = hotkey.eq_ignore_ascii_case(&action.hotkey);
+            let free = !custom_actions::hotkey_taken(actions, index, &hotkey);
+            let item = add(
+                &hotkeys,
+                &hotkey,
+                Some((index, Edit::Hotkey(hotkey.clone()))),
+                free,
+            );
+            item.setState(if current {
+                NSControlStateValueOn
+            } else {
+                NSControlStateValueOff
+            });
+        }
+        menu_item(mtm, &submenu, &target, "Hotkey", None).setSubmenu(Some(&hotkeys));
+        submenu.addItem(&NSMenuItem::separatorItem(mtm));
+        add(&submenu, "Delete Action", Some((index, Edit::Delete)), true);
+
+        let title = format!("{} ({})", action.name, hotkey_label(action));
+        menu_item(mtm, &menu, &target, &title, None).setSubmenu(Some(&submenu));
+    }
+    if !actions.is_empty() {
+        menu.addItem(&NSMenuItem::separatorItem(mtm));
+    }
+    add(&menu, "New Action", None, actions.len() < MAX_ACTIONS);
+    popup.setMenu(Some(&menu));
+}
+
+/// An action's hotkey for the settings UI
+fn hotkey_label(action: &CustomAction) -> &str {
+    if action.hotkey.is_empty() {
+        "no hotkey"
+    } else {
+        &action.hotkey
+    }
+}
+
+/// Append an item sending `action` to the settings target (or, without an
+/// action, one that only opens a submenu)
+fn menu_item(
+    mtm: MainThreadMarker,
+    menu: &NSMenu,
+    target: &SettingsTarget,
+    title: &str,
+    action: Option<Sel>,
+) -> Retained<NSMenuItem> {
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(
+            mtm.alloc::<NSMenuItem>(),
+            &NSString::from_str(title),
+            action,
+            &NSString::from_str(""),
+        )
+    };
+    if action.is_some() {
+        unsafe { item.setTarget(Some(target as &AnyObject)) };
+    }
+    menu.addItem(&item);
+    item
+}
+
+/// A shortcut the way macOS menus show it, e.g. "⇧⌘D"
+fn key_symbols(text: &str) -> String {
+    let Some(combo) = KeyCombo::parse(text) else {
+        return text.to_string();
+    };
+    let m = combo.modifiers;
+    let mut symbols: String = [(m.ctrl, '⌃'), (m.alt, '⌥'), (m.shift, '⇧'), (m.meta, '⌘')]
+        .into_iter()
+        .filter_map(|(held, symbol)| held.then_some(symbol))
+        .collect();
+    let key = match combo.key {
+        Key::Char(c) => c.to_string(),
+        Key::Function(n) => format!("F{}", n),
+        Key::Named(key) => match key {
+            NamedKey::Up => "↑",
+            NamedKey::Down => "↓",
+            NamedKey::Left => "←",
+            NamedKey::Right => "→",
+            NamedKey::Home => "↖",
+            NamedKey::End => "↘",
+            NamedKey::PageUp => "⇞",
+            NamedKey::PageDown => "⇟",
+            NamedKey::Delete => "⌦",
+            NamedKey::Tab => "⇥",
+            NamedKey::Enter => "↩",
+            NamedKey::Escape => "⎋",
+            NamedKey::Backspace => "⌫",
+            other => other.name(),
+        }
+        .to_string(),
+    };
+    symbols.push_str(&key);
+    symbols
+}
+
+/// Listen for the new shortcut of recordable row `row`. The global hotkeys
+/// are off meanwhile, so pressing the current combo records it instead of
+/// running it.
+fn start_recording(row: usize) {
+    stop_recording();
+    crate::hotkeys::unregister_all();
+    if let Some(label) = SHORTCUT_LABELS.lock().get(row) {
+        label.setStringValue(&NSString::from_str("Press keys…"));
+    }
+    let handler = block2::RcBlock::new(move |event: NonNull<NSEvent>| -> *mut NSEvent {
+        let event_ref: &NSEvent = unsafe { event.as_ref() };
+        let modifiers = crate::hotkeys::event_modifiers(event_ref);
+        match crate::hotkeys::combo_for_key(event_ref.keyCode(), modifiers) {
+            // Esc alone cancels; with modifiers it is a shortcut like any other
+            Some(combo) if combo.key == Key::Named(NamedKey::Escape) && !combo.is_usable() => {
+                app::run_on_main(stop_recording);
+            }
+            Some(combo) => app::run_on_main(move || record_shortcut(row, combo)),
+            None => app::run_on_main(|| {
+                let mtm = MainThreadMarker::new().unwrap();
+                crate::hud::show(mtm, "That key can't be a shortcut");
+            }),
+        }
+        // Swallowed: the key never reaches the settings window
+        std::ptr::null_mut()
+    });
+    let monitor = unsafe {
+        NSEvent::addLocalMonitorForEventsMatchingMask_handler(NSEventMask::KeyDown, &handler)
+    };
+    if let Some(monitor) = monitor {
+        *SHORTCUT_MONITOR.lock() = Some((row, monitor));
+    }
+}
+
+/// Stop listening, show the row's shortcut again and turn the global
+/// hotkeys back on (unless they are off in config)
+fn stop_recording() {
+    let Some((row, monitor)) = SHORTCUT_MONITOR.lock().take() else {
+        return;
+    };
+    unsafe { NSEvent::removeMonitor(&monitor) };
+    let st = app::state();
+    let s = st.lock().unwrap();
+    if let Some(label) = SHORTCUT_LABELS.lock().get(row) {
+        let text = s.config.key_strings()[row];
+        label.setStringValue(&NSString::from_str(&key_symbols(text)));
+    }
+    if !s.config.disable_global_shortcuts {
+        crate::hotkeys::register_all();
+    }
+}
+
+/// Key pressed while recording row `row`
+fn record_shortcut(row: usize, combo: KeyCombo) {
+    let mtm = MainThreadMarker::new().unwrap();
+    let (action, _) = RECORDABLE[row];
+    let result = {
+        let st = app::state();
+        let mut s = st.lock().unwrap();
+        let result = s.config.set_key(action, combo);
+        if result.is_ok() {
+            config::save_config(&s.config);
+            crate::hotkeys::set_keys(s.config.key_bindings());
+        }
+        result
+    };
+    match result {
+        Ok(()) => {
+            stop_recording();
+            crate::tray::update_menu(mtm);
+            let message = format!("{}: {}", action.label(), key_symbols(&combo.to_string()));
+            crate::hud::show(mtm, &message);
+        }
+        // Wrong combos keep recording, so the next try needs no click
+        Err(message) => crate::hud::show(mtm, &message),
+    }
+}
+
+/// After the custom actions changed: save, re-read the hotkeys, refill
+fn custom_actions_changed(message: Option<String>) {
+    let mtm = MainThreadMarker::new().unwrap();
+    {
+        let st = app::state();
+        let s = st.lock().unwrap();
+        config::save_config(&s.config);
+        crate::hotkeys::set_custom_actions(&s.config.custom_actions);
+        sync_custom_actions(&s.config);
+    }
+    if let Some(message) = message {
+        crate::hud::show(mtm, &message);
     }
 }
 
@@ -103,12 +491,11 @@ define_class!(
     impl SettingsTarget {
         #[unsafe(method(sliderChanged:))]
         fn slider_changed(&self, sender: &NSSlider) {
-            let val = sender.floatValue();
-            let clamped = (val / 100.0).clamp(0.0, 0.9);
+            explain::note(Cause::Settings);
+            let clamped = slider_level(sender);
 
-            if let Some(label) = SLIDER_LABEL_REF.lock().unwrap().as_ref() {
-                let pct = (clamped * 100.0).round() as i32;
-                label.setStringValue(&NSString::from_str(&format!("{}%", pct)));
+            if let Some(label) = SLIDER_LABEL_REF.lock().as_ref() {
+                set_level_text(label, clamped);
             }
 
             let st = app::state();
@@ -118,78 +505,186 @@ define_class!(
             config::save_config(&s.config);
 
             // Auto-enable the toggle
-            if let Some(toggle) = ENABLED_TOGGLE_REF.lock().unwrap().as_ref() {
+            if let Some(toggle) = ENABLED_TOGGLE_REF.lock().as_ref() {
                 toggle.setState(NSControlStateValueOn);
                 style_toggle(toggle, true);
             }
 
             // Update existing overlays in-place (no flicker) or create if needed
             let mtm = MainThreadMarker::new().unwrap();
-            if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.per_display_opacity) {
+            if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.monitor_opacity) {
                 overlay::show(
                     mtm,
                     s.config.opacity,
                     s.config.multi_monitor,
-                    &s.config.per_display_opacity,
+                    &s.config.monitor_opacity,
                 );
             }
         }
 
         #[unsafe(method(monitorSliderChanged:))]
         fn monitor_slider_changed(&self, sender: &NSSlider) {
+            explain::note(Cause::Settings);
             let tag: isize = unsafe { msg_send![sender, tag] };
             let monitor_idx = tag as usize;
-            let val = sender.floatValue();
-            let clamped = (val / 100.0).clamp(0.0, 0.9);
+            let clamped = slider_level(sender);
 
             // Update the label for this monitor
-            let labels = MONITOR_LABEL_REFS.lock().unwrap();
+            let labels = MONITOR_LABEL_REFS.lock();
             if let Some(label) = labels.get(monitor_idx) {
-                let pct = (clamped * 100.0).round() as i32;
-                label.setStringValue(&NSString::from_str(&format!("{}%", pct)));
+                set_level_text(label, clamped);
             }
             drop(labels);
 
-            // Get display name for this index
-            let display_name = MONITOR_NAMES.lock().unwrap().get(monitor_idx).cloned();
+            // Get the monitor id for this index
+            let ids = MONITOR_IDS.lock().unwrap().clone();
+            let id = ids.get(monitor_idx).cloned();
 
             let st = app::state();
             let mut s = st.lock().unwrap();
-            // Store by display name for persistence
-            if let Some(name) = &display_name {
-                s.config.per_display_opacity.insert(name.clone(), clamped);
-            }
+            // Store by monitor id for persistence, for the whole group if
+            // it is in one
+            let linked = match id {
+                Some(id) => s.config.set_level_for(&id, clamped),
+                None => Vec::new(),
+            };
             s.config.is_enabled = true;
             // Auto-enable multi-monitor if user interacts with secondary monitor slider
             if monitor_idx > 0 {
                 s.config.multi_monitor = true;
             }
             // Also update global opacity to match primary monitor
-            if monitor_idx == 0 {
+            if monitor_idx == 0 || ids.first().is_some_and(|primary| linked.contains(primary)) {
                 s.config.opacity = clamped;
             }
             config::save_config(&s.config);
 
             // Auto-enable the toggle
-            if let Some(toggle) = ENABLED_TOGGLE_REF.lock().unwrap().as_ref() {
+            if let Some(toggle) = ENABLED_TOGGLE_REF.lock().as_ref() {
                 toggle.setState(NSControlStateValueOn);
                 style_toggle(toggle, true);
             }
 
             // Update existing overlays in-place (no flicker) or create if needed
             let mtm = MainThreadMarker::new().unwrap();
-            if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.per_display_opacity) {
+            if !overlay::update_opacity(mtm, s.config.opacity, s.config.multi_monitor, &s.config.monitor_opacity) {
                 overlay::show(
                     mtm,
                     s.config.opacity,
                     s.config.multi_monitor,
-                    &s.config.per_display_opacity,
+                    &s.config.monitor_opacity,
                 );
             }
+            drop(s);
+            // The other members' sliders and the group row follow
+            if linked.len() > 1 {
+                update_ui();
+            }
+        }
+
+        #[unsafe(method(groupSliderChanged:))]
+        fn group_slider_changed(&self, sender: &NSSlider) {
+            explain::note(Cause::Settings);
+            let tag: isize = unsafe { msg_send![sender, tag] };
+            let Some(name) = GROUP_NAMES.lock().unwrap().get(tag as usize).cloned() else {
+                return;
+            };
+            let clamped = slider_level(sender);
+            let primary = MONITOR_IDS.lock().unwrap().first().cloned();
+
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            let Some(first) = s
+                .config
+                .monitor_groups
+                .iter()
+                .find(|group| group.name == name)
+                .and_then(|group| group.monitors.first().cloned())
+            else {
+                return;
+            };
+            let linked = s.config.set_level_for(&first, clamped);
+            if primary.is_some_and(|primary| linked.contains(&primary)) {
+                s.config.opacity = clamped;
+            }
+            s.config.is_enabled = true;
+            s.config.multi_monitor = true;
+            config::save_config(&s.config);
+
+            if let Some(toggle) = ENABLED_TOGGLE_REF.lock().as_ref() {
+                toggle.setState(NSControlStateValueOn);
+                style_toggle(toggle, true);
+            }
+
+            let mtm = MainThreadMarker::new().unwrap();
+            if !overlay::update_opacity(mtm, s.config.opacity, true, &s.config.monitor_opacity) {
+                overlay::show(mtm, s.config.opacity, true, &s.config.monitor_opacity);
+            }
+            drop(s);
+            update_ui();
+        }
+
+        #[unsafe(method(monitorSelected:))]
+        fn monitor_selected(&self, sender: &NSButton) {
+            let tag: isize = unsafe { msg_send![sender, tag] };
+            let mut selected = MONITOR_SELECTED.lock().unwrap();
+            if let Some(ticked) = selected.get_mut(tag as usize) {
+                *ticked = sender.state() == NSControlStateValueOn;
+            }
+            let count = selected.iter().filter(|&&ticked| ticked).count();
+            if let Some(button) = GROUP_BUTTON_REF.lock().as_ref() {
+                button.setEnabled(count >= 2);
+            }
+        }
+
+        #[unsafe(method(groupMonitorsClicked:))]
+        fn group_monitors_clicked(&self, _sender: &NSButton) {
+            let ids = MONITOR_IDS.lock().unwrap().clone();
+            let picked: Vec<MonitorId> = MONITOR_SELECTED
+                .lock()
+                .unwrap()
+                .iter()
+                .zip(&ids)
+                .filter(|(&ticked, _)| ticked)
+                .map(|(_, id)| id.clone())
+                .collect();
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            let Some(name) = monitor_groups::group(&mut s.config.monitor_groups, &picked) else {
+                return;
+            };
+            // Members start from the level of the first one
+            let level = s.config.level_for(&picked[0]);
+            s.config.set_level_for(&picked[0], level);
+            config::save_config(&s.config);
+            let mtm = MainThreadMarker::new().unwrap();
+            if s.config.is_enabled && s.config.multi_monitor {
+                overlay::update_opacity(mtm, s.config.opacity, true, &s.config.monitor_opacity);
+            }
+            drop(s);
+            crate::hud::show(mtm, &format!("{}: {} monitors", name, picked.len()));
+            // Rebuild once this action has returned; the window goes away
+            app::run_on_main(|| crate::ui::rebuild_settings(MainThreadMarker::new().unwrap()));
+        }
+
+        #[unsafe(method(ungroupClicked:))]
+        fn ungroup_clicked(&self, sender: &NSButton) {
+            let tag: isize = unsafe { msg_send![sender, tag] };
+            let Some(name) = GROUP_NAMES.lock().unwrap().get(tag as usize).cloned() else {
+                return;
+            };
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            monitor_groups::ungroup(&mut s.config.monitor_groups, &name);
+            config::save_config(&s.config);
+            drop(s);
+            crate::hud::show(MainThreadMarker::new().unwrap(), &format!("{}: ungrouped", name));
+            app::run_on_main(|| crate::ui::rebuild_settings(MainThreadMarker::new().unwrap()));
         }
 
         #[unsafe(method(enabledToggled:))]
         fn enabled_toggled(&self, sender: &NSButton) {
+            explain::note(Cause::Settings);
             let checked = sender.state() == NSControlStateValueOn;
             style_toggle(sender, checked);
             let st = app::state();
@@ -200,25 +695,25 @@ define_class!(
                 s.config.opacity = s.config.last_opacity;
                 config::save_config(&s.config);
                 let mtm = MainThreadMarker::new().unwrap();
-                overlay::show(
+                overlay::fade_in(
                     mtm,
                     s.config.opacity,
                     s.config.multi_monitor,
-                    &s.config.per_display_opacity,
+                    &s.config.monitor_opacity,
+                    &s.config.fade,
                 );
             } else {
                 s.config.last_opacity = s.config.opacity;
                 s.config.is_enabled = false;
                 config::save_config(&s.config);
-                overlay::hide();
+                overlay::fade_out(&s.config.fade);
             }
 
-            if let Some(slider) = SLIDER_REF.lock().unwrap().as_ref() {
+            if let Some(slider) = SLIDER_REF.lock().as_ref() {
                 slider.setFloatValue(s.config.opacity * 100.0);
             }
-            if let Some(label) = SLIDER_LABEL_REF.lock().unwrap().as_ref() {
-                let pct = (s.config.opacity * 100.0).round() as i32;
-                label.setStringValue(&NSString::from_str(&format!("{}%", pct)));
+            if let Some(label) = SLIDER_LABEL_REF.lock().as_ref() {
+                set_level_text(label, s.config.opacity);
             }
         }
 
@@ -252,11 +747,245 @@ define_class!(
                     mtm,
                     s.config.opacity,
                     s.config.multi_monitor,
-                    &s.config.per_display_opacity,
+                    &s.config.monitor_opacity,
                 );
             }
         }
 
+        #[unsafe(method(mouseHotkeysToggled:))]
+        fn mouse_hotkeys_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.mouse_hotkeys.enabled = checked;
+            config::save_config(&s.config);
+            crate::hotkeys::apply_mouse_hotkeys(&s.config.mouse_hotkeys);
+        }
+
+        #[unsafe(method(penPauseToggled:))]
+        fn pen_pause_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.pen_pause.enabled = checked;
+            config::save_config(&s.config);
+            crate::pen_pause::apply(&s.config.pen_pause);
+        }
+
+        #[unsafe(method(nightBoostToggled:))]
+        fn night_boost_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.night_boost.enabled = checked;
+            config::save_config(&s.config);
+            crate::night_boost::apply(&s.config.night_boost);
+        }
+
+        #[unsafe(method(brightnessRuleToggled:))]
+        fn brightness_rule_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.brightness_rule.enabled = checked;
+            config::save_config(&s.config);
+            crate::brightness::apply(&s.config.brightness_rule);
+        }
+
+        #[unsafe(method(contrastGuardToggled:))]
+        fn contrast_guard_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.contrast_guard.enabled = checked;
+            config::save_config(&s.config);
+            crate::contrast_guard::apply(&s.config.contrast_guard);
+        }
+
+        #[unsafe(method(scheduleToggled:))]
+        fn schedule_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.schedule.enabled = checked;
+            config::save_config(&s.config);
+            scheduler::apply(&s.config.schedule);
+        }
+
+        #[unsafe(method(scheduleDaysChanged:))]
+        fn schedule_days_changed(&self, sender: &NSSegmentedControl) {
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.schedule.days = scheduler::WEEK
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| sender.isSelectedForSegment(*i as isize))
+                .map(|(_, day)| day)
+                .collect();
+            config::save_config(&s.config);
+            scheduler::apply(&s.config.schedule);
+            if let Some(label) = SCHEDULE_LABEL_REF.lock().as_ref() {
+                label.setStringValue(&NSString::from_str(&s.config.schedule.summary()));
+            }
+        }
+
+        #[unsafe(method(shortcutClicked:))]
+        fn shortcut_clicked(&self, sender: &NSButton) {
+            let row = sender.tag() as usize;
+            if row < RECORDABLE.len() {
+                start_recording(row);
+            }
+        }
+
+        #[unsafe(method(levelChordToggled:))]
+        fn level_chord_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.level_chord = checked;
+            config::save_config(&s.config);
+            if !checked {
+                crate::hotkeys::end_level_chord();
+            }
+        }
+
+        #[unsafe(method(disableShortcutsToggled:))]
+        fn disable_shortcuts_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let st = app::state();
+            let mut s = st.lock().unwrap();
+            s.config.disable_global_shortcuts = checked;
+            config::save_config(&s.config);
+            crate::hotkeys::set_global_input_disabled(checked, &s.config.mouse_hotkeys);
+            crate::pen_pause::apply(&s.config.pen_pause);
+        }
+
+        #[unsafe(method(perceptualToggled:))]
+        fn perceptual_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let curve = if checked {
+                OpacityCurve::Perceptual
+            } else {
+                OpacityCurve::Linear
+            };
+            {
+                let st = app::state();
+                let mut s = st.lock().unwrap();
+                if !dimming::switch_curve(&mut s.config, curve) {
+                    return;
+                }
+                config::save_config(&s.config);
+                overlay::set_curve(curve);
+
+                // Stored levels were converted, so re-applying leaves the
+                // screen exactly as bright as before.
+                if overlay::is_visible() {
+                    let mtm = MainThreadMarker::new().unwrap();
+                    overlay::show(
+                        mtm,
+                        s.config.opacity,
+                        s.config.multi_monitor,
+                        &s.config.monitor_opacity,
+                    );
+                }
+            }
+            update_ui();
+            crate::tray::update_menu(MainThreadMarker::new().unwrap());
+        }
+
+        #[unsafe(method(themeAutomationToggled:))]
+        fn theme_automation_toggled(&self, sender: &NSButton) {
+            let checked = sender.state() == NSControlStateValueOn;
+            style_toggle(sender, checked);
+            let mtm = MainThreadMarker::new().unwrap();
+            {
+                let st = app::state();
+                let mut s = st.lock().unwrap();
+                if checked {
+                    // Seeds Dark/Light profiles on first use
+                    profiles::enable_theme_automation(&mut s.config);
+                } else {
+                    s.config.theme_automation.enabled = false;
+                }
+                config::save_config(&s.config);
+                sync_theme_controls(&s.config);
+            }
+            if checked {
+                app::apply_theme_profile(crate::system_theme::is_dark(mtm));
+            }
+        }
+
+        #[unsafe(method(themeProfileChanged:))]
+        fn theme_profile_changed(&self, sender: &NSPopUpButton) {
+            let mtm = MainThreadMarker::new().unwrap();
+            let is_dark_popup = THEME_POPUP_REFS
+                .lock()
+                .as_ref()
+                .is_some_and(|p| std::ptr::eq(&*p[0], sender));
+            {
+                let st = app::state();
+                let mut s = st.lock().unwrap();
+                let choice = profiles::choices(&s.config)
+                    .get(sender.indexOfSelectedItem().max(0) as usize)
+                    .cloned()
+                    .flatten();
+                if is_dark_popup {
+                    s.config.theme_automation.dark_profile = choice;
+                } else {
+                    s.config.theme_automation.light_profile = choice;
+                }
+                config::save_config(&s.config);
+            }
+            // Re-apply if the popup for the current theme changed
+            if crate::system_theme::is_dark(mtm) == is_dark_popup {
+                app::apply_theme_profile(is_dark_popup);
+            }
+        }
+
+        #[unsafe(method(newCustomAction:))]
+        fn new_custom_action(&self, _sender: &NSMenuItem) {
+            let message = {
+                let st = app::state();
+                let mut s = st.lock().unwrap();
+                let action = CustomAction::new(&s.config.custom_actions);
+                let message = if action.hotkey.is_empty() {
+                    format!("{} added", action.name)
+                } else {
+                    format!("{} added, runs on {}", action.name, action.hotkey)
+                };
+                s.config.custom_actions.push(action);
+                message
+            };
+            custom_actions_changed(Some(message));
+        }
+
+        #[unsafe(method(customActionEdited:))]
+        fn custom_action_edited(&self, sender: &NSMenuItem) {
+            let edit = CUSTOM_ACTION_EDITS
+                .lock()
+                .unwrap()
+                .get(sender.tag() as usize)
+                .cloned();
+            let Some((index, edit)) = edit else {
+                return;
+            };
+            let message = {
+                let st = app::state();
+                let mut s = st.lock().unwrap();
+                edit.apply(&mut s.config.custom_actions, index)
+            };
+            custom_actions_changed(message);
+        }
+
         #[unsafe(method(autoUpdateToggled:))]
         fn auto_update_toggled(&self, sender: &NSButton) {
             let checked = sender.state() == NSControlStateValueOn;
@@ -269,16 +998,17 @@ define_class!(
 
         #[unsafe(method(checkForUpdatesClicked:))]
         fn check_for_updates_clicked(&self, _sender: &NSButton) {
-            std::thread::spawn(|| {
-                let result = crate::updater::check_for_update(crate::updater::APP_VERSION);
+            shutdown::spawn("update-check", || {
+                let result = crate::updater::check_for_update_now(crate::updater::APP_VERSION);
                 app::run_on_main(move || {
                     match result {
                         crate::updater::UpdateResult::UpdateAvailable {
                             version,
                             download_url,
+                            held_back,
                             ..
                         } => {
-                            crate::ui::prompt_update(&version, &download_url);
+                            crate::ui::prompt_update(&version, &download_url, held_back);
                         }
                         crate::updater::UpdateResult::NoUpdate => {
                             crate::ui::show_alert(
@@ -297,10 +1027,40 @@ define_class!(
             });
         }
 
+        #[unsafe(method(copyDisplayReport:))]
+        fn copy_display_report(&self, _sender: &NSButton) {
+            let mtm = MainThreadMarker::new().unwrap();
+            let st = app::state();
+            let cfg = st.lock().unwrap().config.clone();
+            let mut report = savemyeyes_shared::displays::build_report(
+                "macOS",
+                &overlay::display_infos(mtm),
+                &cfg,
+            );
+            let mut hooks = crate::hotkeys::installed_hooks();
+            if crate::pen_pause::is_installed() {
+                hooks.push("tablet NSEvent monitor");
+            }
+            report.push('\n');
+            report.push_str(&savemyeyes_shared::displays::input_hooks_line(
+                &hooks,
+                cfg.disable_global_shortcuts,
+            ));
+            report.push_str(&overlay::capabilities().report_line());
+            let pasteboard = NSPasteboard::generalPasteboard();
+            pasteboard.clearContents();
+            let copied = pasteboard
+                .setString_forType(&NSString::from_str(&report), unsafe { NSPasteboardTypeString });
+            if !copied {
+                crate::ui::show_alert("Copy Failed", "Could not write to the clipboard.");
+            }
+        }
+
         #[unsafe(method(tabChanged:))]
         fn tab_changed(&self, sender: &NSSegmentedControl) {
+            stop_recording();
             let idx = sender.selectedSegment();
-            if let Some(views) = TAB_VIEWS.lock().unwrap().as_ref() {
+            if let Some(views) = TAB_VIEWS.lock().as_ref() {
                 for (i, view) in views.iter().enumerate() {
                     view.setHidden(i as isize != idx);
                 }
@@ -338,7 +1098,8 @@ impl SettingsTarget {
 
 /// Rebuild the settings window if it is currently open (e.g. after monitor change).
 pub fn rebuild_settings(mtm: MainThreadMarker) {
-    let mut guard = SETTINGS_WINDOW.lock().unwrap();
+    stop_recording();
+    let mut guard = SETTINGS_WINDOW.lock();
     if guard.is_some() {
         // Close the existing window
         if let Some(ref win) = *guard {
@@ -352,7 +1113,7 @@ pub fn rebuild_settings(mtm: MainThreadMarker) {
 }
 
 pub fn show_settings(mtm: MainThreadMarker) {
-    let mut guard = SETTINGS_WINDOW.lock().unwrap();
+    let mut guard = SETTINGS_WINDOW.lock();
 
     if let Some(ref window) = *guard {
         // Bring window to front even if behind other windows
@@ -500,6 +1261,7 @@ pub fn show_settings(mtm: MainThreadMarker) {
                 NSString::from_str("Dimmer"),
                 NSString::from_str("Settings"),
                 NSString::from_str("Shortcuts"),
+                NSString::from_str("Advanced"),
             ]),
             NSSegmentSwitchTracking::SelectOne,
             Some(&target as &AnyObject),
@@ -514,10 +1276,10 @@ pub fn show_settings(mtm: MainThreadMarker) {
     seg.setSegmentStyle(NSSegmentStyle::Capsule);
     seg.setSelectedSegment(0);
     // Equal width for all segments
-    let seg_w = CONTENT_W / 3.0;
-    seg.setWidth_forSegment(seg_w, 0);
-    seg.setWidth_forSegment(seg_w, 1);
-    seg.setWidth_forSegment(seg_w, 2);
+    let seg_w = CONTENT_W / 4.0;
+    for i in 0..4 {
+        seg.setWidth_forSegment(seg_w, i);
+    }
     content.addSubview(&seg);
     y -= GAP;
 
@@ -530,21 +1292,31 @@ pub fn show_settings(mtm: MainThreadMarker) {
 
     let dimmer_view = build_dimmer_tab(mtm, &cfg, &target, tab_frame);
     let settings_view = build_settings_tab(mtm, &cfg, &target, tab_frame);
-    let shortcuts_view = build_shortcuts_tab(mtm, tab_frame);
+    let shortcuts_view = build_shortcuts_tab(mtm, &cfg, &target, tab_frame);
+    let advanced_view = build_advanced_tab(mtm, &cfg, &target, tab_frame);
 
     // Only dimmer tab visible initially
     settings_view.setHidden(true);
     shortcuts_view.setHidden(true);
+    advanced_view.setHidden(true);
 
     content.addSubview(&dimmer_view);
     content.addSubview(&settings_view);
     content.addSubview(&shortcuts_view);
+    content.addSubview(&advanced_view);
 
-    *TAB_VIEWS.lock().unwrap() = Some(Mt([
+    *TAB_VIEWS.lock() = Some([
         dimmer_view.clone(),
         settings_view.clone(),
         shortcuts_view.clone(),
-    ]));
+        advanced_view.clone(),
+    ]);
+
+    // Right-to-left: the frames above are laid out left to right and
+    // flipped as a whole
+    if locale::current().rtl {
+        mirror(&content);
+    }
 
     window.setContentView(Some(&content));
 
@@ -553,7 +1325,7 @@ pub fn show_settings(mtm: MainThreadMarker) {
     window.orderFrontRegardless();
     window.makeKeyAndOrderFront(None);
 
-    *guard = Some(Mt(window));
+    *guard = Some(window);
 }
 
 // ===========================================================================
@@ -577,110 +1349,197 @@ fn build_dimmer_tab(
     let display_names = overlay::screen_names(mtm);
     let monitor_count = display_names.len().max(1);
 
-    // Store display names for handler lookup
-    *MONITOR_NAMES.lock().unwrap() = display_names.clone();
+    // Store monitor ids for handler lookup
+    let ids = overlay::screen_ids(mtm);
+    *MONITOR_IDS.lock().unwrap() = ids.clone();
 
     // Clear per-monitor refs
-    MONITOR_SLIDER_REFS.lock().unwrap().clear();
-    MONITOR_LABEL_REFS.lock().unwrap().clear();
+    MONITOR_SLIDER_REFS.lock().clear();
+    MONITOR_LABEL_REFS.lock().clear();
 
     let card_h = 110.0;
     let mut current_y = top;
 
     // ── Per-monitor dimming cards ───────────────────────────────────────
-    for idx in 0..monitor_count {
-        current_y -= card_h;
-        let card = make_card(mtm, 0.0, current_y, w, card_h);
-
-        // Title: use actual display name, truncated if too long
-        let raw_name = display_names.get(idx).cloned().unwrap_or_else(|| format!("Monitor {}", idx + 1));
-        let title_text = if monitor_count == 1 {
-            "Dimming Level".to_string()
-        } else {
-            let truncated = if raw_name.len() > 22 {
-                format!("{}…", &raw_name[..21])
+    // Each group's row comes first with its monitors below it, then the
+    // monitors in no group (monitor_groups.rs)
+    let grouping = monitor_count > 2;
+    *MONITOR_SELECTED.lock().unwrap() = vec![false; monitor_count];
+    GROUP_SLIDER_REFS.lock().clear();
+    GROUP_LABEL_REFS.lock().clear();
+    let mut group_names = Vec::new();
+    let mut rows = monitor_groups::layout(&cfg.monitor_groups, &ids);
+    if rows.is_empty() {
+        rows.push((None, vec![0]));
+    }
+    for (group, members) in rows {
+        if let Some(group) = group.map(|g| &cfg.monitor_groups[g]) {
+            current_y -= GROUP_CARD_H;
+            let tag = group_names.len();
+            let card = build_group_card(mtm, cfg, target, group, tag, current_y, w);
+            container.addSubview(&card);
+            group_names.push(group.name.clone());
+            current_y -= GAP;
+        }
+        for idx in members {
+            current_y -= card_h;
+            let card = make_card(mtm, 0.0, current_y, w, card_h);
+
+            // Title: use actual display name, truncated if too long
+            let raw_name = display_names.get(idx).cloned().unwrap_or_else(|| format!("Monitor {}", idx + 1));
+            let title_text = if monitor_count == 1 {
+                "Dimming Level".to_string()
             } else {
-                raw_name.clone()
+                let truncated = if raw_name.len() > 22 {
+                    format!("{}…", &raw_name[..21])
+                } else {
+                    raw_name.clone()
+                };
+                truncated
             };
-            truncated
-        };
-        let title = make_label(mtm, &title_text, FONT_SIZE_SMALL, true);
-        title.setFrame(NSRect::new(
-            NSPoint::new(inner_pad, card_h - 14.0 - 16.0),
-            NSSize::new(200.0, 16.0),
-        ));
-        add_to_card(&card, &title);
-
-        // Per-display opacity (fallback to global) — lookup by display name
-        let opacity = cfg
-            .per_display_opacity
-            .get(&raw_name)
-            .copied()
-            .unwrap_or(cfg.opacity);
-        let pct = (opacity * 100.0).round() as i32;
-
-        // Percentage badge
-        let badge_w = 48.0;
-        let badge_h = 22.0;
-        let (badge_view, badge_label) = make_badge(mtm, &format!("{}%", pct), badge_w, badge_h);
-        badge_view.setFrame(NSRect::new(
-            NSPoint::new(inner_w + inner_pad - badge_w, card_h - 14.0 - 17.0),
-            NSSize::new(badge_w, badge_h),
-        ));
-        add_to_card(&card, &badge_view);
-
-        // Slider
-        let slider_y = card_h - 58.0;
-        let slider = NSSlider::initWithFrame(
-            mtm.alloc::<NSSlider>(),
-            NSRect::new(
-                NSPoint::new(inner_pad, slider_y),
-                NSSize::new(inner_w, 24.0),
-            ),
-        );
-        slider.setMinValue(0.0);
-        slider.setMaxValue(90.0);
-        slider.setFloatValue(opacity * 100.0);
-        slider.setContinuous(true);
-        // Tag identifies which monitor this slider controls
-        let _: () = unsafe { msg_send![&slider, setTag: idx as isize] };
-        unsafe {
-            slider.setTarget(Some(target as &AnyObject));
-            slider.setAction(Some(sel!(monitorSliderChanged:)));
-        }
-        add_to_card(&card, &slider);
+            // Tick box for grouping, left of the title
+            let title_x = if grouping {
+                let tick = unsafe {
+                    NSButton::checkboxWithTitle_target_action(
+                        &NSString::from_str(""),
+                        Some(target as &AnyObject),
+                        Some(sel!(monitorSelected:)),
+                        mtm,
+                    )
+                };
+                let _: () = unsafe { msg_send![&tick, setTag: idx as isize] };
+                tick.setEnabled(!config::is_locked("monitor_groups"));
+                tick.setFrame(NSRect::new(
+                    NSPoint::new(inner_pad, card_h - 14.0 - 17.0),
+                    NSSize::new(18.0, 18.0),
+                ));
+                add_to_card(&card, &tick);
+                inner_pad + 22.0
+            } else {
+                inner_pad
+            };
+            let title = make_label(mtm, &title_text, FONT_SIZE_SMALL, true);
+            title.setFrame(NSRect::new(
+                NSPoint::new(title_x, card_h - 14.0 - 16.0),
+                NSSize::new(200.0, 16.0),
+            ));
+            add_to_card(&card, &title);
+
+            // Per-display opacity (fallback to global) — lookup by monitor id
+            let opacity = ids.get(idx).map_or(cfg.opacity, |id| cfg.level_for(id));
+
+            // Percentage badge
+            let badge_w = BADGE_W;
+            let badge_h = 22.0;
+            let (badge_view, badge_label) =
+                make_badge(mtm, &dimming::percent_label(opacity), badge_w, badge_h);
+            badge_view.setFrame(NSRect::new(
+                NSPoint::new(inner_w + inner_pad - badge_w, card_h - 14.0 - 17.0),
+                NSSize::new(badge_w, badge_h),
+            ));
+            add_to_card(&card, &badge_view);
+            set_level_text(&badge_label, opacity);
+
+            // Slider
+            let slider_y = card_h - 58.0;
+            let slider = NSSlider::initWithFrame(
+                mtm.alloc::<NSSlider>(),
+                NSRect::new(
+                    NSPoint::new(inner_pad, slider_y),
+                    NSSize::new(inner_w, 24.0),
+                ),
+            );
+            slider.setMinValue(0.0);
+            slider.setMaxValue(90.0);
+            slider.setFloatValue(opacity * 100.0);
+            slider.setContinuous(true);
+            // Tag identifies which monitor this slider controls
+            let _: () = unsafe { msg_send![&slider, setTag: idx as isize] };
+            unsafe {
+                slider.setTarget(Some(target as &AnyObject));
+                slider.setAction(Some(sel!(monitorSliderChanged:)));
+            }
+            add_to_card(&card, &slider);
 
-        // Store per-monitor refs
-        MONITOR_SLIDER_REFS.lock().unwrap().push(Mt(slider.clone()));
-        MONITOR_LABEL_REFS.lock().unwrap().push(Mt(badge_label.clone()));
+            // Store per-monitor refs
+            MONITOR_SLIDER_REFS.lock().push(slider.clone());
+            MONITOR_LABEL_REFS.lock().push(badge_label.clone());
 
-        // Monitor 0 is also the "global" slider
-        if idx == 0 {
-            *SLIDER_REF.lock().unwrap() = Some(Mt(slider.clone()));
-            *SLIDER_LABEL_REF.lock().unwrap() = Some(Mt(badge_label.clone()));
-        }
+            // Monitor 0 is also the "global" slider
+            if idx == 0 {
+                *SLIDER_REF.lock() = Some(slider.clone());
+                *SLIDER_LABEL_REF.lock() = Some(badge_label.clone());
+            }
+
+            // Range labels
+            let range_y = slider_y - 16.0;
+            let min_lbl = make_label(mtm, "0%", FONT_SIZE_XS, false);
+            min_lbl.setTextColor(Some(&color(CLR_MUTED)));
+            min_lbl.setFrame(NSRect::new(
+                NSPoint::new(inner_pad, range_y),
+                NSSize::new(40.0, 14.0),
+            ));
+            add_to_card(&card, &min_lbl);
+
+            let max_lbl = make_label(mtm, "90%", FONT_SIZE_XS, false);
+            max_lbl.setTextColor(Some(&color(CLR_MUTED)));
+            max_lbl.setAlignment(NSTextAlignment::Right);
+            max_lbl.setFrame(NSRect::new(
+                NSPoint::new(inner_w + inner_pad - 40.0, range_y),
+                NSSize::new(40.0, 14.0),
+            ));
+            add_to_card(&card, &max_lbl);
 
-        // Range labels
-        let range_y = slider_y - 16.0;
-        let min_lbl = make_label(mtm, "0%", FONT_SIZE_XS, false);
-        min_lbl.setTextColor(Some(&color(CLR_MUTED)));
-        min_lbl.setFrame(NSRect::new(
-            NSPoint::new(inner_pad, range_y),
-            NSSize::new(40.0, 14.0),
+            container.addSubview(&card);
+            current_y -= GAP;
+        }
+    }
+    *GROUP_NAMES.lock().unwrap() = group_names;
+
+    // ── Monitor groups card: group the ticked monitors ──────────────────
+    if grouping {
+        let groups_h = 56.0;
+        current_y -= groups_h;
+        let card = make_card(mtm, 0.0, current_y, w, groups_h);
+        let text_top = (groups_h + 30.0) / 2.0;
+        let groups_title = make_label(mtm, "Monitor Groups", FONT_SIZE_SMALL, true);
+        groups_title.setFrame(NSRect::new(
+            NSPoint::new(inner_pad, text_top - 14.0),
+            NSSize::new(200.0, 14.0),
+        ));
+        add_to_card(&card, &groups_title);
+        let groups_desc = make_label(
+            mtm,
+            "Tick monitors to dim them with one slider",
+            FONT_SIZE_XS,
+            false,
+        );
+        groups_desc.setTextColor(Some(&color(CLR_MUTED)));
+        groups_desc.setFrame(NSRect::new(
+            NSPoint::new(inner_pad, text_top - 30.0),
+            NSSize::new(280.0, 14.0),
         ));
-        add_to_card(&card, &min_lbl);
-
-        let max_lbl = make_label(mtm, "90%", FONT_SIZE_XS, false);
-        max_lbl.setTextColor(Some(&color(CLR_MUTED)));
-        max_lbl.setAlignment(NSTextAlignment::Right);
-        max_lbl.setFrame(NSRect::new(
-            NSPoint::new(inner_w + inner_pad - 40.0, range_y),
-            NSSize::new(40.0, 14.0),
+        add_to_card(&card, &groups_desc);
+        let group_btn = unsafe {
+            NSButton::buttonWithTitle_target_action(
+                &NSString::from_str("Group Selected"),
+                Some(target as &AnyObject),
+                Some(sel!(groupMonitorsClicked:)),
+                mtm,
+            )
+        };
+        group_btn.setBezelStyle(NSBezelStyle::Push);
+        group_btn.setEnabled(false);
+        group_btn.setFrame(NSRect::new(
+            NSPoint::new(w - inner_pad - 130.0, groups_h / 2.0 - 14.0),
+            NSSize::new(130.0, 28.0),
         ));
-        add_to_card(&card, &max_lbl);
-
+        add_to_card(&card, &group_btn);
+        *GROUP_BUTTON_REF.lock() = Some(group_btn);
         container.addSubview(&card);
         current_y -= GAP;
+    } else {
+        *GROUP_BUTTON_REF.lock() = None;
     }
 
     // ── Dimmer Enabled card ─────────────────────────────────────────────
@@ -713,13 +1572,94 @@ fn build_dimmer_tab(
         NSSize::new(TOGGLE_W, TOGGLE_H),
     ));
     add_to_card(&card2, &toggle);
-    *ENABLED_TOGGLE_REF.lock().unwrap() = Some(Mt(toggle.clone()));
+    *ENABLED_TOGGLE_REF.lock() = Some(toggle.clone());
 
     container.addSubview(&card2);
 
     container
 }
 
+/// Row of monitor group `group` (tag `tag`): its name, one slider for all
+/// of its monitors and an Ungroup button
+fn build_group_card(
+    mtm: MainThreadMarker,
+    cfg: &config::AppConfig,
+    target: &SettingsTarget,
+    group: &monitor_groups::MonitorGroup,
+    tag: usize,
+    y: f64,
+    w: f64,
+) -> Retained<NSBox> {
+    let card_h = GROUP_CARD_H;
+    let inner_pad = 20.0;
+    let inner_w = w - inner_pad * 2.0;
+    let card = make_card(mtm, 0.0, y, w, card_h);
+
+    let title_text = format!("{} ({} monitors)", group.name, group.monitors.len());
+    let title = make_label(mtm, &title_text, FONT_SIZE_SMALL, true);
+    title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, card_h - 14.0 - 16.0),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card, &title);
+
+    let opacity = group
+        .monitors
+        .first()
+        .map_or(cfg.opacity, |id| cfg.level_for(id));
+    let badge_w = BADGE_W;
+    let badge_h = 22.0;
+    let (badge_view, badge_label) =
+        make_badge(mtm, &dimming::percent_label(opacity), badge_w, badge_h);
+    badge_view.setFrame(NSRect::new(
+        NSPoint::new(inner_w + inner_pad - badge_w, card_h - 14.0 - 17.0),
+        NSSize::new(badge_w, badge_h),
+    ));
+    add_to_card(&card, &badge_view);
+    set_level_text(&badge_label, opacity);
+
+    let ungroup_btn = unsafe {
+        NSButton::buttonWithTitle_target_action(
+            &NSString::from_str("Ungroup"),
+            Some(target as &AnyObject),
+            Some(sel!(ungroupClicked:)),
+            mtm,
+        )
+    };
+    ungroup_btn.setBezelStyle(NSBezelStyle::Push);
+    let _: () = unsafe { msg_send![&ungroup_btn, setTag: tag as isize] };
+    ungroup_btn.setEnabled(!config::is_locked("monitor_groups"));
+    ungroup_btn.setFrame(NSRect::new(
+        NSPoint::new(
+            inner_w + inner_pad - badge_w - 8.0 - 90.0,
+            card_h - 14.0 - 20.0,
+        ),
+        NSSize::new(90.0, 28.0),
+    ));
+    add_to_card(&card, &ungroup_btn);
+
+    let slider = NSSlider::initWithFrame(
+        mtm.alloc::<NSSlider>(),
+        NSRect::new(
+            NSPoint::new(inner_pad, card_h - 66.0),
+            NSSize::new(inner_w, 24.0),
+        ),
+    );
+    slider.setMinValue(0.0);
+    slider.setMaxValue(90.0);
+    slider.setFloatValue(opacity * 100.0);
+    slider.setContinuous(true);
+    let _: () = unsafe { msg_send![&slider, setTag: tag as isize] };
+    unsafe {
+        slider.setTarget(Some(target as &AnyObject));
+        slider.setAction(Some(sel!(groupSliderChanged:)));
+    }
+    add_to_card(&card, &slider);
+
+    GROUP_SLIDER_REFS.lock().push(slider);
+    GROUP_LABEL_REFS.lock().push(badge_label);
+    card
+}
 fn build_settings_tab(
     mtm: MainThreadMarker,
     cfg: &config::AppConfig,
@@ -774,6 +1714,13 @@ fn build_settings_tab(
         NSPoint::new(w - inner_pad - TOGGLE_W, r2_center - TOGGLE_H / 2.0 + 1.0),
         NSSize::new(TOGGLE_W, TOGGLE_H),
     ));
+    let capabilities = overlay::capabilities();
+    if !capabilities.per_monitor {
+        mm_toggle.setEnabled(false);
+        mm_toggle.setToolTip(Some(&NSString::from_str(
+            Feature::PerMonitor.unsupported_reason(),
+        )));
+    }
     add_to_card(&card1, &mm_toggle);
 
     // Divider
@@ -799,6 +1746,8 @@ fn build_settings_tab(
     add_to_card(&card1, &login_desc);
 
     let login_toggle = make_switch(mtm, target, sel!(autostartToggled:), cfg.launch_on_login);
+    // Pinned by a managed deployment's defaults.json
+    login_toggle.setEnabled(!config::is_locked("launch_on_login"));
     login_toggle.setFrame(NSRect::new(
         NSPoint::new(w - inner_pad - TOGGLE_W, r1_center - TOGGLE_H / 2.0 + 1.0),
         NSSize::new(TOGGLE_W, TOGGLE_H),
@@ -894,17 +1843,163 @@ fn build_settings_tab(
     add_to_card(&card2, &au_desc);
 
     let au_toggle = make_switch(mtm, target, sel!(autoUpdateToggled:), cfg.auto_update);
+    au_toggle.setEnabled(!config::is_locked("auto_update"));
     au_toggle.setFrame(NSRect::new(
         NSPoint::new(w - inner_pad - TOGGLE_W, au_center - TOGGLE_H / 2.0 + 1.0),
         NSSize::new(TOGGLE_W, TOGGLE_H),
     ));
     add_to_card(&card2, &au_toggle);
 
-    container.addSubview(&card2);
+    // Builds without the updater leave the card out
+    let above_card3_y = if crate::updater::ENABLED {
+        container.addSubview(&card2);
+        card2_y
+    } else {
+        card1_y
+    };
+
+    // ── Card 3: Pause While Drawing ─────────────────────────────────────
+    let card3_h = 64.0;
+    let card3_y = above_card3_y - GAP - card3_h;
+    let card3 = make_card(mtm, 0.0, card3_y, w, card3_h);
+
+    let pen_center = card3_h / 2.0;
+    let pen_title = make_label(mtm, "Pause While Drawing", FONT_SIZE_SMALL, true);
+    pen_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, pen_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card3, &pen_title);
+
+    let pen_desc = make_label(mtm, &cfg.pen_pause.summary(), FONT_SIZE_XS, false);
+    pen_desc.setTextColor(Some(&color(CLR_MUTED)));
+    pen_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, pen_center - 16.0),
+        NSSize::new(280.0, 14.0),
+    ));
+    add_to_card(&card3, &pen_desc);
+
+    let pen_toggle = make_switch(mtm, target, sel!(penPauseToggled:), cfg.pen_pause.enabled);
+    pen_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, pen_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card3, &pen_toggle);
+
+    container.addSubview(&card3);
+
+    // ── Card 4: Contrast Guard ──────────────────────────────────────────
+    let card4_h = 64.0;
+    let card4_y = card3_y - GAP - card4_h;
+    let card4 = make_card(mtm, 0.0, card4_y, w, card4_h);
+
+    let guard_center = card4_h / 2.0;
+    let guard_title = make_label(mtm, "Contrast Guard", FONT_SIZE_SMALL, true);
+    guard_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, guard_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card4, &guard_title);
+
+    let guard_desc = make_label(mtm, &cfg.contrast_guard.summary(), FONT_SIZE_XS, false);
+    guard_desc.setTextColor(Some(&color(CLR_MUTED)));
+    guard_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, guard_center - 16.0),
+        NSSize::new(280.0, 14.0),
+    ));
+    add_to_card(&card4, &guard_desc);
+
+    let guard_toggle = make_switch(
+        mtm,
+        target,
+        sel!(contrastGuardToggled:),
+        cfg.contrast_guard.enabled,
+    );
+    guard_toggle.setFrame(NSRect::new(
+        NSPoint::new(
+            w - inner_pad - TOGGLE_W,
+            guard_center - TOGGLE_H / 2.0 + 1.0,
+        ),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card4, &guard_toggle);
+
+    container.addSubview(&card4);
+
+    // ── Card 5: Scheduled Dimming ───────────────────────────────────────
+    let card5_h = 100.0;
+    let card5_y = card4_y - GAP - card5_h;
+    let card5 = make_card(mtm, 0.0, card5_y, w, card5_h);
+
+    let schedule_center = card5_h - 32.0;
+    let schedule_title = make_label(mtm, "Scheduled Dimming", FONT_SIZE_SMALL, true);
+    schedule_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, schedule_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card5, &schedule_title);
+
+    let schedule_desc = make_label(mtm, &cfg.schedule.summary(), FONT_SIZE_XS, false);
+    schedule_desc.setTextColor(Some(&color(CLR_MUTED)));
+    schedule_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, schedule_center - 16.0),
+        NSSize::new(280.0, 14.0),
+    ));
+    add_to_card(&card5, &schedule_desc);
+    *SCHEDULE_LABEL_REF.lock() = Some(schedule_desc);
+
+    let schedule_toggle = make_switch(
+        mtm,
+        target,
+        sel!(scheduleToggled:),
+        cfg.schedule.enabled,
+    );
+    schedule_toggle.setFrame(NSRect::new(
+        NSPoint::new(
+            w - inner_pad - TOGGLE_W,
+            schedule_center - TOGGLE_H / 2.0 + 1.0,
+        ),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card5, &schedule_toggle);
+
+    // Day chips, Monday first
+    let day_labels: Vec<_> = scheduler::WEEK
+        .iter()
+        .map(|day| NSString::from_str(&day.to_string()))
+        .collect();
+    let schedule_days = unsafe {
+        NSSegmentedControl::segmentedControlWithLabels_trackingMode_target_action(
+            &NSArray::from_retained_slice(&day_labels),
+            NSSegmentSwitchTracking::SelectAny,
+            Some(target as &AnyObject),
+            Some(sel!(scheduleDaysChanged:)),
+            mtm,
+        )
+    };
+    let days_w = w - 2.0 * inner_pad;
+    schedule_days.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, 14.0),
+        NSSize::new(days_w, 24.0),
+    ));
+    schedule_days.setSegmentStyle(NSSegmentStyle::Capsule);
+    for (i, day) in scheduler::WEEK.iter().enumerate() {
+        schedule_days.setWidth_forSegment(days_w / 7.0, i as isize);
+        schedule_days.setSelected_forSegment(cfg.schedule.days.contains(day), i as isize);
+    }
+    add_to_card(&card5, &schedule_days);
+
+    // Builds without the schedule thread leave the card out
+    let above_quit_y = if scheduler::ENABLED {
+        container.addSubview(&card5);
+        card5_y
+    } else {
+        card4_y
+    };
 
     // ── Quit Button ─────────────────────────────────────────────────────
     let quit_btn_h = 36.0;
-    let quit_btn_y = card2_y - GAP - quit_btn_h;
+    let quit_btn_y = above_quit_y - GAP - quit_btn_h;
     let quit_btn = unsafe {
         NSButton::buttonWithTitle_target_action(
             &NSString::from_str("Quit SaveMyEyes"),
@@ -929,14 +2024,260 @@ fn build_settings_tab(
     container
 }
 
-fn build_shortcuts_tab(mtm: MainThreadMarker, frame: NSRect) -> Retained<NSView> {
+fn build_advanced_tab(
+    mtm: MainThreadMarker,
+    cfg: &config::AppConfig,
+    target: &SettingsTarget,
+    frame: NSRect,
+) -> Retained<NSView> {
+    let container = NSView::initWithFrame(mtm.alloc::<NSView>(), frame);
+    let w = frame.size.width;
+    let top = frame.size.height;
+    let inner_pad = 20.0;
+
+    // ── Card 1: Perceptual Scale ────────────────────────────────────────
+    let card1_h = 64.0;
+    let card1_y = top - card1_h;
+    let card1 = make_card(mtm, 0.0, card1_y, w, card1_h);
+
+    let curve_center = card1_h / 2.0;
+    let curve_title = make_label(mtm, "Perceptual Scale", FONT_SIZE_SMALL, true);
+    curve_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, curve_center),
+        NSSize::new(200.0, 16.0),
+    ));
+    add_to_card(&card1, &curve_title);
+
+    let curve_desc = make_label(
+        mtm,
+        "Even brightness steps across the slider",
+        FONT_SIZE_XS,
+        false,
+    );
+    curve_desc.setTextColor(Some(&color(CLR_MUTED)));
+    curve_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, curve_center - 16.0),
+        NSSize::new(250.0, 14.0),
+    ));
+    add_to_card(&card1, &curve_desc);
+
+    let curve_toggle = make_switch(
+        mtm,
+        target,
+        sel!(perceptualToggled:),
+        cfg.opacity_curve == OpacityCurve::Perceptual,
+    );
+    curve_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, curve_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card1, &curve_toggle);
+
+    container.addSubview(&card1);
+
+    // ── Card 2: Diagnostics ─────────────────────────────────────────────
+    let card2_h = 64.0;
+    let card2_y = card1_y - GAP - card2_h;
+    let card2 = make_card(mtm, 0.0, card2_y, w, card2_h);
+
+    let diag_center = card2_h / 2.0;
+    let diag_title = make_label(mtm, "Diagnostics", FONT_SIZE_SMALL, true);
+    diag_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, diag_center),
+        NSSize::new(200.0, 16.0),
+    ));
+    add_to_card(&card2, &diag_title);
+
+    let diag_desc = make_label(
+        mtm,
+        "Copy display layout for bug reports",
+        FONT_SIZE_XS,
+        false,
+    );
+    diag_desc.setTextColor(Some(&color(CLR_MUTED)));
+    diag_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, diag_center - 16.0),
+        NSSize::new(220.0, 14.0),
+    ));
+    add_to_card(&card2, &diag_desc);
+
+    let report_btn = unsafe {
+        NSButton::buttonWithTitle_target_action(
+            &NSString::from_str("Copy Report"),
+            Some(target as &AnyObject),
+            Some(sel!(copyDisplayReport:)),
+            mtm,
+        )
+    };
+    report_btn.setBezelStyle(NSBezelStyle::Push);
+    report_btn.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - 100.0, diag_center - 14.0),
+        NSSize::new(100.0, 28.0),
+    ));
+    add_to_card(&card2, &report_btn);
+
+    container.addSubview(&card2);
+
+    // ── Card 3: Follow System Theme ─────────────────────────────────────
+    let card3_h = 140.0;
+    let card3_y = card2_y - GAP - card3_h;
+    let card3 = make_card(mtm, 0.0, card3_y, w, card3_h);
+
+    let theme_center = card3_h - 32.0;
+    let theme_title = make_label(mtm, "Follow System Theme", FONT_SIZE_SMALL, true);
+    theme_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, theme_center),
+        NSSize::new(200.0, 16.0),
+    ));
+    add_to_card(&card3, &theme_title);
+
+    let theme_desc = make_label(
+        mtm,
+        "Switch profiles with macOS appearance",
+        FONT_SIZE_XS,
+        false,
+    );
+    theme_desc.setTextColor(Some(&color(CLR_MUTED)));
+    theme_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, theme_center - 16.0),
+        NSSize::new(250.0, 14.0),
+    ));
+    add_to_card(&card3, &theme_desc);
+
+    let theme_toggle = make_switch(
+        mtm,
+        target,
+        sel!(themeAutomationToggled:),
+        cfg.theme_automation.enabled,
+    );
+    theme_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, theme_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card3, &theme_toggle);
+
+    let divider3 = make_separator(mtm, inner_pad, card3_h - 60.0, w - inner_pad * 2.0);
+    add_to_card(&card3, &divider3);
+
+    // Profile pickers: dark row above light row
+    let popup_w = 140.0;
+    let mut popups = Vec::with_capacity(2);
+    for (row, label_text) in ["In dark mode", "In light mode"].iter().enumerate() {
+        let row_center = card3_h - 84.0 - row as f64 * 34.0;
+        let label = make_label(mtm, label_text, FONT_SIZE_SMALL, false);
+        label.setFrame(NSRect::new(
+            NSPoint::new(inner_pad, row_center - 8.0),
+            NSSize::new(160.0, 16.0),
+        ));
+        add_to_card(&card3, &label);
+
+        let popup = NSPopUpButton::initWithFrame_pullsDown(
+            mtm.alloc::<NSPopUpButton>(),
+            NSRect::new(
+                NSPoint::new(w - inner_pad - popup_w, row_center - 13.0),
+                NSSize::new(popup_w, 26.0),
+            ),
+            false,
+        );
+        unsafe {
+            popup.setTarget(Some(target as &AnyObject));
+            popup.setAction(Some(sel!(themeProfileChanged:)));
+        }
+        add_to_card(&card3, &popup);
+        popups.push(popup);
+    }
+    let light_popup = popups.pop().unwrap();
+    let dark_popup = popups.pop().unwrap();
+    *THEME_TOGGLE_REF.lock() = Some(theme_toggle);
+    *THEME_POPUP_REFS.lock() = Some([dark_popup, light_popup]);
+    sync_theme_controls(cfg);
+
+    container.addSubview(&card3);
+
+    // ── Card 4: Night Boost ─────────────────────────────────────────────
+    let card4_h = 64.0;
+    let card4_y = card3_y - GAP - card4_h;
+    let card4 = make_card(mtm, 0.0, card4_y, w, card4_h);
+
+    let night_center = card4_h / 2.0;
+    let night_title = make_label(mtm, "Night Boost", FONT_SIZE_SMALL, true);
+    night_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, night_center),
+        NSSize::new(200.0, 16.0),
+    ));
+    add_to_card(&card4, &night_title);
+
+    let night_desc = make_label(mtm, &cfg.night_boost.summary(), FONT_SIZE_XS, false);
+    night_desc.setTextColor(Some(&color(CLR_MUTED)));
+    night_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, night_center - 16.0),
+        NSSize::new(250.0, 14.0),
+    ));
+    add_to_card(&card4, &night_desc);
+
+    let night_toggle = make_switch(mtm, target, sel!(nightBoostToggled:), cfg.night_boost.enabled);
+    night_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, night_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card4, &night_toggle);
+
+    container.addSubview(&card4);
+
+    // ── Card 5: Only Dim Bright Panels ──────────────────────────────────
+    let card5_h = 64.0;
+    let card5_y = card4_y - GAP - card5_h;
+    let card5 = make_card(mtm, 0.0, card5_y, w, card5_h);
+
+    let panel_center = card5_h / 2.0;
+    let panel_title = make_label(mtm, "Only Dim Bright Panels", FONT_SIZE_SMALL, true);
+    panel_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, panel_center),
+        NSSize::new(200.0, 16.0),
+    ));
+    add_to_card(&card5, &panel_title);
+
+    let panel_desc = make_label(mtm, &cfg.brightness_rule.summary(), FONT_SIZE_XS, false);
+    panel_desc.setTextColor(Some(&color(CLR_MUTED)));
+    panel_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, panel_center - 16.0),
+        NSSize::new(250.0, 14.0),
+    ));
+    add_to_card(&card5, &panel_desc);
+
+    let panel_toggle = make_switch(
+        mtm,
+        target,
+        sel!(brightnessRuleToggled:),
+        cfg.brightness_rule.enabled,
+    );
+    panel_toggle.setFrame(NSRect::new(
+        NSPoint::new(
+            w - inner_pad - TOGGLE_W,
+            panel_center - TOGGLE_H / 2.0 + 1.0,
+        ),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card5, &panel_toggle);
+
+    container.addSubview(&card5);
+
+    container
+}
+
+fn build_shortcuts_tab(
+    mtm: MainThreadMarker,
+    cfg: &config::AppConfig,
+    target: &SettingsTarget,
+    frame: NSRect,
+) -> Retained<NSView> {
     let container = NSView::initWithFrame(mtm.alloc::<NSView>(), frame);
     let w = frame.size.width;
     let top = frame.size.height;
     let inner_pad = 20.0;
 
     // ── Card: Keyboard Shortcuts ────────────────────────────────────────
-    let card_h = 190.0;
+    let card_h = 222.0;
     let card_y = top - card_h;
     let card = make_card(mtm, 0.0, card_y, w, card_h);
 
@@ -947,20 +2288,21 @@ fn build_shortcuts_tab(mtm: MainThreadMarker, frame: NSRect) -> Retained<NSView>
     ));
     add_to_card(&card, &title);
 
-    // Each shortcut: (label, list-of-individual-keys)
-    let shortcuts: &[(&str, &[&str])] = &[
-        ("Toggle Dimmer", &["⌘", "⇧", "D"]),
-        ("Increase Dimming", &["⌘", "⇧", ">"]),
-        ("Decrease Dimming", &["⌘", "⇧", "<"]),
+    let [toggle, increase, decrease] = cfg.key_strings();
+    let shortcuts = [
+        ("Toggle Dimmer", toggle),
+        ("Increase Dimming", increase),
+        ("Decrease Dimming", decrease),
+        ("Movie Mode", hotkeys::MOVIE_MODE_KEYS),
+        ("Boost Here", hotkeys::BOOST_KEYS),
     ];
 
-    let key_w = 26.0_f64;
+    let key_w = 110.0_f64;
     let key_h = 26.0_f64;
-    let plus_w = 14.0_f64;
-    let key_gap = 4.0_f64;
 
+    let mut labels = Vec::new();
     let mut row_y = card_h - 54.0;
-    for (action, keys) in shortcuts {
+    for (row, (action, keys)) in shortcuts.into_iter().enumerate() {
         // Action label
         let action_lbl = make_label(mtm, action, FONT_SIZE_SMALL, false);
         action_lbl.setTextColor(Some(&color(CLR_MUTED)));
@@ -970,40 +2312,50 @@ fn build_shortcuts_tab(mtm: MainThreadMarker, frame: NSRect) -> Retained<NSView>
         ));
         add_to_card(&card, &action_lbl);
 
-        // Build key pills right-aligned with "+" separators
-        let n = keys.len();
-        let total_w = (n as f64) * key_w
-            + ((n - 1) as f64) * (plus_w + key_gap * 2.0);
-        let mut x = w - inner_pad - total_w;
-        let badge_y = row_y - (key_h - 16.0) / 2.0; // vertically center with action label
-
-        for (i, key) in keys.iter().enumerate() {
-            // Key pill (centered text inside styled container)
-            let (key_view, _key_label) = make_key_pill(mtm, key, key_w, key_h);
-            key_view.setFrame(NSRect::new(
-                NSPoint::new(x, badge_y),
-                NSSize::new(key_w, key_h),
-            ));
-            add_to_card(&card, &key_view);
-            x += key_w;
-
-            // "+" separator between keys
-            if i < n - 1 {
-                x += key_gap;
-                let plus = make_label(mtm, "+", FONT_SIZE_XS, false);
-                plus.setTextColor(Some(&color(CLR_MUTED)));
-                plus.setAlignment(NSTextAlignment::Center);
-                plus.setFrame(NSRect::new(
-                    NSPoint::new(x, badge_y + (key_h - 14.0) / 2.0),
-                    NSSize::new(plus_w, 14.0),
-                ));
-                add_to_card(&card, &plus);
-                x += plus_w + key_gap;
-            }
+        // Key pill, right-aligned and vertically centered with the label
+        let pill_frame = NSRect::new(
+            NSPoint::new(w - inner_pad - key_w, row_y - (key_h - 16.0) / 2.0),
+            NSSize::new(key_w, key_h),
+        );
+        let (key_view, key_label) = make_key_pill(mtm, &key_symbols(keys), key_w, key_h);
+        key_view.setFrame(pill_frame);
+        add_to_card(&card, &key_view);
+
+        // Clicking the pill records a new shortcut (movie mode's and the
+        // boost's are fixed)
+        if row < RECORDABLE.len() {
+            let record_btn = unsafe {
+                NSButton::buttonWithTitle_target_action(
+                    &NSString::from_str(""),
+                    Some(target as &AnyObject),
+                    Some(sel!(shortcutClicked:)),
+                    mtm,
+                )
+            };
+            record_btn.setBordered(false);
+            record_btn.setTransparent(true);
+            record_btn.setTag(row as isize);
+            // Pinned by a managed deployment's defaults.json
+            record_btn.setEnabled(!config::is_locked(RECORDABLE[row].1));
+            record_btn.setFrame(pill_frame);
+            record_btn.setToolTip(Some(&NSString::from_str(
+                "Click, then press the new shortcut. Esc cancels.",
+            )));
+            add_to_card(&card, &record_btn);
+            labels.push(key_label);
+        } else if row == RECORDABLE.len() {
+            key_view.setToolTip(Some(&NSString::from_str(
+                "Movie mode's shortcut can't be changed",
+            )));
+        } else {
+            key_view.setToolTip(Some(&NSString::from_str(
+                "Less dimming while this app stays in front; can't be changed",
+            )));
         }
 
-        row_y -= 40.0;
+        row_y -= 32.0;
     }
+    *SHORTCUT_LABELS.lock() = labels;
 
     container.addSubview(&card);
 
@@ -1028,6 +2380,156 @@ fn build_shortcuts_tab(mtm: MainThreadMarker, frame: NSRect) -> Retained<NSView>
     ));
     container.addSubview(&hint);
 
+    // ── Card: Mouse Button Shortcuts ────────────────────────────────────
+    let card2_h = 64.0;
+    let card2_y = card_y - 40.0 - card2_h;
+    let card2 = make_card(mtm, 0.0, card2_y, w, card2_h);
+
+    let mouse_center = card2_h / 2.0;
+    let mouse_title = make_label(mtm, "Mouse Button Shortcuts", FONT_SIZE_SMALL, true);
+    mouse_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, mouse_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card2, &mouse_title);
+
+    let mouse_desc = make_label(mtm, &cfg.mouse_hotkeys.summary(), FONT_SIZE_XS, false);
+    mouse_desc.setTextColor(Some(&color(CLR_MUTED)));
+    mouse_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, mouse_center - 16.0),
+        NSSize::new(260.0, 14.0),
+    ));
+    add_to_card(&card2, &mouse_desc);
+
+    let mouse_toggle = make_switch(
+        mtm,
+        target,
+        sel!(mouseHotkeysToggled:),
+        cfg.mouse_hotkeys.enabled,
+    );
+    mouse_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, mouse_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card2, &mouse_toggle);
+
+    container.addSubview(&card2);
+
+    // ── Card: Set Level Chord ───────────────────────────────────────────
+    let card3_h = 64.0;
+    let card3_y = card2_y - GAP - card3_h;
+    let card3 = make_card(mtm, 0.0, card3_y, w, card3_h);
+
+    let chord_center = card3_h / 2.0;
+    let chord_title = make_label(mtm, "Set Level Chord", FONT_SIZE_SMALL, true);
+    chord_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, chord_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card3, &chord_title);
+
+    let chord_desc = make_label(
+        mtm,
+        "Toggle shortcut, then two digits (4, 5 = 45%)",
+        FONT_SIZE_XS,
+        false,
+    );
+    chord_desc.setTextColor(Some(&color(CLR_MUTED)));
+    chord_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, chord_center - 16.0),
+        NSSize::new(260.0, 14.0),
+    ));
+    add_to_card(&card3, &chord_desc);
+
+    let chord_toggle = make_switch(mtm, target, sel!(levelChordToggled:), cfg.level_chord);
+    chord_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, chord_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    add_to_card(&card3, &chord_toggle);
+
+    container.addSubview(&card3);
+
+    // ── Card: Disable Global Shortcuts ──────────────────────────────────
+    let card4_h = 64.0;
+    let card4_y = card3_y - GAP - card4_h;
+    let card4 = make_card(mtm, 0.0, card4_y, w, card4_h);
+
+    let off_center = card4_h / 2.0;
+    let off_title = make_label(mtm, "Disable Global Shortcuts", FONT_SIZE_SMALL, true);
+    off_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, off_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card4, &off_title);
+
+    let off_desc = make_label(
+        mtm,
+        "No system-wide hotkeys or input hooks at all",
+        FONT_SIZE_XS,
+        false,
+    );
+    off_desc.setTextColor(Some(&color(CLR_MUTED)));
+    off_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, off_center - 16.0),
+        NSSize::new(260.0, 14.0),
+    ));
+    add_to_card(&card4, &off_desc);
+
+    let off_toggle = make_switch(
+        mtm,
+        target,
+        sel!(disableShortcutsToggled:),
+        cfg.disable_global_shortcuts,
+    );
+    off_toggle.setFrame(NSRect::new(
+        NSPoint::new(w - inner_pad - TOGGLE_W, off_center - TOGGLE_H / 2.0 + 1.0),
+        NSSize::new(TOGGLE_W, TOGGLE_H),
+    ));
+    off_toggle.setEnabled(!config::is_locked("disable_global_shortcuts"));
+    add_to_card(&card4, &off_toggle);
+
+    container.addSubview(&card4);
+
+    // ── Card: Custom Actions ────────────────────────────────────────────
+    let card5_h = 64.0;
+    let card5_y = card4_y - GAP - card5_h;
+    let card5 = make_card(mtm, 0.0, card5_y, w, card5_h);
+
+    let actions_center = card5_h / 2.0;
+    let actions_title = make_label(mtm, "Custom Actions", FONT_SIZE_SMALL, true);
+    actions_title.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, actions_center),
+        NSSize::new(220.0, 16.0),
+    ));
+    add_to_card(&card5, &actions_title);
+
+    let popup_w = 90.0;
+    let actions_desc = make_label(mtm, "", FONT_SIZE_XS, false);
+    actions_desc.setTextColor(Some(&color(CLR_MUTED)));
+    actions_desc.setLineBreakMode(NSLineBreakMode::ByTruncatingTail);
+    actions_desc.setFrame(NSRect::new(
+        NSPoint::new(inner_pad, actions_center - 16.0),
+        NSSize::new(w - inner_pad * 3.0 - popup_w, 14.0),
+    ));
+    add_to_card(&card5, &actions_desc);
+
+    // Pull-down: one submenu per action, then New Action
+    let actions_popup = NSPopUpButton::initWithFrame_pullsDown(
+        mtm.alloc::<NSPopUpButton>(),
+        NSRect::new(
+            NSPoint::new(w - inner_pad - popup_w, actions_center - 13.0),
+            NSSize::new(popup_w, 26.0),
+        ),
+        true,
+    );
+    add_to_card(&card5, &actions_popup);
+    *CUSTOM_ACTIONS_LABEL_REF.lock() = Some(actions_desc);
+    *CUSTOM_ACTIONS_POPUP_REF.lock() = Some(actions_popup);
+    sync_custom_actions(cfg);
+
+    container.addSubview(&card5);
+
     container
 }
 
@@ -1035,6 +2537,27 @@ fn build_shortcuts_tab(mtm: MainThreadMarker, frame: NSRect) -> Retained<NSView>
 // Helper: card (NSBox with custom style)
 // ===========================================================================
 
+/// Flip every subview of `view` (recursively) within its parent, swap left
+/// and right text alignment, and make the controls run right to left.
+fn mirror(view: &NSView) {
+    let width = view.bounds().size.width;
+    for sub in view.subviews().iter() {
+        let mut frame = sub.frame();
+        frame.origin.x = width - frame.origin.x - frame.size.width;
+        sub.setFrame(frame);
+        sub.setUserInterfaceLayoutDirection(NSUserInterfaceLayoutDirection::RightToLeft);
+        if let Some(control) = sub.downcast_ref::<NSControl>() {
+            let alignment = control.alignment();
+            if alignment == NSTextAlignment::Right {
+                control.setAlignment(NSTextAlignment::Left);
+            } else if alignment == NSTextAlignment::Left || alignment == NSTextAlignment::Natural {
+                control.setAlignment(NSTextAlignment::Right);
+            }
+        }
+        mirror(&sub);
+    }
+}
+
 fn make_card(mtm: MainThreadMarker, x: f64, y: f64, w: f64, h: f64) -> Retained<NSBox> {
     let card = NSBox::initWithFrame(
         mtm.alloc::<NSBox>(),
@@ -30,7 +30,7 @@ pub const CLR_TOGGLE_KNOB: (f64, f64, f64) = (1.0, 1.0, 1.0);
 // ── Dimensions ──────────────────────────────────────────────────────────────
 
 pub const WINDOW_W: f64 = 400.0;
-pub const WINDOW_H: f64 = 580.0;
+pub const WINDOW_H: f64 = 756.0;
 pub const PADDING: f64 = 24.0;
 pub const CONTENT_W: f64 = WINDOW_W - 2.0 * PADDING;
 pub const CARD_RADIUS: f64 = 8.0;
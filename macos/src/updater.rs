@@ -2,13 +2,21 @@
 
 pub use savemyeyes_shared::updater::APP_VERSION;
 pub use savemyeyes_shared::updater::UpdateResult;
+pub use savemyeyes_shared::updater::set_endpoint;
+pub use savemyeyes_shared::updater::ENABLED;
 
 /// Check for updates (looks for .dmg assets)
 pub fn check_for_update(current_version: &str) -> UpdateResult {
     savemyeyes_shared::updater::check_for_update(current_version, ".dmg")
 }
 
+/// Check for updates because the user asked (see shared check_for_update_now)
+pub fn check_for_update_now(current_version: &str) -> UpdateResult {
+    savemyeyes_shared::updater::check_for_update_now(current_version, ".dmg")
+}
+
 /// Open a URL in the default browser
+#[allow(dead_code)]
 pub fn open_url(url: &str) {
     savemyeyes_shared::updater::open_url(url);
 }
@@ -0,0 +1,68 @@
+// Example API client: toggle dimming, then follow the state
+//
+//   cargo run -p savemyeyes-shared --example api_client -- <path to SaveMyEyes>
+//
+// Starts the executable as a native messaging host, the way a browser does
+// (the chrome-extension:// origin on the command line is what makes it one),
+// sends a toggle, then asks for the status twice a second and prints every
+// new state, recognised by a higher `seq`, until SaveMyEyes quits. The messages
+// are described by `SaveMyEyes --print-api-schema`.
+
+use std::process::{Command as Process, Stdio};
+use std::time::Duration;
+
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::native_messaging::{read_message, write_message, Query, Reply};
+
+const ORIGIN: &str = "chrome-extension://savemyeyes-api-client/";
+
+fn main() {
+    let Some(exe) = std::env::args().nth(1) else {
+        eprintln!("usage: api_client <path to SaveMyEyes>");
+        std::process::exit(2);
+    };
+    let mut host = Process::new(exe)
+        .arg(ORIGIN)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("start the native messaging host");
+    let mut input = host.stdin.take().unwrap();
+    let mut output = host.stdout.take().unwrap();
+    let mut request = |message: serde_json::Value| -> Reply {
+        write_message(&mut input, &message).expect("send a request");
+        let reply = read_message(&mut output)
+            .expect("read a reply")
+            .expect("host closed the pipe");
+        serde_json::from_value(reply).expect("reply matches the schema")
+    };
+
+    let reply = request(serde_json::to_value(Command::Toggle).unwrap());
+    match reply.error {
+        None => println!("toggled"),
+        Some(error) => println!("toggle failed: {}", error),
+    }
+
+    let mut last_seq = 0;
+    loop {
+        let reply = request(serde_json::to_value(Query::Status).unwrap());
+        if let Some(status) = reply.status {
+            let seq = status["seq"].as_u64().unwrap_or(0);
+            if seq != last_seq {
+                last_seq = seq;
+                println!(
+                    "seq {}: enabled {}, level {}, cause {}",
+                    seq, status["enabled"], status["opacity"], status["cause"]
+                );
+            }
+            if status["running"] == false {
+                break;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    // Closing stdin ends the host
+    drop(input);
+    let _ = host.wait();
+}
@@ -0,0 +1,163 @@
+// Hold-to-adjust acceleration (platform-agnostic)
+//
+// Holding the increase or decrease hotkey keeps changing the level, slowly
+// at first and faster the longer it is held. The press itself changes the
+// level by `initial_step`. Auto-repeat events that follow within REPEAT_GAP
+// continue the hold: the level then moves at `initial_step` per second,
+// speeding up by `acceleration` every second up to `max_rate`. Repeats come
+// in much faster than the level resolution (a tenth of a percent), so
+// `HoldRamp` adds up the fractional change and hands out whole steps,
+// keeping the rest for the next event.
+//
+// Two presets cover most people: "fast" (10% a press, the long-standing
+// step) and "precise" (1% a press, slow ramp); "custom" uses the three
+// values from config. Keyboard and mouse-button hotkeys share one ramp so
+// every input feels the same.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::dimming;
+
+/// Events closer together than this continue a hold. Longer than key
+/// auto-repeat, shorter than the delay before it starts, so the first
+/// repeat counts as a second press and the ones after it ramp.
+pub const REPEAT_GAP: Duration = Duration::from_millis(150);
+
+/// Ramp parameters, all in level units (1.0 = 100%)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RampProfile {
+    /// Change of a single press
+    pub initial_step: f32,
+    /// Rate gained per second held (per second)
+    pub acceleration: f32,
+    /// Fastest change while held (per second)
+    pub max_rate: f32,
+}
+
+/// 1% a press; held, 1%/s speeding up to 15%/s over about three seconds
+pub const PRECISE: RampProfile = RampProfile {
+    initial_step: 0.01,
+    acceleration: 0.05,
+    max_rate: 0.15,
+};
+
+/// 10% a press; held, 10%/s speeding up to 60%/s in under two seconds
+pub const FAST: RampProfile = RampProfile {
+    initial_step: 0.1,
+    acceleration: 0.3,
+    max_rate: 0.6,
+};
+
+/// Which ramp the hotkeys use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RampPreset {
+    Precise,
+    #[default]
+    Fast,
+    /// The values in `AdjustRamp`
+    Custom,
+}
+
+/// `adjust_ramp` config block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdjustRamp {
+    pub preset: RampPreset,
+    /// Change of a single press (custom preset only)
+    pub initial_step: f32,
+    /// Rate gained per second held (custom preset only)
+    pub acceleration: f32,
+    /// Fastest change per second while held (custom preset only)
+    pub max_rate: f32,
+}
+
+impl Default for AdjustRamp {
+    fn default() -> Self {
+        Self {
+            preset: RampPreset::Fast,
+            initial_step: FAST.initial_step,
+            acceleration: FAST.acceleration,
+            max_rate: FAST.max_rate,
+        }
+    }
+}
+
+impl AdjustRamp {
+    /// Parameters in effect for the chosen preset
+    pub fn profile(&self) -> RampProfile {
+        match self.preset {
+            RampPreset::Precise => PRECISE,
+            RampPreset::Fast => FAST,
+            RampPreset::Custom => RampProfile {
+                initial_step: self.initial_step,
+                acceleration: self.acceleration,
+                max_rate: self.max_rate,
+            },
+        }
+    }
+}
+
+/// Turns a stream of increase/decrease events into level changes.
+#[derive(Debug)]
+pub struct HoldRamp {
+    up: bool,
+    hold_started: Option<Instant>,
+    last_event: Option<Instant>,
+    /// Change owed but smaller than one level step
+    pending: f32,
+}
+
+impl Default for HoldRamp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HoldRamp {
+    pub const fn new() -> Self {
+        Self {
+            up: true,
+            hold_started: None,
+            last_event: None,
+            pending: 0.0,
+        }
+    }
+
+    /// Level change for an increase (`up`) or decrease event at `now`.
+    /// 0.0 while a slow hold hasn't added up to a whole step yet.
+    pub fn next(&mut self, profile: RampProfile, up: bool, now: Instant) -> f32 {
+        let held = match (self.hold_started, self.last_event) {
+            (Some(started), Some(last))
+                if self.up == up && now.saturating_duration_since(last) <= REPEAT_GAP =>
+            {
+                Some((now - started, now - last))
+            }
+            _ => None,
+        };
+        self.up = up;
+        self.last_event = Some(now);
+        let magnitude = match held {
+            Some((held, since_last)) => {
+                let rate = (profile.initial_step + profile.acceleration * held.as_secs_f32())
+                    .min(profile.max_rate);
+                self.pending += rate * since_last.as_secs_f32();
+                let steps = (self.pending * dimming::LEVEL_STEPS as f32).floor() as i32;
+                let step = dimming::from_steps(steps.max(0));
+                self.pending -= step;
+                step
+            }
+            None => {
+                self.hold_started = Some(now);
+                self.pending = 0.0;
+                profile.initial_step
+            }
+        };
+        if up {
+            magnitude
+        } else {
+            -magnitude
+        }
+    }
+}
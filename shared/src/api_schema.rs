@@ -0,0 +1,77 @@
+// JSON Schema of the external API (platform-agnostic)
+//
+// Third-party clients (browser extensions, scripts, launchers) drive
+// SaveMyEyes through the native messaging host and follow it through
+// status.json. `--print-api-schema` prints one JSON document describing
+// both, generated with schemars from the same Rust types that parse and
+// write the messages, so the schema can't drift from the code:
+//   commands  events::Command, forwarded to the running app
+//   queries   native_messaging::Query, answered by the host itself
+//   replies   native_messaging::Reply, one per request
+//   status    status_file::Status, the state events: status.json is
+//             rewritten on every change with a higher `seq`, and a "status"
+//             query returns its latest contents
+// examples/api_client.rs in this crate is a small client built on it.
+//
+// Builds without the "api-schema" feature print an error instead.
+
+use serde_json::Value;
+
+/// Command-line flag that prints the schema
+pub const PRINT_ARG: &str = "--print-api-schema";
+
+/// Bumped when a command, reply or status field changes incompatibly
+pub const API_VERSION: u32 = 1;
+
+/// Print the schema if the command line asks for it; returns the exit code.
+/// None for a normal launch.
+pub fn run_from_args() -> Option<i32> {
+    if !std::env::args().skip(1).any(|a| a == PRINT_ARG) {
+        return None;
+    }
+    Some(match document() {
+        Some(document) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&document).unwrap_or_default()
+            );
+            0
+        }
+        None => {
+            eprintln!("[api-schema] built without the api-schema feature");
+            1
+        }
+    })
+}
+
+/// The whole API as one JSON document, or None without the feature
+#[cfg(feature = "api-schema")]
+pub fn document() -> Option<Value> {
+    use crate::events::Command;
+    use crate::native_messaging::{Query, Reply, HOST_NAME};
+    use schemars::schema_for;
+    use serde_json::json;
+
+    Some(json!({
+        "title": "SaveMyEyes API",
+        "api_version": API_VERSION,
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "transport": {
+            "native_messaging": {
+                "host": HOST_NAME,
+                "framing": "32-bit native-endian length, then that many bytes of UTF-8 JSON",
+                "exchange": "every request (a command or a query) gets exactly one reply",
+            },
+            "status_file": "status.json next to config.json, rewritten on every change",
+        },
+        "commands": schema_for!(Command),
+        "queries": schema_for!(Query),
+        "replies": schema_for!(Reply),
+        "status": crate::status_file::json_schema(),
+    }))
+}
+
+#[cfg(not(feature = "api-schema"))]
+pub fn document() -> Option<Value> {
+    None
+}
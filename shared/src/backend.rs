@@ -0,0 +1,158 @@
+// Overlay backend interface (platform-agnostic)
+//
+// What the apps ask of whatever darkens the screen: list the monitors, dim
+// them all at one level or each at its own, read back what is applied, and
+// undim. Each platform's overlay implements `OverlayBackend` next to the
+// code it wraps: `overlay::Backend` on Windows (overlay windows and gamma
+// ramps) and macOS (gamma tables), `overlay::X11Backend` on Linux (ARGB
+// windows). Code written against the trait, such as the `--self-test`
+// script below, then runs on all of them instead of being written three
+// times. The platform-only parts (fades, capture exclusion, HDR, recovery)
+// stay on the platform modules.
+//
+// `Headless` (feature "headless") implements the trait with no-ops over
+// one pretend monitor, so the Windows and macOS crates can be built with
+// `--features headless` on a host of any OS: their `main` then only runs
+// `headless_main`, which answers `--print-api-schema` and runs the
+// `--self-test` script against `Headless`. That keeps the whole workspace
+// building and its unit tests running on one CI host; it checks nothing
+// about the platform backends themselves.
+
+use crate::displays::DisplayInfo;
+
+/// Something that dims the screen
+pub trait OverlayBackend {
+    /// Connected monitors, in the order monitor indices count them
+    fn enumerate(&self) -> Vec<DisplayInfo>;
+    /// Dim every monitor at `level`
+    fn show(&mut self, level: f32) -> Result<(), String>;
+    /// Undim every monitor
+    fn hide(&mut self);
+    /// Change every monitor's level while shown
+    fn set_opacity(&mut self, level: f32);
+    /// Change monitor `monitor`'s level while shown
+    fn set_monitor_opacity(&mut self, monitor: usize, level: f32);
+    /// Level each monitor is dimmed at now (0 while hidden), read back from
+    /// the system where it allows
+    fn applied(&self) -> Vec<f32>;
+    fn is_visible(&self) -> bool {
+        self.applied().iter().any(|&level| level > 0.0)
+    }
+}
+
+/// No-op backend over one pretend 1920×1080 monitor (headless builds)
+#[cfg(feature = "headless")]
+#[derive(Debug, Default)]
+pub struct Headless {
+    /// Level per monitor, None while hidden
+    levels: Option<Vec<f32>>,
+}
+
+#[cfg(feature = "headless")]
+impl OverlayBackend for Headless {
+    fn enumerate(&self) -> Vec<DisplayInfo> {
+        vec![DisplayInfo {
+            id: crate::monitor_id::MonitorId::from_name_position("Headless", 0, 0),
+            name: "Headless".to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale: 1.0,
+            primary: true,
+            hdr: false,
+        }]
+    }
+
+    fn show(&mut self, level: f32) -> Result<(), String> {
+        self.levels = Some(vec![level; self.enumerate().len()]);
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.levels = None;
+    }
+
+    fn set_opacity(&mut self, level: f32) {
+        if let Some(levels) = self.levels.as_mut() {
+            levels.fill(level);
+        }
+    }
+
+    fn set_monitor_opacity(&mut self, monitor: usize, level: f32) {
+        if let Some(slot) = self.levels.as_mut().and_then(|l| l.get_mut(monitor)) {
+            *slot = level;
+        }
+    }
+
+    fn applied(&self) -> Vec<f32> {
+        match &self.levels {
+            Some(levels) => levels.clone(),
+            None => vec![0.0; self.enumerate().len()],
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.levels.is_some()
+    }
+}
+
+/// The `--self-test` script (self_test.rs) against `backend`: show, adjust
+/// everything, adjust one monitor, hide
+pub fn self_test(backend: &mut dyn OverlayBackend, report: &mut crate::self_test::Report) {
+    use crate::self_test::{FIRST_LEVEL, SECOND_LEVEL};
+    let count = backend.enumerate().len();
+    report.check("monitors", count > 0, format!("{} monitor(s)", count));
+    let shown = backend.show(FIRST_LEVEL);
+    report.check("show", shown.is_ok(), format!("{:?}", shown));
+    let applied = backend.applied();
+    let at = |applied: &[f32], level: f32| applied.iter().all(|&l| (l - level).abs() < 0.01);
+    report.check(
+        "show level",
+        at(&applied, FIRST_LEVEL),
+        format!("{:?}", applied),
+    );
+    backend.set_opacity(SECOND_LEVEL);
+    let applied = backend.applied();
+    report.check(
+        "adjust",
+        at(&applied, SECOND_LEVEL),
+        format!("{:?}", applied),
+    );
+    backend.set_monitor_opacity(0, FIRST_LEVEL);
+    let applied = backend.applied();
+    let first = applied.first().copied().unwrap_or_default();
+    report.check(
+        "adjust monitor",
+        (first - FIRST_LEVEL).abs() < 0.01,
+        format!("{:?}", applied),
+    );
+    backend.hide();
+    let applied = backend.applied();
+    let hidden = !backend.is_visible() && at(&applied, 0.0);
+    report.check("hide", hidden, format!("{:?}", applied));
+}
+
+/// `main` of a platform crate built headless for another OS (`platform`
+/// names the OS it really targets). Returns the exit code.
+#[cfg(feature = "headless")]
+pub fn headless_main(platform: &str) -> i32 {
+    if let Some(code) = crate::api_schema::run_from_args() {
+        return code;
+    }
+    if crate::self_test::requested() {
+        let mut report = crate::self_test::Report::new();
+        report.skip(
+            "backend",
+            &format!("headless build, no {} backend", platform),
+        );
+        self_test(&mut Headless::default(), &mut report);
+        return report.finish();
+    }
+    eprintln!(
+        "This is a headless build of SaveMyEyes for {}: it dims nothing. \
+         Build it on {} without the headless feature to run the app.",
+        platform, platform
+    );
+    1
+}
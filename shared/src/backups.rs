@@ -0,0 +1,191 @@
+// Config backups (platform-agnostic)
+//
+// Before a change that reshapes config.json, a copy goes into backups/ next
+// to it, so a bad migration or a surprise renumbering of monitors can be
+// undone. Backups are taken on the first start of a new app version
+// (`check_version`, from load_config) and when the set of connected monitors
+// changes (displays::record_layout), since per-monitor settings are keyed by
+// index. Only the newest KEEP are kept.
+//
+// The platform lists them with `list`, previews a restore with `differences`
+// and calls `restore`, which backs up the current config first (so a restore
+// can be undone too), saves the old one and leaves it to the platform to
+// restart with RESTORED_ARG.
+
+use chrono::{Local, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{self, config_path, AppConfig};
+use crate::locale;
+use crate::updater::{now_secs, APP_VERSION};
+
+/// How many backups are kept
+pub const KEEP: usize = 5;
+
+/// Passed to the instance started after a restore
+pub const RESTORED_ARG: &str = "--restored";
+
+/// One file in backups/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    /// Why it was taken, e.g. "Monitors changed"
+    pub reason: String,
+    /// App version that wrote the config
+    pub app_version: String,
+    /// config.json as it was
+    pub config: Value,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// Picker entry, e.g. "2026-10-16 14:02 — Monitors changed"
+    pub fn label(&self) -> String {
+        let time = Local
+            .timestamp_opt(self.created_at as i64, 0)
+            .single()
+            .map(|t| {
+                let clock = locale::clock(t.hour(), t.minute());
+                format!("{} {}", t.format("%Y-%m-%d"), clock)
+            })
+            .unwrap_or_else(|| "Unknown time".to_string());
+        format!("{} \u{2014} {}", time, self.reason)
+    }
+}
+
+fn dir() -> PathBuf {
+    config_path().with_file_name("backups")
+}
+
+/// Copy the current config.json into backups/ (if there is one) and drop
+/// the oldest backups beyond KEEP. Returns true if a backup was written.
+pub fn take(reason: &str) -> bool {
+    let Ok(data) = fs::read_to_string(config_path()) else {
+        return false;
+    };
+    let Ok(config) = crate::config_text::parse(&data) else {
+        // Nothing worth restoring; load_config reports the broken file
+        return false;
+    };
+    let backup = Backup {
+        created_at: now_secs(),
+        reason: reason.to_string(),
+        app_version: APP_VERSION.to_string(),
+        config,
+        path: PathBuf::new(),
+    };
+    let dir = dir();
+    // Millisecond names: a restore backs up right after another backup
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("config-{}.json", millis));
+    let data = serde_json::to_string_pretty(&backup).unwrap_or_default();
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|()| fs::write(&path, data)) {
+        eprintln!("[backups] could not write {}: {}", path.display(), e);
+        return false;
+    }
+    eprintln!("[backups] saved {} ({})", path.display(), reason);
+    for old in list().into_iter().skip(KEEP) {
+        let _ = fs::remove_file(old.path);
+    }
+    true
+}
+
+/// Backups on disk, newest first
+pub fn list() -> Vec<Backup> {
+    let Ok(entries) = fs::read_dir(dir()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let data = fs::read_to_string(&path).ok()?;
+            let backup: Backup = serde_json::from_str(&data).ok()?;
+            Some(Backup { path, ..backup })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    backups
+}
+
+/// Back up the config once per app version, before this version saves over
+/// what an older one wrote.
+pub fn check_version() {
+    let newest = list().into_iter().next();
+    if newest.is_none_or(|backup| backup.app_version != APP_VERSION) {
+        take(&format!("Started v{}", APP_VERSION));
+    }
+}
+
+/// The config `backup` would restore, sanitized like a loaded one
+pub fn restored_config(backup: &Backup) -> Result<AppConfig, String> {
+    let mut config: AppConfig =
+        serde_json::from_value(backup.config.clone()).map_err(|e| e.to_string())?;
+    config.sanitize();
+    Ok(config)
+}
+
+/// Settings that differ between `current` and `backup`, one line each,
+/// e.g. "opacity: 0.3 → 0.45" ("profiles changed" for long values). Empty
+/// if restoring would change nothing.
+pub fn differences(current: &AppConfig, backup: &Backup) -> Result<Vec<String>, String> {
+    let restored = serde_json::to_value(restored_config(backup)?).map_err(|e| e.to_string())?;
+    let current = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    let (Value::Object(now), Value::Object(then)) = (current, restored) else {
+        return Ok(Vec::new());
+    };
+    Ok(now
+        .iter()
+        .filter_map(|(key, value)| {
+            let old = then.get(key).unwrap_or(&Value::Null);
+            (old != value).then(|| match (short(value), short(old)) {
+                (Some(value), Some(old)) => format!("{}: {} \u{2192} {}", key, value, old),
+                _ => format!("{} changed", key),
+            })
+        })
+        .collect())
+}
+
+/// Compact JSON with floats rounded (config levels are f32), None if too
+/// long to read in a list
+fn short(value: &Value) -> Option<String> {
+    const MAX_CHARS: usize = 40;
+    let text = match value.as_f64() {
+        Some(number) if !value.is_i64() && !value.is_u64() => {
+            ((number * 10_000.0).round() / 10_000.0).to_string()
+        }
+        _ => value.to_string(),
+    };
+    (text.chars().count() <= MAX_CHARS).then_some(text)
+}
+
+/// Replace the config with `backup` (after backing up the current one) and
+/// return it. The platform restarts with RESTORED_ARG so every subsystem
+/// picks it up.
+pub fn restore(backup: &Backup) -> Result<AppConfig, String> {
+    let config = restored_config(backup)?;
+    take("Before restoring a backup");
+    let failures = config::save_failure_count();
+    config::save_config(&config);
+    if config::save_failure_count() > failures {
+        if let Some(failure) = config::last_save_failure().filter(|f| f.saved_to.is_none()) {
+            return Err(config::describe_save_failure(&failure));
+        }
+    }
+    Ok(config)
+}
+
+/// True in the instance started after a restore
+pub fn was_restored() -> bool {
+    std::env::args().any(|a| a == RESTORED_ARG)
+}
@@ -0,0 +1,89 @@
+// Only dim bright panels (platform-agnostic)
+//
+// Laptops with automatic brightness already turn the backlight down in a dim
+// room, and an overlay on top of that just makes the screen hard to read.
+// With this rule on, a monitor is only dimmed while its own panel brightness
+// is at or above its threshold. Each platform reads the backlight (WMI on
+// Windows, DisplayServices on macOS) and asks `allows`. Monitors that don't
+// report a brightness (most external displays) are always dimmed. Like pen
+// pause it is applied on top of the configured levels and never saved.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::dimming::percent_label;
+use crate::monitor_id::MonitorId;
+
+/// How far brightness must fall below the threshold before dimming lifts,
+/// so automatic brightness hovering around it doesn't make the screen flicker
+const HYSTERESIS: f32 = 0.03;
+
+/// Panel brightness rule. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrightnessRule {
+    pub enabled: bool,
+    /// Lowest panel brightness (0.0–1.0) at which dimming engages
+    pub threshold: f32,
+    /// Threshold per monitor keyed by monitor id (monitor_id.rs)
+    pub monitor_threshold: HashMap<MonitorId, f32>,
+    /// Older thresholds keyed by monitor index (Windows) and display name
+    /// (macOS), moved to `monitor_threshold` once the monitor is seen
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub per_monitor_threshold: HashMap<u32, f32>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub per_display_threshold: HashMap<String, f32>,
+}
+
+impl Default for BrightnessRule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.5,
+            monitor_threshold: HashMap::new(),
+            per_monitor_threshold: HashMap::new(),
+            per_display_threshold: HashMap::new(),
+        }
+    }
+}
+
+impl BrightnessRule {
+    /// Threshold for a monitor
+    pub fn threshold_for(&self, id: &MonitorId) -> f32 {
+        self.monitor_threshold
+            .get(id)
+            .copied()
+            .unwrap_or(self.threshold)
+    }
+
+    /// Whether a monitor whose panel is at `brightness` (None if unknown)
+    /// may be dimmed. `dimmed` is whether the rule let it be dimmed at the
+    /// last check.
+    pub fn allows(&self, threshold: f32, brightness: Option<f32>, dimmed: bool) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let Some(brightness) = brightness else {
+            return true;
+        };
+        if dimmed {
+            brightness >= threshold - HYSTERESIS
+        } else {
+            brightness >= threshold
+        }
+    }
+
+    /// One-line summary for the settings UI
+    pub fn summary(&self) -> String {
+        let own = self.monitor_threshold.len();
+        let base = format!(
+            "Dims only when panel brightness is {} or more",
+            percent_label(self.threshold)
+        );
+        match own {
+            0 => base,
+            1 => format!("{} (1 monitor has its own)", base),
+            n => format!("{} ({} monitors have their own)", base, n),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Built-in profiles and example automations (platform-agnostic)
+//
+// builtin_profiles.json is compiled into the binary and gives new users
+// something to pick before they write any config: read-only profiles (Night,
+// Reading, Movie, Presentation) and example theme automations built on them.
+// Built-in profiles are listed and applied like saved ones (profiles.rs
+// falls back to them by name); a saved profile of the same name hides the
+// built-in. To change one, `duplicate` copies it into config `profiles`
+// under a new name. `use_example` copies an example into config
+// `theme_automation`.
+
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::config::AppConfig;
+use crate::profiles::{Profile, ThemeAutomation};
+
+const BUILTIN_JSON: &str = include_str!("builtin_profiles.json");
+
+/// A ready-made theme automation
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExampleRule {
+    pub name: String,
+    /// One line for menus and tooltips
+    pub description: String,
+    pub theme_automation: ThemeAutomation,
+}
+
+#[derive(Deserialize)]
+struct Builtins {
+    profiles: Vec<Profile>,
+    examples: Vec<ExampleRule>,
+}
+
+fn builtins() -> &'static Builtins {
+    static BUILTINS: OnceLock<Builtins> = OnceLock::new();
+    BUILTINS.get_or_init(|| {
+        serde_json::from_str(BUILTIN_JSON).expect("builtin_profiles.json is invalid")
+    })
+}
+
+/// The read-only profiles, in menu order
+pub fn profiles() -> &'static [Profile] {
+    &builtins().profiles
+}
+
+/// The example automations, in menu order
+pub fn examples() -> &'static [ExampleRule] {
+    &builtins().examples
+}
+
+/// Built-in profile named `name` (case-insensitive)
+pub fn find(name: &str) -> Option<&'static Profile> {
+    profiles()
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Copy the built-in profile at `index` into config `profiles` under a free
+/// name ("Night Copy", "Night Copy 2", …). Returns the new name.
+pub fn duplicate(config: &mut AppConfig, index: usize) -> Option<String> {
+    let mut profile = profiles().get(index)?.clone();
+    let base = format!("{} Copy", profile.name);
+    let taken = |name: &str| {
+        config
+            .profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name))
+    };
+    let name = if taken(&base) {
+        (2..)
+            .map(|n| format!("{} {}", base, n))
+            .find(|name| !taken(name))?
+    } else {
+        base
+    };
+    profile.name = name.clone();
+    config.profiles.push(profile);
+    Some(name)
+}
+
+/// Turn on the example automation at `index` in place of the configured
+/// one. The platform then applies the profile for the current OS theme.
+/// Returns the example's name.
+pub fn use_example(config: &mut AppConfig, index: usize) -> Option<&'static str> {
+    let example = examples().get(index)?;
+    config.theme_automation = ThemeAutomation {
+        enabled: true,
+        last_dark: None,
+        ..example.theme_automation.clone()
+    };
+    Some(&example.name)
+}
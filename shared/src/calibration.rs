@@ -0,0 +1,151 @@
+// Display calibration curves from ICC profiles (platform-agnostic)
+//
+// A calibrated display's ICC profile carries a 'vcgt' (video card gamma)
+// tag, the per-channel curve the OS loads into the display's gamma table.
+// Gamma dimming used to replace that table with a plain scaled line, which
+// threw the calibration away and, on displays whose curve already lowers
+// the white point, made the same level noticeably darker than on others.
+// Each platform reads the active profile (GetICMProfile on Windows,
+// CGDisplayCopyColorSpace on macOS) and hands its bytes to `from_icc`;
+// dimming then scales the calibrated curve instead of replacing it. Without
+// a profile, a 'vcgt' tag or with one that can't be parsed, the platforms
+// keep the plain line, as before.
+
+/// Per-channel calibration curve, samples evenly spaced over 0.0–1.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// Red, green and blue output (0.0–1.0)
+    pub channels: [Vec<f32>; 3],
+}
+
+/// Samples a formula-type 'vcgt' is evaluated at
+const FORMULA_SAMPLES: usize = 256;
+
+/// Curves within this of a straight line are treated as no calibration
+const IDENTITY_TOLERANCE: f32 = 0.002;
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// s15Fixed16Number
+fn be_fixed(data: &[u8], at: usize) -> Option<f32> {
+    Some(be_u32(data, at)? as i32 as f32 / 65536.0)
+}
+
+impl Calibration {
+    /// Calibration from the bytes of an ICC profile. None if the profile
+    /// has no usable 'vcgt' tag or its curve is a straight line.
+    pub fn from_icc(profile: &[u8]) -> Option<Self> {
+        let tag_count = be_u32(profile, 128)? as usize;
+        let vcgt = (0..tag_count.min(1024)).find_map(|i| {
+            let entry = 132 + i * 12;
+            let signature = profile.get(entry..entry + 4)?;
+            if signature != b"vcgt" {
+                return None;
+            }
+            let offset = be_u32(profile, entry + 4)? as usize;
+            let size = be_u32(profile, entry + 8)? as usize;
+            profile.get(offset..offset.checked_add(size)?)
+        })?;
+        let calibration = Self::from_vcgt(vcgt)?;
+        (!calibration.is_identity()).then_some(calibration)
+    }
+
+    /// Parse a 'vcgt' tag: type 0 is a table, type 1 a gamma formula
+    fn from_vcgt(tag: &[u8]) -> Option<Self> {
+        if tag.get(0..4)? != b"vcgt" {
+            return None;
+        }
+        match be_u32(tag, 8)? {
+            0 => {
+                let channel_count = be_u16(tag, 12)? as usize;
+                let entries = be_u16(tag, 14)? as usize;
+                let entry_size = be_u16(tag, 16)? as usize;
+                if !matches!(channel_count, 1 | 3) || entries < 2 || !matches!(entry_size, 1 | 2) {
+                    return None;
+                }
+                let max = if entry_size == 1 { 255.0 } else { 65535.0 };
+                let mut channels: [Vec<f32>; 3] = Default::default();
+                for (c, channel) in channels.iter_mut().enumerate().take(channel_count) {
+                    for i in 0..entries {
+                        let at = 18 + (c * entries + i) * entry_size;
+                        let value = if entry_size == 1 {
+                            *tag.get(at)? as f32
+                        } else {
+                            be_u16(tag, at)? as f32
+                        };
+                        channel.push(value / max);
+                    }
+                }
+                if channel_count == 1 {
+                    channels[1] = channels[0].clone();
+                    channels[2] = channels[0].clone();
+                }
+                Some(Self { channels })
+            }
+            1 => {
+                let mut channels: [Vec<f32>; 3] = Default::default();
+                for (c, channel) in channels.iter_mut().enumerate() {
+                    let at = 12 + c * 12;
+                    let (gamma, min, max) = (
+                        be_fixed(tag, at)?,
+                        be_fixed(tag, at + 4)?,
+                        be_fixed(tag, at + 8)?,
+                    );
+                    if gamma.is_nan() || gamma <= 0.0 {
+                        return None;
+                    }
+                    for i in 0..FORMULA_SAMPLES {
+                        let x = i as f32 / (FORMULA_SAMPLES - 1) as f32;
+                        channel.push((min + (max - min) * x.powf(gamma)).clamp(0.0, 1.0));
+                    }
+                }
+                Some(Self { channels })
+            }
+            _ => None,
+        }
+    }
+
+    /// True if every channel is a straight line from black to white
+    pub fn is_identity(&self) -> bool {
+        self.channels.iter().all(|channel| {
+            let last = (channel.len() - 1) as f32;
+            channel
+                .iter()
+                .enumerate()
+                .all(|(i, v)| (v - i as f32 / last).abs() <= IDENTITY_TOLERANCE)
+        })
+    }
+
+    /// Calibrated output of `channel` for input `x` (0.0–1.0), interpolated
+    pub fn sample(&self, channel: usize, x: f32) -> f32 {
+        let curve = &self.channels[channel];
+        let pos = x.clamp(0.0, 1.0) * (curve.len() - 1) as f32;
+        let i = (pos.floor() as usize).min(curve.len() - 2);
+        let t = pos - i as f32;
+        curve[i] + (curve[i + 1] - curve[i]) * t
+    }
+
+    /// Output for full white (brightest channel)
+    pub fn white(&self) -> f32 {
+        self.channels
+            .iter()
+            .filter_map(|channel| channel.last().copied())
+            .fold(0.0, f32::max)
+    }
+
+    /// The curve with white scaled to `scale`, `samples` entries per channel
+    pub fn scaled(&self, scale: f32, samples: usize) -> [Vec<f32>; 3] {
+        let last = (samples.max(2) - 1) as f32;
+        std::array::from_fn(|c| {
+            (0..samples.max(2))
+                .map(|i| self.sample(c, i as f32 / last) * scale)
+                .collect()
+        })
+    }
+}
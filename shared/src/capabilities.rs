@@ -0,0 +1,122 @@
+// Backend capabilities (platform-agnostic)
+//
+// Not every system can do everything the settings windows offer: capture
+// exclusion needs WDA_EXCLUDEFROMCAPTURE (Windows 10 2004 and later), and
+// macOS dims with gamma tables, which never show in captures anyway; some
+// Windows graphics drivers clamp deep gamma ramps, and monitors set to the
+// gamma backend fall back to the overlay there (gamma.rs). Each
+// platform's dimming backend answers with a `Capabilities` (overlay.rs,
+// `capabilities()`), and the settings windows disable the controls of
+// features it lacks, explaining why in the tooltip, instead of showing a
+// toggle that does nothing.
+
+/// What the dimming backend can do on this system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Dimming can be kept out of screenshots and recordings
+    pub capture_exclusion: bool,
+    /// Each monitor can have its own level
+    pub per_monitor: bool,
+    /// The dimming can be tinted warmer
+    pub warmth: bool,
+    /// Parts of a monitor can be left undimmed
+    pub regions: bool,
+    /// Monitor backlights can be set over DDC/CI
+    pub ddc: bool,
+    /// Gamma ramps take every level asked for (false once a driver
+    /// refused or clamped one)
+    pub gamma: bool,
+}
+
+/// A feature the settings windows may gate on `Capabilities`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    CaptureExclusion,
+    PerMonitor,
+    Warmth,
+    Regions,
+    Ddc,
+    Gamma,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [
+        Feature::CaptureExclusion,
+        Feature::PerMonitor,
+        Feature::Warmth,
+        Feature::Regions,
+        Feature::Ddc,
+        Feature::Gamma,
+    ];
+
+    /// Name for the diagnostics report
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::CaptureExclusion => "capture exclusion",
+            Feature::PerMonitor => "per-monitor levels",
+            Feature::Warmth => "warmth",
+            Feature::Regions => "regions",
+            Feature::Ddc => "DDC/CI",
+            Feature::Gamma => "gamma dimming",
+        }
+    }
+
+    /// Tooltip for the controls of this feature when it isn't supported
+    pub fn unsupported_reason(self) -> &'static str {
+        match self {
+            Feature::CaptureExclusion if cfg!(target_os = "macos") => {
+                "Gamma dimming never shows in screenshots or recordings"
+            }
+            Feature::CaptureExclusion => {
+                "This version of Windows can't keep dimming out of captures (needs Windows 10 2004 or later)"
+            }
+            Feature::PerMonitor => "This backend dims every monitor at the same level",
+            Feature::Warmth => "This backend can't tint the dimming",
+            Feature::Regions => "This backend dims whole monitors only",
+            Feature::Ddc => "Monitor backlights can't be set over DDC/CI on this system",
+            Feature::Gamma => {
+                "The graphics driver clamps gamma ramps this deep, so the overlay dims this monitor instead"
+            }
+        }
+    }
+}
+
+impl Capabilities {
+    pub fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::CaptureExclusion => self.capture_exclusion,
+            Feature::PerMonitor => self.per_monitor,
+            Feature::Warmth => self.warmth,
+            Feature::Regions => self.regions,
+            Feature::Ddc => self.ddc,
+            Feature::Gamma => self.gamma,
+        }
+    }
+
+    /// Tooltip for the controls of `feature`: `supported` when it is
+    /// supported, else why not
+    pub fn tooltip(&self, feature: Feature, supported: &'static str) -> &'static str {
+        if self.supports(feature) {
+            supported
+        } else {
+            feature.unsupported_reason()
+        }
+    }
+
+    /// Line for the diagnostics report
+    pub fn report_line(&self) -> String {
+        let names = |supported: bool| {
+            let names: Vec<&str> = Feature::ALL
+                .into_iter()
+                .filter(|f| self.supports(*f) == supported)
+                .map(Feature::name)
+                .collect();
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        };
+        format!("Backend supports: {}; not: {}\n", names(true), names(false))
+    }
+}
@@ -0,0 +1,68 @@
+// OS color filters alongside profiles (platform-agnostic)
+//
+// Colorblind users often pair dimming with a color filter (Windows: Settings
+// > Accessibility > Color filters). With `color_filters` on in config, a
+// profile's `color_filter` is applied together with its dimming, so the tray
+// menu or the theme automation sets up the whole viewing environment in one
+// go. Profiles without one leave the OS filter alone. Only Windows can
+// switch the filter; macOS ignores the setting.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::profiles;
+
+/// OS color filter state a profile asks for (profile `color_filter`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFilter {
+    /// Filter turned off
+    Off,
+    Grayscale,
+    Inverted,
+    GrayscaleInverted,
+    /// Red-green (green weak)
+    Deuteranopia,
+    /// Red-green (red weak)
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+impl ColorFilter {
+    /// Shown in toasts, e.g. "Color filter: Deuteranopia"
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorFilter::Off => "Off",
+            ColorFilter::Grayscale => "Grayscale",
+            ColorFilter::Inverted => "Inverted",
+            ColorFilter::GrayscaleInverted => "Grayscale inverted",
+            ColorFilter::Deuteranopia => "Deuteranopia",
+            ColorFilter::Protanopia => "Protanopia",
+            ColorFilter::Tritanopia => "Tritanopia",
+        }
+    }
+}
+
+/// Filter to apply along with the profile `name`, if the integration is on
+/// and the profile sets one
+pub fn for_profile(config: &AppConfig, name: &str) -> Option<ColorFilter> {
+    if !config.color_filters {
+        return None;
+    }
+    profiles::find(config, name)?.color_filter
+}
+
+/// One-line description for the settings UI, e.g. "Switched by 2 profiles"
+pub fn summary(config: &AppConfig) -> String {
+    match config
+        .profiles
+        .iter()
+        .filter(|p| p.color_filter.is_some())
+        .count()
+    {
+        0 => "No profile sets a color_filter yet".to_string(),
+        1 => "Switched by 1 profile".to_string(),
+        n => format!("Switched by {} profiles", n),
+    }
+}
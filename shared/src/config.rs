@@ -1,7 +1,36 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use crate::adjust_ramp::AdjustRamp;
+use crate::brightness_rule::BrightnessRule;
+use crate::config_text;
+use crate::contrast_guard::ContrastGuard;
+use crate::custom_actions::{CustomAction, KeyCombo, MAX_STEPS};
+use crate::dimming::{self, OpacityCurve, MAX_OPACITY};
+use crate::displays::DisplayInfo;
+use crate::edge_vignette::{self, EdgeVignette};
+use crate::fade::{self, FadeSettings};
+use crate::gradient::{OverlayGradient, MAX_SCALE};
+use crate::hotkeys::{self, HotkeyAction, MouseBinding, MouseHotkeys};
+use crate::monitor_groups::{self, MonitorGroup};
+use crate::monitor_id::{self, MonitorId};
+use crate::motion::Motion;
+use crate::focus_boost::FocusBoost;
+use crate::movie_mode::MovieMode;
+use crate::night_boost::{self, NightBoost};
+use crate::pen_pause::PenPause;
+use crate::profiles::{Profile, ThemeAutomation};
+use crate::reading_monitor::ReadingMonitor;
+use crate::scheduler::{self, DimmingSchedule};
+use crate::space_levels::SpaceLevels;
+use crate::tray_menu::{self, TrayItem};
+use crate::updater::UpdateSnooze;
+use crate::usage_stats::{self, WeeklySummary};
 
 /// Application configuration stored in JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,23 +46,171 @@ pub struct AppConfig {
     pub hotkey_toggle: String,
     pub hotkey_increase: String,
     pub hotkey_decrease: String,
+    /// Side mouse buttons (plus modifiers) bound to the hotkey actions
+    #[serde(default)]
+    pub mouse_hotkeys: MouseHotkeys,
+    /// Toggle hotkey followed by two digits sets an exact level (e.g. 4, 5 → 45%)
+    #[serde(default)]
+    pub level_chord: bool,
+    /// Hotkeys that each run a list of commands
+    #[serde(default)]
+    pub custom_actions: Vec<CustomAction>,
+    /// How the increase/decrease hotkeys speed up while held
+    #[serde(default)]
+    pub adjust_ramp: AdjustRamp,
+    /// Install no global hotkeys, input hooks or event taps at all
+    /// (tray and settings window still work)
+    #[serde(default)]
+    pub disable_global_shortcuts: bool,
+    /// Lift dimming while a pen/tablet is drawing
+    #[serde(default)]
+    pub pen_pause: PenPause,
+    /// Undim the display showing the focused video, dim the others more
+    #[serde(default)]
+    pub movie_mode: MovieMode,
+    /// Lift taken off the level by the boost hotkey while one window keeps
+    /// the focus
+    #[serde(default)]
+    pub focus_boost: FocusBoost,
+    /// Extra dimming on top of the manual level late at night
+    #[serde(default)]
+    pub night_boost: NightBoost,
+    /// Turn dimming on and off at set times of day
+    #[serde(default)]
+    pub schedule: DimmingSchedule,
+    /// Only dim monitors whose panel brightness is already high
+    #[serde(default)]
+    pub brightness_rule: BrightnessRule,
+    /// Dim portrait (reading) monitors a little more
+    #[serde(default)]
+    pub reading_monitor: ReadingMonitor,
+    /// Dim less while the focused monitor already shows dark content
+    #[serde(default)]
+    pub contrast_guard: ContrastGuard,
+    /// Separate levels per macOS Space (desktop)
+    #[serde(default)]
+    pub space_levels: SpaceLevels,
+    /// Weekly notification with the dimmed time and average level
+    #[serde(default)]
+    pub weekly_summary: WeeklySummary,
     #[serde(default = "default_auto_update")]
     pub auto_update: bool,
+    /// Skipped version and "remind me later" state of the update prompt
+    #[serde(default)]
+    pub update_snooze: UpdateSnooze,
     /// Multi-monitor independent brightness control
     #[serde(default)]
     pub multi_monitor: bool,
-    /// Per-monitor opacity values keyed by monitor index (0-based)
+    /// Per-monitor opacity keyed by monitor id (monitor_id.rs)
     #[serde(default)]
+    pub monitor_opacity: HashMap<MonitorId, f32>,
+    /// Older per-monitor opacity keyed by monitor index (Windows), moved to
+    /// `monitor_opacity` by `adopt_monitor_ids`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub per_monitor_opacity: HashMap<u32, f32>,
-    /// Per-display opacity keyed by display name (for persistence across reconnects)
-    #[serde(default)]
+    /// Older per-monitor opacity keyed by display name (macOS), moved to
+    /// `monitor_opacity` by `adopt_monitor_ids`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub per_display_opacity: HashMap<String, f32>,
+    /// Monitors sharing one level in multi-monitor mode (monitor_groups.rs)
+    #[serde(default)]
+    pub monitor_groups: Vec<MonitorGroup>,
+    /// Dimming method used on every monitor unless overridden below (Windows)
+    #[serde(default)]
+    pub dim_backend: DimBackend,
+    /// Per-monitor dimming method keyed by monitor id, for mixing overlay
+    /// and gamma dimming across displays (Windows)
+    #[serde(default)]
+    pub monitor_backend: HashMap<MonitorId, DimBackend>,
+    /// Older `monitor_backend` keyed by monitor index
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_monitor_backend: HashMap<u32, DimBackend>,
+    /// Per-monitor override of `allow_capture` keyed by monitor id, so
+    /// dimming can show up in recordings of one monitor only (Windows)
+    #[serde(default)]
+    pub monitor_allow_capture: HashMap<MonitorId, bool>,
+    /// Older `monitor_allow_capture` keyed by monitor index
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_monitor_allow_capture: HashMap<u32, bool>,
+    /// Designer mode: all dimming is suspended until explicitly turned off.
+    /// Schedules, rules and hotkeys can't re-enable anything meanwhile.
+    #[serde(default)]
+    pub designer_mode: bool,
+    /// Switch to gamma dimming while screen magnification is active, so
+    /// magnified areas aren't dimmed twice (Windows Magnifier)
+    #[serde(default)]
+    pub zoom_compat: bool,
+    /// Keep the lock screen dimmed by switching to gamma dimming while the
+    /// session is locked (Windows)
+    #[serde(default = "default_dim_lock_screen")]
+    pub dim_lock_screen: bool,
+    /// How slider levels map to applied opacity (stored levels are in this scale)
+    #[serde(default)]
+    pub opacity_curve: OpacityCurve,
+    /// Darker at the top of the screen, lighter at the bottom (overlay
+    /// dimming on Windows, see gradient.rs)
+    #[serde(default)]
+    pub overlay_gradient: OverlayGradient,
+    /// Extra dimming along the screen edges, for OLED panels at night
+    /// (overlay dimming on Windows, see edge_vignette.rs)
+    #[serde(default)]
+    pub edge_vignette: EdgeVignette,
+    /// Dimming method for monitors while they run in HDR (Windows).
+    /// Most drivers ignore gamma ramps in HDR, hence the overlay default.
+    #[serde(default)]
+    pub hdr_backend: DimBackend,
+    /// Added to the dim level of HDR monitors (-0.5–0.5), compensating for
+    /// the overlay looking washed out in HDR (Windows)
+    #[serde(default)]
+    pub hdr_opacity_offset: f32,
+    /// Saved dimming states, applied by name
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// Name of the profile applied most recently
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Switch profiles when the OS toggles between light and dark mode
+    #[serde(default)]
+    pub theme_automation: ThemeAutomation,
+    /// Apply each profile's `color_filter` to the OS color filter along with
+    /// its dimming (Windows, see color_filter.rs)
+    #[serde(default)]
+    pub color_filters: bool,
+    /// Release feed queried by the updater instead of GitHub (same JSON
+    /// shape as the GitHub "latest release" API). Usually set by IT through
+    /// defaults.json.
+    #[serde(default)]
+    pub update_endpoint: Option<String>,
+    /// Tray menu entries, top to bottom (see tray_menu.rs)
+    #[serde(default = "tray_menu::default_names")]
+    pub tray_menu: Vec<String>,
+    /// Fades: follow the OS reduced-motion setting, or force them off or on
+    #[serde(default)]
+    pub motion: Motion,
+    /// Fade in and out when dimming is turned on or off (see fade.rs)
+    #[serde(default)]
+    pub fade: FadeSettings,
+}
+
+/// How a monitor is dimmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DimBackend {
+    /// Translucent black click-through window (excluded from captures)
+    #[default]
+    Overlay,
+    /// Scaled display gamma ramp (affects captures, survives full-screen apps)
+    Gamma,
 }
 
 fn default_auto_update() -> bool {
     true
 }
 
+fn default_dim_lock_screen() -> bool {
+    true
+}
+
 fn default_last_opacity() -> f32 {
     0.3
 }
@@ -46,37 +223,882 @@ impl Default for AppConfig {
             launch_on_login: true,
             allow_capture: false,
             last_opacity: 0.3,
-            hotkey_toggle: "Ctrl+Alt+End".into(),
-            hotkey_increase: "Ctrl+Alt+Up".into(),
-            hotkey_decrease: "Ctrl+Alt+Down".into(),
+            hotkey_toggle: hotkeys::DEFAULT_KEYS[0].into(),
+            hotkey_increase: hotkeys::DEFAULT_KEYS[1].into(),
+            hotkey_decrease: hotkeys::DEFAULT_KEYS[2].into(),
+            mouse_hotkeys: MouseHotkeys::default(),
+            level_chord: false,
+            custom_actions: Vec::new(),
+            adjust_ramp: AdjustRamp::default(),
+            disable_global_shortcuts: false,
+            pen_pause: PenPause::default(),
+            movie_mode: MovieMode::default(),
+            focus_boost: FocusBoost::default(),
+            night_boost: NightBoost::default(),
+            schedule: DimmingSchedule::default(),
+            brightness_rule: BrightnessRule::default(),
+            reading_monitor: ReadingMonitor::default(),
+            contrast_guard: ContrastGuard::default(),
+            space_levels: SpaceLevels::default(),
+            weekly_summary: WeeklySummary::default(),
             auto_update: true,
+            update_snooze: UpdateSnooze::default(),
             multi_monitor: false,
+            monitor_opacity: HashMap::new(),
             per_monitor_opacity: HashMap::new(),
+            monitor_groups: Vec::new(),
             per_display_opacity: HashMap::new(),
+            dim_backend: DimBackend::Overlay,
+            monitor_backend: HashMap::new(),
+            per_monitor_backend: HashMap::new(),
+            monitor_allow_capture: HashMap::new(),
+            per_monitor_allow_capture: HashMap::new(),
+            designer_mode: false,
+            zoom_compat: false,
+            dim_lock_screen: true,
+            opacity_curve: OpacityCurve::Linear,
+            overlay_gradient: OverlayGradient::default(),
+            edge_vignette: EdgeVignette::default(),
+            hdr_backend: DimBackend::Overlay,
+            hdr_opacity_offset: 0.0,
+            profiles: Vec::new(),
+            active_profile: None,
+            theme_automation: ThemeAutomation::default(),
+            color_filters: false,
+            update_endpoint: None,
+            tray_menu: tray_menu::default_names(),
+            motion: Motion::System,
+            fade: FadeSettings::default(),
         }
     }
 }
 
-pub fn config_path() -> PathBuf {
+impl AppConfig {
+    /// Dimming level of a monitor in multi-monitor mode
+    pub fn level_for(&self, id: &MonitorId) -> f32 {
+        self.monitor_opacity
+            .get(id)
+            .copied()
+            .unwrap_or(self.opacity)
+    }
+
+    /// Set the level of monitor `id` and of the monitors grouped with it
+    /// (monitor_groups.rs). Returns the monitors set.
+    pub fn set_level_for(&mut self, id: &MonitorId, level: f32) -> Vec<MonitorId> {
+        let linked = monitor_groups::linked(&self.monitor_groups, id);
+        for member in &linked {
+            self.monitor_opacity.insert(member.clone(), level);
+        }
+        linked
+    }
+
+    /// Dimming method for a monitor
+    pub fn backend_for(&self, id: &MonitorId) -> DimBackend {
+        self.monitor_backend
+            .get(id)
+            .copied()
+            .unwrap_or(self.dim_backend)
+    }
+
+    /// Whether dimming on a monitor shows up in screenshots and recordings
+    pub fn allow_capture_for(&self, id: &MonitorId) -> bool {
+        self.monitor_allow_capture
+            .get(id)
+            .copied()
+            .unwrap_or(self.allow_capture)
+    }
+
+    /// Shortcuts of toggle, increase and decrease, as written
+    pub fn key_strings(&self) -> [&str; 3] {
+        [
+            &self.hotkey_toggle,
+            &self.hotkey_increase,
+            &self.hotkey_decrease,
+        ]
+    }
+
+    /// Keyboard shortcuts to register, with their actions
+    pub fn key_bindings(&self) -> Vec<(KeyCombo, HotkeyAction)> {
+        hotkeys::key_bindings(self.key_strings())
+    }
+
+    /// Give `action` (toggle, increase or decrease) the shortcut `combo`
+    /// (settings window recorder). The error says why not.
+    pub fn set_key(&mut self, action: HotkeyAction, combo: KeyCombo) -> Result<(), String> {
+        match action {
+            HotkeyAction::MovieMode => {
+                return Err("Movie mode's shortcut can't be changed".to_string());
+            }
+            HotkeyAction::Boost => {
+                return Err("The boost shortcut can't be changed".to_string());
+            }
+            _ => {}
+        }
+        if !combo.is_usable() {
+            let meta = if cfg!(target_os = "macos") { "Cmd" } else { "Win" };
+            return Err(format!("Hold Ctrl, Alt or {} with the key", meta));
+        }
+        let in_use = self
+            .key_bindings()
+            .into_iter()
+            .find(|(other, other_action)| *other == combo && *other_action != action)
+            .map(|(_, other_action)| other_action.label().to_string())
+            .or_else(|| {
+                self.custom_actions
+                    .iter()
+                    .find(|custom| custom.combo() == Some(combo))
+                    .map(|custom| custom.name.clone())
+            });
+        if let Some(user) = in_use {
+            return Err(format!("{} is already used by {}", combo, user));
+        }
+        let text = combo.to_string();
+        match action {
+            HotkeyAction::Toggle => self.hotkey_toggle = text,
+            HotkeyAction::Increase => self.hotkey_increase = text,
+            _ => self.hotkey_decrease = text,
+        }
+        Ok(())
+    }
+
+    /// Move per-monitor settings still keyed by monitor index or display
+    /// name (config, profiles, brightness rule) to the ids of the connected
+    /// `displays`. Returns true if anything moved and config needs saving.
+    pub fn adopt_monitor_ids(&mut self, displays: &[DisplayInfo]) -> bool {
+        let mut moved = monitor_id::adopt(
+            &mut self.monitor_opacity,
+            &mut self.per_monitor_opacity,
+            &mut self.per_display_opacity,
+            displays,
+        );
+        moved |= monitor_id::adopt(
+            &mut self.monitor_backend,
+            &mut self.per_monitor_backend,
+            &mut HashMap::new(),
+            displays,
+        );
+        moved |= monitor_id::adopt(
+            &mut self.monitor_allow_capture,
+            &mut self.per_monitor_allow_capture,
+            &mut HashMap::new(),
+            displays,
+        );
+        let rule = &mut self.brightness_rule;
+        moved |= monitor_id::adopt(
+            &mut rule.monitor_threshold,
+            &mut rule.per_monitor_threshold,
+            &mut rule.per_display_threshold,
+            displays,
+        );
+        for profile in self.profiles.iter_mut() {
+            moved |= monitor_id::adopt(
+                &mut profile.monitor_opacity,
+                &mut profile.per_monitor_opacity,
+                &mut profile.per_display_opacity,
+                displays,
+            );
+        }
+        if moved {
+            eprintln!("[config] per-monitor settings moved to monitor ids");
+        }
+        moved
+    }
+}
+
+// ── Save failure handling ───────────────────────────────────────────────────
+//
+// Roaming or read-only profiles (and full disks) make config writes fail.
+// Every failed write to the primary location is counted; the save is then
+// retried in a fallback location (local app data on Windows, ~/.config on
+// macOS, where managed Macs may redirect or lock Application Support). After
+// PRIMARY_FAILURE_LIMIT consecutive failures the primary is given up for the
+// rest of the session. load_config also probes the primary folder first, so
+// a folder that can't be written is caught at startup rather than on the
+// first save. The first failure fires the hook registered by the platform UI
+// exactly once, so the user hears about it.
+
+/// Consecutive primary failures before saves go straight to the fallback
+const PRIMARY_FAILURE_LIMIT: u32 = 3;
+
+static PRIMARY_FAILURES: AtomicU32 = AtomicU32::new(0);
+static TOTAL_FAILURES: AtomicU32 = AtomicU32::new(0);
+static USING_FALLBACK: AtomicBool = AtomicBool::new(false);
+static ALERTED: AtomicBool = AtomicBool::new(false);
+static LAST_FAILURE: Mutex<Option<SaveFailure>> = Mutex::new(None);
+static FAILURE_HOOK: Mutex<Option<fn()>> = Mutex::new(None);
+/// Folder used instead of the usual locations (`--fuzz-soak`)
+static DIR_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Details of the most recent failed config write
+#[derive(Debug, Clone)]
+pub struct SaveFailure {
+    /// Location that could not be written
+    pub path: PathBuf,
+    pub error: String,
+    /// Where the config went instead, if the fallback write succeeded
+    pub saved_to: Option<PathBuf>,
+}
+
+/// Keep config.json and its sidecar files in `dir` for this session, with
+/// no fallback. Call before load_config.
+pub fn use_dir(dir: PathBuf) {
+    *DIR_OVERRIDE.lock().unwrap() = Some(dir);
+}
+
+fn primary_config_path() -> PathBuf {
+    if let Some(dir) = DIR_OVERRIDE.lock().unwrap().as_ref() {
+        return dir.join("config.json");
+    }
     let base = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
     base.join("SaveMyEyes").join("config.json")
 }
 
+/// Alternate writable location used when the primary one fails
+fn fallback_config_path() -> Option<PathBuf> {
+    if DIR_OVERRIDE.lock().unwrap().is_some() {
+        return None;
+    }
+    let primary_base = dirs::config_dir();
+    // On macOS local app data is the primary folder too
+    let dot_config = dirs::home_dir()
+        .filter(|_| cfg!(target_os = "macos"))
+        .map(|home| home.join(".config"));
+    [dirs::data_local_dir(), dot_config, dirs::cache_dir()]
+        .into_iter()
+        .flatten()
+        .find(|base| Some(base) != primary_base.as_ref())
+        .map(|base| base.join("SaveMyEyes").join("config.json"))
+}
+
+/// Check the primary folder can be written (create it, write and remove a
+/// probe file). If not, saves go straight to the fallback this session.
+fn probe_primary() {
+    let primary = primary_config_path();
+    let probe = primary.with_file_name(".write-test");
+    let result = write_config(&probe, "").and_then(|()| fs::remove_file(&probe));
+    let Err(e) = result else {
+        return;
+    };
+    PRIMARY_FAILURES.store(PRIMARY_FAILURE_LIMIT, Ordering::SeqCst);
+    record_failure(primary, format!("folder not writable: {}", e));
+    let Some(fallback) = fallback_config_path() else {
+        return;
+    };
+    let fallback_probe = fallback.with_file_name(".write-test");
+    match write_config(&fallback_probe, "").and_then(|()| fs::remove_file(&fallback_probe)) {
+        Ok(()) => {
+            eprintln!("[config] using {} this session", fallback.display());
+            USING_FALLBACK.store(true, Ordering::SeqCst);
+            if let Some(failure) = LAST_FAILURE.lock().unwrap().as_mut() {
+                failure.saved_to = Some(fallback);
+            }
+        }
+        Err(e) => record_failure(fallback, format!("folder not writable: {}", e)),
+    }
+}
+
+/// Where config currently lives (the fallback once saves have moved there).
+/// Sidecar files (state.json, displays.json) sit next to it.
+pub fn config_path() -> PathBuf {
+    if USING_FALLBACK.load(Ordering::SeqCst) {
+        if let Some(path) = fallback_config_path() {
+            return path;
+        }
+    }
+    primary_config_path()
+}
+
 pub fn load_config() -> AppConfig {
-    let path = config_path();
-    if path.exists() {
+    probe_primary();
+    // A fallback copy newer than the primary means earlier saves failed
+    let primary = primary_config_path();
+    if let Some(fallback) = fallback_config_path() {
+        let modified = |p: &PathBuf| fs::metadata(p).and_then(|m| m.modified()).ok();
+        if let Some(fallback_at) = modified(&fallback) {
+            if modified(&primary).is_none_or(|primary_at| fallback_at > primary_at) {
+                USING_FALLBACK.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    // A read-only primary still holds the settings until the first save
+    let path = Some(config_path())
+        .filter(|path| path.exists())
+        .unwrap_or(primary);
+    let mut fixes = Vec::new();
+    let user = if path.exists() {
+        // Keep a copy from before this version first saves over it
+        crate::backups::check_version();
         let data = fs::read_to_string(&path).unwrap_or_default();
-        serde_json::from_str(&data).unwrap_or_default()
+        config_text::parse(&data).unwrap_or_else(|e| {
+            fixes.push(format!("config.json is not valid JSON ({}), using defaults", e));
+            Value::Null
+        })
     } else {
+        FIRST_RUN.store(true, Ordering::SeqCst);
+        Value::Null
+    };
+
+    let mut config = match load_deployment_defaults() {
+        Some(defaults) => layer_config(user, defaults),
+        None if user.is_null() => AppConfig::default(),
+        None => serde_json::from_value(user).unwrap_or_else(|e| {
+            fixes.push(format!("config.json has a bad value ({}), using defaults", e));
+            AppConfig::default()
+        }),
+    };
+    fixes.extend(config.sanitize());
+    for fix in &fixes {
+        eprintln!("[config] {}", fix);
+    }
+    *LOAD_FIXES.lock().unwrap() = fixes;
+    crate::journal::note_choice(&config);
+    crate::status_file::config_loaded(&config);
+    config
+}
+
+// ── Validation ──────────────────────────────────────────────────────────────
+//
+// A hand-edited config can hold anything serde accepts: "opacity": 7.5, an
+// empty hotkey string, a mouse binding without a button. load_config runs
+// `sanitize` on the result: levels are clamped into range and malformed
+// shortcut strings go back to their defaults. The file itself is left as it
+// is until the next save. Platforms show `load_fixes` once at startup.
+
+/// What load_config had to correct, one line per value
+static LOAD_FIXES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl AppConfig {
+    /// Coerce out-of-range levels and reset malformed shortcut strings to
+    /// their defaults. Returns a description of each change.
+    pub fn sanitize(&mut self) -> Vec<String> {
+        let mut fixes = Vec::new();
+        let defaults = AppConfig::default();
+
+        clamp_level(&mut fixes, "opacity", &mut self.opacity, 0.0, MAX_OPACITY);
+        clamp_level(&mut fixes, "last_opacity", &mut self.last_opacity, 0.0, MAX_OPACITY);
+        for (id, level) in self.monitor_opacity.iter_mut() {
+            let name = format!("monitor_opacity[{}]", id);
+            clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+        }
+        for (idx, level) in self.per_monitor_opacity.iter_mut() {
+            let name = format!("per_monitor_opacity[{}]", idx);
+            clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+        }
+        for (display, level) in self.per_display_opacity.iter_mut() {
+            let name = format!("per_display_opacity[{:?}]", display);
+            clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+        }
+        fixes.extend(monitor_groups::sanitize(&mut self.monitor_groups));
+        clamp_level(&mut fixes, "hdr_opacity_offset", &mut self.hdr_opacity_offset, -0.5, 0.5);
+        clamp_level(&mut fixes, "pen_pause.level", &mut self.pen_pause.level, 0.0, MAX_OPACITY);
+        clamp_level(&mut fixes, "movie_mode.boost", &mut self.movie_mode.boost, 0.0, MAX_OPACITY);
+        clamp_level(&mut fixes, "focus_boost.step", &mut self.focus_boost.step, 0.01, MAX_OPACITY);
+        clamp_level(&mut fixes, "night_boost.boost", &mut self.night_boost.boost, 0.0, MAX_OPACITY);
+        clamp_level(&mut fixes, "schedule.level", &mut self.schedule.level, 0.0, MAX_OPACITY);
+        let gradient = &mut self.overlay_gradient;
+        clamp_level(&mut fixes, "overlay_gradient.top", &mut gradient.top, 0.0, MAX_SCALE);
+        clamp_level(&mut fixes, "overlay_gradient.bottom", &mut gradient.bottom, 0.0, MAX_SCALE);
+        let vignette = &mut self.edge_vignette;
+        let shapes = std::iter::once(("edge_vignette.shape".to_string(), &mut vignette.shape)).chain(
+            vignette
+                .monitors
+                .iter_mut()
+                .map(|(id, shape)| (format!("edge_vignette.monitors[{}]", id), shape)),
+        );
+        for (name, shape) in shapes {
+            let width = edge_vignette::MAX_WIDTH;
+            clamp_level(&mut fixes, &format!("{}.width", name), &mut shape.width, 0.0, width);
+            let strength = &mut shape.strength;
+            clamp_level(&mut fixes, &format!("{}.strength", name), strength, 0.0, MAX_OPACITY);
+        }
+        let reading = &mut self.reading_monitor.opacity_offset;
+        clamp_level(&mut fixes, "reading_monitor.opacity_offset", reading, 0.0, MAX_OPACITY);
+        let guard = &mut self.contrast_guard;
+        clamp_level(&mut fixes, "contrast_guard.threshold", &mut guard.threshold, 0.0, 1.0);
+        clamp_level(&mut fixes, "contrast_guard.level", &mut guard.level, 0.0, MAX_OPACITY);
+        let rule = &mut self.brightness_rule;
+        clamp_level(&mut fixes, "brightness_rule.threshold", &mut rule.threshold, 0.0, 1.0);
+        for (id, threshold) in rule.monitor_threshold.iter_mut() {
+            let name = format!("brightness_rule.monitor_threshold[{}]", id);
+            clamp_level(&mut fixes, &name, threshold, 0.0, 1.0);
+        }
+        for (idx, threshold) in rule.per_monitor_threshold.iter_mut() {
+            let name = format!("brightness_rule.per_monitor_threshold[{}]", idx);
+            clamp_level(&mut fixes, &name, threshold, 0.0, 1.0);
+        }
+        for (display, threshold) in rule.per_display_threshold.iter_mut() {
+            let name = format!("brightness_rule.per_display_threshold[{:?}]", display);
+            clamp_level(&mut fixes, &name, threshold, 0.0, 1.0);
+        }
+        for (space, saved) in self.space_levels.levels.iter_mut() {
+            let name = format!("space_levels.levels[{:?}]", space);
+            clamp_level(&mut fixes, &name, &mut saved.opacity, 0.0, MAX_OPACITY);
+            for level in saved.monitor_opacity.values_mut() {
+                clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+            }
+        }
+        let ramp = &mut self.adjust_ramp;
+        clamp_level(&mut fixes, "adjust_ramp.initial_step", &mut ramp.initial_step, 0.001, MAX_OPACITY);
+        clamp_level(&mut fixes, "adjust_ramp.acceleration", &mut ramp.acceleration, 0.0, 5.0);
+        clamp_level(&mut fixes, "adjust_ramp.max_rate", &mut ramp.max_rate, 0.001, 5.0);
+        if self.fade.duration_ms > fade::MAX_DURATION_MS {
+            fixes.push(format!(
+                "fade.duration_ms {} is over {}, using {}",
+                self.fade.duration_ms,
+                fade::MAX_DURATION_MS,
+                fade::MAX_DURATION_MS
+            ));
+            self.fade.duration_ms = fade::MAX_DURATION_MS;
+        }
+        let night_defaults = NightBoost::default();
+        let schedule_defaults = DimmingSchedule::default();
+        let times = [
+            ("night_boost.start", &mut self.night_boost.start, night_defaults.start),
+            ("night_boost.end", &mut self.night_boost.end, night_defaults.end),
+            ("schedule.start", &mut self.schedule.start, schedule_defaults.start),
+            ("schedule.end", &mut self.schedule.end, schedule_defaults.end),
+        ];
+        for (name, value, default) in times {
+            if night_boost::parse_time(value).is_none() {
+                fixes.push(format!("{} {:?} is not a time (HH:MM), using {}", name, value, default));
+                *value = default;
+            }
+        }
+        if self.schedule.sanitize_days() {
+            fixes.push(format!(
+                "schedule.days repeats or is out of order, using {}",
+                scheduler::days_summary(&self.schedule.days)
+            ));
+        }
+        let coordinates = [
+            ("schedule.latitude", &mut self.schedule.latitude, 90.0),
+            ("schedule.longitude", &mut self.schedule.longitude, 180.0),
+        ];
+        for (name, value, max) in coordinates {
+            if let Some(degrees) = value.filter(|d| !(d.is_finite() && d.abs() <= max)) {
+                fixes.push(format!(
+                    "{} {} is outside -{}\u{2013}{}, asking the system for the location",
+                    name, degrees, max, max
+                ));
+                *value = None;
+            }
+        }
+        let summary_defaults = WeeklySummary::default();
+        if usage_stats::parse_day(&self.weekly_summary.day).is_none() {
+            fixes.push(format!(
+                "weekly_summary.day {:?} is not a day of the week, using {}",
+                self.weekly_summary.day, summary_defaults.day
+            ));
+            self.weekly_summary.day = summary_defaults.day;
+        }
+        if night_boost::parse_time(&self.weekly_summary.time).is_none() {
+            fixes.push(format!(
+                "weekly_summary.time {:?} is not a time (HH:MM), using {}",
+                self.weekly_summary.time, summary_defaults.time
+            ));
+            self.weekly_summary.time = summary_defaults.time;
+        }
+        for profile in self.profiles.iter_mut() {
+            let name = format!("profile {:?} opacity", profile.name);
+            clamp_level(&mut fixes, &name, &mut profile.opacity, 0.0, MAX_OPACITY);
+            for level in profile.monitor_opacity.values_mut() {
+                let name = format!("profile {:?} monitor level", profile.name);
+                clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+            }
+            for level in profile.per_monitor_opacity.values_mut() {
+                let name = format!("profile {:?} monitor level", profile.name);
+                clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+            }
+            for level in profile.per_display_opacity.values_mut() {
+                let name = format!("profile {:?} display level", profile.name);
+                clamp_level(&mut fixes, &name, level, 0.0, MAX_OPACITY);
+            }
+        }
+
+        if cfg!(target_os = "macos") && self.key_strings() == hotkeys::UNUSED_MACOS_KEYS {
+            self.hotkey_toggle = defaults.hotkey_toggle.clone();
+            self.hotkey_increase = defaults.hotkey_increase.clone();
+            self.hotkey_decrease = defaults.hotkey_decrease.clone();
+        }
+        // The fixed movie mode and boost shortcuts are taken
+        let mut combos: Vec<KeyCombo> = [hotkeys::MOVIE_MODE_KEYS, hotkeys::BOOST_KEYS]
+            .into_iter()
+            .filter_map(KeyCombo::parse)
+            .collect();
+        let keys = [
+            ("hotkey_toggle", &mut self.hotkey_toggle, defaults.hotkey_toggle),
+            ("hotkey_increase", &mut self.hotkey_increase, defaults.hotkey_increase),
+            ("hotkey_decrease", &mut self.hotkey_decrease, defaults.hotkey_decrease),
+        ];
+        for (name, value, default) in keys {
+            let problem = match KeyCombo::parse(value) {
+                Some(combo) if !combos.contains(&combo) => {
+                    combos.push(combo);
+                    continue;
+                }
+                Some(_) => "is used twice",
+                None => "is not a shortcut",
+            };
+            fixes.push(format!("{} {:?} {}, using {}", name, value, problem, default));
+            if let Some(combo) = KeyCombo::parse(&default) {
+                combos.push(combo);
+            }
+            *value = default;
+        }
+
+        for (i, action) in self.custom_actions.iter_mut().enumerate() {
+            if action.hotkey.is_empty() {
+                continue;
+            }
+            match KeyCombo::parse(&action.hotkey) {
+                Some(combo) if !combos.contains(&combo) => combos.push(combo),
+                parsed => {
+                    let problem = if parsed.is_some() {
+                        "is used twice"
+                    } else {
+                        "is not a shortcut"
+                    };
+                    fixes.push(format!(
+                        "custom_actions[{}].hotkey {:?} {}, clearing it",
+                        i, action.hotkey, problem
+                    ));
+                    action.hotkey.clear();
+                }
+            }
+            if action.steps.len() > MAX_STEPS {
+                fixes.push(format!(
+                    "custom_actions[{}] has {} steps, keeping the first {}",
+                    i,
+                    action.steps.len(),
+                    MAX_STEPS
+                ));
+                action.steps.truncate(MAX_STEPS);
+            }
+        }
+
+        let mouse_defaults = MouseHotkeys::default();
+        let buttons = [
+            ("mouse_hotkeys.toggle", &mut self.mouse_hotkeys.toggle, mouse_defaults.toggle),
+            ("mouse_hotkeys.increase", &mut self.mouse_hotkeys.increase, mouse_defaults.increase),
+            ("mouse_hotkeys.decrease", &mut self.mouse_hotkeys.decrease, mouse_defaults.decrease),
+        ];
+        for (name, value, default) in buttons {
+            if value.as_deref().is_some_and(|text| MouseBinding::parse(text).is_none()) {
+                fixes.push(format!(
+                    "{} {:?} is not a mouse binding, using {}",
+                    name,
+                    value.as_deref().unwrap_or_default(),
+                    default.as_deref().unwrap_or("none")
+                ));
+                *value = default;
+            }
+        }
+
+        let known = |name: &String| TrayItem::parse(name).is_some();
+        if !self.tray_menu.iter().all(known) {
+            let (kept, unknown): (Vec<String>, Vec<String>) =
+                self.tray_menu.drain(..).partition(known);
+            fixes.push(format!(
+                "tray_menu entries {:?} are unknown, ignoring them",
+                unknown
+            ));
+            self.tray_menu = kept;
+        }
+
+        fixes
+    }
+}
+
+/// Clamp `value` into `min..=max`, noting the change in `fixes`. In-range
+/// values are silently snapped to the level resolution (a tenth of a percent).
+fn clamp_level(fixes: &mut Vec<String>, name: &str, value: &mut f32, min: f32, max: f32) {
+    if value.is_finite() && (min..=max).contains(value) {
+        *value = dimming::quantize(*value);
+        return;
+    }
+    let fixed = if value.is_finite() { value.clamp(min, max) } else { min };
+    fixes.push(format!("{} {} is outside {}\u{2013}{}, using {}", name, value, min, max, fixed));
+    *value = fixed;
+}
+
+/// Corrections made by the last load_config (empty if the file was clean)
+pub fn load_fixes() -> Vec<String> {
+    LOAD_FIXES.lock().unwrap().clone()
+}
+
+/// Startup notification text for `fixes`
+pub fn describe_load_fixes(fixes: &[String]) -> String {
+    let noun = if fixes.len() == 1 { "setting" } else { "settings" };
+    format!("Corrected {} invalid {}: {}.", fixes.len(), noun, fixes.join("; "))
+}
+
+// ── Deployment defaults ─────────────────────────────────────────────────────
+//
+// IT departments can ship a defaults.json beside the executable (Windows) or
+// the app bundle (macOS, also read from Contents/Resources). It holds any
+// subset of config keys and is layered beneath the user's config: built-in
+// defaults, then defaults.json, then config.json. Keys listed in its
+// "locked" array always take the defaults.json value, and the settings UI
+// keeps them read-only. Example:
+//
+//   { "opacity": 0.4, "launch_on_login": true, "auto_update": false,
+//     "update_endpoint": "https://updates.example.com/savemyeyes/latest",
+//     "locked": ["auto_update", "update_endpoint"] }
+
+const DEFAULTS_FILE: &str = "defaults.json";
+const LOCKED_KEY: &str = "locked";
+
+/// No config file existed at load time
+static FIRST_RUN: AtomicBool = AtomicBool::new(false);
+/// A defaults.json was found and applied
+static DEPLOYED: AtomicBool = AtomicBool::new(false);
+/// Config keys pinned by defaults.json
+static LOCKED_KEYS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Candidate defaults.json locations, most specific first
+fn deployment_defaults_paths() -> Vec<PathBuf> {
+    let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+    else {
+        return Vec::new();
+    };
+    let mut paths = Vec::new();
+    // SaveMyEyes.app/Contents/MacOS/<exe>: Resources, then beside the bundle
+    if let Some(contents) = exe_dir.parent().filter(|_| exe_dir.ends_with("Contents/MacOS")) {
+        paths.push(contents.join("Resources").join(DEFAULTS_FILE));
+        if let Some(bundle_dir) = contents.parent().and_then(|bundle| bundle.parent()) {
+            paths.push(bundle_dir.join(DEFAULTS_FILE));
+        }
+    }
+    paths.push(exe_dir.join(DEFAULTS_FILE));
+    paths
+}
+
+fn load_deployment_defaults() -> Option<Map<String, Value>> {
+    for path in deployment_defaults_paths() {
+        let Ok(data) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match config_text::parse(&data) {
+            Ok(Value::Object(map)) => {
+                eprintln!("[config] using deployment defaults from {}", path.display());
+                return Some(map);
+            }
+            Ok(_) => eprintln!("[config] ignoring {}: not a JSON object", path.display()),
+            Err(e) => eprintln!("[config] ignoring {}: {}", path.display(), e),
+        }
+    }
+    None
+}
+
+/// Recursively overlay `over` onto `base` (objects merge, anything else replaces)
+fn merge_json(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+/// Built-in defaults, then deployment defaults, then the user's config,
+/// with locked keys forced back to the deployment value.
+fn layer_config(user: Value, mut defaults: Map<String, Value>) -> AppConfig {
+    let locked: Vec<String> = match defaults.remove(LOCKED_KEY) {
+        Some(Value::Array(keys)) => keys
+            .into_iter()
+            .filter_map(|k| k.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut merged = serde_json::to_value(AppConfig::default()).unwrap_or(Value::Null);
+    merge_json(&mut merged, Value::Object(defaults.clone()));
+    if user.is_object() {
+        merge_json(&mut merged, user);
+    }
+    if let Value::Object(map) = &mut merged {
+        for key in &locked {
+            if let Some(value) = defaults.get(key) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    DEPLOYED.store(true, Ordering::SeqCst);
+    *LOCKED_KEYS.lock().unwrap() = locked;
+    serde_json::from_value(merged).unwrap_or_else(|e| {
+        eprintln!("[config] deployment defaults not applied: {}", e);
         AppConfig::default()
+    })
+}
+
+/// True on the first launch of a managed deployment: defaults.json was
+/// applied and there was no user config yet. Platforms use it to act on
+/// seeded settings that live outside config (e.g. login items).
+pub fn is_first_deployed_run() -> bool {
+    FIRST_RUN.load(Ordering::SeqCst) && DEPLOYED.load(Ordering::SeqCst)
+}
+
+/// True if `key` is pinned by defaults.json and shouldn't be changed in the UI
+pub fn is_locked(key: &str) -> bool {
+    LOCKED_KEYS.lock().unwrap().iter().any(|k| k == key)
+}
+
+/// Write `data` to `path` through a temporary file, so a reader never sees
+/// it half-written
+fn write_config(path: &PathBuf, data: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
 }
 
 pub fn save_config(config: &AppConfig) {
-    let path = config_path();
-    if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
+    crate::journal::note_choice(config);
+    crate::status_file::config_saved(config);
+    let data = config_text::render(config);
+
+    let primary = primary_config_path();
+    if PRIMARY_FAILURES.load(Ordering::SeqCst) < PRIMARY_FAILURE_LIMIT {
+        match write_config(&primary, &data) {
+            Ok(()) => {
+                PRIMARY_FAILURES.store(0, Ordering::SeqCst);
+                USING_FALLBACK.store(false, Ordering::SeqCst);
+                return;
+            }
+            Err(e) => {
+                PRIMARY_FAILURES.fetch_add(1, Ordering::SeqCst);
+                record_failure(primary, e.to_string());
+            }
+        }
+    }
+
+    let Some(fallback) = fallback_config_path() else {
+        return;
+    };
+    match write_config(&fallback, &data) {
+        Ok(()) => {
+            USING_FALLBACK.store(true, Ordering::SeqCst);
+            if let Some(failure) = LAST_FAILURE.lock().unwrap().as_mut() {
+                failure.saved_to = Some(fallback);
+            }
+        }
+        Err(e) => record_failure(fallback, e.to_string()),
+    }
+    notify_failure();
+}
+
+fn record_failure(path: PathBuf, error: String) {
+    let total = TOTAL_FAILURES.fetch_add(1, Ordering::SeqCst) + 1;
+    eprintln!(
+        "[config] save to {} failed ({} so far): {}",
+        path.display(),
+        total,
+        error
+    );
+    *LAST_FAILURE.lock().unwrap() = Some(SaveFailure {
+        path,
+        error,
+        saved_to: None,
+    });
+}
+
+/// Fire the failure hook, once per session. Without a hook yet (failure
+/// during startup) the alert is held until one is registered.
+fn notify_failure() {
+    if LAST_FAILURE.lock().unwrap().is_none() {
+        return;
+    }
+    let Some(hook) = *FAILURE_HOOK.lock().unwrap() else {
+        return;
+    };
+    if !ALERTED.swap(true, Ordering::SeqCst) {
+        hook();
+    }
+}
+
+/// Register the callback fired (once) after the first failed save. It may
+/// run on any thread; read the details with `last_save_failure`.
+pub fn set_save_failure_hook(hook: fn()) {
+    *FAILURE_HOOK.lock().unwrap() = Some(hook);
+    notify_failure();
+}
+
+/// Most recent failed write, if any
+pub fn last_save_failure() -> Option<SaveFailure> {
+    LAST_FAILURE.lock().unwrap().clone()
+}
+
+/// Number of failed config writes this session (diagnostics)
+pub fn save_failure_count() -> u32 {
+    TOTAL_FAILURES.load(Ordering::SeqCst)
+}
+
+/// Human-readable summary of a save failure for alerts and toasts
+pub fn describe_save_failure(failure: &SaveFailure) -> String {
+    match &failure.saved_to {
+        Some(saved_to) => format!(
+            "Could not write {} ({}). Settings are being saved to {} instead.",
+            failure.path.display(),
+            failure.error,
+            saved_to.display()
+        ),
+        None => format!(
+            "Could not write {} ({}). Changes will be lost when SaveMyEyes exits.",
+            failure.path.display(),
+            failure.error
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_frees_the_fixed_shortcuts() {
+        let defaults = AppConfig::default();
+        for fixed in [hotkeys::MOVIE_MODE_KEYS, hotkeys::BOOST_KEYS] {
+            let mut cfg = AppConfig {
+                hotkey_toggle: fixed.to_string(),
+                custom_actions: vec![CustomAction {
+                    name: "Reading".to_string(),
+                    hotkey: fixed.to_string(),
+                    steps: Vec::new(),
+                }],
+                ..AppConfig::default()
+            };
+            let fixes = cfg.sanitize();
+            assert!(fixes.iter().any(|f| f.starts_with("hotkey_toggle")), "{:?}", fixes);
+            assert!(fixes.iter().any(|f| f.starts_with("custom_actions[0]")), "{:?}", fixes);
+            assert_eq!(cfg.hotkey_toggle, defaults.hotkey_toggle);
+            assert!(cfg.custom_actions[0].hotkey.is_empty());
+        }
+    }
+
+    #[test]
+    fn key_bindings_hold_each_combo_once() {
+        let mut cfg = AppConfig {
+            hotkey_toggle: hotkeys::BOOST_KEYS.to_string(),
+            ..AppConfig::default()
+        };
+        cfg.sanitize();
+        let bindings = cfg.key_bindings();
+        for (combo, _) in &bindings {
+            let uses = bindings.iter().filter(|(other, _)| other == combo).count();
+            assert_eq!(uses, 1, "{} bound {} times", combo, uses);
+        }
     }
-    let data = serde_json::to_string_pretty(config).unwrap_or_default();
-    let _ = fs::write(&path, data);
 }
@@ -0,0 +1,541 @@
+// Format-preserving config.json saves (platform-agnostic)
+//
+// People hand-edit config.json, and every save used to rewrite it from
+// scratch: comments gone, keys back in the order of the struct. The file is
+// now read as JSON with comments (`//`, `/* */`) and trailing commas, and
+// save_config patches the text already on disk instead of replacing it:
+//   • unchanged values stay byte for byte, with their comments and layout
+//   • a changed object is patched key by key; any other changed value is
+//     written anew in its place
+//   • keys the app no longer writes are removed along with their line
+//   • new keys go at the end of their object, in the file's line endings
+// With no file yet, or one that doesn't parse, the save writes plain
+// pretty-printed JSON as before.
+
+use serde_json::{Map, Value};
+use std::fs;
+
+use crate::config::{config_path, AppConfig};
+
+/// Parse JSON that may hold comments and trailing commas
+pub fn parse(text: &str) -> serde_json::Result<Value> {
+    serde_json::from_slice(&blank_trailing_commas(blank_comments(text)))
+}
+
+/// The text save_config writes for `config`: the file on disk patched where
+/// it differs, or fresh pretty-printed JSON.
+pub(crate) fn render(config: &AppConfig) -> String {
+    render_over(fs::read_to_string(config_path()).ok().as_deref(), config)
+}
+
+/// `existing` patched to hold `config`, or fresh pretty-printed JSON
+fn render_over(existing: Option<&str>, config: &AppConfig) -> String {
+    let pretty = serde_json::to_string_pretty(config).unwrap_or_default();
+    let Some(existing) = existing else {
+        return pretty;
+    };
+    // Through the text, so f32 values compare as they are written ("0.3")
+    let new = serde_json::to_string(config)
+        .ok()
+        .and_then(|json| serde_json::from_str::<Value>(&json).ok());
+    new.and_then(|new| patch(existing, &new)).unwrap_or(pretty)
+}
+
+/// `text` with comments turned into spaces. Every byte keeps its offset, so
+/// parse errors and spans point into the original.
+fn blank_comments(text: &str) -> Vec<u8> {
+    let mut out = text.as_bytes().to_vec();
+    let mut i = 0;
+    let mut in_string = false;
+    while i < out.len() {
+        if in_string {
+            match out[i] {
+                b'\\' => i += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        match (out[i], out.get(i + 1)) {
+            (b'"', _) => in_string = true,
+            (b'/', Some(b'/')) => {
+                while i < out.len() && out[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            (b'/', Some(b'*')) => {
+                let end = out[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(out.len(), |p| i + 2 + p + 2);
+                // Newlines stay, so error line numbers still match
+                for byte in &mut out[i..end] {
+                    if *byte != b'\n' {
+                        *byte = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    out
+}
+
+/// `b` (comments blanked) with commas before `}` and `]` turned into spaces
+fn blank_trailing_commas(mut out: Vec<u8>) -> Vec<u8> {
+    let mut comma = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for i in 0..out.len() {
+        let byte = out[i];
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => {
+                in_string = true;
+                comma = None;
+            }
+            b',' => comma = Some(i),
+            b'}' | b']' => {
+                if let Some(at) = comma.take() {
+                    out[at] = b' ';
+                }
+            }
+            _ if byte.is_ascii_whitespace() => {}
+            _ => comma = None,
+        }
+    }
+    out
+}
+
+/// Where a value sits in the blanked text
+struct Span {
+    start: usize,
+    end: usize,
+    /// Members, for objects
+    members: Option<Vec<Member>>,
+}
+
+struct Member {
+    key: String,
+    /// Offset of the key's opening quote
+    start: usize,
+    value: Span,
+    /// Offset of the comma after the value, if any
+    comma: Option<usize>,
+}
+
+fn skip_blank(b: &[u8], mut i: usize) -> usize {
+    while b.get(i).is_some_and(|c| c.is_ascii_whitespace()) {
+        i += 1;
+    }
+    i
+}
+
+/// End of the string starting at `i` (one past the closing quote)
+fn string_end(b: &[u8], mut i: usize) -> usize {
+    i += 1;
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    b.len()
+}
+
+/// Scan the value starting at `i`. `b` has its comments blanked and parses
+/// as JSON once trailing commas are blanked too.
+fn scan(b: &[u8], i: usize) -> Span {
+    match b.get(i) {
+        Some(b'{') => {
+            let mut members = Vec::new();
+            let mut j = skip_blank(b, i + 1);
+            while j < b.len() && b[j] != b'}' {
+                let start = j;
+                let key_end = string_end(b, j);
+                let key = serde_json::from_slice(&b[start..key_end]).unwrap_or_default();
+                // Past the colon
+                j = skip_blank(b, skip_blank(b, key_end) + 1);
+                let value = scan(b, j);
+                j = skip_blank(b, value.end);
+                let comma = (b.get(j) == Some(&b',')).then_some(j);
+                if comma.is_some() {
+                    j = skip_blank(b, j + 1);
+                }
+                members.push(Member {
+                    key,
+                    start,
+                    value,
+                    comma,
+                });
+            }
+            Span {
+                start: i,
+                end: (j + 1).min(b.len()),
+                members: Some(members),
+            }
+        }
+        Some(b'[') => {
+            let mut j = skip_blank(b, i + 1);
+            while j < b.len() && b[j] != b']' {
+                j = skip_blank(b, scan(b, j).end);
+                if b.get(j) == Some(&b',') {
+                    j = skip_blank(b, j + 1);
+                }
+            }
+            Span {
+                start: i,
+                end: (j + 1).min(b.len()),
+                members: None,
+            }
+        }
+        Some(b'"') => Span {
+            start: i,
+            end: string_end(b, i),
+            members: None,
+        },
+        _ => {
+            let mut j = i;
+            while j < b.len() && !b[j].is_ascii_whitespace() && !b",}]".contains(&b[j]) {
+                j += 1;
+            }
+            Span {
+                start: i,
+                end: j.max(i + 1).min(b.len()),
+                members: None,
+            }
+        }
+    }
+}
+
+/// Equal as config values; numbers compare by value, so 1 and 1.0 match
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| same(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| same(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+/// One replacement: bytes `start..end` of the original become the text
+type Edit = (usize, usize, String);
+
+/// Start of the line holding `i`
+fn line_start(b: &[u8], i: usize) -> usize {
+    b[..i]
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |p| p + 1)
+}
+
+/// Leading whitespace of the line holding `i`, in the original text
+fn indent_at(text: &str, i: usize) -> String {
+    let start = line_start(text.as_bytes(), i);
+    text[start..i]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// Offset of the line break ("\n" or "\r\n") after `i`, if only blanks
+/// come before it
+fn line_break(b: &[u8], i: usize) -> Option<usize> {
+    let mut j = i;
+    while j < b.len() && matches!(b[j], b' ' | b'\t') {
+        j += 1;
+    }
+    match (b.get(j), b.get(j + 1)) {
+        (Some(b'\n'), _) | (Some(b'\r'), Some(b'\n')) => Some(j),
+        _ => None,
+    }
+}
+
+/// Where to add a line after `i`: its line break, or `i` itself
+fn line_end(b: &[u8], i: usize) -> usize {
+    line_break(b, i).unwrap_or(i)
+}
+
+/// `start..end` widened to whole lines when nothing else is on them
+fn whole_lines(b: &[u8], start: usize, end: usize) -> (usize, usize) {
+    let first = line_start(b, start);
+    match line_break(b, end) {
+        Some(last) if b[first..start].iter().all(|c| c.is_ascii_whitespace()) => {
+            (first, last + if b[last] == b'\r' { 2 } else { 1 })
+        }
+        _ => (start, end),
+    }
+}
+
+/// `value` pretty-printed, continuation lines indented by `indent`
+fn pretty(value: &Value, indent: &str) -> String {
+    let text = serde_json::to_string_pretty(value).unwrap_or_default();
+    text.replace('\n', &format!("\n{}", indent))
+}
+
+/// `"key": value`, for a member inserted at `indent`
+fn member_text(key: &str, value: &Value, indent: &str) -> String {
+    let key = serde_json::to_string(key).unwrap_or_default();
+    format!("{}: {}", key, pretty(value, indent))
+}
+
+/// Patch the object at `span` from `old` to `new`. False if it has to be
+/// rewritten whole.
+fn patch_object(
+    text: &str,
+    b: &[u8],
+    span: &Span,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    edits: &mut Vec<Edit>,
+) -> bool {
+    let Some(members) = &span.members else {
+        return false;
+    };
+    let Some(last_kept) = members.iter().rposition(|m| new.contains_key(&m.key)) else {
+        return new.is_empty() && members.is_empty();
+    };
+    let added: Vec<(&String, &Value)> = new
+        .iter()
+        .filter(|(key, _)| !members.iter().any(|m| &m.key == *key))
+        .collect();
+
+    for (index, member) in members.iter().enumerate() {
+        let Some(value) = new.get(&member.key) else {
+            let (start, end) = match member.comma {
+                Some(comma) => whole_lines(b, member.start, comma + 1),
+                None => whole_lines(b, member.start, member.value.end),
+            };
+            edits.push((start, end, String::new()));
+            continue;
+        };
+        if old.get(&member.key).is_some_and(|old| same(old, value)) {
+            continue;
+        }
+        // Only the last of duplicate keys counts when loading
+        if members[index + 1..].iter().any(|m| m.key == member.key) {
+            continue;
+        }
+        let indent = indent_at(text, member.start);
+        if let (Some(Value::Object(old)), Value::Object(value)) = (old.get(&member.key), value) {
+            if patch_object(text, b, &member.value, old, value, edits) {
+                continue;
+            }
+        }
+        edits.push((member.value.start, member.value.end, pretty(value, &indent)));
+    }
+
+    let kept = &members[last_kept];
+    let indent = indent_at(text, members[0].start);
+    if added.is_empty() {
+        // Members after the last kept one are gone; so is its comma
+        if last_kept + 1 < members.len() && members.last().is_some_and(|m| m.comma.is_none()) {
+            if let Some(comma) = kept.comma {
+                edits.push((comma, comma + 1, String::new()));
+            }
+        }
+        return true;
+    }
+    let lines: Vec<String> = added
+        .iter()
+        .map(|(key, value)| format!("\n{}{}", indent, member_text(key, value, &indent)))
+        .collect();
+    let mut inserted = lines.join(",");
+    match kept.comma {
+        Some(comma) => {
+            // A trailing comma after the last member stays the style
+            if last_kept + 1 == members.len() {
+                inserted.push(',');
+            }
+            let at = line_end(b, comma + 1);
+            edits.push((at, at, inserted));
+        }
+        None => {
+            let at = line_end(b, kept.value.end);
+            if at == kept.value.end {
+                edits.push((at, at, format!(",{}", inserted)));
+            } else {
+                edits.push((kept.value.end, kept.value.end, ",".to_string()));
+                edits.push((at, at, inserted));
+            }
+        }
+    }
+    true
+}
+
+/// `text` with its values changed to `new`, keeping everything else. None
+/// if the text doesn't parse or isn't an object.
+fn patch(text: &str, new: &Value) -> Option<String> {
+    let b = blank_comments(text);
+    let Ok(Value::Object(old)) = serde_json::from_slice(&blank_trailing_commas(b.clone())) else {
+        return None;
+    };
+    let Value::Object(new) = new else {
+        return None;
+    };
+    let root = scan(&b, skip_blank(&b, 0));
+    let mut edits = Vec::new();
+    if !patch_object(text, &b, &root, &old, new, &mut edits) {
+        return None;
+    }
+
+    // Back to front, so earlier offsets stay valid
+    edits.sort_by_key(|(start, end, _)| std::cmp::Reverse((*start, *end)));
+    // New lines follow the file's line endings
+    let crlf = text.contains("\r\n");
+    let mut out = text.to_string();
+    let mut limit = out.len();
+    for (start, end, replacement) in edits {
+        if end > limit {
+            return None;
+        }
+        if crlf {
+            out.replace_range(start..end, &replacement.replace('\n', "\r\n"));
+        } else {
+            out.replace_range(start..end, &replacement);
+        }
+        limit = start;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// `text` patched with `edit` applied to its values. The result must
+    /// load as exactly the edited values.
+    fn patched(text: &str, edit: impl FnOnce(&mut Map<String, Value>)) -> String {
+        let mut new = parse(text).unwrap();
+        edit(new.as_object_mut().unwrap());
+        let out = patch(text, &new).unwrap();
+        assert_eq!(parse(&out).unwrap(), new, "{}", out);
+        out
+    }
+
+    #[test]
+    fn unchanged_text_stays_byte_for_byte() {
+        let text = "// SaveMyEyes\n{\n  \"opacity\": 0.3, /* 30% */\n  \"hotkey_toggle\": \"Ctrl+Alt+D\",\n}\n";
+        assert_eq!(patched(text, |_| {}), text);
+    }
+
+    #[test]
+    fn comments_survive_a_changed_scalar() {
+        let text = concat!(
+            "{\n",
+            "  // Dim level, 0-1\n",
+            "  \"opacity\": 0.3,\n",
+            "  /* not a comment: */ \"url\": \"http://x/* y */ // z\",\n",
+            "  \"is_enabled\": true /* on */\n",
+            "}\n",
+        );
+        assert_eq!(parse(text).unwrap()["url"], "http://x/* y */ // z");
+        let out = patched(text, |config| {
+            config.insert("opacity".into(), json!(0.5));
+            config.insert("is_enabled".into(), json!(false));
+        });
+        let expected = text
+            .replace("\"opacity\": 0.3", "\"opacity\": 0.5")
+            .replace("true /* on */", "false /* on */");
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn trailing_commas_stay_the_style() {
+        let text = "{\n  \"a\": 1,\n  \"b\": [1, 2,],\n}\n";
+        let out = patched(text, |config| {
+            config.insert("a".into(), json!(2));
+            config.insert("c".into(), json!(3));
+        });
+        assert_eq!(out, "{\n  \"a\": 2,\n  \"b\": [1, 2,],\n  \"c\": 3,\n}\n");
+    }
+
+    #[test]
+    fn keys_are_added_and_removed_by_line() {
+        let text = "{\n  \"a\": 1,\n  \"b\": 2 // old\n}\n";
+        let out = patched(text, |config| {
+            config.remove("b");
+            config.insert("c".into(), json!({ "d": true }));
+        });
+        assert_eq!(out, "{\n  \"a\": 1,\n  \"c\": {\n    \"d\": true\n  }\n}\n");
+
+        let out = patched("{\"a\":1,\"b\":2}", |config| {
+            config.remove("b");
+        });
+        assert_eq!(out, "{\"a\":1}");
+    }
+
+    #[test]
+    fn nested_objects_are_patched_key_by_key() {
+        let text =
+            "{\n  \"outer\": {\n    // keep\n    \"x\": 1,\n    \"y\": 2\n  },\n  \"z\": 0\n}";
+        let out = patched(text, |config| {
+            let outer = config["outer"].as_object_mut().unwrap();
+            outer.insert("y".into(), json!(3));
+            outer.insert("w".into(), json!(true));
+        });
+        assert_eq!(
+            out,
+            "{\n  \"outer\": {\n    // keep\n    \"x\": 1,\n    \"y\": 3,\n    \"w\": true\n  },\n  \"z\": 0\n}"
+        );
+    }
+
+    #[test]
+    fn only_the_last_duplicate_key_is_patched() {
+        let text = "{\"a\": 1, \"a\": 2}";
+        assert_eq!(parse(text).unwrap()["a"], 2);
+        let out = patched(text, |config| {
+            config.insert("a".into(), json!(5));
+        });
+        assert_eq!(out, "{\"a\": 1, \"a\": 5}");
+    }
+
+    #[test]
+    fn crlf_line_endings_are_kept() {
+        let text = "{\r\n  \"a\": 1,\r\n  \"b\": 2\r\n}\r\n";
+        let out = patched(text, |config| {
+            config.remove("b");
+            config.insert("c".into(), json!([1, 2]));
+        });
+        assert_eq!(
+            out,
+            "{\r\n  \"a\": 1,\r\n  \"c\": [\r\n    1,\r\n    2\r\n  ]\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn unparsable_file_gets_a_plain_render() {
+        let config = AppConfig::default();
+        let pretty = serde_json::to_string_pretty(&config).unwrap();
+        assert_eq!(render_over(None, &config), pretty);
+        assert_eq!(render_over(Some("{ \"opacity\": "), &config), pretty);
+        assert_eq!(render_over(Some("[1, 2]"), &config), pretty);
+        assert_eq!(patch("{ oops }", &json!({})), None);
+
+        // A file that does parse is patched instead
+        let text = "{ /* mine */ \"opacity\": 0.9 }";
+        let out = render_over(Some(text), &config);
+        assert!(out.starts_with("{ /* mine */ \"opacity\": 0.3,"), "{}", out);
+    }
+}
@@ -0,0 +1,111 @@
+// Adaptive contrast guard (platform-agnostic)
+//
+// Heavy dimming over a dark IDE theme turns grey text into no text. With
+// the guard on, each platform samples the focused monitor every
+// `interval_secs`: a SAMPLE_WIDTH x SAMPLE_HEIGHT downscale of what is on
+// screen (GDI on Windows, CGDisplayCreateImage on macOS), read without our
+// own dimming. When its `average_luminance` is below `threshold`, that
+// monitor's level is capped at `level` until the screen brightens again.
+// Sampling is cheap but not free, so a sample that takes longer than
+// SAMPLE_BUDGET stretches the interval (up to MAX_BACKOFF times) until
+// samples are quick again, and nothing is sampled while the focused monitor
+// isn't dimmed. Like pen pause the cap is applied on top of the configured
+// levels and never saved.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::dimming::{percent_label, MAX_OPACITY};
+
+/// Size of the downscaled sample, in pixels
+pub const SAMPLE_WIDTH: usize = 64;
+pub const SAMPLE_HEIGHT: usize = 36;
+
+/// Longest a sample may take before sampling slows down
+pub const SAMPLE_BUDGET: Duration = Duration::from_millis(20);
+
+/// Longest interval after backing off, as a multiple of `interval_secs`
+const MAX_BACKOFF: u32 = 8;
+
+/// How far luminance must rise above the threshold before the cap lifts, so
+/// scrolling past a bright line doesn't make the screen pump
+const HYSTERESIS: f32 = 0.05;
+
+/// Contrast guard rule. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContrastGuard {
+    pub enabled: bool,
+    /// Average luminance (0.0–1.0) below which the screen counts as dark
+    pub threshold: f32,
+    /// Highest level while the focused monitor is dark
+    pub level: f32,
+    /// Seconds between samples
+    pub interval_secs: u64,
+}
+
+impl Default for ContrastGuard {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.2,
+            level: 0.3,
+            interval_secs: 5,
+        }
+    }
+}
+
+impl ContrastGuard {
+    /// Level cap for a monitor showing `luminance`, or None when dimming is
+    /// unrestricted. `capped` is whether it was capped at the last sample.
+    pub fn cap(&self, luminance: f32, capped: bool) -> Option<f32> {
+        if !self.enabled {
+            return None;
+        }
+        let threshold = if capped {
+            self.threshold + HYSTERESIS
+        } else {
+            self.threshold
+        };
+        (luminance < threshold).then(|| self.level.clamp(0.0, MAX_OPACITY))
+    }
+
+    /// Time between samples when they stay within budget
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+
+    /// Wait before the next sample, given the last wait and how long the
+    /// last sample took
+    pub fn next_wait(&self, last_wait: Duration, took: Duration) -> Duration {
+        if took > SAMPLE_BUDGET {
+            (last_wait * 2).min(self.interval() * MAX_BACKOFF)
+        } else {
+            self.interval()
+        }
+    }
+
+    /// One-line summary for the settings UI
+    pub fn summary(&self) -> String {
+        format!(
+            "Dark screens dimmed at most {} (below {} luminance)",
+            percent_label(self.level),
+            percent_label(self.threshold)
+        )
+    }
+}
+
+/// Average luminance (0.0–1.0) of 8-bit pixels, 4 bytes each, with red,
+/// green and blue at the offsets in `rgb`. Rec. 709 weights on the stored
+/// (gamma-encoded) values, which is close to how light a screen looks.
+pub fn average_luminance(pixels: &[u8], rgb: [usize; 3]) -> f32 {
+    let count = pixels.len() / 4;
+    if count == 0 {
+        return 1.0;
+    }
+    let total: f32 = pixels
+        .chunks_exact(4)
+        .map(|p| 0.2126 * p[rgb[0]] as f32 + 0.7152 * p[rgb[1]] as f32 + 0.0722 * p[rgb[2]] as f32)
+        .sum();
+    total / count as f32 / 255.0
+}
@@ -0,0 +1,354 @@
+// Custom hotkey actions (platform-agnostic)
+//
+// A custom action is a named list of steps run one after another by a single
+// hotkey, e.g. "apply profile Movie, pause for 2 hours". Each step is an
+// event-bus `Command`, so a chain can do exactly what the browser extension
+// and savemyeyes:// links can, through the same dispatcher. Before each step
+// the platform asks `step_error` whether it can run now (a profile that was
+// deleted, a monitor that is unplugged); such steps are skipped and the rest
+// still run, and `RunReport` turns the outcome into one toast/HUD line that
+// names every step that failed.
+//
+// Hotkeys are parsed like the fixed ones (shared hotkeys.rs, `KeyCombo`); the
+// settings UI hands new actions the first free one of `new_hotkey`. Steps
+// are picked from `step_menu`, which both settings windows show as a
+// dropdown.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::dimming::{percent_label, MAX_OPACITY};
+use crate::events::Command;
+pub use crate::hotkeys::{Key, KeyCombo};
+use crate::profiles;
+
+/// Most actions the settings window lists
+pub const MAX_ACTIONS: usize = 4;
+
+/// Most steps in one action
+pub const MAX_STEPS: usize = 8;
+
+/// Modifiers of the hotkeys handed to new actions, before the digit
+#[cfg(target_os = "macos")]
+const NEW_HOTKEY_PREFIX: &str = "Cmd+Option+";
+#[cfg(not(target_os = "macos"))]
+const NEW_HOTKEY_PREFIX: &str = "Ctrl+Alt+Shift+";
+
+/// Pause lengths offered as steps, in minutes (a little longer than the
+/// tray's, since a chain is often "for the rest of the evening")
+const PAUSE_STEPS: [u64; 4] = [15, 30, 60, 120];
+
+/// A hotkey that runs a list of commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomAction {
+    pub name: String,
+    /// Shortcut like "Ctrl+Alt+Shift+1" (see `KeyCombo`), empty for none
+    pub hotkey: String,
+    /// Run top to bottom
+    pub steps: Vec<Command>,
+}
+
+impl CustomAction {
+    /// A new, empty action with the first hotkey no other action uses
+    pub fn new(existing: &[CustomAction]) -> Self {
+        let number = existing.len() + 1;
+        Self {
+            name: format!("Action {}", number),
+            hotkey: new_hotkey(existing).unwrap_or_default(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// The parsed hotkey, if it has a usable one
+    pub fn combo(&self) -> Option<KeyCombo> {
+        KeyCombo::parse(&self.hotkey)
+    }
+
+    /// Its steps in a line for the settings UI
+    pub fn summary(&self) -> String {
+        if self.steps.is_empty() {
+            return "No steps yet".to_string();
+        }
+        let labels: Vec<String> = self.steps.iter().map(step_label).collect();
+        labels.join(" \u{2192} ")
+    }
+}
+
+/// Hotkeys the settings UI offers: `NEW_HOTKEY_PREFIX` plus a digit
+pub fn hotkey_choices() -> Vec<String> {
+    (1..=9)
+        .map(|digit| format!("{}{}", NEW_HOTKEY_PREFIX, digit))
+        .collect()
+}
+
+/// Whether an action other than `index` already uses `hotkey`
+pub fn hotkey_taken(actions: &[CustomAction], index: usize, hotkey: &str) -> bool {
+    let combo = KeyCombo::parse(hotkey);
+    actions
+        .iter()
+        .enumerate()
+        .any(|(i, action)| i != index && combo.is_some() && action.combo() == combo)
+}
+
+/// First of `hotkey_choices` that no action in `existing` uses
+pub fn new_hotkey(existing: &[CustomAction]) -> Option<String> {
+    hotkey_choices()
+        .into_iter()
+        .find(|hotkey| !hotkey_taken(existing, existing.len(), hotkey))
+}
+
+/// A change picked in an action's dropdown in the settings UI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    AddStep(Command),
+    RemoveLastStep,
+    Hotkey(String),
+    Delete,
+}
+
+impl Edit {
+    /// Make the change to action `index`. Returns the toast/HUD text.
+    pub fn apply(self, actions: &mut Vec<CustomAction>, index: usize) -> Option<String> {
+        let action = actions.get_mut(index)?;
+        let message = match self {
+            Edit::AddStep(_) if action.steps.len() >= MAX_STEPS => {
+                format!("{} has {} steps already", action.name, MAX_STEPS)
+            }
+            Edit::AddStep(step) => {
+                let message = format!("{}: added {}", action.name, step_label(&step));
+                action.steps.push(step);
+                message
+            }
+            Edit::RemoveLastStep => match action.steps.pop() {
+                Some(step) => format!("{}: removed {}", action.name, step_label(&step)),
+                None => format!("{} has no steps", action.name),
+            },
+            Edit::Hotkey(hotkey) => {
+                let message = format!("{} now runs on {}", action.name, hotkey);
+                action.hotkey = hotkey;
+                message
+            }
+            Edit::Delete => {
+                let removed = actions.remove(index);
+                format!("{} deleted", removed.name)
+            }
+        };
+        Some(message)
+    }
+}
+
+/// Short description of a step for the settings UI and reports
+pub fn step_label(step: &Command) -> String {
+    match step {
+        Command::Toggle => "Toggle dimming".to_string(),
+        Command::SetEnabled { enabled: true } => "Dimming on".to_string(),
+        Command::SetEnabled { enabled: false } => "Dimming off".to_string(),
+        Command::SetLevel { level } => format!("Level {}", percent_label(*level)),
+        Command::SetMonitorLevel { monitor, level } => {
+            format!("Monitor {} at {}", monitor + 1, percent_label(*level))
+        }
+        Command::SetMonitorCapture {
+            monitor,
+            visible: true,
+        } => {
+            format!("Monitor {} in captures", monitor + 1)
+        }
+        Command::SetMonitorCapture {
+            monitor,
+            visible: false,
+        } => {
+            format!("Monitor {} hidden from captures", monitor + 1)
+        }
+        Command::ApplyProfile { name } => format!("Profile {}", name),
+        Command::VideoPlaying { playing: true } => "Video started".to_string(),
+        Command::VideoPlaying { playing: false } => "Video stopped".to_string(),
+        Command::Pause { minutes } => pause_label(*minutes),
+        Command::OpenSettings => "Open settings".to_string(),
+    }
+}
+
+fn pause_label(minutes: u64) -> String {
+    match minutes {
+        60 => "Pause 1 hour".to_string(),
+        m if m % 60 == 0 => format!("Pause {} hours", m / 60),
+        m => format!("Pause {} min", m),
+    }
+}
+
+/// A submenu of the step dropdown
+#[derive(Debug, Clone)]
+pub struct StepGroup {
+    pub label: String,
+    /// Menu label and the step it adds
+    pub steps: Vec<(String, Command)>,
+}
+
+/// Steps the settings UI offers, grouped for a dropdown. `monitors` is the
+/// number connected now.
+pub fn step_menu(config: &AppConfig, monitors: u32) -> Vec<StepGroup> {
+    let level = |percent: u32| percent as f32 / 100.0;
+    let levels = (1..=9)
+        .map(|tens| tens * 10)
+        .filter(|p| level(*p) <= MAX_OPACITY);
+    let mut groups = vec![
+        StepGroup {
+            label: "Dimming".to_string(),
+            steps: [
+                Command::Toggle,
+                Command::SetEnabled { enabled: true },
+                Command::SetEnabled { enabled: false },
+                Command::OpenSettings,
+            ]
+            .into_iter()
+            .map(|step| (step_label(&step), step))
+            .collect(),
+        },
+        StepGroup {
+            label: "Level".to_string(),
+            steps: levels
+                .clone()
+                .map(|p| (format!("{}%", p), Command::SetLevel { level: level(p) }))
+                .collect(),
+        },
+        StepGroup {
+            label: "Profile".to_string(),
+            steps: profiles::listed(config)
+                .into_iter()
+                .map(|p| {
+                    (
+                        p.name.clone(),
+                        Command::ApplyProfile {
+                            name: p.name.clone(),
+                        },
+                    )
+                })
+                .collect(),
+        },
+        StepGroup {
+            label: "Pause".to_string(),
+            steps: PAUSE_STEPS
+                .into_iter()
+                .map(|minutes| (pause_label(minutes), Command::Pause { minutes }))
+                .collect(),
+        },
+    ];
+    // One group per monitor; two monitors or more, else "Level" is enough
+    for monitor in (0..monitors).filter(|_| monitors > 1) {
+        let mut steps: Vec<(String, Command)> = levels
+            .clone()
+            .map(|p| {
+                (
+                    format!("{}%", p),
+                    Command::SetMonitorLevel {
+                        monitor,
+                        level: level(p),
+                    },
+                )
+            })
+            .collect();
+        if cfg!(target_os = "windows") {
+            for visible in [true, false] {
+                let step = Command::SetMonitorCapture { monitor, visible };
+                let label = if visible {
+                    "Show in captures"
+                } else {
+                    "Hide from captures"
+                };
+                steps.push((label.to_string(), step));
+            }
+        }
+        groups.push(StepGroup {
+            label: format!("Monitor {}", monitor + 1),
+            steps,
+        });
+    }
+    groups
+}
+
+/// Why `step` can't run now, or None if it can. `monitors` is the number
+/// connected now. Checked right before the step, after the ones before it
+/// ran, so a profile that turns on per-monitor levels enables the steps
+/// after it.
+pub fn step_error(config: &AppConfig, step: &Command, monitors: u32) -> Option<String> {
+    match step {
+        Command::ApplyProfile { .. } | Command::Pause { .. } if config.designer_mode => {
+            Some("Designer mode is on".to_string())
+        }
+        Command::ApplyProfile { name } if profiles::find(config, name).is_none() => {
+            Some(format!("No profile named \"{}\"", name))
+        }
+        Command::SetMonitorLevel { monitor, .. } | Command::SetMonitorCapture { monitor, .. }
+            if *monitor >= monitors =>
+        {
+            Some(format!("No monitor {}", monitor + 1))
+        }
+        Command::SetMonitorLevel { .. } if !config.multi_monitor => {
+            Some("Per-monitor levels are off".to_string())
+        }
+        Command::SetMonitorCapture { .. } if !cfg!(target_os = "windows") => {
+            Some("Captures can only be set per monitor on Windows".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// What happened when an action ran, for the toast/HUD
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    pub name: String,
+    /// Steps that ran
+    pub done: usize,
+    /// Step number (from 1), label and reason for each skipped step
+    pub failed: Vec<(usize, String, String)>,
+}
+
+impl RunReport {
+    pub fn new(action: &CustomAction) -> Self {
+        Self {
+            name: action.name.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Note the outcome of step `index` (from 0)
+    pub fn record(&mut self, index: usize, step: &Command, error: Option<String>) {
+        match error {
+            Some(reason) => {
+                eprintln!(
+                    "[custom_actions] {}: step {} ({}) skipped: {}",
+                    self.name,
+                    index + 1,
+                    step_label(step),
+                    reason
+                );
+                self.failed.push((index + 1, step_label(step), reason));
+            }
+            None => self.done += 1,
+        }
+    }
+
+    /// One line for the toast/HUD
+    pub fn message(&self) -> String {
+        let total = self.done + self.failed.len();
+        if total == 0 {
+            return format!("{}: no steps", self.name);
+        }
+        if self.failed.is_empty() {
+            let noun = if total == 1 { "step" } else { "steps" };
+            return format!("{}: {} {} done", self.name, total, noun);
+        }
+        let failures: Vec<String> = self
+            .failed
+            .iter()
+            .map(|(number, label, reason)| format!("step {} ({}): {}", number, label, reason))
+            .collect();
+        format!(
+            "{}: {} of {} steps done; {}",
+            self.name,
+            self.done,
+            total,
+            failures.join("; ")
+        )
+    }
+}
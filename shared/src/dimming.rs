@@ -0,0 +1,161 @@
+// Mapping from the user-facing dim level to what the backends apply
+// (platform-agnostic)
+//
+// Config and sliders store the *level* (0.0–0.9). Backends turn it into an
+// overlay alpha or a gamma maximum through `OpacityCurve::to_applied`:
+//   • Linear — level is used as-is (the historical behaviour)
+//   • Perceptual — level is linear in CIE L* lightness, so 45% looks roughly
+//     halfway between undimmed and the darkest setting
+// Both curves agree at 0 and at MAX_OPACITY; switching curves converts the
+// stored levels so the screen doesn't change (see `switch_curve`).
+//
+// Levels have a resolution of a tenth of a percent. Sliders work in those
+// integer steps (whole percents, or 0.5% in fine mode), config values are
+// snapped to them on load, and the alpha byte is rounded rather than
+// truncated so neighbouring levels don't collapse onto the same alpha.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::locale;
+
+/// Highest level/alpha the app ever applies
+pub const MAX_OPACITY: f32 = 0.9;
+
+/// Level steps per 1.0: levels are whole tenths of a percent
+pub const LEVEL_STEPS: i32 = 1000;
+
+/// Highest level in steps (MAX_OPACITY)
+pub const MAX_STEPS: i32 = 900;
+
+/// Slider step in fine mode (0.5%)
+pub const FINE_STEPS: i32 = 5;
+
+/// Level in tenths of a percent (0.375 -> 375)
+pub fn to_steps(level: f32) -> i32 {
+    (level * LEVEL_STEPS as f32).round() as i32
+}
+
+/// Level for a step count (375 -> 0.375)
+pub fn from_steps(steps: i32) -> f32 {
+    steps as f32 / LEVEL_STEPS as f32
+}
+
+/// `level` snapped to the nearest tenth of a percent
+pub fn quantize(level: f32) -> f32 {
+    from_steps(to_steps(level))
+}
+
+/// Slider position (in fractional steps) snapped to whole percents, or to
+/// half percents in fine mode, within 0..=MAX_STEPS
+pub fn snap_steps(steps: f32, fine: bool) -> i32 {
+    let step = if fine { FINE_STEPS } else { 10 };
+    ((steps / step as f32).round() as i32 * step).clamp(0, MAX_STEPS)
+}
+
+/// Layered-window alpha for an applied opacity, rounded to the nearest byte
+pub fn alpha(opacity: f32) -> u8 {
+    (opacity.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Level as a percentage for the UI: "40%", "37.5%" ("37,5%" in regions
+/// with a decimal comma)
+pub fn percent_label(level: f32) -> String {
+    locale::percent(to_steps(level) as f32 / 10.0)
+}
+
+/// Parse a user-typed level in percent ("40", "37.5", "37,5", "37.5%") into
+/// a level snapped to a tenth of a percent. None when it isn't a number in
+/// 0–90.
+pub fn parse_percent(text: &str) -> Option<f32> {
+    let text = text.trim().trim_end_matches('%').trim_end();
+    let number: f32 = text.replace(',', ".").parse().ok()?;
+    let level = quantize(number / 100.0);
+    (number.is_finite() && (0.0..=MAX_OPACITY).contains(&level)).then_some(level)
+}
+
+/// How the slider percentage maps to applied opacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpacityCurve {
+    #[default]
+    Linear,
+    Perceptual,
+}
+
+/// CIE 1976 L* (0–100) for relative luminance Y (0–1)
+fn lightness(y: f32) -> f32 {
+    if y > 0.008856 {
+        116.0 * y.cbrt() - 16.0
+    } else {
+        903.3 * y
+    }
+}
+
+/// Relative luminance Y (0–1) for CIE L* (0–100)
+fn luminance(l: f32) -> f32 {
+    if l > 8.0 {
+        ((l + 16.0) / 116.0).powi(3)
+    } else {
+        l / 903.3
+    }
+}
+
+impl OpacityCurve {
+    /// Applied opacity (overlay alpha, 1 - gamma max) for a user-facing level
+    pub fn to_applied(self, level: f32) -> f32 {
+        let level = level.clamp(0.0, MAX_OPACITY);
+        match self {
+            OpacityCurve::Linear => level,
+            OpacityCurve::Perceptual => {
+                let darkest = lightness(1.0 - MAX_OPACITY);
+                let l = 100.0 - (level / MAX_OPACITY) * (100.0 - darkest);
+                (1.0 - luminance(l)).clamp(0.0, MAX_OPACITY)
+            }
+        }
+    }
+
+    /// User-facing level that produces the given applied opacity
+    pub fn from_applied(self, applied: f32) -> f32 {
+        let applied = applied.clamp(0.0, MAX_OPACITY);
+        match self {
+            OpacityCurve::Linear => applied,
+            OpacityCurve::Perceptual => {
+                let darkest = lightness(1.0 - MAX_OPACITY);
+                let l = lightness(1.0 - applied);
+                ((100.0 - l) / (100.0 - darkest) * MAX_OPACITY).clamp(0.0, MAX_OPACITY)
+            }
+        }
+    }
+}
+
+/// Convert a level so it looks the same under another curve.
+/// Snapped to a tenth of a percent, the level resolution.
+pub fn convert_level(level: f32, from: OpacityCurve, to: OpacityCurve) -> f32 {
+    if from == to {
+        return level;
+    }
+    quantize(to.from_applied(from.to_applied(level)))
+}
+
+/// Switch `config` to `curve`, converting every stored level so the
+/// applied brightness stays the same. Returns false if nothing changed.
+pub fn switch_curve(config: &mut AppConfig, curve: OpacityCurve) -> bool {
+    let from = config.opacity_curve;
+    if from == curve {
+        return false;
+    }
+    config.opacity = convert_level(config.opacity, from, curve);
+    config.last_opacity = convert_level(config.last_opacity, from, curve);
+    for level in config.monitor_opacity.values_mut() {
+        *level = convert_level(*level, from, curve);
+    }
+    for level in config.per_monitor_opacity.values_mut() {
+        *level = convert_level(*level, from, curve);
+    }
+    for level in config.per_display_opacity.values_mut() {
+        *level = convert_level(*level, from, curve);
+    }
+    config.opacity_curve = curve;
+    true
+}
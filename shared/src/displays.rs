@@ -0,0 +1,269 @@
+// Monitor layout snapshots for troubleshooting (platform-agnostic)
+//
+// Each platform enumerates its displays into `DisplayInfo` records and hands
+// them to `record_layout`, which persists them to a sidecar file next to
+// config.json whenever the layout differs from the last one seen. The same
+// records feed the "Copy display report" button in the diagnostics section.
+// A change in which monitors are connected also backs up the config
+// (backups.rs).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{
+    config_path, describe_save_failure, last_save_failure, save_failure_count, AppConfig,
+};
+use crate::dimming::percent_label;
+use crate::monitor_id::MonitorId;
+use crate::updater::APP_VERSION;
+
+/// One connected display as seen by the platform layer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    /// Stable identity (monitor_id.rs), empty in layouts saved before it
+    #[serde(default)]
+    pub id: MonitorId,
+    /// User-facing name (macOS localizedName, Windows device name)
+    pub name: String,
+    /// Position of the top-left corner in desktop coordinates
+    pub x: i32,
+    pub y: i32,
+    /// Size in desktop coordinates
+    pub width: i32,
+    pub height: i32,
+    /// DPI scale factor (1.0 = 96 DPI on Windows, non-Retina on macOS)
+    pub scale: f32,
+    #[serde(default)]
+    pub primary: bool,
+    /// Running in HDR / advanced color mode (Windows only for now)
+    #[serde(default)]
+    pub hdr: bool,
+}
+
+/// Last-seen monitor layout, written to displays.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutSnapshot {
+    /// Seconds since the Unix epoch when this layout was first seen
+    pub captured_at: u64,
+    pub displays: Vec<DisplayInfo>,
+}
+
+pub fn snapshot_path() -> PathBuf {
+    config_path().with_file_name("displays.json")
+}
+
+pub fn load_snapshot() -> Option<LayoutSnapshot> {
+    let data = fs::read_to_string(snapshot_path()).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Persist the given layout if it differs from the last recorded one.
+/// Returns true when the sidecar file was rewritten.
+pub fn record_layout(displays: &[DisplayInfo]) -> bool {
+    if let Some(previous) = load_snapshot() {
+        if previous.displays == displays {
+            return false;
+        }
+        // Keep the config from before monitors were added, removed or
+        // replaced: settings of a monitor that's gone wait under its id
+        let names = |list: &[DisplayInfo]| list.iter().map(|d| d.name.clone()).collect::<Vec<_>>();
+        if names(&previous.displays) != names(displays) {
+            crate::backups::take("Monitors changed");
+        }
+    }
+
+    let snapshot = LayoutSnapshot {
+        captured_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        displays: displays.to_vec(),
+    };
+    let path = snapshot_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let data = serde_json::to_string_pretty(&snapshot).unwrap_or_default();
+    fs::write(&path, data).is_ok()
+}
+
+/// Display names for monitors with the given EDID names, in order: "Monitor
+/// N" where there is none, and identical names get their monitor number
+/// appended so they stay apart. A suffixed name that is itself taken (a
+/// monitor whose EDID already reads "Dell (2)") moves on to the next number.
+pub fn friendly_names(edid: &[Option<String>]) -> Vec<String> {
+    let mut names: Vec<String> = edid
+        .iter()
+        .enumerate()
+        .map(|(i, name)| name.clone().unwrap_or_else(|| format!("Monitor {}", i + 1)))
+        .collect();
+    let duplicated: Vec<bool> = names
+        .iter()
+        .map(|n| names.iter().filter(|m| *m == n).count() > 1)
+        .collect();
+    let mut taken: HashSet<String> = names
+        .iter()
+        .zip(&duplicated)
+        .filter(|(_, duplicated)| !**duplicated)
+        .map(|(name, _)| name.clone())
+        .collect();
+    for (i, name) in names.iter_mut().enumerate() {
+        if !duplicated[i] {
+            continue;
+        }
+        let mut number = i + 1;
+        while taken.contains(&format!("{} ({})", name, number)) {
+            number += 1;
+        }
+        name.push_str(&format!(" ({})", number));
+        taken.insert(name.clone());
+    }
+    names
+}
+
+/// Report line naming the global input hooks actually installed right now,
+/// so "Disable Global Shortcuts" can be verified from diagnostics.
+pub fn input_hooks_line(installed: &[&str], shortcuts_disabled: bool) -> String {
+    let hooks = if installed.is_empty() {
+        "none".to_string()
+    } else {
+        installed.join(", ")
+    };
+    format!(
+        "Global input hooks: {}{}\n",
+        hooks,
+        if shortcuts_disabled {
+            " (global shortcuts disabled)"
+        } else {
+            ""
+        }
+    )
+}
+
+/// Build a plain-text report suitable for pasting into a bug report.
+pub fn build_report(platform: &str, displays: &[DisplayInfo], config: &AppConfig) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("SaveMyEyes v{} ({})\n", APP_VERSION, platform));
+    out.push_str(&format!(
+        "Dimmer: {}, opacity {}, multi-monitor {}\n",
+        if config.is_enabled { "on" } else { "off" },
+        percent_label(config.opacity),
+        if config.multi_monitor { "on" } else { "off" },
+    ));
+    out.push_str(&format!("Config: {}\n", config_path().display()));
+    let failures = save_failure_count();
+    if failures > 0 {
+        out.push_str(&format!("Config save failures this session: {}\n", failures));
+        if let Some(failure) = last_save_failure() {
+            out.push_str(&format!("  last: {}\n", describe_save_failure(&failure)));
+        }
+    }
+    #[cfg(feature = "updater")]
+    out.push_str(&crate::net::report());
+    out.push_str(&format!("\nDisplays ({}):\n", displays.len()));
+    for (i, d) in displays.iter().enumerate() {
+        out.push_str(&format!(
+            "  {}. {}{}{} — {}x{} at ({}, {}), scale {}%\n",
+            i + 1,
+            d.name,
+            if d.primary { " [primary]" } else { "" },
+            if d.hdr { " [HDR]" } else { "" },
+            d.width,
+            d.height,
+            d.x,
+            d.y,
+            (d.scale * 100.0).round() as i32,
+        ));
+        out.push_str(&format!("     id: {}\n", d.id));
+        if let Some(op) = config.monitor_opacity.get(&d.id) {
+            out.push_str(&format!("     saved opacity: {}\n", percent_label(*op)));
+        }
+    }
+
+    match load_snapshot() {
+        Some(snapshot) if snapshot.displays != displays => {
+            out.push_str(&format!(
+                "\nPrevious layout (seen at {}):\n",
+                snapshot.captured_at
+            ));
+            for (i, d) in snapshot.displays.iter().enumerate() {
+                out.push_str(&format!(
+                    "  {}. {} — {}x{} at ({}, {}), scale {}%\n",
+                    i + 1,
+                    d.name,
+                    d.width,
+                    d.height,
+                    d.x,
+                    d.y,
+                    (d.scale * 100.0).round() as i32,
+                ));
+            }
+        }
+        Some(snapshot) => {
+            out.push_str(&format!(
+                "\nLayout unchanged since {} (unix time)\n",
+                snapshot.captured_at
+            ));
+        }
+        None => {}
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(edid: &[Option<&str>]) -> Vec<String> {
+        let edid: Vec<Option<String>> = edid.iter().map(|n| n.map(String::from)).collect();
+        friendly_names(&edid)
+    }
+
+    #[test]
+    fn distinct_names_are_kept() {
+        assert_eq!(
+            names(&[Some("Dell U2720Q"), Some("Built-in Display")]),
+            ["Dell U2720Q", "Built-in Display"]
+        );
+    }
+
+    #[test]
+    fn two_identical_models_are_numbered() {
+        assert_eq!(
+            names(&[Some("Dell U2720Q"), Some("Dell U2720Q")]),
+            ["Dell U2720Q (1)", "Dell U2720Q (2)"]
+        );
+    }
+
+    #[test]
+    fn three_identical_models_are_numbered() {
+        assert_eq!(
+            names(&[Some("LG"), Some("Dell"), Some("LG"), Some("LG")]),
+            ["LG (1)", "Dell", "LG (3)", "LG (4)"]
+        );
+    }
+
+    #[test]
+    fn missing_name_falls_back_to_the_monitor_number() {
+        assert_eq!(
+            names(&[Some("Dell U2720Q"), None, Some("LG")]),
+            ["Dell U2720Q", "Monitor 2", "LG"]
+        );
+    }
+
+    #[test]
+    fn generated_name_colliding_with_an_edid_name() {
+        let list = names(&[Some("Monitor 2"), None]);
+        assert_eq!(list, ["Monitor 2 (1)", "Monitor 2 (2)"]);
+    }
+
+    #[test]
+    fn suffix_colliding_with_an_edid_name() {
+        let list = names(&[Some("Dell"), Some("Dell"), Some("Dell (2)")]);
+        assert_eq!(list, ["Dell (1)", "Dell (3)", "Dell (2)"]);
+    }
+}
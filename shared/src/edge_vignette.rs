@@ -0,0 +1,121 @@
+// Edge vignette: extra dimming along the screen edges (platform-agnostic)
+//
+// On an OLED at night, bright title bars, taskbars and docks along the
+// edges still glow through heavy dimming. With the vignette on, a band
+// `width` deep along each listed edge is dimmed up to `strength` more than
+// the level, fading smoothly to nothing at the inner side of the band.
+// `shape` applies to every monitor; `monitors` gives single monitors their
+// own edges, width and strength by monitor id (an entry without edges turns
+// the vignette off there). Like the night boost it is added on top of the
+// level and never saved into it, and an undimmed monitor stays undimmed.
+//
+// Only a window overlay can vary its alpha across the screen (per-pixel
+// alpha, Windows), like the gradient. Gamma-dimmed monitors and macOS stay
+// uniform.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::dimming::{percent_label, MAX_OPACITY};
+use crate::monitor_id::MonitorId;
+
+/// Widest band, as a share of the monitor's shorter side
+pub const MAX_WIDTH: f32 = 0.5;
+
+/// A screen edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Which edges are dimmed, and how much
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VignetteShape {
+    pub edges: Vec<Edge>,
+    /// Depth of the band, as a share of the monitor's shorter side
+    pub width: f32,
+    /// Added to the level right at the edge
+    pub strength: f32,
+}
+
+impl Default for VignetteShape {
+    fn default() -> Self {
+        Self {
+            edges: vec![Edge::Top, Edge::Bottom, Edge::Left, Edge::Right],
+            width: 0.05,
+            strength: 0.2,
+        }
+    }
+}
+
+impl VignetteShape {
+    /// True if it changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty() || self.width <= 0.0 || self.strength <= 0.0
+    }
+
+    /// Band depth in pixels on a `width` x `height` monitor
+    pub fn band_px(&self, width: i32, height: i32) -> f32 {
+        width.min(height).max(0) as f32 * self.width.clamp(0.0, MAX_WIDTH)
+    }
+
+    /// How close a pixel `distance` pixels from `edge` is to it, from 1.0
+    /// on the edge to 0.0 at the inner side of a `band` pixels deep band
+    pub fn nearness(&self, edge: Edge, distance: f32, band: f32) -> f32 {
+        if band <= 0.0 || !self.edges.contains(&edge) {
+            return 0.0;
+        }
+        // Eased, so the band has no visible inner border
+        let t = (1.0 - distance / band).clamp(0.0, 1.0);
+        t * t
+    }
+
+    /// `level` at `nearness` (see above). Undimmed stays undimmed.
+    pub fn level_at(&self, level: f32, nearness: f32) -> f32 {
+        if level > 0.0 {
+            (level + self.strength * nearness.clamp(0.0, 1.0)).clamp(0.0, MAX_OPACITY)
+        } else {
+            level
+        }
+    }
+}
+
+/// Edge vignette. Off by default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgeVignette {
+    pub enabled: bool,
+    /// Edges of every monitor without an entry in `monitors`
+    pub shape: VignetteShape,
+    /// Edges of single monitors, by monitor id
+    pub monitors: HashMap<MonitorId, VignetteShape>,
+}
+
+impl EdgeVignette {
+    /// The vignette of the monitor `id`, or None if it has none
+    pub fn shape_for(&self, id: &MonitorId) -> Option<&VignetteShape> {
+        if !self.enabled {
+            return None;
+        }
+        let shape = self.monitors.get(id).unwrap_or(&self.shape);
+        (!shape.is_empty()).then_some(shape)
+    }
+
+    /// One-line summary for the settings UI
+    pub fn summary(&self) -> String {
+        let base = format!(
+            "Edges dimmed up to {} more",
+            percent_label(self.shape.strength)
+        );
+        match self.monitors.len() {
+            0 => base,
+            1 => format!("{} (1 monitor set by hand)", base),
+            n => format!("{} ({} monitors set by hand)", base, n),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// App commands from outside the process (platform-agnostic)
+//
+// The central event bus for external control. Every source (the browser
+// extension bridge in native_messaging.rs, the Windows jump list through
+// `from_args`) describes what it wants as a `Command`. The platform layer
+// carries it to the running instance as JSON (WM_COPYDATA on Windows, a
+// distributed notification on macOS), and one dispatcher there runs it on
+// the UI thread, the same way hotkeys and the tray menu change the
+// dimming.
+//
+// Wire format: {"type": "set_level", "level": 0.4}
+// Command line, for the jump list, shortcuts and scripts:
+//   --toggle, --on, --off
+//   --opacity 40                level in percent
+//   --monitor 2 --opacity 60    one monitor, counted from 1
+//   --pause <minutes>, --settings
+// Links (`from_url`), for docs, launchers and bookmarks; the OS starts the
+// app with the URL, which forwards it like a jump list task:
+//   savemyeyes://toggle, savemyeyes://on, savemyeyes://off
+//   savemyeyes://set?opacity=40            level in percent
+//   savemyeyes://set?opacity=40&monitor=2  one monitor, counted from 1
+//   savemyeyes://profile/Night             names percent-encoded
+//   savemyeyes://pause?minutes=30
+//   savemyeyes://settings
+
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::{self, MAX_OPACITY};
+
+/// Command line flags understood by `Command::from_args`
+pub const TOGGLE_ARG: &str = "--toggle";
+pub const ON_ARG: &str = "--on";
+pub const OFF_ARG: &str = "--off";
+pub const OPACITY_ARG: &str = "--opacity";
+pub const MONITOR_ARG: &str = "--monitor";
+pub const PAUSE_ARG: &str = "--pause";
+pub const SETTINGS_ARG: &str = "--settings";
+
+/// Flags that each name what the launch should do; at most one is allowed
+const ACTION_ARGS: [&str; 6] = [
+    TOGGLE_ARG,
+    ON_ARG,
+    OFF_ARG,
+    OPACITY_ARG,
+    PAUSE_ARG,
+    SETTINGS_ARG,
+];
+
+/// Printed when the command line can't be parsed
+pub const USAGE: &str = "\
+usage: savemyeyes [--toggle | --on | --off | --settings]
+       savemyeyes [--monitor <n>] --opacity <percent>
+       savemyeyes --pause <minutes>
+       savemyeyes savemyeyes://<action>";
+
+/// URL scheme handled by `from_url`
+pub const URL_SCHEME: &str = "savemyeyes";
+
+/// Something an external source asks the running app to do
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+    /// Dimming on/off, like the toggle hotkey
+    Toggle,
+    /// Turn dimming on or off
+    SetEnabled { enabled: bool },
+    /// Set the level (0.0–0.9), like the level chord
+    SetLevel { level: f32 },
+    /// Set one monitor's level (0.0–0.9) in multi-monitor mode. Monitors
+    /// count from 0, in the order the settings window lists them.
+    SetMonitorLevel { monitor: u32, level: f32 },
+    /// Show or hide one monitor's dimming in screenshots and recordings
+    /// (Windows). Monitors count from 0, like SetMonitorLevel.
+    SetMonitorCapture { monitor: u32, visible: bool },
+    /// Apply a saved or built-in profile by name, like the tray's Profiles
+    ApplyProfile { name: String },
+    /// A browser tab started or stopped playing video
+    VideoPlaying { playing: bool },
+    /// Lift dimming for a while, like the tray's Pause Dimming entries
+    Pause { minutes: u64 },
+    /// Bring up the settings window
+    OpenSettings,
+}
+
+impl Command {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Parse a command, snapping levels into range. None if malformed.
+    pub fn from_json(text: &str) -> Option<Self> {
+        Self::from_value(serde_json::from_str(text).ok()?)
+    }
+
+    /// Same as from_json, for an already parsed message
+    pub fn from_value(value: serde_json::Value) -> Option<Self> {
+        match serde_json::from_value(value).ok()? {
+            Command::SetLevel { level } if !level.is_finite() => None,
+            Command::SetLevel { level } => Some(Command::SetLevel {
+                level: dimming::quantize(level.clamp(0.0, MAX_OPACITY)),
+            }),
+            Command::SetMonitorLevel { level, .. } if !level.is_finite() => None,
+            Command::SetMonitorLevel { monitor, level } => Some(Command::SetMonitorLevel {
+                monitor,
+                level: dimming::quantize(level.clamp(0.0, MAX_OPACITY)),
+            }),
+            Command::Pause { minutes: 0 } => None,
+            command => Some(command),
+        }
+    }
+
+    /// The command a launcher or script asked for on the command line
+    /// (jump list tasks start the exe again with one). Ok(None) for a
+    /// normal launch; Err for flags that are missing or have bad values,
+    /// with levels checked like from_json.
+    pub fn from_args() -> Result<Option<Self>, String> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self::parse_args(&args)
+    }
+
+    fn parse_args(args: &[String]) -> Result<Option<Self>, String> {
+        // One action per launch: `--on --off` or `--toggle --opacity 40`
+        // has no single meaning, so neither is picked
+        let mut actions = args
+            .iter()
+            .filter(|a| is_url(a) || ACTION_ARGS.contains(&a.as_str()));
+        if let (Some(first), Some(second)) = (actions.next(), actions.next()) {
+            return Err(format!("{} and {} can't be used together", first, second));
+        }
+        if let Some(url) = args.iter().find(|a| is_url(a)) {
+            return Self::from_url(url)
+                .map(Some)
+                .ok_or_else(|| format!("can't handle the link {}", url));
+        }
+        let flag = |name: &str| args.iter().position(|a| a == name);
+        if flag(TOGGLE_ARG).is_some() {
+            return Ok(Some(Command::Toggle));
+        }
+        if flag(ON_ARG).is_some() {
+            return Ok(Some(Command::SetEnabled { enabled: true }));
+        }
+        if flag(OFF_ARG).is_some() {
+            return Ok(Some(Command::SetEnabled { enabled: false }));
+        }
+        if let Some(pos) = flag(OPACITY_ARG) {
+            let command = parse_opacity_args(args, pos)?;
+            return serde_json::to_value(command)
+                .ok()
+                .and_then(Self::from_value)
+                .map(Some)
+                .ok_or_else(|| format!("{} needs a percentage", OPACITY_ARG));
+        }
+        if flag(MONITOR_ARG).is_some() {
+            return Err(format!("{} goes with {}", MONITOR_ARG, OPACITY_ARG));
+        }
+        if let Some(pos) = flag(PAUSE_ARG) {
+            let minutes = args
+                .get(pos + 1)
+                .and_then(|m| m.parse().ok())
+                .filter(|m| *m > 0)
+                .ok_or_else(|| format!("{} needs a number of minutes", PAUSE_ARG))?;
+            return Ok(Some(Command::Pause { minutes }));
+        }
+        Ok(flag(SETTINGS_ARG).map(|_| Command::OpenSettings))
+    }
+
+    /// Parse a savemyeyes:// link. None (and a log line) if it isn't one
+    /// this version understands; levels are checked like from_json.
+    pub fn from_url(url: &str) -> Option<Self> {
+        let command = parse_url(url);
+        if command.is_none() {
+            eprintln!("[events] ignoring link {:?}", url);
+        }
+        Self::from_value(serde_json::to_value(command?).ok()?)
+    }
+}
+
+/// `--opacity <percent>` at `pos`, with `--monitor <n>` (from 1) if given
+fn parse_opacity_args(args: &[String], pos: usize) -> Result<Command, String> {
+    let level = args
+        .get(pos + 1)
+        .and_then(|a| a.trim_end_matches('%').parse::<f32>().ok())
+        .ok_or_else(|| format!("{} needs a percentage", OPACITY_ARG))?
+        / 100.0;
+    match args.iter().position(|a| a == MONITOR_ARG) {
+        Some(pos) => Ok(Command::SetMonitorLevel {
+            monitor: args
+                .get(pos + 1)
+                .and_then(|a| a.parse::<u32>().ok())
+                .and_then(|n| n.checked_sub(1))
+                .ok_or_else(|| format!("{} needs a monitor number from 1", MONITOR_ARG))?,
+            level,
+        }),
+        None => Ok(Command::SetLevel { level }),
+    }
+}
+
+/// True if `text` starts with our URL scheme
+pub fn is_url(text: &str) -> bool {
+    text.get(..URL_SCHEME.len() + 1)
+        .is_some_and(|head| head.eq_ignore_ascii_case(&format!("{}:", URL_SCHEME)))
+}
+
+fn parse_url(url: &str) -> Option<Command> {
+    if !is_url(url) {
+        return None;
+    }
+    let rest = url[URL_SCHEME.len() + 1..].trim_start_matches('/');
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut segments = path.trim_end_matches('/').splitn(2, '/');
+    let action = segments.next()?.to_ascii_lowercase();
+    let argument = segments.next().map(|s| percent_decode(s, false));
+    let query: Vec<(String, String)> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_ascii_lowercase(), percent_decode(value, true)))
+        .collect();
+    let param = |name: &str| {
+        query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim())
+    };
+
+    match action.as_str() {
+        "toggle" => Some(Command::Toggle),
+        "on" => Some(Command::SetEnabled { enabled: true }),
+        "off" => Some(Command::SetEnabled { enabled: false }),
+        "set" => {
+            let level = param("opacity")?
+                .trim_end_matches('%')
+                .parse::<f32>()
+                .ok()?
+                / 100.0;
+            match param("monitor") {
+                Some(monitor) => Some(Command::SetMonitorLevel {
+                    monitor: monitor.parse::<u32>().ok()?.checked_sub(1)?,
+                    level,
+                }),
+                None => Some(Command::SetLevel { level }),
+            }
+        }
+        "profile" => Some(Command::ApplyProfile {
+            name: argument.filter(|name| !name.trim().is_empty())?,
+        }),
+        "pause" => Some(Command::Pause {
+            minutes: param("minutes")?.parse().ok()?,
+        }),
+        "settings" => Some(Command::OpenSettings),
+        _ => None,
+    }
+}
+
+/// Undo %XX escapes (and `+` for a space in query values)
+fn percent_decode(text: &str, plus_is_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) if plus_is_space => out.push(b' '),
+            (byte, _) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        Command::parse_args(&args)
+    }
+
+    #[test]
+    fn plain_launch_has_no_command() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(parse(&["--minimized"]), Ok(None));
+    }
+
+    #[test]
+    fn parses_flags() {
+        assert_eq!(parse(&["--toggle"]), Ok(Some(Command::Toggle)));
+        assert_eq!(
+            parse(&["--off"]),
+            Ok(Some(Command::SetEnabled { enabled: false }))
+        );
+        assert_eq!(
+            parse(&["--pause", "30"]),
+            Ok(Some(Command::Pause { minutes: 30 }))
+        );
+        assert_eq!(
+            parse(&["--opacity", "40%"]),
+            Ok(Some(Command::SetLevel {
+                level: dimming::quantize(0.4)
+            }))
+        );
+        assert_eq!(
+            parse(&["--monitor", "2", "--opacity", "60"]),
+            Ok(Some(Command::SetMonitorLevel {
+                monitor: 1,
+                level: dimming::quantize(0.6)
+            }))
+        );
+    }
+
+    #[test]
+    fn bad_values_are_errors() {
+        for args in [
+            &["--opacity"][..],
+            &["--opacity", "dim"],
+            &["--opacity", "nan"],
+            &["--monitor", "0", "--opacity", "40"],
+            &["--monitor", "left", "--opacity", "40"],
+            &["--opacity", "40", "--monitor"],
+            &["--monitor", "2"],
+            &["--pause"],
+            &["--pause", "0"],
+            &["--pause", "soon"],
+            &["savemyeyes://frobnicate"],
+            &["--on", "--off"],
+            &["--toggle", "--opacity", "40"],
+            &["--pause", "30", "--settings"],
+            &["--monitor", "2", "--opacity", "60", "--off"],
+            &["savemyeyes://toggle", "--on"],
+        ] {
+            assert!(parse(args).is_err(), "{:?}", args);
+        }
+    }
+}
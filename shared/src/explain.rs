@@ -0,0 +1,294 @@
+// "Why is my screen dark?" (platform-agnostic)
+//
+// People forget SaveMyEyes is running and go looking for a broken display.
+// The tray entry "Why is my screen dark?" opens a small window that says
+// what is dimmed, what caused it, and offers one button per feature that is
+// darkening the screen right now to turn it off.
+//
+// What caused a change is its provenance: every place that changes dimming
+// (settings window, tray, hotkeys, commands on the event bus, profiles,
+// theme automation, Spaces) calls `note` before the change is applied, and
+// `AppliedState::new` stamps the last noted `Cause` onto every state the
+// overlay modules report to the journal, so state.json and status.json
+// carry it too. Features that darken on top of the chosen level (night
+// boost, movie mode) aren't changes of their own; the platform passes what
+// is in effect right now as `Live`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::config::AppConfig;
+use crate::dimming::percent_label;
+use crate::pause;
+use crate::presentation;
+
+/// What made the last change to the dimming
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum Cause {
+    /// Nothing noted yet
+    #[default]
+    Unknown,
+    /// Put back at launch (config, crash journal or self-update hand-over)
+    Startup,
+    /// The settings window
+    Settings,
+    /// A tray menu entry
+    Tray,
+    /// A keyboard or mouse hotkey
+    Hotkey,
+    /// A command from another process (browser extension, jump list)
+    Command,
+    /// A profile picked by hand
+    Profile { name: String },
+    /// Theme automation applied a profile for the system theme
+    ThemeAutomation { name: String },
+    /// The levels remembered for a macOS Space (space_levels.rs)
+    Space,
+    /// Scheduled dimming reached its start or end time (scheduler.rs)
+    Schedule,
+}
+
+impl Cause {
+    /// How the level was set, to follow "Dimming is on, "
+    fn describe(&self) -> Option<String> {
+        Some(match self {
+            Cause::Unknown => return None,
+            Cause::Startup => "as it was when SaveMyEyes started".to_string(),
+            Cause::Settings => "set in the settings window".to_string(),
+            Cause::Tray => "set from the tray menu".to_string(),
+            Cause::Hotkey => "set with a hotkey".to_string(),
+            Cause::Command => "set by another app (browser extension or shortcut)".to_string(),
+            Cause::Profile { name } => format!("set by the profile \"{}\"", name),
+            Cause::ThemeAutomation { name } => format!(
+                "set by theme automation, which applied \"{}\" for the system theme",
+                name
+            ),
+            Cause::Space => "remembered for this desktop".to_string(),
+            Cause::Schedule => "set by the dimming schedule".to_string(),
+        })
+    }
+}
+
+static LAST: Mutex<Cause> = Mutex::new(Cause::Unknown);
+
+/// Record what is about to change the dimming.
+pub fn note(cause: Cause) {
+    *LAST.lock().unwrap() = cause;
+}
+
+/// What made the last change
+pub fn last() -> Cause {
+    LAST.lock().unwrap().clone()
+}
+
+/// Something the explainer offers to turn off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fix {
+    TurnOff,
+    StopThemeAutomation,
+    StopNightBoost,
+    EndMovieMode,
+    StopSpaceLevels,
+}
+
+impl Fix {
+    pub const ALL: [Fix; 5] = [
+        Fix::TurnOff,
+        Fix::StopThemeAutomation,
+        Fix::StopNightBoost,
+        Fix::EndMovieMode,
+        Fix::StopSpaceLevels,
+    ];
+
+    /// Button label
+    pub fn label(self) -> &'static str {
+        match self {
+            Fix::TurnOff => "Turn Dimming Off",
+            Fix::StopThemeAutomation => "Turn Off Theme Automation",
+            Fix::StopNightBoost => "Turn Off Night Boost",
+            Fix::EndMovieMode => "End Movie Mode",
+            Fix::StopSpaceLevels => "Use One Level for All Desktops",
+        }
+    }
+
+    /// Position in ALL (Windows passes fixes between windows by index)
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|fix| *fix == self).unwrap_or(0)
+    }
+
+    /// Turn the feature off in `config`. Returns false for the fixes that
+    /// aren't settings (TurnOff, EndMovieMode): the platform runs those
+    /// through its own toggles, which also put back the levels.
+    pub fn apply(self, config: &mut AppConfig) -> bool {
+        match self {
+            Fix::StopThemeAutomation => config.theme_automation.enabled = false,
+            Fix::StopNightBoost => config.night_boost.enabled = false,
+            Fix::StopSpaceLevels => config.space_levels.enabled = false,
+            Fix::TurnOff | Fix::EndMovieMode => return false,
+        }
+        true
+    }
+
+    /// Toast once the fix is applied
+    pub fn done_message(self) -> &'static str {
+        match self {
+            Fix::TurnOff => "Dimming off",
+            Fix::StopThemeAutomation => "Theme automation off",
+            Fix::StopNightBoost => "Night boost off",
+            Fix::EndMovieMode => "Movie mode off",
+            Fix::StopSpaceLevels => "One level for all desktops",
+        }
+    }
+}
+
+/// One monitor as it is dimmed now
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorLevel {
+    pub name: String,
+    /// Chosen level, before night boost and movie mode
+    pub level: f32,
+    /// Kept undimmed by the brightness rule
+    pub gated: bool,
+}
+
+/// What is in effect right now, from the platform
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Live {
+    /// Dimming is on screen
+    pub dimming: bool,
+    pub monitors: Vec<MonitorLevel>,
+    /// Night boost in effect, if any
+    pub night_boost: Option<f32>,
+    pub movie_mode: bool,
+}
+
+/// One reason the screen looks the way it does
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reason {
+    pub text: String,
+    pub fix: Option<Fix>,
+}
+
+/// Contents of the explainer window
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub headline: String,
+    /// One line per monitor
+    pub monitors: Vec<String>,
+    pub reasons: Vec<Reason>,
+}
+
+impl Explanation {
+    /// Fixes to offer, in the order of the reasons
+    pub fn fixes(&self) -> Vec<Fix> {
+        self.reasons
+            .iter()
+            .filter_map(|reason| reason.fix)
+            .collect()
+    }
+
+    /// Everything but the fixes, as plain text
+    pub fn text(&self) -> String {
+        let mut text = self.headline.clone();
+        if !self.monitors.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&self.monitors.join("\n"));
+        }
+        if !self.reasons.is_empty() {
+            text.push('\n');
+            for reason in &self.reasons {
+                text.push_str(&format!("\n\u{2022} {}", reason.text));
+            }
+        }
+        text
+    }
+}
+
+/// Explain the screen for `config`, the last `cause` and the `live` state.
+pub fn explain(config: &AppConfig, cause: &Cause, live: &Live) -> Explanation {
+    if !live.dimming {
+        let mut reasons = Vec::new();
+        if config.designer_mode {
+            reasons.push("Designer mode is on, so nothing is dimmed.".to_string());
+        } else if pause::is_active() {
+            let minutes = pause::remaining().map_or(0, |left| left.as_secs().div_ceil(60));
+            reasons.push(format!("Dimming is paused for {} more min.", minutes));
+        } else if presentation::is_active() {
+            reasons.push("Presentation mode is on, so nothing is dimmed.".to_string());
+        }
+        reasons.push(
+            "If the screen still looks dark, check the brightness buttons on the monitor \
+             and the system's display settings."
+                .to_string(),
+        );
+        return Explanation {
+            headline: "SaveMyEyes isn't dimming the screen right now.".to_string(),
+            monitors: Vec::new(),
+            reasons: reasons
+                .into_iter()
+                .map(|text| Reason { text, fix: None })
+                .collect(),
+        };
+    }
+
+    let monitors = live
+        .monitors
+        .iter()
+        .map(|monitor| {
+            if monitor.gated {
+                format!(
+                    "{}: not dimmed, its brightness is below the threshold",
+                    monitor.name
+                )
+            } else {
+                format!("{}: {}", monitor.name, percent_label(monitor.level))
+            }
+        })
+        .collect();
+
+    let mut reasons = vec![Reason {
+        text: match cause.describe() {
+            Some(how) => format!("Dimming is on, {}.", how),
+            None => "Dimming is on.".to_string(),
+        },
+        fix: Some(Fix::TurnOff),
+    }];
+    if config.theme_automation.enabled {
+        reasons.push(Reason {
+            text: "Theme automation applies a profile when the system switches between light \
+                   and dark mode."
+                .to_string(),
+            fix: Some(Fix::StopThemeAutomation),
+        });
+    }
+    if let Some(boost) = live.night_boost {
+        reasons.push(Reason {
+            text: format!(
+                "Night boost adds {} until {}.",
+                percent_label(boost),
+                config.night_boost.end.trim()
+            ),
+            fix: Some(Fix::StopNightBoost),
+        });
+    }
+    if live.movie_mode {
+        reasons.push(Reason {
+            text: "Movie mode darkens every display but the one with the video.".to_string(),
+            fix: Some(Fix::EndMovieMode),
+        });
+    }
+    if config.space_levels.enabled {
+        reasons.push(Reason {
+            text: "Each desktop keeps its own level, so switching desktops changes it.".to_string(),
+            fix: Some(Fix::StopSpaceLevels),
+        });
+    }
+
+    Explanation {
+        headline: "SaveMyEyes is dimming the screen.".to_string(),
+        monitors,
+        reasons,
+    }
+}
@@ -0,0 +1,110 @@
+// Toggle fade (platform-agnostic)
+//
+// Turning dimming on or off eases the screen between undimmed and the
+// level over `duration_ms` instead of snapping, which is jarring in a dark
+// room. The platform keeps a factor between 0 (undimmed) and 1 (the full
+// level) that it multiplies into what it applies (overlay alpha on
+// Windows, the gamma maximum on macOS), and steps it every `FRAME` with
+// `Fade::factor`. A fade turned around halfway (toggled again) starts from
+// the factor it had reached, so it never jumps.
+//
+// Like the HUD fade, it only plays when animations do (motion.rs); a
+// duration of 0 turns it off.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Time between fade steps, about one per display frame
+pub const FRAME: Duration = Duration::from_millis(16);
+/// Longest fade `duration_ms` may ask for
+pub const MAX_DURATION_MS: u32 = 5000;
+
+/// How the factor moves over the fade
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    /// Slow start
+    EaseIn,
+    /// Slow end
+    EaseOut,
+    /// Slow start and end
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Eased progress for linear progress `t` (0–1)
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// `fade` config block
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FadeSettings {
+    /// Length of a full fade in milliseconds (0 = off)
+    pub duration_ms: u32,
+    pub easing: Easing,
+}
+
+impl Default for FadeSettings {
+    fn default() -> Self {
+        Self {
+            duration_ms: 400,
+            easing: Easing::EaseInOut,
+        }
+    }
+}
+
+/// One fade in progress
+#[derive(Debug, Clone, Copy)]
+pub struct Fade {
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Fade {
+    /// Fade from factor `from` to `to` (0 or 1). Starting partway shortens
+    /// the fade in proportion, so turning around takes as long as it took
+    /// to get there.
+    pub fn new(from: f32, to: f32, settings: &FadeSettings) -> Self {
+        let full = Duration::from_millis(settings.duration_ms.min(MAX_DURATION_MS) as u64);
+        Self {
+            from,
+            to,
+            started: Instant::now(),
+            duration: full.mul_f32((to - from).abs().clamp(0.0, 1.0)),
+            easing: settings.easing,
+        }
+    }
+
+    /// Factor to apply now, and whether the fade is over
+    pub fn factor(&self) -> (f32, bool) {
+        if self.duration.is_zero() {
+            return (self.to, true);
+        }
+        let t = self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32();
+        if t >= 1.0 {
+            return (self.to, true);
+        }
+        let eased = self.easing.apply(t);
+        (self.from + (self.to - self.from) * eased, false)
+    }
+
+    /// Whether this fade ends undimmed
+    pub fn fading_out(&self) -> bool {
+        self.to == 0.0
+    }
+}
@@ -0,0 +1,119 @@
+// "Boost here": less dimming while one window keeps the focus
+// (platform-agnostic)
+//
+// The boost hotkey (fixed, like movie mode's) lifts the dimming by `step`,
+// 20% by default, for as long as the window that had the focus when it was
+// pressed keeps it: a bright document read for a minute without touching
+// the level. Pressing it again on the same window lifts another step.
+//
+// Each press pushes an override on an `OverrideStack`, which the dimming
+// backend applies to every level on top of everything else (the night
+// boost, reading monitors) and which is never saved. The platforms watch
+// the focus while the stack isn't empty and hand it every change; the
+// overrides tied to another window are dropped and the levels come back.
+// A window is its handle plus its title, so another tab or document in the
+// same window (its title changes) ends the boost too.
+
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::{self, MAX_OPACITY};
+
+/// Boost hotkey settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusBoost {
+    /// Taken off the level by each press
+    pub step: f32,
+}
+
+impl Default for FocusBoost {
+    fn default() -> Self {
+        Self { step: 0.2 }
+    }
+}
+
+/// The window an override is tied to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusedWindow {
+    /// Platform handle (HWND, process id)
+    pub handle: u64,
+    pub title: String,
+}
+
+/// One press of the boost hotkey
+#[derive(Debug, Clone, PartialEq)]
+struct LevelOverride {
+    window: FocusedWindow,
+    lift: f32,
+}
+
+/// Temporary overrides on top of the levels, newest last
+#[derive(Debug, Default)]
+pub struct OverrideStack {
+    overrides: Vec<LevelOverride>,
+}
+
+impl OverrideStack {
+    pub const fn new() -> Self {
+        Self {
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.overrides.is_empty()
+    }
+
+    /// Lift the levels by `lift` while `window` has the focus. Returns the
+    /// whole lift now applied to it.
+    pub fn push(&mut self, window: FocusedWindow, lift: f32) -> f32 {
+        self.focus_changed(Some(&window));
+        self.overrides.push(LevelOverride { window, lift });
+        self.lift()
+    }
+
+    /// The focus moved to `window` (None: to nothing we can name). Drops
+    /// the overrides tied to any other window; returns whether there were
+    /// any.
+    pub fn focus_changed(&mut self, window: Option<&FocusedWindow>) -> bool {
+        let before = self.overrides.len();
+        self.overrides.retain(|o| Some(&o.window) == window);
+        self.overrides.len() != before
+    }
+
+    /// Drop every override. Returns whether there were any.
+    pub fn clear(&mut self) -> bool {
+        let had = self.is_active();
+        self.overrides.clear();
+        had
+    }
+
+    /// Everything taken off the levels
+    pub fn lift(&self) -> f32 {
+        self.overrides.iter().map(|o| o.lift).sum()
+    }
+
+    /// `level` with the overrides applied
+    pub fn apply(&self, level: f32) -> f32 {
+        if self.overrides.is_empty() {
+            return level;
+        }
+        (level - self.lift()).clamp(0.0, MAX_OPACITY)
+    }
+}
+
+/// HUD message after a press: "Boost −20%: Report.pdf"
+pub fn message(lift: f32, title: &str) -> String {
+    const MAX_TITLE: usize = 40;
+    let title: String = if title.chars().count() > MAX_TITLE {
+        title.chars().take(MAX_TITLE - 1).chain(['…']).collect()
+    } else {
+        title.to_string()
+    };
+    let lift = dimming::percent_label(lift);
+    if title.is_empty() {
+        format!("Boost \u{2212}{}", lift)
+    } else {
+        format!("Boost \u{2212}{}: {}", lift, title)
+    }
+}
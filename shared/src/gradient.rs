@@ -0,0 +1,85 @@
+// Gradient dimming: darker at the top, lighter at the bottom (platform-agnostic)
+//
+// Bright toolbars, tab strips and title bars sit at the top of the screen,
+// the content people read further down. With the gradient on, the dim level
+// runs from `top` times the chosen level at the top edge to `bottom` times
+// it at the bottom edge, so 40% with the default 125%/75% is 50% at the top
+// and 30% at the bottom. The chosen level stays the one the slider, hotkeys
+// and profiles work with.
+//
+// Only a window overlay can vary its alpha across the screen (per-pixel
+// alpha, Windows). A gamma ramp applies to the whole display, so monitors
+// dimmed through gamma, and macOS, stay uniform.
+
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::MAX_OPACITY;
+
+/// Top edge choices, as a share of the level (the settings button cycles)
+pub const TOP_STEPS: [f32; 6] = [1.0, 1.1, 1.25, 1.5, 1.75, 2.0];
+/// Bottom edge choices, as a share of the level
+pub const BOTTOM_STEPS: [f32; 6] = [0.25, 0.5, 0.6, 0.75, 0.9, 1.0];
+/// Largest share either edge may have
+pub const MAX_SCALE: f32 = 2.0;
+
+/// Overlay gradient. Off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayGradient {
+    pub enabled: bool,
+    /// Share of the level at the top edge (1.25 = 125%)
+    pub top: f32,
+    /// Share of the level at the bottom edge
+    pub bottom: f32,
+}
+
+impl Default for OverlayGradient {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            top: 1.25,
+            bottom: 0.75,
+        }
+    }
+}
+
+impl OverlayGradient {
+    /// True if every row gets the same level
+    pub fn is_flat(&self) -> bool {
+        !self.enabled || (self.top == 1.0 && self.bottom == 1.0)
+    }
+
+    /// Level at `position` down the screen (0.0 = top edge, 1.0 = bottom)
+    /// for the chosen `level`
+    pub fn level_at(&self, level: f32, position: f32) -> f32 {
+        if !self.enabled {
+            return level;
+        }
+        let position = position.clamp(0.0, 1.0);
+        let scale = self.top + (self.bottom - self.top) * position;
+        (level * scale).clamp(0.0, MAX_OPACITY)
+    }
+
+    /// "Top 125% · Bottom 75%"
+    pub fn label(&self) -> String {
+        format!(
+            "Top {} \u{00b7} Bottom {}",
+            scale_label(self.top),
+            scale_label(self.bottom)
+        )
+    }
+}
+
+/// "125%"
+pub fn scale_label(scale: f32) -> String {
+    format!("{}%", (scale * 100.0).round() as i32)
+}
+
+/// The step after `current` in `steps`, wrapping to the first
+pub fn next_step(steps: &[f32], current: f32) -> f32 {
+    steps
+        .iter()
+        .copied()
+        .find(|step| *step > current + 0.001)
+        .unwrap_or(steps[0])
+}
@@ -0,0 +1,485 @@
+// Hotkey actions, keyboard shortcuts and mouse-button bindings
+// (platform-agnostic)
+//
+// Keyboard shortcuts are written like "Ctrl+Shift+F9" or "Cmd+Option+D" and
+// parsed into a `KeyCombo` here; each platform registers (Windows) or
+// matches (macOS) the combos from config and does so again when they
+// change. Movie mode and "boost here" (focus_boost.rs) keep fixed shortcuts.
+//
+// Mouse-button hotkeys bind the side buttons (Mouse4 = back, Mouse5 =
+// forward) plus modifiers to the same actions; bindings are stored as
+// strings like "Ctrl+Mouse5". The platform hooks only report which button
+// went down and which modifiers were held, and `MouseHotkeys::action_for`
+// picks the action to dispatch.
+//
+// Level chord: with `level_chord` on, the toggle hotkey also opens a short
+// window in which two typed digits set an exact level (toggle, 4, 5 → 45%).
+// `LevelChord` is the state machine; the platforms feed it digits while it
+// is active and show each step in the HUD.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// What a hotkey does, whichever input triggered it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    Toggle,
+    Increase,
+    Decrease,
+    /// Movie mode on/off (keyboard only, no mouse binding)
+    MovieMode,
+    /// Less dimming while the focused window keeps the focus (keyboard
+    /// only, no mouse binding)
+    Boost,
+}
+
+impl HotkeyAction {
+    /// Name for the settings UI and its messages
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::Toggle => "Toggle dimmer",
+            HotkeyAction::Increase => "Increase dimming",
+            HotkeyAction::Decrease => "Decrease dimming",
+            HotkeyAction::MovieMode => "Movie mode",
+            HotkeyAction::Boost => "Boost here",
+        }
+    }
+}
+
+/// Mouse side buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    /// XButton1 / "back"
+    Mouse4,
+    /// XButton2 / "forward"
+    Mouse5,
+}
+
+/// Modifier keys held with a binding. `meta` is Win on Windows, Cmd on macOS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+/// A mouse button plus the exact modifiers that must be held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseBinding {
+    pub modifiers: Modifiers,
+    pub button: MouseButton,
+}
+
+impl MouseBinding {
+    /// Parse "Ctrl+Alt+Mouse4" style strings (case-insensitive). Exactly one
+    /// button is required; "Back"/"Forward" and "XButton1"/"XButton2" work too.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::default();
+        let mut button = None;
+        for part in text.split('+').map(|p| p.trim().to_ascii_lowercase()) {
+            match part.as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" | "opt" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "win" | "cmd" | "command" | "meta" | "super" => modifiers.meta = true,
+                "mouse4" | "xbutton1" | "back" if button.is_none() => {
+                    button = Some(MouseButton::Mouse4)
+                }
+                "mouse5" | "xbutton2" | "forward" if button.is_none() => {
+                    button = Some(MouseButton::Mouse5)
+                }
+                _ => return None,
+            }
+        }
+        button.map(|button| Self { modifiers, button })
+    }
+}
+
+impl fmt::Display for MouseBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = &self.modifiers;
+        if m.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if m.alt {
+            write!(f, "{}", if cfg!(target_os = "macos") { "Option+" } else { "Alt+" })?;
+        }
+        if m.shift {
+            write!(f, "Shift+")?;
+        }
+        if m.meta {
+            write!(f, "{}", if cfg!(target_os = "macos") { "Cmd+" } else { "Win+" })?;
+        }
+        match self.button {
+            MouseButton::Mouse4 => write!(f, "Mouse4"),
+            MouseButton::Mouse5 => write!(f, "Mouse5"),
+        }
+    }
+}
+
+/// Mouse-button hotkeys. Off by default: the global mouse hook behind them
+/// sees every mouse event system-wide, which costs a little on each move.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseHotkeys {
+    pub enabled: bool,
+    pub toggle: Option<String>,
+    pub increase: Option<String>,
+    pub decrease: Option<String>,
+}
+
+impl Default for MouseHotkeys {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle: None,
+            increase: Some("Ctrl+Mouse5".into()),
+            decrease: Some("Ctrl+Mouse4".into()),
+        }
+    }
+}
+
+impl MouseHotkeys {
+    /// Valid bindings with their actions. Unparseable strings are logged and skipped.
+    pub fn bindings(&self) -> Vec<(MouseBinding, HotkeyAction)> {
+        [
+            (&self.toggle, HotkeyAction::Toggle),
+            (&self.increase, HotkeyAction::Increase),
+            (&self.decrease, HotkeyAction::Decrease),
+        ]
+        .into_iter()
+        .filter_map(|(text, action)| {
+            let text = text.as_deref()?;
+            match MouseBinding::parse(text) {
+                Some(binding) => Some((binding, action)),
+                None => {
+                    eprintln!("[hotkeys] ignoring invalid mouse binding {:?}", text);
+                    None
+                }
+            }
+        })
+        .collect()
+    }
+
+    /// Action bound to `button` with exactly `modifiers` held, if enabled
+    pub fn action_for(&self, button: MouseButton, modifiers: Modifiers) -> Option<HotkeyAction> {
+        if !self.enabled {
+            return None;
+        }
+        self.bindings()
+            .into_iter()
+            .find(|(b, _)| b.button == button && b.modifiers == modifiers)
+            .map(|(_, action)| action)
+    }
+
+    /// One-line summary for the settings UI, e.g. "Ctrl+Mouse5 = more, Ctrl+Mouse4 = less"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = self
+            .bindings()
+            .iter()
+            .map(|(binding, action)| {
+                let verb = match action {
+                    HotkeyAction::Toggle => "toggle",
+                    HotkeyAction::Increase => "more",
+                    HotkeyAction::Decrease => "less",
+                    HotkeyAction::MovieMode => "movie mode",
+                    HotkeyAction::Boost => "boost here",
+                };
+                format!("{} = {}", binding, verb)
+            })
+            .collect();
+        if parts.is_empty() {
+            "No buttons bound".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// The key of a keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A–Z or 0–9 (letters upper case)
+    Char(char),
+    /// F1–F24
+    Function(u8),
+    /// Arrows, navigation, editing keys and a little punctuation
+    Named(NamedKey),
+}
+
+/// Keys without a character of their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    Space,
+    Tab,
+    Enter,
+    Escape,
+    Backspace,
+    Period,
+    Comma,
+}
+
+impl NamedKey {
+    pub const ALL: [NamedKey; 17] = [
+        NamedKey::Up,
+        NamedKey::Down,
+        NamedKey::Left,
+        NamedKey::Right,
+        NamedKey::Home,
+        NamedKey::End,
+        NamedKey::PageUp,
+        NamedKey::PageDown,
+        NamedKey::Insert,
+        NamedKey::Delete,
+        NamedKey::Space,
+        NamedKey::Tab,
+        NamedKey::Enter,
+        NamedKey::Escape,
+        NamedKey::Backspace,
+        NamedKey::Period,
+        NamedKey::Comma,
+    ];
+
+    /// Name as written in shortcut strings
+    pub fn name(self) -> &'static str {
+        match self {
+            NamedKey::Up => "Up",
+            NamedKey::Down => "Down",
+            NamedKey::Left => "Left",
+            NamedKey::Right => "Right",
+            NamedKey::Home => "Home",
+            NamedKey::End => "End",
+            NamedKey::PageUp => "PageUp",
+            NamedKey::PageDown => "PageDown",
+            NamedKey::Insert => "Insert",
+            NamedKey::Delete => "Delete",
+            NamedKey::Space => "Space",
+            NamedKey::Tab => "Tab",
+            NamedKey::Enter => "Enter",
+            NamedKey::Escape => "Esc",
+            NamedKey::Backspace => "Backspace",
+            NamedKey::Period => ".",
+            NamedKey::Comma => ",",
+        }
+    }
+
+    /// Parse a lower-case key name, including the usual alternatives
+    fn parse(name: &str) -> Option<Self> {
+        let alias = match name {
+            "pgup" => "pageup",
+            "pgdn" => "pagedown",
+            "ins" => "insert",
+            "del" => "delete",
+            "return" => "enter",
+            "escape" => "esc",
+            "period" => ".",
+            "comma" => ",",
+            other => other,
+        };
+        Self::ALL
+            .into_iter()
+            .find(|key| key.name().eq_ignore_ascii_case(alias))
+    }
+}
+
+/// Modifiers plus one key: the keyboard shortcuts of the hotkey actions
+/// (`hotkey_toggle` etc. in config) and of custom actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl KeyCombo {
+    /// Parse "Ctrl+Shift+F9" / "Cmd+Option+D" style strings
+    /// (case-insensitive). At least one of Ctrl, Alt or Win/Cmd is
+    /// required, so a plain or shifted key can't be taken from every other
+    /// app.
+    pub fn parse(text: &str) -> Option<Self> {
+        let parts: Vec<String> = text
+            .split('+')
+            .map(|p| p.trim().to_ascii_lowercase())
+            .collect();
+        let (key, names) = parts.split_last()?;
+        let mut modifiers = Modifiers::default();
+        for name in names {
+            match name.as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" | "opt" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "win" | "cmd" | "command" | "meta" | "super" => modifiers.meta = true,
+                _ => return None,
+            }
+        }
+        let mut chars = key.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() => Key::Char(c.to_ascii_uppercase()),
+            _ => match key
+                .strip_prefix('f')
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=24).contains(n))
+            {
+                Some(n) => Key::Function(n),
+                None => Key::Named(NamedKey::parse(key)?),
+            },
+        };
+        Some(Self { modifiers, key }).filter(Self::is_usable)
+    }
+
+    /// At least one of Ctrl, Alt or Win/Cmd is held
+    pub fn is_usable(&self) -> bool {
+        self.modifiers.ctrl || self.modifiers.alt || self.modifiers.meta
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = &self.modifiers;
+        if m.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if m.alt {
+            write!(f, "{}", if cfg!(target_os = "macos") { "Option+" } else { "Alt+" })?;
+        }
+        if m.shift {
+            write!(f, "Shift+")?;
+        }
+        if m.meta {
+            write!(f, "{}", if cfg!(target_os = "macos") { "Cmd+" } else { "Win+" })?;
+        }
+        match self.key {
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Function(n) => write!(f, "F{}", n),
+            Key::Named(key) => write!(f, "{}", key.name()),
+        }
+    }
+}
+
+/// Default shortcuts of toggle, increase and decrease
+#[cfg(target_os = "macos")]
+pub const DEFAULT_KEYS: [&str; 3] = ["Cmd+Shift+D", "Cmd+Shift+.", "Cmd+Shift+,"];
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_KEYS: [&str; 3] = ["Ctrl+Alt+End", "Ctrl+Alt+Up", "Ctrl+Alt+Down"];
+
+/// Movie mode's shortcut, which can't be changed
+#[cfg(target_os = "macos")]
+pub const MOVIE_MODE_KEYS: &str = "Cmd+Shift+M";
+#[cfg(not(target_os = "macos"))]
+pub const MOVIE_MODE_KEYS: &str = "Ctrl+Alt+M";
+
+/// The boost hotkey's shortcut (focus_boost.rs), which can't be changed
+#[cfg(target_os = "macos")]
+pub const BOOST_KEYS: &str = "Cmd+Shift+B";
+#[cfg(not(target_os = "macos"))]
+pub const BOOST_KEYS: &str = "Ctrl+Alt+B";
+
+/// What `hotkey_toggle`, `hotkey_increase` and `hotkey_decrease` held before
+/// macOS read them: the Windows defaults, which macOS never used
+pub const UNUSED_MACOS_KEYS: [&str; 3] = ["Ctrl+Alt+End", "Ctrl+Alt+Up", "Ctrl+Alt+Down"];
+
+/// Keyboard shortcuts of the hotkey actions, from the config strings of
+/// toggle, increase and decrease plus the fixed movie mode and boost ones.
+/// Unparseable strings are skipped (sanitize has reset them already).
+pub fn key_bindings(keys: [&str; 3]) -> Vec<(KeyCombo, HotkeyAction)> {
+    let actions = [
+        HotkeyAction::Toggle,
+        HotkeyAction::Increase,
+        HotkeyAction::Decrease,
+    ];
+    keys.into_iter()
+        .zip(actions)
+        .chain([
+            (MOVIE_MODE_KEYS, HotkeyAction::MovieMode),
+            (BOOST_KEYS, HotkeyAction::Boost),
+        ])
+        .filter_map(|(text, action)| Some((KeyCombo::parse(text)?, action)))
+        .collect()
+}
+
+/// How long after the toggle hotkey both level digits must arrive
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Highest level a chord can set (the slider maximum)
+const CHORD_MAX_PERCENT: u32 = 90;
+
+/// Result of feeding a digit to a `LevelChord`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordStep {
+    /// Not listening: no chord started, or it timed out
+    Inactive,
+    /// First digit typed, waiting for the second
+    Partial(u8),
+    /// Both digits typed: set this percentage (clamped to 90)
+    Complete(u32),
+}
+
+/// "Toggle hotkey, digit, digit" state machine
+#[derive(Debug, Default)]
+pub struct LevelChord {
+    started: Option<Instant>,
+    first: Option<u8>,
+}
+
+impl LevelChord {
+    pub const fn new() -> Self {
+        Self {
+            started: None,
+            first: None,
+        }
+    }
+
+    /// Begin listening for digits (on the toggle hotkey)
+    pub fn start(&mut self, now: Instant) {
+        self.started = Some(now);
+        self.first = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.started = None;
+        self.first = None;
+    }
+
+    /// Still inside the chord window
+    pub fn is_active(&self, now: Instant) -> bool {
+        self.started
+            .is_some_and(|t| now.saturating_duration_since(t) <= CHORD_TIMEOUT)
+    }
+
+    /// Feed a typed digit (0–9). Anything after the timeout cancels the chord.
+    pub fn push_digit(&mut self, digit: u8, now: Instant) -> ChordStep {
+        if !self.is_active(now) || digit > 9 {
+            self.cancel();
+            return ChordStep::Inactive;
+        }
+        match self.first.take() {
+            None => {
+                self.first = Some(digit);
+                ChordStep::Partial(digit)
+            }
+            Some(first) => {
+                self.cancel();
+                ChordStep::Complete((first as u32 * 10 + digit as u32).min(CHORD_MAX_PERCENT))
+            }
+        }
+    }
+}
+
+/// HUD text while a chord is being typed: "Level: _ _", "Level: 4 _"
+pub fn chord_prompt(first: Option<u8>) -> String {
+    match first {
+        Some(d) => format!("Level: {} _", d),
+        None => "Level: _ _".to_string(),
+    }
+}
@@ -0,0 +1,354 @@
+// Crash-resistant journal of the applied screen state (platform-agnostic)
+//
+// config.json holds user preferences and is only written when an interaction
+// finishes (slider released, toggle clicked). The screen, however, changes
+// continuously during a drag or a ramp. The platform overlay modules report
+// every applied state here; a background writer flushes the latest one to
+// state.json at most every FLUSH_MS, using write-to-temp + rename so a crash
+// never leaves a torn file.
+//
+// A clean exit deletes the journal. If it is still there on the next launch,
+// the process died with the screen ahead of config, and `reconcile` carries
+// the last applied state over into config so the user gets what they last saw.
+// Whether dimming is on comes from the user's choice journaled with each
+// state, not from the screen: a pause, movie mode or the screen clip takes
+// dimming off the screen for a while, and a crash then must not turn it off
+// for good.
+//
+// A self-update relaunch `hand_over`s the journal instead of deleting it: the
+// new process reads it first thing and puts the same dimming back on screen
+// before any other startup work, so the screen is only bright for the moment
+// between the two processes. `dark_gap_ms` measures that moment.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{config_path, save_config, AppConfig};
+use crate::dimming::OpacityCurve;
+use crate::explain::{self, Cause};
+use crate::monitor_id::MonitorId;
+use crate::status_file;
+
+/// How often pending state is flushed to disk
+const FLUSH_MS: u64 = 250;
+
+/// The user's own setting, whatever is on screen at the moment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Choice {
+    /// Dimming turned on
+    pub enabled: bool,
+    /// Global level
+    pub level: f32,
+}
+
+/// Effective state of the screen, as last applied by the overlay module
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedState {
+    /// Milliseconds since the Unix epoch when this state was applied
+    pub applied_at: u64,
+    /// Dimming currently on screen
+    pub enabled: bool,
+    /// Global opacity in effect
+    pub opacity: f32,
+    /// Effective per-monitor opacity keyed by monitor id
+    #[serde(default)]
+    pub monitor_opacity: HashMap<MonitorId, f32>,
+    /// The same keyed by monitor index (Windows)
+    #[serde(default)]
+    pub per_monitor_opacity: HashMap<u32, f32>,
+    /// The same keyed by display name (macOS)
+    #[serde(default)]
+    pub per_display_opacity: HashMap<String, f32>,
+    /// Profile that produced this state, if any
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Dimming is paused until this Unix timestamp (seconds), if any
+    #[serde(default)]
+    pub paused_until: Option<u64>,
+    /// Curve the levels were mapped through
+    #[serde(default)]
+    pub curve: OpacityCurve,
+    /// Milliseconds since the Unix epoch when the process quit to hand the
+    /// screen over to its replacement (self-update relaunch)
+    #[serde(default)]
+    pub handed_over_at: Option<u64>,
+    /// What made the last change (explain.rs)
+    #[serde(default)]
+    pub cause: Cause,
+    /// The user's setting as of the last config load or save (None in
+    /// journals of older versions)
+    #[serde(default)]
+    pub choice: Option<Choice>,
+}
+
+impl AppliedState {
+    pub fn new(enabled: bool, opacity: f32) -> Self {
+        Self {
+            applied_at: now_ms(),
+            enabled,
+            opacity,
+            monitor_opacity: HashMap::new(),
+            per_monitor_opacity: HashMap::new(),
+            per_display_opacity: HashMap::new(),
+            active_profile: None,
+            paused_until: None,
+            curve: OpacityCurve::default(),
+            handed_over_at: None,
+            cause: explain::last(),
+            choice: *CHOICE.lock().unwrap(),
+        }
+    }
+
+    /// How long the screen went without dimming between the hand-over and
+    /// now, if this state was handed over
+    pub fn dark_gap_ms(&self) -> Option<u64> {
+        self.handed_over_at.map(|at| now_ms().saturating_sub(at))
+    }
+}
+
+static PENDING: Mutex<Option<AppliedState>> = Mutex::new(None);
+/// Journaled with every state, see `Choice`
+static CHOICE: Mutex<Option<Choice>> = Mutex::new(None);
+static WRITER_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set for processes that must not touch the user's journal (self-test)
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+pub fn journal_path() -> PathBuf {
+    config_path().with_file_name("state.json")
+}
+
+pub fn load() -> Option<AppliedState> {
+    let data = fs::read_to_string(journal_path()).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn write(state: &AppliedState) {
+    let path = journal_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_string(state).unwrap_or_default();
+    if fs::write(&tmp, data).is_ok() {
+        let _ = fs::rename(&tmp, &path);
+    }
+}
+
+/// Record a newly applied state. Cheap enough to call on every slider
+/// movement; the actual write happens on a background thread.
+pub fn record(state: AppliedState) {
+    if DISABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    *PENDING.lock().unwrap() = Some(state);
+
+    if !WRITER_RUNNING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(Duration::from_millis(FLUSH_MS));
+            // Write while holding the lock so clear() can't race a flush
+            // and leave a journal behind after a clean exit.
+            let mut pending = PENDING.lock().unwrap();
+            match pending.take() {
+                Some(state) => {
+                    write(&state);
+                    status_file::applied(&state);
+                }
+                None => {
+                    drop(pending);
+                    WRITER_RUNNING.store(false, Ordering::SeqCst);
+                    // A record() may have raced in after the take() above;
+                    // restart the loop if nobody else picked it up.
+                    if PENDING.lock().unwrap().is_none()
+                        || WRITER_RUNNING.swap(true, Ordering::SeqCst)
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Config was loaded or saved: its enabled state and level are the user's
+/// choice from now on
+pub(crate) fn note_choice(config: &AppConfig) {
+    *CHOICE.lock().unwrap() = Some(Choice {
+        enabled: config.is_enabled,
+        level: config.opacity,
+    });
+}
+
+/// Ignore every later `record` in this process, so a run that isn't the
+/// app (e.g. `--self-test`) leaves the running app's journal alone.
+pub fn disable() {
+    DISABLED.store(true, Ordering::SeqCst);
+}
+
+/// True after `disable` (status.json is left alone too)
+pub(crate) fn is_disabled() -> bool {
+    DISABLED.load(Ordering::SeqCst)
+}
+
+/// Drop any pending write and delete the journal (clean exit, after the
+/// final config save). status.json is kept, marked as not running.
+pub fn clear() {
+    let mut pending = PENDING.lock().unwrap();
+    if let Some(state) = pending.take() {
+        status_file::applied(&state);
+    }
+    let _ = fs::remove_file(journal_path());
+    status_file::finish();
+}
+
+/// Keep the journal for the process about to replace this one: flush the
+/// last applied state now, stamped with the hand-over time, and stop
+/// recording. Called right before quitting for a relaunch, in place of
+/// `clear`.
+pub fn hand_over() {
+    let mut pending = PENDING.lock().unwrap();
+    if let Some(mut state) = pending.take().or_else(load) {
+        state.handed_over_at = Some(now_ms());
+        write(&state);
+        status_file::applied(&state);
+    }
+    status_file::finish();
+    DISABLED.store(true, Ordering::SeqCst);
+}
+
+/// Carry a leftover journal (unclean exit) over into `config` when it is
+/// newer than the last config save. Saves config and deletes the journal.
+/// Returns true if config was changed.
+pub fn reconcile(config: &mut AppConfig) -> bool {
+    let Some(state) = load() else {
+        return false;
+    };
+    let _ = fs::remove_file(journal_path());
+
+    let config_saved_at = fs::metadata(config_path())
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    if state.applied_at <= config_saved_at {
+        return false;
+    }
+
+    let changed = carry_over(state, config);
+    if changed {
+        save_config(config);
+    }
+    changed
+}
+
+/// Copy the user's choice and the levels of `state` into `config`. True if
+/// anything changed.
+fn carry_over(state: AppliedState, config: &mut AppConfig) -> bool {
+    let choice = state.choice.unwrap_or(Choice {
+        enabled: state.enabled,
+        level: state.opacity,
+    });
+    // While dimming is on screen its level is the user's, and newer than
+    // the choice during a drag (config is saved on release)
+    let level = if state.enabled {
+        state.opacity
+    } else {
+        choice.level
+    };
+    let mut changed = false;
+    if config.is_enabled != choice.enabled {
+        config.is_enabled = choice.enabled;
+        changed = true;
+    }
+    if choice.enabled && (config.opacity - level).abs() > f32::EPSILON {
+        config.opacity = level;
+        changed = true;
+    }
+    for (id, op) in state.monitor_opacity.clone() {
+        if config.monitor_opacity.insert(id, op) != Some(op) {
+            changed = true;
+        }
+    }
+    // Journal of a version without monitor ids: adopt_monitor_ids moves
+    // these once the monitors are listed
+    if state.monitor_opacity.is_empty() {
+        for (idx, op) in state.per_monitor_opacity {
+            if config.per_monitor_opacity.insert(idx, op) != Some(op) {
+                changed = true;
+            }
+        }
+        for (name, op) in state.per_display_opacity {
+            if config.per_display_opacity.insert(name.clone(), op) != Some(op) {
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(enabled: bool, opacity: f32) -> AppConfig {
+        AppConfig {
+            is_enabled: enabled,
+            opacity,
+            ..AppConfig::default()
+        }
+    }
+
+    fn state(on_screen: bool, opacity: f32, choice: Option<(bool, f32)>) -> AppliedState {
+        let mut state = AppliedState::new(on_screen, opacity);
+        state.choice = choice.map(|(enabled, level)| Choice { enabled, level });
+        state
+    }
+
+    #[test]
+    fn crash_during_a_pause_keeps_dimming_on() {
+        // Paused (or in movie mode, or under the screen clip): the screen
+        // is bright while dimming stays chosen at 40%
+        let mut cfg = config(true, 0.4);
+        assert!(!carry_over(state(false, 0.0, Some((true, 0.4))), &mut cfg));
+        assert!(cfg.is_enabled);
+        assert_eq!(cfg.opacity, 0.4);
+
+        // Turned off by the user, then the process died
+        let mut cfg = config(true, 0.4);
+        assert!(carry_over(state(false, 0.0, Some((false, 0.4))), &mut cfg));
+        assert!(!cfg.is_enabled);
+        assert_eq!(cfg.opacity, 0.4);
+    }
+
+    #[test]
+    fn level_on_screen_is_newer_than_the_choice() {
+        // Crashed mid-drag, before the release saved 55%
+        let mut cfg = config(true, 0.4);
+        let id = MonitorId::from_name_position("DELL", 0, 0);
+        let mut dragged = state(true, 0.55, Some((true, 0.4)));
+        dragged.monitor_opacity.insert(id.clone(), 0.2);
+        assert!(carry_over(dragged, &mut cfg));
+        assert_eq!(cfg.opacity, 0.55);
+        assert_eq!(cfg.monitor_opacity.get(&id), Some(&0.2));
+    }
+
+    #[test]
+    fn older_journals_follow_the_screen() {
+        let mut cfg = config(false, 0.3);
+        assert!(carry_over(state(true, 0.5, None), &mut cfg));
+        assert!(cfg.is_enabled);
+        assert_eq!(cfg.opacity, 0.5);
+    }
+}
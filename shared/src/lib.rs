@@ -1,4 +1,65 @@
 // Shared types and logic for SaveMyEyes (cross-platform)
+//
+// Layers, from the bottom:
+//   • savemyeyes-core (core/): pure logic with no OS, network or allocation
+//     (version compare, time-of-day math, the pause state machine), no_std
+//     so it builds and runs anywhere
+//   • this crate: config, rules and everything else the apps share; it may
+//     use files, threads and the clock, but no platform frameworks
+//   • net (feature "updater"): the only code that opens connections
+//   • windows/, macos/ and linux/: platform glue; windows/ and macos/ also
+//     build on other hosts with feature "headless" (backend.rs)
+// Logic that needs none of the OS belongs in core.
 
+pub mod adjust_ramp;
+pub mod api_schema;
+pub mod backend;
+pub mod backups;
+pub mod brightness_rule;
+pub mod builtin_profiles;
+pub mod calibration;
+pub mod capabilities;
+pub mod color_filter;
 pub mod config;
+pub mod config_text;
+pub mod contrast_guard;
+pub mod custom_actions;
+pub mod dimming;
+pub mod displays;
+pub mod edge_vignette;
+pub mod events;
+pub mod explain;
+pub mod fade;
+pub mod focus_boost;
+pub mod gradient;
+pub mod hotkeys;
+pub mod journal;
+pub mod locale;
+pub mod monitor_groups;
+pub mod monitor_id;
+pub mod motion;
+pub mod movie_mode;
+pub mod native_messaging;
+#[cfg(feature = "updater")]
+pub mod net;
+pub mod night_boost;
+pub mod pause;
+pub mod pen_pause;
+pub mod presentation;
+pub mod profiles;
+pub mod reading_monitor;
+#[cfg(feature = "scheduler")]
+pub mod schedule;
+pub mod scheduler;
+pub mod self_test;
+pub mod shutdown;
+pub mod soak;
+pub mod space_levels;
+pub mod status_file;
+#[cfg(feature = "scheduler")]
+pub mod sun;
+pub mod time_change;
+pub mod trace;
+pub mod tray_menu;
 pub mod updater;
+pub mod usage_stats;
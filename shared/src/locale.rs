@@ -0,0 +1,101 @@
+// Regional number and time formats, and layout direction (platform-agnostic)
+//
+// The UI text is English, but numbers and times follow the user's regional
+// format: "37,5%" where the decimal separator is a comma, and "11:00 PM"
+// where the clock is 12-hour. Each platform reads the format from the OS at
+// startup (GetLocaleInfoEx on Windows, NSLocale on macOS) and calls `set`.
+// Levels reach the UI through dimming::percent_label, which uses `percent`;
+// times from config ("23:00") go through `time`. config.json, the command
+// bus, status.json and the logs keep the fixed "0.4" / "23:00" forms.
+//
+// `rtl` mirrors the settings window for right-to-left languages (Arabic,
+// Hebrew): WS_EX_LAYOUTRTL on Windows, flipped frames and layout direction
+// on macOS. It belongs to the UI language, which is English until
+// translations exist, so for now only `--rtl` turns it on, to check the
+// mirrored layout.
+
+use std::sync::Mutex;
+
+use crate::night_boost::parse_time;
+
+/// Command-line flag that mirrors the settings window
+pub const RTL_ARG: &str = "--rtl";
+
+/// How numbers, times and the settings layout are presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// "." or "," (or whatever the region uses)
+    pub decimal_separator: char,
+    /// 24-hour clock; false for "11:00 PM"
+    pub hour24: bool,
+    /// Mirror the settings layout right to left
+    pub rtl: bool,
+}
+
+impl Locale {
+    /// What the app showed before it read the regional format
+    pub const FIXED: Locale = Locale {
+        decimal_separator: '.',
+        hour24: true,
+        rtl: false,
+    };
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::FIXED
+    }
+}
+
+static CURRENT: Mutex<Locale> = Mutex::new(Locale::FIXED);
+
+/// Use `locale` for everything formatted from now on.
+pub fn set(locale: Locale) {
+    *CURRENT.lock().unwrap() = locale;
+}
+
+/// The locale in use
+pub fn current() -> Locale {
+    *CURRENT.lock().unwrap()
+}
+
+/// Whether the command line asks for the mirrored layout
+pub fn rtl_requested() -> bool {
+    std::env::args().any(|arg| arg == RTL_ARG)
+}
+
+/// `value` with the regional decimal separator, e.g. "37,5"
+pub fn number(value: f32) -> String {
+    let text = value.to_string();
+    match current().decimal_separator {
+        '.' => text,
+        separator => text.replace('.', &separator.to_string()),
+    }
+}
+
+/// A percentage already in percent, e.g. "37,5%"
+pub fn percent(value: f32) -> String {
+    format!("{}%", number(value))
+}
+
+/// A time of day, "23:05" or "11:05 PM"
+pub fn clock(hour: u32, minute: u32) -> String {
+    if current().hour24 {
+        return format!("{:02}:{:02}", hour, minute);
+    }
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    let hour = match hour % 12 {
+        0 => 12,
+        h => h,
+    };
+    format!("{}:{:02} {}", hour, minute, suffix)
+}
+
+/// A config time ("HH:MM") for the UI. Text that isn't a time is shown as
+/// it is.
+pub fn time(text: &str) -> String {
+    match parse_time(text) {
+        Some(minutes) => clock(minutes / 60, minutes % 60),
+        None => text.trim().to_string(),
+    }
+}
@@ -0,0 +1,121 @@
+// Monitor groups (platform-agnostic)
+//
+// With four or more monitors, per-monitor levels get tedious: the two side
+// monitors are usually meant to be dimmed alike. A group names a set of
+// monitors that share one level. Setting the level of any member, from its
+// slider, the group's slider or the increase/decrease hotkeys aimed at it
+// with the cursor, sets the same level on every member; monitors outside
+// any group stay independent. Groups only link levels in multi-monitor
+// mode, and each member's level is still saved under its own id in
+// `monitor_opacity`, so ungrouping leaves every monitor where it was.
+//
+// A monitor belongs to one group at most; `sanitize` drops later
+// duplicates and groups left with fewer than two monitors. Members that
+// aren't connected keep their place in the group until they are.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monitor_id::MonitorId;
+
+/// Monitors dimmed together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorGroup {
+    /// Shown on the group's row, e.g. "Side monitors"
+    pub name: String,
+    pub monitors: Vec<MonitorId>,
+}
+
+/// The group `id` belongs to
+pub fn group_of<'a>(groups: &'a [MonitorGroup], id: &MonitorId) -> Option<&'a MonitorGroup> {
+    groups.iter().find(|group| group.monitors.contains(id))
+}
+
+/// Monitors whose level changes with `id`'s: its group, or just `id`
+pub fn linked(groups: &[MonitorGroup], id: &MonitorId) -> Vec<MonitorId> {
+    match group_of(groups, id) {
+        Some(group) => group.monitors.clone(),
+        None => vec![id.clone()],
+    }
+}
+
+/// Group `ids` under a new name ("Group 1", "Group 2", …), taking them out
+/// of the groups they were in. Returns the new group's name, or None for
+/// fewer than two monitors.
+pub fn group(groups: &mut Vec<MonitorGroup>, ids: &[MonitorId]) -> Option<String> {
+    if ids.len() < 2 {
+        return None;
+    }
+    for group in groups.iter_mut() {
+        group.monitors.retain(|id| !ids.contains(id));
+    }
+    groups.retain(|group| group.monitors.len() >= 2);
+    let name = (1..)
+        .map(|n| format!("Group {}", n))
+        .find(|name| !groups.iter().any(|group| group.name == *name))
+        .unwrap_or_default();
+    groups.push(MonitorGroup {
+        name: name.clone(),
+        monitors: ids.to_vec(),
+    });
+    Some(name)
+}
+
+/// Remove group `name`; its monitors become independent
+pub fn ungroup(groups: &mut Vec<MonitorGroup>, name: &str) {
+    groups.retain(|group| group.name != name);
+}
+
+/// Drop duplicate members and groups of fewer than two monitors. Returns
+/// a description of each change.
+pub fn sanitize(groups: &mut Vec<MonitorGroup>) -> Vec<String> {
+    let mut fixes = Vec::new();
+    let mut seen: Vec<MonitorId> = Vec::new();
+    for group in groups.iter_mut() {
+        group.monitors.retain(|id| {
+            if seen.contains(id) {
+                fixes.push(format!(
+                    "monitor_groups lists monitor {} twice, keeping the first",
+                    id
+                ));
+                return false;
+            }
+            seen.push(id.clone());
+            true
+        });
+    }
+    groups.retain(|group| {
+        let keep = group.monitors.len() >= 2;
+        if !keep {
+            fixes.push(format!(
+                "monitor group {:?} has fewer than two monitors, removing it",
+                group.name
+            ));
+        }
+        keep
+    });
+    fixes
+}
+
+/// Order of the connected monitors `ids` in a settings window: each group
+/// with a connected member, then the monitors in no group. Each entry is
+/// the group's index in `groups` (None for an ungrouped monitor) and the
+/// indices in `ids` of the monitors shown under it.
+pub fn layout(groups: &[MonitorGroup], ids: &[MonitorId]) -> Vec<(Option<usize>, Vec<usize>)> {
+    let mut rows: Vec<(Option<usize>, Vec<usize>)> = groups
+        .iter()
+        .enumerate()
+        .map(|(g, group)| {
+            let members = (0..ids.len())
+                .filter(|&i| group.monitors.contains(&ids[i]))
+                .collect();
+            (Some(g), members)
+        })
+        .filter(|(_, members): &(_, Vec<usize>)| !members.is_empty())
+        .collect();
+    rows.extend(
+        (0..ids.len())
+            .filter(|&i| group_of(groups, &ids[i]).is_none())
+            .map(|i| (None, vec![i])),
+    );
+    rows
+}
@@ -0,0 +1,113 @@
+// Stable monitor identity (platform-agnostic)
+//
+// Per-monitor settings used to be keyed two ways: by monitor index on
+// Windows, which shifts when a monitor is unplugged or the order changes,
+// and by display name on macOS, which two identical models share. A
+// MonitorId names one physical monitor the same way on both platforms:
+//   • "hw-…" hashes what the monitor reports about itself: the monitor
+//     device path on Windows (EDID manufacturer and product code plus the
+//     connector instance), EDID vendor, model and serial number on macOS
+//   • "pos-…" hashes the display name and desktop position, for monitors
+//     that report nothing usable (virtual displays, some adapters); it
+//     changes when the monitor is moved in the display arrangement
+// Two connected monitors can still come out the same: identical models
+// without a serial number on macOS, or the same name at the same position.
+// `assign` appends "-2", "-3" in enumeration order so they stay apart while
+// connected; which of them gets the suffix follows that order. Hashes are
+// FNV-1a, so ids are the same across runs, builds and platforms.
+//
+// Config maps keyed by MonitorId replace the old index and name keyed ones.
+// Those are still read: `adopt` moves every old entry to the id of the
+// monitor it refers to once the platform has listed the connected monitors.
+// Entries for monitors that aren't connected wait until they are.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::displays::DisplayInfo;
+
+/// One physical monitor
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct MonitorId(String);
+
+impl MonitorId {
+    /// Id from what the monitor reports about itself
+    pub fn from_hardware(parts: &[&str]) -> Self {
+        Self(format!("hw-{:016x}", fnv1a(parts)))
+    }
+
+    /// Fallback id from the display name and desktop position
+    pub fn from_name_position(name: &str, x: i32, y: i32) -> Self {
+        let (x, y) = (x.to_string(), y.to_string());
+        Self(format!("pos-{:016x}", fnv1a(&[name, &x, &y])))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for MonitorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// 64-bit FNV-1a over the parts, each followed by a 0xff separator
+pub(crate) fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0xff)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Make the ids of the connected monitors (in enumeration order) unique:
+/// the second monitor with an id gets "-2" appended, the third "-3".
+pub fn assign(ids: &mut [MonitorId]) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for id in ids.iter_mut() {
+        let count = seen.entry(id.0.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            id.0 = format!("{}-{}", id.0, count);
+        }
+    }
+}
+
+/// Entries of `map` for the connected monitors `ids`, keyed by monitor
+/// index, for the overlay code that works by index
+pub fn by_index<V: Copy>(map: &HashMap<MonitorId, V>, ids: &[MonitorId]) -> HashMap<u32, V> {
+    ids.iter()
+        .enumerate()
+        .filter_map(|(i, id)| Some((i as u32, *map.get(id)?)))
+        .collect()
+}
+
+/// Move the old index and name keyed entries for connected monitors into
+/// `map`, overwriting what it has for them. Returns true if any moved.
+pub fn adopt<V>(
+    map: &mut HashMap<MonitorId, V>,
+    by_index: &mut HashMap<u32, V>,
+    by_name: &mut HashMap<String, V>,
+    displays: &[DisplayInfo],
+) -> bool {
+    let mut moved = false;
+    for (i, display) in displays.iter().enumerate() {
+        if let Some(value) = by_index.remove(&(i as u32)) {
+            map.insert(display.id.clone(), value);
+            moved = true;
+        }
+        if let Some(value) = by_name.remove(&display.name) {
+            map.insert(display.id.clone(), value);
+            moved = true;
+        }
+    }
+    moved
+}
@@ -0,0 +1,49 @@
+// Reduced motion (platform-agnostic)
+//
+// The HUD fades out instead of vanishing. Motion-sensitive users turn
+// animations off in the OS (Windows: "Animation effects", macOS: "Reduce
+// motion"), and by default we follow that. `motion` in config overrides it
+// either way. The platform stores the setting at startup with `set` and asks
+// `animate` before each animation, passing its OS query so the OS setting is
+// read fresh every time.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Whether animations play (config `motion`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Motion {
+    /// Animate unless the OS asks for reduced motion
+    #[default]
+    System,
+    /// Never animate
+    Reduced,
+    /// Always animate
+    Full,
+}
+
+static SETTING: AtomicU8 = AtomicU8::new(0);
+
+/// Store the configured setting
+pub fn set(motion: Motion) {
+    SETTING.store(motion as u8, Ordering::SeqCst);
+}
+
+pub fn setting() -> Motion {
+    match SETTING.load(Ordering::SeqCst) {
+        1 => Motion::Reduced,
+        2 => Motion::Full,
+        _ => Motion::System,
+    }
+}
+
+/// Whether to play an animation now. `os_reduces_motion` is only called when
+/// the setting follows the OS.
+pub fn animate(os_reduces_motion: impl FnOnce() -> bool) -> bool {
+    match setting() {
+        Motion::System => !os_reduces_motion(),
+        Motion::Reduced => false,
+        Motion::Full => true,
+    }
+}
@@ -0,0 +1,45 @@
+// Movie mode (platform-agnostic)
+//
+// One action for watching video: the display showing the focused window is
+// undimmed and every other display is dimmed `boost` further than it is
+// configured, so the surroundings fall away. The levels are applied on top
+// of the config and never saved; toggling movie mode off (or any other
+// level change) puts back exactly what was configured.
+//
+// With `on_browser_video` the companion browser extension turns it on while
+// a tab plays video, and off again when it stops (events::Command).
+
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::MAX_OPACITY;
+
+/// Movie mode settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovieMode {
+    /// Added to the level of the displays that aren't showing the video
+    pub boost: f32,
+    /// Follow video playback reported by the browser extension
+    pub on_browser_video: bool,
+}
+
+impl Default for MovieMode {
+    fn default() -> Self {
+        Self {
+            boost: 0.3,
+            on_browser_video: false,
+        }
+    }
+}
+
+impl MovieMode {
+    /// Level for a display configured at `configured` (0.0 when dimming is
+    /// off); `focused` is the display showing the video.
+    pub fn level(&self, configured: f32, focused: bool) -> f32 {
+        if focused {
+            0.0
+        } else {
+            (configured + self.boost).clamp(0.0, MAX_OPACITY)
+        }
+    }
+}
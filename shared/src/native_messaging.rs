@@ -0,0 +1,233 @@
+// Browser extension bridge: native messaging host (platform-agnostic)
+//
+// A companion extension reaches SaveMyEyes through the browser's native
+// messaging. The browser starts our executable with the extension's origin
+// on the command line and talks over stdin/stdout, each message a 32-bit
+// native-endian length followed by that many bytes of UTF-8 JSON. The host
+// process lives only as long as the extension's port, separate from the app:
+// it forwards commands (events::Command) to the running instance and answers
+// "status" from status.json. Every request gets exactly one reply:
+//   {"type": "toggle"}                        -> {"ok": true}
+//   {"type": "status"}                        -> {"ok": true, "status": {...}}
+//   {"type": "video_playing", "playing": true} -> {"ok": false, "error": "..."}
+//
+// `--install-native-host <chrome|firefox> <extension-id>` writes the host
+// manifest that tells the browser where the executable is; the platforms put
+// it where that browser looks (a registry key on Windows, a fixed folder on
+// macOS).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::events::Command;
+use crate::status_file;
+
+/// Host name the extension connects to (also the manifest file name)
+pub const HOST_NAME: &str = "com.kdspl.savemyeyes";
+
+/// Command-line flag that writes the host manifest
+pub const INSTALL_ARG: &str = "--install-native-host";
+
+/// Largest message accepted from the browser
+const MAX_MESSAGE: usize = 1024 * 1024;
+
+/// Request the host answers itself rather than forwarding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Query {
+    /// Latest contents of status.json
+    Status,
+}
+
+/// Answer to every request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+pub struct Reply {
+    pub ok: bool,
+    /// status.json, for a "status" query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Value>,
+    /// Why the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Reply {
+    fn ok(status: Option<Value>) -> Self {
+        Self {
+            ok: true,
+            status,
+            error: None,
+        }
+    }
+
+    fn error(text: &str) -> Self {
+        Self {
+            ok: false,
+            status: None,
+            error: Some(text.to_string()),
+        }
+    }
+}
+
+/// Browser family; each has its own manifest format and location
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    /// Chrome and other Chromium browsers
+    Chrome,
+    Firefox,
+}
+
+impl Browser {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "chrome" => Some(Browser::Chrome),
+            "firefox" => Some(Browser::Firefox),
+            _ => None,
+        }
+    }
+}
+
+/// What the command line asks of the native messaging code
+#[derive(Debug, Clone, PartialEq)]
+pub enum Launch {
+    /// Started by the browser: serve messages on stdin/stdout
+    Host,
+    /// Write the host manifest for `browser`, allowing `extension_id`
+    Install {
+        browser: Browser,
+        extension_id: String,
+    },
+    /// INSTALL_ARG with missing or unknown arguments
+    Usage,
+}
+
+/// Native messaging launch requested on the command line, if any
+pub fn from_args() -> Option<Launch> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(pos) = args.iter().position(|a| a == INSTALL_ARG) {
+        let browser = args.get(pos + 1).and_then(|b| Browser::parse(b));
+        let extension_id = args.get(pos + 2).filter(|id| !id.trim().is_empty());
+        return Some(match (browser, extension_id) {
+            (Some(browser), Some(id)) => Launch::Install {
+                browser,
+                extension_id: id.trim().to_string(),
+            },
+            _ => Launch::Usage,
+        });
+    }
+    // Chrome passes the caller's origin, Firefox the manifest path
+    let manifest_name = format!("{}.json", HOST_NAME);
+    args.iter()
+        .any(|a| a.starts_with("chrome-extension://") || a.ends_with(&manifest_name))
+        .then_some(Launch::Host)
+}
+
+/// Help text for Launch::Usage
+pub fn usage() -> String {
+    format!(
+        "usage: savemyeyes {} <chrome|firefox> <extension-id>",
+        INSTALL_ARG
+    )
+}
+
+/// Host manifest for `browser` pointing at `exe`
+pub fn manifest(browser: Browser, exe: &Path, extension_id: &str) -> Value {
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": "SaveMyEyes screen dimmer",
+        "path": exe.display().to_string(),
+        "type": "stdio",
+    });
+    match browser {
+        Browser::Chrome => {
+            manifest["allowed_origins"] = json!([format!("chrome-extension://{}/", extension_id)]);
+        }
+        Browser::Firefox => manifest["allowed_extensions"] = json!([extension_id]),
+    }
+    manifest
+}
+
+/// Write the manifest for the current executable into `dir`. Returns its path.
+pub fn write_manifest(browser: Browser, dir: &Path, extension_id: &str) -> io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let text = serde_json::to_string_pretty(&manifest(browser, &exe, extension_id))?;
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", HOST_NAME));
+    fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Read one framed message. None once the browser closes the pipe; a frame
+/// that isn't JSON reads as Null so it still gets an error reply.
+pub fn read_message(input: &mut impl Read) -> io::Result<Option<Value>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_MESSAGE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "message of {} bytes is over the {} byte limit",
+                len, MAX_MESSAGE
+            ),
+        ));
+    }
+    let mut data = vec![0u8; len];
+    input.read_exact(&mut data)?;
+    Ok(Some(serde_json::from_slice(&data).unwrap_or(Value::Null)))
+}
+
+/// Write one framed message
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let data = serde_json::to_vec(message)?;
+    output.write_all(&(data.len() as u32).to_ne_bytes())?;
+    output.write_all(&data)?;
+    output.flush()
+}
+
+/// Serve the browser until it closes stdin. `forward` hands a command to the
+/// running app and returns false if there is none. Returns the exit code.
+pub fn serve(forward: impl Fn(&Command) -> bool) -> i32 {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => return 0,
+            Err(e) => {
+                eprintln!("[native-host] {}", e);
+                return 1;
+            }
+        };
+        let reply = serde_json::to_value(handle(message, &forward)).unwrap_or_default();
+        if let Err(e) = write_message(&mut output, &reply) {
+            eprintln!("[native-host] {}", e);
+            return 1;
+        }
+    }
+}
+
+fn handle(message: Value, forward: &impl Fn(&Command) -> bool) -> Reply {
+    if let Ok(Query::Status) = serde_json::from_value(message.clone()) {
+        return match status_file::read() {
+            Some(status) => Reply::ok(Some(status)),
+            None => Reply::error("no status yet"),
+        };
+    }
+    let Some(command) = Command::from_value(message) else {
+        return Reply::error("unknown message");
+    };
+    if forward(&command) {
+        Reply::ok(None)
+    } else {
+        Reply::error("SaveMyEyes is not running")
+    }
+}
@@ -0,0 +1,295 @@
+// Network access (feature "updater")
+//
+// The only module that talks to the network: plain HTTP GETs for the update
+// check and the installer download. What to fetch and what the answer means
+// stays in updater.rs, so the policy there builds and runs without a
+// network stack.
+//
+// No request may hang or retry forever. Every request has connect and read
+// timeouts, and one that fails in a way that may pass (no connection, a
+// timeout, a 5xx or 429 answer, a body cut short) is tried up to `ATTEMPTS`
+// times, waiting `BACKOFF`, then twice that, each plus up to half again at
+// random so clients don't retry in step. Quitting cuts the waits short.
+//
+// Each endpoint (host) has a circuit breaker. After `TRIP_AFTER` requests
+// in a row failed that way, background requests to it are skipped for
+// `OPEN_SECS`, then one is let through again. Requests the user asked for
+// (`Ask::User`) go through an open breaker, and their result counts like
+// any other. The breakers are kept in net.json next to config.json, so a
+// restart doesn't start the retries over, and `report` lists them for the
+// diagnostics report.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::config_path;
+use crate::shutdown;
+use crate::updater::now_secs;
+
+const USER_AGENT: &str = "SaveMyEyes-Updater";
+
+/// Longest wait for a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest wait for the next bytes of an answer
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Tries per request
+const ATTEMPTS: u32 = 3;
+/// Wait before the second try, doubled before each one after it
+const BACKOFF: Duration = Duration::from_secs(2);
+/// Requests failed in a row that open an endpoint's breaker
+const TRIP_AFTER: u32 = 3;
+/// How long an open breaker skips background requests
+const OPEN_SECS: u64 = 24 * 60 * 60;
+
+/// Why a GET produced no body
+#[derive(Debug)]
+pub enum Failure {
+    /// No response (offline, DNS, refused) or an HTTP error status
+    Request(String),
+    /// A response whose body couldn't be read
+    Body(String),
+    /// Not sent: the endpoint's breaker is open until this Unix time
+    Skipped(u64),
+}
+
+/// Who a request is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ask {
+    /// Background work (the startup update check), skipped while the
+    /// endpoint's breaker is open
+    Background,
+    /// The user ("Check for Updates", an update download), always sent
+    User,
+}
+
+/// Failures of one endpoint (net.json)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Breaker {
+    /// Requests failed in a row
+    pub failures: u32,
+    /// Background requests are skipped until this Unix time (seconds)
+    pub open_until: Option<u64>,
+    /// What the last failed request said
+    pub last_error: String,
+}
+
+/// Breakers by endpoint, read from net.json on first use
+static BREAKERS: Mutex<Option<BTreeMap<String, Breaker>>> = Mutex::new(None);
+
+/// GET `url` and return the body as text.
+pub fn get_text(url: &str, accept: &str, ask: Ask) -> Result<String, Failure> {
+    request(url, Some(accept), ask, |response| {
+        response.into_string().map_err(|e| e.to_string())
+    })
+}
+
+/// GET `url` into the file at `path`, replacing it.
+pub fn download(url: &str, path: &Path, ask: Ask) -> Result<(), String> {
+    let result = request(url, None, ask, |response| {
+        // Created again for every try, so a retry starts from an empty file
+        let mut file =
+            fs::File::create(path).map_err(|e| format!("Failed to create temp file: {}", e))?;
+        std::io::copy(&mut response.into_reader(), &mut file)
+            .map_err(|e| format!("Failed to write update: {}", e))?;
+        Ok(())
+    });
+    result.map_err(|failure| match failure {
+        Failure::Request(e) => format!("Download failed: {}", e),
+        Failure::Body(e) => e,
+        Failure::Skipped(until) => format!("Download skipped until {}", local_time(until)),
+    })
+}
+
+/// GET `url` with the timeouts, retries and breaker described above, and
+/// hand the response to `read`
+fn request<T>(
+    url: &str,
+    accept: Option<&str>,
+    ask: Ask,
+    read: impl Fn(ureq::Response) -> Result<T, String>,
+) -> Result<T, Failure> {
+    let endpoint = endpoint(url);
+    if ask == Ask::Background {
+        if let Some(until) = open_until(&endpoint) {
+            eprintln!(
+                "[net] {} skipped, failing until {}",
+                endpoint,
+                local_time(until)
+            );
+            return Err(Failure::Skipped(until));
+        }
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build();
+    let mut attempt = 1;
+    loop {
+        let mut get = agent.get(url);
+        if let Some(accept) = accept {
+            get = get.set("Accept", accept);
+        }
+        let failure = match get.call() {
+            Ok(response) => match read(response) {
+                Ok(value) => {
+                    record(&endpoint, None);
+                    return Ok(value);
+                }
+                Err(e) => Failure::Body(e),
+            },
+            // The endpoint answered; asking again won't change the answer
+            Err(ureq::Error::Status(code, response)) if code != 429 && code < 500 => {
+                record(&endpoint, None);
+                return Err(Failure::Request(
+                    ureq::Error::Status(code, response).to_string(),
+                ));
+            }
+            Err(e) => Failure::Request(e.to_string()),
+        };
+        eprintln!(
+            "[net] {} try {}/{} failed: {:?}",
+            endpoint, attempt, ATTEMPTS, failure
+        );
+        if attempt == ATTEMPTS || !shutdown::sleep(backoff(attempt)) {
+            record(&endpoint, Some(&failure));
+            return Err(failure);
+        }
+        attempt += 1;
+    }
+}
+
+/// Wait after try `attempt` failed
+fn backoff(attempt: u32) -> Duration {
+    // Enough randomness for spreading retries out
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let jitter = (nanos % 1000) as f64 / 2000.0;
+    (BACKOFF * 2u32.pow(attempt - 1)).mul_f64(1.0 + jitter)
+}
+
+/// Host (and port) of `url`: what one breaker covers
+fn endpoint(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#'])
+        .next()
+        .unwrap_or(rest)
+        .to_ascii_lowercase()
+}
+
+/// When the breaker of `endpoint` closes again, if it is open
+fn open_until(endpoint: &str) -> Option<u64> {
+    let now = now_secs();
+    with_breakers(|breakers| {
+        breakers
+            .get(endpoint)
+            .and_then(|breaker| breaker.open_until)
+            .filter(|&until| now < until)
+    })
+}
+
+/// Count a request to `endpoint` that succeeded (None) or failed
+fn record(endpoint: &str, failure: Option<&Failure>) {
+    with_breakers(|breakers| {
+        let Some(failure) = failure else {
+            if breakers.remove(endpoint).is_some() {
+                save(breakers);
+            }
+            return;
+        };
+        let last_error = match failure {
+            Failure::Request(e) | Failure::Body(e) => e.clone(),
+            // Nothing was sent
+            Failure::Skipped(_) => return,
+        };
+        let breaker = breakers.entry(endpoint.to_string()).or_default();
+        breaker.failures += 1;
+        breaker.last_error = last_error;
+        if breaker.failures >= TRIP_AFTER {
+            let until = now_secs() + OPEN_SECS;
+            breaker.open_until = Some(until);
+            eprintln!(
+                "[net] {} failed {} times in a row, skipping it until {}",
+                endpoint,
+                breaker.failures,
+                local_time(until)
+            );
+        }
+        save(breakers);
+    })
+}
+
+/// Diagnostics report section on endpoints whose last requests failed
+/// (empty when all is well)
+pub fn report() -> String {
+    let now = now_secs();
+    with_breakers(|breakers| {
+        let mut out = String::new();
+        for (endpoint, breaker) in breakers.iter() {
+            out.push_str(&format!(
+                "  {}: {} failed request(s) in a row",
+                endpoint, breaker.failures
+            ));
+            match breaker.open_until.filter(|&until| now < until) {
+                Some(until) => out.push_str(&format!(
+                    ", background requests skipped until {}\n",
+                    local_time(until)
+                )),
+                None => out.push('\n'),
+            }
+            out.push_str(&format!("     last: {}\n", breaker.last_error));
+        }
+        if out.is_empty() {
+            out
+        } else {
+            format!("\nNetwork:\n{}", out)
+        }
+    })
+}
+
+fn with_breakers<R>(f: impl FnOnce(&mut BTreeMap<String, Breaker>) -> R) -> R {
+    let mut breakers = BREAKERS.lock().unwrap();
+    f(breakers.get_or_insert_with(load))
+}
+
+fn breakers_path() -> PathBuf {
+    config_path().with_file_name("net.json")
+}
+
+fn load() -> BTreeMap<String, Breaker> {
+    fs::read_to_string(breakers_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save(breakers: &BTreeMap<String, Breaker>) {
+    let path = breakers_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let data = serde_json::to_string_pretty(breakers).unwrap_or_default();
+    if let Err(e) = fs::write(&path, data) {
+        eprintln!("[net] could not write {}: {}", path.display(), e);
+    }
+}
+
+/// Unix time `secs` as local "YYYY-MM-DD HH:MM"
+fn local_time(secs: u64) -> String {
+    DateTime::from_timestamp(secs as i64, 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
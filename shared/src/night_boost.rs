@@ -0,0 +1,92 @@
+// Night boost: dim a little more late at night (platform-agnostic)
+//
+// Not a schedule: the user keeps choosing the level by hand, and between
+// `start` and `end` (local wall-clock time, "HH:MM", may cross midnight)
+// `boost` is added on top of whatever they chose. Like pen pause it is
+// applied on top of the configured levels and never saved, so in the morning
+// the manual level (and last_opacity) is exactly what it was.
+
+use chrono::{Local, Timelike};
+use savemyeyes_core::clock;
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::{from_steps, percent_label, to_steps, MAX_OPACITY};
+use crate::locale;
+
+/// Night boost rule. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NightBoost {
+    pub enabled: bool,
+    /// Added to every dimmed level while the window is open
+    pub boost: f32,
+    /// Local time the boost starts ("HH:MM")
+    pub start: String,
+    /// Local time the boost ends ("HH:MM", before `start` = next morning)
+    pub end: String,
+}
+
+impl Default for NightBoost {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            boost: 0.1,
+            start: "23:00".to_string(),
+            end: "06:00".to_string(),
+        }
+    }
+}
+
+pub use savemyeyes_core::clock::parse_time;
+
+impl NightBoost {
+    /// Whether the boost applies at `minute` (minutes since local midnight)
+    pub fn is_active_at(&self, minute: u32) -> bool {
+        if !self.enabled || self.boost <= 0.0 {
+            return false;
+        }
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        clock::in_window(start, end, minute)
+    }
+
+    /// Boost in effect right now (local time), or None
+    pub fn current(&self) -> Option<f32> {
+        let now = Local::now();
+        self.is_active_at(now.hour() * 60 + now.minute())
+            .then(|| self.boost.clamp(0.0, MAX_OPACITY))
+    }
+
+    /// Summary for the settings UI
+    pub fn summary(&self) -> String {
+        format!(
+            "+{} from {} to {}",
+            percent_label(self.boost),
+            locale::time(&self.start),
+            locale::time(&self.end)
+        )
+    }
+}
+
+/// `level` with `boost` added. Undimmed stays undimmed.
+pub fn boosted(level: f32, boost: Option<f32>) -> f32 {
+    match boost {
+        Some(boost) if level > 0.0 => (level + boost).clamp(0.0, MAX_OPACITY),
+        _ => level,
+    }
+}
+
+/// Level text for the UI: "45% (+10% night boost)" while boosted, else "35%"
+pub fn level_label(level: f32, boost: Option<f32>) -> String {
+    let applied = boosted(level, boost);
+    if applied > level {
+        format!(
+            "{} (+{} night boost)",
+            percent_label(applied),
+            percent_label(from_steps(to_steps(applied) - to_steps(level)))
+        )
+    } else {
+        percent_label(level)
+    }
+}
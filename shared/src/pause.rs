@@ -0,0 +1,107 @@
+// Timed pause (platform-agnostic)
+//
+// "Pause for 30 Minutes" lifts dimming for a while and brings it back by
+// itself. Like designer mode, the platform suppresses the overlay while
+// config keeps the levels, so hotkeys still adjust what comes back. Unlike
+// designer mode the pause isn't saved: a restart ends it. The platform
+// starts a timer for `start`'s duration and resumes when `take_expired`
+// says so; a newer pause or an early resume makes older timers no-ops. The
+// deadline is kept in `time_change::monotonic_ms`, so setting the clock or
+// changing the timezone neither stretches nor cuts a pause.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use savemyeyes_core::pause::Pause;
+
+use crate::status_file;
+use crate::time_change::monotonic_ms;
+
+/// Pause lengths offered in the tray menu, in minutes
+pub const DURATIONS: [u64; 3] = [15, 30, 60];
+
+/// Deadline of the running pause in `monotonic_ms`
+static PAUSE: Mutex<Pause<u64>> = Mutex::new(Pause::new());
+
+/// Pause for `minutes`, replacing any running pause. Returns how long the
+/// platform timer should wait.
+pub fn start(minutes: u64) -> Duration {
+    start_at(monotonic_ms(), minutes)
+}
+
+pub(crate) fn start_at(now_ms: u64, minutes: u64) -> Duration {
+    let duration = Duration::from_secs(minutes * 60);
+    PAUSE
+        .lock()
+        .unwrap()
+        .start(now_ms + duration.as_millis() as u64);
+    status_file::pause_changed();
+    duration
+}
+
+/// End the pause early. Returns false if there was none.
+pub fn end() -> bool {
+    let ended = PAUSE.lock().unwrap().end();
+    if ended {
+        status_file::pause_changed();
+    }
+    ended
+}
+
+pub fn is_active() -> bool {
+    PAUSE.lock().unwrap().is_active()
+}
+
+/// Time left in the running pause
+pub fn remaining() -> Option<Duration> {
+    remaining_at(monotonic_ms())
+}
+
+pub(crate) fn remaining_at(now_ms: u64) -> Option<Duration> {
+    let until = PAUSE.lock().unwrap().until()?;
+    Some(Duration::from_millis(until.saturating_sub(now_ms)))
+}
+
+/// Called by the platform timer: true (and the pause is over) if it has run
+/// out, false if it already ended or was extended meanwhile.
+pub fn take_expired() -> bool {
+    take_expired_at(monotonic_ms())
+}
+
+pub(crate) fn take_expired_at(now_ms: u64) -> bool {
+    let expired = PAUSE.lock().unwrap().take_expired(now_ms);
+    if expired {
+        status_file::pause_changed();
+    }
+    expired
+}
+
+/// Unix time the running pause ends, for status.json
+pub fn until_unix() -> Option<u64> {
+    let left = remaining()?;
+    Some(crate::updater::now_secs() + left.as_secs())
+}
+
+/// Menu label for a pause of `minutes`
+pub fn duration_label(minutes: u64) -> String {
+    match minutes {
+        60 => "Pause for 1 Hour".to_string(),
+        m if m % 60 == 0 => format!("Pause for {} Hours", m / 60),
+        m => format!("Pause for {} Minutes", m),
+    }
+}
+
+/// Menu label that ends the running pause, with the time left
+pub fn resume_label() -> String {
+    let minutes = remaining().map_or(0, |left| left.as_secs().div_ceil(60));
+    format!("Resume Dimming ({} min left)", minutes)
+}
+
+/// Toast/HUD text after starting a pause of `minutes`
+pub fn started_message(minutes: u64) -> String {
+    match minutes {
+        60 => "Dimming paused for 1 hour".to_string(),
+        m if m % 60 == 0 => format!("Dimming paused for {} hours", m / 60),
+        m => format!("Dimming paused for {} minutes", m),
+    }
+}
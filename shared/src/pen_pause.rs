@@ -0,0 +1,60 @@
+// Pause dimming while a pen is drawing (platform-agnostic)
+//
+// Artists want true brightness and color while the pen is on the tablet.
+// Each platform timestamps pen input (pen-tagged mouse input on Windows,
+// tablet events on macOS) and asks `PenPause::cap` how far dimming may go
+// right now. The cap lifts `idle_ms` after the last pen input. It is applied
+// on top of the configured levels and never saved, so turning it off or
+// putting the pen down restores exactly what was there.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::dimming::percent_label;
+
+/// Pen activity rule. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PenPause {
+    pub enabled: bool,
+    /// Highest level while drawing (0.0 = pause dimming entirely)
+    pub level: f32,
+    /// How long after the last pen input dimming comes back
+    pub idle_ms: u64,
+}
+
+impl Default for PenPause {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            level: 0.0,
+            idle_ms: 3000,
+        }
+    }
+}
+
+impl PenPause {
+    /// Level cap in effect at `now`, or None when dimming is unrestricted
+    pub fn cap(&self, last_pen_input: Option<Instant>, now: Instant) -> Option<f32> {
+        if !self.enabled {
+            return None;
+        }
+        let last = last_pen_input?;
+        (now.saturating_duration_since(last) < Duration::from_millis(self.idle_ms))
+            .then(|| self.level.clamp(0.0, crate::dimming::MAX_OPACITY))
+    }
+
+    /// One-line summary for the settings UI
+    pub fn summary(&self) -> String {
+        let idle = self.idle_ms as f64 / 1000.0;
+        if self.level <= 0.0 {
+            format!("Pen input pauses dimming until {}s idle", idle)
+        } else {
+            format!(
+                "Pen input limits dimming to {} until {}s idle",
+                percent_label(self.level),
+                idle
+            )
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Presentation mode (platform-agnostic)
+//
+// One click before a meeting: dimming is lifted and nothing pops up on its
+// own (HUD, tray notifications, update prompts) for the chosen time or until
+// it is turned off, then everything comes back by itself. It works like the
+// built-in "Presentation" profile without touching config: the platform
+// suppresses the overlay as for a timed pause (see pause.rs), and every
+// subsystem that shows something unasked checks `is_active` first. Like a
+// pause it isn't saved; a restart ends it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Lengths offered in the tray menu, in minutes (None = until turned off)
+pub const DURATIONS: [Option<u64>; 4] = [Some(30), Some(60), Some(120), None];
+
+/// Some(end) while active; the end is None for "until I turn it off"
+static STATE: Mutex<Option<Option<Instant>>> = Mutex::new(None);
+
+/// Start presentation mode for `minutes` (None = until ended), replacing
+/// any running one. Returns how long the platform timer should wait.
+pub fn start(minutes: Option<u64>) -> Option<Duration> {
+    let duration = minutes.map(|m| Duration::from_secs(m * 60));
+    *STATE.lock().unwrap() = Some(duration.map(|d| Instant::now() + d));
+    duration
+}
+
+/// End presentation mode early. Returns false if it wasn't on.
+pub fn end() -> bool {
+    STATE.lock().unwrap().take().is_some()
+}
+
+/// True while presentation mode is on: stay quiet
+pub fn is_active() -> bool {
+    STATE.lock().unwrap().is_some()
+}
+
+/// Time left, None if off or running until turned off
+pub fn remaining() -> Option<Duration> {
+    let until = (*STATE.lock().unwrap())??;
+    Some(until.saturating_duration_since(Instant::now()))
+}
+
+/// Called by the platform timer: true (and presentation mode is over) if it
+/// has run out, false if it already ended or was restarted meanwhile.
+pub fn take_expired() -> bool {
+    let mut state = STATE.lock().unwrap();
+    match *state {
+        Some(Some(at)) if at <= Instant::now() => {
+            *state = None;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Submenu label for a presentation of `minutes`
+pub fn duration_label(minutes: Option<u64>) -> String {
+    match minutes {
+        None => "Until I Turn It Off".to_string(),
+        Some(60) => "1 Hour".to_string(),
+        Some(m) if m % 60 == 0 => format!("{} Hours", m / 60),
+        Some(m) => format!("{} Minutes", m),
+    }
+}
+
+/// Menu label that ends presentation mode, with the time left
+pub fn end_label() -> String {
+    match remaining() {
+        Some(left) => format!(
+            "End Presentation Mode ({} min left)",
+            left.as_secs().div_ceil(60)
+        ),
+        None => "End Presentation Mode".to_string(),
+    }
+}
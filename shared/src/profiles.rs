@@ -0,0 +1,237 @@
+// Named dimming profiles and the automations that switch between them
+// (platform-agnostic)
+//
+// A profile is a saved dimming state: on/off, global level and optional
+// per-monitor / per-display levels. Applying one rewrites the matching
+// config fields and records it as the active profile; the platform layer
+// then saves config and pushes the new levels to the screen as usual.
+//
+// Theme automation: the platform watches the OS light/dark setting and calls
+// `apply_for_theme` on every change.
+//
+// Built-in profiles (builtin_profiles.rs) are found and listed after the
+// saved ones, so menus and automations can use them without any config.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::builtin_profiles;
+use crate::color_filter::ColorFilter;
+use crate::config::AppConfig;
+use crate::explain::{self, Cause};
+use crate::monitor_id::MonitorId;
+
+/// A saved dimming state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Dimming on when the profile is applied
+    pub enabled: bool,
+    /// Global level (0.0–0.9)
+    pub opacity: f32,
+    /// Per-monitor levels keyed by monitor id (monitor_id.rs)
+    #[serde(default)]
+    pub monitor_opacity: HashMap<MonitorId, f32>,
+    /// Older per-monitor levels keyed by monitor index (Windows) and display
+    /// name (macOS), moved to `monitor_opacity` once the monitor is seen
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_monitor_opacity: HashMap<u32, f32>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_display_opacity: HashMap<String, f32>,
+    /// OS color filter switched along with the profile, if config
+    /// `color_filters` is on (Windows). None leaves the filter alone.
+    #[serde(default)]
+    pub color_filter: Option<ColorFilter>,
+}
+
+impl Profile {
+    /// Snapshot the current dimming state of `config` under `name`
+    pub fn capture(name: &str, config: &AppConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            enabled: config.is_enabled,
+            opacity: if config.is_enabled {
+                config.opacity
+            } else {
+                config.last_opacity
+            },
+            monitor_opacity: config.monitor_opacity.clone(),
+            per_monitor_opacity: HashMap::new(),
+            per_display_opacity: HashMap::new(),
+            color_filter: None,
+        }
+    }
+}
+
+/// Follow the OS light/dark setting with a profile for each
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeAutomation {
+    pub enabled: bool,
+    /// Profile applied when the OS switches to dark mode
+    #[serde(default)]
+    pub dark_profile: Option<String>,
+    /// Profile applied when the OS switches to light mode
+    #[serde(default)]
+    pub light_profile: Option<String>,
+    /// OS theme seen when a profile was last applied (true = dark), so a
+    /// restart only re-applies after a real change
+    #[serde(default)]
+    pub last_dark: Option<bool>,
+}
+
+impl ThemeAutomation {
+    /// Profile name configured for the given OS theme
+    pub fn profile_for(&self, dark: bool) -> Option<&str> {
+        if dark {
+            self.dark_profile.as_deref()
+        } else {
+            self.light_profile.as_deref()
+        }
+    }
+}
+
+/// Look up a profile by name (case-insensitive), saved profiles first, then
+/// the built-in ones
+pub fn find<'a>(config: &'a AppConfig, name: &str) -> Option<&'a Profile> {
+    config
+        .profiles
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .or_else(|| builtin_profiles::find(name))
+}
+
+/// Profiles offered in menus: the saved ones, then the built-ins they don't
+/// hide
+pub fn listed(config: &AppConfig) -> Vec<&Profile> {
+    let saved = |name: &str| {
+        config
+            .profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name))
+    };
+    let builtins = builtin_profiles::profiles()
+        .iter()
+        .filter(|p| !saved(&p.name));
+    config.profiles.iter().chain(builtins).collect()
+}
+
+/// Whether `name` refers to a built-in profile (not hidden by a saved one)
+pub fn is_builtin(config: &AppConfig, name: &str) -> bool {
+    builtin_profiles::find(name).is_some()
+        && !config
+            .profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Apply the named profile to `config`. Returns false if there is no such
+/// profile. Per-monitor levels the profile doesn't mention are set to its
+/// global level so the whole screen follows the profile; a profile that
+/// turns dimming off leaves them alone.
+pub fn apply(config: &mut AppConfig, name: &str) -> bool {
+    let Some(profile) = find(config, name).cloned() else {
+        return false;
+    };
+
+    let opacity = profile.opacity.clamp(0.0, 0.9);
+    if profile.enabled {
+        config.is_enabled = true;
+        config.opacity = opacity;
+        if opacity > 0.0 {
+            config.last_opacity = opacity;
+        }
+        for (id, level) in config.monitor_opacity.iter_mut() {
+            *level = profile.monitor_opacity.get(id).copied().unwrap_or(opacity);
+        }
+        for (id, level) in &profile.monitor_opacity {
+            config.monitor_opacity.entry(id.clone()).or_insert(*level);
+        }
+    } else {
+        if config.is_enabled && config.opacity > 0.0 {
+            config.last_opacity = config.opacity;
+        }
+        config.is_enabled = false;
+        config.opacity = 0.0;
+    }
+
+    explain::note(Cause::Profile {
+        name: profile.name.clone(),
+    });
+    config.active_profile = Some(profile.name);
+    true
+}
+
+/// Apply the profile theme automation picked for the OS theme, if enabled
+/// and configured. Returns the applied profile's name.
+pub fn apply_for_theme(config: &mut AppConfig, dark: bool) -> Option<String> {
+    if !config.theme_automation.enabled {
+        return None;
+    }
+    config.theme_automation.last_dark = Some(dark);
+    let name = config.theme_automation.profile_for(dark)?.to_string();
+    if !apply(config, &name) {
+        return None;
+    }
+    explain::note(Cause::ThemeAutomation { name: name.clone() });
+    Some(name)
+}
+
+/// Like `apply_for_theme`, but only if the OS theme differs from the one
+/// last acted on (startup: keep manual tweaks made since the last switch).
+pub fn apply_if_theme_changed(config: &mut AppConfig, dark: bool) -> Option<String> {
+    if config.theme_automation.last_dark == Some(dark) {
+        return None;
+    }
+    apply_for_theme(config, dark)
+}
+
+/// Profile names offered for the automation pickers: None, then every
+/// listed profile
+pub fn choices(config: &AppConfig) -> Vec<Option<String>> {
+    std::iter::once(None)
+        .chain(listed(config).into_iter().map(|p| Some(p.name.clone())))
+        .collect()
+}
+
+/// The choice after `current` in `choices`, wrapping around (for click-to-cycle pickers)
+pub fn next_choice(config: &AppConfig, current: Option<&str>) -> Option<String> {
+    let choices = choices(config);
+    let pos = choices
+        .iter()
+        .position(|c| c.as_deref() == current)
+        .unwrap_or(0);
+    choices[(pos + 1) % choices.len()].clone()
+}
+
+/// Turn theme automation on. With no profiles mapped yet, create a "Dark"
+/// profile from the current dimming state and an undimmed "Light" one, so
+/// the automation does something sensible straight away.
+pub fn enable_theme_automation(config: &mut AppConfig) {
+    config.theme_automation.enabled = true;
+    let automation = &config.theme_automation;
+    if automation.dark_profile.is_some() || automation.light_profile.is_some() {
+        return;
+    }
+
+    if find(config, "Dark").is_none() {
+        let mut dark = Profile::capture("Dark", config);
+        dark.enabled = true;
+        if dark.opacity <= 0.0 {
+            dark.opacity = config.last_opacity.max(0.3);
+        }
+        config.profiles.push(dark);
+    }
+    if find(config, "Light").is_none() {
+        config.profiles.push(Profile {
+            name: "Light".into(),
+            enabled: false,
+            opacity: 0.0,
+            monitor_opacity: HashMap::new(),
+            per_monitor_opacity: HashMap::new(),
+            per_display_opacity: HashMap::new(),
+            color_filter: None,
+        });
+    }
+    config.theme_automation.dark_profile = Some("Dark".into());
+    config.theme_automation.light_profile = Some("Light".into());
+}
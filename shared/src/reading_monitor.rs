@@ -0,0 +1,68 @@
+// Reading monitors (platform-agnostic)
+//
+// A monitor turned to portrait is mostly read from: documents, code, long
+// pages of text, usually white. With `reading_monitor.enabled`, every
+// monitor taller than it is wide counts as a reading monitor and is dimmed
+// `opacity_offset` more than its level, so the big white page doesn't glare
+// next to the landscape screens. Orientation is checked again whenever the
+// display layout changes, so rotating a monitor switches it at once.
+// `monitors` overrides the detection per monitor id: true makes any monitor
+// a reading monitor, false leaves a portrait one alone. Like the night
+// boost, the offset is applied on top of the configured level and never
+// saved into it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::dimming::{percent_label, MAX_OPACITY};
+use crate::monitor_id::MonitorId;
+
+/// Reading monitor preset. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingMonitor {
+    pub enabled: bool,
+    /// Added to the level of a reading monitor while it is dimmed
+    pub opacity_offset: f32,
+    /// Reading monitor or not, per monitor id, instead of the orientation
+    pub monitors: HashMap<MonitorId, bool>,
+}
+
+impl Default for ReadingMonitor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opacity_offset: 0.1,
+            monitors: HashMap::new(),
+        }
+    }
+}
+
+impl ReadingMonitor {
+    /// Whether the monitor `id` is a reading monitor, given its orientation
+    pub fn applies(&self, id: &MonitorId, portrait: bool) -> bool {
+        self.enabled && self.monitors.get(id).copied().unwrap_or(portrait)
+    }
+
+    /// `level` for a reading monitor. Undimmed stays undimmed.
+    pub fn adjusted(&self, level: f32) -> f32 {
+        if level > 0.0 {
+            (level + self.opacity_offset).clamp(0.0, MAX_OPACITY)
+        } else {
+            level
+        }
+    }
+
+    /// One-line summary for the settings UI
+    pub fn summary(&self) -> String {
+        let base = format!(
+            "Portrait monitors dimmed {} more",
+            percent_label(self.opacity_offset)
+        );
+        match self.monitors.len() {
+            0 => base,
+            1 => format!("{} (1 monitor set by hand)", base),
+            n => format!("{} ({} monitors set by hand)", base, n),
+        }
+    }
+}
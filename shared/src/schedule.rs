@@ -0,0 +1,711 @@
+// Time-of-day scheduling core (platform-agnostic, no threads, no I/O)
+//
+// A schedule is a list of wall-clock entries ("at 21:00 go to 40%"), each
+// repeating on the days of the week it lists, so weekdays and weekends can
+// follow different rhythms. An entry stays in effect until the next one
+// fires, across midnight and across days without entries of their own (a
+// Friday 23:00 entry holds until Monday's first one if the weekend has
+// none). Every evaluation resolves the entries to absolute instants in the
+// timezone of the supplied `now`, so:
+//   • DST gaps/overlaps are resolved per day (a 02:30 entry on a spring-forward
+//     day fires at the first valid instant after the gap; on a fall-back day
+//     it fires at the first of the two occurrences)
+//   • ramps are measured in real elapsed time, so a 30-minute ramp spanning
+//     the clock change still takes 30 minutes
+//   • a system timezone change mid-session is picked up on the next call,
+//     because nothing is cached between evaluations
+//   • a device that sleeps through a transition catches up on wake: the
+//     result depends only on `now`, and `Scheduler::tick` reports that
+//     transitions were crossed while it wasn't looking
+//   • a clock set by hand or by NTP is handled like a wake (`Tick::resumed`):
+//     `Scheduler::tick_with` compares the clock's `generation` (by default
+//     `time_change::generation`) with the one it last saw, and runtimes tick
+//     again as soon as it moves instead of waiting for their next poll
+//
+// Time is injected through the `Clock` trait so the platform runtimes use
+// `SystemClock` while anything else can drive the core with fixed instants.
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+use savemyeyes_core::clock;
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::WEEK;
+use crate::time_change;
+
+/// How far evaluation looks back for the entry in effect and ahead for the
+/// next one: a whole week, so days without entries are bridged
+const SPAN_DAYS: i64 = 7;
+
+/// One wall-clock point of a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Local time of day the entry starts at
+    pub time: NaiveTime,
+    /// Target opacity (0.0 = dimmer off)
+    pub opacity: f32,
+    /// Days of the week the entry fires on (empty = never)
+    #[serde(default = "every_day")]
+    pub days: Vec<Weekday>,
+}
+
+fn every_day() -> Vec<Weekday> {
+    WEEK.to_vec()
+}
+
+impl ScheduleEntry {
+    /// Entry firing every day of the week
+    pub fn daily(time: NaiveTime, opacity: f32) -> Self {
+        Self {
+            time,
+            opacity,
+            days: every_day(),
+        }
+    }
+
+    pub fn fires_on(&self, day: Weekday) -> bool {
+        self.days.contains(&day)
+    }
+}
+
+/// A weekly repeating schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub entries: Vec<ScheduleEntry>,
+    /// Minutes over which opacity fades from the previous entry's value
+    /// to the new one, starting at the entry's time (0 = switch instantly)
+    #[serde(default)]
+    pub ramp_minutes: u32,
+}
+
+/// A schedule entry resolved to an absolute instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub at: DateTime<Utc>,
+    pub opacity: f32,
+}
+
+/// Result of evaluating a schedule at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    /// Opacity the schedule asks for right now
+    pub opacity: f32,
+    /// True while fading between two entries
+    pub ramping: bool,
+    /// Transition currently in effect (or ramping in)
+    pub active: Transition,
+    /// Next transition after `now`
+    pub next: Option<Transition>,
+}
+
+/// Source of the current time
+pub trait Clock {
+    type Tz: TimeZone;
+    fn now(&self) -> DateTime<Self::Tz>;
+    /// Moves whenever the clock is set or its timezone changes
+    fn generation(&self) -> u64 {
+        time_change::generation()
+    }
+}
+
+/// Wall clock in the system's local timezone (re-read on every call)
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    type Tz = Local;
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Resolve a local date + time to an instant, handling DST edges.
+///
+/// Times that fall in a spring-forward gap resolve to the first valid
+/// instant after the gap; ambiguous fall-back times resolve to the earlier
+/// occurrence so an entry fires once per day.
+pub fn resolve_local<Tz: TimeZone>(tz: &Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let naive = date.and_time(time);
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => {
+            // Walk forward minute by minute until we leave the gap
+            // (real-world gaps are at most a couple of hours).
+            for minutes in 1..=(3 * 60) {
+                let shifted = naive + Duration::minutes(minutes);
+                match tz.from_local_datetime(&shifted) {
+                    LocalResult::Single(dt) => return dt.with_timezone(&Utc),
+                    LocalResult::Ambiguous(earliest, _) => return earliest.with_timezone(&Utc),
+                    LocalResult::None => continue,
+                }
+            }
+            Utc.from_utc_datetime(&naive)
+        }
+    }
+}
+
+impl Schedule {
+    /// Entries that fire on `date` (by its day of the week)
+    pub fn entries_for(&self, date: NaiveDate) -> Vec<&ScheduleEntry> {
+        let day = date.weekday();
+        self.entries.iter().filter(|e| e.fires_on(day)).collect()
+    }
+
+    /// All transitions from `days_before` days before to a week after the
+    /// local date of `now`, sorted by instant.
+    pub fn transitions_around<Tz: TimeZone>(
+        &self,
+        now: &DateTime<Tz>,
+        days_before: i64,
+    ) -> Vec<Transition> {
+        let tz = now.timezone();
+        let today = now.date_naive();
+        let mut out = Vec::new();
+        for offset in -days_before..=SPAN_DAYS {
+            let date = today + Duration::days(offset);
+            for entry in self.entries_for(date) {
+                out.push(Transition {
+                    at: resolve_local(&tz, date, entry.time),
+                    opacity: entry.opacity.clamp(0.0, 1.0),
+                });
+            }
+        }
+        out.sort_by_key(|t| t.at);
+        out
+    }
+
+    /// Evaluate the schedule at `now`. None if no entry fires on any day.
+    pub fn evaluate<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<Evaluation> {
+        if self.entries.iter().all(|e| e.days.is_empty()) {
+            return None;
+        }
+        let now_utc = now.with_timezone(&Utc);
+        let transitions = self.transitions_around(now, SPAN_DAYS);
+        let ramp = Duration::minutes(self.ramp_minutes as i64);
+
+        // Opacity reached at each transition's start; a ramp that is
+        // interrupted by the next entry hands over its partial value.
+        let mut start_values = Vec::with_capacity(transitions.len());
+        let mut value = transitions.first()?.opacity;
+        for (i, t) in transitions.iter().enumerate() {
+            if i > 0 {
+                let prev = &transitions[i - 1];
+                value = ramp_value(start_values[i - 1], prev.opacity, prev.at, ramp, t.at);
+            }
+            start_values.push(value);
+        }
+
+        let index = transitions.iter().rposition(|t| t.at <= now_utc)?;
+        let active = transitions[index];
+        let opacity = ramp_value(start_values[index], active.opacity, active.at, ramp, now_utc);
+        Some(Evaluation {
+            opacity,
+            ramping: now_utc < active.at + ramp && (opacity - active.opacity).abs() > f32::EPSILON,
+            active,
+            next: transitions.get(index + 1).copied(),
+        })
+    }
+}
+
+/// Linear fade from `from` to `to` over `ramp`, starting at `start`.
+fn ramp_value(from: f32, to: f32, start: DateTime<Utc>, ramp: Duration, at: DateTime<Utc>) -> f32 {
+    let elapsed = (at - start).num_milliseconds();
+    clock::ramp(from, to, elapsed, ramp.num_milliseconds())
+}
+
+/// Output of one scheduler tick that the runtime should apply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    pub opacity: f32,
+    /// At least one transition started since the previous tick. Runtimes
+    /// use this to drop a manual override that was made before it.
+    pub crossed_transition: bool,
+    /// The previous tick was long ago (sleep/hibernate) or in the future
+    /// (clock moved backwards); the result is a catch-up, not a step.
+    pub resumed: bool,
+}
+
+/// Minimal state carried between evaluations by a runtime loop
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    last_tick: Option<DateTime<Utc>>,
+    last_opacity: Option<f32>,
+    /// Clock generation seen by the previous `tick_with`
+    generation: Option<u64>,
+}
+
+/// A gap between ticks longer than this is treated as a resume from sleep
+const RESUME_GAP_SECS: i64 = 120;
+
+/// Opacity changes smaller than this aren't worth re-applying
+const MIN_STEP: f32 = 0.005;
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget previous ticks so the next one always reports a result
+    /// (e.g. after the schedule was edited or re-enabled).
+    pub fn reset(&mut self) {
+        self.last_tick = None;
+        self.last_opacity = None;
+    }
+
+    /// Evaluate at `now` and return what to apply, or None when nothing
+    /// changed since the previous tick.
+    pub fn tick<Tz: TimeZone>(&mut self, schedule: &Schedule, now: &DateTime<Tz>) -> Option<Tick> {
+        self.step(schedule, now, false)
+    }
+
+    fn step<Tz: TimeZone>(
+        &mut self,
+        schedule: &Schedule,
+        now: &DateTime<Tz>,
+        clock_changed: bool,
+    ) -> Option<Tick> {
+        let eval = schedule.evaluate(now)?;
+        let now_utc = now.with_timezone(&Utc);
+
+        let (crossed_transition, resumed) = match self.last_tick {
+            None => (true, false),
+            Some(_) if clock_changed => (true, true),
+            Some(last) if last > now_utc => (true, true),
+            Some(last) => {
+                let crossed = schedule
+                    .transitions_around(now, SPAN_DAYS)
+                    .iter()
+                    .any(|t| t.at > last && t.at <= now_utc);
+                (crossed, (now_utc - last).num_seconds() > RESUME_GAP_SECS)
+            }
+        };
+        self.last_tick = Some(now_utc);
+
+        let changed = match self.last_opacity {
+            None => true,
+            Some(prev) => (prev - eval.opacity).abs() >= MIN_STEP,
+        };
+        if !changed && !crossed_transition {
+            return None;
+        }
+        self.last_opacity = Some(eval.opacity);
+        Some(Tick {
+            opacity: eval.opacity,
+            crossed_transition,
+            resumed,
+        })
+    }
+
+    /// Tick at the time read from `clock`. A clock that was set since the
+    /// previous call always reports a result, as a resume.
+    pub fn tick_with<C: Clock>(&mut self, schedule: &Schedule, clock: &C) -> Option<Tick> {
+        let generation = clock.generation();
+        let clock_changed = self.generation.is_some_and(|seen| seen != generation);
+        self.generation = Some(generation);
+        self.step(schedule, &clock.now(), clock_changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, MappedLocalTime, NaiveDateTime};
+    use std::cell::Cell;
+
+    /// Central European time with the 2026 switches: UTC+1, and UTC+2 from
+    /// 29 March 02:00 (clocks jump to 03:00) to 25 October 03:00 (clocks go
+    /// back to 02:00)
+    #[derive(Debug, Clone, Copy)]
+    struct Cet;
+
+    fn spring_forward() -> DateTime<Utc> {
+        utc(2026, 3, 29, 1, 0)
+    }
+
+    fn fall_back() -> DateTime<Utc> {
+        utc(2026, 10, 25, 1, 0)
+    }
+
+    fn hours(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &NaiveDateTime,
+        ) -> MappedLocalTime<FixedOffset> {
+            // Summer time first, so an overlap lists the earlier instant first
+            let fits: Vec<FixedOffset> = [hours(2), hours(1)]
+                .into_iter()
+                .filter(|offset| self.offset_from_utc_datetime(&(*local - *offset)) == *offset)
+                .collect();
+            match fits[..] {
+                [] => MappedLocalTime::None,
+                [offset] => MappedLocalTime::Single(offset),
+                [earlier, later] => MappedLocalTime::Ambiguous(earlier, later),
+                _ => unreachable!(),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let summer = spring_forward().naive_utc()..fall_back().naive_utc();
+            if summer.contains(utc) {
+                hours(2)
+            } else {
+                hours(1)
+            }
+        }
+    }
+
+    /// Wall clock the tests move by hand, in a zone they can change. It
+    /// counts its own changes, so `time_change::notify` in another test
+    /// doesn't reach it.
+    struct TestClock<Tz> {
+        now: Cell<DateTime<Utc>>,
+        tz: Cell<Tz>,
+        changes: Cell<u64>,
+    }
+
+    impl<Tz: TimeZone + Copy> TestClock<Tz> {
+        fn new(tz: Tz, now: DateTime<Utc>) -> Self {
+            Self {
+                now: Cell::new(now),
+                tz: Cell::new(tz),
+                changes: Cell::new(0),
+            }
+        }
+
+        /// Time passing (or passing asleep)
+        fn advance(&self, by: Duration) {
+            self.now.set(self.now.get() + by);
+        }
+
+        /// The clock set by hand or by NTP, forward or back
+        fn jump(&self, by: Duration) {
+            self.advance(by);
+            self.changes.set(self.changes.get() + 1);
+        }
+    }
+
+    impl<Tz: TimeZone + Copy> Clock for TestClock<Tz> {
+        type Tz = Tz;
+        fn now(&self) -> DateTime<Tz> {
+            self.now.get().with_timezone(&self.tz.get())
+        }
+        fn generation(&self) -> u64 {
+            self.changes.get()
+        }
+    }
+
+    /// A TestClock whose changes are announced through `time_change`
+    struct NotifiedClock(TestClock<Utc>);
+
+    impl Clock for NotifiedClock {
+        type Tz = Utc;
+        fn now(&self) -> DateTime<Utc> {
+            self.0.now()
+        }
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn daily(entries: &[((u32, u32), f32)], ramp_minutes: u32) -> Schedule {
+        Schedule {
+            entries: entries
+                .iter()
+                .map(|&((hour, minute), opacity)| ScheduleEntry::daily(time(hour, minute), opacity))
+                .collect(),
+            ramp_minutes,
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_zone_switches() {
+        let tz = Cet;
+        assert_eq!(spring_forward().with_timezone(&tz).time(), time(3, 0));
+        assert_eq!(
+            (spring_forward() - Duration::minutes(1))
+                .with_timezone(&tz)
+                .time(),
+            time(1, 59)
+        );
+        assert_eq!(fall_back().with_timezone(&tz).time(), time(2, 0));
+        assert_eq!(
+            (fall_back() - Duration::minutes(1))
+                .with_timezone(&tz)
+                .time(),
+            time(2, 59)
+        );
+    }
+
+    #[test]
+    fn spring_forward_gap_resolves_after_the_gap() {
+        let day = date(2026, 3, 29);
+        for in_gap in [time(2, 0), time(2, 30), time(2, 59)] {
+            assert_eq!(resolve_local(&Cet, day, in_gap), spring_forward());
+        }
+        assert_eq!(
+            resolve_local(&Cet, day, time(1, 59)),
+            utc(2026, 3, 29, 0, 59)
+        );
+        assert_eq!(resolve_local(&Cet, day, time(3, 0)), spring_forward());
+        assert_eq!(resolve_local(&Cet, day, time(3, 1)), utc(2026, 3, 29, 1, 1));
+    }
+
+    #[test]
+    fn fall_back_overlap_resolves_to_the_first_occurrence() {
+        let day = date(2026, 10, 25);
+        assert_eq!(
+            resolve_local(&Cet, day, time(2, 0)),
+            utc(2026, 10, 25, 0, 0)
+        );
+        assert_eq!(
+            resolve_local(&Cet, day, time(2, 30)),
+            utc(2026, 10, 25, 0, 30)
+        );
+        assert_eq!(
+            resolve_local(&Cet, day, time(2, 59)),
+            utc(2026, 10, 25, 0, 59)
+        );
+        assert_eq!(
+            resolve_local(&Cet, day, time(3, 0)),
+            utc(2026, 10, 25, 2, 0)
+        );
+        assert_eq!(
+            resolve_local(&Cet, day, time(1, 59)),
+            utc(2026, 10, 24, 23, 59)
+        );
+    }
+
+    #[test]
+    fn entries_in_the_gap_and_overlap_fire_once() {
+        let schedule = daily(&[((2, 30), 0.5), ((12, 0), 0.0)], 0);
+        for day in [spring_forward(), fall_back()] {
+            let at_day = day.with_timezone(&Cet);
+            let firing = schedule
+                .transitions_around(&at_day, 0)
+                .into_iter()
+                .filter(|t| {
+                    t.opacity == 0.5 && t.at.with_timezone(&Cet).date_naive() == at_day.date_naive()
+                })
+                .count();
+            assert_eq!(firing, 1);
+        }
+        // The 02:30 that doesn't exist in spring fires at 03:00
+        let clock = TestClock::new(Cet, spring_forward() - Duration::minutes(1));
+        assert_eq!(schedule.evaluate(&clock.now()).unwrap().opacity, 0.0);
+        clock.advance(Duration::minutes(1));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert_eq!(eval.opacity, 0.5);
+        assert_eq!(eval.active.at, spring_forward());
+        // In autumn it doesn't fire again in the repeated hour
+        let clock = TestClock::new(Cet, fall_back() + Duration::minutes(30));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert_eq!(eval.active.at, utc(2026, 10, 25, 0, 30));
+        assert_eq!(eval.next.unwrap().at, utc(2026, 10, 25, 11, 0));
+    }
+
+    #[test]
+    fn ramp_across_spring_forward_takes_real_time() {
+        // 01:30 is before the jump, so an hour's ramp ends at 03:30
+        let schedule = daily(&[((1, 30), 0.6), ((12, 0), 0.0)], 60);
+        let clock = TestClock::new(Cet, utc(2026, 3, 29, 0, 30));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert!(eval.ramping && close(eval.opacity, 0.0));
+        // 03:00 on the wall is 30 minutes in
+        clock.advance(Duration::minutes(30));
+        assert_eq!(clock.now().time(), time(3, 0));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert!(eval.ramping && close(eval.opacity, 0.3), "{:?}", eval);
+        clock.advance(Duration::minutes(30));
+        assert_eq!(clock.now().time(), time(3, 30));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert!(!eval.ramping && close(eval.opacity, 0.6), "{:?}", eval);
+    }
+
+    #[test]
+    fn ramp_across_fall_back_takes_real_time() {
+        // 01:30 summer time; the hour ends at the first 02:30
+        let schedule = daily(&[((1, 30), 0.6), ((12, 0), 0.0)], 60);
+        let clock = TestClock::new(Cet, utc(2026, 10, 24, 23, 30));
+        assert!(close(schedule.evaluate(&clock.now()).unwrap().opacity, 0.0));
+        clock.advance(Duration::minutes(30));
+        assert!(close(schedule.evaluate(&clock.now()).unwrap().opacity, 0.3));
+        clock.advance(Duration::minutes(30));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert!(!eval.ramping && close(eval.opacity, 0.6), "{:?}", eval);
+        // The second 02:30 doesn't restart it
+        clock.advance(Duration::hours(1));
+        assert_eq!(clock.now().time(), time(2, 30));
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert!(!eval.ramping && close(eval.opacity, 0.6), "{:?}", eval);
+    }
+
+    #[test]
+    fn timezone_change_between_ticks() {
+        let schedule = daily(&[((21, 0), 0.4), ((7, 0), 0.0)], 0);
+        let clock = TestClock::new(hours(1), utc(2026, 6, 1, 19, 30));
+        let mut scheduler = Scheduler::new();
+        assert_eq!(scheduler.tick_with(&schedule, &clock).unwrap().opacity, 0.0);
+        clock.advance(Duration::minutes(1));
+        assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+
+        // Flying east: 20:31 becomes 22:31 and the evening entry is in effect
+        clock.tz.set(hours(3));
+        clock.advance(Duration::minutes(1));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(tick.opacity, 0.4);
+        assert!(!tick.resumed);
+        let eval = schedule.evaluate(&clock.now()).unwrap();
+        assert_eq!(eval.active.at, utc(2026, 6, 1, 18, 0));
+
+        // And back west, where it is 20:33 again
+        clock.tz.set(hours(1));
+        clock.advance(Duration::minutes(1));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(tick.opacity, 0.0);
+        assert_eq!(
+            schedule.evaluate(&clock.now()).unwrap().next.unwrap().at,
+            utc(2026, 6, 1, 20, 0)
+        );
+    }
+
+    #[test]
+    fn resume_catches_up_on_missed_transitions() {
+        let schedule = daily(&[((21, 0), 0.4), ((7, 0), 0.0)], 0);
+        let clock = TestClock::new(Utc, utc(2026, 6, 1, 20, 0));
+        let mut scheduler = Scheduler::new();
+        let first = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert!(first.crossed_transition && !first.resumed);
+
+        // Asleep from 20:00 to 23:00, through the evening entry
+        clock.advance(Duration::hours(3));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(
+            tick,
+            Tick {
+                opacity: 0.4,
+                crossed_transition: true,
+                resumed: true
+            }
+        );
+        clock.advance(Duration::seconds(30));
+        assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+
+        // Asleep for a day: same level, but both entries went by
+        clock.advance(Duration::days(1));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(tick.opacity, 0.4);
+        assert!(tick.crossed_transition && tick.resumed);
+
+        // Clock set back an hour: a catch-up too
+        clock.advance(Duration::hours(-1));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert!(tick.crossed_transition && tick.resumed);
+    }
+
+    #[test]
+    fn resume_needs_a_long_gap() {
+        let schedule = daily(&[((21, 0), 0.4), ((7, 0), 0.0)], 0);
+        let clock = TestClock::new(Utc, utc(2026, 6, 1, 20, 59));
+        let mut scheduler = Scheduler::new();
+        scheduler.tick_with(&schedule, &clock);
+        clock.advance(Duration::seconds(RESUME_GAP_SECS));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert!(tick.crossed_transition && !tick.resumed);
+
+        let clock = TestClock::new(Utc, utc(2026, 6, 2, 6, 59));
+        scheduler.tick_with(&schedule, &clock);
+        clock.advance(Duration::seconds(RESUME_GAP_SECS + 1));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(tick.opacity, 0.0);
+        assert!(tick.crossed_transition && tick.resumed);
+    }
+
+    #[test]
+    fn clock_jumps_are_resumes() {
+        let schedule = daily(&[((21, 0), 0.4), ((7, 0), 0.0)], 0);
+        let clock = TestClock::new(Utc, utc(2026, 6, 1, 12, 0));
+        let mut scheduler = Scheduler::new();
+        scheduler.tick_with(&schedule, &clock);
+        // Time passing without a change reports nothing
+        clock.advance(Duration::minutes(1));
+        assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+
+        for by in [
+            Duration::minutes(1),
+            Duration::minutes(-5),
+            Duration::hours(1),
+        ] {
+            clock.jump(by);
+            let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+            assert_eq!(
+                tick,
+                Tick {
+                    opacity: 0.0,
+                    crossed_transition: true,
+                    resumed: true
+                },
+                "{:?}",
+                by
+            );
+            clock.advance(Duration::seconds(1));
+            assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+        }
+
+        // Forward into the evening
+        clock.jump(Duration::hours(9));
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert_eq!(tick.opacity, 0.4);
+        assert!(tick.crossed_transition && tick.resumed);
+    }
+
+    #[test]
+    fn tick_reevaluates_after_notify() {
+        let _notify = time_change::NOTIFY_LOCK.lock().unwrap();
+        // status.json stays where it is
+        crate::journal::disable();
+        let schedule = daily(&[((21, 0), 0.4), ((7, 0), 0.0)], 0);
+        let clock = NotifiedClock(TestClock::new(Utc, utc(2026, 6, 1, 12, 0)));
+        let mut scheduler = Scheduler::new();
+        scheduler.tick_with(&schedule, &clock);
+        clock.0.advance(Duration::minutes(1));
+        assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+
+        time_change::notify();
+        let tick = scheduler.tick_with(&schedule, &clock).unwrap();
+        assert!(tick.crossed_transition && tick.resumed);
+        assert_eq!(scheduler.tick_with(&schedule, &clock), None);
+    }
+}
@@ -0,0 +1,485 @@
+// Scheduled dimming (platform-agnostic)
+//
+// Turns dimming on at `start` and off at `end` (local wall-clock time,
+// "HH:MM", may cross midnight), like pressing the toggle hotkey at those
+// times. `days` picks the days of the week it turns on; an `end` past
+// midnight falls on the next day, so a Friday 22:00–06:00 window ends on
+// Saturday morning. It only acts when a time is reached: toggling by hand
+// in between sticks until the next one. A background thread evaluates the rule through
+// the schedule core (schedule.rs) and hands each change to the platform's
+// `set_handler` hook, which runs it on the UI thread. The thread also
+// re-evaluates at once when the rule is edited or the system clock changes
+// (time_change.rs), so a new timezone takes effect without waiting.
+//
+// With `follow_sun` the times come from the local sunset and sunrise
+// (sun.rs) instead, recomputed every evaluation, and the level fades in and
+// out over SUN_RAMP_MINUTES centred on each of them. The location is
+// `latitude`/`longitude` when both are set, otherwise the platform's
+// `set_locator` hook asks the OS (asked again after a clock or timezone
+// change, since the device may have travelled). While a fade runs each step
+// is handed over, so a hand toggle during one only lasts until the next step.
+//
+// Builds without the `scheduler` feature keep the rule (so config files
+// stay compatible) but never start the thread.
+
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+use crate::dimming::percent_label;
+use crate::locale;
+
+/// Scheduled dimming rule. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DimmingSchedule {
+    pub enabled: bool,
+    /// Local time dimming turns on ("HH:MM")
+    pub start: String,
+    /// Local time dimming turns off ("HH:MM", before `start` = next morning)
+    pub end: String,
+    /// Level dimming turns on at (per-monitor levels are kept as they are)
+    pub level: f32,
+    /// Days of the week dimming turns on, Monday first (empty = never)
+    pub days: Vec<Weekday>,
+    /// Follow local sunset and sunrise instead of `start` and `end`
+    pub follow_sun: bool,
+    /// Location for sunset and sunrise, in degrees (north and east
+    /// positive). Either one unset = ask the OS.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl Default for DimmingSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "21:00".to_string(),
+            end: "07:00".to_string(),
+            level: 0.4,
+            days: WEEK.to_vec(),
+            follow_sun: false,
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+/// Monday first, the order of the day chips in the settings
+pub const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Short description of a set of days, e.g. "Every day", "Weekdays",
+/// "Weekends" or "Mon, Wed, Fri"
+pub fn days_summary(days: &[Weekday]) -> String {
+    let has = |day: &Weekday| days.contains(day);
+    let weekdays = WEEK[..5].iter().filter(|d| has(d)).count();
+    let weekend = WEEK[5..].iter().filter(|d| has(d)).count();
+    match (weekdays, weekend) {
+        (5, 2) => "Every day".to_string(),
+        (5, 0) => "Weekdays".to_string(),
+        (0, 2) => "Weekends".to_string(),
+        (0, 0) => "Never".to_string(),
+        _ => WEEK
+            .iter()
+            .filter(|d| has(d))
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Runs a change on the UI thread: `Some(level)` dims at that level, None
+/// turns dimming off
+pub type Handler = fn(Option<f32>);
+
+/// Asks the OS where the device is (latitude, longitude); may block, called
+/// on the schedule thread
+pub type Locator = fn() -> Option<(f64, f64)>;
+
+/// Length of the fade around sunset and sunrise
+pub const SUN_RAMP_MINUTES: u32 = 30;
+
+/// Whether this build includes the schedule thread
+pub const ENABLED: bool = cfg!(feature = "scheduler");
+
+impl DimmingSchedule {
+    /// Summary for the settings UI, e.g. "40% from 21:00 to 07:00
+    /// (Weekdays)"; the days are left out when it runs every day
+    pub fn summary(&self) -> String {
+        let window = if self.follow_sun {
+            format!("{} from sunset to sunrise", percent_label(self.level))
+        } else {
+            format!(
+                "{} from {} to {}",
+                percent_label(self.level),
+                locale::time(&self.start),
+                locale::time(&self.end)
+            )
+        };
+        if WEEK.iter().all(|d| self.days.contains(d)) {
+            return window;
+        }
+        format!("{} ({})", window, days_summary(&self.days))
+    }
+
+    /// Turn `day` on or off (a day chip in the settings)
+    pub fn toggle_day(&mut self, day: Weekday) {
+        if let Some(i) = self.days.iter().position(|d| *d == day) {
+            self.days.remove(i);
+        } else {
+            self.days.push(day);
+            self.sanitize_days();
+        }
+    }
+
+    /// Put `days` in week order without repeats. True if that changed them.
+    pub fn sanitize_days(&mut self) -> bool {
+        let days: Vec<Weekday> = WEEK.into_iter().filter(|d| self.days.contains(d)).collect();
+        let changed = days != self.days;
+        self.days = days;
+        changed
+    }
+
+    /// Location from config, if both coordinates are set
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.latitude.zip(self.longitude)
+    }
+}
+
+#[cfg(feature = "scheduler")]
+pub use runner::{apply, set_handler, set_locator};
+
+/// Without the schedule core there is nothing to run
+#[cfg(not(feature = "scheduler"))]
+pub fn apply(_rule: &DimmingSchedule) {}
+
+#[cfg(not(feature = "scheduler"))]
+pub fn set_handler(_handler: Handler) {}
+
+#[cfg(not(feature = "scheduler"))]
+pub fn set_locator(_locator: Locator) {}
+
+#[cfg(feature = "scheduler")]
+mod runner {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, Utc};
+    use savemyeyes_core::clock::parse_time;
+
+    use super::{DimmingSchedule, Handler, Locator, SUN_RAMP_MINUTES, WEEK};
+    use crate::schedule::{Schedule, ScheduleEntry, Scheduler, SystemClock};
+    use crate::sun::{self, Daylight};
+    use crate::{shutdown, time_change};
+
+    /// How often the thread looks for edits and clock changes
+    const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+    /// How often the schedule itself is evaluated, in checks
+    const EVALUATE_EVERY: u32 = 15;
+    /// How long to wait before asking the OS for the location again after
+    /// it had none, in checks
+    const LOCATE_EVERY: u32 = 300;
+
+    static RULE: Mutex<Option<DimmingSchedule>> = Mutex::new(None);
+    static HANDLER: Mutex<Option<Handler>> = Mutex::new(None);
+    static LOCATOR: Mutex<Option<Locator>> = Mutex::new(None);
+    /// Set by `apply`: evaluate from scratch on the next check
+    static EDITED: AtomicBool = AtomicBool::new(false);
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+
+    /// Called from the schedule thread with each change
+    pub fn set_handler(handler: Handler) {
+        *HANDLER.lock().unwrap() = Some(handler);
+    }
+
+    /// Called from the schedule thread when `follow_sun` needs a location
+    /// that config doesn't give
+    pub fn set_locator(locator: Locator) {
+        *LOCATOR.lock().unwrap() = Some(locator);
+    }
+
+    /// Apply the rule from config.
+    pub fn apply(rule: &DimmingSchedule) {
+        *RULE.lock().unwrap() = Some(rule.clone());
+        EDITED.store(true, Ordering::SeqCst);
+        if rule.enabled && !RUNNING.swap(true, Ordering::SeqCst) {
+            shutdown::spawn("scheduler", run);
+        }
+    }
+
+    /// The rule as schedule core entries. None if a time doesn't parse, the
+    /// window is empty, or `follow_sun` has no location.
+    fn to_schedule(rule: &DimmingSchedule, location: Option<(f64, f64)>) -> Option<Schedule> {
+        if rule.follow_sun {
+            return location.map(|(latitude, longitude)| sun_schedule(rule, latitude, longitude));
+        }
+        let time = |text: &str| {
+            let minute = parse_time(text)?;
+            NaiveTime::from_hms_opt(minute / 60, minute % 60, 0)
+        };
+        let (start, end) = (time(&rule.start)?, time(&rule.end)?);
+        (start != end).then(|| Schedule {
+            entries: window(rule, start, end),
+            ramp_minutes: 0,
+        })
+    }
+
+    /// Entries turning dimming on at `start` on the rule's days and off at
+    /// `end`, on the next day when `end` is past midnight
+    fn window(rule: &DimmingSchedule, start: NaiveTime, end: NaiveTime) -> Vec<ScheduleEntry> {
+        let end_days = if end < start {
+            rule.days.iter().map(|d| d.succ()).collect()
+        } else {
+            rule.days.clone()
+        };
+        vec![
+            ScheduleEntry {
+                time: start,
+                opacity: rule.level,
+                days: rule.days.clone(),
+            },
+            ScheduleEntry {
+                time: end,
+                opacity: 0.0,
+                days: end_days,
+            },
+        ]
+    }
+
+    /// Today's sunset and sunrise as schedule core entries, each moved half
+    /// a ramp earlier so the fade is centred on it
+    fn sun_schedule(rule: &DimmingSchedule, latitude: f64, longitude: f64) -> Schedule {
+        let half_ramp = ChronoDuration::minutes(SUN_RAMP_MINUTES as i64 / 2);
+        let local = |at: DateTime<Utc>| (at - half_ramp).with_timezone(&Local).time();
+        let entries = match sun::daylight(Local::now().date_naive(), latitude, longitude) {
+            Daylight::Day { sunrise, sunset } => window(rule, local(sunset), local(sunrise)),
+            Daylight::AlwaysUp => vec![ScheduleEntry::daily(NaiveTime::MIN, 0.0)],
+            // Dimmed all day on the rule's days, undimmed on the others
+            Daylight::AlwaysDown => vec![
+                ScheduleEntry {
+                    time: NaiveTime::MIN,
+                    opacity: rule.level,
+                    days: rule.days.clone(),
+                },
+                ScheduleEntry {
+                    time: NaiveTime::MIN,
+                    opacity: 0.0,
+                    days: WEEK
+                        .into_iter()
+                        .filter(|d| !rule.days.contains(d))
+                        .collect(),
+                },
+            ],
+        };
+        Schedule {
+            entries,
+            ramp_minutes: SUN_RAMP_MINUTES,
+        }
+    }
+
+    /// Ask the platform where the device is
+    fn locate() -> Option<(f64, f64)> {
+        let locator = *LOCATOR.lock().unwrap();
+        let location = locator
+            .and_then(|locate| locate())
+            .filter(|&(lat, lon)| sun::valid(lat, lon));
+        match location {
+            Some((lat, lon)) => eprintln!("[scheduler] located at {:.2}, {:.2}", lat, lon),
+            None => eprintln!("[scheduler] no location for sunset and sunrise"),
+        }
+        location
+    }
+
+    fn run() {
+        let mut scheduler = Scheduler::new();
+        let mut generation = time_change::generation();
+        let mut checks = 0;
+        // Location from the OS, and checks until it may be asked again
+        let mut located = None;
+        let mut locate_wait = 0;
+        loop {
+            let rule = RULE.lock().unwrap().clone();
+            let Some(rule) = rule.filter(|r| r.enabled) else {
+                break;
+            };
+            let now_generation = time_change::generation();
+            let clock_changed = now_generation != generation;
+            generation = now_generation;
+            if clock_changed {
+                // The device may have travelled
+                located = None;
+                locate_wait = 0;
+            }
+            if EDITED.swap(false, Ordering::SeqCst) {
+                // Reports the window we are in as just entered
+                scheduler.reset();
+                checks = 0;
+            }
+            if clock_changed {
+                // tick_with sees the new generation and reports a resume
+                checks = 0;
+            }
+            let mut location = rule.location();
+            if rule.follow_sun && location.is_none() {
+                if located.is_none() && locate_wait == 0 {
+                    located = locate();
+                    locate_wait = LOCATE_EVERY;
+                    checks = 0;
+                }
+                locate_wait = locate_wait.saturating_sub(1);
+                location = located;
+            }
+            if checks == 0 {
+                if let Some(schedule) = to_schedule(&rule, location) {
+                    let tick = scheduler.tick_with(&schedule, &SystemClock);
+                    // Fades around sunset and sunrise go over step by step
+                    if let Some(tick) = tick.filter(|t| t.crossed_transition || rule.follow_sun) {
+                        hand_over((tick.opacity > 0.0).then_some(tick.opacity));
+                    }
+                }
+            }
+            checks = (checks + 1) % EVALUATE_EVERY;
+            if !shutdown::sleep(CHECK_INTERVAL) {
+                break;
+            }
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn hand_over(level: Option<f32>) {
+        eprintln!("[scheduler] dimming {:?}", level);
+        if let Some(handler) = *HANDLER.lock().unwrap() {
+            handler(level);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use chrono::{Datelike, TimeZone, Weekday};
+
+        use super::*;
+
+        fn rule(start: &str, end: &str, days: &[Weekday]) -> DimmingSchedule {
+            DimmingSchedule {
+                enabled: true,
+                start: start.to_string(),
+                end: end.to_string(),
+                days: days.to_vec(),
+                ..DimmingSchedule::default()
+            }
+        }
+
+        /// Opacity the rule asks for on 2026-10-`day` at `hour`:`minute` UTC
+        /// (the 16th is a Friday)
+        fn level_at(schedule: &Schedule, day: u32, hour: u32, minute: u32) -> f32 {
+            let now = Utc
+                .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+                .unwrap();
+            schedule.evaluate(&now).unwrap().opacity
+        }
+
+        #[test]
+        fn weekday_only_rule_skips_the_weekend() {
+            assert_eq!(
+                Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0)
+                    .unwrap()
+                    .weekday(),
+                Weekday::Fri
+            );
+            let schedule = to_schedule(&rule("21:00", "07:00", &WEEK[..5]), None).unwrap();
+            let [on, off] = &schedule.entries[..] else {
+                panic!("expected two entries");
+            };
+            assert_eq!(on.days, WEEK[..5]);
+            // Mondays' first stretch ends on Tuesday, Fridays' on Saturday
+            assert_eq!(off.days, WEEK[1..6]);
+
+            assert_eq!(level_at(&schedule, 15, 22, 0), 0.4); // Thu
+            assert_eq!(level_at(&schedule, 17, 3, 0), 0.4); // Fri night
+            assert_eq!(level_at(&schedule, 17, 7, 0), 0.0);
+            assert_eq!(level_at(&schedule, 17, 22, 0), 0.0); // Sat
+            assert_eq!(level_at(&schedule, 18, 23, 30), 0.0); // Sun
+            assert_eq!(level_at(&schedule, 19, 21, 0), 0.4); // Mon
+        }
+
+        #[test]
+        fn friday_window_ends_saturday_morning() {
+            let schedule = to_schedule(&rule("22:00", "06:00", &[Weekday::Fri]), None).unwrap();
+            assert_eq!(schedule.entries[1].days, [Weekday::Sat]);
+
+            assert_eq!(level_at(&schedule, 15, 23, 0), 0.0); // Thu
+            assert_eq!(level_at(&schedule, 16, 21, 59), 0.0);
+            assert_eq!(level_at(&schedule, 16, 22, 0), 0.4);
+            assert_eq!(level_at(&schedule, 17, 5, 59), 0.4);
+            assert_eq!(level_at(&schedule, 17, 6, 0), 0.0);
+            assert_eq!(level_at(&schedule, 17, 22, 30), 0.0);
+            assert_eq!(level_at(&schedule, 23, 21, 59), 0.0); // next Fri
+            let now = Utc.with_ymd_and_hms(2026, 10, 17, 1, 0, 0).unwrap();
+            let next = schedule.evaluate(&now).unwrap().next.unwrap();
+            assert_eq!(
+                next.at,
+                Utc.with_ymd_and_hms(2026, 10, 17, 6, 0, 0).unwrap()
+            );
+        }
+
+        #[test]
+        fn daytime_window_ends_the_same_day() {
+            let schedule = to_schedule(&rule("09:00", "17:00", &[Weekday::Sat]), None).unwrap();
+            assert_eq!(schedule.entries[1].days, [Weekday::Sat]);
+            assert_eq!(level_at(&schedule, 17, 12, 0), 0.4);
+            assert_eq!(level_at(&schedule, 17, 18, 0), 0.0);
+            assert_eq!(level_at(&schedule, 18, 12, 0), 0.0);
+        }
+
+        #[test]
+        fn no_days_never_dims() {
+            let schedule = to_schedule(&rule("21:00", "07:00", &[]), None).unwrap();
+            let now = Utc.with_ymd_and_hms(2026, 10, 16, 22, 0, 0).unwrap();
+            assert_eq!(schedule.evaluate(&now), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+
+    #[test]
+    fn days_are_kept_in_week_order() {
+        let mut rule = DimmingSchedule {
+            days: vec![Weekday::Sun, Weekday::Mon, Weekday::Sun],
+            ..DimmingSchedule::default()
+        };
+        assert!(rule.sanitize_days());
+        assert_eq!(rule.days, [Weekday::Mon, Weekday::Sun]);
+        assert!(!rule.sanitize_days());
+
+        rule.toggle_day(Weekday::Wed);
+        assert_eq!(rule.days, [Weekday::Mon, Weekday::Wed, Weekday::Sun]);
+        rule.toggle_day(Weekday::Mon);
+        assert_eq!(rule.days, [Weekday::Wed, Weekday::Sun]);
+    }
+
+    #[test]
+    fn summary_names_the_days() {
+        let mut rule = DimmingSchedule::default();
+        assert!(!rule.summary().contains('('));
+        rule.days = WEEK[..5].to_vec();
+        assert!(rule.summary().ends_with(" (Weekdays)"));
+        rule.days = vec![Weekday::Mon, Weekday::Fri];
+        assert!(rule.summary().ends_with(" (Mon, Fri)"));
+        rule.days.clear();
+        assert!(rule.summary().ends_with(" (Never)"));
+        assert_eq!(days_summary(&WEEK[5..]), "Weekends");
+    }
+}
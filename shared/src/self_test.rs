@@ -0,0 +1,82 @@
+// `--self-test`: end-to-end smoke test of the real dimming backend
+//
+// The platform binaries drive their own overlay/gamma code through a fixed
+// script (show at 30%, check every monitor is dimmed, adjust to 60%, toggle
+// off, check everything was torn down) and report each check here. The
+// process exit code is non-zero if any check failed, so a build machine with
+// a real desktop session can run it as an integration test.
+
+/// Command-line flag that runs the self-test instead of the app
+pub const ARG: &str = "--self-test";
+
+/// First level the script shows
+pub const FIRST_LEVEL: f32 = 0.3;
+/// Level the script adjusts to
+pub const SECOND_LEVEL: f32 = 0.6;
+
+/// True if the self-test was requested on the command line
+pub fn requested() -> bool {
+    std::env::args().any(|a| a == ARG)
+}
+
+/// Collects check results and prints one line per check.
+#[derive(Debug, Default)]
+pub struct Report {
+    passed: usize,
+    failed: Vec<String>,
+    /// Print failures only (long runs such as `--fuzz-soak`)
+    quiet: bool,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A report that prints only failed checks
+    pub fn quiet() -> Self {
+        Self {
+            quiet: true,
+            ..Self::default()
+        }
+    }
+
+    /// Record one check. `detail` says what was observed.
+    pub fn check(&mut self, name: &str, ok: bool, detail: impl AsRef<str>) -> bool {
+        let detail = detail.as_ref();
+        if ok {
+            self.passed += 1;
+            if !self.quiet {
+                println!("[self-test] ok      {} ({})", name, detail);
+            }
+        } else {
+            self.failed.push(name.to_string());
+            eprintln!("[self-test] FAILED  {} ({})", name, detail);
+        }
+        ok
+    }
+
+    /// Record a check that could not run on this machine (not a failure).
+    pub fn skip(&mut self, name: &str, reason: &str) {
+        println!("[self-test] skipped {} ({})", name, reason);
+    }
+
+    /// Print the summary and return the process exit code.
+    pub fn finish(self) -> i32 {
+        if self.failed.is_empty() {
+            println!("[self-test] passed: {} checks", self.passed);
+            0
+        } else {
+            let mut names = self.failed.clone();
+            names.sort();
+            names.dedup();
+            eprintln!(
+                "[self-test] {} of {} checks failed: {}",
+                self.failed.len(),
+                self.passed + self.failed.len(),
+                names.join(", ")
+            );
+            1
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// Coordinated shutdown (platform-agnostic)
+//
+// Quitting used to tear down in whatever order the exit path ran, while the
+// background threads (overlay watchdog, brightness, night boost and pen
+// polls, pause timers, the update check) kept going. One of them could put
+// gamma back or re-create an overlay right after it was restored, or open an
+// update prompt on the way out, and a tray icon removed before a hang stayed
+// behind as a ghost. Quitting now goes:
+//   1. `begin`: every background loop sees `is_stopping` and wakes from
+//      `sleep` at once
+//   2. `join`: wait for the threads started with `spawn`, up to a timeout. A
+//      thread stuck in a system call (WMI, a download) is named in the log
+//      and left to the process exit
+//   3. the platform restores gamma and removes the overlays
+//   4. then removes the tray icon and persists state (config, journal,
+//      status.json)
+// Threads that block in the OS for the whole session (the theme watcher,
+// the hotkey and event tap threads) aren't joined; they only post messages
+// to the UI thread, which no longer handles them.
+//
+// Every thread started with `spawn` is background work, so it first runs the
+// platform's `set_thread_start` hook, which lowers its priority.
+
+use std::sync::{Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How long `join` waits for the background threads
+pub const TIMEOUT: Duration = Duration::from_secs(2);
+
+static STOPPING: Mutex<bool> = Mutex::new(false);
+static WAKE: Condvar = Condvar::new();
+static THREADS: Mutex<Vec<(&'static str, JoinHandle<()>)>> = Mutex::new(Vec::new());
+static THREAD_START: Mutex<Option<fn()>> = Mutex::new(None);
+
+/// Run `hook` first on every thread started with `spawn` from now on.
+pub fn set_thread_start(hook: fn()) {
+    *THREAD_START.lock().unwrap() = Some(hook);
+}
+
+/// Start a background thread that `join` waits for. Does nothing once
+/// shutdown has begun.
+pub fn spawn<F>(name: &'static str, f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    if is_stopping() {
+        return;
+    }
+    let hook = *THREAD_START.lock().unwrap();
+    let spawned = std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            if let Some(hook) = hook {
+                hook();
+            }
+            f()
+        });
+    match spawned {
+        Ok(handle) => {
+            let mut threads = THREADS.lock().unwrap();
+            // Threads that already finished don't need joining
+            threads.retain(|(_, handle)| !handle.is_finished());
+            threads.push((name, handle));
+        }
+        Err(e) => eprintln!("[shutdown] could not start {}: {}", name, e),
+    }
+}
+
+/// True once the app is quitting
+pub fn is_stopping() -> bool {
+    *STOPPING.lock().unwrap()
+}
+
+/// Sleep for `duration`, or until shutdown begins. Returns false if the
+/// app is quitting.
+pub fn sleep(duration: Duration) -> bool {
+    let stopping = STOPPING.lock().unwrap();
+    let (stopping, _) = WAKE
+        .wait_timeout_while(stopping, duration, |stopping| !*stopping)
+        .unwrap();
+    !*stopping
+}
+
+/// Tell every background thread to stop.
+pub fn begin() {
+    *STOPPING.lock().unwrap() = true;
+    WAKE.notify_all();
+}
+
+/// Wait up to `timeout` for the threads started with `spawn` to finish.
+/// Returns false if some are still running.
+pub fn join(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut threads = std::mem::take(&mut *THREADS.lock().unwrap());
+    loop {
+        let (finished, running): (Vec<_>, Vec<_>) = threads
+            .into_iter()
+            .partition(|(_, handle)| handle.is_finished());
+        for (_, handle) in finished {
+            let _ = handle.join();
+        }
+        threads = running;
+        if threads.is_empty() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            let names: Vec<&str> = threads.iter().map(|(name, _)| *name).collect();
+            eprintln!("[shutdown] still running at exit: {}", names.join(", "));
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,215 @@
+// `--fuzz-soak <minutes>`: randomized soak test over the event bus
+// (platform-agnostic)
+//
+// For a nightly run on a build machine with a desktop session. The platform
+// starts the app as usual, but on scratch settings in the temp folder, so a
+// run never touches the user's config, and drives it from a background
+// thread for the given time: random commands (toggle, set_enabled,
+// set_level, set_monitor_level, apply_profile, pause, video_playing) go
+// through events::forward, the path the browser bridge and jump list use,
+// a few every second, interleaving with the app's own timers and poll
+// threads. After each one the invariants are checked:
+//   • every level in memory and in config.json is within 0–MAX_OPACITY
+//   • while dimming shows, one overlay window per monitor
+//   • config.json and status.json on disk still parse
+// Levels out of range and names that don't exist are sent on purpose; the
+// bus has to reject or clamp them. The seed is printed first; `--seed <n>`
+// replays a run. The exit code is non-zero if any check failed.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{self, AppConfig};
+use crate::dimming::MAX_OPACITY;
+use crate::events::Command;
+use crate::self_test::Report;
+use crate::status_file;
+
+/// Command-line flag that runs the soak test, followed by the minutes
+pub const ARG: &str = "--fuzz-soak";
+/// Command-line flag that replays a run
+pub const SEED_ARG: &str = "--seed";
+
+/// Longest pause between two commands; the shortest is a tenth of it
+pub const MAX_GAP: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub duration: Duration,
+    pub seed: u64,
+}
+
+/// The soak run the command line asks for, if any. Err for a missing or
+/// malformed minute count or seed.
+pub fn from_args() -> Result<Option<Options>, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value = |name: &str| {
+        let pos = args.iter().position(|a| a == name)?;
+        Some(args.get(pos + 1).map(String::as_str).unwrap_or(""))
+    };
+    let Some(minutes) = value(ARG) else {
+        return Ok(None);
+    };
+    let minutes: u64 = minutes
+        .parse()
+        .ok()
+        .filter(|m| *m > 0)
+        .ok_or_else(|| format!("{} needs a number of minutes", ARG))?;
+    let seed = match value(SEED_ARG) {
+        Some(seed) => seed
+            .parse()
+            .map_err(|_| format!("{} needs a number", SEED_ARG))?,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64,
+    };
+    Ok(Some(Options {
+        duration: Duration::from_secs(minutes * 60),
+        seed,
+    }))
+}
+
+/// Scratch settings folder for a run
+pub fn dir() -> PathBuf {
+    std::env::temp_dir().join("SaveMyEyes-soak")
+}
+
+/// Point config (and status.json, the journal, ...) at a fresh scratch
+/// folder. Multi-monitor mode is on, so set_monitor_level has something to
+/// do, and the update check is off. Call before load_config.
+pub fn prepare() {
+    let dir = dir();
+    let _ = fs::remove_dir_all(&dir);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("[soak] could not create {}: {}", dir.display(), e);
+    }
+    let seed = serde_json::json!({ "multi_monitor": true, "auto_update": false });
+    let _ = fs::write(dir.join("config.json"), seed.to_string());
+    config::use_dir(dir);
+}
+
+/// Small deterministic generator (xorshift64*), so a seed replays a run
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero would stay zero
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n.max(1)
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    /// A level, now and then out of range
+    fn level(&mut self) -> f32 {
+        if self.chance(10) {
+            [-0.5, 1.0, 7.5][self.below(3) as usize]
+        } else {
+            self.below(91) as f32 / 100.0
+        }
+    }
+
+    /// Time to the next command
+    pub fn gap(&mut self) -> Duration {
+        let max = MAX_GAP.as_millis() as u64;
+        Duration::from_millis(max / 10 + self.below(max - max / 10))
+    }
+}
+
+/// A random command for `monitors` monitors and the profiles in `profiles`
+pub fn random_command(rng: &mut Rng, monitors: u32, profiles: &[String]) -> Command {
+    match rng.below(100) {
+        0..=14 => Command::Toggle,
+        15..=24 => Command::SetEnabled {
+            enabled: rng.chance(50),
+        },
+        25..=49 => Command::SetLevel { level: rng.level() },
+        50..=74 => Command::SetMonitorLevel {
+            // One past the last monitor now and then
+            monitor: rng.below(monitors as u64 + 1) as u32,
+            level: rng.level(),
+        },
+        75..=86 => {
+            let name = if profiles.is_empty() || rng.chance(10) {
+                "No Such Profile".to_string()
+            } else {
+                profiles[rng.below(profiles.len() as u64) as usize].clone()
+            };
+            Command::ApplyProfile { name }
+        }
+        87..=92 => Command::Pause {
+            minutes: 1 + rng.below(2),
+        },
+        _ => Command::VideoPlaying {
+            playing: rng.chance(50),
+        },
+    }
+}
+
+fn in_range(level: f32) -> bool {
+    (0.0..=MAX_OPACITY).contains(&level)
+}
+
+/// Every level in `config` is within range
+pub fn check_levels(report: &mut Report, source: &str, config: &AppConfig) {
+    let mut levels = vec![
+        ("opacity".to_string(), config.opacity),
+        ("last_opacity".to_string(), config.last_opacity),
+    ];
+    for (id, level) in &config.monitor_opacity {
+        levels.push((format!("monitor_opacity[{}]", id), *level));
+    }
+    let bad: Vec<String> = levels
+        .iter()
+        .filter(|(_, level)| !in_range(*level))
+        .map(|(name, level)| format!("{} = {}", name, level))
+        .collect();
+    report.check(
+        &format!("{}: levels in range", source),
+        bad.is_empty(),
+        bad.join(", "),
+    );
+}
+
+/// config.json and status.json parse, and config.json holds valid levels
+pub fn check_files(report: &mut Report) {
+    let path = config::config_path();
+    let config = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| crate::config_text::parse(&text).map_err(|e| e.to_string()))
+        .and_then(|value| serde_json::from_value::<AppConfig>(value).map_err(|e| e.to_string()));
+    match config {
+        Ok(config) => {
+            report.check("config.json parses", true, path.display().to_string());
+            check_levels(report, "config.json", &config);
+        }
+        Err(e) => {
+            report.check("config.json parses", false, e);
+        }
+    }
+
+    let status = status_file::status_path();
+    // Not written yet in the first moments
+    if status.exists() {
+        report.check(
+            "status.json parses",
+            status_file::read().is_some(),
+            status.display().to_string(),
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// Level per desktop (platform-agnostic)
+//
+// With this on, each macOS Space (desktop) keeps its own dim level: a dark
+// level on the desktop with the editor, a light one on the desktop with
+// photos. Nothing is set up front. When the desktop changes, the levels in
+// use are stored under the desktop being left and the ones stored for the
+// desktop being entered come back, so whatever was last set on a desktop
+// sticks to it. Desktops are keyed by the Space's UUID, which survives
+// restarts; where it can't be read the 1-based position among the
+// display's desktops stands in ("desktop 2"), which moves if desktops are
+// reordered. Off by default.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::monitor_id::MonitorId;
+
+/// Levels remembered for one desktop
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpaceLevel {
+    pub opacity: f32,
+    /// Monitor levels keyed by monitor id (multi-monitor)
+    pub monitor_opacity: HashMap<MonitorId, f32>,
+}
+
+impl Default for SpaceLevel {
+    fn default() -> Self {
+        Self {
+            opacity: 0.3,
+            monitor_opacity: HashMap::new(),
+        }
+    }
+}
+
+/// `space_levels` config block
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpaceLevels {
+    pub enabled: bool,
+    /// Levels per desktop, keyed by `key`
+    pub levels: HashMap<String, SpaceLevel>,
+}
+
+/// Config key of a desktop: its UUID, or its position if there is none
+pub fn key(uuid: Option<&str>, index: usize) -> String {
+    match uuid {
+        Some(uuid) if !uuid.is_empty() => uuid.to_string(),
+        _ => format!("desktop {}", index),
+    }
+}
+
+impl SpaceLevels {
+    /// Desktop change from `from` (None if unknown) to `to` while `current`
+    /// is on screen: remember `current` for `from` and return the levels
+    /// stored for `to`, if any.
+    pub fn switch(
+        &mut self,
+        from: Option<&str>,
+        to: &str,
+        current: SpaceLevel,
+    ) -> Option<SpaceLevel> {
+        if let Some(from) = from.filter(|from| *from != to) {
+            self.levels.insert(from.to_string(), current);
+        }
+        self.levels.get(to).cloned()
+    }
+}
@@ -0,0 +1,184 @@
+// Machine-readable status for status bars and desktop widgets
+//
+// status.json sits next to config.json and describes what is on screen, for
+// tools like Rainmeter or Übersicht to poll. It is written by the journal's
+// background writer (so at most every journal FLUSH_MS while levels change)
+// and at startup or when a saved setting it reports changes. Every write goes to a
+// temp file that is renamed over status.json, so readers never see a torn
+// file. Unlike the crash journal it stays after exit, with "running": false.
+//
+// Schema (version 1; fields are only ever added):
+//   version             1
+//   running             false once the app has exited cleanly
+//   seq                 +1 per write; restarts at 1 with each app launch
+//   updated_at          Unix time in milliseconds of this write
+//   enabled             dimming on
+//   opacity             global level, 0.0–0.9
+//   monitor_opacity     {"hw-5f0c…": 0.4, ...} by monitor id (see monitor_id.rs)
+//   per_monitor_opacity {"0": 0.4, ...} by monitor index (Windows)
+//   per_display_opacity {"DELL U2720Q": 0.4, ...} by display name (macOS)
+//   profile             name of the last applied profile, or null
+//   paused              dimming suspended (designer mode or a timed pause);
+//                       levels are what comes back afterwards
+//   paused_until        Unix time in seconds a timed pause ends, or null
+//   cause               what made the last change, e.g. {"by": "hotkey"} or
+//                       {"by": "profile", "name": "Night"} (see explain.rs)
+//
+// Writes are serialized, so a higher seq (or, across launches, a later
+// updated_at) is always the newer state.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{config_path, AppConfig};
+use crate::explain::Cause;
+use crate::journal::{self, AppliedState};
+use crate::monitor_id::MonitorId;
+use crate::pause;
+use crate::usage_stats;
+
+const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "api-schema", derive(schemars::JsonSchema))]
+struct Status<'a> {
+    version: u32,
+    running: bool,
+    seq: u64,
+    updated_at: u64,
+    enabled: bool,
+    opacity: f32,
+    monitor_opacity: &'a HashMap<MonitorId, f32>,
+    per_monitor_opacity: &'a HashMap<u32, f32>,
+    per_display_opacity: &'a HashMap<String, f32>,
+    profile: Option<&'a str>,
+    paused: bool,
+    paused_until: Option<u64>,
+    cause: Option<&'a Cause>,
+}
+
+/// Everything the next write needs
+struct Writer {
+    seq: u64,
+    /// Last applied screen state
+    applied: Option<AppliedState>,
+    /// From config: last applied profile, designer mode
+    profile: Option<String>,
+    paused: bool,
+}
+
+static WRITER: Mutex<Writer> = Mutex::new(Writer {
+    seq: 0,
+    applied: None,
+    profile: None,
+    paused: false,
+});
+
+pub fn status_path() -> PathBuf {
+    config_path().with_file_name("status.json")
+}
+
+/// Last written status, for other processes (e.g. the browser bridge)
+pub fn read() -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(status_path()).ok()?).ok()
+}
+
+/// JSON Schema of status.json (api_schema.rs)
+#[cfg(feature = "api-schema")]
+pub(crate) fn json_schema() -> schemars::Schema {
+    schemars::schema_for!(Status<'static>)
+}
+
+/// New screen state from the journal writer
+pub(crate) fn applied(state: &AppliedState) {
+    let mut writer = WRITER.lock().unwrap();
+    writer.applied = Some(state.clone());
+    write(&mut writer, true);
+}
+
+/// Startup: report the configured state until the overlay applies it.
+pub(crate) fn config_loaded(config: &AppConfig) {
+    let mut writer = WRITER.lock().unwrap();
+    let mut state = AppliedState::new(config.is_enabled, config.opacity);
+    state.monitor_opacity = config.monitor_opacity.clone();
+    writer.applied = Some(state);
+    writer.profile = config.active_profile.clone();
+    writer.paused = config.designer_mode;
+    write(&mut writer, true);
+}
+
+/// Config saved; rewrite if the profile or designer mode changed.
+pub(crate) fn config_saved(config: &AppConfig) {
+    let mut writer = WRITER.lock().unwrap();
+    if writer.profile == config.active_profile && writer.paused == config.designer_mode {
+        return;
+    }
+    writer.profile = config.active_profile.clone();
+    writer.paused = config.designer_mode;
+    write(&mut writer, true);
+}
+
+/// A timed pause started or ended.
+pub(crate) fn pause_changed() {
+    let mut writer = WRITER.lock().unwrap();
+    write(&mut writer, true);
+}
+
+/// Clean exit: keep the last state, marked as not running.
+pub(crate) fn finish() {
+    let mut writer = WRITER.lock().unwrap();
+    write(&mut writer, false);
+}
+
+/// Write while holding the lock, so files land in seq order.
+fn write(writer: &mut Writer, running: bool) {
+    if journal::is_disabled() {
+        return;
+    }
+    writer.seq += 1;
+    let empty_ids = HashMap::new();
+    let empty_monitors = HashMap::new();
+    let empty_displays = HashMap::new();
+    let applied = writer.applied.as_ref();
+    let status = Status {
+        version: VERSION,
+        running,
+        seq: writer.seq,
+        updated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        enabled: applied.is_some_and(|a| a.enabled),
+        opacity: applied.map_or(0.0, |a| a.opacity),
+        monitor_opacity: applied.map_or(&empty_ids, |a| &a.monitor_opacity),
+        per_monitor_opacity: applied.map_or(&empty_monitors, |a| &a.per_monitor_opacity),
+        per_display_opacity: applied.map_or(&empty_displays, |a| &a.per_display_opacity),
+        profile: writer.profile.as_deref(),
+        paused: writer.paused || pause::is_active(),
+        paused_until: applied.and_then(|a| a.paused_until).or_else(pause::until_unix),
+        cause: applied.map(|a| &a.cause),
+    };
+
+    // Per-monitor levels, when set, are what is on screen
+    let level = if status.monitor_opacity.is_empty() {
+        status.opacity
+    } else {
+        status.monitor_opacity.values().sum::<f32>() / status.monitor_opacity.len() as f32
+    };
+    let dimmed = running && status.enabled && !status.paused && level > 0.0;
+    usage_stats::sample(dimmed.then_some(level));
+
+    let path = status_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_string_pretty(&status).unwrap_or_default();
+    if fs::write(&tmp, data).is_ok() {
+        let _ = fs::rename(&tmp, &path);
+    }
+}
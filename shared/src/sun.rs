@@ -0,0 +1,84 @@
+// Sunrise and sunset (platform-agnostic, no I/O)
+//
+// The sunrise equation as NOAA simplifies it: mean solar anomaly, equation
+// of center, ecliptic longitude, declination, then the hour angle at which
+// the sun's upper edge crosses the horizon (-0.833° for refraction and the
+// solar disc). It is within a minute or two of the published tables at
+// mid latitudes, which is plenty for timing a dimming ramp. Above the polar
+// circles there are days without a sunrise or a sunset; those come back as
+// `AlwaysUp` / `AlwaysDown` instead of a made-up time.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Julian day of 2000-01-01 12:00 UTC
+const J2000: f64 = 2_451_545.0;
+/// Julian day of the Unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// Sun's altitude at sunrise/sunset: refraction plus the disc's radius
+const HORIZON_DEG: f64 = -0.833;
+/// Tilt of the Earth's axis
+const OBLIQUITY_DEG: f64 = 23.4397;
+
+/// The sun on one day at one place
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Daylight {
+    Day {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// Midnight sun: above the horizon all day
+    AlwaysUp,
+    /// Polar night: below the horizon all day
+    AlwaysDown,
+}
+
+/// Whether `latitude`/`longitude` (degrees, north and east positive) are on
+/// the globe
+pub fn valid(latitude: f64, longitude: f64) -> bool {
+    latitude.is_finite()
+        && longitude.is_finite()
+        && latitude.abs() <= 90.0
+        && longitude.abs() <= 180.0
+}
+
+/// Sunrise and sunset around noon of `date` at `latitude`/`longitude`
+pub fn daylight(date: NaiveDate, latitude: f64, longitude: f64) -> Daylight {
+    let epoch = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let days = (date - epoch).num_days() as f64;
+
+    // Mean solar noon at this longitude
+    let noon = days - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+
+    let declination = (ecliptic.sin() * OBLIQUITY_DEG.to_radians().sin()).asin();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (HORIZON_DEG.to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if cos_hour_angle < -1.0 {
+        return Daylight::AlwaysUp;
+    }
+    if cos_hour_angle > 1.0 || !cos_hour_angle.is_finite() {
+        return Daylight::AlwaysDown;
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    match (
+        from_julian(transit - half_day),
+        from_julian(transit + half_day),
+    ) {
+        (Some(sunrise), Some(sunset)) => Daylight::Day { sunrise, sunset },
+        _ => Daylight::AlwaysDown,
+    }
+}
+
+fn from_julian(day: f64) -> Option<DateTime<Utc>> {
+    let millis = ((day - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    DateTime::from_timestamp_millis(millis)
+}
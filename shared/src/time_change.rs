@@ -0,0 +1,112 @@
+// Wall-clock changes (platform-agnostic)
+//
+// The wall clock can jump: a timezone change, an NTP correction, the user
+// setting the time by hand, a VM resuming. Anything that measures a
+// duration (timed pauses, debounces, poll pacing) runs on `Instant` or
+// `monotonic_ms`, so a jump can neither stretch a 30-minute pause to hours
+// nor end it early. Anything tied to the time of day (the schedule) has to
+// be re-evaluated instead: the platform calls `notify` on WM_TIMECHANGE
+// (Windows) or NSSystemClockDidChangeNotification (macOS), and loops that
+// care compare `generation` with the value they last saw.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::status_file;
+
+/// Added to `monotonic_ms` so it is never near zero, which callers keep as
+/// "never" and subtract debounce lengths from
+const BASE_MS: u64 = 24 * 60 * 60 * 1000;
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Bumped by every `notify`
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Milliseconds on a clock that never jumps or runs backwards, for storing
+/// in atomics where an `Instant` doesn't fit. Only differences mean
+/// anything.
+pub fn monotonic_ms() -> u64 {
+    BASE_MS + START.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// The system clock or timezone changed.
+pub fn notify() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    eprintln!("[time] system clock changed");
+    // paused_until is Unix time worked out from the time left, which
+    // hasn't changed, so it has to be recomputed
+    status_file::pause_changed();
+}
+
+/// Number of clock changes so far
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Held by tests that call `notify`, so they don't see each other's
+#[cfg(all(test, feature = "scheduler"))]
+pub(crate) static NOTIFY_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(all(test, feature = "scheduler"))]
+mod tests {
+    use super::*;
+    use crate::pause;
+    use crate::schedule::Clock;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use std::cell::Cell;
+
+    /// Wall clock that is set forward and back, announcing it like the
+    /// platform does
+    struct JumpingClock(Cell<DateTime<Utc>>);
+
+    impl JumpingClock {
+        fn jump(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+            notify();
+        }
+    }
+
+    impl Clock for JumpingClock {
+        type Tz = Utc;
+        fn now(&self) -> DateTime<Utc> {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn pause_expiry_follows_monotonic_ms() {
+        let _notify = NOTIFY_LOCK.lock().unwrap();
+        // status.json stays where it is
+        crate::journal::disable();
+        let started_wall = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+        let wall = JumpingClock(Cell::new(started_wall));
+        let generation = wall.generation();
+        // A 30-minute pause with a second left
+        let started = monotonic_ms() - (30 * 60 * 1000 - 1000);
+        let length = pause::start_at(started, 30);
+        let deadline = started + length.as_millis() as u64;
+        let wall_deadline = started_wall + Duration::seconds(1);
+
+        // Two hours ahead: a deadline on the wall clock would be long past
+        wall.jump(Duration::hours(2));
+        assert!(wall.now() > wall_deadline);
+        let before = monotonic_ms();
+        assert!(!pause::take_expired());
+        let left = pause::remaining().unwrap().as_millis() as u64;
+        let after = monotonic_ms();
+        assert!(deadline - after <= left && left <= deadline - before);
+
+        // Three hours back: on the wall clock it would run for hours more
+        wall.jump(Duration::hours(-3));
+        assert!(wall_deadline - wall.now() > Duration::hours(1));
+        while monotonic_ms() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(pause::take_expired());
+        assert_eq!(pause::remaining(), None);
+        assert!(!pause::take_expired());
+        assert!(wall.generation() >= generation + 2);
+    }
+}
@@ -0,0 +1,36 @@
+// Trace marks around dimming work (platform-agnostic)
+//
+// When a stutter or a dropped frame gets blamed on SaveMyEyes, a system
+// trace should show what the app was doing at that moment. Each platform
+// emits these marks into the OS tracing system: TraceLogging events on
+// Windows (ETW, viewed in WPA) and signposts on macOS (viewed in
+// Instruments). They use the same names on both, so a trace from either
+// reads the same. Nothing is written unless a trace session is recording,
+// and nothing goes to disk or the logs.
+
+use std::ffi::CStr;
+
+/// Something worth seeing on a trace timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// An overlay window created for a monitor
+    OverlayCreate,
+    /// A pass putting the overlays back on top
+    Reassert,
+    /// A gamma ramp written for a monitor
+    GammaUpdate,
+    /// One step of the HUD fade-out or the toggle fade
+    FadeFrame,
+}
+
+impl Mark {
+    /// Event name in the trace
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Mark::OverlayCreate => c"OverlayCreate",
+            Mark::Reassert => c"Reassert",
+            Mark::GammaUpdate => c"GammaUpdate",
+            Mark::FadeFrame => c"FadeFrame",
+        }
+    }
+}
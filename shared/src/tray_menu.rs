@@ -0,0 +1,151 @@
+// Configurable tray menu (platform-agnostic)
+//
+// `tray_menu` in config lists the tray menu entries top to bottom, so basic
+// users keep a short menu and power users can surface the actions they use
+// most. Entries:
+//   toggle, movie_mode, designer_mode  the usual switches
+//   presets                            fixed levels (20/40/60/80%)
+//   profiles                           saved and built-in profiles
+//   pause                              timed pauses (see pause.rs)
+//   presentation                       presentation mode (presentation.rs)
+//   recording                          show dimming in recordings (Windows)
+//   explain                            "Why is my screen dark?" (explain.rs)
+//   updates                            "Check for Updates…" (updater builds)
+//   separator, settings, quit
+// Settings and Quit are always added if missing so the menu can't lock the
+// user out. Each platform builds its native menu from `items`.
+
+use crate::config::AppConfig;
+
+/// Levels offered by the `presets` entry
+pub const PRESETS: [f32; 4] = [0.2, 0.4, 0.6, 0.8];
+
+/// One `tray_menu` entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayItem {
+    Toggle,
+    MovieMode,
+    DesignerMode,
+    Presets,
+    Profiles,
+    Pause,
+    Presentation,
+    Recording,
+    Explain,
+    Updates,
+    Separator,
+    Settings,
+    Quit,
+}
+
+impl TrayItem {
+    pub const ALL: [TrayItem; 13] = [
+        TrayItem::Toggle,
+        TrayItem::MovieMode,
+        TrayItem::DesignerMode,
+        TrayItem::Presets,
+        TrayItem::Profiles,
+        TrayItem::Pause,
+        TrayItem::Presentation,
+        TrayItem::Recording,
+        TrayItem::Explain,
+        TrayItem::Updates,
+        TrayItem::Separator,
+        TrayItem::Settings,
+        TrayItem::Quit,
+    ];
+
+    /// Config spelling of the entry
+    pub fn name(self) -> &'static str {
+        match self {
+            TrayItem::Toggle => "toggle",
+            TrayItem::MovieMode => "movie_mode",
+            TrayItem::DesignerMode => "designer_mode",
+            TrayItem::Presets => "presets",
+            TrayItem::Profiles => "profiles",
+            TrayItem::Pause => "pause",
+            TrayItem::Presentation => "presentation",
+            TrayItem::Recording => "recording",
+            TrayItem::Explain => "explain",
+            TrayItem::Updates => "updates",
+            TrayItem::Separator => "separator",
+            TrayItem::Settings => "settings",
+            TrayItem::Quit => "quit",
+        }
+    }
+
+    /// Parse a config entry (case-insensitive, '-' or ' ' for '_')
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+        Self::ALL.into_iter().find(|item| item.name() == text)
+    }
+}
+
+/// Menu each platform shipped with before it was configurable
+pub fn default_items() -> Vec<TrayItem> {
+    let mut items = vec![
+        TrayItem::Toggle,
+        TrayItem::MovieMode,
+        TrayItem::DesignerMode,
+        TrayItem::Separator,
+        TrayItem::Explain,
+        TrayItem::Settings,
+    ];
+    if cfg!(target_os = "macos") {
+        items.push(TrayItem::Updates);
+    }
+    items.extend([TrayItem::Separator, TrayItem::Quit]);
+    items
+}
+
+/// Default value of config `tray_menu`
+pub fn default_names() -> Vec<String> {
+    default_items()
+        .into_iter()
+        .map(|item| item.name().to_string())
+        .collect()
+}
+
+/// Entries to show for `config`, top to bottom. Unknown names are skipped,
+/// Settings and Quit are appended if missing, entries with nothing to show
+/// (updates without the updater, recording on macOS) are dropped, and
+/// separators are collapsed so none leads, trails or doubles up.
+pub fn items(config: &AppConfig) -> Vec<TrayItem> {
+    let mut listed: Vec<TrayItem> = config
+        .tray_menu
+        .iter()
+        .filter_map(|name| TrayItem::parse(name))
+        .filter(|item| *item != TrayItem::Updates || crate::updater::ENABLED)
+        .filter(|item| *item != TrayItem::Recording || !cfg!(target_os = "macos"))
+        .collect();
+    if !listed.contains(&TrayItem::Settings) {
+        listed.push(TrayItem::Separator);
+        listed.push(TrayItem::Settings);
+    }
+    if !listed.contains(&TrayItem::Quit) {
+        listed.push(TrayItem::Separator);
+        listed.push(TrayItem::Quit);
+    }
+
+    let mut items: Vec<TrayItem> = Vec::with_capacity(listed.len());
+    for item in listed {
+        if item == TrayItem::Separator
+            && items.last().is_none_or(|last| *last == TrayItem::Separator)
+        {
+            continue;
+        }
+        if item != TrayItem::Separator && items.contains(&item) {
+            continue;
+        }
+        items.push(item);
+    }
+    if items.last() == Some(&TrayItem::Separator) {
+        items.pop();
+    }
+    items
+}
+
+/// Whether `level` is the current dimming level of `config`
+pub fn is_current_preset(config: &AppConfig, level: f32) -> bool {
+    config.is_enabled && (config.opacity - level).abs() < 0.0005
+}
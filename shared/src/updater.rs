@@ -1,9 +1,59 @@
 // HTTP-based update checker against GitHub releases (platform-agnostic)
+//
+// The automatic startup check respects the user's answer to earlier
+// prompts (`UpdateSnooze`): a skipped version is never offered again
+// (newer ones are), and "Later" hides the prompt for a while. The wait
+// grows with each consecutive "Later" (1, 3, 7, then 14 days), so someone
+// who keeps postponing is asked less and less often. "Check for Updates"
+// always asks.
+//
+// The startup check goes quiet while net.rs's breaker for the release feed
+// is open (after repeated failures); "Check for Updates" still gets through.
+//
+// Releases can be rolled out in stages. A release whose JSON carries
+// `"rollout_percent": 25` (a custom feed, see `set_endpoint`), or whose
+// notes have a line starting "Rollout: 25%" (GitHub), is only offered to
+// the 25% of machines whose bucket is below 25; values over 100 count as
+// 100. The bucket (0-99) is hashed from the machine's id (MachineGuid on
+// Windows, IOPlatformUUID on macOS, /etc/machine-id on Linux), so a
+// machine stays in or out as the percentage grows, and nothing about it
+// leaves the machine. Releases without either are offered to everyone.
+// The startup check says nothing about a release this machine isn't in
+// yet; "Check for Updates" reports it as `held_back` so the user can get
+// it now anyway.
+//
+// Builds without the `updater` feature keep these types (so config files
+// stay compatible) but never touch the network: checks and downloads fail
+// with `NOT_BUILT`, and the platform UIs hide their update controls.
 
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "updater")]
+use crate::net;
 
 static CHECKING: AtomicBool = AtomicBool::new(false);
 
+/// Whether this build includes the update checker
+pub const ENABLED: bool = cfg!(feature = "updater");
+
+/// Error of checks and downloads in builds without the updater
+pub const NOT_BUILT: &str = "Updates are not included in this build";
+
+#[cfg(feature = "updater")]
+const DEFAULT_ENDPOINT: &str = "https://api.github.com/repos/KDSPL/savemyeyes/releases/latest";
+
+/// Release feed override (config `update_endpoint`)
+static ENDPOINT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Query `endpoint` instead of GitHub. It must serve the same JSON as the
+/// GitHub "latest release" API (tag_name, html_url, assets).
+pub fn set_endpoint(endpoint: Option<&str>) {
+    *ENDPOINT.lock().unwrap() = endpoint.filter(|e| !e.trim().is_empty()).map(String::from);
+}
+
 /// Application version — single source of truth
 pub const APP_VERSION: &str = "0.9.5";
 
@@ -15,35 +65,122 @@ pub enum UpdateResult {
         #[allow(dead_code)]
         url: String,
         download_url: String,
+        /// Rollout percentage of a staged release this machine isn't in
+        /// yet (only from `check_for_update_now`)
+        held_back: Option<u8>,
     },
     #[allow(dead_code)]
     Error(String),
 }
 
+/// Days the automatic prompt waits after the 1st, 2nd, 3rd... "Later"
+const REMIND_DAYS: [u64; 4] = [1, 3, 7, 14];
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Answer to an update prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateChoice {
+    Install,
+    /// Ask again after `UpdateSnooze::remind_days`
+    Later,
+    /// Never offer this version again
+    Skip,
+}
+
+/// What the user said to earlier update prompts (persisted in config)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSnooze {
+    /// Version the user chose to skip
+    pub skipped_version: Option<String>,
+    /// No automatic prompt before this Unix time (seconds)
+    pub remind_after: Option<u64>,
+    /// Consecutive "Later" answers, for the growing wait
+    pub declines: u32,
+}
+
+impl UpdateSnooze {
+    /// Whether the automatic check may offer `version` at `now` (Unix seconds)
+    pub fn should_prompt(&self, version: &str, now: u64) -> bool {
+        if let Some(skipped) = &self.skipped_version {
+            if !version_newer(version, skipped) {
+                return false;
+            }
+        }
+        self.remind_after.is_none_or(|after| now >= after)
+    }
+
+    /// Days the next "Later" will wait
+    pub fn remind_days(&self) -> u64 {
+        REMIND_DAYS[(self.declines as usize).min(REMIND_DAYS.len() - 1)]
+    }
+
+    /// Remember the answer given for `version` at `now`
+    pub fn record(&mut self, choice: UpdateChoice, version: &str, now: u64) {
+        match choice {
+            UpdateChoice::Install => *self = Self::default(),
+            UpdateChoice::Later => {
+                self.remind_after = Some(now + self.remind_days() * DAY_SECS);
+                self.declines += 1;
+            }
+            UpdateChoice::Skip => {
+                self.skipped_version = Some(version.to_string());
+                self.remind_after = None;
+                self.declines = 0;
+            }
+        }
+    }
+}
+
+/// Current Unix time in seconds
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Check for updates by fetching the latest release info from GitHub.
 /// `asset_suffix` should be ".exe" on Windows, ".dmg" on macOS, etc.
-/// This runs synchronously — call from a background thread.
+/// This runs synchronously — call from a background thread. For the
+/// automatic check; see `check_for_update_now`.
 pub fn check_for_update(current_version: &str, asset_suffix: &str) -> UpdateResult {
+    check(current_version, asset_suffix, false)
+}
+
+/// `check_for_update` for "Check for Updates": sent even while repeated
+/// failures hold the automatic check back.
+pub fn check_for_update_now(current_version: &str, asset_suffix: &str) -> UpdateResult {
+    check(current_version, asset_suffix, true)
+}
+
+fn check(current_version: &str, asset_suffix: &str, asked: bool) -> UpdateResult {
     if CHECKING.swap(true, Ordering::SeqCst) {
         return UpdateResult::Error("Already checking".into());
     }
 
-    let result = do_check(current_version, asset_suffix);
+    let result = do_check(current_version, asset_suffix, asked);
     CHECKING.store(false, Ordering::SeqCst);
     result
 }
 
-fn do_check(current_version: &str, asset_suffix: &str) -> UpdateResult {
-    let url = "https://api.github.com/repos/KDSPL/savemyeyes/releases/latest";
+#[cfg(not(feature = "updater"))]
+fn do_check(_current_version: &str, _asset_suffix: &str, _asked: bool) -> UpdateResult {
+    UpdateResult::Error(NOT_BUILT.into())
+}
 
-    let response = match ureq::get(url)
-        .set("User-Agent", "SaveMyEyes-Updater")
-        .set("Accept", "application/json")
-        .call()
-    {
-        Ok(r) => r,
-        Err(e) => {
-            let err_str = e.to_string();
+#[cfg(feature = "updater")]
+fn do_check(current_version: &str, asset_suffix: &str, asked: bool) -> UpdateResult {
+    let url = ENDPOINT
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+
+    let ask = if asked { net::Ask::User } else { net::Ask::Background };
+    let body = match net::get_text(&url, "application/json", ask) {
+        Ok(b) => b,
+        Err(net::Failure::Request(err_str)) => {
             if err_str.contains("404")
                 || err_str.contains("network")
                 || err_str.contains("connect")
@@ -52,11 +189,11 @@ fn do_check(current_version: &str, asset_suffix: &str) -> UpdateResult {
             }
             return UpdateResult::Error(format!("Request failed: {}", err_str));
         }
-    };
-
-    let body = match response.into_string() {
-        Ok(b) => b,
-        Err(e) => return UpdateResult::Error(format!("Failed to read response: {}", e)),
+        Err(net::Failure::Body(e)) => {
+            return UpdateResult::Error(format!("Failed to read response: {}", e))
+        }
+        // Failing lately; the next startup after that tries again
+        Err(net::Failure::Skipped(_)) => return UpdateResult::NoUpdate,
     };
 
     let tag = extract_json_string(&body, "tag_name").unwrap_or_default();
@@ -76,14 +213,121 @@ fn do_check(current_version: &str, asset_suffix: &str) -> UpdateResult {
         )
     });
 
-    if version_newer(latest_version, current_version) {
-        UpdateResult::UpdateAvailable {
-            version: latest_version.to_string(),
-            url: html_url,
-            download_url,
+    if !version_newer(latest_version, current_version) {
+        return UpdateResult::NoUpdate;
+    }
+    let percent = rollout_percent(&body);
+    let held_back = (!in_rollout(machine_bucket(), percent)).then_some(percent);
+    if held_back.is_some() && !asked {
+        eprintln!(
+            "[updater] v{} is rolling out to {}% of machines, not this one yet",
+            latest_version, percent
+        );
+        return UpdateResult::NoUpdate;
+    }
+    UpdateResult::UpdateAvailable {
+        version: latest_version.to_string(),
+        url: html_url,
+        download_url,
+        held_back,
+    }
+}
+
+/// Share of machines a release is offered to (0-100): its
+/// `rollout_percent`, a "Rollout: N%" line in its notes, or 100. Larger
+/// values count as 100.
+pub fn rollout_percent(json: &str) -> u8 {
+    let percent = extract_json_number(json, "rollout_percent").or_else(|| notes_rollout(json));
+    percent.map_or(100, |p| p.min(100) as u8)
+}
+
+/// N of the first line in the release notes that starts with "Rollout: N%".
+/// The notes are still JSON-escaped, so a line starts after a `\n` escape
+/// or at the opening quote of `"body"`; a "Rollout:" anywhere else (in a
+/// sentence, or another field) doesn't count.
+fn notes_rollout(json: &str) -> Option<u64> {
+    let marker = "Rollout:";
+    json.match_indices(marker).find_map(|(pos, _)| {
+        let before = json[..pos].trim_end_matches([' ', '\t']);
+        let body_start = before
+            .strip_suffix('"')
+            .and_then(|key| key.trim_end().strip_suffix(':'))
+            .is_some_and(|key| key.trim_end().ends_with("\"body\""));
+        if !body_start && !before.ends_with("\\n") && !before.ends_with('\n') {
+            return None;
         }
-    } else {
-        UpdateResult::NoUpdate
+        let after = json[pos + marker.len()..].trim_start_matches([' ', '\t']);
+        let digits = after.find(|c: char| !c.is_ascii_digit())?;
+        if digits == 0 || !after[digits..].starts_with('%') {
+            return None;
+        }
+        // Too many digits to parse is still more than 100
+        Some(after[..digits].parse().unwrap_or(u64::MAX))
+    })
+}
+
+/// Whether a machine in `bucket` (0-99) gets a release rolled out to
+/// `percent` of machines
+pub fn in_rollout(bucket: u8, percent: u8) -> bool {
+    bucket < percent
+}
+
+/// This machine's rollout bucket (0-99), the same on every run
+pub fn machine_bucket() -> u8 {
+    static BUCKET: OnceLock<u8> = OnceLock::new();
+    *BUCKET.get_or_init(|| {
+        // Without a machine id, the home folder still keeps it stable
+        let id = machine_id().unwrap_or_else(|| {
+            dirs::home_dir()
+                .map(|home| home.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        bucket_of(&id)
+    })
+}
+
+/// Rollout bucket (0-99) of the machine with id `id`
+fn bucket_of(id: &str) -> u8 {
+    (crate::monitor_id::fnv1a(&["rollout", id]) % 100) as u8
+}
+
+/// The OS's id of this installation, if it can be read
+fn machine_id() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: no console flashing up
+        let output = std::process::Command::new("reg")
+            .args([
+                "query",
+                r"HKLM\SOFTWARE\Microsoft\Cryptography",
+                "/v",
+                "MachineGuid",
+            ])
+            .creation_flags(0x0800_0000)
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let line = text.lines().find(|l| l.contains("MachineGuid"))?;
+        line.split_whitespace().last().map(String::from)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("ioreg")
+            .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let line = text.lines().find(|l| l.contains("IOPlatformUUID"))?;
+        line.rsplit('"').nth(1).map(String::from)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .map(|id| id.trim().to_string())
+            .find(|id| !id.is_empty())
     }
 }
 
@@ -123,41 +367,37 @@ pub fn extract_json_string(json: &str, key: &str) -> Option<String> {
     Some(after_quote[..end].to_string())
 }
 
+/// Extract a non-negative integer value from JSON by key name
+pub fn extract_json_number(json: &str, key: &str) -> Option<u64> {
+    let search = format!("\"{}\"", key);
+    let pos = json.find(&search)?;
+    let after_key = &json[pos + search.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+    let end = after_colon
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after_colon.len());
+    after_colon[..end].parse().ok()
+}
+
 /// Returns true if `a` is newer than `b` (simple semver comparison)
-pub fn version_newer(a: &str, b: &str) -> bool {
-    let parse =
-        |v: &str| -> Vec<u32> { v.split('.').filter_map(|s| s.parse::<u32>().ok()).collect() };
-    let va = parse(a);
-    let vb = parse(b);
-    for i in 0..va.len().max(vb.len()) {
-        let ca = va.get(i).copied().unwrap_or(0);
-        let cb = vb.get(i).copied().unwrap_or(0);
-        if ca > cb {
-            return true;
-        }
-        if ca < cb {
-            return false;
-        }
-    }
-    false
+pub use savemyeyes_core::version::newer as version_newer;
+
+/// Download a file from a URL to a temp path. Returns the path on success.
+#[cfg(not(feature = "updater"))]
+pub fn download_to_temp(
+    _download_url: &str,
+    _filename: &str,
+) -> Result<std::path::PathBuf, String> {
+    Err(NOT_BUILT.into())
 }
 
 /// Download a file from a URL to a temp path. Returns the path on success.
+#[cfg(feature = "updater")]
 pub fn download_to_temp(download_url: &str, filename: &str) -> Result<std::path::PathBuf, String> {
-    let response = ureq::get(download_url)
-        .set("User-Agent", "SaveMyEyes-Updater")
-        .call()
-        .map_err(|e| format!("Download failed: {}", e))?;
-
     let temp_dir = std::env::temp_dir();
     let temp_path = temp_dir.join(filename);
-
-    let mut reader = response.into_reader();
-    let mut file = std::fs::File::create(&temp_path)
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
-    std::io::copy(&mut reader, &mut file)
-        .map_err(|e| format!("Failed to write update: {}", e))?;
-
+    // Only after the user chose to install
+    net::download(download_url, &temp_path, net::Ask::User)?;
     Ok(temp_path)
 }
 
@@ -178,10 +418,94 @@ pub fn open_url(url: &str) {
     }
     #[cfg(target_os = "macos")]
     {
-        let _ = std::process::Command::new("open").arg(url).spawn();
+        if let Err(e) = std::process::Command::new("open").arg(url).spawn() {
+            eprintln!("[updater] could not run open for {}: {}", url, e);
+        }
     }
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
         let _ = std::process::Command::new("xdg-open").arg(url).spawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollout_from_the_json_field() {
+        assert_eq!(
+            rollout_percent(r#"{"tag_name":"v2.0.0","rollout_percent": 25}"#),
+            25
+        );
+        assert_eq!(rollout_percent(r#"{"rollout_percent":0}"#), 0);
+        assert_eq!(rollout_percent(r#"{"rollout_percent":250}"#), 100);
+        // The field wins over the notes
+        assert_eq!(
+            rollout_percent(r#"{"rollout_percent":40,"body":"Rollout: 10%"}"#),
+            40
+        );
+        assert_eq!(
+            rollout_percent(r#"{"tag_name":"v2.0.0","body":"Fixes"}"#),
+            100
+        );
+    }
+
+    #[test]
+    fn rollout_from_a_line_in_the_notes() {
+        assert_eq!(rollout_percent(r#"{"body":"Rollout: 25%\r\nFixes"}"#), 25);
+        assert_eq!(rollout_percent(r#"{"body": "Fixes\n\nRollout:10%"}"#), 10);
+        assert_eq!(
+            rollout_percent(r#"{"body":"Fixes\n  Rollout: 5 %\nRollout: 7%"}"#),
+            7
+        );
+        assert_eq!(rollout_percent(r#"{"body":"Rollout: 150%"}"#), 100);
+        assert_eq!(
+            rollout_percent(r#"{"body":"Rollout: 99999999999999999999999%"}"#),
+            100
+        );
+    }
+
+    #[test]
+    fn rollout_mentions_off_a_line_start_dont_count() {
+        for json in [
+            r#"{"body":"Staged. Rollout: 10% first"}"#,
+            r#"{"name":"Rollout: 10%","body":"Fixes"}"#,
+            r#"{"body":"Rollout: soon\nRollout: %"}"#,
+            r#"{"body":"Rollout: -5%"}"#,
+        ] {
+            assert_eq!(rollout_percent(json), 100, "{}", json);
+        }
+    }
+
+    #[test]
+    fn buckets_are_stable() {
+        let id = "4c4c4544-0042-3510-8052-b4c04f4e3732";
+        assert_eq!(bucket_of(id), bucket_of(id));
+        assert_ne!(bucket_of(id), bucket_of("another machine"));
+        assert_eq!(machine_bucket(), machine_bucket());
+        assert!(machine_bucket() < 100);
+        // A machine in at 25% stays in as the rollout grows
+        for bucket in 0..100 {
+            let first = (0..=100).find(|&p| in_rollout(bucket, p)).unwrap();
+            assert!((first..=100).all(|p| in_rollout(bucket, p)));
+        }
+        assert!((0..100).all(|b| !in_rollout(b, 0) && in_rollout(b, 100)));
+    }
+
+    #[test]
+    fn buckets_spread_evenly() {
+        let mut counts = [0u32; 100];
+        for n in 0..100_000 {
+            counts[bucket_of(&format!("machine-{}", n)) as usize] += 1;
+        }
+        // 1000 machines per bucket on average
+        assert!(
+            counts.iter().all(|&c| (800..1200).contains(&c)),
+            "{:?}",
+            counts
+        );
+        let in_quarter: u32 = counts[..25].iter().sum();
+        assert!((24_000..26_000).contains(&in_quarter), "{}", in_quarter);
+    }
+}
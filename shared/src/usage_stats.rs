@@ -0,0 +1,212 @@
+// Local usage stats and the weekly summary (platform-agnostic)
+//
+// stats.json, next to config.json, counts per local day how long the screen
+// was dimmed and at which level. Nothing leaves the machine. status_file.rs
+// passes on every state it reports, so the stats follow the same on/off,
+// pause and level changes status.json does: the span since the last change
+// is credited when the next one comes, split at midnight, and written out at
+// most once a minute and on exit. Days older than KEEP_DAYS are dropped.
+//
+// With `weekly_summary` on, the platform asks `due_summary` once a minute;
+// on the chosen day, from the chosen time on, it returns the text of one
+// notification per week ("This week: dimmed for 38h, 42% on average").
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeDelta, TimeZone, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::config_path;
+use crate::dimming::percent_label;
+use crate::night_boost::parse_time;
+
+/// Days kept in stats.json
+const KEEP_DAYS: i64 = 56;
+/// Least time between two writes of stats.json while dimming runs
+const SAVE_EVERY: Duration = Duration::from_secs(60);
+
+/// Weekly summary notification. Off by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeeklySummary {
+    pub enabled: bool,
+    /// Day of the week it is shown ("Sun", "Monday", ...)
+    pub day: String,
+    /// Local time it is shown from ("HH:MM")
+    pub time: String,
+}
+
+impl Default for WeeklySummary {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            day: "Sun".to_string(),
+            time: "18:00".to_string(),
+        }
+    }
+}
+
+/// Parse a day of the week ("Sun", "sunday")
+pub fn parse_day(text: &str) -> Option<Weekday> {
+    text.trim().parse().ok()
+}
+
+/// One local day
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Day {
+    /// Seconds the screen was dimmed
+    dimmed_secs: u64,
+    /// Sum of level × seconds, for the average
+    level_secs: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Stats {
+    days: BTreeMap<NaiveDate, Day>,
+    /// Day the last weekly summary was shown
+    last_summary: Option<NaiveDate>,
+}
+
+struct Tracker {
+    /// Loaded on first use
+    stats: Option<Stats>,
+    /// Since when the screen is dimmed, and at which level
+    since: Option<(DateTime<Local>, f32)>,
+    saved_at: Option<Instant>,
+}
+
+static TRACKER: Mutex<Tracker> = Mutex::new(Tracker {
+    stats: None,
+    since: None,
+    saved_at: None,
+});
+
+pub fn stats_path() -> PathBuf {
+    config_path().with_file_name("stats.json")
+}
+
+fn load() -> Stats {
+    fs::read_to_string(stats_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(stats: &Stats) {
+    let path = stats_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_string_pretty(stats).unwrap_or_default();
+    if fs::write(&tmp, data).is_ok() {
+        let _ = fs::rename(&tmp, &path);
+    }
+}
+
+/// Local midnight starting the day after `time`
+fn next_midnight(time: DateTime<Local>) -> Option<DateTime<Local>> {
+    let next = time.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?;
+    Local.from_local_datetime(&next).earliest()
+}
+
+/// Add `level` from `from` to `to` to the days it falls on
+fn credit(stats: &mut Stats, mut from: DateTime<Local>, to: DateTime<Local>, level: f32) {
+    while from < to {
+        let end = next_midnight(from).map_or(to, |midnight| midnight.min(to));
+        let secs = (end - from).num_seconds().max(0) as u64;
+        let day = stats.days.entry(from.date_naive()).or_default();
+        day.dimmed_secs += secs;
+        day.level_secs += level as f64 * secs as f64;
+        if end <= from {
+            break;
+        }
+        from = end;
+    }
+}
+
+impl Tracker {
+    /// Credit the span up to `now`, keeping the current level running
+    fn flush(&mut self, now: DateTime<Local>) -> &mut Stats {
+        let stats = self.stats.get_or_insert_with(load);
+        if let Some((since, level)) = self.since {
+            credit(stats, since, now, level);
+            self.since = Some((now, level));
+        }
+        let oldest = now.date_naive() - TimeDelta::days(KEEP_DAYS);
+        stats.days.retain(|day, _| *day > oldest);
+        stats
+    }
+}
+
+/// The screen is now dimmed at `level`, or not dimmed (None).
+pub(crate) fn sample(level: Option<f32>) {
+    let now = Local::now();
+    let mut tracker = TRACKER.lock().unwrap();
+    let was_dimmed = tracker.since.is_some();
+    if was_dimmed || level.is_some() {
+        tracker.flush(now);
+    }
+    tracker.since = level.map(|level| (now, level));
+    // Write when dimming stops, and now and then while it runs
+    let due = tracker.saved_at.is_none_or(|at| at.elapsed() >= SAVE_EVERY);
+    if was_dimmed && (level.is_none() || due) {
+        tracker.saved_at = Some(Instant::now());
+        if let Some(stats) = tracker.stats.as_ref() {
+            save(stats);
+        }
+    }
+}
+
+/// Dimmed time and average level over the 7 days up to today
+fn week_text(stats: &Stats, today: NaiveDate) -> String {
+    let first = today - TimeDelta::days(6);
+    let (secs, level_secs) = stats
+        .days
+        .range(first..=today)
+        .fold((0u64, 0f64), |(secs, level_secs), (_, day)| {
+            (secs + day.dimmed_secs, level_secs + day.level_secs)
+        });
+    if secs < 60 {
+        return "This week: the screen wasn't dimmed.".to_string();
+    }
+    let time = if secs < 3600 {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{}h", (secs as f64 / 3600.0).round() as u64)
+    };
+    let average = (level_secs / secs as f64) as f32;
+    format!(
+        "This week: dimmed for {}, {} on average.",
+        time,
+        percent_label(average)
+    )
+}
+
+/// Text of the weekly summary if it is due now (at most once a week).
+/// Marks it shown.
+pub fn due_summary(rule: &WeeklySummary) -> Option<String> {
+    if !rule.enabled {
+        return None;
+    }
+    let now = Local::now();
+    if Some(now.weekday()) != parse_day(&rule.day)
+        || now.hour() * 60 + now.minute() < parse_time(&rule.time)?
+    {
+        return None;
+    }
+    let today = now.date_naive();
+    let mut tracker = TRACKER.lock().unwrap();
+    let stats = tracker.flush(now);
+    if stats.last_summary == Some(today) {
+        return None;
+    }
+    stats.last_summary = Some(today);
+    let text = week_text(stats, today);
+    save(stats);
+    Some(text)
+}
@@ -1,3 +1,6 @@
 fn main() {
-    let _ = embed_resource::compile("resources/app.rc", embed_resource::NONE);
+    // The icon and manifest only go into Windows builds (not headless ones)
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        let _ = embed_resource::compile("resources/app.rc", embed_resource::NONE);
+    }
 }
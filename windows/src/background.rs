@@ -0,0 +1,91 @@
+// Scheduling hygiene for background work.
+//
+// SaveMyEyes sits in the tray all day, so its background threads (the
+// overlay z-order/watchdog poll, the brightness poll, update checks; every
+// thread started through shutdown::spawn) shouldn't compete with what the
+// user is doing or show up as a noisy app in latency and battery analyzers.
+// Each one lowers itself when it starts:
+//   • below-normal priority
+//   • EcoQoS (execution speed throttling), which lets Windows run it on
+//     efficiency cores and line its wake-ups up with other work
+// The threads keep sleeping on condvars (shutdown.rs, poll_pace.rs) rather
+// than waitable timers, since those sleeps must also end the moment
+// activity or shutdown wakes them. The hotkey thread keeps normal priority.
+//
+// For the process as a whole, Windows is asked to ignore timer resolution
+// requests (PROCESS_POWER_THROTTLING_IGNORE_TIMER_RESOLUTION, Windows 11),
+// so nothing loaded into the process can raise the system timer rate on
+// its behalf; the app itself never calls timeBeginPeriod. Older Windows
+// versions refuse the unknown flags, which is logged and otherwise ignored.
+
+#[cfg(windows)]
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetCurrentThread, ProcessPowerThrottling, SetProcessInformation,
+    SetThreadInformation, SetThreadPriority, ThreadPowerThrottling,
+    PROCESS_POWER_THROTTLING_CURRENT_VERSION, PROCESS_POWER_THROTTLING_IGNORE_TIMER_RESOLUTION,
+    PROCESS_POWER_THROTTLING_STATE, THREAD_POWER_THROTTLING_CURRENT_VERSION,
+    THREAD_POWER_THROTTLING_EXECUTION_SPEED, THREAD_POWER_THROTTLING_STATE,
+    THREAD_PRIORITY_BELOW_NORMAL,
+};
+
+use savemyeyes_shared::shutdown;
+
+/// Set up the process and have every background thread lower itself.
+pub fn init() {
+    ignore_timer_resolution();
+    shutdown::set_thread_start(lower_current_thread);
+}
+
+/// Ask Windows to ignore timer resolution requests from this process
+#[cfg(windows)]
+fn ignore_timer_resolution() {
+    let state = PROCESS_POWER_THROTTLING_STATE {
+        Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: PROCESS_POWER_THROTTLING_IGNORE_TIMER_RESOLUTION,
+        StateMask: PROCESS_POWER_THROTTLING_IGNORE_TIMER_RESOLUTION,
+    };
+    let result = unsafe {
+        SetProcessInformation(
+            GetCurrentProcess(),
+            ProcessPowerThrottling,
+            &state as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<PROCESS_POWER_THROTTLING_STATE>() as u32,
+        )
+    };
+    if let Err(e) = result {
+        eprintln!(
+            "[background] timer resolution throttling unavailable: {}",
+            e
+        );
+    }
+}
+
+/// Below-normal priority and EcoQoS for the calling thread
+#[cfg(windows)]
+fn lower_current_thread() {
+    let state = THREAD_POWER_THROTTLING_STATE {
+        Version: THREAD_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: THREAD_POWER_THROTTLING_EXECUTION_SPEED,
+        StateMask: THREAD_POWER_THROTTLING_EXECUTION_SPEED,
+    };
+    unsafe {
+        let thread = GetCurrentThread();
+        if let Err(e) = SetThreadPriority(thread, THREAD_PRIORITY_BELOW_NORMAL) {
+            eprintln!("[background] SetThreadPriority failed: {}", e);
+        }
+        // EcoQoS needs Windows 10 1709 or later
+        let _ = SetThreadInformation(
+            thread,
+            ThreadPowerThrottling,
+            &state as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<THREAD_POWER_THROTTLING_STATE>() as u32,
+        );
+    }
+}
+
+// Headless builds leave the process and its threads as they are
+#[cfg(not(windows))]
+fn ignore_timer_resolution() {}
+
+#[cfg(not(windows))]
+fn lower_current_thread() {}
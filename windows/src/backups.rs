@@ -0,0 +1,96 @@
+// Restoring a config backup (shared backups.rs).
+//
+// "Restore settings from backup…" in the Settings tab opens a menu of the
+// kept backups at the cursor. Picking one previews what would change in a
+// message box; confirming saves it as config.json and restarts SaveMyEyes
+// with --restored, the same hand-over as the elevated relaunch: the new
+// instance waits for this one to release the single-instance mutex.
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, POINT};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreatePopupMenu, DestroyMenu, GetCursorPos, MessageBoxW, TrackPopupMenu, IDYES,
+    MB_ICONQUESTION, MB_YESNO, MF_STRING, TPM_LEFTALIGN, TPM_RETURNCMD, TPM_TOPALIGN,
+};
+
+use savemyeyes_shared::backups::{self, Backup, RESTORED_ARG};
+use savemyeyes_shared::config::AppConfig;
+
+/// Most changed settings listed in the preview
+const MAX_PREVIEW_LINES: usize = 12;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Let the user pick one of `list` from a menu at the cursor.
+pub fn pick(hwnd: HWND, list: &[Backup]) -> Option<Backup> {
+    unsafe {
+        let menu = CreatePopupMenu().ok()?;
+        for (i, backup) in list.iter().enumerate() {
+            let label = wide(&backup.label());
+            let _ = AppendMenuW(menu, MF_STRING, i + 1, PCWSTR(label.as_ptr()));
+        }
+        let mut pt = POINT::default();
+        let _ = GetCursorPos(&mut pt);
+        let picked = TrackPopupMenu(
+            menu,
+            TPM_LEFTALIGN | TPM_TOPALIGN | TPM_RETURNCMD,
+            pt.x,
+            pt.y,
+            Some(0),
+            hwnd,
+            None,
+        );
+        let _ = DestroyMenu(menu);
+        // 0 = dismissed
+        let index = (picked.0 as usize).checked_sub(1)?;
+        list.get(index).cloned()
+    }
+}
+
+/// Show what restoring `backup` over `current` changes and ask to go ahead.
+pub fn confirm(hwnd: HWND, current: &AppConfig, backup: &Backup) -> Result<bool, String> {
+    let changes = backups::differences(current, backup)?;
+    let mut message = format!("Restore the settings from {}?\n\n", backup.label());
+    if changes.is_empty() {
+        message.push_str("They match your current settings.");
+    } else {
+        message.push_str("Changes:\n");
+        for line in changes.iter().take(MAX_PREVIEW_LINES) {
+            message.push_str(&format!("\u{2022} {}\n", line));
+        }
+        if changes.len() > MAX_PREVIEW_LINES {
+            let more = changes.len() - MAX_PREVIEW_LINES;
+            message.push_str(&format!("\u{2022} and {} more\n", more));
+        }
+    }
+    message.push_str(
+        "\nSaveMyEyes restarts to apply them. Your current settings are backed up first.",
+    );
+    let msg_wide = wide(&message);
+    let title_wide = wide("Restore Settings");
+    unsafe {
+        Ok(MessageBoxW(
+            Some(hwnd),
+            PCWSTR(msg_wide.as_ptr()),
+            PCWSTR(title_wide.as_ptr()),
+            MB_YESNO | MB_ICONQUESTION,
+        ) == IDYES)
+    }
+}
+
+/// Start a new instance with RESTORED_ARG. The caller quits on success.
+pub fn relaunch() -> bool {
+    let exe = match std::env::current_exe() {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+    match std::process::Command::new(exe).arg(RESTORED_ARG).spawn() {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("[backups] could not restart: {}", e);
+            false
+        }
+    }
+}
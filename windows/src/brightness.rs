@@ -0,0 +1,186 @@
+// Only dim bright panels (shared brightness_rule.rs) on Windows.
+//
+// Laptop panels report their backlight level through WMI (root\WMI
+// WmiMonitorBrightness, 0–100); external monitors don't, so the rule leaves
+// them dimmed. While the rule is on, a poll thread reads the levels every
+// POLL_INTERVAL and hands the monitors the rule leaves undimmed to the
+// overlay. WMI names panels by device instance
+// ("DISPLAY\BOE0867\4&2a2b4c6d&0&UID8388688_0"), which is matched against
+// the monitor device path used as the stable id in monitors.rs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use windows::core::{w, BSTR};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoSetProxyBlanket, CLSCTX_INPROC_SERVER,
+    COINIT_MULTITHREADED, EOAC_NONE, RPC_C_AUTHN_LEVEL_CALL, RPC_C_IMP_LEVEL_IMPERSONATE,
+};
+use windows::Win32::System::Variant::VARIANT;
+use windows::Win32::System::Wmi::{
+    IWbemClassObject, IWbemLocator, IWbemServices, WbemLocator, WBEM_FLAG_FORWARD_ONLY,
+    WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_INFINITE,
+};
+
+use savemyeyes_shared::brightness_rule::BrightnessRule;
+use savemyeyes_shared::shutdown;
+
+use crate::{monitors, overlay};
+
+/// How often the poll thread reads the panel brightness
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// RPC_C_AUTHN_WINNT / RPC_C_AUTHZ_NONE (Win32_System_Rpc isn't enabled)
+const AUTHN_WINNT: u32 = 10;
+const AUTHZ_NONE: u32 = 0;
+
+static RULE: Mutex<Option<BrightnessRule>> = Mutex::new(None);
+/// Monitor indices the rule left undimmed at the last check
+static UNDIMMED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// Apply the rule from config.
+pub fn apply(rule: &BrightnessRule) {
+    *RULE.lock().unwrap() = Some(rule.clone());
+    if !rule.enabled {
+        set_undimmed(Vec::new());
+    } else if !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("brightness-poll", poll);
+    }
+}
+
+fn poll() {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+    let services = match connect() {
+        Ok(services) => services,
+        Err(e) => {
+            eprintln!("[brightness] cannot reach WMI: {}", e);
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+    };
+    loop {
+        let rule = RULE.lock().unwrap().clone();
+        let Some(rule) = rule.filter(|r| r.enabled) else {
+            break;
+        };
+        check(&services, &rule);
+        if !shutdown::sleep(POLL_INTERVAL) {
+            // Quitting: the overlays are about to go, leave them be
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    set_undimmed(Vec::new());
+}
+
+fn connect() -> windows::core::Result<IWbemServices> {
+    unsafe {
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)?;
+        let services = locator.ConnectServer(
+            &BSTR::from("ROOT\\WMI"),
+            &BSTR::new(),
+            &BSTR::new(),
+            &BSTR::new(),
+            0,
+            &BSTR::new(),
+            None,
+        )?;
+        CoSetProxyBlanket(
+            &services,
+            AUTHN_WINNT,
+            AUTHZ_NONE,
+            None,
+            RPC_C_AUTHN_LEVEL_CALL,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            None,
+            EOAC_NONE,
+        )?;
+        Ok(services)
+    }
+}
+
+/// Brightness (0.0–1.0) of every panel that reports one, by WMI instance name
+fn read(services: &IWbemServices) -> Vec<(String, f32)> {
+    let mut levels = Vec::new();
+    unsafe {
+        let Ok(rows) = services.ExecQuery(
+            &BSTR::from("WQL"),
+            &BSTR::from("SELECT InstanceName, CurrentBrightness FROM WmiMonitorBrightness"),
+            WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+            None,
+        ) else {
+            // No panel with a controllable backlight (desktops)
+            return levels;
+        };
+        loop {
+            let mut row: [Option<IWbemClassObject>; 1] = [None];
+            let mut returned = 0;
+            let _ = rows.Next(WBEM_INFINITE, &mut row, &mut returned);
+            let Some(row) = row[0].take().filter(|_| returned == 1) else {
+                break;
+            };
+            let mut name = VARIANT::default();
+            let mut level = VARIANT::default();
+            if row
+                .Get(w!("InstanceName"), 0, &mut name, None, None)
+                .is_err()
+                || row
+                    .Get(w!("CurrentBrightness"), 0, &mut level, None, None)
+                    .is_err()
+            {
+                continue;
+            }
+            if let (Ok(name), Ok(level)) = (BSTR::try_from(&name), u32::try_from(&level)) {
+                levels.push((name.to_string(), level.min(100) as f32 / 100.0));
+            }
+        }
+    }
+    levels
+}
+
+/// Whether WMI instance `instance` ("DISPLAY\BOE0867\4&2a2b&0&UID8388_0")
+/// is the monitor with device path `path`
+/// ("\\?\DISPLAY#BOE0867#4&2a2b&0&UID8388#{e6f07b5f-...}")
+fn is_same_monitor(instance: &str, path: &str) -> bool {
+    let id = instance.rsplit_once('_').map_or(instance, |(id, _)| id);
+    let id = format!("{}#", id.replace('\\', "#")).to_ascii_uppercase();
+    path.to_ascii_uppercase().contains(&id)
+}
+
+/// Work out which monitors the rule leaves undimmed right now
+fn check(services: &IWbemServices, rule: &BrightnessRule) {
+    let levels = read(services);
+    let previous = UNDIMMED.lock().unwrap().clone();
+    let mut undimmed = Vec::new();
+    for (index, monitor) in monitors::list().iter().enumerate() {
+        let index = index as u32;
+        let brightness = levels
+            .iter()
+            .find(|(instance, _)| is_same_monitor(instance, &monitor.stable_id))
+            .map(|(_, level)| *level);
+        let threshold = rule.threshold_for(&monitor.id);
+        if !rule.allows(threshold, brightness, !previous.contains(&index)) {
+            undimmed.push(index);
+        }
+    }
+    if undimmed != previous {
+        eprintln!(
+            "[brightness] panels at {:?}, undimmed monitors now {:?}",
+            levels.iter().map(|(_, level)| *level).collect::<Vec<_>>(),
+            undimmed
+        );
+    }
+    set_undimmed(undimmed);
+}
+
+fn set_undimmed(undimmed: Vec<u32>) {
+    let changed = std::mem::replace(&mut *UNDIMMED.lock().unwrap(), undimmed.clone()) != undimmed;
+    if changed {
+        overlay::set_brightness_gate(&undimmed);
+    }
+}
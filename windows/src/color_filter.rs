@@ -0,0 +1,189 @@
+// Windows color filters (Settings > Accessibility > Color filters).
+//
+// The filter lives in HKCU\Software\Microsoft\ColorFiltering: Active (0/1),
+// FilterType (0 grayscale, 1 inverted, 2 grayscale inverted, 3 deuteranopia,
+// 4 protanopia, 5 tritanopia) and HotkeyEnabled. There is no API to switch
+// it, and writing Active does nothing until the filter is toggled. So the
+// type is written to the registry and the filter is toggled with its own
+// shortcut, Win+Ctrl+C, which only works while HotkeyEnabled is 1; it is
+// turned on for the duration if the user has it off. A type change while the
+// filter is on toggles it off and on again so the new type is picked up.
+// This runs on a worker thread since the toggles need time to settle.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use windows::core::PCWSTR;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegGetValueW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
+    KEY_SET_VALUE, REG_DWORD, REG_OPTION_NON_VOLATILE, RRF_RT_REG_DWORD,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    VIRTUAL_KEY, VK_C, VK_CONTROL, VK_LWIN,
+};
+
+use savemyeyes_shared::color_filter::{self, ColorFilter};
+use savemyeyes_shared::config::AppConfig;
+
+const COLOR_FILTERING_KEY: &str = "Software\\Microsoft\\ColorFiltering";
+const ACTIVE: &str = "Active";
+const FILTER_TYPE: &str = "FilterType";
+const HOTKEY_ENABLED: &str = "HotkeyEnabled";
+
+/// Time Windows gets to act on one shortcut press
+const SETTLE: Duration = Duration::from_millis(400);
+
+/// Held while a switch is in progress, so quick profile changes queue up
+static SWITCHING: Mutex<()> = Mutex::new(());
+
+fn wide_string(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// FilterType value, None for Off
+fn filter_type(filter: ColorFilter) -> Option<u32> {
+    match filter {
+        ColorFilter::Off => None,
+        ColorFilter::Grayscale => Some(0),
+        ColorFilter::Inverted => Some(1),
+        ColorFilter::GrayscaleInverted => Some(2),
+        ColorFilter::Deuteranopia => Some(3),
+        ColorFilter::Protanopia => Some(4),
+        ColorFilter::Tritanopia => Some(5),
+    }
+}
+
+fn read_dword(name: &str) -> Option<u32> {
+    let key_path = wide_string(COLOR_FILTERING_KEY);
+    let value_name = wide_string(name);
+    let mut data = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key_path.as_ptr()),
+            PCWSTR(value_name.as_ptr()),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut data as *mut u32 as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+        .is_ok()
+        .then_some(data)
+    }
+}
+
+fn write_dword(name: &str, value: u32) -> bool {
+    let key_path = wide_string(COLOR_FILTERING_KEY);
+    let value_name = wide_string(name);
+    let mut hkey = HKEY::default();
+    unsafe {
+        // The key is missing until color filters were first touched
+        let created = RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key_path.as_ptr()),
+            None,
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut hkey,
+            None,
+        );
+        if created.is_err() {
+            return false;
+        }
+        let result = RegSetValueExW(
+            hkey,
+            PCWSTR(value_name.as_ptr()),
+            None,
+            REG_DWORD,
+            Some(&value.to_le_bytes()),
+        );
+        let _ = RegCloseKey(hkey);
+        result.is_ok()
+    }
+}
+
+fn key_input(key: VIRTUAL_KEY, up: bool) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                dwFlags: if up {
+                    KEYEVENTF_KEYUP
+                } else {
+                    KEYBD_EVENT_FLAGS(0)
+                },
+                ..Default::default()
+            },
+        },
+    }
+}
+
+/// Press Win+Ctrl+C
+fn press_shortcut() -> bool {
+    let inputs = [
+        key_input(VK_LWIN, false),
+        key_input(VK_CONTROL, false),
+        key_input(VK_C, false),
+        key_input(VK_C, true),
+        key_input(VK_CONTROL, true),
+        key_input(VK_LWIN, true),
+    ];
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    sent as usize == inputs.len()
+}
+
+/// Bring the OS filter to `filter` (blocking).
+fn switch(filter: ColorFilter) {
+    let _switching = SWITCHING.lock().unwrap();
+    let active = read_dword(ACTIVE) == Some(1);
+    let wanted = filter_type(filter);
+    let type_changes = wanted.is_some_and(|t| read_dword(FILTER_TYPE) != Some(t));
+    if let Some(t) = wanted {
+        if type_changes && !write_dword(FILTER_TYPE, t) {
+            eprintln!("[color_filter] could not write FilterType");
+            return;
+        }
+    }
+    let presses = match (active, wanted.is_some()) {
+        (false, true) | (true, false) => 1,
+        (true, true) if type_changes => 2,
+        _ => 0,
+    };
+    if presses == 0 {
+        return;
+    }
+
+    let hotkey_was_enabled = read_dword(HOTKEY_ENABLED) == Some(1);
+    if !hotkey_was_enabled && !write_dword(HOTKEY_ENABLED, 1) {
+        eprintln!("[color_filter] could not enable the color filter shortcut");
+        return;
+    }
+    for _ in 0..presses {
+        if !press_shortcut() {
+            eprintln!("[color_filter] shortcut input was blocked");
+            break;
+        }
+        std::thread::sleep(SETTLE);
+    }
+    if !hotkey_was_enabled {
+        write_dword(HOTKEY_ENABLED, 0);
+    }
+    eprintln!("[color_filter] switched to {}", filter.label());
+}
+
+/// Switch the OS filter to `filter` in the background.
+pub fn apply(filter: ColorFilter) {
+    std::thread::spawn(move || switch(filter));
+}
+
+/// Switch the OS filter as profile `name` asks, if config `color_filters`
+/// is on (after the profile was applied).
+pub fn follow_profile(cfg: &AppConfig, name: &str) {
+    if let Some(filter) = color_filter::for_profile(cfg, name) {
+        apply(filter);
+    }
+}
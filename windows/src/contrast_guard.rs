@@ -0,0 +1,155 @@
+// Adaptive contrast guard (shared contrast_guard.rs) on Windows.
+//
+// While the rule is on, a poll thread samples the monitor under the focused
+// window: one StretchBlt of the screen DC into a tiny DIB, which costs a
+// few milliseconds rather than a DXGI duplication session kept open for a
+// sample every few seconds. Overlays excluded from capture (the default)
+// and gamma ramps don't show in the sample; an overlay that is captured is
+// divided back out, using its alpha, so the guard sees the content and not
+// our own dimming. The cap goes to the overlay for that monitor only.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, GetDC, ReleaseDC, SelectObject,
+    SetStretchBltMode, StretchBlt, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HALFTONE,
+    HGDIOBJ, SRCCOPY,
+};
+
+use savemyeyes_shared::contrast_guard::{
+    average_luminance, ContrastGuard, SAMPLE_HEIGHT, SAMPLE_WIDTH,
+};
+use savemyeyes_shared::shutdown;
+
+use crate::{monitors, movie_mode, overlay};
+
+static RULE: Mutex<Option<ContrastGuard>> = Mutex::new(None);
+/// Monitor index and cap from the last sample
+static CAP: Mutex<Option<(u32, f32)>> = Mutex::new(None);
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// Apply the rule from config.
+pub fn apply(rule: &ContrastGuard) {
+    *RULE.lock().unwrap() = Some(rule.clone());
+    if !rule.enabled {
+        set_cap(None);
+    } else if !POLLING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("contrast-guard", poll);
+    }
+}
+
+fn poll() {
+    let mut wait = None;
+    loop {
+        let rule = RULE.lock().unwrap().clone();
+        let Some(rule) = rule.filter(|r| r.enabled) else {
+            break;
+        };
+        let started = Instant::now();
+        check(&rule);
+        let wait_now = rule.next_wait(wait.unwrap_or(rule.interval()), started.elapsed());
+        if wait.is_some_and(|w| w < wait_now) {
+            eprintln!("[contrast_guard] sampling slow, next in {:?}", wait_now);
+        }
+        wait = Some(wait_now);
+        if !shutdown::sleep(wait_now) {
+            // Quitting: the overlays are about to go, leave them be
+            POLLING.store(false, Ordering::SeqCst);
+            return;
+        }
+    }
+    POLLING.store(false, Ordering::SeqCst);
+    set_cap(None);
+}
+
+/// Sample the focused monitor and cap it if it is dark
+fn check(rule: &ContrastGuard) {
+    let index = movie_mode::focused_monitor();
+    let Some(veil) = overlay::capture_veil(index) else {
+        // Not dimmed: nothing to guard, and no need to look
+        set_cap(None);
+        return;
+    };
+    let Some(monitor) = monitors::list().into_iter().nth(index as usize) else {
+        return;
+    };
+    let Some(luminance) = sample(&monitor.rect) else {
+        return;
+    };
+    // Content under a captured overlay of alpha a shows at (1 - a)
+    let luminance = (luminance / (1.0 - veil).max(0.1)).min(1.0);
+    let capped = CAP.lock().unwrap().is_some_and(|(i, _)| i == index);
+    set_cap(rule.cap(luminance, capped).map(|cap| (index, cap)));
+}
+
+fn set_cap(cap: Option<(u32, f32)>) {
+    let changed = std::mem::replace(&mut *CAP.lock().unwrap(), cap) != cap;
+    if changed {
+        overlay::set_contrast_cap(cap);
+    }
+}
+
+/// Average luminance of the screen inside `rect`
+fn sample(rect: &RECT) -> Option<f32> {
+    let bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: SAMPLE_WIDTH as i32,
+            biHeight: -(SAMPLE_HEIGHT as i32), // top-down
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    unsafe {
+        let screen = GetDC(None);
+        if screen.is_invalid() {
+            return None;
+        }
+        let mem_dc = CreateCompatibleDC(Some(screen));
+        let mut bits: *mut std::ffi::c_void = std::ptr::null_mut();
+        let luminance =
+            match CreateDIBSection(Some(mem_dc), &bmi, DIB_RGB_COLORS, &mut bits, None, 0) {
+                Ok(dib) => {
+                    let old = SelectObject(mem_dc, HGDIOBJ::from(dib));
+                    // HALFTONE averages the pixels each sample covers
+                    SetStretchBltMode(mem_dc, HALFTONE);
+                    let copied = StretchBlt(
+                        mem_dc,
+                        0,
+                        0,
+                        SAMPLE_WIDTH as i32,
+                        SAMPLE_HEIGHT as i32,
+                        Some(screen),
+                        rect.left,
+                        rect.top,
+                        rect.right - rect.left,
+                        rect.bottom - rect.top,
+                        SRCCOPY,
+                    )
+                    .as_bool();
+                    let pixels = std::slice::from_raw_parts(
+                        bits as *const u8,
+                        SAMPLE_WIDTH * SAMPLE_HEIGHT * 4,
+                    );
+                    // BGRA
+                    let luminance = copied.then(|| average_luminance(pixels, [2, 1, 0]));
+                    SelectObject(mem_dc, old);
+                    let _ = DeleteObject(HGDIOBJ::from(dib));
+                    luminance
+                }
+                Err(e) => {
+                    eprintln!("[contrast_guard] CreateDIBSection failed: {}", e);
+                    None
+                }
+            };
+        let _ = DeleteDC(mem_dc);
+        ReleaseDC(None, screen);
+        luminance
+    }
+}
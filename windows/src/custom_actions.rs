@@ -0,0 +1,119 @@
+// Custom hotkey actions (shared custom_actions.rs) on Windows.
+//
+// The hotkeys are registered next to the fixed ones (hotkeys.rs) and arrive
+// as WM_HOTKEY on the settings window, where `run` carries the steps one
+// after another through the external command dispatcher (events.rs), on the
+// UI thread. The Shortcuts tab edits an action through `edit_menu`, a popup
+// menu that serves as the step dropdown.
+
+use std::sync::{Arc, Mutex};
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, POINT};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreatePopupMenu, DestroyMenu, TrackPopupMenu, MENU_ITEM_FLAGS, MF_CHECKED,
+    MF_GRAYED, MF_SEPARATOR, MF_STRING, TPM_LEFTALIGN, TPM_NONOTIFY, TPM_RETURNCMD, TPM_TOPALIGN,
+};
+
+use savemyeyes_shared::custom_actions::{self, Edit, RunReport, MAX_STEPS};
+
+use crate::config::AppConfig;
+use crate::tray::{append, append_submenu};
+use crate::{events, monitors};
+
+/// Run custom action `index` (UI thread). Returns the toast text.
+pub fn run(hwnd: HWND, config: &Arc<Mutex<AppConfig>>, index: usize) -> Option<String> {
+    let action = config.lock().unwrap().custom_actions.get(index)?.clone();
+    eprintln!("[custom_actions] running {:?}", action.name);
+    let mut report = RunReport::new(&action);
+    for (i, step) in action.steps.iter().enumerate() {
+        // Checked against the config as the steps before left it
+        let error = {
+            let cfg = config.lock().unwrap();
+            custom_actions::step_error(&cfg, step, monitors::count())
+        };
+        if error.is_none() {
+            events::run(hwnd, config, step.clone());
+        }
+        report.record(i, step, error);
+    }
+    Some(report.message())
+}
+
+/// Show the dropdown of action `index` at `at` (screen coordinates) and
+/// return what was picked.
+pub fn edit_menu(hwnd: HWND, cfg: &AppConfig, index: usize, at: POINT) -> Option<Edit> {
+    let action = cfg.custom_actions.get(index)?;
+    // A menu item's command ID is 1 + the index of its edit here
+    let mut edits = Vec::new();
+    let mut id_for = |edit: Edit| {
+        edits.push(edit);
+        edits.len() as u32
+    };
+    let picked = unsafe {
+        let menu = CreatePopupMenu().ok()?;
+
+        let add = CreatePopupMenu().ok()?;
+        for group in custom_actions::step_menu(cfg, monitors::count()) {
+            let submenu = CreatePopupMenu().ok()?;
+            let empty = group.steps.is_empty();
+            for (label, step) in group.steps {
+                append(submenu, MF_STRING, id_for(Edit::AddStep(step)), &label);
+            }
+            append_submenu(add, enabled(!empty), submenu, &group.label);
+        }
+        let room = action.steps.len() < MAX_STEPS;
+        append_submenu(menu, enabled(room), add, "Add Step");
+        let flags = enabled(!action.steps.is_empty());
+        append(
+            menu,
+            flags,
+            id_for(Edit::RemoveLastStep),
+            "Remove Last Step",
+        );
+
+        let hotkeys = CreatePopupMenu().ok()?;
+        for hotkey in custom_actions::hotkey_choices() {
+            let flags = if hotkey.eq_ignore_ascii_case(&action.hotkey) {
+                MF_STRING | MF_CHECKED
+            } else {
+                enabled(!custom_actions::hotkey_taken(
+                    &cfg.custom_actions,
+                    index,
+                    &hotkey,
+                ))
+            };
+            append(
+                hotkeys,
+                flags,
+                id_for(Edit::Hotkey(hotkey.clone())),
+                &hotkey,
+            );
+        }
+        append_submenu(menu, MF_STRING, hotkeys, "Hotkey");
+        let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+        append(menu, MF_STRING, id_for(Edit::Delete), "Delete Action");
+
+        let picked = TrackPopupMenu(
+            menu,
+            TPM_LEFTALIGN | TPM_TOPALIGN | TPM_RETURNCMD | TPM_NONOTIFY,
+            at.x,
+            at.y,
+            Some(0),
+            hwnd,
+            None,
+        );
+        // Also destroys the submenus
+        let _ = DestroyMenu(menu);
+        picked.0 as usize
+    };
+    edits.into_iter().nth(picked.checked_sub(1)?)
+}
+
+fn enabled(on: bool) -> MENU_ITEM_FLAGS {
+    if on {
+        MF_STRING
+    } else {
+        MF_STRING | MF_GRAYED
+    }
+}
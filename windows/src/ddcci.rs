@@ -0,0 +1,198 @@
+// Hardware brightness over DDC/CI on Windows.
+//
+// The High-Level Monitor Configuration API (dxva2) sets the backlight of
+// monitors that speak DDC/CI, which most external monitors do and laptop
+// panels don't (those report through WMI instead, see brightness.rs). It
+// works beside the overlay rather than instead of it: the backlight lowers
+// the light the panel makes, the overlay dims what is drawn on it, and a
+// monitor can have both. The monitor keeps its own brightness, so nothing
+// is saved in config.
+//
+// DDC/CI is slow (tens of milliseconds a call, far more on some monitors),
+// so a worker thread does all of it. `refresh` reads every monitor and
+// posts WM_BACKLIGHT to the settings window when done; `set` queues a new
+// level, and only the latest queued level per monitor is sent, so a slider
+// drag never backs up. The worker exits once the queue is empty.
+
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::Mutex;
+
+use windows::Win32::Devices::Display::{
+    DestroyPhysicalMonitors, GetMonitorBrightness, GetNumberOfPhysicalMonitorsFromHMONITOR,
+    GetPhysicalMonitorsFromHMONITOR, SetMonitorBrightness, PHYSICAL_MONITOR,
+};
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, WPARAM};
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_APP};
+
+use savemyeyes_shared::monitor_id::MonitorId;
+use savemyeyes_shared::shutdown;
+
+use crate::monitors;
+
+/// Posted to the settings window when a refresh has read every monitor
+pub const WM_BACKLIGHT: u32 = WM_APP + 18;
+
+/// A monitor whose backlight can be set
+#[derive(Debug, Clone)]
+pub struct Backlight {
+    pub id: MonitorId,
+    /// Friendly name (monitors::friendly_name)
+    pub name: String,
+    /// Brightness within the monitor's range, 0–100
+    pub percent: u32,
+    /// The monitor's own minimum and maximum
+    range: (u32, u32),
+}
+
+/// Work for the worker thread
+#[derive(Default)]
+struct Queue {
+    refresh: bool,
+    /// Latest level per monitor
+    levels: Vec<(MonitorId, u32)>,
+}
+
+/// Monitors found by the last refresh
+static BACKLIGHTS: Mutex<Vec<Backlight>> = Mutex::new(Vec::new());
+static QUEUE: Mutex<Queue> = Mutex::new(Queue {
+    refresh: false,
+    levels: Vec::new(),
+});
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Settings window that receives WM_BACKLIGHT
+static NOTIFY_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// Post WM_BACKLIGHT to `hwnd` and look for DDC/CI monitors.
+pub fn start(hwnd: HWND) {
+    NOTIFY_WINDOW.store(hwnd.0 as isize, Ordering::SeqCst);
+    refresh();
+}
+
+/// Read every monitor's brightness again (startup, WM_DISPLAYCHANGE)
+pub fn refresh() {
+    enqueue(|queue| queue.refresh = true);
+}
+
+/// Monitors with a settable backlight, as of the last refresh
+pub fn monitors() -> Vec<Backlight> {
+    BACKLIGHTS.lock().unwrap().clone()
+}
+
+/// Set the backlight of monitor `id` to `percent` of its range.
+pub fn set(id: &MonitorId, percent: u32) {
+    let percent = percent.min(100);
+    if let Some(found) = BACKLIGHTS.lock().unwrap().iter_mut().find(|b| b.id == *id) {
+        found.percent = percent;
+    }
+    enqueue(|queue| {
+        queue.levels.retain(|(queued, _)| queued != id);
+        queue.levels.push((id.clone(), percent));
+    });
+}
+
+fn enqueue(add: impl FnOnce(&mut Queue)) {
+    // The worker checks for an empty queue under the same lock
+    let mut queue = QUEUE.lock().unwrap();
+    add(&mut queue);
+    if !RUNNING.swap(true, Ordering::SeqCst) {
+        shutdown::spawn("ddcci", run);
+    }
+}
+
+fn run() {
+    loop {
+        let work = {
+            let mut queue = QUEUE.lock().unwrap();
+            if (!queue.refresh && queue.levels.is_empty()) || shutdown::is_stopping() {
+                RUNNING.store(false, Ordering::SeqCst);
+                return;
+            }
+            mem::take(&mut *queue)
+        };
+        if work.refresh {
+            read_all();
+        }
+        for (id, percent) in work.levels {
+            write(&id, percent);
+        }
+    }
+}
+
+fn read_all() {
+    let found: Vec<Backlight> = monitors::list()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, monitor)| {
+            let (min, current, max) = with_physical(monitor.hmonitor, read)?;
+            let span = max.saturating_sub(min).max(1);
+            Some(Backlight {
+                id: monitor.id,
+                name: monitors::friendly_name(index as u32),
+                percent: (current.clamp(min, max) - min) * 100 / span,
+                range: (min, max),
+            })
+        })
+        .collect();
+    eprintln!("[ddcci] {} monitor(s) with DDC/CI brightness", found.len());
+    *BACKLIGHTS.lock().unwrap() = found;
+
+    let raw = NOTIFY_WINDOW.load(Ordering::SeqCst);
+    if raw != 0 {
+        unsafe {
+            let _ = PostMessageW(
+                Some(HWND(raw as *mut _)),
+                WM_BACKLIGHT,
+                WPARAM(0),
+                LPARAM(0),
+            );
+        }
+    }
+}
+
+fn write(id: &MonitorId, percent: u32) {
+    let range = BACKLIGHTS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|b| b.id == *id)
+        .map(|b| b.range);
+    let Some((min, max)) = range else {
+        return;
+    };
+    let Some(monitor) = monitors::list().into_iter().find(|m| m.id == *id) else {
+        return;
+    };
+    let value = min + (max - min) * percent / 100;
+    let written = with_physical(monitor.hmonitor, |handle| {
+        (unsafe { SetMonitorBrightness(handle, value) } != 0).then_some(())
+    });
+    if written.is_none() {
+        eprintln!("[ddcci] {} refused brightness {}", id, value);
+    }
+}
+
+/// Minimum, current and maximum brightness
+fn read(handle: HANDLE) -> Option<(u32, u32, u32)> {
+    let (mut min, mut current, mut max) = (0, 0, 0);
+    let ok = unsafe { GetMonitorBrightness(handle, &mut min, &mut current, &mut max) } != 0;
+    (ok && max > min).then_some((min, current, max))
+}
+
+/// Run `f` on the first physical monitor behind `hmonitor` (more than one
+/// only when monitors are cloned)
+fn with_physical<R>(hmonitor: HMONITOR, f: impl FnOnce(HANDLE) -> Option<R>) -> Option<R> {
+    let mut count = 0;
+    unsafe { GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut count) }.ok()?;
+    if count == 0 {
+        return None;
+    }
+    let mut physical = vec![PHYSICAL_MONITOR::default(); count as usize];
+    unsafe { GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut physical) }.ok()?;
+    let result = f(physical[0].hPhysicalMonitor);
+    unsafe {
+        let _ = DestroyPhysicalMonitors(&physical);
+    }
+    result
+}
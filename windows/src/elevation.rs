@@ -0,0 +1,95 @@
+// Optional elevated (administrator) relaunch.
+//
+// A normal-integrity overlay can't always cover windows of elevated apps and
+// our hotkeys don't reach an elevated foreground window (UIPI). Power users
+// can relaunch SaveMyEyes elevated: the current state is saved to config,
+// a new instance is started via ShellExecute "runas" with --elevated, and
+// this instance quits so the new one can take over the single-instance mutex.
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    MessageBoxW, IDYES, MB_ICONWARNING, MB_YESNO, SW_SHOWNORMAL,
+};
+
+/// Command-line flag passed to the elevated instance
+pub const ELEVATED_ARG: &str = "--elevated";
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// True if this process runs with an elevated (admin) token.
+pub fn is_elevated() -> bool {
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut size = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        )
+        .is_ok();
+        let _ = CloseHandle(token);
+        ok && elevation.TokenIsElevated != 0
+    }
+}
+
+/// True if we were started by `relaunch_elevated`.
+pub fn was_relaunched_elevated() -> bool {
+    std::env::args().any(|a| a == ELEVATED_ARG)
+}
+
+/// Explain the tradeoffs and ask for confirmation.
+pub fn confirm_relaunch(hwnd: HWND) -> bool {
+    let message = "Running SaveMyEyes as administrator lets the dimming overlay and \
+                   hotkeys cover elevated (admin) windows.\n\n\
+                   Tradeoffs:\n\
+                   \u{2022} Windows asks for UAC consent every time you do this.\n\
+                   \u{2022} Start on Login still launches the normal, non-elevated app.\n\
+                   \u{2022} SaveMyEyes runs with full administrator rights until it quits.\n\n\
+                   Relaunch elevated now?";
+    let msg_wide = wide(message);
+    let title_wide = wide("Run Elevated");
+    unsafe {
+        MessageBoxW(
+            Some(hwnd),
+            PCWSTR(msg_wide.as_ptr()),
+            PCWSTR(title_wide.as_ptr()),
+            MB_YESNO | MB_ICONWARNING,
+        ) == IDYES
+    }
+}
+
+/// Start an elevated copy of ourselves. Returns false if the user declined
+/// the UAC prompt or the launch failed; the caller quits on success.
+pub fn relaunch_elevated() -> bool {
+    let exe = match std::env::current_exe() {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+    let exe_wide = wide(&exe.to_string_lossy());
+    let verb = wide("runas");
+    let args = wide(ELEVATED_ARG);
+    unsafe {
+        let result = ShellExecuteW(
+            None,
+            PCWSTR(verb.as_ptr()),
+            PCWSTR(exe_wide.as_ptr()),
+            PCWSTR(args.as_ptr()),
+            None,
+            SW_SHOWNORMAL,
+        );
+        // ShellExecute reports success with a value greater than 32
+        result.0 as isize > 32
+    }
+}
@@ -0,0 +1,155 @@
+// External commands (shared events.rs) on Windows.
+//
+// Another process (the native messaging host, or the exe started again by a
+// jump list task or from the command line) finds the settings window by
+// class name and sends the command as JSON in a WM_COPYDATA message. The
+// window procedure hands it to `dispatch`, which runs it on the UI thread
+// like a hotkey. WM_COPYDATA is let through UIPI so an elevated instance
+// still hears from the unelevated host the browser starts.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::System::DataExchange::COPYDATASTRUCT;
+use windows::Win32::UI::WindowsAndMessaging::{
+    AllowSetForegroundWindow, ChangeWindowMessageFilterEx, FindWindowW, SendMessageTimeoutW,
+    ASFW_ANY, MSGFLT_ALLOW, SMTO_ABORTIFHUNG, WM_COPYDATA,
+};
+
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::{self, Cause};
+
+use crate::config::AppConfig;
+use crate::movie_mode;
+use crate::ui;
+
+/// COPYDATASTRUCT.dwData tag for a JSON command ("SME" + version 1)
+const COPYDATA_COMMAND: usize = 0x534D_4501;
+
+/// How long a sender waits for the running instance
+const SEND_TIMEOUT_MS: u32 = 2000;
+
+/// Movie mode is on because the browser reported a playing video
+static VIDEO_MOVIE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Deliver `command` to the running instance. False if none is running or it
+/// didn't answer.
+pub fn forward(command: &Command) -> bool {
+    let class: Vec<u16> = ui::CLASS_NAME.encode_utf16().collect();
+    let Ok(hwnd) = (unsafe { FindWindowW(PCWSTR(class.as_ptr()), PCWSTR::null()) }) else {
+        return false;
+    };
+    let json = command.to_json();
+    let data = COPYDATASTRUCT {
+        dwData: COPYDATA_COMMAND,
+        cbData: json.len() as u32,
+        lpData: json.as_ptr() as *mut _,
+    };
+    let mut handled = 0usize;
+    let sent = unsafe {
+        // We usually hold the foreground (the user just clicked us); pass it
+        // on so Open Settings can raise the window
+        let _ = AllowSetForegroundWindow(ASFW_ANY);
+        SendMessageTimeoutW(
+            hwnd,
+            WM_COPYDATA,
+            WPARAM(0),
+            LPARAM(&data as *const _ as isize),
+            SMTO_ABORTIFHUNG,
+            SEND_TIMEOUT_MS,
+            Some(&mut handled),
+        )
+    };
+    sent.0 != 0 && handled != 0
+}
+
+/// Accept commands from unelevated senders (call once on the settings window)
+pub fn allow_senders(hwnd: HWND) {
+    unsafe {
+        let _ = ChangeWindowMessageFilterEx(hwnd, WM_COPYDATA, MSGFLT_ALLOW, None);
+    }
+}
+
+/// The command in a WM_COPYDATA `lparam`, if it is one of ours
+pub unsafe fn receive(lparam: LPARAM) -> Option<Command> {
+    let data = (lparam.0 as *const COPYDATASTRUCT).as_ref()?;
+    if data.dwData != COPYDATA_COMMAND || data.lpData.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts(data.lpData as *const u8, data.cbData as usize);
+    Command::from_json(std::str::from_utf8(bytes).ok()?)
+}
+
+/// Run a command on the UI thread. Returns the toast message, if any.
+pub fn dispatch(hwnd: HWND, config: &Arc<Mutex<AppConfig>>, command: Command) -> Option<String> {
+    eprintln!("[events] {:?}", command);
+    explain::note(Cause::Command);
+    run(hwnd, config, command)
+}
+
+/// Carry out `command` (UI thread), whoever asked for it
+pub fn run(hwnd: HWND, config: &Arc<Mutex<AppConfig>>, command: Command) -> Option<String> {
+    let message = match command {
+        Command::Toggle => {
+            crate::do_toggle_dimmer(config);
+            Some(enabled_message(config))
+        }
+        Command::SetEnabled { enabled } => {
+            if config.lock().unwrap().is_enabled != enabled {
+                crate::do_toggle_dimmer(config);
+            }
+            Some(enabled_message(config))
+        }
+        Command::SetLevel { level } => Some(crate::do_set_level(config, level)),
+        Command::SetMonitorLevel { monitor, level } => {
+            Some(crate::do_set_monitor_level(config, monitor, level))
+        }
+        Command::SetMonitorCapture { monitor, visible } => {
+            ui::set_monitor_capture(hwnd, monitor, visible)
+        }
+        Command::ApplyProfile { name } => ui::apply_profile(hwnd, &name),
+        Command::VideoPlaying { playing } => video_playing(config, playing),
+        Command::Pause { minutes } => {
+            if config.lock().unwrap().designer_mode {
+                Some("Designer mode is on".to_string())
+            } else {
+                ui::start_pause(hwnd, minutes);
+                None
+            }
+        }
+        Command::OpenSettings => {
+            ui::show_window(hwnd);
+            None
+        }
+    };
+    ui::sync_from_config(hwnd);
+    message
+}
+
+fn enabled_message(config: &Arc<Mutex<AppConfig>>) -> String {
+    if config.lock().unwrap().is_enabled {
+        "Dimming on".to_string()
+    } else {
+        "Dimming off".to_string()
+    }
+}
+
+/// Movie mode follows browser video when movie_mode.on_browser_video is set.
+/// Only a movie mode started here is ended here.
+fn video_playing(config: &Arc<Mutex<AppConfig>>, playing: bool) -> Option<String> {
+    if !config.lock().unwrap().movie_mode.on_browser_video {
+        return None;
+    }
+    if playing && !movie_mode::is_active() {
+        let message = movie_mode::toggle(config);
+        VIDEO_MOVIE_MODE.store(movie_mode::is_active(), Ordering::SeqCst);
+        Some(message)
+    } else if !playing && VIDEO_MOVIE_MODE.swap(false, Ordering::SeqCst) && movie_mode::is_active()
+    {
+        Some(movie_mode::toggle(config))
+    } else {
+        None
+    }
+}
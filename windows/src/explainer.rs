@@ -0,0 +1,276 @@
+// "Why is my screen dark?" window (shared explain.rs).
+//
+// Opened from the tray entry of the same name. A small topmost window with
+// the explanation as plain text and one button per feature darkening the
+// screen right now, plus Close. Config belongs to the settings window, so a
+// fix button posts WM_EXPLAIN_FIX (wparam = index into explain::Fix::ALL)
+// to it and closes; the settings window applies the fix like the matching
+// tray or settings control would. There is one window at a time: opening it
+// again replaces the text and buttons and brings it to the front.
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CreateFontIndirectW, DeleteObject, DrawTextW, GetDC, GetSysColorBrush, ReleaseDC, SelectObject,
+    CLEARTYPE_QUALITY, COLOR_BTNFACE, DEFAULT_CHARSET, DT_CALCRECT, DT_WORDBREAK, HFONT, HGDIOBJ,
+    LOGFONTW,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    AdjustWindowRectEx, CreateWindowExW, DefWindowProcW, DestroyWindow, GetCursorPos, LoadCursorW,
+    PostMessageW, RegisterClassW, SendMessageW, SetForegroundWindow, SetWindowPos,
+    BS_DEFPUSHBUTTON, BS_PUSHBUTTON, HMENU, HWND_TOPMOST, IDC_ARROW, SWP_NOMOVE, SWP_NOSIZE,
+    SWP_SHOWWINDOW, WINDOW_STYLE, WM_APP, WM_CLOSE, WM_COMMAND, WM_DESTROY, WM_SETFONT, WNDCLASSW,
+    WS_CAPTION, WS_CHILD, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP, WS_SYSMENU, WS_VISIBLE,
+};
+
+use savemyeyes_shared::explain::{Explanation, Fix};
+
+use crate::monitors;
+use crate::ui::theme::FONT_NAME;
+
+/// Posted to the settings window: apply explain::Fix::ALL[wparam]
+pub const WM_EXPLAIN_FIX: u32 = WM_APP + 16;
+
+const CLASS_NAME: &str = "SaveMyEyesExplainer\0";
+const TITLE: &str = "Why is my screen dark?";
+const FONT_SIZE: i32 = -15;
+/// Client width; the height follows the text and buttons
+const WIDTH: i32 = 420;
+const PAD: i32 = 16;
+const BUTTON_HEIGHT: i32 = 30;
+const GAP: i32 = 8;
+/// Button command IDs: ID_FIX_BASE + position in the window's fix list
+const ID_CLOSE: usize = 1;
+const ID_FIX_BASE: usize = 100;
+
+static WINDOW: AtomicIsize = AtomicIsize::new(0);
+static OWNER: AtomicIsize = AtomicIsize::new(0);
+static FONT: AtomicIsize = AtomicIsize::new(0);
+/// Fixes behind the window's buttons, in button order
+static FIXES: Mutex<Vec<Fix>> = Mutex::new(Vec::new());
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn create_font() -> HFONT {
+    let face = wide(FONT_NAME);
+    let mut lf = LOGFONTW {
+        lfHeight: FONT_SIZE,
+        lfWeight: 400,
+        lfQuality: CLEARTYPE_QUALITY,
+        lfCharSet: DEFAULT_CHARSET,
+        ..Default::default()
+    };
+    let len = face.len().min(32);
+    lf.lfFaceName[..len].copy_from_slice(&face[..len]);
+    unsafe { CreateFontIndirectW(&lf) }
+}
+
+/// Height of `text` wrapped to `width` in `font`
+unsafe fn text_height(font: HFONT, text: &str, width: i32) -> i32 {
+    let hdc = GetDC(None);
+    let old_font = SelectObject(hdc, HGDIOBJ::from(font));
+    let mut wide_text: Vec<u16> = text.encode_utf16().collect();
+    let mut rect = RECT {
+        right: width,
+        ..Default::default()
+    };
+    DrawTextW(hdc, &mut wide_text, &mut rect, DT_CALCRECT | DT_WORDBREAK);
+    SelectObject(hdc, old_font);
+    ReleaseDC(None, hdc);
+    rect.bottom - rect.top
+}
+
+unsafe extern "system" fn explainer_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_COMMAND => {
+            let id = wparam.0 & 0xFFFF;
+            if id >= ID_FIX_BASE {
+                let fix = FIXES.lock().unwrap().get(id - ID_FIX_BASE).copied();
+                let owner = OWNER.load(Ordering::SeqCst);
+                if let Some(fix) = fix.filter(|_| owner != 0) {
+                    let _ = PostMessageW(
+                        Some(HWND(owner as *mut std::ffi::c_void)),
+                        WM_EXPLAIN_FIX,
+                        WPARAM(fix.index()),
+                        LPARAM(0),
+                    );
+                }
+            }
+            if id == ID_CLOSE || id >= ID_FIX_BASE {
+                let _ = DestroyWindow(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_CLOSE => {
+            let _ = DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            WINDOW.store(0, Ordering::SeqCst);
+            let font = FONT.swap(0, Ordering::SeqCst);
+            if font != 0 {
+                let _ = DeleteObject(HGDIOBJ(font as *mut std::ffi::c_void));
+            }
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+unsafe fn add_child(
+    parent: HWND,
+    class: &str,
+    text: &str,
+    style: i32,
+    id: usize,
+    rect: (i32, i32, i32, i32),
+    font: HFONT,
+) {
+    let class = wide(class);
+    let text = wide(text);
+    let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+    let child = CreateWindowExW(
+        Default::default(),
+        PCWSTR(class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(style as u32),
+        rect.0,
+        rect.1,
+        rect.2,
+        rect.3,
+        Some(parent),
+        Some(HMENU(id as *mut std::ffi::c_void)),
+        Some(hinstance.into()),
+        None,
+    );
+    if let Ok(child) = child {
+        SendMessageW(
+            child,
+            WM_SETFONT,
+            Some(WPARAM(font.0 as usize)),
+            Some(LPARAM(1)),
+        );
+    }
+}
+
+/// Show `explanation` on the monitor under the cursor (UI thread only).
+/// Fix buttons post WM_EXPLAIN_FIX to `owner`.
+pub fn show(owner: HWND, explanation: &Explanation) {
+    let existing = WINDOW.load(Ordering::SeqCst);
+    if existing != 0 {
+        unsafe {
+            let _ = DestroyWindow(HWND(existing as *mut std::ffi::c_void));
+        }
+    }
+    OWNER.store(owner.0 as isize, Ordering::SeqCst);
+    let fixes = explanation.fixes();
+    *FIXES.lock().unwrap() = fixes.clone();
+
+    unsafe {
+        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+        let class_name: Vec<u16> = CLASS_NAME.encode_utf16().collect();
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(explainer_proc),
+            hInstance: hinstance.into(),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: GetSysColorBrush(COLOR_BTNFACE),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+
+        let font = create_font();
+        FONT.store(font.0 as isize, Ordering::SeqCst);
+        let text = explanation.text();
+        let text_width = WIDTH - PAD * 2;
+        let text_h = text_height(font, &text, text_width);
+        let buttons = fixes.len() as i32 + 1;
+        let height = PAD + text_h + PAD + buttons * (BUTTON_HEIGHT + GAP) - GAP + PAD;
+
+        let style = WS_POPUP | WS_CAPTION | WS_SYSMENU;
+        let ex_style = WS_EX_TOPMOST | WS_EX_TOOLWINDOW;
+        let mut frame = RECT {
+            right: WIDTH,
+            bottom: height,
+            ..Default::default()
+        };
+        let _ = AdjustWindowRectEx(&mut frame, style, false, ex_style);
+        let (frame_w, frame_h) = (frame.right - frame.left, frame.bottom - frame.top);
+
+        // Centered in the work area of the monitor under the cursor
+        let mut pt = Default::default();
+        let _ = GetCursorPos(&mut pt);
+        let work = monitors::at_point(pt.x, pt.y)
+            .map(|m| m.work)
+            .unwrap_or_default();
+        let x = work.left + (work.right - work.left - frame_w) / 2;
+        let y = work.top + (work.bottom - work.top - frame_h) / 2;
+
+        let title = wide(TITLE);
+        let Ok(hwnd) = CreateWindowExW(
+            ex_style,
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR(title.as_ptr()),
+            style,
+            x,
+            y,
+            frame_w,
+            frame_h,
+            None,
+            None,
+            Some(hinstance.into()),
+            None,
+        ) else {
+            let _ = DeleteObject(HGDIOBJ::from(font));
+            FONT.store(0, Ordering::SeqCst);
+            return;
+        };
+        WINDOW.store(hwnd.0 as isize, Ordering::SeqCst);
+
+        add_child(
+            hwnd,
+            "STATIC",
+            &text,
+            0,
+            0,
+            (PAD, PAD, text_width, text_h),
+            font,
+        );
+        let mut top = PAD + text_h + PAD;
+        for (i, fix) in fixes.iter().enumerate() {
+            // The first fix is the likely one
+            let style = if i == 0 {
+                BS_DEFPUSHBUTTON
+            } else {
+                BS_PUSHBUTTON
+            };
+            let rect = (PAD, top, text_width, BUTTON_HEIGHT);
+            add_child(
+                hwnd,
+                "BUTTON",
+                fix.label(),
+                style,
+                ID_FIX_BASE + i,
+                rect,
+                font,
+            );
+            top += BUTTON_HEIGHT + GAP;
+        }
+        let rect = (PAD, top, text_width, BUTTON_HEIGHT);
+        add_child(hwnd, "BUTTON", "Close", BS_PUSHBUTTON, ID_CLOSE, rect, font);
+
+        let flags = SWP_SHOWWINDOW | SWP_NOMOVE | SWP_NOSIZE;
+        let _ = SetWindowPos(hwnd, Some(HWND_TOPMOST), 0, 0, 0, 0, flags);
+        let _ = SetForegroundWindow(hwnd);
+    }
+}
@@ -0,0 +1,77 @@
+// Failure injection for the overlay recovery paths (`--self-test`).
+//
+// The watchdog, `recreate` and `refit` only run when something outside the
+// app goes wrong, so nothing exercises them on a healthy desktop. These
+// hooks make it go wrong on purpose:
+//   • destroy an overlay window behind the overlay module's back, as another
+//     process or a driver reset would
+//   • make SetWindowDisplayAffinity fail, as it does on builds without
+//     WDA_EXCLUDEFROMCAPTURE or when DWM composition is off
+//   • hide the last monitors from the monitor list, as unplugging one would
+//   • run the watchdog check on the next poll instead of within 5s (30s
+//     while the poll thread is idle)
+// The self-test drives them and checks the overlays come back to one per
+// monitor. They only exist in debug builds; in release builds AVAILABLE is
+// false, every query returns "no fault" and the self-test skips those steps.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[cfg(windows)]
+use windows::Win32::Foundation::HWND;
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+
+/// Whether faults can be injected in this build
+pub const AVAILABLE: bool = cfg!(debug_assertions);
+
+static AFFINITY_FAILS: AtomicBool = AtomicBool::new(false);
+static REMOVED_MONITORS: AtomicUsize = AtomicUsize::new(0);
+static WATCHDOG_NOW: AtomicBool = AtomicBool::new(false);
+
+/// Destroy an overlay window without telling the overlay module. Must be
+/// called on the thread that created it.
+#[cfg(windows)]
+pub fn destroy_window(hwnd: isize) -> bool {
+    AVAILABLE && unsafe { DestroyWindow(HWND(hwnd as *mut std::ffi::c_void)).is_ok() }
+}
+
+/// Headless builds have no windows to destroy
+#[cfg(not(windows))]
+pub fn destroy_window(_hwnd: isize) -> bool {
+    false
+}
+
+/// Make every SetWindowDisplayAffinity call fail until cleared.
+pub fn fail_display_affinity(fail: bool) {
+    AFFINITY_FAILS.store(fail, Ordering::SeqCst);
+}
+
+/// True while SetWindowDisplayAffinity should fail
+pub fn display_affinity_fails() -> bool {
+    AVAILABLE && AFFINITY_FAILS.load(Ordering::SeqCst)
+}
+
+/// Hide the last `count` monitors from the monitor list (0 brings them back).
+pub fn remove_monitors(count: usize) {
+    REMOVED_MONITORS.store(count, Ordering::SeqCst);
+}
+
+/// Number of monitors hidden from the monitor list
+pub fn removed_monitors() -> usize {
+    if AVAILABLE {
+        REMOVED_MONITORS.load(Ordering::SeqCst)
+    } else {
+        0
+    }
+}
+
+/// Have the watchdog check the overlay windows on its next poll.
+pub fn run_watchdog_now() {
+    WATCHDOG_NOW.store(true, Ordering::SeqCst);
+    crate::poll_pace::activity();
+}
+
+/// True (once) if a watchdog check was requested
+pub fn take_watchdog_request() -> bool {
+    AVAILABLE && WATCHDOG_NOW.swap(false, Ordering::SeqCst)
+}
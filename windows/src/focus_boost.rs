@@ -0,0 +1,62 @@
+// "Boost here": less dimming while the foreground window keeps the focus.
+//
+// Ctrl+Alt+B takes `focus_boost.step` off every level for the window in
+// the foreground (see shared focus_boost.rs), and again on each further
+// press. The overrides live in the overlay (overlay.rs applies them to
+// every level); its poll thread and foreground hook compare `foreground`
+// with the window they're tied to and drop them when another window, or
+// another title in the same window, has the focus. Nothing is saved.
+
+use std::sync::{Arc, Mutex};
+
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+};
+
+use savemyeyes_shared::focus_boost::{self, FocusedWindow};
+
+use crate::config::AppConfig;
+use crate::overlay;
+
+/// The foreground window, None for none or one of ours (the settings
+/// window, the tray menu)
+pub fn foreground() -> Option<FocusedWindow> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == GetCurrentProcessId() {
+            return None;
+        }
+        let mut title = vec![0u16; GetWindowTextLengthW(hwnd) as usize + 1];
+        let len = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+        Some(FocusedWindow {
+            handle: hwnd.0 as usize as u64,
+            title: String::from_utf16_lossy(&title[..len]),
+        })
+    }
+}
+
+/// The boost hotkey: lift the dimming one step while the foreground window
+/// keeps the focus. Returns the message for the HUD/toast.
+pub fn boost(config: &Arc<Mutex<AppConfig>>) -> String {
+    let cfg = config.lock().unwrap();
+    if cfg.designer_mode {
+        return "Designer mode is on \u{2014} dimming paused".to_string();
+    }
+    if !cfg.is_enabled {
+        return "Dimming is off".to_string();
+    }
+    let Some(window) = foreground() else {
+        return "No window to boost".to_string();
+    };
+    let title = window.title.clone();
+    let lift = overlay::push_focus_boost(window, cfg.focus_boost.step);
+    let message = focus_boost::message(lift, &title);
+    eprintln!("[boost] {}", message);
+    message
+}
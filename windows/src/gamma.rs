@@ -0,0 +1,274 @@
+// Per-monitor gamma ramp dimming (SetDeviceGammaRamp).
+//
+// Alternative to the overlay window for monitors configured with the gamma
+// backend. Each monitor is addressed through a display DC created from its
+// device name (\\.\DISPLAY1, ...), so different monitors can be dimmed to
+// different levels and mixed with overlay-dimmed ones.
+//
+// The original ramp of every touched device is saved on first use and put
+// back by `restore_all` (hide / exit). Drivers are free to refuse ramps that
+// stray too far from identity, so every apply reads the ramp back and
+// reports whether the requested level actually took effect. Many clamp only
+// the deeper levels (often past ~50%), so a refusal is remembered per device
+// with its level: from then on that level and deeper go straight to the
+// overlay instead of writing a ramp that won't stick, shallower ones still
+// try gamma. The settings window shows which monitors fell back
+// (`refused`); a display change forgets the refusals and tries again.
+//
+// A calibrated monitor's ramp isn't a straight line: it's the 'vcgt' curve
+// from its ICC profile. Dimming scales that curve (see shared
+// calibration.rs) rather than replacing it, so calibration survives and
+// the level means the same on every monitor. Calibrations are read once per
+// device and dropped on hide and on display changes, so a new profile is
+// picked up the next time the overlays are shown.
+
+use savemyeyes_shared::calibration::Calibration;
+use savemyeyes_shared::trace::Mark;
+use std::sync::Mutex;
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Graphics::Gdi::{CreateDCW, DeleteDC, HDC};
+use windows::Win32::UI::ColorSystem::{GetDeviceGammaRamp, GetICMProfileW, SetDeviceGammaRamp};
+
+use crate::trace;
+
+/// 3 × 256 entries (red, green, blue), as expected by the GDI gamma APIs
+pub type GammaRamp = [[u16; 256]; 3];
+
+/// Readback may differ slightly from what we wrote (driver rounding)
+const RAMP_TOLERANCE: u16 = 512;
+
+/// Original ramps of devices we've modified, restored on hide/exit
+static SAVED_RAMPS: Mutex<Vec<(String, GammaRamp)>> = Mutex::new(Vec::new());
+
+/// Devices that refused or clamped a ramp, with the shallowest level refused
+static REFUSED: Mutex<Vec<(String, f32)>> = Mutex::new(Vec::new());
+
+/// Why `apply` left a device to the overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refused {
+    /// No display DC for the device
+    NoDevice,
+    /// The driver refused this level (or a shallower one) before
+    Known,
+    /// The driver refused or clamped the ramp just now
+    New,
+}
+
+/// Calibration of each device read so far (None = none, linear ramp)
+static CALIBRATIONS: Mutex<Vec<(String, Option<Calibration>)>> = Mutex::new(Vec::new());
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Display DC for a device name, deleted on drop.
+struct DisplayDc(HDC);
+
+impl DisplayDc {
+    fn open(device: &str) -> Option<Self> {
+        let name = wide(device);
+        let hdc = unsafe { CreateDCW(PCWSTR::null(), PCWSTR(name.as_ptr()), PCWSTR::null(), None) };
+        if hdc.is_invalid() {
+            None
+        } else {
+            Some(Self(hdc))
+        }
+    }
+
+    fn read(&self) -> Option<GammaRamp> {
+        let mut ramp: GammaRamp = [[0; 256]; 3];
+        let ok = unsafe { GetDeviceGammaRamp(self.0, ramp.as_mut_ptr() as *mut std::ffi::c_void) };
+        ok.as_bool().then_some(ramp)
+    }
+
+    fn write(&self, ramp: &GammaRamp) -> bool {
+        unsafe { SetDeviceGammaRamp(self.0, ramp.as_ptr() as *const std::ffi::c_void).as_bool() }
+    }
+
+    /// Path of the ICC profile associated with the device
+    fn profile_path(&self) -> Option<String> {
+        let mut size = 0u32;
+        unsafe {
+            let _ = GetICMProfileW(self.0, &mut size, None);
+        }
+        if size == 0 {
+            return None;
+        }
+        let mut path = vec![0u16; size as usize];
+        let ok = unsafe { GetICMProfileW(self.0, &mut size, Some(PWSTR(path.as_mut_ptr()))) };
+        let len = path.iter().position(|&c| c == 0).unwrap_or(path.len());
+        ok.as_bool().then(|| String::from_utf16_lossy(&path[..len]))
+    }
+}
+
+impl Drop for DisplayDc {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = DeleteDC(self.0);
+        }
+    }
+}
+
+/// Calibration of `device` from its ICC profile, read on first use
+fn calibration(device: &str, dc: &DisplayDc) -> Option<Calibration> {
+    let mut calibrations = CALIBRATIONS.lock().unwrap();
+    if let Some((_, calibration)) = calibrations.iter().find(|(name, _)| name == device) {
+        return calibration.clone();
+    }
+    let path = dc.profile_path();
+    let calibration = path
+        .as_deref()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|profile| Calibration::from_icc(&profile));
+    if calibration.is_some() {
+        eprintln!(
+            "[gamma] {} calibrated by {}",
+            device,
+            path.unwrap_or_default()
+        );
+    }
+    calibrations.push((device.to_string(), calibration.clone()));
+    calibration
+}
+
+/// Forget the calibrations read so far (display configuration changed).
+pub fn forget_calibrations() {
+    CALIBRATIONS.lock().unwrap().clear();
+}
+
+/// Ramp scaled so white maps to `1.0 - opacity` brightness: the
+/// calibration curve if there is one, a straight line otherwise.
+fn dimmed_ramp(opacity: f32, calibration: Option<&Calibration>) -> GammaRamp {
+    let scale = 1.0 - opacity.clamp(0.0, 0.9);
+    let mut ramp: GammaRamp = [[0; 256]; 3];
+    match calibration {
+        Some(calibration) => {
+            for (channel, curve) in ramp.iter_mut().zip(calibration.scaled(scale, 256)) {
+                for (value, level) in channel.iter_mut().zip(curve) {
+                    *value = (level * 65535.0).round() as u16;
+                }
+            }
+        }
+        None => {
+            for channel in ramp.iter_mut() {
+                for (i, value) in channel.iter_mut().enumerate() {
+                    *value = ((i as f32 * 257.0) * scale).round() as u16;
+                }
+            }
+        }
+    }
+    ramp
+}
+
+fn ramps_match(a: &GammaRamp, b: &GammaRamp) -> bool {
+    a.iter()
+        .flatten()
+        .zip(b.iter().flatten())
+        .all(|(x, y)| x.abs_diff(*y) <= RAMP_TOLERANCE)
+}
+
+/// Dim `device` to `opacity` via its gamma ramp. Fails if the driver
+/// refuses or clamps the ramp, now or at this depth before.
+pub fn apply(device: &str, opacity: f32) -> Result<(), Refused> {
+    let _trace = trace::span(Mark::GammaUpdate, device, opacity);
+    if refused(device).is_some_and(|level| opacity >= level) {
+        return Err(Refused::Known);
+    }
+    let Some(dc) = DisplayDc::open(device) else {
+        return Err(Refused::NoDevice);
+    };
+
+    {
+        let mut saved = SAVED_RAMPS.lock().unwrap();
+        if !saved.iter().any(|(name, _)| name == device) {
+            if let Some(original) = dc.read() {
+                saved.push((device.to_string(), original));
+            }
+        }
+    }
+
+    let ramp = dimmed_ramp(opacity, calibration(device, &dc).as_ref());
+    if !dc.write(&ramp) {
+        eprintln!("[gamma] {} rejected ramp for {:.0}%", device, opacity * 100.0);
+        return Err(refuse(device, opacity));
+    }
+    match dc.read() {
+        Some(applied) if ramps_match(&applied, &ramp) => Ok(()),
+        _ => {
+            eprintln!("[gamma] {} clamped ramp for {:.0}%", device, opacity * 100.0);
+            Err(refuse(device, opacity))
+        }
+    }
+}
+
+/// Remember that `device` refused `opacity`
+fn refuse(device: &str, opacity: f32) -> Refused {
+    let mut refused = REFUSED.lock().unwrap();
+    match refused.iter_mut().find(|(name, _)| name == device) {
+        Some((_, level)) => *level = level.min(opacity),
+        None => refused.push((device.to_string(), opacity)),
+    }
+    Refused::New
+}
+
+/// Shallowest level `device` refused, if it refused any
+pub fn refused(device: &str) -> Option<f32> {
+    REFUSED
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(name, _)| name == device)
+        .map(|(_, level)| *level)
+}
+
+/// True if any device refused a ramp
+pub fn any_refused() -> bool {
+    !REFUSED.lock().unwrap().is_empty()
+}
+
+/// Try gamma again on every device (display configuration changed).
+pub fn forget_refusals() {
+    REFUSED.lock().unwrap().clear();
+}
+
+/// Put back the original ramp of one device (if we changed it).
+pub fn restore(device: &str) {
+    let mut saved = SAVED_RAMPS.lock().unwrap();
+    if let Some(pos) = saved.iter().position(|(name, _)| name == device) {
+        let (name, ramp) = saved.remove(pos);
+        if let Some(dc) = DisplayDc::open(&name) {
+            dc.write(&ramp);
+        }
+    }
+}
+
+/// Put back the original ramp of every device we changed.
+pub fn restore_all() {
+    forget_calibrations();
+    let mut saved = SAVED_RAMPS.lock().unwrap();
+    for (name, ramp) in saved.drain(..) {
+        if let Some(dc) = DisplayDc::open(&name) {
+            dc.write(&ramp);
+        }
+    }
+}
+
+/// Current ramp of `device` (`--self-test` before/after comparison)
+pub fn read(device: &str) -> Option<GammaRamp> {
+    DisplayDc::open(device)?.read()
+}
+
+/// True if `device` currently shows `ramp` (within driver rounding)
+pub fn shows(device: &str, ramp: &GammaRamp) -> bool {
+    read(device).is_some_and(|current| ramps_match(&current, ramp))
+}
+
+/// True if `device` is currently dimmed to `opacity` via its ramp
+pub fn is_dimmed_to(device: &str, opacity: f32) -> bool {
+    let Some(dc) = DisplayDc::open(device) else {
+        return false;
+    };
+    let ramp = dimmed_ramp(opacity, calibration(device, &dc).as_ref());
+    dc.read()
+        .is_some_and(|current| ramps_match(&current, &ramp))
+}
@@ -0,0 +1,103 @@
+// HDR (advanced color) detection per monitor via the DisplayConfig APIs.
+//
+// Each active display path is mapped back to its GDI device name
+// (\\.\DISPLAY1, ...) so the result lines up with the names the overlay and
+// gamma code already use. A path counts as HDR when advanced color is
+// enabled and it isn't merely wide color gamut on an SDR panel.
+//
+// In HDR the compositor works in scRGB: a black layered window at a given
+// alpha looks lighter than in SDR, and gamma ramps are ignored by most
+// drivers. overlay.rs uses this to pick the backend and an opacity offset
+// for HDR monitors.
+
+use windows::Win32::Devices::Display::{
+    DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+    DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO,
+    DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SOURCE_DEVICE_NAME,
+    QDC_ONLY_ACTIVE_PATHS,
+};
+use windows::Win32::Foundation::ERROR_SUCCESS;
+
+// Bits of DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO's flags word
+const ADVANCED_COLOR_ENABLED: u32 = 0x2;
+const WIDE_COLOR_ENFORCED: u32 = 0x4;
+
+/// Active display paths (one per lit monitor)
+pub fn active_paths() -> Vec<DISPLAYCONFIG_PATH_INFO> {
+    unsafe {
+        let mut path_count = 0u32;
+        let mut mode_count = 0u32;
+        if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
+            != ERROR_SUCCESS
+        {
+            return Vec::new();
+        }
+        let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+        let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+        if QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut path_count,
+            paths.as_mut_ptr(),
+            &mut mode_count,
+            modes.as_mut_ptr(),
+            None,
+        ) != ERROR_SUCCESS
+        {
+            return Vec::new();
+        }
+        paths.truncate(path_count as usize);
+        paths
+    }
+}
+
+/// GDI device name of the path's source (\\.\DISPLAY1, ...)
+pub fn source_name(path: &DISPLAYCONFIG_PATH_INFO) -> Option<String> {
+    let mut name = DISPLAYCONFIG_SOURCE_DEVICE_NAME {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+            size: std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
+            adapterId: path.sourceInfo.adapterId,
+            id: path.sourceInfo.id,
+        },
+        ..Default::default()
+    };
+    if unsafe { DisplayConfigGetDeviceInfo(&mut name.header) } != 0 {
+        return None;
+    }
+    let len = name
+        .viewGdiDeviceName
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(name.viewGdiDeviceName.len());
+    Some(String::from_utf16_lossy(&name.viewGdiDeviceName[..len]))
+}
+
+fn is_hdr_path(path: &DISPLAYCONFIG_PATH_INFO) -> bool {
+    let mut info = DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO,
+            size: std::mem::size_of::<DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO>() as u32,
+            adapterId: path.targetInfo.adapterId,
+            id: path.targetInfo.id,
+        },
+        ..Default::default()
+    };
+    if unsafe { DisplayConfigGetDeviceInfo(&mut info.header) } != 0 {
+        return false;
+    }
+    let flags = unsafe { info.Anonymous.value };
+    flags & ADVANCED_COLOR_ENABLED != 0 && flags & WIDE_COLOR_ENFORCED == 0
+}
+
+/// GDI device names of every monitor currently running in HDR, sorted.
+pub fn hdr_devices() -> Vec<String> {
+    let mut devices: Vec<String> = active_paths()
+        .iter()
+        .filter(|path| is_hdr_path(path))
+        .filter_map(source_name)
+        .collect();
+    devices.sort();
+    devices.dedup();
+    devices
+}
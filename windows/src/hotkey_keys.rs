@@ -0,0 +1,195 @@
+// Hotkey IDs and virtual key mapping for hotkeys.rs.
+//
+// Everything about a shortcut that doesn't need Windows itself: which
+// WM_HOTKEY ID belongs to which action, chord digit or custom action, and
+// how a shortcut (shared hotkeys.rs) maps to RegisterHotKey modifiers and a
+// virtual key and back. hotkeys.rs registers them; the settings window's
+// shortcut recorder reads the held modifiers itself (GetKeyState).
+
+use savemyeyes_shared::hotkeys::{HotkeyAction, Key, KeyCombo, NamedKey};
+
+use crate::sys::{
+    HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, VK_BACK, VK_CONTROL,
+    VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_F24, VK_HOME, VK_INSERT, VK_LCONTROL, VK_LEFT,
+    VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_NEXT, VK_OEM_COMMA, VK_OEM_PERIOD, VK_PRIOR,
+    VK_RCONTROL, VK_RETURN, VK_RIGHT, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_SPACE, VK_TAB,
+    VK_UP,
+};
+
+/// Hotkey IDs (must be unique within the application)
+pub const HOTKEY_TOGGLE: i32 = 1;
+pub const HOTKEY_INCREASE: i32 = 2;
+pub const HOTKEY_DECREASE: i32 = 3;
+pub const HOTKEY_MOVIE: i32 = 4;
+pub const HOTKEY_BOOST: i32 = 5;
+
+/// Digit hotkeys registered during a level chord: HOTKEY_DIGIT_BASE +
+/// variant * 10 + digit (variants: row, numpad, and both with the toggle
+/// hotkey's modifiers held)
+pub const HOTKEY_DIGIT_BASE: i32 = 100;
+pub const DIGIT_VARIANTS: i32 = 4;
+
+/// Custom action hotkeys: HOTKEY_CUSTOM_BASE + index into custom_actions
+pub const HOTKEY_CUSTOM_BASE: i32 = 200;
+/// Custom actions that get a hotkey (the rest of the ID space is unused)
+pub const MAX_CUSTOM: usize = 100;
+
+/// Hotkey ID that triggers `action`
+pub fn id_for(action: HotkeyAction) -> i32 {
+    match action {
+        HotkeyAction::Toggle => HOTKEY_TOGGLE,
+        HotkeyAction::Increase => HOTKEY_INCREASE,
+        HotkeyAction::Decrease => HOTKEY_DECREASE,
+        HotkeyAction::MovieMode => HOTKEY_MOVIE,
+        HotkeyAction::Boost => HOTKEY_BOOST,
+    }
+}
+
+/// Index into custom_actions for a hotkey ID, if it is one
+pub fn custom_action(id: i32) -> Option<usize> {
+    let index = usize::try_from(id - HOTKEY_CUSTOM_BASE).ok()?;
+    (index < MAX_CUSTOM).then_some(index)
+}
+
+/// Digit typed for a chord hotkey ID, if it is one
+pub fn chord_digit(id: i32) -> Option<u8> {
+    let offset = id - HOTKEY_DIGIT_BASE;
+    (0..DIGIT_VARIANTS * 10)
+        .contains(&offset)
+        .then_some((offset % 10) as u8)
+}
+
+/// RegisterHotKey modifiers and virtual key for a shortcut; `repeat` keeps
+/// the key's auto-repeat
+pub fn combo_keys(combo: &KeyCombo, repeat: bool) -> (HOT_KEY_MODIFIERS, u32) {
+    let held = combo.modifiers;
+    let mut mods = if repeat { 0 } else { MOD_NOREPEAT.0 };
+    for (on, flag) in [
+        (held.ctrl, MOD_CONTROL),
+        (held.alt, MOD_ALT),
+        (held.shift, MOD_SHIFT),
+        (held.meta, MOD_WIN),
+    ] {
+        if on {
+            mods |= flag.0;
+        }
+    }
+    // VK codes of A–Z and 0–9 are their upper case ASCII codes
+    let vk = match combo.key {
+        Key::Char(c) => c as u32,
+        Key::Function(n) => VK_F1.0 as u32 + n as u32 - 1,
+        Key::Named(key) => named_vk(key) as u32,
+    };
+    (HOT_KEY_MODIFIERS(mods), vk)
+}
+
+/// The shortcut key of virtual key `vk`. None for keys a shortcut can't use.
+pub fn key_from_vk(vk: u16) -> Option<Key> {
+    let key = match vk {
+        // VK codes of 0–9 and A–Z are their upper case ASCII codes
+        0x30..=0x39 | 0x41..=0x5A => Key::Char(vk as u8 as char),
+        _ if (VK_F1.0..=VK_F24.0).contains(&vk) => Key::Function((vk - VK_F1.0 + 1) as u8),
+        _ => Key::Named(NamedKey::ALL.into_iter().find(|key| named_vk(*key) == vk)?),
+    };
+    Some(key)
+}
+
+/// True for Ctrl, Alt, Shift and Win (left, right or either)
+pub fn is_modifier(vk: u16) -> bool {
+    [
+        VK_SHIFT, VK_CONTROL, VK_MENU, VK_LWIN, VK_RWIN, VK_LSHIFT, VK_RSHIFT, VK_LCONTROL,
+        VK_RCONTROL, VK_LMENU, VK_RMENU,
+    ]
+    .iter()
+    .any(|modifier| modifier.0 == vk)
+}
+
+fn named_vk(key: NamedKey) -> u16 {
+    let vk = match key {
+        NamedKey::Up => VK_UP,
+        NamedKey::Down => VK_DOWN,
+        NamedKey::Left => VK_LEFT,
+        NamedKey::Right => VK_RIGHT,
+        NamedKey::Home => VK_HOME,
+        NamedKey::End => VK_END,
+        NamedKey::PageUp => VK_PRIOR,
+        NamedKey::PageDown => VK_NEXT,
+        NamedKey::Insert => VK_INSERT,
+        NamedKey::Delete => VK_DELETE,
+        NamedKey::Space => VK_SPACE,
+        NamedKey::Tab => VK_TAB,
+        NamedKey::Enter => VK_RETURN,
+        NamedKey::Escape => VK_ESCAPE,
+        NamedKey::Backspace => VK_BACK,
+        NamedKey::Period => VK_OEM_PERIOD,
+        NamedKey::Comma => VK_OEM_COMMA,
+    };
+    vk.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use savemyeyes_shared::hotkeys::Modifiers;
+
+    fn combo(text: &str) -> KeyCombo {
+        KeyCombo::parse(text).unwrap()
+    }
+
+    #[test]
+    fn ids_map_back() {
+        assert_eq!(id_for(HotkeyAction::Toggle), HOTKEY_TOGGLE);
+        assert_eq!(id_for(HotkeyAction::Boost), HOTKEY_BOOST);
+        for id in [HOTKEY_TOGGLE, HOTKEY_BOOST] {
+            assert_eq!(custom_action(id), None);
+            assert_eq!(chord_digit(id), None);
+        }
+        assert_eq!(custom_action(HOTKEY_CUSTOM_BASE), Some(0));
+        assert_eq!(custom_action(HOTKEY_CUSTOM_BASE + 99), Some(99));
+        assert_eq!(custom_action(HOTKEY_CUSTOM_BASE + 100), None);
+        assert_eq!(chord_digit(HOTKEY_DIGIT_BASE + 7), Some(7));
+        // The numpad variant of 3 with the toggle's modifiers held
+        assert_eq!(chord_digit(HOTKEY_DIGIT_BASE + 33), Some(3));
+        assert_eq!(chord_digit(HOTKEY_DIGIT_BASE + DIGIT_VARIANTS * 10), None);
+        assert_eq!(chord_digit(HOTKEY_CUSTOM_BASE), None);
+    }
+
+    #[test]
+    fn combo_keys_sets_modifiers_and_repeat() {
+        let (mods, vk) = combo_keys(&combo("Ctrl+Alt+D"), false);
+        assert_eq!(mods.0, MOD_CONTROL.0 | MOD_ALT.0 | MOD_NOREPEAT.0);
+        assert_eq!(vk, 'D' as u32);
+        let (mods, vk) = combo_keys(&combo("Shift+Win+F12"), true);
+        assert_eq!(mods.0, MOD_SHIFT.0 | MOD_WIN.0);
+        assert_eq!(vk, 0x7B);
+        let (_, vk) = combo_keys(&combo("Ctrl+Up"), true);
+        assert_eq!(vk, VK_UP.0 as u32);
+    }
+
+    #[test]
+    fn every_key_maps_back() {
+        let mut keys: Vec<Key> = ('A'..='Z').chain('0'..='9').map(Key::Char).collect();
+        keys.extend((1..=24).map(Key::Function));
+        keys.extend(NamedKey::ALL.into_iter().map(Key::Named));
+        for key in keys {
+            let combo = KeyCombo {
+                modifiers: Modifiers::default(),
+                key,
+            };
+            let (_, vk) = combo_keys(&combo, false);
+            assert_eq!(key_from_vk(vk as u16), Some(key), "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn unusable_keys_and_modifiers() {
+        for vk in [VK_SHIFT, VK_LCONTROL, VK_RMENU, VK_LWIN] {
+            assert!(is_modifier(vk.0));
+            assert_eq!(key_from_vk(vk.0), None);
+        }
+        assert!(!is_modifier(b'A' as u16));
+        // Numpad 0 and Caps Lock can't be shortcuts
+        assert_eq!(key_from_vk(0x60), None);
+        assert_eq!(key_from_vk(0x14), None);
+    }
+}
@@ -1,44 +1,375 @@
 // Global hotkeys using Win32 RegisterHotKey API
+//
+// The hotkeys belong to a message-only window on a thread of their own, so
+// they still arrive while the settings window is busy painting or being
+// dragged. That thread only forwards each WM_HOTKEY to the settings window,
+// where it is handled like the other events; mouse-button hotkeys
+// (mouse_hooks.rs) post WM_HOTKEY with the same IDs. RegisterHotKey only
+// works on the thread that owns the window, so the functions below send
+// their request over to the hotkey thread and wait for it.
+//
+// Level chord: after the toggle hotkey, the digit keys (top row and numpad,
+// with or without the toggle's modifiers still held) are registered as hotkeys for
+// CHORD_TIMEOUT, then released again, so digits only leave other apps for
+// that short window.
+//
+// The toggle, increase and decrease shortcuts come from config
+// (`hotkey_toggle` etc., parsed by shared hotkeys.rs) and are registered
+// afresh whenever `set_keys` hands over new ones; movie mode and the boost
+// hotkey (focus_boost.rs) keep their fixed shortcuts. The hotkey IDs and
+// the virtual key of each shortcut come from hotkey_keys.rs.
+//
+// Custom actions (shared custom_actions.rs) add one hotkey each, from
+// HOTKEY_CUSTOM_BASE up, in config order. They are registered with the fixed
+// ones and again whenever the settings window edits them.
+//
+// Increase and decrease are registered without MOD_NOREPEAT, so holding them
+// sends auto-repeat WM_HOTKEYs that `ramp_step` turns into an accelerating
+// change (shared adjust_ramp.rs).
+//
+// With "Disable Global Shortcuts" on, nothing here is registered at all.
 
-use windows::Win32::Foundation::HWND;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use savemyeyes_shared::adjust_ramp::{AdjustRamp, HoldRamp};
+use savemyeyes_shared::custom_actions::CustomAction;
+use savemyeyes_shared::hotkeys::{
+    ChordStep, HotkeyAction, KeyCombo, LevelChord, Modifiers, CHORD_TIMEOUT,
+};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT,
-    VK_DOWN, VK_END, VK_UP,
+    GetKeyState, RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL,
+    MOD_NOREPEAT, VK_CONTROL, VK_LWIN, VK_MENU, VK_NUMPAD0, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, KillTimer, PostMessageW,
+    RegisterClassW, SendMessageW, SetTimer, HWND_MESSAGE, MSG, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_APP, WM_HOTKEY, WM_TIMER, WNDCLASSW,
 };
 
-/// Hotkey IDs (must be unique within the application)
-pub const HOTKEY_TOGGLE: i32 = 1;
-pub const HOTKEY_INCREASE: i32 = 2;
-pub const HOTKEY_DECREASE: i32 = 3;
+use crate::hotkey_keys::{
+    combo_keys, key_from_vk, DIGIT_VARIANTS, HOTKEY_CUSTOM_BASE, HOTKEY_DIGIT_BASE, MAX_CUSTOM,
+};
+pub use crate::hotkey_keys::{
+    chord_digit, custom_action, id_for, is_modifier, HOTKEY_BOOST, HOTKEY_DECREASE,
+    HOTKEY_INCREASE, HOTKEY_MOVIE, HOTKEY_TOGGLE,
+};
 
-/// Register all global hotkeys. Returns true if all succeed.
-pub fn register_all(hwnd: HWND) -> bool {
-    let mods = HOT_KEY_MODIFIERS(MOD_CONTROL.0 | MOD_ALT.0 | MOD_NOREPEAT.0);
-    let mut ok = true;
+const CLASS_NAME: &str = "SaveMyEyesHotkeys\0";
+
+/// Hotkey-window timer that ends an unfinished chord
+const CHORD_TIMER_ID: usize = 1;
+
+/// Requests sent to the hotkey window (RegisterHotKey must run on its thread)
+const WM_REGISTER_ALL: u32 = WM_APP + 1;
+const WM_UNREGISTER_ALL: u32 = WM_APP + 2;
+const WM_START_CHORD: u32 = WM_APP + 3;
+const WM_END_CHORD: u32 = WM_APP + 4;
+const WM_REGISTER_CUSTOM: u32 = WM_APP + 5;
 
-    unsafe {
-        // Ctrl+Alt+End → Toggle dimmer
-        if RegisterHotKey(Some(hwnd), HOTKEY_TOGGLE, mods, VK_END.0 as u32).is_err() {
-            ok = false;
+static CHORD: Mutex<LevelChord> = Mutex::new(LevelChord::new());
+static RAMP: Mutex<HoldRamp> = Mutex::new(HoldRamp::new());
+/// At least one of the hotkeys is registered (for diagnostics)
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+/// register_all ran and unregister_all hasn't since
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Shortcuts of the hotkey actions (config hotkey_toggle etc.)
+static KEYS: Mutex<Vec<(KeyCombo, HotkeyAction)>> = Mutex::new(Vec::new());
+/// Hotkey of each custom action, by index (None: it has no usable one)
+static CUSTOM: Mutex<Vec<Option<KeyCombo>>> = Mutex::new(Vec::new());
+/// Message-only window owning the hotkeys, and the window hotkeys go to
+static HOTKEY_HWND: AtomicIsize = AtomicIsize::new(0);
+static TARGET_HWND: AtomicIsize = AtomicIsize::new(0);
+
+unsafe extern "system" fn hotkey_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_HOTKEY => {
+            let target = TARGET_HWND.load(Ordering::SeqCst);
+            let _ = PostMessageW(Some(HWND(target as *mut _)), WM_HOTKEY, wparam, lparam);
+            LRESULT(0)
+        }
+        WM_REGISTER_ALL => LRESULT(register(hwnd) as isize),
+        WM_UNREGISTER_ALL => {
+            unregister(hwnd);
+            LRESULT(0)
+        }
+        WM_START_CHORD => {
+            register_digits(hwnd);
+            LRESULT(0)
         }
-        // Ctrl+Alt+Up → Increase opacity
-        if RegisterHotKey(Some(hwnd), HOTKEY_INCREASE, mods, VK_UP.0 as u32).is_err() {
-            ok = false;
+        WM_END_CHORD => {
+            unregister_digits(hwnd);
+            LRESULT(0)
         }
-        // Ctrl+Alt+Down → Decrease opacity
-        if RegisterHotKey(Some(hwnd), HOTKEY_DECREASE, mods, VK_DOWN.0 as u32).is_err() {
-            ok = false;
+        WM_REGISTER_CUSTOM => {
+            register_custom(hwnd);
+            LRESULT(0)
         }
+        WM_TIMER if wparam.0 == CHORD_TIMER_ID => {
+            // No digits in time: give the digit keys back
+            CHORD.lock().unwrap().cancel();
+            unregister_digits(hwnd);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
+}
 
-    ok
+unsafe fn create_window() -> Option<HWND> {
+    let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+    let class_name: Vec<u16> = CLASS_NAME.encode_utf16().collect();
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(hotkey_proc),
+        hInstance: hinstance.into(),
+        lpszClassName: PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+    match CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WINDOW_STYLE::default(),
+        0,
+        0,
+        0,
+        0,
+        Some(HWND_MESSAGE),
+        None,
+        Some(hinstance.into()),
+        None,
+    ) {
+        Ok(hwnd) => Some(hwnd),
+        Err(e) => {
+            eprintln!("[hotkeys] could not create the hotkey window: {}", e);
+            None
+        }
+    }
+}
+
+/// Start the hotkey thread; hotkeys are forwarded to `target` (the settings
+/// window). Returns once the hotkey window exists.
+pub fn start(target: HWND) {
+    TARGET_HWND.store(target.0 as isize, Ordering::SeqCst);
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || unsafe {
+        let hwnd = create_window();
+        if let Some(hwnd) = hwnd {
+            HOTKEY_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+        }
+        let _ = ready_tx.send(());
+        if hwnd.is_none() {
+            return;
+        }
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            DispatchMessageW(&msg);
+        }
+    });
+    let _ = ready_rx.recv();
+}
+
+/// Have the hotkey thread handle `msg` and wait for its answer
+fn request(msg: u32) -> LRESULT {
+    let hwnd = HOTKEY_HWND.load(Ordering::SeqCst);
+    if hwnd == 0 {
+        return LRESULT(0);
+    }
+    unsafe { SendMessageW(HWND(hwnd as *mut _), msg, None, None) }
+}
+
+/// Register all global hotkeys. Returns true if all succeed.
+pub fn register_all() -> bool {
+    ACTIVE.store(true, Ordering::SeqCst);
+    request(WM_REGISTER_ALL).0 != 0
 }
 
 /// Unregister all global hotkeys
-pub fn unregister_all(hwnd: HWND) {
-    unsafe {
-        let _ = UnregisterHotKey(Some(hwnd), HOTKEY_TOGGLE);
-        let _ = UnregisterHotKey(Some(hwnd), HOTKEY_INCREASE);
-        let _ = UnregisterHotKey(Some(hwnd), HOTKEY_DECREASE);
+pub fn unregister_all() {
+    ACTIVE.store(false, Ordering::SeqCst);
+    request(WM_UNREGISTER_ALL);
+}
+
+/// Use `keys` (config `key_bindings`) for the hotkey actions, registering
+/// them now unless global hotkeys are off. Returns false if one of them is
+/// taken by another app.
+pub fn set_keys(keys: Vec<(KeyCombo, HotkeyAction)>) -> bool {
+    *KEYS.lock().unwrap() = keys;
+    if ACTIVE.load(Ordering::SeqCst) {
+        request(WM_REGISTER_ALL).0 != 0
+    } else {
+        true
+    }
+}
+
+/// Use the hotkeys of `actions` (config custom_actions), registering them
+/// now unless global hotkeys are off.
+pub fn set_custom_actions(actions: &[CustomAction]) {
+    *CUSTOM.lock().unwrap() = actions
+        .iter()
+        .take(MAX_CUSTOM)
+        .map(CustomAction::combo)
+        .collect();
+    if ACTIVE.load(Ordering::SeqCst) {
+        request(WM_REGISTER_CUSTOM);
+    }
+}
+
+/// True while the global hotkeys are registered
+pub fn is_registered() -> bool {
+    REGISTERED.load(Ordering::SeqCst)
+}
+
+/// Level change for an increase (`up`) or decrease hotkey event, following
+/// the configured ramp while the key is held. 0.0 until a slow hold adds up
+/// to a whole step.
+pub fn ramp_step(ramp: &AdjustRamp, up: bool) -> f32 {
+    let now = Instant::now();
+    RAMP.lock().unwrap().next(ramp.profile(), up, now)
+}
+
+/// Start listening for level digits after the toggle hotkey.
+pub fn start_level_chord() {
+    CHORD.lock().unwrap().start(Instant::now());
+    request(WM_START_CHORD);
+}
+
+/// Feed a chord digit; ends the chord unless more digits are expected.
+pub fn push_chord_digit(digit: u8) -> ChordStep {
+    let step = CHORD.lock().unwrap().push_digit(digit, Instant::now());
+    if !matches!(step, ChordStep::Partial(_)) {
+        end_level_chord();
+    }
+    step
+}
+
+/// Stop listening for digits and give the keys back to other apps.
+pub fn end_level_chord() {
+    CHORD.lock().unwrap().cancel();
+    request(WM_END_CHORD);
+}
+
+/// Register the hotkey actions' shortcuts afresh on the hotkey window (its
+/// thread only)
+unsafe fn register(hwnd: HWND) -> bool {
+    unregister(hwnd);
+    let keys = KEYS.lock().unwrap().clone();
+    let mut ok = true;
+    let mut any = false;
+    for (combo, action) in &keys {
+        // Auto-repeat while held drives the adjust ramp
+        let repeat = matches!(action, HotkeyAction::Increase | HotkeyAction::Decrease);
+        let (mods, vk) = combo_keys(combo, repeat);
+        match RegisterHotKey(Some(hwnd), id_for(*action), mods, vk) {
+            Ok(()) => any = true,
+            Err(e) => {
+                eprintln!("[hotkeys] {} not registered: {}", combo, e);
+                ok = false;
+            }
+        }
+    }
+
+    REGISTERED.store(any, Ordering::SeqCst);
+    register_custom(hwnd);
+    ok
+}
+
+unsafe fn unregister(hwnd: HWND) {
+    for id in [
+        HOTKEY_TOGGLE,
+        HOTKEY_INCREASE,
+        HOTKEY_DECREASE,
+        HOTKEY_MOVIE,
+        HOTKEY_BOOST,
+    ] {
+        let _ = UnregisterHotKey(Some(hwnd), id);
+    }
+    unregister_custom(hwnd);
+    REGISTERED.store(false, Ordering::SeqCst);
+}
+
+/// Register the custom action hotkeys afresh (hotkey thread only). One
+/// taken by another app only costs that action its hotkey.
+unsafe fn register_custom(hwnd: HWND) {
+    unregister_custom(hwnd);
+    let combos = CUSTOM.lock().unwrap().clone();
+    for (index, combo) in combos.iter().enumerate() {
+        let Some(combo) = combo else {
+            continue;
+        };
+        let (mods, vk) = combo_keys(combo, false);
+        let id = HOTKEY_CUSTOM_BASE + index as i32;
+        if let Err(e) = RegisterHotKey(Some(hwnd), id, mods, vk) {
+            eprintln!(
+                "[hotkeys] custom action {} hotkey not registered: {}",
+                index + 1,
+                e
+            );
+        }
+    }
+}
+
+unsafe fn unregister_custom(hwnd: HWND) {
+    for id in HOTKEY_CUSTOM_BASE..HOTKEY_CUSTOM_BASE + MAX_CUSTOM as i32 {
+        let _ = UnregisterHotKey(Some(hwnd), id);
+    }
+}
+
+/// The shortcut of key `vk` with the modifiers held right now (the settings
+/// window's shortcut recorder). None for keys a shortcut can't use.
+pub fn combo_from_key(vk: u16) -> Option<KeyCombo> {
+    let key = key_from_vk(vk)?;
+    let held = |vk: u16| unsafe { GetKeyState(vk as i32) } < 0;
+    let modifiers = Modifiers {
+        ctrl: held(VK_CONTROL.0),
+        alt: held(VK_MENU.0),
+        shift: held(VK_SHIFT.0),
+        meta: held(VK_LWIN.0) || held(VK_RWIN.0),
+    };
+    Some(KeyCombo { modifiers, key })
+}
+
+/// Register the chord digits and arm the chord timeout (hotkey thread only)
+unsafe fn register_digits(hwnd: HWND) {
+    let plain = HOT_KEY_MODIFIERS(MOD_NOREPEAT.0);
+    let toggle = KEYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(_, action)| *action == HotkeyAction::Toggle)
+        .map(|(combo, _)| combo_keys(combo, false).0);
+    let held = toggle.unwrap_or(HOT_KEY_MODIFIERS(MOD_CONTROL.0 | MOD_ALT.0 | MOD_NOREPEAT.0));
+    let variants = [
+        (plain, b'0' as u32),
+        (plain, VK_NUMPAD0.0 as u32),
+        (held, b'0' as u32),
+        (held, VK_NUMPAD0.0 as u32),
+    ];
+
+    for (variant, (mods, vk_zero)) in variants.iter().enumerate() {
+        for digit in 0..10 {
+            let id = HOTKEY_DIGIT_BASE + variant as i32 * 10 + digit;
+            // Already registered if a chord is restarted; failures just
+            // mean that variant is taken by another app
+            let _ = RegisterHotKey(Some(hwnd), id, *mods, vk_zero + digit as u32);
+        }
+    }
+    let timeout_ms = CHORD_TIMEOUT.as_millis() as u32;
+    SetTimer(Some(hwnd), CHORD_TIMER_ID, timeout_ms, None);
+}
+
+unsafe fn unregister_digits(hwnd: HWND) {
+    let _ = KillTimer(Some(hwnd), CHORD_TIMER_ID);
+    for id in HOTKEY_DIGIT_BASE..HOTKEY_DIGIT_BASE + DIGIT_VARIANTS * 10 {
+        let _ = UnregisterHotKey(Some(hwnd), id);
     }
 }
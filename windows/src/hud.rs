@@ -0,0 +1,236 @@
+// On-screen HUD for hotkey feedback.
+//
+// A small rounded pill near the bottom of the monitor under the cursor that
+// shows the new level for a moment after a hotkey changed it, e.g.
+// "Dell U2720Q: 60%". The window is click-through, never takes focus and is
+// raised above the dimming overlays each time it is shown so it stays
+// readable. One window is created lazily and reused; a timer hides it,
+// fading it out unless animations are off (shared motion.rs). Nothing is
+// shown during presentation mode.
+
+use std::sync::atomic::{AtomicIsize, AtomicU32, Ordering};
+use std::sync::Mutex;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, SIZE, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateFontIndirectW, CreateRoundRectRgn, CreateSolidBrush, DeleteObject, DrawTextW,
+    EndPaint, FillRect, GetDC, GetTextExtentPoint32W, InvalidateRect, ReleaseDC, SelectObject,
+    SetBkMode, SetTextColor, SetWindowRgn, CLEARTYPE_QUALITY, DEFAULT_CHARSET, DT_CENTER,
+    DT_SINGLELINE, DT_VCENTER, HFONT, HGDIOBJ, LOGFONTW, PAINTSTRUCT, TRANSPARENT,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, GetClientRect, GetCursorPos, KillTimer, RegisterClassW,
+    SetLayeredWindowAttributes, SetTimer, SetWindowPos, ShowWindow, SystemParametersInfoW,
+    HWND_TOPMOST, LWA_ALPHA, SPI_GETCLIENTAREAANIMATION, SWP_NOACTIVATE, SWP_SHOWWINDOW, SW_HIDE,
+    SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, WM_PAINT, WM_TIMER, WNDCLASSW, WS_EX_LAYERED,
+    WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+};
+
+use savemyeyes_shared::motion;
+use savemyeyes_shared::presentation;
+use savemyeyes_shared::trace::Mark;
+
+use crate::monitors;
+use crate::trace;
+use crate::ui::theme::{CLR_FOREGROUND, CLR_SECONDARY, FONT_NAME};
+
+const CLASS_NAME: &str = "SaveMyEyesHud\0";
+const HIDE_TIMER_ID: usize = 1;
+const FADE_TIMER_ID: usize = 2;
+/// How long the HUD stays up after the last update
+const SHOW_MS: u32 = 1200;
+const FONT_SIZE: i32 = -16;
+const PAD_X: i32 = 20;
+const PAD_Y: i32 = 10;
+/// Distance from the bottom of the work area
+const BOTTOM_MARGIN: i32 = 96;
+const ALPHA: u8 = 235;
+/// Fade-out: FADE_STEPS alpha steps, FADE_STEP_MS apart
+const FADE_STEPS: u32 = 12;
+const FADE_STEP_MS: u32 = 16;
+
+static HUD_HWND: AtomicIsize = AtomicIsize::new(0);
+static MESSAGE: Mutex<String> = Mutex::new(String::new());
+/// Fade steps left while fading out
+static FADE_LEFT: AtomicU32 = AtomicU32::new(0);
+
+/// "Animation effects" are turned off in Windows settings
+pub fn os_reduces_motion() -> bool {
+    let mut animate = windows::core::BOOL::from(true);
+    let read = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut animate as *mut _ as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    read.is_ok() && !animate.as_bool()
+}
+
+/// Hide at once and restore full alpha for the next show
+unsafe fn hide(hwnd: HWND) {
+    let _ = KillTimer(Some(hwnd), FADE_TIMER_ID);
+    let _ = ShowWindow(hwnd, SW_HIDE);
+    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), ALPHA, LWA_ALPHA);
+}
+
+fn create_font() -> HFONT {
+    let face: Vec<u16> = FONT_NAME.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut lf = LOGFONTW {
+        lfHeight: FONT_SIZE,
+        lfWeight: 600,
+        lfQuality: CLEARTYPE_QUALITY,
+        lfCharSet: DEFAULT_CHARSET,
+        ..Default::default()
+    };
+    let len = face.len().min(32);
+    lf.lfFaceName[..len].copy_from_slice(&face[..len]);
+    unsafe { CreateFontIndirectW(&lf) }
+}
+
+unsafe extern "system" fn hud_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut rect = RECT::default();
+            let _ = GetClientRect(hwnd, &mut rect);
+
+            let brush = CreateSolidBrush(CLR_SECONDARY);
+            FillRect(hdc, &rect, brush);
+            let _ = DeleteObject(HGDIOBJ::from(brush));
+
+            let font = create_font();
+            let old_font = SelectObject(hdc, HGDIOBJ::from(font));
+            SetTextColor(hdc, CLR_FOREGROUND);
+            SetBkMode(hdc, TRANSPARENT);
+            let mut text: Vec<u16> = MESSAGE.lock().unwrap().encode_utf16().collect();
+            DrawTextW(hdc, &mut text, &mut rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+            SelectObject(hdc, old_font);
+            let _ = DeleteObject(HGDIOBJ::from(font));
+
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_TIMER if wparam.0 == HIDE_TIMER_ID => {
+            let _ = KillTimer(Some(hwnd), HIDE_TIMER_ID);
+            if motion::animate(os_reduces_motion) {
+                FADE_LEFT.store(FADE_STEPS, Ordering::SeqCst);
+                SetTimer(Some(hwnd), FADE_TIMER_ID, FADE_STEP_MS, None);
+            } else {
+                hide(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_TIMER if wparam.0 == FADE_TIMER_ID => {
+            let left = FADE_LEFT.load(Ordering::SeqCst).saturating_sub(1);
+            FADE_LEFT.store(left, Ordering::SeqCst);
+            if left == 0 {
+                hide(hwnd);
+            } else {
+                let alpha = (ALPHA as u32 * left / FADE_STEPS) as u8;
+                trace::mark(Mark::FadeFrame, "hud", alpha as f32 / 255.0);
+                let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+            }
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// The HUD window, created on first use.
+fn window() -> Option<HWND> {
+    let existing = HUD_HWND.load(Ordering::SeqCst);
+    if existing != 0 {
+        return Some(HWND(existing as *mut _));
+    }
+
+    unsafe {
+        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+        let class_name: Vec<u16> = CLASS_NAME.encode_utf16().collect();
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(hud_proc),
+            hInstance: hinstance.into(),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR::null(),
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(hinstance.into()),
+            None,
+        )
+        .ok()?;
+        let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), ALPHA, LWA_ALPHA);
+        HUD_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+        Some(hwnd)
+    }
+}
+
+/// Show `message` on the monitor under the cursor (UI thread only).
+/// Calling again while visible updates the text and restarts the timer.
+pub fn show(message: &str) {
+    if presentation::is_active() {
+        return;
+    }
+    let Some(hwnd) = window() else {
+        return;
+    };
+    *MESSAGE.lock().unwrap() = message.to_string();
+
+    unsafe {
+        // Size the pill to the text
+        let hdc = GetDC(Some(hwnd));
+        let font = create_font();
+        let old_font = SelectObject(hdc, HGDIOBJ::from(font));
+        let wide: Vec<u16> = message.encode_utf16().collect();
+        let mut size = SIZE::default();
+        let _ = GetTextExtentPoint32W(hdc, &wide, &mut size);
+        SelectObject(hdc, old_font);
+        let _ = DeleteObject(HGDIOBJ::from(font));
+        ReleaseDC(Some(hwnd), hdc);
+        let width = size.cx + PAD_X * 2;
+        let height = size.cy + PAD_Y * 2;
+
+        // Bottom center of the work area of the monitor under the cursor
+        let mut pt = POINT::default();
+        let _ = GetCursorPos(&mut pt);
+        let Some(work) = monitors::at_point(pt.x, pt.y).map(|m| m.work) else {
+            return;
+        };
+        let x = work.left + (work.right - work.left - width) / 2;
+        let y = work.bottom - BOTTOM_MARGIN - height;
+
+        // The window owns the region after SetWindowRgn
+        let region = CreateRoundRectRgn(0, 0, width + 1, height + 1, height, height);
+        if SetWindowRgn(hwnd, Some(region), false) == 0 {
+            let _ = DeleteObject(HGDIOBJ::from(region));
+        }
+        let _ = SetWindowPos(
+            hwnd,
+            Some(HWND_TOPMOST),
+            x,
+            y,
+            width,
+            height,
+            SWP_NOACTIVATE | SWP_SHOWWINDOW,
+        );
+        // Cut short a fade-out still running
+        let _ = KillTimer(Some(hwnd), FADE_TIMER_ID);
+        let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), ALPHA, LWA_ALPHA);
+        let _ = InvalidateRect(Some(hwnd), None, true);
+        SetTimer(Some(hwnd), HIDE_TIMER_ID, SHOW_MS, None);
+    }
+}
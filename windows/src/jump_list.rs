@@ -0,0 +1,93 @@
+// Taskbar jump list.
+//
+// Right-clicking the taskbar button (or the pinned app) offers Toggle Dimmer,
+// Pause for 30 Minutes and Open Settings, so dimming can be controlled with
+// the mouse without finding the tray icon. Each task starts the exe again
+// with a command line flag (shared events.rs `from_args`); that process
+// forwards the command to this instance over WM_COPYDATA (events.rs) and
+// exits. The list is written at every start so it follows the exe if it moves.
+
+use std::path::Path;
+
+use windows::core::{Interface, Result, HSTRING};
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::StructuredStorage::{PropVariantClear, PROPVARIANT};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::System::Variant::VT_LPWSTR;
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, SHStrDupW,
+    ShellLink,
+};
+
+use savemyeyes_shared::events::{PAUSE_ARG, SETTINGS_ARG, TOGGLE_ARG};
+use savemyeyes_shared::pause;
+
+/// Length of the jump list's pause
+const PAUSE_MINUTES: u64 = 30;
+
+/// Write the Tasks section of the jump list (UI thread, at startup).
+pub fn install() {
+    let Ok(exe) = std::env::current_exe() else {
+        eprintln!("[jump_list] could not find our exe");
+        return;
+    };
+    match write_tasks(&exe) {
+        Ok(()) => eprintln!("[jump_list] tasks installed"),
+        Err(e) => eprintln!("[jump_list] could not install tasks: {}", e),
+    }
+}
+
+/// (title, arguments) of each task, top to bottom
+fn tasks() -> Vec<(String, String)> {
+    vec![
+        ("Toggle Dimmer".to_string(), TOGGLE_ARG.to_string()),
+        (
+            pause::duration_label(PAUSE_MINUTES),
+            format!("{} {}", PAUSE_ARG, PAUSE_MINUTES),
+        ),
+        ("Open Settings".to_string(), SETTINGS_ARG.to_string()),
+    ]
+}
+
+fn write_tasks(exe: &Path) -> Result<()> {
+    unsafe {
+        // Already initialized on this thread is fine
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut slots = 0u32;
+        let _removed: IObjectArray = list.BeginList(&mut slots)?;
+        let collection: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for (title, args) in tasks() {
+            collection.AddObject(&task(exe, &title, &args)?)?;
+        }
+        list.AddUserTasks(&collection)?;
+        list.CommitList()
+    }
+}
+
+/// Shell link that starts `exe` with `args`, shown as `title`
+unsafe fn task(exe: &Path, title: &str, args: &str) -> Result<IShellLinkW> {
+    let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+    let exe = HSTRING::from(exe.as_os_str());
+    link.SetPath(&exe)?;
+    link.SetArguments(&HSTRING::from(args))?;
+    link.SetIconLocation(&exe, 0)?;
+
+    // Tasks show the link's title property, not its description
+    let store: IPropertyStore = link.cast()?;
+    let mut value = PROPVARIANT::default();
+    (*value.Anonymous.Anonymous).vt = VT_LPWSTR;
+    (*value.Anonymous.Anonymous).Anonymous.pwszVal = SHStrDupW(&HSTRING::from(title))?;
+    let result = store
+        .SetValue(&PKEY_Title, &value)
+        .and_then(|()| store.Commit());
+    let _ = PropVariantClear(&mut value);
+    result?;
+    Ok(link)
+}
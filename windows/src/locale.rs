@@ -0,0 +1,51 @@
+// Regional number and time format (shared locale.rs) on Windows.
+//
+// Read once at startup from the user's regional settings: the decimal
+// separator, and whether the time format uses a 24-hour clock ("H" rather
+// than "h" in LOCALE_STIMEFORMAT). A change in Settings > Time & language
+// shows after a restart.
+
+#[cfg(windows)]
+use windows::core::PCWSTR;
+#[cfg(windows)]
+use windows::Win32::Globalization::GetLocaleInfoEx;
+
+use savemyeyes_shared::locale::{self, Locale};
+
+use crate::sys::{LOCALE_SDECIMAL, LOCALE_STIMEFORMAT};
+
+/// Read the regional format and hand it to the shared formatter.
+pub fn init() {
+    let fixed = Locale::FIXED;
+    let decimal_separator = info(LOCALE_SDECIMAL)
+        .and_then(|text| text.chars().next())
+        .unwrap_or(fixed.decimal_separator);
+    let hour24 = info(LOCALE_STIMEFORMAT).map_or(fixed.hour24, |format| format.contains('H'));
+    let current = Locale {
+        decimal_separator,
+        hour24,
+        rtl: locale::rtl_requested(),
+    };
+    if current != fixed {
+        eprintln!("[locale] {:?}", current);
+    }
+    locale::set(current);
+}
+
+/// One value of the user's default locale
+#[cfg(windows)]
+fn info(kind: u32) -> Option<String> {
+    let mut buf = [0u16; 80];
+    // A null name is LOCALE_NAME_USER_DEFAULT
+    let len = unsafe { GetLocaleInfoEx(PCWSTR::null(), kind, Some(&mut buf)) };
+    if len <= 1 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buf[..len as usize - 1]))
+}
+
+/// Headless builds: no regional settings, so the fixed format
+#[cfg(not(windows))]
+fn info(_kind: u32) -> Option<String> {
+    None
+}
@@ -0,0 +1,39 @@
+// Device location for the sunset/sunrise schedule (shared scheduler.rs) on
+// Windows.
+//
+// Asked on the schedule thread through the WinRT Geolocator, which blocks
+// until Windows has a fix or refuses: location services off, or desktop
+// apps denied under Settings > Privacy > Location. A refusal just means no
+// location; `schedule.latitude`/`longitude` in config work without it.
+
+#[cfg(windows)]
+use windows::Devices::Geolocation::Geolocator;
+#[cfg(windows)]
+use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+/// Latitude and longitude of the device, if Windows will tell
+#[cfg(windows)]
+pub fn locate() -> Option<(f64, f64)> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+    let position = Geolocator::new()
+        .and_then(|locator| locator.GetGeopositionAsync())
+        .and_then(|operation| operation.get())
+        .and_then(|position| position.Coordinate())
+        .and_then(|coordinate| coordinate.Point())
+        .and_then(|point| point.Position());
+    match position {
+        Ok(position) => Some((position.Latitude, position.Longitude)),
+        Err(e) => {
+            eprintln!("[location] no location from Windows: {}", e);
+            None
+        }
+    }
+}
+
+/// Headless builds have no location
+#[cfg(not(windows))]
+pub fn locate() -> Option<(f64, f64)> {
+    None
+}
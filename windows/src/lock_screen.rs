@@ -0,0 +1,57 @@
+// Dimming on the lock screen.
+//
+// The lock screen lives on the secure desktop, where our overlay windows
+// don't exist, so locking at night used to show it at full brightness.
+// Gamma ramps belong to the display rather than a desktop and stay applied
+// there. With `dim_lock_screen` on, the settings window is registered for
+// session notifications. On WTS_SESSION_LOCK every monitor switches to gamma
+// dimming (overlay.rs re-applies the current levels), and on unlock each
+// goes back to its configured backend. Monitors in HDR keep their HDR
+// backend, since most drivers ignore gamma ramps there. Nothing here needs
+// a service; the sign-in screen before logon stays undimmed.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use windows::Win32::Foundation::{HWND, WPARAM};
+use windows::Win32::System::RemoteDesktop::{
+    WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{WTS_SESSION_LOCK, WTS_SESSION_UNLOCK};
+
+use crate::overlay;
+
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Start or stop receiving WM_WTSSESSION_CHANGE in `hwnd`.
+pub fn set_enabled(hwnd: HWND, enabled: bool) {
+    if enabled == REGISTERED.load(Ordering::SeqCst) {
+        return;
+    }
+    unsafe {
+        if enabled {
+            if let Err(e) = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) {
+                eprintln!("[lock] session notifications unavailable: {}", e);
+                return;
+            }
+        } else {
+            let _ = WTSUnRegisterSessionNotification(hwnd);
+            overlay::set_session_locked(false);
+        }
+    }
+    REGISTERED.store(enabled, Ordering::SeqCst);
+}
+
+/// Handle WM_WTSSESSION_CHANGE
+pub fn on_session_change(wparam: WPARAM) {
+    match wparam.0 as u32 {
+        WTS_SESSION_LOCK => {
+            eprintln!("[lock] session locked, dimming through gamma");
+            overlay::set_session_locked(true);
+        }
+        WTS_SESSION_UNLOCK => {
+            eprintln!("[lock] session unlocked");
+            overlay::set_session_locked(false);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,38 @@
+// Windows Magnifier detection.
+//
+// Magnifier re-renders the desktop — overlay windows included — so a
+// magnified region of a dimmed screen gets dimmed twice and low alpha shows
+// banding. Gamma dimming is applied after composition and doesn't have this
+// problem, so the overlay switches to it while Magnifier runs (zoom compat).
+
+#[cfg(windows)]
+use windows::core::PCWSTR;
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::{FindWindowW, IsWindowVisible};
+
+/// Top-level window classes owned by Magnify.exe
+const MAGNIFIER_CLASSES: &[&str] = &["MagUIClass", "Screen Magnifier Window"];
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// True while Windows Magnifier is running and showing a magnified view.
+#[cfg(windows)]
+pub fn is_active() -> bool {
+    MAGNIFIER_CLASSES.iter().any(|class| {
+        let class = wide(class);
+        unsafe {
+            match FindWindowW(PCWSTR(class.as_ptr()), PCWSTR::null()) {
+                Ok(hwnd) => IsWindowVisible(hwnd).as_bool(),
+                Err(_) => false,
+            }
+        }
+    })
+}
+
+/// Headless builds: there is no Magnifier
+#[cfg(not(windows))]
+pub fn is_active() -> bool {
+    false
+}
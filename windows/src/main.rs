@@ -1,59 +1,299 @@
 // Prevents console window in release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+// Headless builds compile modules whose callers are Windows-only
+#![cfg_attr(not(windows), allow(dead_code))]
 
+// Built for another OS with feature "headless" (CI checking the workspace on
+// one host), the binary is the shared no-op backend's main (shared
+// backend.rs), but the modules that only call Windows for a value or two
+// still build and run their tests: their Windows calls sit behind
+// cfg(windows) with headless stand-ins, and sys.rs has the Win32 data types
+// they use. Everything else is Windows-only.
+#[cfg(all(not(windows), not(feature = "headless")))]
+compile_error!("savemyeyes-windows targets Windows; build it elsewhere with --features headless");
+
+#[cfg(not(windows))]
+fn main() {
+    std::process::exit(savemyeyes_shared::backend::headless_main("Windows"));
+}
+
+#[cfg(windows)]
 mod autostart;
+mod background;
+#[cfg(windows)]
+mod backups;
+#[cfg(windows)]
+mod brightness;
+#[cfg(windows)]
+mod color_filter;
+#[cfg(windows)]
 mod config;
+#[cfg(windows)]
+mod contrast_guard;
+#[cfg(windows)]
+mod custom_actions;
+#[cfg(windows)]
+mod ddcci;
+#[cfg(windows)]
+mod elevation;
+#[cfg(windows)]
+mod events;
+#[cfg(windows)]
+mod explainer;
+mod faults;
+#[cfg(windows)]
+mod focus_boost;
+#[cfg(windows)]
+mod gamma;
+#[cfg(windows)]
+mod hdr;
+mod hotkey_keys;
+#[cfg(windows)]
 mod hotkeys;
+#[cfg(windows)]
+mod hud;
+#[cfg(windows)]
+mod jump_list;
+mod locale;
+mod location;
+#[cfg(windows)]
+mod lock_screen;
+mod magnifier;
+mod monitor_identity;
+#[cfg(windows)]
+mod monitors;
+#[cfg(windows)]
+mod mouse_hooks;
+#[cfg(windows)]
+mod movie_mode;
+#[cfg(windows)]
+mod native_host;
+#[cfg(windows)]
 mod overlay;
+#[cfg(windows)]
+mod overlay_compat;
+#[cfg(windows)]
+mod overlay_gradient;
+mod poll_pace;
+mod resources;
+#[cfg(windows)]
+mod scheduler;
+#[cfg(windows)]
+mod self_test;
+#[cfg(windows)]
+mod shell_windows;
+#[cfg(windows)]
+mod soak;
+mod sys;
+#[cfg(windows)]
+mod system_theme;
+#[cfg(windows)]
+mod trace;
+#[cfg(windows)]
 mod tray;
+#[cfg(windows)]
 mod ui;
+#[cfg(not(windows))]
+mod ui {
+    pub mod controls;
+    pub mod theme;
+}
+#[cfg(windows)]
 mod updater;
+#[cfg(windows)]
+mod url_scheme;
 
+#[cfg(windows)]
 use config::AppConfig;
+#[cfg(windows)]
+use savemyeyes_shared::events::{Command, USAGE};
+#[cfg(windows)]
+use savemyeyes_shared::explain::{self, Cause};
+#[cfg(windows)]
+use savemyeyes_shared::{
+    api_schema, dimming, monitor_groups, motion, presentation, profiles, shutdown,
+};
+#[cfg(windows)]
 use std::sync::{Arc, Mutex};
+#[cfg(windows)]
 use windows::core::PCWSTR;
+#[cfg(windows)]
 use windows::Win32::System::Threading::{CreateMutexW, OpenMutexW, SYNCHRONIZATION_ACCESS_RIGHTS};
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{
     DispatchMessageW, GetMessageW, TranslateMessage, MSG,
 };
 
+#[cfg(windows)]
 const SINGLE_INSTANCE_MUTEX: &str = "SaveMyEyesMutex\0";
 
+#[cfg(windows)]
 fn main() {
-    // Single-instance check
-    if is_already_running() {
+    // Backend smoke test on a real desktop (CI); never starts the app
+    if let Some(code) = self_test::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // API schema for third-party clients; never starts the app
+    if let Some(code) = api_schema::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // Browser extension bridge; never starts the app
+    if let Some(code) = native_host::run_from_args() {
+        std::process::exit(code);
+    }
+
+    // Jump list task or command line (--toggle, --opacity 40, ...): hand
+    // the command to the running instance. If there is none, start
+    // normally and run it once the window exists. A bad command line
+    // exits with the usage instead.
+    let launch_command = match Command::from_args() {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if launch_command.as_ref().is_some_and(events::forward) {
         return;
     }
 
-    // Clean up .old exe from a previous self-update
-    updater::cleanup_old_exe();
+    // Randomized soak test (nightly CI): the app itself, on scratch settings
+    let soak_run = match savemyeyes_shared::soak::from_args() {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("[soak] {}", e);
+            std::process::exit(2);
+        }
+    };
 
-    // Check if we were just updated
+    // Single-instance check. An elevated relaunch, a restart after a backup
+    // restore or an update waits for the instance that started it to exit
+    // and release the mutex.
+    let relaunched_elevated = elevation::was_relaunched_elevated();
+    let restored = savemyeyes_shared::backups::was_restored();
     let just_updated = updater::was_just_updated();
+    if is_already_running(relaunched_elevated || restored || just_updated) {
+        if soak_run.is_some() {
+            // Its commands would reach the running instance instead
+            eprintln!("[soak] SaveMyEyes is already running; quit it first");
+            std::process::exit(2);
+        }
+        if let Some(command) = launch_command {
+            // Running but its window didn't answer (still starting, hung)
+            eprintln!("SaveMyEyes is running but didn't take {:?}", command);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if soak_run.is_some() {
+        savemyeyes_shared::soak::prepare();
+    }
+
+    // Background threads run below normal priority, with EcoQoS
+    background::init();
+    // Decimal separator and clock for everything shown from here on
+    locale::init();
+    // Trace events for WPA, before the first overlay is created
+    trace::init();
+
+    // Dimming from before a self-update relaunch (or a crash) goes back on
+    // screen first; the setup below takes it over
+    explain::note(Cause::Startup);
+    let warm_started = overlay::warm_start();
+
+    // Clean up .old exe from a previous self-update
+    updater::cleanup_old_exe();
 
     // Load config
-    let cfg = config::load_config();
+    let mut cfg = config::load_config();
+    // Pick up screen state that was applied but never saved (crash mid-drag)
+    savemyeyes_shared::journal::reconcile(&mut cfg);
+    updater::set_endpoint(cfg.update_endpoint.as_deref());
+    // Managed installs: apply the seeded autostart choice once
+    if config::is_first_deployed_run() {
+        if cfg.launch_on_login {
+            autostart::enable();
+        } else {
+            autostart::disable();
+        }
+    }
     let config = Arc::new(Mutex::new(cfg));
 
+    // Remember the monitor layout for troubleshooting reports
+    monitors::record_layout();
+
     // Create the settings window
     let hwnd = ui::create_window(config.clone());
 
     // Setup system tray
     tray::add_tray_icon(hwnd);
+    events::allow_senders(hwnd);
+    jump_list::install();
+    url_scheme::register();
+    ui::watch_save_failures(hwnd);
+    ui::report_config_fixes(hwnd);
+    system_theme::watch(hwnd);
+    // Gamma dimming while locked, so the lock screen isn't full brightness
+    lock_screen::set_enabled(hwnd, config.lock().unwrap().dim_lock_screen);
 
-    // Register global hotkeys
-    hotkeys::register_all(hwnd);
+    // Register global hotkeys (none at all in hotkey-less mode) on their
+    // own thread, which forwards them to the settings window
+    hotkeys::start(hwnd);
+    {
+        let cfg = config.lock().unwrap();
+        hotkeys::set_keys(cfg.key_bindings());
+        hotkeys::set_custom_actions(&cfg.custom_actions);
+        if cfg.disable_global_shortcuts {
+            mouse_hooks::set_blocked(true);
+        } else {
+            hotkeys::register_all();
+        }
+        mouse_hooks::apply(hwnd, &cfg.mouse_hotkeys);
+    }
 
     // Show overlay if enabled
     {
-        let cfg = config.lock().unwrap();
+        let mut cfg = config.lock().unwrap();
+        ui::apply_monitor_settings(&mut cfg);
+        // The OS theme may have flipped while we weren't running
+        let dark = system_theme::is_dark();
+        if !cfg.designer_mode {
+            if let Some(name) = profiles::apply_if_theme_changed(&mut cfg, dark) {
+                config::save_config(&cfg);
+                color_filter::follow_profile(&cfg, &name);
+            }
+        }
+        overlay::set_curve(cfg.opacity_curve);
+        overlay::set_gradient(&cfg.overlay_gradient);
+        motion::set(cfg.motion);
+        overlay::set_zoom_compat(cfg.zoom_compat);
+        overlay::set_pen_pause(&cfg.pen_pause);
+        overlay::set_night_boost(&cfg.night_boost);
+        overlay::set_reading_monitor(&cfg.reading_monitor);
+        overlay::set_edge_vignette(&cfg.edge_vignette);
+        brightness::apply(&cfg.brightness_rule);
+        contrast_guard::apply(&cfg.contrast_guard);
+        scheduler::start(hwnd, &cfg.schedule);
+        ddcci::start(hwnd);
+        mouse_hooks::set_pen_watch(cfg.pen_pause.enabled);
+        overlay::set_hdr_adjustments(cfg.hdr_backend, cfg.hdr_opacity_offset);
+        overlay::set_notify_window(hwnd);
+        if cfg.designer_mode {
+            overlay::set_suppressed(true);
+            tray::set_tooltip(hwnd, ui::tray_tooltip(true));
+        }
         if cfg.is_enabled {
             if cfg.multi_monitor {
-                overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
+                overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
             }
             overlay::show_overlay(cfg.opacity, false);
+        } else if warm_started {
+            overlay::hide_overlay();
         }
     }
+    ui::sync_from_config(hwnd);
 
     // Show and focus main window on startup
     ui::show_window(hwnd);
@@ -61,19 +301,51 @@ fn main() {
     // Show "just updated" toast if relaunched after self-update
     if just_updated {
         ui::show_toast(hwnd, &format!("Updated to v{} successfully!", updater::APP_VERSION));
+    } else if relaunched_elevated && elevation::is_elevated() {
+        ui::show_toast(hwnd, "Running as administrator");
+    } else if restored {
+        ui::show_toast(hwnd, "Settings restored from backup");
+    }
+
+    // Jump list task or command line that found no running instance
+    if let Some(command) = launch_command {
+        if let Some(message) = events::dispatch(hwnd, &config, command) {
+            ui::show_toast(hwnd, &message);
+        }
+    }
+
+    if let Some(options) = soak_run {
+        soak::start(hwnd, config.clone(), options);
     }
 
     // Auto-check for updates in background (silent, after 5 seconds)
-    {
+    if updater::ENABLED {
         let config_clone = config.clone();
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_secs(5));
+        shutdown::spawn("auto-update-check", move || {
+            if !shutdown::sleep(std::time::Duration::from_secs(5)) {
+                return;
+            }
             let auto_update = config_clone.lock().unwrap().auto_update;
             if auto_update {
                 let result = updater::check_for_update(updater::APP_VERSION);
+                if shutdown::is_stopping() {
+                    return;
+                }
                 if let updater::UpdateResult::UpdateAvailable { version, download_url, .. } = result {
+                    // Skipped or snoozed versions aren't offered again here
+                    if !updater::should_auto_prompt(&config_clone, &version) {
+                        eprintln!("[updater] v{} available, prompt snoozed", version);
+                        return;
+                    }
+                    if presentation::is_active() {
+                        eprintln!(
+                            "[updater] v{} available, not prompting while presenting",
+                            version
+                        );
+                        return;
+                    }
                     // Ask user if they want to auto-download
-                    if updater::prompt_update_dialog(&version) {
+                    if updater::prompt_update_dialog(&version, &config_clone) {
                         match updater::download_update(&download_url) {
                             Ok(path) => {
                                 let _ = updater::apply_update_and_relaunch(&path);
@@ -98,26 +370,55 @@ fn main() {
         }
     }
 
-    // Cleanup
-    hotkeys::unregister_all(hwnd);
-    tray::remove_tray_icon(hwnd);
+    // Shutdown (shared shutdown.rs): stop input and the background threads
+    // first, so nothing puts dimming back once it is removed
+    shutdown::begin();
+    poll_pace::activity();
+    hotkeys::end_level_chord();
+    hotkeys::unregister_all();
+    mouse_hooks::uninstall();
+    shutdown::join(shutdown::TIMEOUT);
+    // Then the screen, the tray icon and state, in that order: a hang past
+    // this point leaves neither a dimmed screen nor a ghost icon
     overlay::hide_overlay();
+    tray::remove_tray_icon(hwnd);
+    savemyeyes_shared::journal::clear();
+    if let Some(code) = soak::exit_code() {
+        std::process::exit(code);
+    }
 }
 
-/// Check if another instance is already running
-fn is_already_running() -> bool {
+/// Check if another instance is already running.
+/// With `wait_for_exit`, give the other instance a few seconds to quit first
+/// (used when it just relaunched us elevated).
+#[cfg(windows)]
+fn is_already_running(wait_for_exit: bool) -> bool {
+    use windows::Win32::Foundation::CloseHandle;
+
     let name: Vec<u16> = SINGLE_INSTANCE_MUTEX.encode_utf16().collect();
+    let attempts = if wait_for_exit { 100 } else { 1 };
 
     unsafe {
-        // Try to open existing mutex
-        let existing = OpenMutexW(
-            SYNCHRONIZATION_ACCESS_RIGHTS(0x001F0001), // MUTEX_ALL_ACCESS
-            false,
-            PCWSTR(name.as_ptr()),
-        );
-        if existing.is_ok() {
-            // Another instance exists
-            return true;
+        for attempt in 0..attempts {
+            // Try to open existing mutex
+            let existing = OpenMutexW(
+                SYNCHRONIZATION_ACCESS_RIGHTS(0x001F0001), // MUTEX_ALL_ACCESS
+                false,
+                PCWSTR(name.as_ptr()),
+            );
+            match existing {
+                Ok(handle) => {
+                    if attempt + 1 == attempts {
+                        // Another instance exists
+                        return true;
+                    }
+                    // Close our handle so the mutex can go away when the
+                    // other instance exits
+                    let _ = CloseHandle(handle);
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                }
+                Err(_) => break,
+            }
         }
 
         // Create the mutex (this instance owns it)
@@ -127,8 +428,10 @@ fn is_already_running() -> bool {
 }
 
 /// Toggle dimmer on/off (called from hotkey handler)
+#[cfg(windows)]
 pub fn do_toggle_dimmer(config: &Arc<Mutex<AppConfig>>) {
     let mut cfg = config.lock().unwrap();
+    movie_mode::forget(&cfg);
 
     if cfg.is_enabled {
         // Turning OFF
@@ -138,20 +441,21 @@ pub fn do_toggle_dimmer(config: &Arc<Mutex<AppConfig>>) {
         cfg.is_enabled = false;
         cfg.opacity = 0.0;
         config::save_config(&cfg);
-        overlay::hide_overlay();
+        overlay::fade_out(&cfg.fade);
     } else {
         // Turning ON
         cfg.is_enabled = true;
         cfg.opacity = cfg.last_opacity;
         config::save_config(&cfg);
         if cfg.multi_monitor {
-            overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
+            overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
         }
-        overlay::show_overlay(cfg.opacity, false);
+        overlay::fade_in(cfg.opacity, false, &cfg.fade);
     }
 }
 
 /// Get the cursor position
+#[cfg(windows)]
 fn get_cursor_pos() -> (i32, i32) {
     use windows::Win32::Foundation::POINT;
     use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
@@ -162,32 +466,103 @@ fn get_cursor_pos() -> (i32, i32) {
     (pt.x, pt.y)
 }
 
-/// Adjust opacity by delta (called from hotkey handler)
-pub fn do_adjust_opacity(config: &Arc<Mutex<AppConfig>>, delta: f32) {
-    let mut cfg = config.lock().unwrap();
+/// Adjust opacity by delta (called from hotkey handler). Returns the HUD
+/// message for the new level, e.g. "Dell U2720Q: 60%".
+#[cfg(windows)]
+pub fn do_adjust_opacity(config: &Arc<Mutex<AppConfig>>, delta: f32) -> String {
+    change_level(config, |current| current + delta)
+}
 
-    if cfg.multi_monitor {
-        // Multi-monitor mode: adjust only the monitor under the cursor
-        let (cx, cy) = get_cursor_pos();
-        let mon_idx = overlay::get_monitor_index_at_point(cx, cy);
+/// Set an exact level in percent (level chord). Returns the HUD message.
+#[cfg(windows)]
+pub fn do_set_opacity(config: &Arc<Mutex<AppConfig>>, percent: u32) -> String {
+    change_level(config, |_| percent as f32 / 100.0)
+}
 
-        let was_disabled = !cfg.is_enabled;
-        if was_disabled {
-            cfg.is_enabled = true;
-            cfg.opacity = cfg.last_opacity;
+/// Set an exact level (external command). Returns the HUD message.
+#[cfg(windows)]
+pub fn do_set_level(config: &Arc<Mutex<AppConfig>>, level: f32) -> String {
+    change_level(config, |_| level)
+}
+
+/// Set one monitor's level (external command, multi-monitor mode only).
+/// Returns the HUD message.
+#[cfg(windows)]
+pub fn do_set_monitor_level(config: &Arc<Mutex<AppConfig>>, monitor: u32, level: f32) -> String {
+    let mut cfg = config.lock().unwrap();
+    if !cfg.multi_monitor {
+        return "Per-monitor levels are off".to_string();
+    }
+    if monitor >= monitors::count() {
+        return format!("No monitor {}", monitor + 1);
+    }
+    leave_movie_mode(&cfg);
+    change_monitor_level(&mut cfg, monitor, |_| level)
+}
+
+/// Back from movie mode to the configured levels
+#[cfg(windows)]
+fn leave_movie_mode(cfg: &AppConfig) {
+    if movie_mode::is_active() {
+        movie_mode::forget(cfg);
+        if cfg.is_enabled {
+            overlay::show_overlay(cfg.opacity, cfg.allow_capture);
         }
+    }
+}
 
-        let current = cfg.per_monitor_opacity.get(&mon_idx).copied().unwrap_or(cfg.opacity);
-        let new_opacity = (current + delta).clamp(0.0, 0.9);
-        cfg.per_monitor_opacity.insert(mon_idx, new_opacity);
-        config::save_config(&cfg);
+/// Apply `level(current)` to monitor `mon_idx`, turning dimming on if needed.
+/// Returns the HUD message.
+#[cfg(windows)]
+fn change_monitor_level(cfg: &mut AppConfig, mon_idx: u32, level: impl Fn(f32) -> f32) -> String {
+    let id = monitors::ids().get(mon_idx as usize).cloned().unwrap_or_default();
 
-        if was_disabled {
-            overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
-            overlay::show_overlay(cfg.opacity, false);
-        } else if overlay::is_visible() {
-            overlay::set_monitor_opacity(mon_idx, new_opacity);
+    let was_disabled = !cfg.is_enabled;
+    if was_disabled {
+        cfg.is_enabled = true;
+        cfg.opacity = cfg.last_opacity;
+    }
+
+    let current = cfg.level_for(&id);
+    let new_opacity = dimming::quantize(level(current).clamp(0.0, 0.9));
+    // Grouped monitors follow (monitor_groups.rs)
+    let linked = cfg.set_level_for(&id, new_opacity);
+    config::save_config(cfg);
+
+    if was_disabled {
+        overlay::set_per_monitor_opacities(&monitors::levels_by_index(cfg));
+        overlay::show_overlay(cfg.opacity, false);
+    } else if overlay::is_visible() {
+        for (i, member) in monitors::ids().iter().enumerate() {
+            if linked.contains(member) {
+                overlay::set_monitor_opacity(i as u32, new_opacity);
+            }
         }
+    }
+
+    let name = match monitor_groups::group_of(&cfg.monitor_groups, &id) {
+        Some(group) => group.name.clone(),
+        None => monitors::friendly_name(mon_idx),
+    };
+    let message = format!("{}: {}", name, dimming::percent_label(new_opacity));
+    eprintln!("[hotkey] {}", message);
+    message
+}
+
+/// Apply `level(current)` to the monitor under the cursor (multi-monitor
+/// mode) or globally, turning dimming on if needed.
+#[cfg(windows)]
+fn change_level(config: &Arc<Mutex<AppConfig>>, level: impl Fn(f32) -> f32) -> String {
+    let mut cfg = config.lock().unwrap();
+
+    // Back from movie mode to the configured levels first
+    leave_movie_mode(&cfg);
+
+    if cfg.multi_monitor {
+        // Multi-monitor mode: adjust only the monitor under the cursor
+        let (cx, cy) = get_cursor_pos();
+        let mon_idx = monitors::index_at_point(cx, cy);
+        change_monitor_level(&mut cfg, mon_idx, level)
     } else {
         // Single-monitor mode: original behavior
         let was_disabled = !cfg.is_enabled;
@@ -196,7 +571,7 @@ pub fn do_adjust_opacity(config: &Arc<Mutex<AppConfig>>, delta: f32) {
             cfg.opacity = cfg.last_opacity;
         }
 
-        let new_opacity = (cfg.opacity + delta).clamp(0.0, 0.9);
+        let new_opacity = dimming::quantize(level(cfg.opacity).clamp(0.0, 0.9));
         cfg.opacity = new_opacity;
 
         if new_opacity > 0.0 {
@@ -209,5 +584,7 @@ pub fn do_adjust_opacity(config: &Arc<Mutex<AppConfig>>, delta: f32) {
         } else if overlay::is_visible() {
             overlay::set_opacity(cfg.opacity);
         }
+
+        format!("Dimming: {}", dimming::percent_label(new_opacity))
     }
 }
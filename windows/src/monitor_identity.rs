@@ -0,0 +1,219 @@
+// Who each monitor is, for monitors.rs.
+//
+// Everything about naming and identifying a monitor that doesn't need
+// Windows itself: the EDID target data DisplayConfig reports, matched to a
+// GDI device by its name, the stable id (the target's device path, or the
+// GDI name when there is none), the MonitorId config keys settings by
+// (unique among the connected monitors) and the friendly name. monitors.rs
+// asks Windows for the devices and targets and hands them over here.
+
+use std::collections::HashMap;
+
+use savemyeyes_shared::displays;
+use savemyeyes_shared::monitor_id::{self, MonitorId};
+
+use crate::sys::RECT;
+
+/// EDID data of an active monitor
+pub struct Target {
+    /// Friendly name, None if the monitor reports none
+    pub name: Option<String>,
+    pub device_path: String,
+}
+
+impl Target {
+    /// Target from the DisplayConfig name fields. Built-in panels usually
+    /// report no name and get "Built-in Display" instead.
+    pub fn new(friendly_name: &[u16], device_path: &[u16], internal: bool) -> Self {
+        let name = utf16_field(friendly_name);
+        let name = if !name.is_empty() {
+            Some(name)
+        } else if internal {
+            Some("Built-in Display".to_string())
+        } else {
+            None
+        };
+        Self {
+            name,
+            device_path: utf16_field(device_path),
+        }
+    }
+}
+
+/// Text of a zero-terminated UTF-16 field
+pub fn utf16_field(raw: &[u16]) -> String {
+    let len = raw.iter().position(|&c| c == 0).unwrap_or(raw.len());
+    String::from_utf16_lossy(&raw[..len]).trim().to_string()
+}
+
+/// How a monitor is known across reconnects and in config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identity {
+    /// EDID device path, or the GDI device name if the monitor has none
+    pub stable_id: String,
+    pub id: MonitorId,
+    /// e.g. "Dell U2720Q", "Built-in Display" or "Monitor 2"
+    pub name: String,
+}
+
+/// Identities of the monitors with GDI device names and bounds `devices`
+/// (EnumDisplayMonitors order), given the EDID `targets` keyed by GDI
+/// device name. The last `removed` monitors are left out (faults.rs), after
+/// the ids are made unique so the others keep theirs.
+pub fn identify(
+    devices: &[(String, RECT)],
+    targets: &HashMap<String, Target>,
+    removed: usize,
+) -> Vec<Identity> {
+    let edid: Vec<Option<String>> = devices
+        .iter()
+        .map(|(device, _)| targets.get(device).and_then(|t| t.name.clone()))
+        .collect();
+    let names = displays::friendly_names(&edid);
+    let stable_ids: Vec<String> = devices
+        .iter()
+        .map(|(device, _)| {
+            targets
+                .get(device)
+                .map(|t| t.device_path.clone())
+                .filter(|path| !path.is_empty())
+                .unwrap_or_else(|| device.clone())
+        })
+        .collect();
+    let mut ids: Vec<MonitorId> = devices
+        .iter()
+        .zip(&stable_ids)
+        .map(|((device, rect), stable_id)| {
+            if stable_id != device {
+                MonitorId::from_hardware(&[stable_id])
+            } else {
+                MonitorId::from_name_position(device, rect.left, rect.top)
+            }
+        })
+        .collect();
+    monitor_id::assign(&mut ids);
+    let mut identities: Vec<Identity> = stable_ids
+        .into_iter()
+        .zip(ids)
+        .zip(names)
+        .map(|((stable_id, id), name)| Identity {
+            stable_id,
+            id,
+            name,
+        })
+        .collect();
+    identities.truncate(identities.len().saturating_sub(removed));
+    identities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain([0, 0x41]).collect()
+    }
+
+    fn device(name: &str, left: i32) -> (String, RECT) {
+        let rect = RECT {
+            left,
+            top: 0,
+            right: left + 1920,
+            bottom: 1080,
+        };
+        (name.to_string(), rect)
+    }
+
+    fn target(name: &str, path: &str) -> Target {
+        Target::new(&utf16(name), &utf16(path), false)
+    }
+
+    #[test]
+    fn targets_read_their_name_fields() {
+        let dell = target(" DELL U2720Q ", r"\\?\DISPLAY#DELA0F4#5&1");
+        assert_eq!(dell.name.as_deref(), Some("DELL U2720Q"));
+        assert_eq!(dell.device_path, r"\\?\DISPLAY#DELA0F4#5&1");
+        assert_eq!(target("", "x").name, None);
+        let panel = Target::new(&utf16(""), &utf16("y"), true);
+        assert_eq!(panel.name.as_deref(), Some("Built-in Display"));
+        assert_eq!(utf16_field(&[0x41, 0x42]), "AB");
+    }
+
+    #[test]
+    fn targets_match_by_gdi_device() {
+        let devices = [device(r"\\.\DISPLAY1", 0), device(r"\\.\DISPLAY2", 1920)];
+        let targets = HashMap::from([
+            (r"\\.\DISPLAY2".to_string(), target("LG HDR 4K", "path-lg")),
+            (
+                r"\\.\DISPLAY1".to_string(),
+                target("DELL U2720Q", "path-dell"),
+            ),
+            (r"\\.\DISPLAY9".to_string(), target("Gone", "path-gone")),
+        ]);
+        let found = identify(&devices, &targets, 0);
+        assert_eq!(found[0].name, "DELL U2720Q");
+        assert_eq!(found[0].stable_id, "path-dell");
+        assert_eq!(found[0].id, MonitorId::from_hardware(&["path-dell"]));
+        assert_eq!(found[1].name, "LG HDR 4K");
+        assert_eq!(found[1].stable_id, "path-lg");
+    }
+
+    #[test]
+    fn stable_id_falls_back_to_the_device_name() {
+        let devices = [device(r"\\.\DISPLAY1", 0), device(r"\\.\DISPLAY2", 1920)];
+        // DISPLAY1 has a target without a device path, DISPLAY2 none at all
+        let targets = HashMap::from([(r"\\.\DISPLAY1".to_string(), target("DELL U2720Q", ""))]);
+        let found = identify(&devices, &targets, 0);
+        assert_eq!(found[0].stable_id, r"\\.\DISPLAY1");
+        assert_eq!(found[0].name, "DELL U2720Q");
+        assert_eq!(
+            found[0].id,
+            MonitorId::from_name_position(r"\\.\DISPLAY1", 0, 0)
+        );
+        assert_eq!(found[1].stable_id, r"\\.\DISPLAY2");
+        assert_eq!(found[1].name, "Monitor 2");
+        assert_eq!(
+            found[1].id,
+            MonitorId::from_name_position(r"\\.\DISPLAY2", 1920, 0)
+        );
+    }
+
+    #[test]
+    fn identical_monitors_get_distinct_ids() {
+        // Three units of one model that report the same device path
+        let devices = [
+            device(r"\\.\DISPLAY1", 0),
+            device(r"\\.\DISPLAY2", 1920),
+            device(r"\\.\DISPLAY3", 3840),
+        ];
+        let targets = HashMap::from([
+            (r"\\.\DISPLAY1".to_string(), target("DELL U2720Q", "same")),
+            (r"\\.\DISPLAY2".to_string(), target("DELL U2720Q", "same")),
+            (r"\\.\DISPLAY3".to_string(), target("DELL U2720Q", "same")),
+        ]);
+        let found = identify(&devices, &targets, 0);
+        let first = MonitorId::from_hardware(&["same"]);
+        assert_eq!(found[0].id, first);
+        assert_eq!(found[1].id.as_str(), format!("{}-2", first));
+        assert_eq!(found[2].id.as_str(), format!("{}-3", first));
+        assert_eq!(found[0].name, "DELL U2720Q (1)");
+        assert_eq!(found[2].name, "DELL U2720Q (3)");
+    }
+
+    #[test]
+    fn removed_monitors_are_cut_after_the_ids() {
+        let devices = [
+            device(r"\\.\DISPLAY1", 0),
+            device(r"\\.\DISPLAY2", 1920),
+            device(r"\\.\DISPLAY3", 3840),
+        ];
+        let targets = HashMap::from([
+            (r"\\.\DISPLAY1".to_string(), target("DELL U2720Q", "same")),
+            (r"\\.\DISPLAY2".to_string(), target("DELL U2720Q", "same")),
+        ]);
+        let all = identify(&devices, &targets, 0);
+        let fewer = identify(&devices, &targets, 1);
+        assert_eq!(fewer[..], all[..2]);
+        assert_eq!(identify(&devices, &targets, 5), []);
+    }
+}
@@ -0,0 +1,272 @@
+// Monitor enumeration: one list of the connected monitors for the overlays,
+// the settings window, the HUD and the diagnostics.
+//
+// `list` returns a MonitorInfo per monitor in EnumDisplayMonitors order (the
+// order monitor indices in config refer to): GDI device name, bounds, work
+// area, DPI, primary and HDR state, the HMONITOR, a friendly name and a
+// stable id. Nothing is kept between calls, so it is safe to call from any
+// thread at any time and always reflects the current layout; callers that
+// need several facts take one list instead of asking repeatedly.
+//
+// Friendly names ("Dell U2720Q") come from the EDID via the DisplayConfig
+// target name, kept apart by the shared `displays::friendly_names`; the
+// stable id is the target's monitor device path, which stays the same
+// across reconnects and reboots while the GDI name and index don't. The
+// MonitorId per-monitor settings are keyed by is hashed from it (shared
+// monitor_id.rs). monitor_identity.rs works these out from what Windows
+// reports. The layout is recorded in the shared displays.json
+// sidecar whenever it changes (startup and WM_DISPLAYCHANGE). The self-test
+// can hide monitors from `list` and `count` to simulate unplugging
+// (faults.rs).
+
+use savemyeyes_shared::dimming;
+use savemyeyes_shared::displays::{self, DisplayInfo};
+use savemyeyes_shared::monitor_id::{self, MonitorId};
+use std::collections::HashMap;
+use windows::Win32::Devices::Display::{
+    DisplayConfigGetDeviceInfo, DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+    DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL,
+    DISPLAYCONFIG_TARGET_DEVICE_NAME,
+};
+use windows::Win32::Foundation::{LPARAM, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, MonitorFromPoint, HDC, HMONITOR, MONITORINFO,
+    MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+
+use crate::config::AppConfig;
+use crate::faults;
+use crate::hdr;
+use crate::monitor_identity::{self, utf16_field, Target};
+
+const MONITORINFOF_PRIMARY: u32 = 0x0000_0001;
+
+/// One connected monitor
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    /// Same monitor, same id across reconnects and reboots (EDID device
+    /// path; the GDI device name if the monitor has none)
+    pub stable_id: String,
+    /// Key of the monitor's settings in config, from `stable_id`
+    pub id: MonitorId,
+    /// e.g. "Dell U2720Q", "Built-in Display" or "Monitor 2"
+    pub name: String,
+    /// GDI device name (\\.\DISPLAY1, ...)
+    pub device: String,
+    pub rect: RECT,
+    /// `rect` without the taskbar
+    pub work: RECT,
+    /// Effective DPI (96 = 100%)
+    pub dpi: u32,
+    pub primary: bool,
+    pub hdr: bool,
+    pub hmonitor: HMONITOR,
+}
+
+impl MonitorInfo {
+    /// Taller than wide, i.e. turned to portrait
+    pub fn is_portrait(&self) -> bool {
+        self.rect.bottom - self.rect.top > self.rect.right - self.rect.left
+    }
+
+    /// Layout entry for displays.json and the diagnostics report
+    pub fn display_info(&self) -> DisplayInfo {
+        DisplayInfo {
+            id: self.id.clone(),
+            name: self.device.clone(),
+            x: self.rect.left,
+            y: self.rect.top,
+            width: self.rect.right - self.rect.left,
+            height: self.rect.bottom - self.rect.top,
+            scale: self.dpi as f32 / 96.0,
+            primary: self.primary,
+            hdr: self.hdr,
+        }
+    }
+}
+
+/// Handles of the connected monitors in EnumDisplayMonitors order
+fn handles() -> Vec<HMONITOR> {
+    unsafe extern "system" fn enum_proc(
+        hmonitor: HMONITOR,
+        _: HDC,
+        _: *mut RECT,
+        lparam: LPARAM,
+    ) -> windows::core::BOOL {
+        let handles = &mut *(lparam.0 as *mut Vec<HMONITOR>);
+        handles.push(hmonitor);
+        windows::core::BOOL::from(true)
+    }
+
+    let mut handles: Vec<HMONITOR> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            None,
+            None,
+            Some(enum_proc),
+            LPARAM(&mut handles as *mut Vec<HMONITOR> as isize),
+        );
+    }
+    handles
+}
+
+/// EDID data of each active monitor, keyed by GDI device name
+fn targets() -> HashMap<String, Target> {
+    let mut targets = HashMap::new();
+    for path in hdr::active_paths() {
+        let Some(device) = hdr::source_name(&path) else {
+            continue;
+        };
+        let mut target = DISPLAYCONFIG_TARGET_DEVICE_NAME {
+            header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+                r#type: DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+                size: std::mem::size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as u32,
+                adapterId: path.targetInfo.adapterId,
+                id: path.targetInfo.id,
+            },
+            ..Default::default()
+        };
+        if unsafe { DisplayConfigGetDeviceInfo(&mut target.header) } != 0 {
+            continue;
+        }
+        let internal = target.outputTechnology == DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL;
+        targets.insert(
+            device,
+            Target::new(
+                &target.monitorFriendlyDeviceName,
+                &target.monitorDevicePath,
+                internal,
+            ),
+        );
+    }
+    targets
+}
+
+/// The connected monitors in EnumDisplayMonitors order. A monitor that
+/// vanishes while being described is left out.
+pub fn list() -> Vec<MonitorInfo> {
+    let targets = targets();
+    let hdr = hdr::hdr_devices();
+    let mut list: Vec<MonitorInfo> = handles()
+        .into_iter()
+        .filter_map(|hmonitor| unsafe {
+            let mut mi = MONITORINFOEXW::default();
+            mi.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            let mi_ptr = &mut mi as *mut MONITORINFOEXW as *mut MONITORINFO;
+            if !GetMonitorInfoW(hmonitor, mi_ptr).as_bool() {
+                return None;
+            }
+            let device = utf16_field(&mi.szDevice);
+            let mut dpi_x = 96u32;
+            let mut dpi_y = 96u32;
+            let _ = GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+            Some(MonitorInfo {
+                stable_id: String::new(),
+                id: MonitorId::default(),
+                name: String::new(),
+                rect: mi.monitorInfo.rcMonitor,
+                work: mi.monitorInfo.rcWork,
+                dpi: dpi_x,
+                primary: mi.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+                hdr: hdr.contains(&device),
+                hmonitor,
+                device,
+            })
+        })
+        .collect();
+    let devices: Vec<(String, RECT)> = list.iter().map(|m| (m.device.clone(), m.rect)).collect();
+    let identities = monitor_identity::identify(&devices, &targets, faults::removed_monitors());
+    list.truncate(identities.len());
+    for (monitor, identity) in list.iter_mut().zip(identities) {
+        monitor.stable_id = identity.stable_id;
+        monitor.id = identity.id;
+        monitor.name = identity.name;
+    }
+    list
+}
+
+/// Number of connected monitors
+pub fn count() -> u32 {
+    handles().len().saturating_sub(faults::removed_monitors()) as u32
+}
+
+/// Index of the monitor containing (or nearest to) a screen point, 0 if
+/// there is none.
+pub fn index_at_point(x: i32, y: i32) -> u32 {
+    let target = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) };
+    handles().iter().position(|h| *h == target).unwrap_or(0) as u32
+}
+
+/// The monitor containing (or nearest to) a screen point
+pub fn at_point(x: i32, y: i32) -> Option<MonitorInfo> {
+    let target = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) };
+    list().into_iter().find(|m| m.hmonitor == target)
+}
+
+/// Ids of the connected monitors, in monitor index order
+pub fn ids() -> Vec<MonitorId> {
+    list().into_iter().map(|m| m.id).collect()
+}
+
+/// Per-monitor levels of `config` keyed by monitor index, for the overlays
+pub fn levels_by_index(config: &AppConfig) -> HashMap<u32, f32> {
+    monitor_id::by_index(&config.monitor_opacity, &ids())
+}
+
+/// Layout entries of the connected monitors (displays.json, diagnostics).
+pub fn enumerate() -> Vec<DisplayInfo> {
+    list().iter().map(MonitorInfo::display_info).collect()
+}
+
+/// Record the current layout in the sidecar file if it changed.
+pub fn record_layout() {
+    displays::record_layout(&enumerate());
+}
+
+/// Friendly name of the monitor at `index`, "Monitor N" if unknown.
+pub fn friendly_name(index: u32) -> String {
+    list()
+        .into_iter()
+        .nth(index as usize)
+        .map(|m| m.name)
+        .unwrap_or_else(|| format!("Monitor {}", index + 1))
+}
+
+/// Plain-text display report for the diagnostics "Copy Report" button.
+pub fn display_report(config: &AppConfig) -> String {
+    let list = list();
+    let layout: Vec<DisplayInfo> = list.iter().map(MonitorInfo::display_info).collect();
+    let mut report = displays::build_report("Windows", &layout, config);
+    report.push_str("\nMonitor IDs:\n");
+    for (i, m) in list.iter().enumerate() {
+        let line = format!(
+            "  {}. {} \u{2014} {} ({})\n",
+            i + 1,
+            m.name,
+            m.stable_id,
+            m.id
+        );
+        report.push_str(&line);
+        if let Some(level) = crate::gamma::refused(&m.device) {
+            report.push_str(&format!(
+                "     gamma ramp clamped at {}, overlay used from there\n",
+                dimming::percent_label(level)
+            ));
+        }
+    }
+    let mut hooks = Vec::new();
+    if crate::hotkeys::is_registered() {
+        hooks.push("hotkeys (RegisterHotKey)");
+    }
+    if crate::mouse_hooks::is_installed() {
+        hooks.push("mouse hook (WH_MOUSE_LL)");
+    }
+    report.push('\n');
+    report.push_str(&displays::input_hooks_line(&hooks, config.disable_global_shortcuts));
+    report.push_str(&crate::overlay::capabilities().report_line());
+    report.push_str(&crate::overlay_compat::report_line());
+    report.push_str(&crate::poll_pace::report_line());
+    report.push_str(&crate::resources::report_line());
+    report
+}
@@ -0,0 +1,183 @@
+// Mouse-button hotkeys via a low-level mouse hook (WH_MOUSE_LL).
+//
+// Only installed while mouse hotkeys are enabled: a low-level hook sees every
+// mouse event on the desktop, so it costs a little even on plain moves. The
+// hook runs on the UI thread's message loop. A matching XButton press is
+// swallowed (so browsers don't also navigate back/forward) together with its
+// release, and posted to the settings window as WM_HOTKEY with the keyboard
+// hotkey's ID, so both inputs share one dispatcher.
+//
+// The same hook also timestamps pen input for the pen pause rule: Windows
+// tags mouse input synthesized from pen and touch with MI_WP_SIGNATURE in
+// dwExtraInfo, which is the only system-wide way to tell a pen from a mouse.
+// It is installed while either feature needs it, and never while global
+// shortcuts are disabled (see `set_blocked`).
+
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, PostMessageW, SetWindowsHookExW, UnhookWindowsHookEx, HC_ACTION, HHOOK,
+    MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_HOTKEY, WM_XBUTTONDOWN, WM_XBUTTONUP, XBUTTON1, XBUTTON2,
+};
+
+use savemyeyes_shared::hotkeys::{Modifiers, MouseButton, MouseHotkeys};
+
+use crate::hotkeys;
+use crate::poll_pace;
+
+static HOOK: AtomicIsize = AtomicIsize::new(0);
+static TARGET_HWND: AtomicIsize = AtomicIsize::new(0);
+static SETTINGS: Mutex<Option<MouseHotkeys>> = Mutex::new(None);
+/// Buttons whose press we swallowed, so the release is swallowed too
+/// (bit 0 = Mouse4, bit 1 = Mouse5)
+static SWALLOWED: AtomicU8 = AtomicU8::new(0);
+/// "Disable Global Shortcuts": never install the hook
+static BLOCKED: AtomicBool = AtomicBool::new(false);
+/// Pen pause wants pen timestamps
+static PEN_WATCH: AtomicBool = AtomicBool::new(false);
+static LAST_PEN_INPUT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// dwExtraInfo tag on mouse input synthesized from pen or touch
+const MI_WP_SIGNATURE: usize = 0xFF515700;
+const SIGNATURE_MASK: usize = 0xFFFFFF00;
+/// Set in the tagged dwExtraInfo for touch (clear for pen)
+const TOUCH_FLAG: usize = 0x80;
+
+fn key_down(vk: u16) -> bool {
+    unsafe { GetAsyncKeyState(vk as i32) as u16 & 0x8000 != 0 }
+}
+
+fn current_modifiers() -> Modifiers {
+    Modifiers {
+        ctrl: key_down(VK_CONTROL.0),
+        alt: key_down(VK_MENU.0),
+        shift: key_down(VK_SHIFT.0),
+        meta: key_down(VK_LWIN.0) || key_down(VK_RWIN.0),
+    }
+}
+
+fn is_pen(extra_info: usize) -> bool {
+    extra_info & SIGNATURE_MASK == MI_WP_SIGNATURE && extra_info & TOUCH_FLAG == 0
+}
+
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let msg = wparam.0 as u32;
+    if code == HC_ACTION as i32
+        && PEN_WATCH.load(Ordering::Relaxed)
+        && is_pen((*(lparam.0 as *const MSLLHOOKSTRUCT)).dwExtraInfo)
+    {
+        let now = Instant::now();
+        let previous = LAST_PEN_INPUT.lock().unwrap().replace(now);
+        // A stroke starting: wake the overlay poll thread so the cap is quick
+        if previous.is_none_or(|at| now - at > Duration::from_secs(1)) {
+            poll_pace::activity();
+        }
+    }
+    if code == HC_ACTION as i32 && (msg == WM_XBUTTONDOWN || msg == WM_XBUTTONUP) {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        let button = match (info.mouseData >> 16) as u16 {
+            XBUTTON1 => Some(MouseButton::Mouse4),
+            XBUTTON2 => Some(MouseButton::Mouse5),
+            _ => None,
+        };
+        if let Some(button) = button {
+            let bit = if button == MouseButton::Mouse4 { 1 } else { 2 };
+            if msg == WM_XBUTTONUP {
+                if SWALLOWED.fetch_and(!bit, Ordering::SeqCst) & bit != 0 {
+                    return LRESULT(1);
+                }
+            } else {
+                let action = SETTINGS
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .and_then(|s| s.action_for(button, current_modifiers()));
+                if let Some(action) = action {
+                    let hwnd = TARGET_HWND.load(Ordering::SeqCst);
+                    let _ = PostMessageW(
+                        Some(HWND(hwnd as *mut _)),
+                        WM_HOTKEY,
+                        WPARAM(hotkeys::id_for(action) as usize),
+                        LPARAM(0),
+                    );
+                    SWALLOWED.fetch_or(bit, Ordering::SeqCst);
+                    return LRESULT(1);
+                }
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Install or remove the hook to match `settings`. Call from the UI thread.
+pub fn apply(hwnd: HWND, settings: &MouseHotkeys) {
+    *SETTINGS.lock().unwrap() = Some(settings.clone());
+    TARGET_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+    update_hook();
+}
+
+/// Start or stop timestamping pen input. Call from the UI thread.
+pub fn set_pen_watch(enabled: bool) {
+    PEN_WATCH.store(enabled, Ordering::SeqCst);
+    if !enabled {
+        *LAST_PEN_INPUT.lock().unwrap() = None;
+    }
+    update_hook();
+}
+
+/// Forbid (and remove) or allow the hook. Call from the UI thread.
+pub fn set_blocked(blocked: bool) {
+    BLOCKED.store(blocked, Ordering::SeqCst);
+    update_hook();
+}
+
+/// True while the low-level mouse hook is installed
+pub fn is_installed() -> bool {
+    HOOK.load(Ordering::SeqCst) != 0
+}
+
+/// When the pen was last used (None if never, or pen watch is off)
+pub fn last_pen_input() -> Option<Instant> {
+    *LAST_PEN_INPUT.lock().unwrap()
+}
+
+/// Install the hook if mouse hotkeys or pen watch need it, else remove it
+fn update_hook() {
+    let hotkeys_active = SETTINGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|s| s.enabled && !s.bindings().is_empty());
+    let active =
+        !BLOCKED.load(Ordering::SeqCst) && (hotkeys_active || PEN_WATCH.load(Ordering::SeqCst));
+
+    if active && HOOK.load(Ordering::SeqCst) == 0 {
+        unsafe {
+            let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+            match SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), Some(hinstance.into()), 0) {
+                Ok(hook) => HOOK.store(hook.0 as isize, Ordering::SeqCst),
+                Err(e) => eprintln!("[mouse] SetWindowsHookExW failed: {}", e),
+            }
+        }
+    } else if !active {
+        uninstall();
+    }
+}
+
+/// Remove the hook if installed
+pub fn uninstall() {
+    let hook = HOOK.swap(0, Ordering::SeqCst);
+    if hook != 0 {
+        unsafe {
+            let _ = UnhookWindowsHookEx(HHOOK(hook as *mut _));
+        }
+    }
+    SWALLOWED.store(0, Ordering::SeqCst);
+}
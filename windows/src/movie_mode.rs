@@ -0,0 +1,152 @@
+// Movie mode: undim the monitor showing the focused video, dim the rest more.
+//
+// Toggled from the tray menu or Ctrl+Alt+M. The monitor is the one under the
+// center of the focused window. When the tray menu has the focus (our own
+// window) or a shell surface does, the topmost regular window of another
+// process is used instead. Levels come from shared::movie_mode and are only
+// pushed to the overlay, never saved; turning it off re-applies the config.
+// Anything else that changes the dimming (toggle, hotkeys) ends it too.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetTopWindow, GetWindow, GetWindowLongW, GetWindowRect,
+    GetWindowTextLengthW, GetWindowThreadProcessId, IsIconic, IsWindowVisible, GWL_EXSTYLE,
+    GW_HWNDNEXT, WS_EX_TOOLWINDOW,
+};
+
+use crate::config::AppConfig;
+use crate::monitors;
+use crate::overlay;
+use crate::shell_windows;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// True while movie mode levels are applied
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Movie mode is over because something else is setting the levels: put
+/// the configured per-monitor levels back for the caller to apply.
+pub fn forget(cfg: &AppConfig) {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        configured_levels(cfg);
+    }
+}
+
+/// Turn movie mode on or off. Returns the message for the HUD/toast.
+pub fn toggle(config: &Arc<Mutex<AppConfig>>) -> String {
+    let cfg = config.lock().unwrap();
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        restore(&cfg);
+        eprintln!("[movie] off");
+        return "Movie mode off".to_string();
+    }
+    if cfg.designer_mode {
+        return "Designer mode is on \u{2014} dimming paused".to_string();
+    }
+
+    let focused = focused_monitor();
+    let levels: HashMap<u32, f32> = monitors::ids()
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            let idx = idx as u32;
+            let configured = if !cfg.is_enabled {
+                0.0
+            } else if cfg.multi_monitor {
+                cfg.level_for(id)
+            } else {
+                cfg.opacity
+            };
+            (idx, cfg.movie_mode.level(configured, idx == focused))
+        })
+        .collect();
+    overlay::set_per_monitor_opacities(&levels);
+    overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+    ACTIVE.store(true, Ordering::SeqCst);
+
+    let message = format!("Movie mode: {}", monitors::friendly_name(focused));
+    eprintln!("[movie] {}", message);
+    message
+}
+
+/// Put the configured levels back.
+fn restore(cfg: &AppConfig) {
+    configured_levels(cfg);
+    if cfg.is_enabled {
+        overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+    } else {
+        overlay::hide_overlay();
+    }
+}
+
+fn configured_levels(cfg: &AppConfig) {
+    if cfg.multi_monitor {
+        overlay::set_per_monitor_opacities(&monitors::levels_by_index(cfg));
+    } else {
+        overlay::set_per_monitor_opacities(&HashMap::new());
+    }
+}
+
+/// Monitor index under the center of the window the user is watching.
+pub fn focused_monitor() -> u32 {
+    let Some(hwnd) = watched_window() else {
+        return 0;
+    };
+    let mut rect = RECT::default();
+    if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+        return 0;
+    }
+    monitors::index_at_point(
+        rect.left + (rect.right - rect.left) / 2,
+        rect.top + (rect.bottom - rect.top) / 2,
+    )
+}
+
+/// The foreground window, or the topmost candidate when that's our own
+/// window (tray menu) or not a regular app window.
+fn watched_window() -> Option<HWND> {
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if is_candidate(foreground) {
+            return Some(foreground);
+        }
+        let mut hwnd = GetTopWindow(None).ok()?;
+        loop {
+            if is_candidate(hwnd) {
+                return Some(hwnd);
+            }
+            hwnd = GetWindow(hwnd, GW_HWNDNEXT).ok()?;
+        }
+    }
+}
+
+/// Visible, titled, non-tool window of another process
+fn is_candidate(hwnd: HWND) -> bool {
+    if hwnd.is_invalid() {
+        return false;
+    }
+    unsafe {
+        if !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return false;
+        }
+        if GetWindowTextLengthW(hwnd) == 0 {
+            return false;
+        }
+        if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+            return false;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == GetCurrentProcessId() {
+            return false;
+        }
+    }
+    !shell_windows::is_shell_experience(hwnd)
+}
@@ -0,0 +1,97 @@
+// Browser extension bridge on Windows (see shared native_messaging.rs).
+//
+// When the browser starts us as its native messaging host we serve its port
+// and forward commands to the running instance (events::forward) instead of
+// starting the app. `--install-native-host` writes the manifest next to
+// config.json and registers it under the browser's NativeMessagingHosts key
+// in HKCU, which is where Chrome and Firefox look it up.
+
+use std::path::Path;
+
+use windows::core::PCWSTR;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_WRITE,
+    REG_OPTION_NON_VOLATILE, REG_SZ,
+};
+
+use savemyeyes_shared::config::config_path;
+use savemyeyes_shared::native_messaging::{self, Browser, Launch, HOST_NAME};
+
+use crate::events;
+
+/// Serve the browser or install the manifest if the command line asks for
+/// it; returns the exit code. None for a normal launch.
+pub fn run_from_args() -> Option<i32> {
+    Some(match native_messaging::from_args()? {
+        Launch::Host => native_messaging::serve(events::forward),
+        Launch::Install {
+            browser,
+            extension_id,
+        } => install(browser, &extension_id),
+        Launch::Usage => {
+            eprintln!("{}", native_messaging::usage());
+            2
+        }
+    })
+}
+
+fn install(browser: Browser, extension_id: &str) -> i32 {
+    let (folder, key) = match browser {
+        Browser::Chrome => ("chrome", "Software\\Google\\Chrome\\NativeMessagingHosts"),
+        Browser::Firefox => ("firefox", "Software\\Mozilla\\NativeMessagingHosts"),
+    };
+    let dir = config_path()
+        .with_file_name("native-messaging")
+        .join(folder);
+    let path = match native_messaging::write_manifest(browser, &dir, extension_id) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("[native-host] could not write manifest: {}", e);
+            return 1;
+        }
+    };
+    let key = format!("{}\\{}", key, HOST_NAME);
+    if !register(&key, &path) {
+        eprintln!("[native-host] could not register HKCU\\{}", key);
+        return 1;
+    }
+    println!(
+        "Wrote {} and registered it under HKCU\\{}",
+        path.display(),
+        key
+    );
+    0
+}
+
+/// Point the default value of HKCU\`key` at the manifest
+fn register(key: &str, manifest: &Path) -> bool {
+    let key_path: Vec<u16> = key.encode_utf16().chain(std::iter::once(0)).collect();
+    let data: Vec<u16> = manifest
+        .display()
+        .to_string()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let data_bytes =
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * 2) };
+    let mut hkey = HKEY::default();
+    unsafe {
+        let created = RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key_path.as_ptr()),
+            Some(0),
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_WRITE,
+            None,
+            &mut hkey,
+            None,
+        );
+        if created.is_err() {
+            return false;
+        }
+        let result = RegSetValueExW(hkey, PCWSTR::null(), Some(0), REG_SZ, Some(data_bytes));
+        let _ = RegCloseKey(hkey);
+        result.is_ok()
+    }
+}
@@ -11,25 +11,122 @@
 //     we record the timestamp of the last event and wait 500ms after the LAST
 //     event before re-asserting. This lets the window manager settle first.
 //   • SWP_NOSENDCHANGING prevents notifying other apps of our re-topping.
+//   • Shell experience windows (Start, search, Task View, notification center)
+//     sit in a z-band above ours. While one has the foreground we don't fight
+//     it; we remember it and re-assert exactly once after it closes.
+//   • The Win+Shift+S screen clip layer dims the screen itself, and the two
+//     together are unusably dark. While it has the foreground every overlay
+//     drops to 0% (gamma restored too); levels come back when it closes.
+//   • Known overlay apps (overlay_compat.rs) get their own treatment:
+//     annotators keep their windows above ours, and other dimmers or
+//     magnifiers taking the foreground don't trigger a re-assertion.
+//
+// Monitors configured for the gamma backend keep their overlay window at
+// alpha 0 and are dimmed through gamma.rs instead. If the driver refuses the
+// ramp, that monitor falls back to overlay alpha, from then on for that
+// level and deeper, and the settings window is told (WM_GAMMA_REFUSED) so it
+// can say why. With zoom compat on, every
+// monitor uses gamma while Windows Magnifier is active (see magnifier.rs),
+// and the same happens while the session is locked (see lock_screen.rs).
+// Monitors running in HDR (see hdr.rs) use the configured HDR backend and
+// opacity offset instead; the poll thread re-applies levels and notifies the
+// settings window when a monitor enters or leaves HDR.
+// Recording mode (tray, not saved) is for people recording tutorials about
+// the app itself: every overlay shows up in captures and every monitor uses
+// the overlay backend, since gamma dimming never reaches a capture.
+// While the pen pause rule is on, the poll thread also caps every level while
+// a pen is drawing (see mouse_hooks.rs for pen detection), and adds the
+// night boost to every dimmed level while its hours are on (checked every
+// second, the settings window is told when it starts or ends).
+//
+// Turning dimming on or off fades it in or out (shared fade.rs): a thread
+// timer on the UI thread steps a factor that `apply_level` multiplies into
+// every monitor's level, overlay alpha and gamma alike. Rebuilding the
+// overlays halfway (a monitor added, a window lost) keeps the fade going.
+//
+// Reading monitors (reading_monitor.rs) get their offset added after the
+// night boost; which monitors they are is worked out again from the
+// orientation on every show and refit, so rotating one takes effect at once.
+//
+// With the overlay gradient on, every overlay is drawn darker at the top and
+// lighter at the bottom with per-pixel alpha (overlay_gradient.rs); gamma
+// monitors stay uniform. The edge vignette is drawn the same way, on top of
+// the gradient, with its shape per monitor worked out with the reading
+// monitors.
+//
+// The boost hotkey's overrides (focus_boost.rs) come off every level last,
+// before the caps. The poll thread and the foreground hook drop them once
+// their window loses the focus or changes its title.
+//
+// The poll thread slows down to 1s polls and a 30s watchdog while nothing is
+// happening or the machine is on battery (see poll_pace.rs).
+//
+// Rotating a monitor or changing its resolution keeps the overlays but moves
+// and resizes them in place (`refit`, on WM_DISPLAYCHANGE and after a desktop
+// switch), so there is no destroy/recreate blink. Only a monitor being added
+// or removed rebuilds them.
+//
+// Whether capture exclusion works is learnt from SetWindowDisplayAffinity
+// itself: builds before Windows 10 2004 reject WDA_EXCLUDEFROMCAPTURE, and
+// `capabilities()` reports it unsupported after such a failure so the
+// settings window can disable the capture pills.
+//
+// The self-test breaks these recovery paths on purpose through faults.rs
+// (window destroyed from outside, display affinity failing, monitor removed)
+// and checks the overlays converge back to one per monitor.
 
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 use windows::core::PCWSTR;
-use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
-use windows::Win32::Graphics::Gdi::{
-    CreateSolidBrush, EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO,
-};
+use windows::Win32::Foundation::{COLORREF, E_ACCESSDENIED, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::Graphics::Gdi::{GetStockObject, BLACK_BRUSH, HBRUSH};
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExW, DefWindowProcW, DestroyWindow, IsWindow, RegisterClassW,
-    SetLayeredWindowAttributes, SetWindowDisplayAffinity, SetWindowPos, ShowWindow, CS_HREDRAW,
-    CS_VREDRAW, HWND_TOPMOST, LWA_ALPHA, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSENDCHANGING,
-    SWP_NOSIZE, SW_HIDE, WDA_EXCLUDEFROMCAPTURE, WDA_NONE, WNDCLASSW, WS_DISABLED, WS_EX_LAYERED,
-    WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP, WS_VISIBLE,
+    CreateWindowExW, DefWindowProcW, DestroyWindow, GetForegroundWindow, GetLayeredWindowAttributes,
+    GetWindowDisplayAffinity, IsWindow, IsWindowVisible, KillTimer, PostMessageW, RegisterClassW, SetLayeredWindowAttributes, SetTimer, SetWindowDisplayAffinity,
+    SetWindowPos, ShowWindow, CS_HREDRAW, CS_VREDRAW, HWND_TOPMOST, LWA_ALPHA, SWP_NOACTIVATE,
+    SWP_NOMOVE, SWP_NOSENDCHANGING, SWP_NOSIZE, SW_HIDE, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+    WNDCLASSW, WS_DISABLED, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST,
+    WS_EX_TRANSPARENT, WS_POPUP, WS_VISIBLE, WM_APP,
 };
 
+use savemyeyes_shared::backend::OverlayBackend;
+use savemyeyes_shared::capabilities::Capabilities;
+use savemyeyes_shared::dimming::{self, OpacityCurve};
+use savemyeyes_shared::displays::DisplayInfo;
+use savemyeyes_shared::edge_vignette::{EdgeVignette, VignetteShape};
+use savemyeyes_shared::explain::MonitorLevel;
+use savemyeyes_shared::fade::{self, Fade, FadeSettings};
+use savemyeyes_shared::focus_boost::{FocusedWindow, OverrideStack};
+use savemyeyes_shared::gradient::OverlayGradient;
+use savemyeyes_shared::journal::{self, AppliedState};
+use savemyeyes_shared::monitor_id::MonitorId;
+use savemyeyes_shared::motion;
+use savemyeyes_shared::night_boost::{self, NightBoost};
+use savemyeyes_shared::pen_pause::PenPause;
+use savemyeyes_shared::reading_monitor::ReadingMonitor;
+use savemyeyes_shared::shutdown;
+use savemyeyes_shared::time_change;
+use savemyeyes_shared::trace::Mark;
+
+use crate::config::DimBackend;
+use crate::faults;
+use crate::focus_boost;
+use crate::gamma;
+use crate::hdr;
+use crate::hud;
+use crate::magnifier;
+use crate::monitors::{self, MonitorInfo};
+use crate::mouse_hooks;
+use crate::overlay_compat;
+use crate::overlay_gradient;
+use crate::poll_pace;
+use crate::resources;
+use crate::shell_windows;
+use crate::trace;
+
 // Thread-safe wrappers
 struct HwndWrapper(isize);
 unsafe impl Send for HwndWrapper {}
@@ -39,26 +136,111 @@ struct HookWrapper(isize);
 unsafe impl Send for HookWrapper {}
 unsafe impl Sync for HookWrapper {}
 
-/// Info about each monitor overlay (window handle + monitor index)
+/// Info about each monitor overlay (window handle + monitor index + device
+/// name + monitor id)
 struct OverlayEntry {
     hwnd: HwndWrapper,
     monitor_index: u32,
+    device: String,
+    id: MonitorId,
 }
 
 static OVERLAY_WINDOWS: Mutex<Vec<OverlayEntry>> = Mutex::new(Vec::new());
 static CURRENT_OPACITY: Mutex<f32> = Mutex::new(0.3);
 static ALLOW_CAPTURE: Mutex<bool> = Mutex::new(false);
+/// Per-monitor capture overrides (monitor index → allow), see set_capture_overrides
+static CAPTURE_OVERRIDES: Mutex<Vec<(u32, bool)>> = Mutex::new(Vec::new());
+/// The last WDA_EXCLUDEFROMCAPTURE was refused
+static EXCLUDE_FAILED: AtomicBool = AtomicBool::new(false);
 static CLASS_REGISTERED: Mutex<bool> = Mutex::new(false);
 static WATCHDOG_RUNNING: AtomicBool = AtomicBool::new(false);
-static EVENT_HOOK: Mutex<Option<HookWrapper>> = Mutex::new(None);
+/// Foreground and desktop-switch event hooks
+static EVENT_HOOKS: Mutex<Vec<HookWrapper>> = Mutex::new(Vec::new());
 
 /// Per-monitor opacities (monitor_index -> opacity)
 static PER_MONITOR_OPACITY: Mutex<Option<Vec<(u32, f32)>>> = Mutex::new(None);
 
-/// Counter used during monitor enumeration to assign indices
-static MONITOR_ENUM_COUNTER: Mutex<u32> = Mutex::new(0);
-
-/// Timestamp (millis since epoch) of the last foreground event.
+/// Dimming method per monitor: (default, per-index overrides)
+static BACKENDS: Mutex<(DimBackend, Vec<(u32, DimBackend)>)> =
+    Mutex::new((DimBackend::Overlay, Vec::new()));
+
+/// Curve mapping slider levels to applied alpha / gamma
+static CURVE: Mutex<OpacityCurve> = Mutex::new(OpacityCurve::Linear);
+/// Top-to-bottom gradient; while enabled the overlays are drawn with per-pixel
+/// alpha (overlay_gradient.rs)
+static GRADIENT: Mutex<Option<OverlayGradient>> = Mutex::new(None);
+/// Edge vignette, and its shape per monitor (index) as of the last show or
+/// refit; while enabled the overlays are drawn with per-pixel alpha too
+static VIGNETTE: Mutex<Option<EdgeVignette>> = Mutex::new(None);
+static VIGNETTE_SHAPES: Mutex<Vec<(u32, VignetteShape)>> = Mutex::new(Vec::new());
+
+/// Zoom compat option, and whether Magnifier was active at the last check
+static ZOOM_COMPAT: AtomicBool = AtomicBool::new(false);
+static MAGNIFIER_ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Session locked with lock screen dimming on: gamma everywhere (lock_screen.rs)
+static SESSION_LOCKED: AtomicBool = AtomicBool::new(false);
+/// Recording mode: overlays everywhere, included in captures
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Pen pause rule, and the level cap it imposed at the last check
+static PEN_PAUSE: Mutex<Option<PenPause>> = Mutex::new(None);
+static PEN_CAP: Mutex<Option<f32>> = Mutex::new(None);
+
+/// Level cap the contrast guard put on one monitor (index, cap)
+static CONTRAST_CAP: Mutex<Option<(u32, f32)>> = Mutex::new(None);
+
+/// Monitors the brightness rule keeps undimmed (brightness.rs)
+static BRIGHTNESS_GATE: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Night boost rule, and the boost it added at the last check
+static NIGHT_BOOST: Mutex<Option<NightBoost>> = Mutex::new(None);
+static NIGHT_BOOST_NOW: Mutex<Option<f32>> = Mutex::new(None);
+/// Boost hotkey overrides, dropped when their window loses the focus
+/// (focus_boost.rs), never saved
+static FOCUS_BOOST: Mutex<OverrideStack> = Mutex::new(OverrideStack::new());
+
+/// Toggle fade in progress, and the factor it has reached (1 = full level)
+static FADE: Mutex<Option<Fade>> = Mutex::new(None);
+static FADE_FACTOR: Mutex<f32> = Mutex::new(1.0);
+/// Thread timer stepping the fade, 0 when none
+static FADE_TIMER: AtomicUsize = AtomicUsize::new(0);
+
+/// Reading monitor preset, and the monitors (indices) it applies to as of
+/// the last show or refit
+static READING: Mutex<Option<ReadingMonitor>> = Mutex::new(None);
+static READING_MONITORS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Device names of monitors currently in HDR, and the backend / level
+/// offset applied to them
+static HDR_DEVICES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static HDR_ADJUST: Mutex<(DimBackend, f32)> = Mutex::new((DimBackend::Overlay, 0.0));
+
+/// Overlays are up from `warm_start` and the next show adopts them
+static WARM_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Window notified with WM_HDR_CHANGED / WM_OVERLAY_LOST / WM_NIGHT_BOOST_CHANGED
+/// / WM_GAMMA_REFUSED
+static NOTIFY_HWND: AtomicIsize = AtomicIsize::new(0);
+
+/// Posted to the notify window after HDR state changed (WPARAM = number of
+/// HDR monitors)
+pub const WM_HDR_CHANGED: u32 = WM_APP + 11;
+
+/// Posted to the notify window when the watchdog finds an overlay window
+/// destroyed from outside; the handler calls `recreate`
+pub const WM_OVERLAY_LOST: u32 = WM_APP + 14;
+
+/// Posted to the notify window when a gamma monitor's driver refused or
+/// clamped its ramp and the overlay took over (WPARAM = monitor index)
+pub const WM_GAMMA_REFUSED: u32 = WM_APP + 19;
+
+/// Posted to the notify window when the night boost starts or ends
+pub const WM_NIGHT_BOOST_CHANGED: u32 = WM_APP + 15;
+
+/// Designer mode: overlays and gamma stay off no matter who asks
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// `time_change::monotonic_ms` of the last foreground event.
 /// 0 means no pending re-assertion.
 static REASSERT_REQUESTED_AT: AtomicU64 = AtomicU64::new(0);
 
@@ -67,20 +249,22 @@ static REASSERT_REQUESTED_AT: AtomicU64 = AtomicU64::new(0);
 /// ping-pong that causes flickering.
 const DEBOUNCE_MS: u64 = 500;
 
+/// Shell experience window currently covering the overlay (0 = none).
+/// Debounced re-assertion is suspended until it closes.
+static SHELL_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// Screen clip layer (Win+Shift+S) currently up (0 = none). Every monitor
+/// is undimmed until it closes.
+static CLIP_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
 const CLASS_NAME: &str = "SaveMyEyesOverlay\0";
 
 // WinEvent constants
 const EVENT_SYSTEM_FOREGROUND: u32 = 0x0003;
+const EVENT_SYSTEM_DESKTOPSWITCH: u32 = 0x0020;
 const WINEVENT_OUTOFCONTEXT: u32 = 0x0000;
 const WINEVENT_SKIPOWNPROCESS: u32 = 0x0002;
 
-fn now_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 /// Minimal window proc — no WM_WINDOWPOSCHANGING override.
 unsafe extern "system" fn window_proc(
     hwnd: HWND,
@@ -106,7 +290,8 @@ fn register_class() -> bool {
             lpfnWndProc: Some(window_proc),
             hInstance: hinstance.into(),
             lpszClassName: PCWSTR(class_name.as_ptr()),
-            hbrBackground: CreateSolidBrush(COLORREF(0)),
+            // Stock brush: nothing to free, nothing leaked if registration fails
+            hbrBackground: HBRUSH(GetStockObject(BLACK_BRUSH).0),
             ..Default::default()
         };
 
@@ -120,16 +305,19 @@ fn register_class() -> bool {
     }
 }
 
-/// Re-assert topmost on all overlay windows.
+/// Re-assert topmost on all overlay windows (just below a running
+/// annotator's windows, if there is one).
 fn reassert_topmost() {
+    let insert_after = overlay_compat::insert_after().unwrap_or(HWND_TOPMOST);
     let windows = OVERLAY_WINDOWS.lock().unwrap();
+    let _trace = trace::span(Mark::Reassert, "", windows.len() as f32);
     for entry in windows.iter() {
         unsafe {
             let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
             if IsWindow(Some(hwnd)).as_bool() {
                 let _ = SetWindowPos(
                     hwnd,
-                    Some(HWND_TOPMOST),
+                    Some(insert_after),
                     0,
                     0,
                     0,
@@ -145,44 +333,101 @@ fn reassert_topmost() {
 /// Instead of immediately re-asserting (which causes flickering), we just
 /// record the timestamp. A background thread checks this and waits for the
 /// debounce period to elapse before actually re-asserting.
+/// Also fired after a desktop switch (UAC prompt, lock screen), when the
+/// display mode may have changed while our desktop was hidden.
 unsafe extern "system" fn win_event_proc(
     _hook: HWINEVENTHOOK,
-    _event: u32,
-    _hwnd: HWND,
+    event: u32,
+    hwnd: HWND,
     _id_object: i32,
     _id_child: i32,
     _id_event_thread: u32,
     _event_time: u32,
 ) {
+    poll_pace::activity();
+    if event == EVENT_SYSTEM_FOREGROUND {
+        check_focus_boost();
+    }
+    if event == EVENT_SYSTEM_DESKTOPSWITCH {
+        // Out-of-context hooks run on the installing (UI) thread, which
+        // owns the overlay windows
+        refit();
+        return;
+    }
+
+    if shell_windows::is_screen_clip(hwnd) {
+        // Region selection started: undim now, it darkens the screen itself.
+        // It is topmost too, so don't fight it either.
+        if CLIP_WINDOW.swap(hwnd.0 as isize, Ordering::SeqCst) == 0 {
+            eprintln!("[overlay] screen clip opened, undimming");
+            reapply_levels();
+        }
+        REASSERT_REQUESTED_AT.store(0, Ordering::SeqCst);
+        return;
+    }
+
+    if shell_windows::is_shell_experience(hwnd) {
+        // Start / Task View / notification center opened: re-asserting now
+        // would only flash. Wait for it to close instead.
+        SHELL_WINDOW.store(hwnd.0 as isize, Ordering::SeqCst);
+        REASSERT_REQUESTED_AT.store(0, Ordering::SeqCst);
+        return;
+    }
+
+    if SHELL_WINDOW.swap(0, Ordering::SeqCst) != 0 {
+        // Shell surface was dismissed by switching to another window:
+        // re-assert on the next poll without waiting for the debounce.
+        REASSERT_REQUESTED_AT.store(time_change::monotonic_ms().saturating_sub(DEBOUNCE_MS), Ordering::SeqCst);
+        return;
+    }
+
+    if overlay_compat::skip_reassert(hwnd) {
+        // Another dimmer or a magnifier: fighting it over z-order is the flicker
+        return;
+    }
+
     // Record "re-assertion needed" with current timestamp.
     // Each new event resets the debounce timer.
-    REASSERT_REQUESTED_AT.store(now_ms(), Ordering::SeqCst);
+    REASSERT_REQUESTED_AT.store(time_change::monotonic_ms(), Ordering::SeqCst);
+}
+
+/// True once the remembered shell experience (or screen clip) window is gone
+/// or has lost the foreground.
+fn shell_window_closed(raw: isize) -> bool {
+    unsafe {
+        let hwnd = HWND(raw as *mut std::ffi::c_void);
+        !IsWindow(Some(hwnd)).as_bool()
+            || !IsWindowVisible(hwnd).as_bool()
+            || GetForegroundWindow() != hwnd
+    }
 }
 
 fn install_event_hook() {
-    let mut hook_guard = EVENT_HOOK.lock().unwrap();
-    if hook_guard.is_some() {
+    let mut hooks = EVENT_HOOKS.lock().unwrap();
+    if !hooks.is_empty() {
         return;
     }
-    unsafe {
-        let hook = SetWinEventHook(
-            EVENT_SYSTEM_FOREGROUND,
-            EVENT_SYSTEM_FOREGROUND,
-            None,
-            Some(win_event_proc),
-            0,
-            0,
-            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
-        );
-        if !hook.is_invalid() {
-            *hook_guard = Some(HookWrapper(hook.0 as isize));
+    for event in [EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_DESKTOPSWITCH] {
+        unsafe {
+            let hook = SetWinEventHook(
+                event,
+                event,
+                None,
+                Some(win_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+            );
+            if !hook.is_invalid() {
+                hooks.push(HookWrapper(hook.0 as isize));
+            }
         }
     }
 }
 
 fn uninstall_event_hook() {
-    let mut hook_guard = EVENT_HOOK.lock().unwrap();
-    if let Some(wrapper) = hook_guard.take() {
+    let mut hooks = EVENT_HOOKS.lock().unwrap();
+    for wrapper in hooks.drain(..) {
         unsafe {
             let hook = HWINEVENTHOOK(wrapper.0 as *mut std::ffi::c_void);
             let _ = UnhookWinEvent(hook);
@@ -190,142 +435,563 @@ fn uninstall_event_hook() {
     }
 }
 
-/// Callback for EnumDisplayMonitors — creates one overlay per monitor
-unsafe extern "system" fn monitor_enum_proc(
-    hmonitor: HMONITOR,
-    _hdc: HDC,
-    _lprect: *mut RECT,
-    _lparam: LPARAM,
-) -> windows::core::BOOL {
-    let mut mi = MONITORINFO {
-        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
-        ..Default::default()
+fn is_hdr(device: &str) -> bool {
+    HDR_DEVICES.lock().unwrap().iter().any(|d| d == device)
+}
+
+fn backend_for(monitor_index: u32, device: &str) -> DimBackend {
+    // Gamma ramps never show up in a recording (nobody records the lock screen)
+    if RECORDING.load(Ordering::SeqCst) && !SESSION_LOCKED.load(Ordering::SeqCst) {
+        return DimBackend::Overlay;
+    }
+    // Gamma ramps are ignored in HDR, so this wins over zoom compat
+    if is_hdr(device) {
+        return HDR_ADJUST.lock().unwrap().0;
+    }
+    if ZOOM_COMPAT.load(Ordering::SeqCst) && MAGNIFIER_ACTIVE.load(Ordering::SeqCst) {
+        return DimBackend::Gamma;
+    }
+    // Overlay windows don't reach the secure desktop, gamma ramps do
+    if SESSION_LOCKED.load(Ordering::SeqCst) {
+        return DimBackend::Gamma;
+    }
+    let backends = BACKENDS.lock().unwrap();
+    backends
+        .1
+        .iter()
+        .find(|(idx, _)| *idx == monitor_index)
+        .map(|(_, b)| *b)
+        .unwrap_or(backends.0)
+}
+
+/// Opacity for a monitor: per-monitor if available, else global
+fn opacity_for(monitor_index: u32) -> f32 {
+    let per_mon = PER_MONITOR_OPACITY.lock().unwrap();
+    if let Some(ref map) = *per_mon {
+        map.iter()
+            .find(|(idx, _)| *idx == monitor_index)
+            .map(|(_, o)| *o)
+            .unwrap_or(*CURRENT_OPACITY.lock().unwrap())
+    } else {
+        *CURRENT_OPACITY.lock().unwrap()
+    }
+}
+
+/// Re-apply current levels to every overlay (after a backend switch)
+fn reapply_levels() {
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    for entry in windows.iter() {
+        unsafe {
+            let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+            apply_level(hwnd, entry.monitor_index, &entry.device, opacity_for(entry.monitor_index));
+        }
+    }
+}
+
+/// Switch every monitor to gamma if Magnifier started or stopped.
+fn check_magnifier() {
+    let active = ZOOM_COMPAT.load(Ordering::SeqCst) && magnifier::is_active();
+    if MAGNIFIER_ACTIVE.swap(active, Ordering::SeqCst) != active {
+        reapply_levels();
+    }
+}
+
+/// Cap or restore levels when the pen starts or stops drawing.
+fn check_pen() {
+    let cap = PEN_PAUSE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|rule| rule.cap(mouse_hooks::last_pen_input(), std::time::Instant::now()));
+    let changed = {
+        let mut current = PEN_CAP.lock().unwrap();
+        std::mem::replace(&mut *current, cap) != cap
     };
+    if changed {
+        eprintln!("[overlay] pen cap now {:?}", cap);
+        reapply_levels();
+    }
+}
 
-    if GetMonitorInfoW(hmonitor, &mut mi).as_bool() {
-        let rect = mi.rcMonitor;
-        let width = rect.right - rect.left;
-        let height = rect.bottom - rect.top;
-
-        // Assign a monitor index
-        let monitor_index = {
-            let mut counter = MONITOR_ENUM_COUNTER.lock().unwrap();
-            let idx = *counter;
-            *counter += 1;
-            idx
-        };
+/// Drop the boost hotkey's overrides once their window lost the focus.
+fn check_focus_boost() {
+    let dropped = {
+        let mut stack = FOCUS_BOOST.lock().unwrap();
+        stack.is_active() && stack.focus_changed(focus_boost::foreground().as_ref())
+    };
+    if dropped {
+        eprintln!("[overlay] focus moved, boost over");
+        reapply_levels();
+    }
+}
 
-        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
-        let class_name: Vec<u16> = CLASS_NAME.encode_utf16().collect();
+/// Re-apply levels and notify the UI when the night boost starts or ends.
+fn check_night_boost() {
+    let boost = NIGHT_BOOST.lock().unwrap().as_ref().and_then(|rule| rule.current());
+    let changed = std::mem::replace(&mut *NIGHT_BOOST_NOW.lock().unwrap(), boost) != boost;
+    if changed {
+        eprintln!("[overlay] night boost now {:?}", boost);
+        reapply_levels();
+        let notify = NOTIFY_HWND.load(Ordering::SeqCst);
+        if notify != 0 {
+            unsafe {
+                let _ = PostMessageW(
+                    Some(HWND(notify as *mut std::ffi::c_void)),
+                    WM_NIGHT_BOOST_CHANGED,
+                    WPARAM(boost.is_some() as usize),
+                    LPARAM(0),
+                );
+            }
+        }
+    }
+}
 
-        let hwnd = CreateWindowExW(
-            WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
-            PCWSTR(class_name.as_ptr()),
-            PCWSTR::null(),
-            WS_POPUP | WS_VISIBLE | WS_DISABLED,
-            rect.left,
-            rect.top,
-            width,
-            height,
-            None,
-            None,
-            Some(hinstance.into()),
-            None,
-        );
+/// Re-apply levels and notify the UI if any monitor entered or left HDR.
+fn check_hdr() {
+    let devices = hdr::hdr_devices();
+    let changed = {
+        let mut current = HDR_DEVICES.lock().unwrap();
+        if *current == devices {
+            false
+        } else {
+            eprintln!("[overlay] HDR monitors now: {:?}", devices);
+            *current = devices.clone();
+            true
+        }
+    };
+    if changed {
+        reapply_levels();
+        let notify = NOTIFY_HWND.load(Ordering::SeqCst);
+        if notify != 0 {
+            unsafe {
+                let _ = PostMessageW(
+                    Some(HWND(notify as *mut std::ffi::c_void)),
+                    WM_HDR_CHANGED,
+                    WPARAM(devices.len()),
+                    LPARAM(0),
+                );
+            }
+        }
+    }
+}
 
-        if let Ok(hwnd) = hwnd {
-            // Determine opacity: per-monitor if available, else global
-            let opacity = {
-                let per_mon = PER_MONITOR_OPACITY.lock().unwrap();
-                if let Some(ref map) = *per_mon {
-                    map.iter()
-                        .find(|(idx, _)| *idx == monitor_index)
-                        .map(|(_, o)| *o)
-                        .unwrap_or(*CURRENT_OPACITY.lock().unwrap())
-                } else {
-                    *CURRENT_OPACITY.lock().unwrap()
+/// Find the reading monitors among `monitors` (EnumDisplayMonitors order).
+fn update_reading_monitors(monitors: &[MonitorInfo]) {
+    let indices: Vec<u32> = match &*READING.lock().unwrap() {
+        Some(rule) => monitors
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| rule.applies(&m.id, m.is_portrait()))
+            .map(|(index, _)| index as u32)
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut current = READING_MONITORS.lock().unwrap();
+    if *current != indices {
+        eprintln!("[overlay] reading monitors now: {:?}", indices);
+        *current = indices;
+    }
+}
+
+/// Work out the vignette of each of `monitors` (EnumDisplayMonitors order).
+fn update_vignettes(monitors: &[MonitorInfo]) {
+    let shapes = match &*VIGNETTE.lock().unwrap() {
+        Some(rule) => monitors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, m)| Some((index as u32, rule.shape_for(&m.id)?.clone())))
+            .collect(),
+        None => Vec::new(),
+    };
+    *VIGNETTE_SHAPES.lock().unwrap() = shapes;
+}
+
+/// True while the overlays are drawn with per-pixel alpha
+fn per_pixel_alpha() -> bool {
+    GRADIENT.lock().unwrap().is_some_and(|g| g.enabled)
+        || VIGNETTE.lock().unwrap().as_ref().is_some_and(|v| v.enabled)
+}
+
+/// Dim one monitor to `level` (night boost, reading and HDR offsets added, then mapped
+/// through the curve and scaled by the toggle fade) using its configured backend. 0 while the screen clip
+/// layer is up or the brightness rule keeps the monitor undimmed.
+/// Gamma monitors keep a fully transparent overlay unless the ramp is refused.
+unsafe fn apply_level(hwnd: HWND, monitor_index: u32, device: &str, level: f32) {
+    let level = night_boost::boosted(level, *NIGHT_BOOST_NOW.lock().unwrap());
+    let level = match &*READING.lock().unwrap() {
+        Some(rule) if READING_MONITORS.lock().unwrap().contains(&monitor_index) => {
+            rule.adjusted(level)
+        }
+        _ => level,
+    };
+    let level = if is_hdr(device) && level > 0.0 {
+        (level + HDR_ADJUST.lock().unwrap().1).clamp(0.0, 0.9)
+    } else {
+        level
+    };
+    let level = FOCUS_BOOST.lock().unwrap().apply(level);
+    let level = match *PEN_CAP.lock().unwrap() {
+        Some(cap) => level.min(cap),
+        None => level,
+    };
+    let level = match *CONTRAST_CAP.lock().unwrap() {
+        Some((index, cap)) if index == monitor_index => level.min(cap),
+        _ => level,
+    };
+    let gated = BRIGHTNESS_GATE.lock().unwrap().contains(&monitor_index);
+    let level = if CLIP_WINDOW.load(Ordering::SeqCst) != 0 || gated {
+        0.0
+    } else {
+        level
+    };
+    let curve = *CURVE.lock().unwrap();
+    let fade = *FADE_FACTOR.lock().unwrap();
+    let opacity = curve.to_applied(level) * fade;
+    let use_gamma = backend_for(monitor_index, device) == DimBackend::Gamma
+        && match gamma::apply(device, opacity) {
+            Ok(()) => true,
+            Err(refused) => {
+                if refused == gamma::Refused::New {
+                    notify_gamma_refused(monitor_index);
                 }
-            };
-            let alpha = (opacity * 255.0) as u8;
-            let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
-
-            // Capture exclusion — ShareX, OBS, Snipping Tool, etc. won't see the dimming
-            let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
-            let affinity = if allow_capture {
-                WDA_NONE
-            } else {
-                WDA_EXCLUDEFROMCAPTURE
-            };
-            let _ = SetWindowDisplayAffinity(hwnd, affinity);
+                false
+            }
+        };
+    if !use_gamma {
+        gamma::restore(device);
+    }
+    if per_pixel_alpha() {
+        let gradient = GRADIENT.lock().unwrap().filter(|g| g.enabled);
+        let vignette = VIGNETTE_SHAPES
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(index, _)| *index == monitor_index)
+            .map(|(_, shape)| shape.clone());
+        overlay_gradient::paint(hwnd, vignette.as_ref(), |position, nearness| {
+            if use_gamma {
+                return 0;
+            }
+            let level = gradient.map_or(level, |g| g.level_at(level, position));
+            let level = vignette.as_ref().map_or(level, |v| v.level_at(level, nearness));
+            dimming::alpha(curve.to_applied(level) * fade)
+        });
+        return;
+    }
+    let alpha = if use_gamma {
+        0
+    } else {
+        dimming::alpha(opacity)
+    };
+    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+}
 
-            let _ = SetWindowPos(
-                hwnd,
-                Some(HWND_TOPMOST),
-                0,
-                0,
-                0,
-                0,
-                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_NOSENDCHANGING,
+/// Tell the settings window the overlay took over from a refused ramp
+fn notify_gamma_refused(monitor_index: u32) {
+    let notify = NOTIFY_HWND.load(Ordering::SeqCst);
+    if notify != 0 {
+        unsafe {
+            let _ = PostMessageW(
+                Some(HWND(notify as *mut std::ffi::c_void)),
+                WM_GAMMA_REFUSED,
+                WPARAM(monitor_index as usize),
+                LPARAM(0),
             );
+        }
+    }
+}
 
-            OVERLAY_WINDOWS
-                .lock()
-                .unwrap()
-                .push(OverlayEntry {
-                    hwnd: HwndWrapper(hwnd.0 as isize),
-                    monitor_index,
-                });
+/// Capture setting in effect for a monitor (override, else the global one)
+fn allow_capture_for(monitor_index: u32) -> bool {
+    if RECORDING.load(Ordering::SeqCst) {
+        return true;
+    }
+    CAPTURE_OVERRIDES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(idx, _)| *idx == monitor_index)
+        .map(|(_, allow)| *allow)
+        .unwrap_or(*ALLOW_CAPTURE.lock().unwrap())
+}
+
+unsafe fn apply_capture(hwnd: HWND, monitor_index: u32) {
+    let affinity = if allow_capture_for(monitor_index) {
+        WDA_NONE
+    } else {
+        WDA_EXCLUDEFROMCAPTURE
+    };
+    let result = if faults::display_affinity_fails() {
+        Err(E_ACCESSDENIED.into())
+    } else {
+        SetWindowDisplayAffinity(hwnd, affinity)
+    };
+    if affinity == WDA_EXCLUDEFROMCAPTURE {
+        EXCLUDE_FAILED.store(result.is_err(), Ordering::SeqCst);
+    }
+    // The overlay still dims, it just shows up in captures
+    if let Err(e) = result {
+        eprintln!(
+            "[overlay] capture setting failed for monitor {}: {}",
+            monitor_index, e
+        );
+    }
+}
+
+/// Report what is on screen now to the crash journal. Hidden overlays don't
+/// mean dimming is off: the user's choice is journaled along with it.
+fn journal_applied() {
+    if SUPPRESSED.load(Ordering::SeqCst) {
+        return;
+    }
+    let mut state = AppliedState::new(is_visible(), *CURRENT_OPACITY.lock().unwrap());
+    state.curve = *CURVE.lock().unwrap();
+    if let Some(ref map) = *PER_MONITOR_OPACITY.lock().unwrap() {
+        state.per_monitor_opacity = map.iter().copied().collect();
+    }
+    // Not while holding PER_MONITOR_OPACITY: reapply_levels locks the
+    // windows first
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    for (index, level) in &state.per_monitor_opacity {
+        if let Some(entry) = windows.iter().find(|e| e.monitor_index == *index) {
+            state.monitor_opacity.insert(entry.id.clone(), *level);
         }
     }
+    drop(windows);
+    journal::record(state);
+}
 
-    windows::core::BOOL::from(true)
+/// Create the overlay for the monitor at `monitor_index`
+unsafe fn create_overlay(monitor_index: u32, monitor: &MonitorInfo) {
+    let opacity = opacity_for(monitor_index);
+    let _trace = trace::span(Mark::OverlayCreate, &monitor.device, opacity);
+    let rect = monitor.rect;
+    let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+    let class_name: Vec<u16> = CLASS_NAME.encode_utf16().collect();
+
+    let Ok(hwnd) = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WS_POPUP | WS_VISIBLE | WS_DISABLED,
+        rect.left,
+        rect.top,
+        rect.right - rect.left,
+        rect.bottom - rect.top,
+        None,
+        None,
+        Some(hinstance.into()),
+        None,
+    ) else {
+        return;
+    };
+
+    resources::OVERLAY_WINDOWS.created();
+    apply_level(hwnd, monitor_index, &monitor.device, opacity);
+
+    // Capture exclusion — ShareX, OBS, Snipping Tool, etc. won't see the dimming
+    apply_capture(hwnd, monitor_index);
+
+    let insert_after = overlay_compat::insert_after().unwrap_or(HWND_TOPMOST);
+    let _ = SetWindowPos(
+        hwnd,
+        Some(insert_after),
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_NOSENDCHANGING,
+    );
+
+    OVERLAY_WINDOWS.lock().unwrap().push(OverlayEntry {
+        hwnd: HwndWrapper(hwnd.0 as isize),
+        monitor_index,
+        device: monitor.device.clone(),
+        id: monitor.id.clone(),
+    });
 }
 
 /// Show overlay with given opacity on all monitors.
 pub fn show_overlay(opacity: f32, allow_capture: bool) {
+    stop_fade();
+    show_overlays(opacity, allow_capture);
+}
+
+/// Fade dimming in to `opacity` on all monitors (UI thread), or show it at
+/// once when fades are off. Turning around a fade out starts where it got to.
+pub fn fade_in(opacity: f32, allow_capture: bool, settings: &FadeSettings) {
+    if !fades(settings) {
+        show_overlay(opacity, allow_capture);
+        return;
+    }
+    let from = match FADE.lock().unwrap().take() {
+        Some(_) => *FADE_FACTOR.lock().unwrap(),
+        None => 0.0,
+    };
+    *FADE_FACTOR.lock().unwrap() = from;
+    show_overlays(opacity, allow_capture);
+    if is_visible() {
+        start_fade(Fade::new(from, 1.0, settings));
+    } else {
+        stop_fade();
+    }
+}
+
+/// Fade dimming out and then hide the overlays (UI thread), or hide them at
+/// once when fades are off. Turning around a fade in starts where it got to.
+pub fn fade_out(settings: &FadeSettings) {
+    let from = match FADE.lock().unwrap().take() {
+        Some(_) => *FADE_FACTOR.lock().unwrap(),
+        None => 1.0,
+    };
+    if !fades(settings) || !is_visible() {
+        hide_overlay();
+        return;
+    }
+    start_fade(Fade::new(from, 0.0, settings));
+}
+
+fn fades(settings: &FadeSettings) -> bool {
+    settings.duration_ms > 0 && motion::animate(hud::os_reduces_motion)
+}
+
+fn start_fade(fade: Fade) {
+    *FADE.lock().unwrap() = Some(fade);
+    let old = FADE_TIMER.load(Ordering::SeqCst);
+    // Passing the old timer's id replaces it
+    let timer = unsafe {
+        SetTimer(None, old, fade::FRAME.as_millis() as u32, Some(fade_step))
+    };
+    FADE_TIMER.store(timer, Ordering::SeqCst);
+}
+
+/// End any fade where it is and go back to full levels. Callers show or
+/// hide the overlays right after.
+fn stop_fade() {
+    let timer = FADE_TIMER.swap(0, Ordering::SeqCst);
+    if timer != 0 {
+        unsafe {
+            let _ = KillTimer(None, timer);
+        }
+    }
+    *FADE.lock().unwrap() = None;
+    *FADE_FACTOR.lock().unwrap() = 1.0;
+}
+
+/// Fade timer: apply the next factor, and hide the overlays once a fade out
+/// is over
+unsafe extern "system" fn fade_step(_: HWND, _: u32, _: usize, _: u32) {
+    let Some(fade) = *FADE.lock().unwrap() else {
+        return;
+    };
+    let (factor, done) = fade.factor();
+    trace::mark(Mark::FadeFrame, "overlay", factor);
+    if done && fade.fading_out() {
+        hide_overlay();
+        return;
+    }
+    *FADE_FACTOR.lock().unwrap() = factor;
+    if done {
+        stop_fade();
+    }
+    reapply_levels();
+}
+
+fn show_overlays(opacity: f32, allow_capture: bool) {
     *CURRENT_OPACITY.lock().unwrap() = opacity.clamp(0.0, 0.9);
     *ALLOW_CAPTURE.lock().unwrap() = allow_capture;
+    poll_pace::activity();
 
-    hide_overlay();
+    // Recreating the warm-start overlays would flash the screen bright again
+    let adopt = WARM_STARTED.swap(false, Ordering::SeqCst) && is_visible();
+    if !adopt {
+        remove_overlays();
+    }
 
-    if !register_class() {
+    if SUPPRESSED.load(Ordering::SeqCst) {
+        return;
+    }
+    MAGNIFIER_ACTIVE.store(
+        ZOOM_COMPAT.load(Ordering::SeqCst) && magnifier::is_active(),
+        Ordering::SeqCst,
+    );
+    *HDR_DEVICES.lock().unwrap() = hdr::hdr_devices();
+    *NIGHT_BOOST_NOW.lock().unwrap() = NIGHT_BOOST.lock().unwrap().as_ref().and_then(|r| r.current());
+    let monitors = monitors::list();
+    update_reading_monitors(&monitors);
+    update_vignettes(&monitors);
+
+    if adopt {
+        reapply_levels();
+        for entry in OVERLAY_WINDOWS.lock().unwrap().iter() {
+            let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+            unsafe { apply_capture(hwnd, entry.monitor_index) };
+        }
+        journal_applied();
         return;
     }
 
-    // Reset monitor counter before enumeration
-    *MONITOR_ENUM_COUNTER.lock().unwrap() = 0;
+    if !register_class() {
+        return;
+    }
 
-    unsafe {
-        let _ = EnumDisplayMonitors(None, None, Some(monitor_enum_proc), LPARAM(0));
+    overlay_compat::scan();
+    for (index, monitor) in monitors.iter().enumerate() {
+        unsafe { create_overlay(index as u32, monitor) };
     }
 
     // Install event hook for foreground changes
     install_event_hook();
+    journal_applied();
 
     // Start the debounce + watchdog thread.
     // This single thread handles:
     //   1. Debounced z-order re-assertion (waits 500ms after last foreground event)
-    //   2. Watchdog for externally destroyed windows (checks every 5s)
+    //   2. Watchdog for externally destroyed windows (every 5s, 30s when idle)
     if !WATCHDOG_RUNNING.swap(true, Ordering::SeqCst) {
-        std::thread::spawn(|| {
-            let mut watchdog_counter: u32 = 0;
+        shutdown::spawn("overlay-poll", || {
+            let mut last_checks = Instant::now();
+            let mut last_watchdog = Instant::now();
 
             loop {
-                // Poll every 200ms (fast enough for debounce, low CPU usage)
-                std::thread::sleep(std::time::Duration::from_millis(200));
+                // Poll every 200ms while something is going on, every 1s
+                // otherwise (see poll_pace.rs)
+                let busy = REASSERT_REQUESTED_AT.load(Ordering::SeqCst) > 0
+                    || SHELL_WINDOW.load(Ordering::SeqCst) != 0
+                    || CLIP_WINDOW.load(Ordering::SeqCst) != 0
+                    || PEN_CAP.lock().unwrap().is_some();
+                let fast = poll_pace::is_fast(busy);
+                poll_pace::wait(fast);
 
                 let windows = OVERLAY_WINDOWS.lock().unwrap();
-                if windows.is_empty() {
+                if windows.is_empty() || shutdown::is_stopping() {
                     drop(windows);
                     WATCHDOG_RUNNING.store(false, Ordering::SeqCst);
                     break;
                 }
                 drop(windows);
 
+                // ── Shell experience window open: hold off until it closes ──
+                let shell = SHELL_WINDOW.load(Ordering::SeqCst);
+                if shell != 0 && shell_window_closed(shell) {
+                    SHELL_WINDOW.store(0, Ordering::SeqCst);
+                    REASSERT_REQUESTED_AT.store(0, Ordering::SeqCst);
+                    reassert_topmost();
+                }
+
+                // ── Screen clip closed: restore levels ──
+                let clip = CLIP_WINDOW.load(Ordering::SeqCst);
+                if clip != 0 && shell_window_closed(clip) {
+                    CLIP_WINDOW.store(0, Ordering::SeqCst);
+                    eprintln!("[overlay] screen clip closed, restoring levels");
+                    reapply_levels();
+                    reassert_topmost();
+                }
+
                 // ── Debounced re-assertion ──
                 let requested_at = REASSERT_REQUESTED_AT.load(Ordering::SeqCst);
                 if requested_at > 0 {
-                    let elapsed = now_ms().saturating_sub(requested_at);
+                    let elapsed = time_change::monotonic_ms().saturating_sub(requested_at);
                     if elapsed >= DEBOUNCE_MS {
                         // Enough time has passed since the last foreground event.
                         // The window manager has settled — safe to re-assert now.
@@ -335,10 +1001,24 @@ pub fn show_overlay(opacity: f32, allow_capture: bool) {
                     // else: still within debounce window, wait longer
                 }
 
-                // ── Watchdog (every 5s = 25 × 200ms) ──
-                watchdog_counter += 1;
-                if watchdog_counter >= 25 {
-                    watchdog_counter = 0;
+                // ── Pen pause (every poll, so drawing brightens quickly) ──
+                check_pen();
+
+                // ── Boost hotkey (every poll, catches title changes too) ──
+                check_focus_boost();
+
+                // ── Magnifier, HDR and night boost checks (every 1s) ──
+                if last_checks.elapsed() >= Duration::from_secs(1) {
+                    last_checks = Instant::now();
+                    check_magnifier();
+                    check_hdr();
+                    check_night_boost();
+                }
+
+                // ── Watchdog (every 5s or 30s, or when the self-test asks) ──
+                if poll_pace::watchdog_due(last_watchdog, fast) || faults::take_watchdog_request() {
+                    last_watchdog = Instant::now();
+                    overlay_compat::scan();
 
                     let windows = OVERLAY_WINDOWS.lock().unwrap();
                     let mut needs_recreate = false;
@@ -354,34 +1034,18 @@ pub fn show_overlay(opacity: f32, allow_capture: bool) {
                     drop(windows);
 
                     if needs_recreate {
-                        let opacity = *CURRENT_OPACITY.lock().unwrap();
-                        let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
-                        // Tear down and rebuild
-                        {
-                            uninstall_event_hook();
-                            let mut windows = OVERLAY_WINDOWS.lock().unwrap();
-                            for entry in windows.drain(..) {
-                                unsafe {
-                                    let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
-                                    let _ = ShowWindow(hwnd, SW_HIDE);
-                                    let _ = DestroyWindow(hwnd);
-                                }
-                            }
-                        }
-                        if register_class() {
-                            *CURRENT_OPACITY.lock().unwrap() = opacity;
-                            *ALLOW_CAPTURE.lock().unwrap() = allow_capture;
-                            // Reset monitor counter before re-enumeration
-                            *MONITOR_ENUM_COUNTER.lock().unwrap() = 0;
+                        // Windows belong to the thread that created them, so
+                        // the UI thread tears down and rebuilds (see recreate)
+                        let notify = NOTIFY_HWND.load(Ordering::SeqCst);
+                        if notify != 0 {
                             unsafe {
-                                let _ = EnumDisplayMonitors(
-                                    None,
-                                    None,
-                                    Some(monitor_enum_proc),
+                                let _ = PostMessageW(
+                                    Some(HWND(notify as *mut std::ffi::c_void)),
+                                    WM_OVERLAY_LOST,
+                                    WPARAM(0),
                                     LPARAM(0),
                                 );
                             }
-                            install_event_hook();
                         }
                     }
                 }
@@ -390,19 +1054,141 @@ pub fn show_overlay(opacity: f32, allow_capture: bool) {
     }
 }
 
+/// Put the journaled dimming back on screen before the rest of startup
+/// (after a self-update relaunch or a crash), with default settings apart
+/// from the curve. The next `show_overlay` takes these overlays over in
+/// place. Returns true if anything was shown.
+pub fn warm_start() -> bool {
+    let Some(state) = journal::load() else {
+        return false;
+    };
+    if !state.enabled {
+        return false;
+    }
+    set_curve(state.curve);
+    if !state.per_monitor_opacity.is_empty() {
+        set_per_monitor_opacities(&state.per_monitor_opacity);
+    }
+    show_overlay(state.opacity, false);
+    // Startup sets per-monitor levels again only if they are configured
+    *PER_MONITOR_OPACITY.lock().unwrap() = None;
+    if !is_visible() {
+        return false;
+    }
+    WARM_STARTED.store(true, Ordering::SeqCst);
+    let percent = state.opacity * 100.0;
+    match state.dark_gap_ms() {
+        Some(gap) => eprintln!(
+            "[overlay] warm start at {:.0}%, screen was undimmed for {} ms",
+            percent, gap
+        ),
+        None => eprintln!("[overlay] warm start at {:.0}% from the journal", percent),
+    }
+    true
+}
+
+/// Rebuild every overlay at the current level after one was destroyed from
+/// outside (UI thread, on WM_OVERLAY_LOST).
+pub fn recreate() {
+    if !is_visible() {
+        return;
+    }
+    eprintln!("[overlay] overlay window lost, recreating");
+    let opacity = *CURRENT_OPACITY.lock().unwrap();
+    let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
+    show_overlays(opacity, allow_capture);
+}
+
+/// Move and resize every overlay to its monitor's current bounds (UI thread,
+/// on WM_DISPLAYCHANGE and after a desktop switch). Rotation and resolution
+/// changes keep the monitor list, so the windows are moved in place; if a
+/// monitor was added or removed, the overlays are rebuilt instead.
+pub fn refit() {
+    gamma::forget_calibrations();
+    gamma::forget_refusals();
+    if !is_visible() {
+        return;
+    }
+    let monitors = monitors::list();
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    let same_monitors = windows.len() == monitors.len()
+        && windows.iter().zip(&monitors).all(|(entry, m)| entry.device == m.device);
+    if !same_monitors {
+        drop(windows);
+        eprintln!("[overlay] monitors added or removed, rebuilding overlays");
+        let opacity = *CURRENT_OPACITY.lock().unwrap();
+        let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
+        show_overlays(opacity, allow_capture);
+        return;
+    }
+    for (entry, m) in windows.iter().zip(&monitors) {
+        unsafe {
+            let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+            let _ = SetWindowPos(
+                hwnd,
+                Some(HWND_TOPMOST),
+                m.rect.left,
+                m.rect.top,
+                m.rect.right - m.rect.left,
+                m.rect.bottom - m.rect.top,
+                SWP_NOACTIVATE | SWP_NOSENDCHANGING,
+            );
+        }
+    }
+    drop(windows);
+    // A rotation may have made a monitor a reading monitor, or not
+    update_reading_monitors(&monitors);
+    update_vignettes(&monitors);
+    // A mode change resets gamma ramps
+    reapply_levels();
+}
+
+/// Destroy one overlay window (on the thread that created it).
+fn destroy_entry(entry: OverlayEntry) {
+    unsafe {
+        let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+        if !IsWindow(Some(hwnd)).as_bool() {
+            // Already destroyed from outside
+            resources::OVERLAY_WINDOWS.destroyed();
+            return;
+        }
+        let _ = ShowWindow(hwnd, SW_HIDE);
+        match DestroyWindow(hwnd) {
+            Ok(()) => resources::OVERLAY_WINDOWS.destroyed(),
+            Err(e) => eprintln!(
+                "[overlay] could not destroy overlay for monitor {}: {}",
+                entry.monitor_index, e
+            ),
+        }
+    }
+}
+
 /// Hide overlay windows and clean up hooks
 pub fn hide_overlay() {
+    stop_fade();
+    remove_overlays();
+}
+
+fn remove_overlays() {
     uninstall_event_hook();
     REASSERT_REQUESTED_AT.store(0, Ordering::SeqCst);
+    SHELL_WINDOW.store(0, Ordering::SeqCst);
+    CLIP_WINDOW.store(0, Ordering::SeqCst);
 
     let mut windows = OVERLAY_WINDOWS.lock().unwrap();
     for entry in windows.drain(..) {
-        unsafe {
-            let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
-            let _ = ShowWindow(hwnd, SW_HIDE);
-            let _ = DestroyWindow(hwnd);
-        }
+        destroy_entry(entry);
     }
+    drop(windows);
+    // Wake the poll thread so it notices the overlays are gone and exits
+    poll_pace::activity();
+    debug_assert_eq!(
+        resources::OVERLAY_WINDOWS.live(),
+        0,
+        "[overlay] overlay windows left after hide"
+    );
+    gamma::restore_all();
+    journal_applied();
 }
 
 /// Update overlay alpha on all windows.
@@ -412,16 +1198,16 @@ pub fn set_opacity(opacity: f32) {
     *CURRENT_OPACITY.lock().unwrap() = opacity;
     // Clear per-monitor overrides when setting global opacity
     *PER_MONITOR_OPACITY.lock().unwrap() = None;
-    let alpha = (opacity * 255.0) as u8;
 
     let windows = OVERLAY_WINDOWS.lock().unwrap();
     for entry in windows.iter() {
         unsafe {
             let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
-            let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+            apply_level(hwnd, entry.monitor_index, &entry.device, opacity);
         }
     }
     drop(windows);
+    journal_applied();
 
     // Re-assert on user-initiated change
     reassert_topmost();
@@ -442,17 +1228,17 @@ pub fn set_monitor_opacity(monitor_index: u32, opacity: f32) {
         }
     }
 
-    let alpha = (opacity * 255.0) as u8;
     let windows = OVERLAY_WINDOWS.lock().unwrap();
     for entry in windows.iter() {
         if entry.monitor_index == monitor_index {
             unsafe {
                 let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
-                let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+                apply_level(hwnd, monitor_index, &entry.device, opacity);
             }
         }
     }
     drop(windows);
+    journal_applied();
     reassert_topmost();
 }
 
@@ -462,84 +1248,333 @@ pub fn set_per_monitor_opacities(opacities: &std::collections::HashMap<u32, f32>
     *PER_MONITOR_OPACITY.lock().unwrap() = Some(vec);
 }
 
-/// Get the number of monitors that have overlay windows.
-#[allow(dead_code)]
-pub fn get_monitor_count() -> u32 {
-    OVERLAY_WINDOWS.lock().unwrap().len() as u32
+/// Enter or leave designer mode. While suppressed, `show_overlay` is a
+/// no-op; opacity changes are still remembered for when it's lifted.
+pub fn set_suppressed(suppressed: bool) {
+    SUPPRESSED.store(suppressed, Ordering::SeqCst);
+    if suppressed {
+        hide_overlay();
+    }
 }
 
-/// Enumerate connected monitors and return their count.
-pub fn enumerate_monitor_count() -> u32 {
-    use std::sync::atomic::AtomicU32;
-    static COUNT: AtomicU32 = AtomicU32::new(0);
-    COUNT.store(0, Ordering::SeqCst);
+/// Set the level → opacity curve. Callers re-apply levels afterwards
+/// (stored levels change scale along with the curve).
+pub fn set_curve(curve: OpacityCurve) {
+    *CURVE.lock().unwrap() = curve;
+}
 
-    unsafe extern "system" fn count_proc(
-        _: HMONITOR,
-        _: HDC,
-        _: *mut RECT,
-        _: LPARAM,
-    ) -> windows::core::BOOL {
-        COUNT.fetch_add(1, Ordering::SeqCst);
-        windows::core::BOOL::from(true)
+/// Set the overlay gradient, applied at once. Turning it on or off rebuilds
+/// the overlays, which can't switch between one alpha and per-pixel alpha.
+pub fn set_gradient(gradient: &OverlayGradient) {
+    let was_per_pixel = per_pixel_alpha();
+    *GRADIENT.lock().unwrap() = Some(*gradient);
+    if was_per_pixel != per_pixel_alpha() && is_visible() {
+        let opacity = *CURRENT_OPACITY.lock().unwrap();
+        let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
+        show_overlays(opacity, allow_capture);
+    } else {
+        reapply_levels();
     }
+}
 
-    unsafe {
-        let _ = EnumDisplayMonitors(None, None, Some(count_proc), LPARAM(0));
+/// Set the edge vignette, applied at once. Like the gradient, turning it on
+/// or off may rebuild the overlays.
+pub fn set_edge_vignette(vignette: &EdgeVignette) {
+    let was_per_pixel = per_pixel_alpha();
+    *VIGNETTE.lock().unwrap() = Some(vignette.clone());
+    update_vignettes(&monitors::list());
+    if was_per_pixel != per_pixel_alpha() && is_visible() {
+        let opacity = *CURRENT_OPACITY.lock().unwrap();
+        let allow_capture = *ALLOW_CAPTURE.lock().unwrap();
+        show_overlays(opacity, allow_capture);
+    } else {
+        reapply_levels();
     }
-    COUNT.load(Ordering::SeqCst)
 }
 
-/// Get the monitor index (0-based) that contains the given point (cursor position).
-/// Returns 0 if no match found.
-pub fn get_monitor_index_at_point(x: i32, y: i32) -> u32 {
-    use windows::Win32::Graphics::Gdi::MonitorFromPoint;
-    use windows::Win32::Foundation::POINT;
-    use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
+/// Switch every monitor to gamma while the session is locked, and back to
+/// its configured backend after unlock.
+pub fn set_session_locked(locked: bool) {
+    if SESSION_LOCKED.swap(locked, Ordering::SeqCst) != locked {
+        reapply_levels();
+    }
+}
 
-    let pt = POINT { x, y };
-    let target_monitor = unsafe { MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST) };
+/// Turn recording mode on or off, applied at once.
+pub fn set_recording(on: bool) {
+    if RECORDING.swap(on, Ordering::SeqCst) == on {
+        return;
+    }
+    eprintln!("[overlay] recording mode {}", if on { "on" } else { "off" });
+    reapply_levels();
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    for entry in windows.iter() {
+        let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+        unsafe { apply_capture(hwnd, entry.monitor_index) };
+    }
+}
+
+/// True while recording mode is on
+pub fn recording() -> bool {
+    RECORDING.load(Ordering::SeqCst)
+}
+
+/// Enable or disable zoom compat (gamma dimming while Magnifier is active).
+pub fn set_zoom_compat(enabled: bool) {
+    ZOOM_COMPAT.store(enabled, Ordering::SeqCst);
+    check_magnifier();
+}
+
+/// Set the pen pause rule (the poll thread applies it).
+pub fn set_pen_pause(rule: &PenPause) {
+    *PEN_PAUSE.lock().unwrap() = Some(rule.clone());
+    check_pen();
+}
 
-    // Enumerate monitors and find matching index
-    struct EnumData {
-        target: HMONITOR,
-        found_index: u32,
-        current_index: u32,
+/// Lift every level by `lift` while `window` keeps the focus, on top of the
+/// boosts already on it, and apply at once. Returns the whole lift.
+pub fn push_focus_boost(window: FocusedWindow, lift: f32) -> f32 {
+    let lift = FOCUS_BOOST.lock().unwrap().push(window, lift);
+    reapply_levels();
+    lift
+}
+
+/// Set the night boost rule and apply it at once.
+pub fn set_night_boost(rule: &NightBoost) {
+    *NIGHT_BOOST.lock().unwrap() = Some(rule.clone());
+    check_night_boost();
+}
+
+/// Set the reading monitor preset and apply it at once.
+pub fn set_reading_monitor(rule: &ReadingMonitor) {
+    *READING.lock().unwrap() = Some(rule.clone());
+    update_reading_monitors(&monitors::list());
+    reapply_levels();
+}
+
+/// Cap the level of one monitor (index, cap) for the contrast guard, or
+/// lift the cap, and re-apply if that changed anything.
+pub fn set_contrast_cap(cap: Option<(u32, f32)>) {
+    let changed = std::mem::replace(&mut *CONTRAST_CAP.lock().unwrap(), cap) != cap;
+    if changed {
+        eprintln!("[overlay] contrast cap now {:?}", cap);
+        reapply_levels();
     }
+}
 
-    unsafe extern "system" fn find_proc(
-        hmonitor: HMONITOR,
-        _: HDC,
-        _: *mut RECT,
-        lparam: LPARAM,
-    ) -> windows::core::BOOL {
-        let data = &mut *(lparam.0 as *mut EnumData);
-        if hmonitor == data.target {
-            data.found_index = data.current_index;
-        }
-        data.current_index += 1;
-        windows::core::BOOL::from(true)
+/// None if monitor `monitor_index` isn't dimmed. Otherwise how dark its
+/// overlay makes it look in a screen capture: 0.0 when captures leave the
+/// overlay out or the monitor is dimmed by gamma.
+pub fn capture_veil(monitor_index: u32) -> Option<f32> {
+    if SUPPRESSED.load(Ordering::SeqCst) || opacity_for(monitor_index) <= 0.0 {
+        return None;
+    }
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    let entry = windows.iter().find(|e| e.monitor_index == monitor_index)?;
+    if !allow_capture_for(monitor_index) {
+        return Some(0.0);
     }
+    let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+    let mut alpha = 0u8;
+    let read = unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), None) }.is_ok();
+    Some(if read { alpha as f32 / 255.0 } else { 0.0 })
+}
 
-    let mut data = EnumData {
-        target: target_monitor,
-        found_index: 0,
-        current_index: 0,
-    };
+/// Keep the monitors at `undimmed` (indices) undimmed and re-apply at once.
+pub fn set_brightness_gate(undimmed: &[u32]) {
+    *BRIGHTNESS_GATE.lock().unwrap() = undimmed.to_vec();
+    reapply_levels();
+}
 
-    unsafe {
-        let _ = EnumDisplayMonitors(
-            None,
-            None,
-            Some(find_proc),
-            LPARAM(&mut data as *mut EnumData as isize),
-        );
+/// Chosen level of each dimmed monitor, before night boost and movie mode
+/// ("Why is my screen dark?")
+pub fn monitor_levels() -> Vec<MonitorLevel> {
+    let current = *CURRENT_OPACITY.lock().unwrap();
+    let per_monitor = PER_MONITOR_OPACITY.lock().unwrap().clone();
+    let gate = BRIGHTNESS_GATE.lock().unwrap().clone();
+    let indices: Vec<u32> = OVERLAY_WINDOWS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|entry| entry.monitor_index)
+        .collect();
+    indices
+        .into_iter()
+        .map(|index| {
+            let level = per_monitor
+                .as_ref()
+                .and_then(|map| map.iter().find(|(i, _)| *i == index))
+                .map_or(current, |(_, level)| *level);
+            MonitorLevel {
+                name: monitors::friendly_name(index),
+                level,
+                gated: gate.contains(&index),
+            }
+        })
+        .collect()
+}
+
+/// Boost the night boost rule adds right now, or None
+pub fn night_boost() -> Option<f32> {
+    NIGHT_BOOST.lock().unwrap().as_ref().and_then(|rule| rule.current())
+}
+
+/// Set the dimming method per monitor (applied on the next show/opacity change).
+/// Backend and level offset used for monitors while they run in HDR.
+pub fn set_hdr_adjustments(backend: DimBackend, offset: f32) {
+    *HDR_ADJUST.lock().unwrap() = (backend, offset.clamp(-0.5, 0.5));
+}
+
+/// Window to receive WM_HDR_CHANGED, WM_OVERLAY_LOST and WM_NIGHT_BOOST_CHANGED
+/// notifications.
+pub fn set_notify_window(hwnd: HWND) {
+    NOTIFY_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+}
+
+/// Set the per-monitor capture overrides (applied on the next show).
+pub fn set_capture_overrides(overrides: &std::collections::HashMap<u32, bool>) {
+    *CAPTURE_OVERRIDES.lock().unwrap() = overrides.iter().map(|(k, v)| (*k, *v)).collect();
+}
+
+/// Let one monitor's dimming show up in captures (or not), applied at once.
+pub fn set_monitor_capture(monitor_index: u32, allow: bool) {
+    {
+        let mut overrides = CAPTURE_OVERRIDES.lock().unwrap();
+        overrides.retain(|(idx, _)| *idx != monitor_index);
+        overrides.push((monitor_index, allow));
+    }
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    for entry in windows.iter().filter(|e| e.monitor_index == monitor_index) {
+        unsafe { apply_capture(HWND(entry.hwnd.0 as *mut std::ffi::c_void), monitor_index) };
+    }
+}
+
+/// What dimming can do on this system, for the settings window
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        capture_exclusion: !EXCLUDE_FAILED.load(Ordering::SeqCst),
+        per_monitor: true,
+        warmth: false,
+        regions: false,
+        ddc: !crate::ddcci::monitors().is_empty(),
+        gamma: !gamma::any_refused(),
     }
+}
 
-    data.found_index
+pub fn set_backends(default: DimBackend, overrides: &std::collections::HashMap<u32, DimBackend>) {
+    *BACKENDS.lock().unwrap() = (default, overrides.iter().map(|(k, v)| (*k, *v)).collect());
+}
+
+/// Get the number of monitors that have overlay windows.
+#[allow(dead_code)]
+pub fn get_monitor_count() -> u32 {
+    OVERLAY_WINDOWS.lock().unwrap().len() as u32
 }
 
 /// Check if overlay is visible
 pub fn is_visible() -> bool {
     !OVERLAY_WINDOWS.lock().unwrap().is_empty()
 }
+
+/// The overlay behind the shared `OverlayBackend` (backend.rs): always
+/// shown capture-safe, on whatever backends `set_backends` picked
+pub struct Backend;
+
+impl OverlayBackend for Backend {
+    fn enumerate(&self) -> Vec<DisplayInfo> {
+        monitors::enumerate()
+    }
+
+    fn show(&mut self, level: f32) -> Result<(), String> {
+        show_overlay(level, false);
+        if is_visible() {
+            Ok(())
+        } else {
+            Err("no overlay window was created".to_string())
+        }
+    }
+
+    fn hide(&mut self) {
+        hide_overlay();
+    }
+
+    fn set_opacity(&mut self, level: f32) {
+        set_opacity(level);
+    }
+
+    fn set_monitor_opacity(&mut self, monitor: usize, level: f32) {
+        set_monitor_opacity(monitor as u32, level);
+    }
+
+    /// Chosen level per monitor, 0 for those without a window
+    fn applied(&self) -> Vec<f32> {
+        let current = *CURRENT_OPACITY.lock().unwrap();
+        let per_monitor = PER_MONITOR_OPACITY.lock().unwrap().clone();
+        let windows = OVERLAY_WINDOWS.lock().unwrap();
+        (0..monitors::count())
+            .map(|index| {
+                if !windows.iter().any(|entry| entry.monitor_index == index) {
+                    return 0.0;
+                }
+                per_monitor
+                    .as_ref()
+                    .and_then(|map| map.iter().find(|(i, _)| *i == index))
+                    .map_or(current, |(_, level)| *level)
+            })
+            .collect()
+    }
+
+    fn is_visible(&self) -> bool {
+        is_visible()
+    }
+}
+
+/// What one overlay window is doing right now (`--self-test`)
+pub struct OverlayProbe {
+    pub hwnd: isize,
+    pub monitor_index: u32,
+    pub device: String,
+    pub visible: bool,
+    /// Layered alpha, or None if it couldn't be read
+    pub alpha: Option<u8>,
+    /// Whether the window is excluded from capture, or None if unreadable
+    pub capture_excluded: Option<bool>,
+}
+
+/// Inspect every overlay window.
+pub fn probe() -> Vec<OverlayProbe> {
+    let windows = OVERLAY_WINDOWS.lock().unwrap();
+    windows
+        .iter()
+        .map(|entry| unsafe {
+            let hwnd = HWND(entry.hwnd.0 as *mut std::ffi::c_void);
+            let mut alpha = 0u8;
+            let read = GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), None).is_ok();
+            let mut affinity = 0u32;
+            let affinity_read = GetWindowDisplayAffinity(hwnd, &mut affinity).is_ok();
+            OverlayProbe {
+                hwnd: entry.hwnd.0,
+                monitor_index: entry.monitor_index,
+                device: entry.device.clone(),
+                visible: IsWindowVisible(hwnd).as_bool(),
+                alpha: read.then_some(alpha),
+                capture_excluded: affinity_read.then_some(affinity == WDA_EXCLUDEFROMCAPTURE.0),
+            }
+        })
+        .collect()
+}
+
+/// True while an overlay window handle still exists (`--self-test`)
+pub fn window_exists(hwnd: isize) -> bool {
+    unsafe { IsWindow(Some(HWND(hwnd as *mut std::ffi::c_void))).as_bool() }
+}
+
+/// True while the z-order/watchdog poll thread is alive
+pub fn watchdog_running() -> bool {
+    WATCHDOG_RUNNING.load(Ordering::SeqCst)
+}
+
+/// True while the foreground/desktop-switch event hooks are installed
+pub fn event_hook_installed() -> bool {
+    !EVENT_HOOKS.lock().unwrap().is_empty()
+}
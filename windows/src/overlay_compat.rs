@@ -0,0 +1,190 @@
+// Known other overlay-style apps and how the overlay gets along with them.
+//
+// Screen annotators, other dimmers and magnifiers also keep topmost windows
+// on screen, and each side re-asserting its z-order after the other turns
+// into visible flicker. KNOWN_APPS lists the ones we know about with the
+// way to coexist:
+//   • StayBelow — annotators (ZoomIt, Epic Pen, gInk): their drawing has to
+//     stay readable, so re-assertion puts the overlays just below their
+//     topmost windows instead of at the very top
+//   • NoReassert — other overlay dimmers and magnifiers: their windows
+//     taking the foreground doesn't trigger a re-assertion at all
+//   • GammaOnly — gamma tools (f.lux, LightBulb): no z-order conflict, but
+//     both sides rewrite the same ramps, so it's noted for the gamma backend
+// The poll thread in overlay.rs rescans the top-level windows every few
+// seconds; what was found is listed in the diagnostics report.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetTopWindow, GetWindow, GetWindowLongW, GetWindowThreadProcessId, IsWindowVisible,
+    GWL_EXSTYLE, GW_HWNDNEXT, WS_EX_TOPMOST,
+};
+
+use crate::shell_windows;
+
+/// How the overlay coexists with an app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    StayBelow,
+    NoReassert,
+    GammaOnly,
+}
+
+/// One entry of the compatibility registry
+pub struct KnownApp {
+    pub name: &'static str,
+    /// Lowercase executable names
+    pub processes: &'static [&'static str],
+    pub strategy: Strategy,
+}
+
+pub const KNOWN_APPS: &[KnownApp] = &[
+    KnownApp {
+        name: "ZoomIt",
+        processes: &["zoomit.exe", "zoomit64.exe"],
+        strategy: Strategy::StayBelow,
+    },
+    KnownApp {
+        name: "Epic Pen",
+        processes: &["epicpen.exe"],
+        strategy: Strategy::StayBelow,
+    },
+    KnownApp {
+        name: "gInk",
+        processes: &["gink.exe", "ppink.exe"],
+        strategy: Strategy::StayBelow,
+    },
+    KnownApp {
+        name: "Windows Magnifier",
+        processes: &["magnify.exe"],
+        strategy: Strategy::NoReassert,
+    },
+    KnownApp {
+        name: "Iris",
+        processes: &["iris.exe", "iris mini.exe"],
+        strategy: Strategy::NoReassert,
+    },
+    KnownApp {
+        name: "CareUEyes",
+        processes: &["careueyes.exe"],
+        strategy: Strategy::NoReassert,
+    },
+    KnownApp {
+        name: "PangoBright",
+        processes: &["pangobright.exe"],
+        strategy: Strategy::NoReassert,
+    },
+    KnownApp {
+        name: "f.lux",
+        processes: &["flux.exe"],
+        strategy: Strategy::GammaOnly,
+    },
+    KnownApp {
+        name: "LightBulb",
+        processes: &["lightbulb.exe"],
+        strategy: Strategy::GammaOnly,
+    },
+];
+
+/// What the last scan found
+struct Scan {
+    /// Indices into KNOWN_APPS with a visible window, in registry order
+    apps: Vec<usize>,
+    /// Lowest topmost window of a StayBelow app (the overlays go right
+    /// below it), 0 if none
+    stay_below: isize,
+}
+
+static LAST_SCAN: Mutex<Scan> = Mutex::new(Scan {
+    apps: Vec::new(),
+    stay_below: 0,
+});
+
+/// Registry entry for an executable name
+fn app_for(process: &str) -> Option<usize> {
+    KNOWN_APPS
+        .iter()
+        .position(|app| app.processes.contains(&process))
+}
+
+/// Registry entry of the app owning `hwnd`
+fn app_of(hwnd: HWND) -> Option<usize> {
+    shell_windows::process_name(hwnd).and_then(|name| app_for(&name))
+}
+
+/// Walk the top-level windows (top to bottom) and note the known apps.
+pub fn scan() {
+    let mut apps = Vec::new();
+    let mut stay_below = 0;
+    // One process lookup per process, not per window
+    let mut by_pid: HashMap<u32, Option<usize>> = HashMap::new();
+    unsafe {
+        let mut next = GetTopWindow(None).ok();
+        while let Some(hwnd) = next {
+            next = GetWindow(hwnd, GW_HWNDNEXT).ok();
+            if !IsWindowVisible(hwnd).as_bool() {
+                continue;
+            }
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            let Some(app) = *by_pid.entry(pid).or_insert_with(|| app_of(hwnd)) else {
+                continue;
+            };
+            if !apps.contains(&app) {
+                apps.push(app);
+            }
+            let topmost = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOPMOST.0 != 0;
+            if topmost && KNOWN_APPS[app].strategy == Strategy::StayBelow {
+                stay_below = hwnd.0 as isize;
+            }
+        }
+    }
+    apps.sort_unstable();
+    let mut last = LAST_SCAN.lock().unwrap();
+    if last.apps != apps {
+        let names: Vec<&str> = apps.iter().map(|&i| KNOWN_APPS[i].name).collect();
+        eprintln!("[overlay_compat] overlay apps running: {:?}", names);
+    }
+    *last = Scan { apps, stay_below };
+}
+
+/// Window the overlays should sit right below instead of HWND_TOPMOST
+pub fn insert_after() -> Option<HWND> {
+    let hwnd = LAST_SCAN.lock().unwrap().stay_below;
+    (hwnd != 0).then_some(HWND(hwnd as *mut std::ffi::c_void))
+}
+
+/// True if `hwnd` taking the foreground should not trigger a re-assertion
+pub fn skip_reassert(hwnd: HWND) -> bool {
+    let scan = LAST_SCAN.lock().unwrap();
+    let watched = scan
+        .apps
+        .iter()
+        .any(|&i| KNOWN_APPS[i].strategy == Strategy::NoReassert);
+    drop(scan);
+    watched && app_of(hwnd).is_some_and(|i| KNOWN_APPS[i].strategy == Strategy::NoReassert)
+}
+
+/// Line for the diagnostics report
+pub fn report_line() -> String {
+    let apps = LAST_SCAN.lock().unwrap().apps.clone();
+    if apps.is_empty() {
+        return "Overlay apps: none detected\n".into();
+    }
+    let notes: Vec<String> = apps
+        .iter()
+        .map(|&i| {
+            let app = &KNOWN_APPS[i];
+            let note = match app.strategy {
+                Strategy::StayBelow => "dimming stays below its drawing",
+                Strategy::NoReassert => "z-order not re-asserted against it",
+                Strategy::GammaOnly => "also sets gamma ramps, prefer the overlay backend",
+            };
+            format!("{} ({})", app.name, note)
+        })
+        .collect();
+    format!("Overlay apps: {}\n", notes.join(", "))
+}
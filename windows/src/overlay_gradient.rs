@@ -0,0 +1,136 @@
+// Per-pixel alpha for the overlay gradient and the edge vignette (shared
+// gradient.rs, edge_vignette.rs).
+//
+// SetLayeredWindowAttributes gives a layered window one alpha for every
+// pixel. For a gradient the overlay is drawn with UpdateLayeredWindow
+// instead: a black 32-bit DIB the size of the window, each row carrying its
+// own alpha. The vignette varies along a row as well; its nearness is
+// rounded to NEARNESS_STEPS, so each row needs only that many alpha values
+// however wide the monitor is. A window can't switch between the two once
+// either was used, so turning both off or one on rebuilds the overlays
+// (overlay::set_gradient, overlay::set_edge_vignette).
+
+use windows::Win32::Foundation::{COLORREF, HWND, POINT, RECT, SIZE};
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, SelectObject, AC_SRC_ALPHA,
+    AC_SRC_OVER, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, BLENDFUNCTION, DIB_RGB_COLORS, HGDIOBJ,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowRect, UpdateLayeredWindow, ULW_ALPHA};
+
+use savemyeyes_shared::edge_vignette::{Edge, VignetteShape};
+
+/// Distinct vignette nearness values per row
+const NEARNESS_STEPS: usize = 64;
+
+/// Paint `hwnd` black with the alpha `alpha_at(position, nearness)` on
+/// every pixel, position running from 0.0 at the top edge to 1.0 at the
+/// bottom and nearness from `vignette` (always 0.0 without one).
+pub unsafe fn paint(
+    hwnd: HWND,
+    vignette: Option<&VignetteShape>,
+    alpha_at: impl Fn(f32, f32) -> u8,
+) {
+    let mut rect = RECT::default();
+    if GetWindowRect(hwnd, &mut rect).is_err() {
+        return;
+    }
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+        return;
+    }
+
+    let bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height, // top-down
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mem_dc = CreateCompatibleDC(None);
+    let mut bits: *mut std::ffi::c_void = std::ptr::null_mut();
+    match CreateDIBSection(Some(mem_dc), &bmi, DIB_RGB_COLORS, &mut bits, None, 0) {
+        Ok(dib) => {
+            let old = SelectObject(mem_dc, HGDIOBJ::from(dib));
+            let pixels =
+                std::slice::from_raw_parts_mut(bits as *mut u32, (width * height) as usize);
+            fill(pixels, width, height, vignette, alpha_at);
+
+            let origin = POINT { x: 0, y: 0 };
+            let position = POINT {
+                x: rect.left,
+                y: rect.top,
+            };
+            let size = SIZE {
+                cx: width,
+                cy: height,
+            };
+            let blend = BLENDFUNCTION {
+                BlendOp: AC_SRC_OVER as u8,
+                BlendFlags: 0,
+                SourceConstantAlpha: 255,
+                AlphaFormat: AC_SRC_ALPHA as u8,
+            };
+            if let Err(e) = UpdateLayeredWindow(
+                hwnd,
+                None,
+                Some(&position),
+                Some(&size),
+                Some(mem_dc),
+                Some(&origin),
+                COLORREF(0),
+                Some(&blend),
+                ULW_ALPHA,
+            ) {
+                eprintln!("[overlay_gradient] UpdateLayeredWindow failed: {}", e);
+            }
+            SelectObject(mem_dc, old);
+            let _ = DeleteObject(HGDIOBJ::from(dib));
+        }
+        Err(e) => eprintln!("[overlay_gradient] CreateDIBSection failed: {}", e),
+    }
+    let _ = DeleteDC(mem_dc);
+}
+
+/// Fill the top-down `pixels` of a `width` x `height` window
+fn fill(
+    pixels: &mut [u32],
+    width: i32,
+    height: i32,
+    vignette: Option<&VignetteShape>,
+    alpha_at: impl Fn(f32, f32) -> u8,
+) {
+    // Black premultiplied by any alpha is still 0 in every channel
+    let pixel = |alpha: u8| (alpha as u32) << 24;
+    let last_row = (height - 1).max(1) as f32;
+    let rows = pixels.chunks_exact_mut(width as usize).enumerate();
+    let Some(vignette) = vignette else {
+        for (row, line) in rows {
+            line.fill(pixel(alpha_at(row as f32 / last_row, 0.0)));
+        }
+        return;
+    };
+
+    let band = vignette.band_px(width, height);
+    let near = |edge: Edge, distance: i32| vignette.nearness(edge, distance as f32, band);
+    let step = |nearness: f32| (nearness * NEARNESS_STEPS as f32).round() as usize;
+    let columns: Vec<usize> = (0..width)
+        .map(|x| step(near(Edge::Left, x).max(near(Edge::Right, width - 1 - x))))
+        .collect();
+    for (row, line) in rows {
+        let position = row as f32 / last_row;
+        let row = row as i32;
+        let row_near = near(Edge::Top, row).max(near(Edge::Bottom, height - 1 - row));
+        let table: [u32; NEARNESS_STEPS + 1] = std::array::from_fn(|k| {
+            let nearness = (k as f32 / NEARNESS_STEPS as f32).max(row_near);
+            pixel(alpha_at(position, nearness))
+        });
+        for (out, column) in line.iter_mut().zip(&columns) {
+            *out = table[*column];
+        }
+    }
+}
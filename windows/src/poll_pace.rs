@@ -0,0 +1,121 @@
+// How often the overlay poll thread wakes up.
+//
+// The poll thread in overlay.rs debounces z-order re-assertion, watches for
+// shell windows closing and pen input, and runs the watchdog. At a fixed
+// 200 ms with a 5 s watchdog it kept waking a laptop's CPU whether or not
+// anything was happening. It now runs fast only while it has something to
+// do:
+//   • fast (200 ms polls, watchdog every 5 s) for a while after window
+//     management activity: a foreground change, a desktop switch, pen input,
+//     the overlays being shown or hidden. The while is ACTIVE_MS on AC power
+//     and the shorter BATTERY_ACTIVE_MS on battery, just long enough for a
+//     debounced re-assertion to go through
+//   • fast while work is pending (a re-assertion waiting out its debounce,
+//     a shell window or screen clip open)
+//   • idle (1 s polls, watchdog every 30 s) otherwise
+// Activity also wakes a sleeping thread right away, so going idle never
+// delays the first reaction, only the housekeeping in between.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use savemyeyes_shared::time_change;
+#[cfg(windows)]
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+const FAST_POLL_MS: u64 = 200;
+const IDLE_POLL_MS: u64 = 1000;
+const FAST_WATCHDOG_MS: u64 = 5_000;
+const IDLE_WATCHDOG_MS: u64 = 30_000;
+/// How long the thread stays fast after activity, on AC and on battery
+const ACTIVE_MS: u64 = 10_000;
+const BATTERY_ACTIVE_MS: u64 = 2_000;
+
+/// `time_change::monotonic_ms` of the last activity (0 = none yet)
+static LAST_ACTIVITY: AtomicU64 = AtomicU64::new(0);
+
+/// Set by `activity`, cleared when the thread wakes
+static WOKEN: Mutex<bool> = Mutex::new(false);
+static WAKE: Condvar = Condvar::new();
+
+/// True while running on battery (false if the power status is unknown)
+#[cfg(windows)]
+pub fn on_battery() -> bool {
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status).is_ok() && status.ACLineStatus == 0 }
+}
+
+/// Headless builds: always on AC power
+#[cfg(not(windows))]
+pub fn on_battery() -> bool {
+    false
+}
+
+/// Window management activity: poll fast for a while, starting now.
+pub fn activity() {
+    LAST_ACTIVITY.store(time_change::monotonic_ms(), Ordering::SeqCst);
+    *WOKEN.lock().unwrap() = true;
+    WAKE.notify_all();
+}
+
+/// True if the thread should poll fast. `busy` is pending work.
+pub fn is_fast(busy: bool) -> bool {
+    if busy {
+        return true;
+    }
+    let since = time_change::monotonic_ms().saturating_sub(LAST_ACTIVITY.load(Ordering::SeqCst));
+    since < BATTERY_ACTIVE_MS || (since < ACTIVE_MS && !on_battery())
+}
+
+/// Sleep one poll interval, or until `activity` is called.
+pub fn wait(fast: bool) {
+    let interval = Duration::from_millis(if fast { FAST_POLL_MS } else { IDLE_POLL_MS });
+    let woken = WOKEN.lock().unwrap();
+    let (mut woken, _) = WAKE
+        .wait_timeout_while(woken, interval, |woken| !*woken)
+        .unwrap();
+    *woken = false;
+}
+
+/// True if the watchdog is due, given when it last ran
+pub fn watchdog_due(last: Instant, fast: bool) -> bool {
+    let interval = if fast {
+        FAST_WATCHDOG_MS
+    } else {
+        IDLE_WATCHDOG_MS
+    };
+    last.elapsed() >= Duration::from_millis(interval)
+}
+
+/// Line for the diagnostics report
+pub fn report_line() -> String {
+    format!(
+        "Overlay polling: {}{}\n",
+        if is_fast(false) { "fast" } else { "idle" },
+        if on_battery() { " (on battery)" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watchdog_waits_longer_while_idle() {
+        let last = Instant::now() - Duration::from_millis(FAST_WATCHDOG_MS + 100);
+        assert!(watchdog_due(last, true));
+        assert!(!watchdog_due(last, false));
+        assert!(!watchdog_due(Instant::now(), true));
+    }
+
+    #[test]
+    fn pending_work_or_activity_polls_fast() {
+        assert!(is_fast(true));
+        activity();
+        assert!(is_fast(false));
+        // activity() left the wake flag set, so this returns at once
+        wait(false);
+        assert!(!*WOKEN.lock().unwrap());
+    }
+}
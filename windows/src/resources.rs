@@ -0,0 +1,115 @@
+// GDI / USER resource accounting for long uptimes.
+//
+// Overlay windows are counted as they're created and destroyed (a failed
+// DestroyWindow is a leaked USER object, so only successful ones count).
+// The process-wide GDI and USER handle counts from GetGuiResources go into
+// the diagnostics report, so a slow leak shows up there after days of
+// show/hide cycles. Debug builds assert balance where it must hold: no
+// overlay windows left after hide, and no GDI objects gained by a paint.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(windows)]
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetGuiResources, GR_GDIOBJECTS, GR_USEROBJECTS,
+};
+
+/// Created/destroyed tally for one kind of resource
+pub struct Counter {
+    created: AtomicU64,
+    destroyed: AtomicU64,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Self {
+            created: AtomicU64::new(0),
+            destroyed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn created(&self) {
+        self.created.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn destroyed(&self) {
+        self.destroyed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Created and not yet destroyed
+    pub fn live(&self) -> u64 {
+        self.created
+            .load(Ordering::SeqCst)
+            .saturating_sub(self.destroyed.load(Ordering::SeqCst))
+    }
+
+    pub fn total(&self) -> u64 {
+        self.created.load(Ordering::SeqCst)
+    }
+}
+
+/// Overlay windows (one per monitor while dimming)
+pub static OVERLAY_WINDOWS: Counter = Counter::new();
+
+/// GDI objects held by this process
+#[cfg(windows)]
+pub fn gdi_objects() -> u32 {
+    unsafe { GetGuiResources(GetCurrentProcess(), GR_GDIOBJECTS) }
+}
+
+/// USER objects held by this process
+#[cfg(windows)]
+pub fn user_objects() -> u32 {
+    unsafe { GetGuiResources(GetCurrentProcess(), GR_USEROBJECTS) }
+}
+
+/// Headless builds hold no GDI objects
+#[cfg(not(windows))]
+pub fn gdi_objects() -> u32 {
+    0
+}
+
+/// Headless builds hold no USER objects
+#[cfg(not(windows))]
+pub fn user_objects() -> u32 {
+    0
+}
+
+/// Debug builds: `what` must not have changed the GDI object count
+/// (`before` taken with `gdi_objects()` when it started).
+pub fn debug_check_gdi(what: &str, before: u32) {
+    if cfg!(debug_assertions) {
+        let after = gdi_objects();
+        debug_assert_eq!(before, after, "[resources] {} leaked GDI objects", what);
+    }
+}
+
+/// Line for the diagnostics report
+pub fn report_line() -> String {
+    format!(
+        "Handles: {} GDI, {} USER; overlay windows: {} live, {} created since start\n",
+        gdi_objects(),
+        user_objects(),
+        OVERLAY_WINDOWS.live(),
+        OVERLAY_WINDOWS.total()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_tracks_live_resources() {
+        let counter = Counter::new();
+        counter.created();
+        counter.created();
+        counter.destroyed();
+        assert_eq!(counter.live(), 1);
+        assert_eq!(counter.total(), 2);
+        // A stray destroy never wraps around
+        counter.destroyed();
+        counter.destroyed();
+        assert_eq!(counter.live(), 0);
+    }
+}
@@ -0,0 +1,97 @@
+// Scheduled dimming (shared scheduler.rs) on Windows.
+//
+// The schedule thread posts each change to the settings window, whose
+// window procedure carries it out on the UI thread through the external
+// command path (events.rs), so it turns dimming on and off exactly like the
+// toggle hotkey and a level command would. Following the sun, the OS
+// location comes from location.rs, and each fade step is a level command.
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_APP};
+
+use savemyeyes_shared::events::Command;
+use savemyeyes_shared::explain::{self, Cause};
+use savemyeyes_shared::scheduler::{self, DimmingSchedule};
+
+use crate::config::{self, AppConfig};
+use crate::{events, location};
+
+/// Posted to the settings window: wparam 1 = on at the f32 level in the low
+/// bits of lparam, 0 = off
+pub const WM_SCHEDULE: u32 = WM_APP + 17;
+
+/// Settings window that receives WM_SCHEDULE
+static NOTIFY_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// Post schedule changes to `hwnd` and apply the rule from config.
+pub fn start(hwnd: HWND, rule: &DimmingSchedule) {
+    NOTIFY_WINDOW.store(hwnd.0 as isize, Ordering::SeqCst);
+    scheduler::set_handler(post);
+    scheduler::set_locator(location::locate);
+    scheduler::apply(rule);
+}
+
+/// Schedule thread: hand the change to the UI thread
+fn post(level: Option<f32>) {
+    let raw = NOTIFY_WINDOW.load(Ordering::SeqCst);
+    if raw == 0 {
+        return;
+    }
+    let bits = level.map_or(0, f32::to_bits);
+    unsafe {
+        let _ = PostMessageW(
+            Some(HWND(raw as *mut _)),
+            WM_SCHEDULE,
+            WPARAM(level.is_some() as usize),
+            LPARAM(bits as isize),
+        );
+    }
+}
+
+/// The level a WM_SCHEDULE message carries (None = dimming off)
+pub fn level_from(wparam: WPARAM, lparam: LPARAM) -> Option<f32> {
+    (wparam.0 != 0).then(|| f32::from_bits(lparam.0 as u32))
+}
+
+/// Carry out a schedule change (UI thread). Returns the toast message.
+pub fn run(hwnd: HWND, config: &Arc<Mutex<AppConfig>>, level: Option<f32>) -> Option<String> {
+    let (designer_mode, multi_monitor, was_enabled) = {
+        let cfg = config.lock().unwrap();
+        (cfg.designer_mode, cfg.multi_monitor, cfg.is_enabled)
+    };
+    if designer_mode {
+        return None;
+    }
+    explain::note(Cause::Schedule);
+    let command = match level {
+        // Per-monitor levels stay as they are
+        Some(_) if multi_monitor => Command::SetEnabled { enabled: true },
+        Some(level) => Command::SetLevel { level },
+        None => Command::SetEnabled { enabled: false },
+    };
+    events::run(hwnd, config, command);
+    if level.is_none() {
+        // A fade to off leaves its last step as the level to turn back on
+        // at; make it the scheduled level again
+        let mut cfg = config.lock().unwrap();
+        if cfg.schedule.follow_sun && !cfg.multi_monitor {
+            cfg.last_opacity = cfg.schedule.level;
+            config::save_config(&cfg);
+        }
+    }
+    // Already as scheduled (e.g. at startup): nothing to announce
+    if was_enabled == level.is_some() {
+        return None;
+    }
+    Some(
+        if level.is_some() {
+            "Scheduled dimming on"
+        } else {
+            "Scheduled dimming off"
+        }
+        .to_string(),
+    )
+}
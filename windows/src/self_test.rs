@@ -0,0 +1,341 @@
+// `--self-test`: drive the real overlay and gamma backends and check the result.
+//
+// Runs in place of the app (no tray, settings or hotkeys) and touches
+// neither config.json nor the crash journal. The script:
+//   0. the shared script       → show, adjust, adjust one monitor and hide
+//                                through overlay::Backend (shared backend.rs)
+//   1. show the overlay at 30% → one visible window per monitor at that alpha
+//   2. adjust to 60%           → every window follows
+//   3. break the overlays      → debug builds only (faults.rs): a window
+//                                destroyed from outside, display affinity
+//                                failing and a monitor removed each end with
+//                                one live window per monitor at 60%
+//   4. switch to gamma at 30%  → ramps read back dimmed (skipped per monitor
+//                                if the driver refuses, as the app falls back)
+//   5. toggle off              → windows destroyed, event hooks removed,
+//                                original ramps back, poll thread gone
+// Meant for a build machine with an interactive desktop; don't run it
+// while the app itself is dimming the screen.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, PeekMessageW, RegisterClassW,
+    TranslateMessage, HWND_MESSAGE, MSG, PM_REMOVE, WINDOW_EX_STYLE, WINDOW_STYLE, WNDCLASSW,
+};
+
+use savemyeyes_shared::backend;
+use savemyeyes_shared::dimming::{self, OpacityCurve};
+use savemyeyes_shared::journal;
+use savemyeyes_shared::self_test::{self, Report, FIRST_LEVEL, SECOND_LEVEL};
+
+use crate::config::DimBackend;
+use crate::faults;
+use crate::gamma::{self, GammaRamp};
+use crate::monitors;
+use crate::overlay::{self, OverlayProbe};
+use crate::resources;
+
+/// Time given to the window manager after each step
+const SETTLE_MS: u64 = 300;
+/// The poll thread notices an empty overlay list within one 200ms tick
+const THREAD_EXIT_MS: u64 = 1000;
+/// The watchdog runs within one tick once asked, then the rebuild is posted
+const RECOVER_MS: u64 = 1000;
+
+const NOTIFY_CLASS_NAME: &str = "SaveMyEyesSelfTest\0";
+
+/// Run the self-test if `--self-test` was passed. Returns the process exit
+/// code, or None to start the app normally.
+pub fn run_from_args() -> Option<i32> {
+    self_test::requested().then(run)
+}
+
+fn run() -> i32 {
+    journal::disable();
+    let mut report = Report::new();
+
+    let monitors = monitors::count();
+    if !report.check("monitors detected", monitors > 0, format!("{} monitor(s)", monitors)) {
+        return report.finish();
+    }
+
+    overlay::set_curve(OpacityCurve::Linear);
+    overlay::set_backends(DimBackend::Overlay, &HashMap::new());
+
+    // 0. Shared script
+    backend::self_test(&mut overlay::Backend, &mut report);
+    settle(SETTLE_MS);
+
+    // 1. Show
+    overlay::show_overlay(FIRST_LEVEL, false);
+    settle(SETTLE_MS);
+    let windows = overlay::probe();
+    report.check(
+        "show: one overlay per monitor",
+        windows.len() as u32 == monitors,
+        format!("{} window(s), {} monitor(s)", windows.len(), monitors),
+    );
+    check_overlay_levels(&mut report, "show", &windows, FIRST_LEVEL);
+
+    // 2. Adjust
+    overlay::set_opacity(SECOND_LEVEL);
+    settle(SETTLE_MS);
+    check_overlay_levels(&mut report, "adjust", &overlay::probe(), SECOND_LEVEL);
+
+    // 3. Recovery
+    if faults::AVAILABLE {
+        let exclusion_works = windows.iter().all(|w| w.capture_excluded == Some(true));
+        check_recovery(&mut report, SECOND_LEVEL, exclusion_works);
+    } else {
+        report.skip("recovery", "faults can only be injected in debug builds");
+    }
+
+    // 4. Gamma backend (original ramps saved first for the cleanup check)
+    let originals: Vec<(String, Option<GammaRamp>)> = windows
+        .iter()
+        .map(|w| (w.device.clone(), gamma::read(&w.device)))
+        .collect();
+    overlay::set_backends(DimBackend::Gamma, &HashMap::new());
+    overlay::set_opacity(FIRST_LEVEL);
+    settle(SETTLE_MS);
+    for window in overlay::probe() {
+        let name = format!("gamma: {}", window.device);
+        if window.alpha == Some(0) {
+            report.check(
+                &name,
+                gamma::is_dimmed_to(&window.device, FIRST_LEVEL),
+                "ramp read back",
+            );
+        } else {
+            report.skip(&name, "driver refused the ramp");
+            check_overlay_levels(&mut report, "gamma fallback", &[window], FIRST_LEVEL);
+        }
+    }
+
+    // 5. Toggle off
+    let handles: Vec<isize> = overlay::probe().iter().map(|w| w.hwnd).collect();
+    overlay::hide_overlay();
+    settle(SETTLE_MS);
+    report.check(
+        "off: overlay list empty",
+        !overlay::is_visible(),
+        format!("{} window(s) left", overlay::probe().len()),
+    );
+    let alive = handles.iter().filter(|h| overlay::window_exists(**h)).count();
+    report.check(
+        "off: windows destroyed",
+        alive == 0,
+        format!("{} of {} still exist", alive, handles.len()),
+    );
+    report.check(
+        "off: event hooks removed",
+        !overlay::event_hook_installed(),
+        "SetWinEventHook",
+    );
+    for (device, original) in &originals {
+        let name = format!("off: {} ramp restored", device);
+        match original {
+            Some(ramp) => {
+                report.check(&name, gamma::shows(device, ramp), "compared with ramp before test");
+            }
+            None => report.skip(&name, "ramp unreadable"),
+        }
+    }
+    let deadline = Instant::now() + Duration::from_millis(THREAD_EXIT_MS);
+    while overlay::watchdog_running() && Instant::now() < deadline {
+        settle(50);
+    }
+    report.check(
+        "off: poll thread exited",
+        !overlay::watchdog_running(),
+        format!("within {}ms", THREAD_EXIT_MS),
+    );
+
+    report.finish()
+}
+
+/// Break the overlays the ways their recovery paths handle, and check each
+/// time that they come back to one window per monitor at `level`.
+fn check_recovery(report: &mut Report, level: f32, exclusion_works: bool) {
+    // Destroyed from outside: the watchdog notices and posts WM_OVERLAY_LOST,
+    // which the notify window answers like the settings window does
+    let notify = create_notify_window();
+    let victim = overlay::probe().first().map(|w| w.hwnd);
+    match (notify, victim) {
+        (Some(notify), Some(victim)) => {
+            overlay::set_notify_window(notify);
+            report.check(
+                "watchdog: window destroyed from outside",
+                faults::destroy_window(victim),
+                "DestroyWindow",
+            );
+            faults::run_watchdog_now();
+            let deadline = Instant::now() + Duration::from_millis(RECOVER_MS);
+            let all_alive = || overlay::probe().iter().all(|w| overlay::window_exists(w.hwnd));
+            while !all_alive() && Instant::now() < deadline {
+                settle(50);
+            }
+            settle(SETTLE_MS);
+            check_window_set(report, "watchdog", level);
+            overlay::set_notify_window(HWND::default());
+            unsafe {
+                let _ = DestroyWindow(notify);
+            }
+        }
+        _ => report.skip("watchdog", "no overlay or notify window"),
+    }
+
+    // Display affinity failing: the overlays still dim, only capture
+    // exclusion is lost, and the next show excludes them again
+    faults::fail_display_affinity(true);
+    overlay::show_overlay(level, false);
+    settle(SETTLE_MS);
+    check_window_set(report, "affinity failing", level);
+    faults::fail_display_affinity(false);
+    overlay::show_overlay(level, false);
+    settle(SETTLE_MS);
+    let windows = overlay::probe();
+    if exclusion_works {
+        let excluded = windows
+            .iter()
+            .filter(|w| w.capture_excluded == Some(true))
+            .count();
+        report.check(
+            "affinity restored: excluded from capture",
+            excluded == windows.len(),
+            format!("{} of {} window(s)", excluded, windows.len()),
+        );
+    } else {
+        report.skip(
+            "affinity restored: excluded from capture",
+            "capture exclusion unsupported here",
+        );
+    }
+
+    // Monitor removed and plugged back in: refit (WM_DISPLAYCHANGE) rebuilds
+    let monitors = monitors::count() as usize;
+    if monitors < 2 {
+        report.skip("monitor removed", "needs two monitors");
+        return;
+    }
+    faults::remove_monitors(1);
+    overlay::refit();
+    settle(SETTLE_MS);
+    report.check(
+        "monitor removed: its overlay is gone",
+        overlay::probe().len() == monitors - 1,
+        format!("{} window(s), {} monitor(s) left", overlay::probe().len(), monitors - 1),
+    );
+    check_window_set(report, "monitor removed", level);
+    faults::remove_monitors(0);
+    overlay::refit();
+    settle(SETTLE_MS);
+    check_window_set(report, "monitor back", level);
+}
+
+/// Exactly one live overlay per monitor, in monitor order and at `level`,
+/// with none left over from before.
+fn check_window_set(report: &mut Report, step: &str, level: f32) {
+    let monitors = monitors::list();
+    let windows = overlay::probe();
+    let in_order = windows.len() == monitors.len()
+        && windows
+            .iter()
+            .zip(&monitors)
+            .enumerate()
+            .all(|(index, (w, m))| w.monitor_index == index as u32 && w.device == m.device);
+    report.check(
+        &format!("{}: one overlay per monitor", step),
+        in_order,
+        format!("{} window(s), {} monitor(s)", windows.len(), monitors.len()),
+    );
+    let alive = windows.iter().filter(|w| overlay::window_exists(w.hwnd)).count();
+    let live = resources::OVERLAY_WINDOWS.live();
+    report.check(
+        &format!("{}: no lost or leaked windows", step),
+        alive == windows.len() && live == alive as u64,
+        format!("{} of {} alive, {} counted live", alive, windows.len(), live),
+    );
+    check_overlay_levels(report, step, &windows, level);
+}
+
+/// Every window visible with the alpha `level` maps to (±1 for rounding).
+fn check_overlay_levels(report: &mut Report, step: &str, windows: &[OverlayProbe], level: f32) {
+    let expected = dimming::alpha(OpacityCurve::Linear.to_applied(level));
+    for window in windows {
+        let name = format!("{}: monitor {} ({})", step, window.monitor_index, window.device);
+        let ok = window.visible && window.alpha.is_some_and(|a| a.abs_diff(expected) <= 1);
+        report.check(
+            &name,
+            ok,
+            format!(
+                "visible {}, alpha {:?}, expected {}",
+                window.visible, window.alpha, expected
+            ),
+        );
+    }
+}
+
+/// Pump messages for `ms` so window creation and z-order changes complete.
+fn settle(ms: u64) {
+    let deadline = Instant::now() + Duration::from_millis(ms);
+    let mut msg = MSG::default();
+    while Instant::now() < deadline {
+        unsafe {
+            while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Stand-in for the settings window: rebuilds the overlays on WM_OVERLAY_LOST.
+unsafe extern "system" fn notify_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == overlay::WM_OVERLAY_LOST {
+        overlay::recreate();
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Message-only window for the overlay notifications.
+fn create_notify_window() -> Option<HWND> {
+    unsafe {
+        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+        let class_name: Vec<u16> = NOTIFY_CLASS_NAME.encode_utf16().collect();
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(notify_proc),
+            hInstance: hinstance.into(),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR::null(),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            Some(hinstance.into()),
+            None,
+        )
+        .ok()
+    }
+}
@@ -0,0 +1,130 @@
+// Identification of shell "experience" windows.
+//
+// Start menu, search, Task View and the notification center live in a
+// z-band above regular topmost windows. Fighting them with re-asserts only
+// causes flashes, so the overlay waits until they close (see overlay.rs).
+// The Win+Shift+S screen clip layer is recognised here too: it dims the
+// screen itself, so the overlay steps aside while it is up.
+
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONULL,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetWindowRect, GetWindowThreadProcessId,
+};
+
+/// Window classes that are always shell surfaces (Task View / Alt+Tab).
+const SHELL_CLASSES: &[&str] = &[
+    "MultitaskingViewFrame",
+    "XamlExplorerHostIslandWindow",
+    "TaskSwitcherWnd",
+    "ForegroundStaging",
+];
+
+/// Hosts of CoreWindow-based shell surfaces (Start, search, notification center).
+const SHELL_HOSTS: &[&str] = &[
+    "startmenuexperiencehost.exe",
+    "searchhost.exe",
+    "searchapp.exe",
+    "shellexperiencehost.exe",
+];
+
+/// Host of the Windows 10 screen clip layer (every window it shows is one).
+const CLIP_HOST: &str = "screenclippinghost.exe";
+
+/// Windows 11 Snipping Tool, which shows the clip layer as a window covering
+/// the whole monitor next to its regular app window.
+const SNIPPING_TOOL: &str = "snippingtool.exe";
+
+/// Window class name of `hwnd`.
+pub fn class_name(hwnd: HWND) -> String {
+    let mut buf = [0u16; 256];
+    let len = unsafe { GetClassNameW(hwnd, &mut buf) };
+    String::from_utf16_lossy(&buf[..len.max(0) as usize])
+}
+
+/// Lowercase executable file name of the process owning `hwnd`.
+pub fn process_name(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 260];
+        let mut size = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buf[..size as usize]);
+        let name = path.rsplit('\\').next().unwrap_or(&path).to_lowercase();
+        Some(name)
+    }
+}
+
+/// True if `hwnd` is a shell experience surface that draws above the overlay.
+pub fn is_shell_experience(hwnd: HWND) -> bool {
+    if hwnd.is_invalid() {
+        return false;
+    }
+    let class = class_name(hwnd);
+    if SHELL_CLASSES.contains(&class.as_str()) {
+        return true;
+    }
+    if class == "Windows.UI.Core.CoreWindow" {
+        if let Some(name) = process_name(hwnd) {
+            return SHELL_HOSTS.contains(&name.as_str());
+        }
+    }
+    false
+}
+
+/// True if `hwnd` is the Win+Shift+S region selection layer.
+pub fn is_screen_clip(hwnd: HWND) -> bool {
+    if hwnd.is_invalid() {
+        return false;
+    }
+    match process_name(hwnd).as_deref() {
+        Some(CLIP_HOST) => true,
+        Some(SNIPPING_TOOL) => covers_monitor(hwnd),
+        _ => false,
+    }
+}
+
+/// True if `hwnd` covers the whole monitor it is on.
+fn covers_monitor(hwnd: HWND) -> bool {
+    unsafe {
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL);
+        if monitor.is_invalid() {
+            return false;
+        }
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        let screen = info.rcMonitor;
+        rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom
+    }
+}
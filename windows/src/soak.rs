@@ -0,0 +1,118 @@
+// `--fuzz-soak` (shared soak.rs) on Windows.
+//
+// main.rs starts the app as usual on scratch settings and hands the settings
+// window to `start`. A background thread then sends the random commands with
+// events::forward, i.e. WM_COPYDATA to that window, which is how the jump
+// list and the browser bridge reach a running instance. Each one is handled
+// before forward returns, so the checks after it see its result, while the
+// overlay poll, brightness and pause timers keep running in between. At the
+// end the thread picks Quit from the tray menu, and main exits with the
+// result once the normal shutdown has run.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_COMMAND};
+
+use savemyeyes_shared::profiles;
+use savemyeyes_shared::self_test::Report;
+use savemyeyes_shared::shutdown;
+use savemyeyes_shared::soak::{self, Options, Rng};
+
+use crate::config::AppConfig;
+use crate::events;
+use crate::monitors;
+use crate::overlay;
+use crate::tray;
+
+/// Time the overlays get to settle after a monitor count mismatch
+const SETTLE: Duration = Duration::from_millis(500);
+/// Progress line every this many commands
+const PROGRESS_EVERY: u64 = 500;
+
+/// Exit code of a finished run; -1 while none finished
+static EXIT_CODE: AtomicI32 = AtomicI32::new(-1);
+
+/// Start the soak run against the settings window `hwnd`.
+pub fn start(hwnd: HWND, config: Arc<Mutex<AppConfig>>, options: Options) {
+    let raw = hwnd.0 as isize;
+    shutdown::spawn("fuzz-soak", move || run(raw, config, options));
+}
+
+/// Process exit code once a run has finished
+pub fn exit_code() -> Option<i32> {
+    Some(EXIT_CODE.load(Ordering::SeqCst)).filter(|code| *code >= 0)
+}
+
+fn run(hwnd: isize, config: Arc<Mutex<AppConfig>>, options: Options) {
+    println!(
+        "[soak] {} min, seed {} ({})",
+        options.duration.as_secs() / 60,
+        options.seed,
+        soak::dir().display()
+    );
+    let mut rng = Rng::new(options.seed);
+    let mut report = Report::quiet();
+    let deadline = Instant::now() + options.duration;
+    let mut sent = 0u64;
+
+    while Instant::now() < deadline {
+        let names: Vec<String> = profiles::listed(&config.lock().unwrap())
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        let command = soak::random_command(&mut rng, monitors::count(), &names);
+        report.check(
+            "command delivered",
+            events::forward(&command),
+            format!("{:?}", command),
+        );
+        sent += 1;
+        if !shutdown::sleep(rng.gap()) {
+            // Quit from the tray: report what ran so far
+            break;
+        }
+
+        let snapshot = config.lock().unwrap().clone();
+        soak::check_levels(&mut report, "memory", &snapshot);
+        check_overlays(&mut report);
+        soak::check_files(&mut report);
+        if sent.is_multiple_of(PROGRESS_EVERY) {
+            println!("[soak] {} commands", sent);
+        }
+    }
+
+    println!("[soak] {} commands sent", sent);
+    EXIT_CODE.store(report.finish(), Ordering::SeqCst);
+    unsafe {
+        let _ = PostMessageW(
+            Some(HWND(hwnd as *mut std::ffi::c_void)),
+            WM_COMMAND,
+            WPARAM(tray::IDM_QUIT as usize),
+            LPARAM(0),
+        );
+    }
+}
+
+/// While dimming shows, one overlay window per monitor. A rebuild in
+/// progress gets a moment to finish before it counts as a failure.
+fn check_overlays(report: &mut Report) {
+    let settled = Instant::now() + SETTLE;
+    loop {
+        if !overlay::is_visible() {
+            return;
+        }
+        let (windows, monitors) = (overlay::probe().len() as u32, monitors::count());
+        if windows == monitors || Instant::now() >= settled {
+            report.check(
+                "one overlay per monitor",
+                windows == monitors,
+                format!("{} window(s), {} monitor(s)", windows, monitors),
+            );
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,84 @@
+// Win32 data types used by the modules that build on any OS.
+//
+// On Windows these are the `windows` crate's own types. Built elsewhere
+// (feature "headless", see main.rs) they are stand-ins with the same names,
+// layout and values, so hit-testing, the palette and the hotkey key mapping
+// compile and run their tests on the CI host. Only plain data lives here;
+// anything that calls into Windows stays behind cfg(windows) in its module.
+
+#[cfg(windows)]
+pub use windows::Win32::Foundation::{COLORREF, RECT};
+#[cfg(windows)]
+pub use windows::Win32::Globalization::{LOCALE_SDECIMAL, LOCALE_STIMEFORMAT};
+#[cfg(windows)]
+pub use windows::Win32::UI::Input::KeyboardAndMouse::{
+    HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, VK_BACK, VK_CONTROL,
+    VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_F24, VK_HOME, VK_INSERT, VK_LCONTROL, VK_LEFT,
+    VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_NEXT, VK_OEM_COMMA, VK_OEM_PERIOD, VK_PRIOR,
+    VK_RCONTROL, VK_RETURN, VK_RIGHT, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_SPACE, VK_TAB,
+    VK_UP,
+};
+
+#[cfg(not(windows))]
+pub use headless::*;
+
+#[cfg(not(windows))]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+mod headless {
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct RECT {
+        pub left: i32,
+        pub top: i32,
+        pub right: i32,
+        pub bottom: i32,
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct COLORREF(pub u32);
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct HOT_KEY_MODIFIERS(pub u32);
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct VIRTUAL_KEY(pub u16);
+
+    pub const LOCALE_SDECIMAL: u32 = 0x000E;
+    pub const LOCALE_STIMEFORMAT: u32 = 0x1003;
+
+    pub const MOD_ALT: HOT_KEY_MODIFIERS = HOT_KEY_MODIFIERS(0x0001);
+    pub const MOD_CONTROL: HOT_KEY_MODIFIERS = HOT_KEY_MODIFIERS(0x0002);
+    pub const MOD_SHIFT: HOT_KEY_MODIFIERS = HOT_KEY_MODIFIERS(0x0004);
+    pub const MOD_WIN: HOT_KEY_MODIFIERS = HOT_KEY_MODIFIERS(0x0008);
+    pub const MOD_NOREPEAT: HOT_KEY_MODIFIERS = HOT_KEY_MODIFIERS(0x4000);
+
+    pub const VK_BACK: VIRTUAL_KEY = VIRTUAL_KEY(0x08);
+    pub const VK_TAB: VIRTUAL_KEY = VIRTUAL_KEY(0x09);
+    pub const VK_RETURN: VIRTUAL_KEY = VIRTUAL_KEY(0x0D);
+    pub const VK_SHIFT: VIRTUAL_KEY = VIRTUAL_KEY(0x10);
+    pub const VK_CONTROL: VIRTUAL_KEY = VIRTUAL_KEY(0x11);
+    pub const VK_MENU: VIRTUAL_KEY = VIRTUAL_KEY(0x12);
+    pub const VK_ESCAPE: VIRTUAL_KEY = VIRTUAL_KEY(0x1B);
+    pub const VK_SPACE: VIRTUAL_KEY = VIRTUAL_KEY(0x20);
+    pub const VK_PRIOR: VIRTUAL_KEY = VIRTUAL_KEY(0x21);
+    pub const VK_NEXT: VIRTUAL_KEY = VIRTUAL_KEY(0x22);
+    pub const VK_END: VIRTUAL_KEY = VIRTUAL_KEY(0x23);
+    pub const VK_HOME: VIRTUAL_KEY = VIRTUAL_KEY(0x24);
+    pub const VK_LEFT: VIRTUAL_KEY = VIRTUAL_KEY(0x25);
+    pub const VK_UP: VIRTUAL_KEY = VIRTUAL_KEY(0x26);
+    pub const VK_RIGHT: VIRTUAL_KEY = VIRTUAL_KEY(0x27);
+    pub const VK_DOWN: VIRTUAL_KEY = VIRTUAL_KEY(0x28);
+    pub const VK_INSERT: VIRTUAL_KEY = VIRTUAL_KEY(0x2D);
+    pub const VK_DELETE: VIRTUAL_KEY = VIRTUAL_KEY(0x2E);
+    pub const VK_LWIN: VIRTUAL_KEY = VIRTUAL_KEY(0x5B);
+    pub const VK_RWIN: VIRTUAL_KEY = VIRTUAL_KEY(0x5C);
+    pub const VK_F1: VIRTUAL_KEY = VIRTUAL_KEY(0x70);
+    pub const VK_F24: VIRTUAL_KEY = VIRTUAL_KEY(0x87);
+    pub const VK_LSHIFT: VIRTUAL_KEY = VIRTUAL_KEY(0xA0);
+    pub const VK_RSHIFT: VIRTUAL_KEY = VIRTUAL_KEY(0xA1);
+    pub const VK_LCONTROL: VIRTUAL_KEY = VIRTUAL_KEY(0xA2);
+    pub const VK_RCONTROL: VIRTUAL_KEY = VIRTUAL_KEY(0xA3);
+    pub const VK_LMENU: VIRTUAL_KEY = VIRTUAL_KEY(0xA4);
+    pub const VK_RMENU: VIRTUAL_KEY = VIRTUAL_KEY(0xA5);
+    pub const VK_OEM_COMMA: VIRTUAL_KEY = VIRTUAL_KEY(0xBC);
+    pub const VK_OEM_PERIOD: VIRTUAL_KEY = VIRTUAL_KEY(0xBE);
+}
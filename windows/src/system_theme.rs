@@ -0,0 +1,108 @@
+// OS light/dark mode watcher for the theme automation.
+//
+// Reads HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize
+// AppsUseLightTheme (0 = dark) and blocks on RegNotifyChangeKeyValue in a
+// background thread. When the value flips, WM_SYSTEM_THEME_CHANGED is posted
+// to the settings window (WPARAM 1 = dark), which applies the mapped profile.
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegNotifyChangeKeyValue, RegOpenKeyExW, RegQueryValueExW, HKEY,
+    HKEY_CURRENT_USER, KEY_NOTIFY, KEY_READ, REG_NOTIFY_CHANGE_LAST_SET,
+};
+use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_APP};
+
+const PERSONALIZE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+const VALUE_NAME: &str = "AppsUseLightTheme";
+
+/// Posted to the settings window when the OS theme changed (WPARAM 1 = dark)
+pub const WM_SYSTEM_THEME_CHANGED: u32 = WM_APP + 13;
+
+fn wide_string(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn open_personalize_key() -> Option<HKEY> {
+    let key_path = wide_string(PERSONALIZE_KEY);
+    let mut hkey = HKEY::default();
+    unsafe {
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key_path.as_ptr()),
+            Some(0),
+            KEY_READ | KEY_NOTIFY,
+            &mut hkey,
+        )
+        .is_ok()
+        .then_some(hkey)
+    }
+}
+
+fn read_is_dark(hkey: HKEY) -> bool {
+    let value_name = wide_string(VALUE_NAME);
+    let mut data = 1u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    unsafe {
+        let result = RegQueryValueExW(
+            hkey,
+            PCWSTR(value_name.as_ptr()),
+            None,
+            None,
+            Some(&mut data as *mut u32 as *mut u8),
+            Some(&mut size),
+        );
+        // Missing value means the light default
+        result.is_ok() && data == 0
+    }
+}
+
+/// True if apps are currently using the dark theme
+pub fn is_dark() -> bool {
+    match open_personalize_key() {
+        Some(hkey) => {
+            let dark = read_is_dark(hkey);
+            unsafe {
+                let _ = RegCloseKey(hkey);
+            }
+            dark
+        }
+        None => false,
+    }
+}
+
+/// Watch for theme changes for the rest of the session, notifying `hwnd`.
+pub fn watch(hwnd: HWND) {
+    let hwnd_val = hwnd.0 as isize;
+    std::thread::spawn(move || {
+        let Some(hkey) = open_personalize_key() else {
+            eprintln!("[theme] cannot open Personalize key; theme automation disabled");
+            return;
+        };
+        let mut dark = read_is_dark(hkey);
+        loop {
+            // Blocks until any value under the key is written
+            let result =
+                unsafe { RegNotifyChangeKeyValue(hkey, false, REG_NOTIFY_CHANGE_LAST_SET, None, false) };
+            if result.is_err() {
+                eprintln!("[theme] registry watch failed: {:?}", result);
+                break;
+            }
+            let now_dark = read_is_dark(hkey);
+            if now_dark != dark {
+                dark = now_dark;
+                unsafe {
+                    let _ = PostMessageW(
+                        Some(HWND(hwnd_val as *mut _)),
+                        WM_SYSTEM_THEME_CHANGED,
+                        WPARAM(dark as usize),
+                        LPARAM(0),
+                    );
+                }
+            }
+        }
+        unsafe {
+            let _ = RegCloseKey(hkey);
+        }
+    });
+}
@@ -0,0 +1,170 @@
+// Trace marks (shared trace.rs) as ETW TraceLogging events on Windows.
+//
+// The provider is "SaveMyEyes" with a fixed GUID (PROVIDER_ID), registered
+// at startup. Each event carries the monitor or pass it is about (Detail),
+// the level involved (Value) and, for timed work, how long it took
+// (DurationUs). The TraceLogging metadata is built by hand, the layout the
+// TraceLoggingProvider.h macros produce, so WPA and tracefmt decode the
+// fields without a manifest. Until a session enables the provider, a mark
+// costs one EventProviderEnabled call.
+
+use std::ffi::CString;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Instant;
+
+use windows::core::GUID;
+use windows::Win32::System::Diagnostics::Etw::{
+    EventProviderEnabled, EventProviderSetTraits, EventRegister, EventSetInformation,
+    EventWriteTransfer, EVENT_DATA_DESCRIPTOR, EVENT_DATA_DESCRIPTOR_0, EVENT_DATA_DESCRIPTOR_0_0,
+    EVENT_DESCRIPTOR, REGHANDLE,
+};
+
+use savemyeyes_shared::trace::Mark;
+
+/// Provider GUID to enable in a trace session
+pub const PROVIDER_ID: GUID = GUID::from_u128(0x5b0c2d47_8e3f_4c1a_9d62_7f41a0e3b915);
+const PROVIDER_NAME: &str = "SaveMyEyes";
+
+/// TraceLogging channel, which marks the event as self-describing
+const CHANNEL_TRACELOGGING: u8 = 11;
+/// Verbose: these are only wanted by someone recording a trace
+const LEVEL_VERBOSE: u8 = 5;
+
+// Field types (TlgIn*) and data descriptor types (EVENT_DATA_DESCRIPTOR_TYPE_*)
+const IN_ANSISTRING: u8 = 2;
+const IN_UINT64: u8 = 10;
+const IN_FLOAT: u8 = 11;
+const DESCRIPTOR_EVENT_METADATA: u8 = 1;
+const DESCRIPTOR_PROVIDER_METADATA: u8 = 2;
+
+/// Registration handle, 0 until `init` succeeds
+static HANDLE: AtomicI64 = AtomicI64::new(0);
+
+/// Register the provider (startup).
+pub fn init() {
+    let mut handle = REGHANDLE::default();
+    let registered = unsafe { EventRegister(&PROVIDER_ID, None, None, &mut handle) };
+    if registered != 0 {
+        eprintln!("[trace] could not register the provider ({})", registered);
+        return;
+    }
+    let traits = provider_traits();
+    unsafe {
+        EventSetInformation(
+            handle,
+            EventProviderSetTraits,
+            traits.as_ptr() as *const _,
+            traits.len() as u32,
+        );
+    }
+    HANDLE.store(handle.0, Ordering::SeqCst);
+}
+
+/// Whether a session is recording our events
+fn enabled() -> Option<REGHANDLE> {
+    let handle = REGHANDLE(HANDLE.load(Ordering::SeqCst));
+    let on = handle.0 != 0 && unsafe { EventProviderEnabled(handle, LEVEL_VERBOSE, 0) };
+    on.then_some(handle)
+}
+
+/// Emit a single mark.
+pub fn mark(mark: Mark, detail: &str, value: f32) {
+    if let Some(handle) = enabled() {
+        write(handle, mark, detail, value, 0);
+    }
+}
+
+/// Time the work until the returned guard is dropped, and emit the mark
+/// with its duration then.
+pub fn span(mark: Mark, detail: &str, value: f32) -> Span {
+    Span {
+        mark,
+        open: enabled().map(|_| (detail.to_string(), value, Instant::now())),
+    }
+}
+
+/// A timed mark, emitted on drop
+pub struct Span {
+    mark: Mark,
+    /// Detail, value and start, when a session was recording at the start
+    open: Option<(String, f32, Instant)>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some((detail, value, start)) = self.open.take() else {
+            return;
+        };
+        if let Some(handle) = enabled() {
+            let micros = start.elapsed().as_micros() as u64;
+            write(handle, self.mark, &detail, value, micros);
+        }
+    }
+}
+
+/// Provider traits: total size, then the provider name
+fn provider_traits() -> Vec<u8> {
+    let mut blob = vec![0, 0];
+    blob.extend_from_slice(PROVIDER_NAME.as_bytes());
+    blob.push(0);
+    let size = blob.len() as u16;
+    blob[..2].copy_from_slice(&size.to_le_bytes());
+    blob
+}
+
+/// Event metadata: total size, tags, the event name, then each field's
+/// name and type
+fn event_metadata(mark: Mark) -> Vec<u8> {
+    let mut blob = vec![0, 0, 0];
+    blob.extend_from_slice(mark.name().to_bytes_with_nul());
+    for (field, in_type) in [
+        ("Detail", IN_ANSISTRING),
+        ("Value", IN_FLOAT),
+        ("DurationUs", IN_UINT64),
+    ] {
+        blob.extend_from_slice(field.as_bytes());
+        blob.push(0);
+        blob.push(in_type);
+    }
+    let size = blob.len() as u16;
+    blob[..2].copy_from_slice(&size.to_le_bytes());
+    blob
+}
+
+fn write(handle: REGHANDLE, mark: Mark, detail: &str, value: f32, micros: u64) {
+    let descriptor = EVENT_DESCRIPTOR {
+        Channel: CHANNEL_TRACELOGGING,
+        Level: LEVEL_VERBOSE,
+        ..Default::default()
+    };
+    let traits = provider_traits();
+    let metadata = event_metadata(mark);
+    let detail = CString::new(detail.replace('\0', "")).unwrap_or_default();
+    let detail = detail.as_bytes_with_nul();
+    let value = value.to_le_bytes();
+    let micros = micros.to_le_bytes();
+    let data = [
+        data_descriptor(&traits, DESCRIPTOR_PROVIDER_METADATA),
+        data_descriptor(&metadata, DESCRIPTOR_EVENT_METADATA),
+        data_descriptor(detail, 0),
+        data_descriptor(&value, 0),
+        data_descriptor(&micros, 0),
+    ];
+    unsafe {
+        EventWriteTransfer(handle, &descriptor, None, None, Some(&data));
+    }
+}
+
+fn data_descriptor(bytes: &[u8], kind: u8) -> EVENT_DATA_DESCRIPTOR {
+    EVENT_DATA_DESCRIPTOR {
+        Ptr: bytes.as_ptr() as u64,
+        Size: bytes.len() as u32,
+        Anonymous: EVENT_DATA_DESCRIPTOR_0 {
+            Anonymous: EVENT_DATA_DESCRIPTOR_0_0 {
+                Type: kind,
+                Reserved1: 0,
+                Reserved2: 0,
+            },
+        },
+    }
+}
@@ -4,13 +4,26 @@ use windows::core::PCWSTR;
 use windows::Win32::Foundation::{HWND, POINT};
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::Shell::{
-    Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+    Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_MESSAGE, NIF_TIP, NIIF_INFO, NIIF_WARNING, NIM_ADD,
+    NIM_DELETE, NIM_MODIFY, NOTIFYICONDATAW, NOTIFY_ICON_INFOTIP_FLAGS,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     AppendMenuW, CreatePopupMenu, DestroyMenu, GetCursorPos, LoadIconW, SetForegroundWindow,
-    TrackPopupMenu, MF_STRING, TPM_BOTTOMALIGN, TPM_LEFTALIGN,
+    TrackPopupMenu, HMENU, MENU_ITEM_FLAGS, MF_CHECKED, MF_GRAYED, MF_POPUP, MF_SEPARATOR,
+    MF_STRING, TPM_BOTTOMALIGN, TPM_LEFTALIGN,
 };
 
+use savemyeyes_shared::builtin_profiles;
+use savemyeyes_shared::dimming;
+use savemyeyes_shared::hotkeys;
+use savemyeyes_shared::pause;
+use savemyeyes_shared::presentation;
+use savemyeyes_shared::profiles;
+use savemyeyes_shared::tray_menu::{self, TrayItem, PRESETS};
+
+use crate::config::AppConfig;
+use crate::overlay;
+
 /// Custom message ID for tray icon callbacks
 pub const WM_TRAY_ICON: u32 = 0x0401; // WM_APP + 1
 
@@ -18,6 +31,30 @@ pub const WM_TRAY_ICON: u32 = 0x0401; // WM_APP + 1
 pub const IDM_TOGGLE: u32 = 1001;
 pub const IDM_SETTINGS: u32 = 1002;
 pub const IDM_QUIT: u32 = 1003;
+pub const IDM_DESIGNER: u32 = 1004;
+pub const IDM_MOVIE: u32 = 1005;
+pub const IDM_RESUME: u32 = 1006;
+pub const IDM_UPDATES: u32 = 1007;
+pub const IDM_END_PRESENTATION: u32 = 1008;
+pub const IDM_RECORDING: u32 = 1009;
+pub const IDM_EXPLAIN: u32 = 1010;
+/// Dim Level entries: IDM_PRESET_BASE + index into tray_menu::PRESETS
+pub const IDM_PRESET_BASE: u32 = 1100;
+/// Profiles entries: IDM_PROFILE_BASE + index into profiles::listed
+pub const IDM_PROFILE_BASE: u32 = 1200;
+/// Pause Dimming entries: IDM_PAUSE_BASE + index into pause::DURATIONS
+pub const IDM_PAUSE_BASE: u32 = 1300;
+/// Duplicate to Customize entries: IDM_DUPLICATE_BASE + index into the
+/// built-in profiles
+pub const IDM_DUPLICATE_BASE: u32 = 1400;
+/// Theme Examples entries: IDM_EXAMPLE_BASE + index into the examples
+pub const IDM_EXAMPLE_BASE: u32 = 1500;
+/// Presentation Mode entries: IDM_PRESENTATION_BASE + index into
+/// presentation::DURATIONS
+pub const IDM_PRESENTATION_BASE: u32 = 1600;
+
+/// Profiles listed in the menu (the rest of the ID range belongs to pause)
+const MAX_PROFILES: usize = (IDM_PAUSE_BASE - IDM_PROFILE_BASE) as usize;
 
 fn wide_str(s: &str) -> Vec<u16> {
     s.encode_utf16().chain(std::iter::once(0)).collect()
@@ -29,7 +66,7 @@ pub fn add_tray_icon(hwnd: HWND) -> bool {
         let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
         // Load icon from embedded resource (ID 1)
         // Use PCWSTR with the integer ID cast to a pointer
-        let icon_id = PCWSTR(1 as *const u16);
+        let icon_id = PCWSTR(std::ptr::without_provenance(1));
         let hicon = LoadIconW(Some(hinstance.into()), icon_id);
 
         let mut nid = NOTIFYICONDATAW {
@@ -54,6 +91,59 @@ pub fn add_tray_icon(hwnd: HWND) -> bool {
     }
 }
 
+/// Update the tray tooltip (e.g. to flag designer mode)
+pub fn set_tooltip(hwnd: HWND, text: &str) {
+    unsafe {
+        let mut nid = NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: hwnd,
+            uID: 1,
+            uFlags: NIF_TIP,
+            ..Default::default()
+        };
+        let tip = wide_str(text);
+        let len = tip.len().min(128);
+        nid.szTip[..len].copy_from_slice(&tip[..len]);
+        let _ = Shell_NotifyIconW(NIM_MODIFY, &nid);
+    }
+}
+
+/// Show a warning notification (toast) from the tray icon. Only logged
+/// during presentation mode.
+pub fn show_warning(hwnd: HWND, title: &str, text: &str) {
+    show_notification(hwnd, title, text, NIIF_WARNING);
+}
+
+/// Show an informational notification from the tray icon (same rules as
+/// show_warning). Clicking it opens the settings window.
+pub fn show_info(hwnd: HWND, title: &str, text: &str) {
+    show_notification(hwnd, title, text, NIIF_INFO);
+}
+
+fn show_notification(hwnd: HWND, title: &str, text: &str, icon: NOTIFY_ICON_INFOTIP_FLAGS) {
+    if presentation::is_active() {
+        eprintln!("[tray] held back during presentation: {}: {}", title, text);
+        return;
+    }
+    unsafe {
+        let mut nid = NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: hwnd,
+            uID: 1,
+            uFlags: NIF_INFO,
+            dwInfoFlags: icon,
+            ..Default::default()
+        };
+        let title = wide_str(title);
+        let len = title.len().min(nid.szInfoTitle.len());
+        nid.szInfoTitle[..len].copy_from_slice(&title[..len]);
+        let info = wide_str(text);
+        let len = info.len().min(nid.szInfo.len());
+        nid.szInfo[..len].copy_from_slice(&info[..len]);
+        let _ = Shell_NotifyIconW(NIM_MODIFY, &nid);
+    }
+}
+
 /// Remove the system tray icon
 pub fn remove_tray_icon(hwnd: HWND) {
     unsafe {
@@ -67,32 +157,120 @@ pub fn remove_tray_icon(hwnd: HWND) {
     }
 }
 
-/// Show the tray context menu
-pub fn show_context_menu(hwnd: HWND) {
+/// Show the tray context menu, built from config `tray_menu`
+pub fn show_context_menu(hwnd: HWND, config: &AppConfig, designer_mode: bool, movie_mode: bool) {
     unsafe {
         let menu = CreatePopupMenu().unwrap();
-        let toggle_text = wide_str("Toggle Dimmer");
-        let settings_text = wide_str("Settings");
-        let quit_text = wide_str("Quit");
-
-        let _ = AppendMenuW(
-            menu,
-            MF_STRING,
-            IDM_TOGGLE as usize,
-            PCWSTR(toggle_text.as_ptr()),
-        );
-        let _ = AppendMenuW(
-            menu,
-            MF_STRING,
-            IDM_SETTINGS as usize,
-            PCWSTR(settings_text.as_ptr()),
-        );
-        let _ = AppendMenuW(
-            menu,
-            MF_STRING,
-            IDM_QUIT as usize,
-            PCWSTR(quit_text.as_ptr()),
-        );
+        for item in tray_menu::items(config) {
+            match item {
+                TrayItem::Toggle => append(menu, MF_STRING, IDM_TOGGLE, "Toggle Dimmer"),
+                TrayItem::MovieMode => append(
+                    menu,
+                    checked(movie_mode),
+                    IDM_MOVIE,
+                    &format!("Movie Mode\t{}", hotkeys::MOVIE_MODE_KEYS),
+                ),
+                TrayItem::DesignerMode => {
+                    append(menu, checked(designer_mode), IDM_DESIGNER, "Designer Mode")
+                }
+                TrayItem::Presets => {
+                    let submenu = CreatePopupMenu().unwrap();
+                    for (i, level) in PRESETS.iter().enumerate() {
+                        append(
+                            submenu,
+                            checked(tray_menu::is_current_preset(config, *level)),
+                            IDM_PRESET_BASE + i as u32,
+                            &dimming::percent_label(*level),
+                        );
+                    }
+                    append_submenu(menu, MF_STRING, submenu, "Dim Level");
+                }
+                TrayItem::Profiles => {
+                    let submenu = CreatePopupMenu().unwrap();
+                    let listed = profiles::listed(config);
+                    for (i, profile) in listed.iter().take(MAX_PROFILES).enumerate() {
+                        // Built-ins follow the saved profiles
+                        if i > 0
+                            && profiles::is_builtin(config, &profile.name)
+                            && !profiles::is_builtin(config, &listed[i - 1].name)
+                        {
+                            let _ = AppendMenuW(submenu, MF_SEPARATOR, 0, PCWSTR::null());
+                        }
+                        let active = config
+                            .active_profile
+                            .as_deref()
+                            .is_some_and(|name| name.eq_ignore_ascii_case(&profile.name));
+                        append(
+                            submenu,
+                            checked(active),
+                            IDM_PROFILE_BASE + i as u32,
+                            &profile.name,
+                        );
+                    }
+                    let _ = AppendMenuW(submenu, MF_SEPARATOR, 0, PCWSTR::null());
+                    let copies = CreatePopupMenu().unwrap();
+                    for (i, profile) in builtin_profiles::profiles().iter().enumerate() {
+                        let id = IDM_DUPLICATE_BASE + i as u32;
+                        append(copies, MF_STRING, id, &profile.name);
+                    }
+                    append_submenu(submenu, MF_STRING, copies, "Duplicate to Customize");
+                    let examples = CreatePopupMenu().unwrap();
+                    for (i, example) in builtin_profiles::examples().iter().enumerate() {
+                        let id = IDM_EXAMPLE_BASE + i as u32;
+                        append(examples, MF_STRING, id, &example.name);
+                    }
+                    append_submenu(submenu, MF_STRING, examples, "Theme Examples");
+                    append_submenu(menu, MF_STRING, submenu, "Profiles");
+                }
+                TrayItem::Pause if pause::is_active() => {
+                    append(menu, MF_STRING, IDM_RESUME, &pause::resume_label())
+                }
+                TrayItem::Pause => {
+                    let submenu = CreatePopupMenu().unwrap();
+                    for (i, minutes) in pause::DURATIONS.iter().enumerate() {
+                        let label = pause::duration_label(*minutes);
+                        append(submenu, MF_STRING, IDM_PAUSE_BASE + i as u32, &label);
+                    }
+                    // Designer mode already pauses everything
+                    let flags = if designer_mode {
+                        MF_STRING | MF_GRAYED
+                    } else {
+                        MF_STRING
+                    };
+                    append_submenu(menu, flags, submenu, "Pause Dimming");
+                }
+                TrayItem::Presentation if presentation::is_active() => {
+                    let label = presentation::end_label();
+                    append(menu, MF_STRING, IDM_END_PRESENTATION, &label)
+                }
+                TrayItem::Presentation => {
+                    let submenu = CreatePopupMenu().unwrap();
+                    for (i, minutes) in presentation::DURATIONS.iter().enumerate() {
+                        let label = presentation::duration_label(*minutes);
+                        append(submenu, MF_STRING, IDM_PRESENTATION_BASE + i as u32, &label);
+                    }
+                    let flags = if designer_mode {
+                        MF_STRING | MF_GRAYED
+                    } else {
+                        MF_STRING
+                    };
+                    append_submenu(menu, flags, submenu, "Presentation Mode");
+                }
+                TrayItem::Recording => append(
+                    menu,
+                    checked(overlay::recording()),
+                    IDM_RECORDING,
+                    "Show Dimming in Recordings",
+                ),
+                TrayItem::Explain => append(menu, MF_STRING, IDM_EXPLAIN, "Why Is My Screen Dark?"),
+                TrayItem::Updates => append(menu, MF_STRING, IDM_UPDATES, "Check for Updates"),
+                TrayItem::Separator => {
+                    let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+                }
+                TrayItem::Settings => append(menu, MF_STRING, IDM_SETTINGS, "Settings"),
+                TrayItem::Quit => append(menu, MF_STRING, IDM_QUIT, "Quit"),
+            }
+        }
 
         let mut pt = POINT::default();
         let _ = GetCursorPos(&mut pt);
@@ -110,6 +288,69 @@ pub fn show_context_menu(hwnd: HWND) {
             None,
         );
 
+        // Also destroys the submenus
         let _ = DestroyMenu(menu);
     }
 }
+
+fn checked(on: bool) -> MENU_ITEM_FLAGS {
+    if on {
+        MF_STRING | MF_CHECKED
+    } else {
+        MF_STRING
+    }
+}
+
+pub unsafe fn append(menu: HMENU, flags: MENU_ITEM_FLAGS, id: u32, text: &str) {
+    let text = wide_str(text);
+    let _ = AppendMenuW(menu, flags, id as usize, PCWSTR(text.as_ptr()));
+}
+
+pub unsafe fn append_submenu(menu: HMENU, flags: MENU_ITEM_FLAGS, submenu: HMENU, text: &str) {
+    let text = wide_str(text);
+    let _ = AppendMenuW(
+        menu,
+        flags | MF_POPUP,
+        submenu.0 as usize,
+        PCWSTR(text.as_ptr()),
+    );
+}
+
+/// Level of the Dim Level entry `id`, if it is one
+pub fn preset_level(id: u32) -> Option<f32> {
+    let index = id.checked_sub(IDM_PRESET_BASE)? as usize;
+    PRESETS.get(index).copied()
+}
+
+/// Index into profiles::listed of the Profiles entry `id`, if it is one
+pub fn profile_index(id: u32) -> Option<usize> {
+    let index = id.checked_sub(IDM_PROFILE_BASE)? as usize;
+    (index < MAX_PROFILES).then_some(index)
+}
+
+/// Index into the built-in profiles of the Duplicate to Customize entry
+/// `id`, if it is one
+pub fn duplicate_index(id: u32) -> Option<usize> {
+    let index = id.checked_sub(IDM_DUPLICATE_BASE)? as usize;
+    (index < builtin_profiles::profiles().len()).then_some(index)
+}
+
+/// Index into the example automations of the Theme Examples entry `id`, if
+/// it is one
+pub fn example_index(id: u32) -> Option<usize> {
+    let index = id.checked_sub(IDM_EXAMPLE_BASE)? as usize;
+    (index < builtin_profiles::examples().len()).then_some(index)
+}
+
+/// Minutes of the Pause Dimming entry `id`, if it is one
+pub fn pause_minutes(id: u32) -> Option<u64> {
+    let index = id.checked_sub(IDM_PAUSE_BASE)? as usize;
+    pause::DURATIONS.get(index).copied()
+}
+
+/// Minutes of the Presentation Mode entry `id` (inner None = until turned
+/// off), if it is one
+pub fn presentation_minutes(id: u32) -> Option<Option<u64>> {
+    let index = id.checked_sub(IDM_PRESENTATION_BASE)? as usize;
+    presentation::DURATIONS.get(index).copied()
+}
@@ -1,6 +1,11 @@
 // UI control state tracking and hit-testing
 
-use windows::Win32::Foundation::RECT;
+use savemyeyes_shared::capabilities::Capabilities;
+use savemyeyes_shared::dimming;
+use savemyeyes_shared::hotkeys;
+use savemyeyes_shared::monitor_id::MonitorId;
+
+use crate::sys::RECT;
 
 /// Which tab is active
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +13,7 @@ pub enum Tab {
     Dimmer = 0,
     Settings = 1,
     Shortcuts = 2,
+    Advanced = 3,
 }
 
 /// State for a toggle switch control
@@ -29,7 +35,7 @@ impl ToggleState {
 /// State for the opacity slider
 #[derive(Debug, Clone)]
 pub struct SliderState {
-    pub value: i32, // 0-90
+    pub value: i32, // 0-900, tenths of a percent (dimming::to_steps)
     pub dragging: bool,
     pub rect: RECT,       // full track rect
     pub thumb_rect: RECT, // thumb hit area
@@ -39,7 +45,7 @@ pub struct SliderState {
 impl SliderState {
     pub fn new(value: i32) -> Self {
         Self {
-            value: value.clamp(0, 90),
+            value: value.clamp(0, dimming::MAX_STEPS),
             dragging: false,
             rect: RECT::default(),
             thumb_rect: RECT::default(),
@@ -50,20 +56,77 @@ impl SliderState {
     /// Get x position of slider thumb based on current value
     pub fn thumb_x(&self) -> i32 {
         let track_width = self.rect.right - self.rect.left;
-        self.rect.left + (self.value as f32 / 90.0 * track_width as f32) as i32
+        self.rect.left + (self.fraction() * track_width as f32) as i32
+    }
+
+    /// Position of the value along the track (0.0-1.0)
+    pub fn fraction(&self) -> f32 {
+        self.value as f32 / dimming::MAX_STEPS as f32
+    }
+
+    /// Level for the current value
+    pub fn level(&self) -> f32 {
+        dimming::from_steps(self.value)
     }
 
-    /// Calculate value from an x position within the slider track
-    pub fn value_from_x(&self, x: i32) -> i32 {
+    /// Calculate value from an x position within the slider track, in whole
+    /// percents or, in fine mode (Shift held), half percents
+    pub fn value_from_x(&self, x: i32, fine: bool) -> i32 {
         let track_width = self.rect.right - self.rect.left;
         if track_width <= 0 {
             return self.value;
         }
         let rel_x = (x - self.rect.left).clamp(0, track_width);
-        ((rel_x as f32 / track_width as f32) * 90.0).round() as i32
+        let steps = rel_x as f32 / track_width as f32 * dimming::MAX_STEPS as f32;
+        dimming::snap_steps(steps, fine)
     }
 }
 
+/// A monitor's hardware brightness slider (ddcci.rs), 0–100% of its range
+#[derive(Debug, Clone)]
+pub struct BacklightRow {
+    pub id: MonitorId,
+    pub name: String,
+    pub percent: u32,
+    pub dragging: bool,
+    pub rect: RECT,
+    pub thumb_rect: RECT,
+}
+
+impl BacklightRow {
+    /// Position of the value along the track (0.0-1.0)
+    pub fn fraction(&self) -> f32 {
+        self.percent as f32 / 100.0
+    }
+
+    pub fn thumb_x(&self) -> i32 {
+        let track_width = self.rect.right - self.rect.left;
+        self.rect.left + (self.fraction() * track_width as f32) as i32
+    }
+
+    /// Whole percent for an x position within the track
+    pub fn percent_from_x(&self, x: i32) -> u32 {
+        let track_width = self.rect.right - self.rect.left;
+        if track_width <= 0 {
+            return self.percent;
+        }
+        let rel_x = (x - self.rect.left).clamp(0, track_width);
+        (rel_x as f32 / track_width as f32 * 100.0).round() as u32
+    }
+}
+
+/// A monitor group's row on the Dimmer tab (monitor_groups.rs): one slider
+/// for every connected member
+#[derive(Debug, Clone)]
+pub struct GroupRow {
+    pub name: String,
+    /// Monitor indices of the connected members
+    pub members: Vec<usize>,
+    pub slider: SliderState,
+    /// Hit area of the "Ungroup" pill
+    pub ungroup_rect: RECT,
+}
+
 /// State for the "Check Now" button
 #[derive(Debug, Clone)]
 pub struct ButtonState {
@@ -84,10 +147,28 @@ impl ButtonState {
     }
 }
 
+/// A custom action as the Shortcuts tab lists it
+#[derive(Debug, Clone)]
+pub struct CustomActionRow {
+    pub name: String,
+    pub hotkey: String,
+    /// e.g. "Profile Movie → Pause 2 hours"
+    pub summary: String,
+    /// Opens the action's dropdown (add step, hotkey, delete)
+    pub edit_btn: ButtonState,
+}
+
+/// Hover text for a hit area (see tooltip.rs)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tooltip {
+    pub rect: RECT,
+    pub text: String,
+}
+
 /// Complete UI state
 pub struct UiState {
     pub active_tab: Tab,
-    pub tab_rects: [RECT; 3],
+    pub tab_rects: [RECT; 4],
     pub tab_bar_rect: RECT,
 
     // Dimmer tab
@@ -99,59 +180,295 @@ pub struct UiState {
     pub multi_monitor_enabled: bool,
     pub monitor_sliders: Vec<SliderState>,
     pub monitor_count: u32,
+    /// HDR state per monitor index (EnumDisplayMonitors order)
+    pub monitor_hdr: Vec<bool>,
+    /// Per monitor index: its driver refused a gamma ramp (gamma.rs), so
+    /// the overlay dims it
+    pub monitor_gamma_refused: Vec<bool>,
+    /// Friendly name per monitor (see monitors::friendly_name)
+    pub monitor_names: Vec<String>,
+    /// Id per monitor, the key of its settings in config
+    pub monitor_ids: Vec<MonitorId>,
+    /// What dimming can do here (overlay::capabilities), for gating controls
+    pub capabilities: Capabilities,
+    /// Per monitor: dimming shows up in screenshots/recordings
+    pub monitor_capture: Vec<bool>,
+    /// Hit areas of the per-monitor capture pills
+    pub monitor_capture_rects: Vec<RECT>,
+    /// Per monitor: dimmed as a reading monitor (reading_monitor.rs)
+    pub monitor_reading: Vec<bool>,
+    /// Hit areas of the per-monitor reading pills
+    pub monitor_reading_rects: Vec<RECT>,
+    /// Per monitor: ticked for grouping (its number label clicked)
+    pub monitor_selected: Vec<bool>,
+    /// Hit areas of the per-monitor number labels
+    pub monitor_label_rects: Vec<RECT>,
+    /// Groups with a connected member, drawn above their monitors' cards
+    pub group_rows: Vec<GroupRow>,
+    /// Groups the ticked monitors
+    pub group_btn: ButtonState,
+    /// Monitors whose backlight can be set over DDC/CI
+    pub backlights: Vec<BacklightRow>,
 
     // Settings tab
     pub autostart_toggle: ToggleState,
     pub auto_update_toggle: ToggleState,
     pub check_update_btn: ButtonState,
     pub update_status_text: String,
+    pub pen_pause_toggle: ToggleState,
+    /// e.g. "Pen input pauses dimming until 3s idle"
+    pub pen_pause_summary: String,
+    pub night_boost_toggle: ToggleState,
+    /// e.g. "+10% from 23:00 to 06:00"
+    pub night_boost_summary: String,
+    /// Boost the night boost adds right now (shown next to the levels)
+    pub night_boost: Option<f32>,
+    pub brightness_rule_toggle: ToggleState,
+    /// e.g. "Dims only when panel brightness is 50% or more"
+    pub brightness_rule_summary: String,
+    pub contrast_guard_toggle: ToggleState,
+    /// e.g. "Dark screens dimmed at most 30% (below 20% luminance)"
+    pub contrast_guard_summary: String,
+    pub schedule_toggle: ToggleState,
+    /// e.g. "40% from 21:00 to 07:00"
+    pub schedule_summary: String,
+    /// Days the schedule turns dimming on, Monday first (scheduler::WEEK)
+    pub schedule_days: [bool; 7],
+    pub schedule_day_rects: [RECT; 7],
+    /// "Restore settings from backup…" link under the cards
+    pub restore_backup_rect: RECT,
+
+    // Advanced tab
+    pub perceptual_toggle: ToggleState,
+    pub zoom_compat_toggle: ToggleState,
+    pub gradient_toggle: ToggleState,
+    /// Gradient edge levels (click cycles through gradient::TOP_STEPS and
+    /// BOTTOM_STEPS)
+    pub gradient_top_btn: ButtonState,
+    pub gradient_bottom_btn: ButtonState,
+    pub color_filters_toggle: ToggleState,
+    /// e.g. "Switched by 2 profiles"
+    pub color_filters_summary: String,
+    pub reading_monitor_toggle: ToggleState,
+    /// e.g. "Portrait monitors dimmed 10% more"
+    pub reading_monitor_summary: String,
+    pub vignette_toggle: ToggleState,
+    /// e.g. "Edges dimmed up to 20% more"
+    pub vignette_summary: String,
+    pub theme_auto_toggle: ToggleState,
+    /// Profile pickers for the theme automation (click cycles through profiles)
+    pub theme_dark_btn: ButtonState,
+    pub theme_light_btn: ButtonState,
+    pub copy_report_btn: ButtonState,
+    pub elevate_btn: ButtonState,
 
     // Shortcuts tab
-    pub shortcut_texts: [String; 3],
+    pub shortcut_texts: [String; 5],
+    /// Key pills of the toggle, increase and decrease rows (click to record)
+    pub shortcut_rects: [RECT; 3],
+    /// Row waiting for its new shortcut (see ui/mod.rs WM_KEYDOWN)
+    pub recording_shortcut: Option<usize>,
+    pub mouse_hotkeys_toggle: ToggleState,
+    /// Bound mouse buttons, e.g. "Ctrl+Mouse5 = more, Ctrl+Mouse4 = less"
+    pub mouse_hotkeys_summary: String,
+    pub level_chord_toggle: ToggleState,
+    pub disable_shortcuts_toggle: ToggleState,
+    pub new_action_btn: ButtonState,
+    /// One row per custom action, in config order
+    pub custom_actions: Vec<CustomActionRow>,
 
     // Toast
     pub toast_message: String,
     pub toast_visible: bool,
 
+    /// Hover texts of the controls on screen, refilled by every paint
+    pub tooltips: Vec<Tooltip>,
+
     // Header credit link
     pub credit_rect: RECT,
+
+    // Designer mode badge (header, click to exit)
+    pub designer_mode: bool,
+    pub designer_badge_rect: RECT,
 }
 
 impl UiState {
     pub fn new() -> Self {
         Self {
             active_tab: Tab::Dimmer,
-            tab_rects: [RECT::default(); 3],
+            tab_rects: [RECT::default(); 4],
             tab_bar_rect: RECT::default(),
 
-            slider: SliderState::new(30),
+            slider: SliderState::new(300),
             enabled_toggle: ToggleState::new(true),
 
             multi_monitor_toggle: ToggleState::new(false),
             multi_monitor_enabled: false,
             monitor_sliders: Vec::new(),
             monitor_count: 0,
+            monitor_hdr: Vec::new(),
+            monitor_gamma_refused: Vec::new(),
+            monitor_names: Vec::new(),
+            monitor_ids: Vec::new(),
+            capabilities: capabilities(),
+            monitor_capture: Vec::new(),
+            monitor_capture_rects: Vec::new(),
+            monitor_reading: Vec::new(),
+            monitor_reading_rects: Vec::new(),
+            monitor_selected: Vec::new(),
+            monitor_label_rects: Vec::new(),
+            group_rows: Vec::new(),
+            group_btn: ButtonState::new("Group"),
+            backlights: Vec::new(),
 
             autostart_toggle: ToggleState::new(false),
             auto_update_toggle: ToggleState::new(true),
             check_update_btn: ButtonState::new("Check Now"),
             update_status_text: String::new(),
+            pen_pause_toggle: ToggleState::new(false),
+            pen_pause_summary: String::new(),
+            night_boost_toggle: ToggleState::new(false),
+            night_boost_summary: String::new(),
+            night_boost: None,
+            brightness_rule_toggle: ToggleState::new(false),
+            brightness_rule_summary: String::new(),
+            contrast_guard_toggle: ToggleState::new(false),
+            contrast_guard_summary: String::new(),
+            schedule_toggle: ToggleState::new(false),
+            schedule_summary: String::new(),
+            schedule_days: [true; 7],
+            schedule_day_rects: [RECT::default(); 7],
+            restore_backup_rect: RECT::default(),
+
+            perceptual_toggle: ToggleState::new(false),
+            zoom_compat_toggle: ToggleState::new(false),
+            gradient_toggle: ToggleState::new(false),
+            gradient_top_btn: ButtonState::new("Top 125%"),
+            gradient_bottom_btn: ButtonState::new("Bottom 75%"),
+            color_filters_toggle: ToggleState::new(false),
+            color_filters_summary: String::new(),
+            reading_monitor_toggle: ToggleState::new(false),
+            reading_monitor_summary: String::new(),
+            vignette_toggle: ToggleState::new(false),
+            vignette_summary: String::new(),
+            theme_auto_toggle: ToggleState::new(false),
+            theme_dark_btn: ButtonState::new("None"),
+            theme_light_btn: ButtonState::new("None"),
+            copy_report_btn: ButtonState::new("Copy Report"),
+            elevate_btn: ButtonState::new("Relaunch"),
 
             shortcut_texts: [
-                "Ctrl+Alt+End".into(),
-                "Ctrl+Alt+Up".into(),
-                "Ctrl+Alt+Down".into(),
+                hotkeys::DEFAULT_KEYS[0].into(),
+                hotkeys::DEFAULT_KEYS[1].into(),
+                hotkeys::DEFAULT_KEYS[2].into(),
+                hotkeys::MOVIE_MODE_KEYS.into(),
+                hotkeys::BOOST_KEYS.into(),
             ],
+            shortcut_rects: [RECT::default(); 3],
+            recording_shortcut: None,
+            mouse_hotkeys_toggle: ToggleState::new(false),
+            mouse_hotkeys_summary: String::new(),
+            level_chord_toggle: ToggleState::new(false),
+            disable_shortcuts_toggle: ToggleState::new(false),
+            new_action_btn: ButtonState::new("New Action"),
+            custom_actions: Vec::new(),
 
             toast_message: String::new(),
             toast_visible: false,
 
+            tooltips: Vec::new(),
+
             credit_rect: RECT::default(),
+
+            designer_mode: false,
+            designer_badge_rect: RECT::default(),
+        }
+    }
+
+    /// Show `text` while the mouse rests on `rect` (called while painting)
+    pub fn tooltip(&mut self, rect: RECT, text: impl Into<String>) {
+        if rect.right > rect.left && rect.bottom > rect.top {
+            self.tooltips.push(Tooltip {
+                rect,
+                text: text.into(),
+            });
         }
     }
 }
 
+/// What dimming can do here, as the overlay module reports it
+#[cfg(windows)]
+fn capabilities() -> Capabilities {
+    crate::overlay::capabilities()
+}
+
+/// Headless builds: none of the optional features
+#[cfg(not(windows))]
+fn capabilities() -> Capabilities {
+    Capabilities {
+        capture_exclusion: false,
+        per_monitor: false,
+        warmth: false,
+        regions: false,
+        ddc: false,
+        gamma: false,
+    }
+}
+
 /// Check if a point is inside a rect
 pub fn point_in_rect(x: i32, y: i32, r: &RECT) -> bool {
     x >= r.left && x < r.right && y >= r.top && y < r.bottom
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> RECT {
+        RECT {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn slider_value_follows_the_track() {
+        let mut slider = SliderState::new(300);
+        // 300 px for 900 steps: 3 steps a pixel
+        slider.rect = rect(100, 0, 400, 20);
+        assert_eq!(slider.value_from_x(162, false), 190);
+        assert_eq!(slider.value_from_x(162, true), 185);
+        assert_eq!(slider.value_from_x(40, false), 0);
+        assert_eq!(slider.value_from_x(500, true), dimming::MAX_STEPS);
+        assert_eq!(slider.thumb_x(), 200);
+    }
+
+    #[test]
+    fn slider_without_a_track_keeps_its_value() {
+        let slider = SliderState::new(2000);
+        assert_eq!(slider.value, dimming::MAX_STEPS);
+        assert_eq!(slider.value_from_x(50, false), dimming::MAX_STEPS);
+    }
+
+    #[test]
+    fn point_in_rect_excludes_the_far_edges() {
+        let r = rect(10, 20, 30, 40);
+        assert!(point_in_rect(10, 20, &r));
+        assert!(point_in_rect(29, 39, &r));
+        assert!(!point_in_rect(30, 25, &r));
+        assert!(!point_in_rect(15, 40, &r));
+        assert!(!point_in_rect(9, 25, &r));
+    }
+
+    #[test]
+    fn tooltips_need_an_area() {
+        let mut ui = UiState::new();
+        ui.tooltip(rect(0, 0, 10, 10), "Dim");
+        ui.tooltip(rect(5, 5, 5, 10), "Empty");
+        ui.tooltip(RECT::default(), "Unpainted");
+        assert_eq!(ui.tooltips.len(), 1);
+        assert_eq!(ui.tooltips[0].text, "Dim");
+    }
+}
@@ -1,25 +1,64 @@
 pub mod controls;
 pub mod painting;
 pub mod theme;
+pub mod tooltip;
 
 use controls::*;
 use theme::*;
 
 use crate::config::{self, AppConfig};
-use crate::{autostart, overlay, tray, updater};
-
+use savemyeyes_shared::builtin_profiles;
+use savemyeyes_shared::capabilities::Feature;
+use savemyeyes_shared::color_filter;
+use savemyeyes_shared::custom_actions::{self, CustomAction};
+use savemyeyes_shared::dimming::{self, OpacityCurve};
+use crate::{
+    autostart, ddcci, elevation, explainer, gamma, hud, monitors, mouse_hooks, overlay,
+    system_theme, tray, updater,
+};
+use savemyeyes_shared::explain::{self, Cause, Fix, Live};
+use savemyeyes_shared::gradient;
+use savemyeyes_shared::hotkeys::{self, ChordStep, HotkeyAction};
+use savemyeyes_shared::locale;
+use savemyeyes_shared::monitor_groups;
+use savemyeyes_shared::monitor_id;
+use savemyeyes_shared::pause;
+use savemyeyes_shared::presentation;
+use savemyeyes_shared::profiles;
+use savemyeyes_shared::scheduler;
+use savemyeyes_shared::shutdown;
+use savemyeyes_shared::time_change;
+use savemyeyes_shared::usage_stats;
+
+use std::sync::atomic::{AtomicIsize, Ordering};
 use std::sync::{Arc, Mutex};
 use windows::core::PCWSTR;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
-use windows::Win32::UI::Input::KeyboardAndMouse::{ReleaseCapture, SetCapture};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetKeyState, ReleaseCapture, SetCapture, SetFocus, VK_ESCAPE, VK_SHIFT,
+};
+use windows::Win32::UI::Shell::NIN_BALLOONUSERCLICK;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-const CLASS_NAME: &str = "SaveMyEyesSettingsWnd\0";
+pub const CLASS_NAME: &str = "SaveMyEyesSettingsWnd\0";
 const WM_TRAY_CALLBACK: u32 = tray::WM_TRAY_ICON;
 const TOAST_TIMER_ID: usize = 100;
 const STATUS_CLEAR_TIMER_ID: usize = 101;
+/// Ends a timed pause (pause.rs)
+const PAUSE_TIMER_ID: usize = 102;
+/// Ends a timed presentation mode (presentation.rs)
+const PRESENTATION_TIMER_ID: usize = 103;
+/// Checks once a minute whether the weekly summary is due (usage_stats.rs)
+const SUMMARY_TIMER_ID: usize = 104;
+/// Posted by the config save-failure hook (may fire off the UI thread)
+const WM_CONFIG_SAVE_FAILED: u32 = WM_APP + 12;
+/// Shown when clicking a setting pinned by defaults.json
+const MANAGED_SETTING_TOAST: &str = "Set by your organization";
+
+/// Settings window, for hooks that can't carry a handle
+static MAIN_HWND: AtomicIsize = AtomicIsize::new(0);
 
 /// Shared state pointer stored in GWLP_USERDATA
 struct WndState {
@@ -34,13 +73,20 @@ fn wide(s: &str) -> Vec<u16> {
     s.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
+/// The recordable shortcut rows of the Shortcuts tab and their config keys
+const RECORDABLE: [(HotkeyAction, &str); 3] = [
+    (HotkeyAction::Toggle, "hotkey_toggle"),
+    (HotkeyAction::Increase, "hotkey_increase"),
+    (HotkeyAction::Decrease, "hotkey_decrease"),
+];
+
 /// Create and return the settings window (initially hidden)
 pub fn create_window(config: Arc<Mutex<AppConfig>>) -> HWND {
     let class_name = wide(CLASS_NAME);
 
     unsafe {
         let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
-        let icon_id = PCWSTR(1 as *const u16);
+        let icon_id = PCWSTR(std::ptr::without_provenance(1));
         let hicon = LoadIconW(Some(hinstance.into()), icon_id)
             .ok()
             .or_else(|| LoadIconW(None, IDI_APPLICATION).ok())
@@ -67,7 +113,14 @@ pub fn create_window(config: Arc<Mutex<AppConfig>>) -> HWND {
             bottom: WINDOW_HEIGHT,
         };
         let style = WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_MINIMIZEBOX;
-        let _ = AdjustWindowRectEx(&mut wr, style, false, WINDOW_EX_STYLE::default());
+        // Right-to-left: Windows mirrors the client area, the painting and
+        // the mouse coordinates, so the layout code stays as it is
+        let ex_style = if locale::current().rtl {
+            WS_EX_LAYOUTRTL
+        } else {
+            WINDOW_EX_STYLE::default()
+        };
+        let _ = AdjustWindowRectEx(&mut wr, style, false, ex_style);
 
         let win_w = wr.right - wr.left;
         let win_h = wr.bottom - wr.top;
@@ -75,7 +128,7 @@ pub fn create_window(config: Arc<Mutex<AppConfig>>) -> HWND {
         let title = wide("SaveMyEyes by KraftPixel");
 
         let hwnd = CreateWindowExW(
-            WINDOW_EX_STYLE::default(),
+            ex_style,
             PCWSTR(class_name.as_ptr()),
             PCWSTR(title.as_ptr()),
             style,
@@ -94,33 +147,63 @@ pub fn create_window(config: Arc<Mutex<AppConfig>>) -> HWND {
         let mut ui = UiState::new();
         {
             let cfg = config.lock().unwrap();
-            ui.slider.value = (cfg.opacity * 100.0).round() as i32;
+            ui.slider.value = dimming::to_steps(cfg.opacity);
             ui.enabled_toggle.checked = cfg.is_enabled;
             ui.autostart_toggle.checked = cfg.launch_on_login;
             ui.auto_update_toggle.checked = cfg.auto_update;
+            ui.zoom_compat_toggle.checked = cfg.zoom_compat;
+            sync_gradient_controls(&mut ui, &cfg);
+            ui.color_filters_toggle.checked = cfg.color_filters;
+            ui.color_filters_summary = color_filter::summary(&cfg);
+            ui.reading_monitor_toggle.checked = cfg.reading_monitor.enabled;
+            ui.reading_monitor_summary = cfg.reading_monitor.summary();
+            ui.vignette_toggle.checked = cfg.edge_vignette.enabled;
+            ui.vignette_summary = cfg.edge_vignette.summary();
+            ui.perceptual_toggle.checked = cfg.opacity_curve == OpacityCurve::Perceptual;
+            sync_theme_controls(&mut ui, &cfg);
             ui.multi_monitor_toggle.checked = cfg.multi_monitor;
             ui.multi_monitor_enabled = cfg.multi_monitor;
+            ui.designer_mode = cfg.designer_mode;
             // Initialize per-monitor sliders
-            let mon_count = overlay::enumerate_monitor_count();
-            ui.monitor_count = mon_count;
-            for i in 0..mon_count {
-                let opacity = cfg.per_monitor_opacity.get(&i).copied().unwrap_or(cfg.opacity);
-                let mut s = controls::SliderState::new((opacity * 100.0).round() as i32);
-                s.monitor_index = Some(i);
-                ui.monitor_sliders.push(s);
-            }
+            rebuild_monitor_cards(&mut ui, &cfg);
             ui.shortcut_texts = [
                 cfg.hotkey_toggle.clone(),
                 cfg.hotkey_increase.clone(),
                 cfg.hotkey_decrease.clone(),
+                hotkeys::MOVIE_MODE_KEYS.into(),
+                hotkeys::BOOST_KEYS.into(),
             ];
+            ui.mouse_hotkeys_toggle.checked = cfg.mouse_hotkeys.enabled;
+            ui.mouse_hotkeys_summary = cfg.mouse_hotkeys.summary();
+            ui.level_chord_toggle.checked = cfg.level_chord;
+            ui.disable_shortcuts_toggle.checked = cfg.disable_global_shortcuts;
+            sync_custom_actions(&mut ui, &cfg);
+            ui.pen_pause_toggle.checked = cfg.pen_pause.enabled;
+            ui.pen_pause_summary = cfg.pen_pause.summary();
+            ui.night_boost_toggle.checked = cfg.night_boost.enabled;
+            ui.night_boost_summary = cfg.night_boost.summary();
+            ui.night_boost = overlay::night_boost();
+            ui.brightness_rule_toggle.checked = cfg.brightness_rule.enabled;
+            ui.brightness_rule_summary = cfg.brightness_rule.summary();
+            ui.contrast_guard_toggle.checked = cfg.contrast_guard.enabled;
+            ui.contrast_guard_summary = cfg.contrast_guard.summary();
+            ui.schedule_toggle.checked = cfg.schedule.enabled;
+            ui.schedule_summary = cfg.schedule.summary();
+            ui.schedule_days = scheduler::WEEK.map(|day| cfg.schedule.days.contains(&day));
         }
         // Sync autostart toggle with actual registry state
         ui.autostart_toggle.checked = autostart::is_enabled();
 
+        if elevation::is_elevated() {
+            ui.elevate_btn.text = "Elevated".into();
+            ui.elevate_btn.disabled = true;
+        }
+
         let wnd_state = Box::new(WndState { ui, config });
 
         WND_STATE = Box::into_raw(wnd_state);
+        tooltip::create(hwnd);
+        SetTimer(Some(hwnd), SUMMARY_TIMER_ID, 60_000, None);
 
         hwnd
     }
@@ -148,6 +231,11 @@ pub fn invalidate(hwnd: HWND) {
     }
 }
 
+/// Slider fine mode: Shift held while dragging moves in 0.5% steps
+fn fine_mode() -> bool {
+    unsafe { GetKeyState(VK_SHIFT.0 as i32) < 0 }
+}
+
 /// Update UI state from config (called when hotkeys change things)
 pub fn sync_from_config(hwnd: HWND) {
     unsafe {
@@ -156,22 +244,681 @@ pub fn sync_from_config(hwnd: HWND) {
         }
         let state = &mut *WND_STATE;
         let cfg = state.config.lock().unwrap();
-        state.ui.slider.value = (cfg.opacity * 100.0).round() as i32;
+        state.ui.slider.value = dimming::to_steps(cfg.opacity);
         state.ui.enabled_toggle.checked = cfg.is_enabled;
         state.ui.multi_monitor_enabled = cfg.multi_monitor;
         state.ui.multi_monitor_toggle.checked = cfg.multi_monitor;
+        sync_theme_controls(&mut state.ui, &cfg);
+        state.ui.color_filters_toggle.checked = cfg.color_filters;
+        state.ui.color_filters_summary = color_filter::summary(&cfg);
         // Sync per-monitor sliders
         for slider in state.ui.monitor_sliders.iter_mut() {
-            if let Some(idx) = slider.monitor_index {
-                let opacity = cfg.per_monitor_opacity.get(&idx).copied().unwrap_or(cfg.opacity);
-                slider.value = (opacity * 100.0).round() as i32;
+            let id = slider
+                .monitor_index
+                .and_then(|i| state.ui.monitor_ids.get(i as usize));
+            if let Some(id) = id {
+                slider.value = dimming::to_steps(cfg.level_for(id));
             }
         }
+        rebuild_group_rows(&mut state.ui, &cfg);
         drop(cfg);
         invalidate(hwnd);
     }
 }
 
+/// Rebuild the per-monitor cards for the monitors connected now (opening
+/// the window, enabling multi-monitor mode, WM_DISPLAYCHANGE). A slider being
+/// dragged keeps its state if its monitor is still there; the others reload
+/// their level from config.
+fn rebuild_monitor_cards(ui: &mut UiState, cfg: &AppConfig) {
+    let list = monitors::list();
+    let mon_count = list.len() as u32;
+    ui.monitor_count = mon_count;
+    ui.capabilities = overlay::capabilities();
+    ui.monitor_hdr = list.iter().map(|m| m.hdr).collect();
+    ui.monitor_gamma_refused = list
+        .iter()
+        .map(|m| gamma::refused(&m.device).is_some())
+        .collect();
+    ui.monitor_reading = list
+        .iter()
+        .map(|m| cfg.reading_monitor.applies(&m.id, m.is_portrait()))
+        .collect();
+    ui.monitor_ids = list.iter().map(|m| m.id.clone()).collect();
+    ui.monitor_names = list.into_iter().map(|m| m.name).collect();
+    ui.monitor_capture = ui
+        .monitor_ids
+        .iter()
+        .map(|id| cfg.allow_capture_for(id))
+        .collect();
+    ui.monitor_capture_rects.truncate(mon_count as usize);
+    ui.monitor_reading_rects.truncate(mon_count as usize);
+    let old = std::mem::take(&mut ui.monitor_sliders);
+    let mut dragged = old.into_iter().find(|s| s.dragging);
+    for i in 0..mon_count {
+        let slider = match dragged.take_if(|s| s.monitor_index == Some(i)) {
+            Some(s) => s,
+            None => {
+                let opacity = cfg.level_for(&ui.monitor_ids[i as usize]);
+                let mut s = controls::SliderState::new(dimming::to_steps(opacity));
+                s.monitor_index = Some(i);
+                s
+            }
+        };
+        ui.monitor_sliders.push(slider);
+    }
+    ui.monitor_selected = vec![false; mon_count as usize];
+    ui.monitor_label_rects.truncate(mon_count as usize);
+    rebuild_group_rows(ui, cfg);
+}
+
+/// Rebuild the group rows from config for the monitors in `ui.monitor_ids`
+/// (monitor_groups.rs). A row shows its first connected member's level; a
+/// group slider being dragged keeps its state.
+fn rebuild_group_rows(ui: &mut UiState, cfg: &AppConfig) {
+    let old = std::mem::take(&mut ui.group_rows);
+    let mut dragged = old.into_iter().find(|row| row.slider.dragging);
+    for (group, members) in monitor_groups::layout(&cfg.monitor_groups, &ui.monitor_ids) {
+        let Some(group) = group.map(|g| &cfg.monitor_groups[g]) else {
+            continue;
+        };
+        let row = match dragged.take_if(|row| row.name == group.name) {
+            Some(row) => row,
+            None => GroupRow {
+                name: group.name.clone(),
+                slider: SliderState::new(dimming::to_steps(
+                    cfg.level_for(&ui.monitor_ids[members[0]]),
+                )),
+                members,
+                ungroup_rect: RECT::default(),
+            },
+        };
+        ui.group_rows.push(row);
+    }
+}
+
+/// Monitor indices whose level moves with monitor `i`'s: its group's
+/// connected members, or just `i`
+fn linked_indices(ui: &UiState, i: usize) -> Vec<usize> {
+    ui.group_rows
+        .iter()
+        .find(|row| row.members.contains(&i))
+        .map_or_else(|| vec![i], |row| row.members.clone())
+}
+
+/// Show `value` on the sliders of monitor `i` and the monitors grouped with
+/// it, and dim them at it if dimming is on (a slider being dragged)
+fn preview_linked(state: &mut WndState, i: usize, value: i32) {
+    let linked = linked_indices(&state.ui, i);
+    for &m in &linked {
+        if let Some(slider) = state.ui.monitor_sliders.get_mut(m) {
+            slider.value = value;
+        }
+    }
+    if let Some(row) = state
+        .ui
+        .group_rows
+        .iter_mut()
+        .find(|row| row.members.contains(&i))
+    {
+        row.slider.value = value;
+    }
+    let cfg = state.config.lock().unwrap();
+    if overlay::is_visible() || cfg.is_enabled {
+        for &m in &linked {
+            overlay::set_monitor_opacity(m as u32, dimming::from_steps(value));
+        }
+    }
+}
+
+/// Save `value` as the level of monitor `i` and the monitors grouped with
+/// it (a slider released), turning dimming on if needed. Returns the toast.
+fn commit_linked(state: &mut WndState, i: usize, value: i32) -> String {
+    let opacity = dimming::from_steps(value);
+    let mut cfg = state.config.lock().unwrap();
+    if let Some(id) = state.ui.monitor_ids.get(i) {
+        cfg.set_level_for(id, opacity);
+    }
+    config::save_config(&cfg);
+    if overlay::is_visible() {
+        for m in linked_indices(&state.ui, i) {
+            overlay::set_monitor_opacity(m as u32, opacity);
+        }
+    }
+    // Auto-enable dimmer
+    if !cfg.is_enabled && value > 0 {
+        cfg.is_enabled = true;
+        state.ui.enabled_toggle.checked = true;
+        config::save_config(&cfg);
+        overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
+        overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+    }
+    let name = match state
+        .ui
+        .group_rows
+        .iter()
+        .find(|row| row.members.contains(&i))
+    {
+        Some(row) => row.name.clone(),
+        None => monitors::friendly_name(i as u32),
+    };
+    format!("{}: {}", name, dimming::percent_label(opacity))
+}
+
+/// Backlight sliders from the last DDC/CI refresh. A slider being dragged
+/// keeps its value.
+fn sync_backlights(ui: &mut UiState) {
+    let old = std::mem::take(&mut ui.backlights);
+    ui.backlights = ddcci::monitors()
+        .into_iter()
+        .map(|found| match old.iter().find(|row| row.dragging && row.id == found.id) {
+            Some(row) => row.clone(),
+            None => BacklightRow {
+                id: found.id,
+                name: found.name,
+                percent: found.percent,
+                dragging: false,
+                rect: RECT::default(),
+                thumb_rect: RECT::default(),
+            },
+        })
+        .collect();
+}
+
+/// Custom action rows and the New Action button
+fn sync_custom_actions(ui: &mut UiState, cfg: &AppConfig) {
+    ui.custom_actions = cfg
+        .custom_actions
+        .iter()
+        .take(custom_actions::MAX_ACTIONS)
+        .map(|action| CustomActionRow {
+            name: action.name.clone(),
+            hotkey: action.hotkey.clone(),
+            summary: action.summary(),
+            edit_btn: ButtonState::new("Edit \u{25BE}"),
+        })
+        .collect();
+    ui.new_action_btn.disabled = cfg.custom_actions.len() >= custom_actions::MAX_ACTIONS;
+}
+
+/// Theme automation toggle and profile picker labels
+fn sync_theme_controls(ui: &mut UiState, cfg: &AppConfig) {
+    let automation = &cfg.theme_automation;
+    ui.theme_auto_toggle.checked = automation.enabled;
+    ui.theme_dark_btn.text = automation.dark_profile.clone().unwrap_or_else(|| "None".into());
+    ui.theme_light_btn.text = automation.light_profile.clone().unwrap_or_else(|| "None".into());
+}
+
+/// Gradient toggle and edge level labels
+fn sync_gradient_controls(ui: &mut UiState, cfg: &AppConfig) {
+    let gradient = &cfg.overlay_gradient;
+    ui.gradient_toggle.checked = gradient.enabled;
+    ui.gradient_top_btn.text = format!("Top {}", gradient::scale_label(gradient.top));
+    ui.gradient_bottom_btn.text = format!("Bottom {}", gradient::scale_label(gradient.bottom));
+}
+
+/// Apply the profile mapped to the OS theme (if the automation is on),
+/// save, and push the result to the overlay. Returns the profile name.
+fn apply_theme_profile(hwnd: HWND, dark: bool) -> Option<String> {
+    unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let state = &mut *WND_STATE;
+        let applied = {
+            let mut cfg = state.config.lock().unwrap();
+            // Designer mode: nothing may change the dimming meanwhile
+            if cfg.designer_mode {
+                return None;
+            }
+            let name = profiles::apply_for_theme(&mut cfg, dark)?;
+            config::save_config(&cfg);
+            show_configured_dimming(&cfg);
+            crate::color_filter::follow_profile(&cfg, &name);
+            name
+        };
+        sync_from_config(hwnd);
+        Some(applied)
+    }
+}
+
+/// Apply the profile at `index` in profiles::listed (tray menu). Returns the
+/// toast text.
+fn apply_profile_at(hwnd: HWND, index: usize) -> Option<String> {
+    let name = unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let cfg = (*WND_STATE).config.lock().unwrap();
+        profiles::listed(&cfg).get(index)?.name.clone()
+    };
+    apply_profile(hwnd, &name)
+}
+
+/// Apply the profile called `name` (tray menu, external command). Returns the
+/// toast text.
+pub fn apply_profile(hwnd: HWND, name: &str) -> Option<String> {
+    unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let state = &mut *WND_STATE;
+        let message = {
+            let mut cfg = state.config.lock().unwrap();
+            if cfg.designer_mode {
+                return Some("Designer mode is on".to_string());
+            }
+            let Some(name) = profiles::find(&cfg, name).map(|p| p.name.clone()) else {
+                return Some(format!("No profile named \"{}\"", name));
+            };
+            crate::movie_mode::forget(&cfg);
+            profiles::apply(&mut cfg, &name);
+            config::save_config(&cfg);
+            show_configured_dimming(&cfg);
+            crate::color_filter::follow_profile(&cfg, &name);
+            format!("Profile: {}", name)
+        };
+        sync_from_config(hwnd);
+        Some(message)
+    }
+}
+
+/// Show (`allow`) or hide monitor `index`'s dimming in captures: the
+/// capture pill, and the SetMonitorCapture command. Returns the toast text.
+pub fn set_monitor_capture(hwnd: HWND, index: u32, allow: bool) -> Option<String> {
+    unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let state = &mut *WND_STATE;
+        if !state.ui.capabilities.capture_exclusion {
+            return Some(Feature::CaptureExclusion.unsupported_reason().to_string());
+        }
+        let i = index as usize;
+        if state.ui.monitor_capture.len() <= i {
+            state.ui.monitor_capture.resize(i + 1, false);
+        }
+        state.ui.monitor_capture[i] = allow;
+        if let Some(id) = state.ui.monitor_ids.get(i) {
+            let mut cfg = state.config.lock().unwrap();
+            cfg.monitor_allow_capture.insert(id.clone(), allow);
+            config::save_config(&cfg);
+        }
+        overlay::set_monitor_capture(index, allow);
+        state.ui.capabilities = overlay::capabilities();
+        invalidate(hwnd);
+        if !state.ui.capabilities.capture_exclusion {
+            // Refused just now
+            return Some(Feature::CaptureExclusion.unsupported_reason().to_string());
+        }
+        let name = state.ui.monitor_names.get(i).cloned().unwrap_or_default();
+        Some(if allow {
+            format!("{}: dimming shows in captures", name)
+        } else {
+            format!("{}: dimming hidden from captures", name)
+        })
+    }
+}
+
+/// Copy the built-in profile at `index` into config (tray menu). Returns the
+/// toast text.
+fn duplicate_builtin(index: usize) -> Option<String> {
+    unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let mut cfg = (*WND_STATE).config.lock().unwrap();
+        let name = builtin_profiles::duplicate(&mut cfg, index)?;
+        config::save_config(&cfg);
+        Some(format!("Saved \"{}\"; edit it in config.json", name))
+    }
+}
+
+/// Switch the theme automation to the example at `index` (tray menu) and
+/// apply it for the current OS theme. Returns the toast text.
+fn use_theme_example(hwnd: HWND, index: usize) -> Option<String> {
+    unsafe {
+        if WND_STATE.is_null() {
+            return None;
+        }
+        let example = {
+            let mut cfg = (*WND_STATE).config.lock().unwrap();
+            let example = builtin_profiles::use_example(&mut cfg, index)?;
+            config::save_config(&cfg);
+            example
+        };
+        if apply_theme_profile(hwnd, system_theme::is_dark()).is_none() {
+            sync_from_config(hwnd);
+        }
+        Some(format!("Follow System Theme: {}", example))
+    }
+}
+
+/// Move settings still keyed by monitor index to the connected monitors'
+/// ids and hand the per-monitor settings to the overlays under the current
+/// indices (startup and WM_DISPLAYCHANGE)
+pub fn apply_monitor_settings(cfg: &mut AppConfig) {
+    let layout = monitors::enumerate();
+    let ids: Vec<_> = layout.iter().map(|d| d.id.clone()).collect();
+    if cfg.adopt_monitor_ids(&layout) {
+        config::save_config(cfg);
+    }
+    let backends = monitor_id::by_index(&cfg.monitor_backend, &ids);
+    overlay::set_backends(cfg.dim_backend, &backends);
+    overlay::set_capture_overrides(&monitor_id::by_index(&cfg.monitor_allow_capture, &ids));
+    if cfg.multi_monitor {
+        overlay::set_per_monitor_opacities(&monitor_id::by_index(&cfg.monitor_opacity, &ids));
+    }
+}
+
+/// Put the configured levels on screen (or take dimming off)
+fn show_configured_dimming(cfg: &AppConfig) {
+    if cfg.is_enabled {
+        if cfg.multi_monitor {
+            overlay::set_per_monitor_opacities(&monitors::levels_by_index(cfg));
+        }
+        overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+    } else {
+        overlay::hide_overlay();
+    }
+}
+
+/// Enter or leave designer mode: suspend all dimming (and block anything
+/// from re-enabling it) until turned off, then restore the configured state.
+pub fn set_designer_mode(hwnd: HWND, on: bool) {
+    unsafe {
+        if WND_STATE.is_null() {
+            return;
+        }
+        let state = &mut *WND_STATE;
+        {
+            let mut cfg = state.config.lock().unwrap();
+            crate::movie_mode::forget(&cfg);
+            cfg.designer_mode = on;
+            config::save_config(&cfg);
+            // Designer mode takes over from a timed pause or presentation
+            pause::end();
+            presentation::end();
+            let _ = KillTimer(Some(hwnd), PAUSE_TIMER_ID);
+            let _ = KillTimer(Some(hwnd), PRESENTATION_TIMER_ID);
+            overlay::set_suppressed(on);
+            if !on {
+                show_configured_dimming(&cfg);
+            }
+        }
+        state.ui.designer_mode = on;
+        tray::set_tooltip(hwnd, tray_tooltip(on));
+        show_toast(
+            hwnd,
+            if on {
+                "Designer mode on \u{2014} dimming paused"
+            } else {
+                "Designer mode off"
+            },
+        );
+    }
+}
+
+/// Open the "Why is my screen dark?" window for what is on screen now
+fn show_explainer(hwnd: HWND) {
+    unsafe {
+        if WND_STATE.is_null() {
+            return;
+        }
+        let cfg = (*WND_STATE).config.lock().unwrap().clone();
+        let live = Live {
+            dimming: overlay::is_visible(),
+            monitors: overlay::monitor_levels(),
+            night_boost: overlay::night_boost(),
+            movie_mode: crate::movie_mode::is_active(),
+        };
+        explainer::show(hwnd, &explain::explain(&cfg, &explain::last(), &live));
+    }
+}
+
+/// Apply a fix picked in the explainer window
+fn apply_fix(hwnd: HWND, fix: Fix) {
+    unsafe {
+        if WND_STATE.is_null() {
+            return;
+        }
+        let state = &mut *WND_STATE;
+        explain::note(Cause::Tray);
+        match fix {
+            Fix::TurnOff => {
+                if state.config.lock().unwrap().is_enabled {
+                    crate::do_toggle_dimmer(&state.config);
+                }
+            }
+            Fix::EndMovieMode => {
+                if crate::movie_mode::is_active() {
+                    crate::movie_mode::toggle(&state.config);
+                }
+            }
+            _ => {
+                let mut cfg = state.config.lock().unwrap();
+                if fix.apply(&mut cfg) {
+                    config::save_config(&cfg);
+                    overlay::set_night_boost(&cfg.night_boost);
+                }
+            }
+        }
+        sync_from_config(hwnd);
+        state.ui.night_boost = overlay::night_boost();
+        show_toast(hwnd, fix.done_message());
+    }
+}
+
+/// Check for updates in the background (Check Now button, tray menu),
+/// reporting through WM_APP + 10
+fn check_for_updates_now(hwnd: HWND) {
+    let config = unsafe {
+        if WND_STATE.is_null() {
+            return;
+        }
+        let state = &mut *WND_STATE;
+        state.ui.check_update_btn.disabled = true;
+        state.ui.update_status_text = "Checking...".into();
+        state.config.clone()
+    };
+    invalidate(hwnd);
+
+    // Run update check in background thread (always asks,
+    // even for a skipped or snoozed version)
+    let hwnd_val = hwnd.0 as isize;
+    shutdown::spawn("update-check", move || {
+        let result = updater::check_for_update_now(updater::APP_VERSION);
+        // No prompt on the way out
+        if shutdown::is_stopping() {
+            return;
+        }
+        match result {
+            updater::UpdateResult::UpdateAvailable { version, download_url, held_back, .. } => {
+                // Ask user if they want to auto-download (a staged release
+                // this machine isn't in yet: whether to get it anyway)
+                let install = match held_back {
+                    Some(percent) => updater::prompt_held_back_dialog(&version, percent, &config),
+                    None => updater::prompt_update_dialog(&version, &config),
+                };
+                if install {
+                    // Signal "downloading" to UI
+                    unsafe {
+                        let _ = PostMessageW(
+                            Some(HWND(hwnd_val as *mut _)),
+                            WM_APP + 10,
+                            WPARAM(3), // 3 = downloading
+                            LPARAM(0),
+                        );
+                    }
+                    match updater::download_update(&download_url) {
+                        Ok(path) => {
+                            let _ = updater::apply_update_and_relaunch(&path);
+                        }
+                        Err(_e) => {
+                            // Fallback: open releases page
+                            updater::open_url("https://github.com/KDSPL/savemyeyes/releases");
+                            unsafe {
+                                let _ = PostMessageW(
+                                    Some(HWND(hwnd_val as *mut _)),
+                                    WM_APP + 10,
+                                    WPARAM(4), // 4 = download failed
+                                    LPARAM(0),
+                                );
+                            }
+                        }
+                    }
+                } else {
+                    // User declined
+                    unsafe {
+                        let _ = PostMessageW(
+                            Some(HWND(hwnd_val as *mut _)),
+                            WM_APP + 10,
+                            WPARAM(0),
+                            LPARAM(0),
+                        );
+                    }
+                }
+            }
+            updater::UpdateResult::NoUpdate => {
+                unsafe {
+                    let _ = PostMessageW(
+                        Some(HWND(hwnd_val as *mut _)),
+                        WM_APP + 10,
+                        WPARAM(0),
+                        LPARAM(0),
+                    );
+                }
+            }
+            updater::UpdateResult::Error(_) => {
+                unsafe {
+                    let _ = PostMessageW(
+                        Some(HWND(hwnd_val as *mut _)),
+                        WM_APP + 10,
+                        WPARAM(2),
+                        LPARAM(0),
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Lift dimming for `minutes` (tray menu). The overlay is suppressed as in
+/// designer mode, with config untouched, until PAUSE_TIMER_ID fires.
+pub fn start_pause(hwnd: HWND, minutes: u64) {
+    let millis = pause::start(minutes).as_millis() as u32;
+    overlay::set_suppressed(true);
+    unsafe {
+        SetTimer(Some(hwnd), PAUSE_TIMER_ID, millis, None);
+    }
+    tray::set_tooltip(hwnd, "SaveMyEyes \u{2014} Dimming paused");
+    show_toast(hwnd, &pause::started_message(minutes));
+}
+
+/// End a timed pause early (tray menu)
+pub fn end_pause(hwnd: HWND) {
+    if pause::end() {
+        resume_from_pause(hwnd);
+    }
+}
+
+/// Bring back the configured dimming after a timed pause or presentation
+/// mode (unless designer mode or presentation mode still holds it off)
+fn resume_from_pause(hwnd: HWND) {
+    unsafe {
+        let _ = KillTimer(Some(hwnd), PAUSE_TIMER_ID);
+        if WND_STATE.is_null() {
+            return;
+        }
+        let state = &mut *WND_STATE;
+        let cfg = state.config.lock().unwrap();
+        if cfg.designer_mode || presentation::is_active() {
+            return;
+        }
+        overlay::set_suppressed(false);
+        show_configured_dimming(&cfg);
+        drop(cfg);
+        tray::set_tooltip(hwnd, tray_tooltip(false));
+        show_toast(hwnd, "Dimming resumed");
+    }
+}
+
+/// Lift dimming and keep the HUD, tray notifications and update prompts
+/// quiet for `minutes`, or until ended (tray menu). Takes over from a
+/// timed pause; the overlay is suppressed the same way.
+pub fn start_presentation(hwnd: HWND, minutes: Option<u64>) {
+    pause::end();
+    unsafe {
+        let _ = KillTimer(Some(hwnd), PAUSE_TIMER_ID);
+        let _ = KillTimer(Some(hwnd), PRESENTATION_TIMER_ID);
+        if let Some(duration) = presentation::start(minutes) {
+            let millis = duration.as_millis() as u32;
+            SetTimer(Some(hwnd), PRESENTATION_TIMER_ID, millis, None);
+        }
+    }
+    overlay::set_suppressed(true);
+    eprintln!("[ui] presentation mode on for {:?} min", minutes);
+    tray::set_tooltip(hwnd, "SaveMyEyes \u{2014} Presentation mode");
+}
+
+/// End presentation mode early (tray menu)
+pub fn end_presentation(hwnd: HWND) {
+    if presentation::end() {
+        resume_from_presentation(hwnd);
+    }
+}
+
+/// Bring back dimming and notifications after presentation mode
+fn resume_from_presentation(hwnd: HWND) {
+    unsafe {
+        let _ = KillTimer(Some(hwnd), PRESENTATION_TIMER_ID);
+    }
+    eprintln!("[ui] presentation mode over");
+    // A pause started meanwhile resumes on its own timer
+    if !pause::is_active() {
+        resume_from_pause(hwnd);
+    }
+}
+
+/// Tray tooltip text for the current designer mode state
+pub fn tray_tooltip(designer_mode: bool) -> &'static str {
+    if designer_mode {
+        "SaveMyEyes \u{2014} Designer mode (dimming paused)"
+    } else {
+        "SaveMyEyes"
+    }
+}
+
+/// Route config save failures to a tray notification. The hook fires once
+/// per session; a failure from before this call (startup) is reported now.
+pub fn watch_save_failures(hwnd: HWND) {
+    fn on_failure() {
+        let hwnd = MAIN_HWND.load(Ordering::SeqCst);
+        if hwnd != 0 {
+            unsafe {
+                let _ = PostMessageW(
+                    Some(HWND(hwnd as *mut _)),
+                    WM_CONFIG_SAVE_FAILED,
+                    WPARAM(0),
+                    LPARAM(0),
+                );
+            }
+        }
+    }
+    MAIN_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+    config::set_save_failure_hook(on_failure);
+}
+
+/// Tell the user what load_config had to correct in a hand-edited config.
+pub fn report_config_fixes(hwnd: HWND) {
+    let fixes = config::load_fixes();
+    if fixes.is_empty() {
+        return;
+    }
+    tray::show_warning(hwnd, "Settings corrected", &config::describe_load_fixes(&fixes));
+    show_toast(hwnd, "Some settings were invalid and have been reset");
+}
+
 /// Show a toast message
 pub fn show_toast(hwnd: HWND, message: &str) {
     unsafe {
@@ -188,6 +935,111 @@ pub fn show_toast(hwnd: HWND, message: &str) {
     }
 }
 
+/// Start recording a new shortcut for row `row` of the Shortcuts tab. The
+/// global hotkeys are off meanwhile, so pressing the current combo records
+/// it instead of running it.
+fn start_recording(hwnd: HWND, state: &mut WndState, row: usize) {
+    crate::hotkeys::end_level_chord();
+    crate::hotkeys::unregister_all();
+    state.ui.recording_shortcut = Some(row);
+    unsafe {
+        let _ = SetFocus(Some(hwnd));
+    }
+    invalidate(hwnd);
+}
+
+/// Stop recording and turn the global hotkeys back on, unless they are off
+/// in config. Returns false if one of them is taken by another app.
+fn stop_recording(hwnd: HWND, state: &mut WndState) -> bool {
+    if state.ui.recording_shortcut.take().is_none() {
+        return true;
+    }
+    invalidate(hwnd);
+    let disabled = state.config.lock().unwrap().disable_global_shortcuts;
+    disabled || crate::hotkeys::register_all()
+}
+
+/// Key `vk` pressed while recording. Returns the toast message, None for a
+/// lone modifier key.
+fn record_shortcut(hwnd: HWND, state: &mut WndState, vk: u16) -> Option<String> {
+    let row = state.ui.recording_shortcut?;
+    if crate::hotkeys::is_modifier(vk) {
+        return None;
+    }
+    let (action, _) = RECORDABLE[row];
+    let Some(combo) = crate::hotkeys::combo_from_key(vk) else {
+        return Some("That key can't be a shortcut".to_string());
+    };
+    // Esc alone cancels; with modifiers it is a shortcut like any other
+    if vk == VK_ESCAPE.0 && !combo.is_usable() {
+        stop_recording(hwnd, state);
+        return Some("Shortcut unchanged".to_string());
+    }
+    let keys = {
+        let mut cfg = state.config.lock().unwrap();
+        // Wrong combos keep recording, so the next try needs no click
+        if let Err(message) = cfg.set_key(action, combo) {
+            return Some(message);
+        }
+        config::save_config(&cfg);
+        cfg.key_bindings()
+    };
+    state.ui.shortcut_texts[row] = combo.to_string();
+    crate::hotkeys::set_keys(keys);
+    if stop_recording(hwnd, state) {
+        Some(format!("{}: {}", action.label(), combo))
+    } else {
+        Some(format!(
+            "{}: {}, but another app may already use it",
+            action.label(),
+            combo
+        ))
+    }
+}
+
+/// Put Unicode text on the clipboard
+fn copy_to_clipboard(hwnd: HWND, text: &str) -> bool {
+    use windows::Win32::Foundation::{GlobalFree, HANDLE};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    const CF_UNICODETEXT: u32 = 13;
+    let data = wide(text);
+
+    unsafe {
+        if OpenClipboard(Some(hwnd)).is_err() {
+            return false;
+        }
+        let _ = EmptyClipboard();
+
+        let ok = match GlobalAlloc(GMEM_MOVEABLE, data.len() * 2) {
+            Ok(hmem) => {
+                let dst = GlobalLock(hmem) as *mut u16;
+                if dst.is_null() {
+                    let _ = GlobalFree(Some(hmem));
+                    false
+                } else {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
+                    let _ = GlobalUnlock(hmem);
+                    // On success the clipboard owns the memory
+                    if SetClipboardData(CF_UNICODETEXT, Some(HANDLE(hmem.0))).is_ok() {
+                        true
+                    } else {
+                        let _ = GlobalFree(Some(hmem));
+                        false
+                    }
+                }
+            }
+            Err(_) => false,
+        };
+
+        let _ = CloseClipboard();
+        ok
+    }
+}
+
 /// Window procedure
 unsafe extern "system" fn wnd_proc(
     hwnd: HWND,
@@ -207,10 +1059,13 @@ unsafe extern "system" fn wnd_proc(
             let mem_dc = CreateCompatibleDC(Some(hdc));
             let mem_bmp = CreateCompatibleBitmap(hdc, client.right, client.bottom);
             let old_bmp = SelectObject(mem_dc, HGDIOBJ::from(mem_bmp));
+            // Same layout as the window, or the blit would mirror the text
+            SetLayout(mem_dc, DC_LAYOUT(GetLayout(hdc)));
 
             if !WND_STATE.is_null() {
                 let state = &mut *WND_STATE;
                 painting::paint(mem_dc, &client, &mut state.ui);
+                tooltip::sync(hwnd, &state.ui.tooltips);
             }
 
             // Blit to screen
@@ -233,6 +1088,12 @@ unsafe extern "system" fn wnd_proc(
             LRESULT(0)
         }
 
+        // The overlay watchdog found a window destroyed from outside
+        x if x == overlay::WM_OVERLAY_LOST => {
+            overlay::recreate();
+            LRESULT(0)
+        }
+
         WM_LBUTTONDOWN => {
             if WND_STATE.is_null() {
                 return DefWindowProcW(hwnd, msg, wparam, lparam);
@@ -241,13 +1102,20 @@ unsafe extern "system" fn wnd_proc(
             let x = (lparam.0 & 0xFFFF) as i16 as i32;
             let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
 
+            // Any click ends a shortcut recording
+            if state.ui.recording_shortcut.is_some() {
+                stop_recording(hwnd, state);
+                return LRESULT(0);
+            }
+
             // Tab clicks
-            for i in 0..3 {
+            for i in 0..state.ui.tab_rects.len() {
                 if point_in_rect(x, y, &state.ui.tab_rects[i]) {
                     state.ui.active_tab = match i {
                         0 => Tab::Dimmer,
                         1 => Tab::Settings,
-                        _ => Tab::Shortcuts,
+                        2 => Tab::Shortcuts,
+                        _ => Tab::Advanced,
                     };
                     invalidate(hwnd);
                     return LRESULT(0);
@@ -259,45 +1127,204 @@ unsafe extern "system" fn wnd_proc(
                 && point_in_rect(x, y, &state.ui.slider.thumb_rect)
                 && !state.ui.multi_monitor_enabled
             {
+                explain::note(Cause::Settings);
                 state.ui.slider.dragging = true;
                 SetCapture(hwnd);
-                let val = state.ui.slider.value_from_x(x);
+                let val = state.ui.slider.value_from_x(x, fine_mode());
                 state.ui.slider.value = val;
                 invalidate(hwnd);
                 return LRESULT(0);
             }
 
-            // Multi-monitor slider drag
+            // Monitor groups: a monitor's number ticks it for grouping, the
+            // Group button groups the ticked ones, Ungroup splits a group
             if state.ui.active_tab == Tab::Dimmer && state.ui.multi_monitor_enabled {
-                for i in 0..state.ui.monitor_sliders.len() {
-                    if point_in_rect(x, y, &state.ui.monitor_sliders[i].thumb_rect) {
-                        state.ui.monitor_sliders[i].dragging = true;
-                        SetCapture(hwnd);
-                        let val = state.ui.monitor_sliders[i].value_from_x(x);
-                        state.ui.monitor_sliders[i].value = val;
-                        invalidate(hwnd);
-                        return LRESULT(0);
-                    }
-                }
-            }
-
-            // Dimmer toggle
-            if state.ui.active_tab == Tab::Dimmer
-                && point_in_rect(x, y, &state.ui.enabled_toggle.rect)
-            {
-                state.ui.enabled_toggle.checked = !state.ui.enabled_toggle.checked;
-                let enabled = state.ui.enabled_toggle.checked;
+                let label_hit = state
+                    .ui
+                    .monitor_label_rects
+                    .iter()
+                    .position(|r| point_in_rect(x, y, r));
+                let ungroup_hit = state
+                    .ui
+                    .group_rows
+                    .iter()
+                    .position(|row| point_in_rect(x, y, &row.ungroup_rect));
+                let group_hit = point_in_rect(x, y, &state.ui.group_btn.rect);
+                if (label_hit.is_some() || ungroup_hit.is_some() || group_hit)
+                    && config::is_locked("monitor_groups")
                 {
+                    show_toast(hwnd, MANAGED_SETTING_TOAST);
+                    return LRESULT(0);
+                }
+                if let Some(ticked) = label_hit.and_then(|i| state.ui.monitor_selected.get_mut(i)) {
+                    *ticked = !*ticked;
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+                if group_hit && !state.ui.group_btn.disabled {
+                    let picked: Vec<_> = state
+                        .ui
+                        .monitor_ids
+                        .iter()
+                        .zip(&state.ui.monitor_selected)
+                        .filter(|(_, &ticked)| ticked)
+                        .map(|(id, _)| id.clone())
+                        .collect();
                     let mut cfg = state.config.lock().unwrap();
-                    cfg.is_enabled = enabled;
+                    if let Some(name) = monitor_groups::group(&mut cfg.monitor_groups, &picked) {
+                        // Members start from the level of the first one
+                        let level = cfg.level_for(&picked[0]);
+                        cfg.set_level_for(&picked[0], level);
+                        config::save_config(&cfg);
+                        if overlay::is_visible() {
+                            for (i, id) in state.ui.monitor_ids.iter().enumerate() {
+                                if picked.contains(id) {
+                                    overlay::set_monitor_opacity(i as u32, level);
+                                }
+                            }
+                        }
+                        for (i, id) in state.ui.monitor_ids.iter().enumerate() {
+                            if picked.contains(id) {
+                                state.ui.monitor_sliders[i].value = dimming::to_steps(level);
+                            }
+                        }
+                        state.ui.monitor_selected.fill(false);
+                        rebuild_group_rows(&mut state.ui, &cfg);
+                        drop(cfg);
+                        show_toast(hwnd, &format!("{}: {} monitors", name, picked.len()));
+                    }
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+                if let Some(g) = ungroup_hit {
+                    let name = state.ui.group_rows[g].name.clone();
+                    let mut cfg = state.config.lock().unwrap();
+                    monitor_groups::ungroup(&mut cfg.monitor_groups, &name);
+                    config::save_config(&cfg);
+                    rebuild_group_rows(&mut state.ui, &cfg);
+                    drop(cfg);
+                    show_toast(hwnd, &format!("{}: ungrouped", name));
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+
+                // Group slider drag
+                let slider_hit = state
+                    .ui
+                    .group_rows
+                    .iter()
+                    .position(|row| point_in_rect(x, y, &row.slider.thumb_rect));
+                if let Some(g) = slider_hit {
+                    explain::note(Cause::Settings);
+                    let row = &mut state.ui.group_rows[g];
+                    row.slider.dragging = true;
+                    SetCapture(hwnd);
+                    let (first, val) = (row.members[0], row.slider.value_from_x(x, fine_mode()));
+                    preview_linked(state, first, val);
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+            }
+
+            // Per-monitor capture pill
+            if state.ui.active_tab == Tab::Dimmer && state.ui.multi_monitor_enabled {
+                let hit = state.ui.monitor_capture_rects.iter().position(|r| point_in_rect(x, y, r));
+                if let Some(i) = hit {
+                    let allow = !state.ui.monitor_capture.get(i).copied().unwrap_or(false);
+                    if let Some(message) = set_monitor_capture(hwnd, i as u32, allow) {
+                        show_toast(hwnd, &message);
+                    }
+                    return LRESULT(0);
+                }
+            }
+
+            // Per-monitor reading pill: override the orientation
+            if state.ui.active_tab == Tab::Dimmer
+                && state.ui.multi_monitor_enabled
+                && state.ui.reading_monitor_toggle.checked
+            {
+                let hit = state
+                    .ui
+                    .monitor_reading_rects
+                    .iter()
+                    .position(|r| point_in_rect(x, y, r));
+                if let Some(i) = hit {
+                    let reading = !state.ui.monitor_reading.get(i).copied().unwrap_or(false);
+                    if state.ui.monitor_reading.len() <= i {
+                        state.ui.monitor_reading.resize(i + 1, false);
+                    }
+                    state.ui.monitor_reading[i] = reading;
+                    if let Some(id) = state.ui.monitor_ids.get(i) {
+                        let mut cfg = state.config.lock().unwrap();
+                        cfg.reading_monitor.monitors.insert(id.clone(), reading);
+                        config::save_config(&cfg);
+                        overlay::set_reading_monitor(&cfg.reading_monitor);
+                        state.ui.reading_monitor_summary = cfg.reading_monitor.summary();
+                    }
+                    let name = state.ui.monitor_names.get(i).cloned().unwrap_or_default();
+                    show_toast(
+                        hwnd,
+                        &if reading {
+                            format!("{}: dimmed as a reading monitor", name)
+                        } else {
+                            format!("{}: dimmed like the others", name)
+                        },
+                    );
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+            }
+
+            // Multi-monitor slider drag
+            if state.ui.active_tab == Tab::Dimmer && state.ui.multi_monitor_enabled {
+                for i in 0..state.ui.monitor_sliders.len() {
+                    if point_in_rect(x, y, &state.ui.monitor_sliders[i].thumb_rect) {
+                        explain::note(Cause::Settings);
+                        state.ui.monitor_sliders[i].dragging = true;
+                        SetCapture(hwnd);
+                        let val = state.ui.monitor_sliders[i].value_from_x(x, fine_mode());
+                        preview_linked(state, i, val);
+                        invalidate(hwnd);
+                        return LRESULT(0);
+                    }
+                }
+            }
+
+            // Backlight slider drag
+            if state.ui.active_tab == Tab::Dimmer {
+                if let Some(row) = state
+                    .ui
+                    .backlights
+                    .iter_mut()
+                    .find(|row| point_in_rect(x, y, &row.thumb_rect))
+                {
+                    row.dragging = true;
+                    SetCapture(hwnd);
+                    row.percent = row.percent_from_x(x);
+                    ddcci::set(&row.id, row.percent);
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+            }
+
+            // Dimmer toggle
+            if state.ui.active_tab == Tab::Dimmer
+                && point_in_rect(x, y, &state.ui.enabled_toggle.rect)
+            {
+                explain::note(Cause::Settings);
+                state.ui.enabled_toggle.checked = !state.ui.enabled_toggle.checked;
+                let enabled = state.ui.enabled_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.is_enabled = enabled;
                     config::save_config(&cfg);
                     if enabled {
                         if cfg.multi_monitor {
-                            overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
+                            overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
                         }
-                        overlay::show_overlay(cfg.opacity, false);
+                        overlay::fade_in(cfg.opacity, false, &cfg.fade);
                     } else {
-                        overlay::hide_overlay();
+                        overlay::fade_out(&cfg.fade);
                     }
                 }
                 show_toast(
@@ -316,6 +1343,10 @@ unsafe extern "system" fn wnd_proc(
             if state.ui.active_tab == Tab::Settings {
                 // Autostart toggle
                 if point_in_rect(x, y, &state.ui.autostart_toggle.rect) {
+                    if config::is_locked("launch_on_login") {
+                        show_toast(hwnd, MANAGED_SETTING_TOAST);
+                        return LRESULT(0);
+                    }
                     state.ui.autostart_toggle.checked = !state.ui.autostart_toggle.checked;
                     let enabled = state.ui.autostart_toggle.checked;
                     if enabled {
@@ -333,151 +1364,708 @@ unsafe extern "system" fn wnd_proc(
                     return LRESULT(0);
                 }
 
-                // Auto-update toggle
-                if point_in_rect(x, y, &state.ui.auto_update_toggle.rect) {
-                    state.ui.auto_update_toggle.checked = !state.ui.auto_update_toggle.checked;
-                    let enabled = state.ui.auto_update_toggle.checked;
-                    {
-                        let mut cfg = state.config.lock().unwrap();
-                        cfg.auto_update = enabled;
-                        config::save_config(&cfg);
-                    }
-                    show_toast(
-                        hwnd,
-                        if enabled {
-                            "Auto-update enabled"
-                        } else {
-                            "Auto-update disabled"
-                        },
-                    );
-                    invalidate(hwnd);
-                    return LRESULT(0);
+                // Auto-update toggle
+                if point_in_rect(x, y, &state.ui.auto_update_toggle.rect) {
+                    if config::is_locked("auto_update") {
+                        show_toast(hwnd, MANAGED_SETTING_TOAST);
+                        return LRESULT(0);
+                    }
+                    state.ui.auto_update_toggle.checked = !state.ui.auto_update_toggle.checked;
+                    let enabled = state.ui.auto_update_toggle.checked;
+                    {
+                        let mut cfg = state.config.lock().unwrap();
+                        cfg.auto_update = enabled;
+                        config::save_config(&cfg);
+                    }
+                    show_toast(
+                        hwnd,
+                        if enabled {
+                            "Auto-update enabled"
+                        } else {
+                            "Auto-update disabled"
+                        },
+                    );
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+
+                // Multi-monitor toggle
+                if point_in_rect(x, y, &state.ui.multi_monitor_toggle.rect) {
+                    if !state.ui.capabilities.per_monitor {
+                        show_toast(hwnd, Feature::PerMonitor.unsupported_reason());
+                        return LRESULT(0);
+                    }
+                    state.ui.multi_monitor_toggle.checked = !state.ui.multi_monitor_toggle.checked;
+                    let enabled = state.ui.multi_monitor_toggle.checked;
+                    state.ui.multi_monitor_enabled = enabled;
+                    {
+                        let mut cfg = state.config.lock().unwrap();
+                        cfg.multi_monitor = enabled;
+                        if enabled {
+                            // Initialize per-monitor opacities from global if not set
+                            rebuild_monitor_cards(&mut state.ui, &cfg);
+                            for id in &state.ui.monitor_ids {
+                                let default_opacity = cfg.opacity;
+                                cfg.monitor_opacity.entry(id.clone()).or_insert(default_opacity);
+                            }
+                            // Apply per-monitor opacities to overlay
+                            overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
+                            if cfg.is_enabled {
+                                overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+                            }
+                        } else {
+                            // Revert to global opacity
+                            if cfg.is_enabled && overlay::is_visible() {
+                                overlay::set_opacity(cfg.opacity);
+                            }
+                        }
+                        config::save_config(&cfg);
+                    }
+                    show_toast(
+                        hwnd,
+                        if enabled {
+                            "Multi-monitor mode enabled"
+                        } else {
+                            "Multi-monitor mode disabled"
+                        },
+                    );
+                    invalidate(hwnd);
+                    return LRESULT(0);
+                }
+
+                // Check Now button
+                if point_in_rect(x, y, &state.ui.check_update_btn.rect)
+                    && !state.ui.check_update_btn.disabled
+                {
+                    check_for_updates_now(hwnd);
+                    return LRESULT(0);
+                }
+            }
+
+            // Perceptual scale toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.perceptual_toggle.rect)
+            {
+                state.ui.perceptual_toggle.checked = !state.ui.perceptual_toggle.checked;
+                let curve = if state.ui.perceptual_toggle.checked {
+                    OpacityCurve::Perceptual
+                } else {
+                    OpacityCurve::Linear
+                };
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    // Stored levels are converted so the screen looks the same
+                    dimming::switch_curve(&mut cfg, curve);
+                    config::save_config(&cfg);
+                    overlay::set_curve(curve);
+                    if cfg.is_enabled {
+                        if cfg.multi_monitor {
+                            overlay::set_per_monitor_opacities(&monitors::levels_by_index(&cfg));
+                        }
+                        overlay::show_overlay(cfg.opacity, cfg.allow_capture);
+                    }
+                }
+                sync_from_config(hwnd);
+                show_toast(
+                    hwnd,
+                    if curve == OpacityCurve::Perceptual {
+                        "Perceptual scale on"
+                    } else {
+                        "Perceptual scale off"
+                    },
+                );
+                return LRESULT(0);
+            }
+
+            // Shortcut recorder
+            let shortcut_hit = state
+                .ui
+                .shortcut_rects
+                .iter()
+                .position(|r| point_in_rect(x, y, r));
+            if let (Tab::Shortcuts, Some(row)) = (state.ui.active_tab, shortcut_hit) {
+                if config::is_locked(RECORDABLE[row].1) {
+                    show_toast(hwnd, MANAGED_SETTING_TOAST);
+                    return LRESULT(0);
+                }
+                start_recording(hwnd, state, row);
+                return LRESULT(0);
+            }
+
+            // Mouse button hotkeys toggle
+            if state.ui.active_tab == Tab::Shortcuts
+                && point_in_rect(x, y, &state.ui.mouse_hotkeys_toggle.rect)
+            {
+                state.ui.mouse_hotkeys_toggle.checked = !state.ui.mouse_hotkeys_toggle.checked;
+                let enabled = state.ui.mouse_hotkeys_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.mouse_hotkeys.enabled = enabled;
+                    config::save_config(&cfg);
+                    mouse_hooks::apply(hwnd, &cfg.mouse_hotkeys);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Mouse button shortcuts on"
+                    } else {
+                        "Mouse button shortcuts off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Hotkey-less mode toggle
+            if state.ui.active_tab == Tab::Shortcuts
+                && point_in_rect(x, y, &state.ui.disable_shortcuts_toggle.rect)
+            {
+                if config::is_locked("disable_global_shortcuts") {
+                    show_toast(hwnd, MANAGED_SETTING_TOAST);
+                    return LRESULT(0);
+                }
+                state.ui.disable_shortcuts_toggle.checked =
+                    !state.ui.disable_shortcuts_toggle.checked;
+                let disabled = state.ui.disable_shortcuts_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.disable_global_shortcuts = disabled;
+                    config::save_config(&cfg);
+                }
+                if disabled {
+                    crate::hotkeys::end_level_chord();
+                    crate::hotkeys::unregister_all();
+                    mouse_hooks::set_blocked(true);
+                } else {
+                    mouse_hooks::set_blocked(false);
+                    crate::hotkeys::register_all();
+                }
+                show_toast(
+                    hwnd,
+                    if disabled {
+                        "Global shortcuts disabled"
+                    } else {
+                        "Global shortcuts enabled"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // New custom action
+            if state.ui.active_tab == Tab::Shortcuts
+                && point_in_rect(x, y, &state.ui.new_action_btn.rect)
+            {
+                if state.ui.new_action_btn.disabled {
+                    return LRESULT(0);
+                }
+                let message = {
+                    let mut cfg = state.config.lock().unwrap();
+                    let action = CustomAction::new(&cfg.custom_actions);
+                    let message = if action.hotkey.is_empty() {
+                        format!("{} added", action.name)
+                    } else {
+                        format!("{} added, runs on {}", action.name, action.hotkey)
+                    };
+                    cfg.custom_actions.push(action);
+                    config::save_config(&cfg);
+                    crate::hotkeys::set_custom_actions(&cfg.custom_actions);
+                    sync_custom_actions(&mut state.ui, &cfg);
+                    message
+                };
+                show_toast(hwnd, &message);
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Custom action dropdowns
+            let edit_hit = state
+                .ui
+                .custom_actions
+                .iter()
+                .position(|row| point_in_rect(x, y, &row.edit_btn.rect));
+            if let (Tab::Shortcuts, Some(index)) = (state.ui.active_tab, edit_hit) {
+                let rect = state.ui.custom_actions[index].edit_btn.rect;
+                let mut at = POINT {
+                    x: rect.left,
+                    y: rect.bottom,
+                };
+                let _ = ClientToScreen(hwnd, &mut at);
+                let edit = {
+                    let cfg = state.config.lock().unwrap();
+                    crate::custom_actions::edit_menu(hwnd, &cfg, index, at)
+                };
+                let Some(edit) = edit else {
+                    return LRESULT(0);
+                };
+                let message = {
+                    let mut cfg = state.config.lock().unwrap();
+                    let message = edit.apply(&mut cfg.custom_actions, index);
+                    config::save_config(&cfg);
+                    crate::hotkeys::set_custom_actions(&cfg.custom_actions);
+                    sync_custom_actions(&mut state.ui, &cfg);
+                    message
+                };
+                if let Some(message) = message {
+                    show_toast(hwnd, &message);
+                }
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Level chord toggle
+            if state.ui.active_tab == Tab::Shortcuts
+                && point_in_rect(x, y, &state.ui.level_chord_toggle.rect)
+            {
+                state.ui.level_chord_toggle.checked = !state.ui.level_chord_toggle.checked;
+                let enabled = state.ui.level_chord_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.level_chord = enabled;
+                    config::save_config(&cfg);
+                }
+                if !enabled {
+                    crate::hotkeys::end_level_chord();
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Set level chord on"
+                    } else {
+                        "Set level chord off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Pen pause toggle
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.pen_pause_toggle.rect)
+            {
+                state.ui.pen_pause_toggle.checked = !state.ui.pen_pause_toggle.checked;
+                let enabled = state.ui.pen_pause_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.pen_pause.enabled = enabled;
+                    config::save_config(&cfg);
+                    mouse_hooks::set_pen_watch(enabled);
+                    overlay::set_pen_pause(&cfg.pen_pause);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Pause while drawing on"
+                    } else {
+                        "Pause while drawing off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Night boost toggle
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.night_boost_toggle.rect)
+            {
+                state.ui.night_boost_toggle.checked = !state.ui.night_boost_toggle.checked;
+                let enabled = state.ui.night_boost_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.night_boost.enabled = enabled;
+                    config::save_config(&cfg);
+                    overlay::set_night_boost(&cfg.night_boost);
+                }
+                state.ui.night_boost = overlay::night_boost();
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Night boost on"
+                    } else {
+                        "Night boost off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Brightness rule toggle
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.brightness_rule_toggle.rect)
+            {
+                state.ui.brightness_rule_toggle.checked = !state.ui.brightness_rule_toggle.checked;
+                let enabled = state.ui.brightness_rule_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.brightness_rule.enabled = enabled;
+                    config::save_config(&cfg);
+                    crate::brightness::apply(&cfg.brightness_rule);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Dimming bright panels only"
+                    } else {
+                        "Dimming at any panel brightness"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Contrast guard toggle
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.contrast_guard_toggle.rect)
+            {
+                state.ui.contrast_guard_toggle.checked = !state.ui.contrast_guard_toggle.checked;
+                let enabled = state.ui.contrast_guard_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.contrast_guard.enabled = enabled;
+                    config::save_config(&cfg);
+                    crate::contrast_guard::apply(&cfg.contrast_guard);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Dimming less over dark content"
+                    } else {
+                        "Dimming dark content fully"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Scheduled dimming toggle
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.schedule_toggle.rect)
+            {
+                state.ui.schedule_toggle.checked = !state.ui.schedule_toggle.checked;
+                let enabled = state.ui.schedule_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.schedule.enabled = enabled;
+                    config::save_config(&cfg);
+                    scheduler::apply(&cfg.schedule);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Dimming schedule on"
+                    } else {
+                        "Dimming schedule off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Scheduled dimming day chips
+            let chips = &state.ui.schedule_day_rects;
+            let day_chip = chips.iter().position(|r| point_in_rect(x, y, r));
+            if let Some(i) = day_chip.filter(|_| state.ui.active_tab == Tab::Settings) {
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.schedule.toggle_day(scheduler::WEEK[i]);
+                    config::save_config(&cfg);
+                    scheduler::apply(&cfg.schedule);
+                    state.ui.schedule_days[i] = cfg.schedule.days.contains(&scheduler::WEEK[i]);
+                    state.ui.schedule_summary = cfg.schedule.summary();
+                }
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Restore settings from backup
+            if state.ui.active_tab == Tab::Settings
+                && point_in_rect(x, y, &state.ui.restore_backup_rect)
+            {
+                let list = savemyeyes_shared::backups::list();
+                if list.is_empty() {
+                    show_toast(hwnd, "No backups yet");
+                    return LRESULT(0);
+                }
+                let Some(backup) = crate::backups::pick(hwnd, &list) else {
+                    return LRESULT(0);
+                };
+                let current = state.config.lock().unwrap().clone();
+                match crate::backups::confirm(hwnd, &current, &backup) {
+                    Ok(true) => {}
+                    Ok(false) => return LRESULT(0),
+                    Err(e) => {
+                        eprintln!("[ui] unreadable backup {}: {}", backup.path.display(), e);
+                        show_toast(hwnd, "That backup can't be read");
+                        return LRESULT(0);
+                    }
+                }
+                match savemyeyes_shared::backups::restore(&backup) {
+                    Ok(restored) => {
+                        // A late save from this instance must not undo it
+                        *state.config.lock().unwrap() = restored;
+                        if crate::backups::relaunch() {
+                            PostQuitMessage(0);
+                        } else {
+                            show_toast(hwnd, "Restored; restart SaveMyEyes to apply");
+                        }
+                    }
+                    Err(e) => tray::show_warning(hwnd, "Restore Failed", &e),
+                }
+                return LRESULT(0);
+            }
+
+            // Magnifier compatibility toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.zoom_compat_toggle.rect)
+            {
+                state.ui.zoom_compat_toggle.checked = !state.ui.zoom_compat_toggle.checked;
+                let enabled = state.ui.zoom_compat_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.zoom_compat = enabled;
+                    config::save_config(&cfg);
+                }
+                overlay::set_zoom_compat(enabled);
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Magnifier compatibility on"
+                    } else {
+                        "Magnifier compatibility off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Overlay gradient toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.gradient_toggle.rect)
+            {
+                let gradient = {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.overlay_gradient.enabled = !cfg.overlay_gradient.enabled;
+                    config::save_config(&cfg);
+                    sync_gradient_controls(&mut state.ui, &cfg);
+                    cfg.overlay_gradient
+                };
+                overlay::set_gradient(&gradient);
+                show_toast(
+                    hwnd,
+                    if gradient.enabled {
+                        "Gradient on"
+                    } else {
+                        "Gradient off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Gradient edge levels
+            let top_hit = point_in_rect(x, y, &state.ui.gradient_top_btn.rect);
+            if state.ui.active_tab == Tab::Advanced
+                && (top_hit || point_in_rect(x, y, &state.ui.gradient_bottom_btn.rect))
+            {
+                let gradient = {
+                    let mut cfg = state.config.lock().unwrap();
+                    let edges = &mut cfg.overlay_gradient;
+                    if top_hit {
+                        edges.top = gradient::next_step(&gradient::TOP_STEPS, edges.top);
+                    } else {
+                        edges.bottom = gradient::next_step(&gradient::BOTTOM_STEPS, edges.bottom);
+                    }
+                    config::save_config(&cfg);
+                    sync_gradient_controls(&mut state.ui, &cfg);
+                    cfg.overlay_gradient
+                };
+                overlay::set_gradient(&gradient);
+                show_toast(hwnd, &format!("Gradient: {}", gradient.label()));
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Windows color filters toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.color_filters_toggle.rect)
+            {
+                state.ui.color_filters_toggle.checked = !state.ui.color_filters_toggle.checked;
+                let enabled = state.ui.color_filters_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.color_filters = enabled;
+                    config::save_config(&cfg);
+                    // Bring the filter in line with the profile in use
+                    if let Some(name) = cfg.active_profile.clone() {
+                        crate::color_filter::follow_profile(&cfg, &name);
+                    }
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Color filters follow profiles"
+                    } else {
+                        "Color filters left alone"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Reading monitors toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.reading_monitor_toggle.rect)
+            {
+                state.ui.reading_monitor_toggle.checked = !state.ui.reading_monitor_toggle.checked;
+                let enabled = state.ui.reading_monitor_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.reading_monitor.enabled = enabled;
+                    config::save_config(&cfg);
+                    overlay::set_reading_monitor(&cfg.reading_monitor);
+                    rebuild_monitor_cards(&mut state.ui, &cfg);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Portrait monitors dimmed as reading monitors"
+                    } else {
+                        "Reading monitors off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Edge vignette toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.vignette_toggle.rect)
+            {
+                state.ui.vignette_toggle.checked = !state.ui.vignette_toggle.checked;
+                let enabled = state.ui.vignette_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    cfg.edge_vignette.enabled = enabled;
+                    config::save_config(&cfg);
+                    overlay::set_edge_vignette(&cfg.edge_vignette);
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Screen edges dimmed more"
+                    } else {
+                        "Edge vignette off"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Follow system theme toggle
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.theme_auto_toggle.rect)
+            {
+                let enabled = !state.ui.theme_auto_toggle.checked;
+                {
+                    let mut cfg = state.config.lock().unwrap();
+                    if enabled {
+                        // Seeds Dark/Light profiles on first use
+                        profiles::enable_theme_automation(&mut cfg);
+                    } else {
+                        cfg.theme_automation.enabled = false;
+                    }
+                    config::save_config(&cfg);
+                    sync_theme_controls(&mut state.ui, &cfg);
+                }
+                if enabled {
+                    apply_theme_profile(hwnd, system_theme::is_dark());
+                }
+                show_toast(
+                    hwnd,
+                    if enabled {
+                        "Following system theme"
+                    } else {
+                        "No longer following system theme"
+                    },
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Theme automation profile pickers
+            let dark_hit = point_in_rect(x, y, &state.ui.theme_dark_btn.rect);
+            if state.ui.active_tab == Tab::Advanced
+                && (dark_hit || point_in_rect(x, y, &state.ui.theme_light_btn.rect))
+            {
+                let name = {
+                    let mut cfg = state.config.lock().unwrap();
+                    let current = cfg.theme_automation.profile_for(dark_hit).map(str::to_string);
+                    let next = profiles::next_choice(&cfg, current.as_deref());
+                    if dark_hit {
+                        cfg.theme_automation.dark_profile = next.clone();
+                    } else {
+                        cfg.theme_automation.light_profile = next.clone();
+                    }
+                    config::save_config(&cfg);
+                    sync_theme_controls(&mut state.ui, &cfg);
+                    next
+                };
+                // Re-apply if the picker for the current theme changed
+                if system_theme::is_dark() == dark_hit {
+                    apply_theme_profile(hwnd, dark_hit);
+                }
+                show_toast(
+                    hwnd,
+                    &format!(
+                        "{} mode: {}",
+                        if dark_hit { "Dark" } else { "Light" },
+                        name.as_deref().unwrap_or("no change")
+                    ),
+                );
+                invalidate(hwnd);
+                return LRESULT(0);
+            }
+
+            // Copy display report
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.copy_report_btn.rect)
+            {
+                let report = {
+                    let cfg = state.config.lock().unwrap();
+                    monitors::display_report(&cfg)
+                };
+                if copy_to_clipboard(hwnd, &report) {
+                    show_toast(hwnd, "Display report copied");
+                } else {
+                    show_toast(hwnd, "Could not access clipboard");
                 }
+                return LRESULT(0);
+            }
 
-                // Multi-monitor toggle
-                if point_in_rect(x, y, &state.ui.multi_monitor_toggle.rect) {
-                    state.ui.multi_monitor_toggle.checked = !state.ui.multi_monitor_toggle.checked;
-                    let enabled = state.ui.multi_monitor_toggle.checked;
-                    state.ui.multi_monitor_enabled = enabled;
+            // Relaunch elevated
+            if state.ui.active_tab == Tab::Advanced
+                && point_in_rect(x, y, &state.ui.elevate_btn.rect)
+                && !state.ui.elevate_btn.disabled
+            {
+                if elevation::confirm_relaunch(hwnd) {
+                    // Hand the current state over to the elevated instance
                     {
-                        let mut cfg = state.config.lock().unwrap();
-                        cfg.multi_monitor = enabled;
-                        if enabled {
-                            // Initialize per-monitor opacities from global if not set
-                            let mon_count = overlay::enumerate_monitor_count();
-                            state.ui.monitor_count = mon_count;
-                            state.ui.monitor_sliders.clear();
-                            for i in 0..mon_count {
-                                let opacity = cfg.per_monitor_opacity.get(&i).copied().unwrap_or(cfg.opacity);
-                                let mut s = controls::SliderState::new((opacity * 100.0).round() as i32);
-                                s.monitor_index = Some(i);
-                                state.ui.monitor_sliders.push(s);
-                                let default_opacity = cfg.opacity;
-                                cfg.per_monitor_opacity.entry(i).or_insert(default_opacity);
-                            }
-                            // Apply per-monitor opacities to overlay
-                            overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
-                            if cfg.is_enabled {
-                                overlay::show_overlay(cfg.opacity, cfg.allow_capture);
-                            }
-                        } else {
-                            // Revert to global opacity
-                            if cfg.is_enabled && overlay::is_visible() {
-                                overlay::set_opacity(cfg.opacity);
-                            }
-                        }
+                        let cfg = state.config.lock().unwrap();
                         config::save_config(&cfg);
                     }
-                    show_toast(
-                        hwnd,
-                        if enabled {
-                            "Multi-monitor mode enabled"
-                        } else {
-                            "Multi-monitor mode disabled"
-                        },
-                    );
-                    invalidate(hwnd);
-                    return LRESULT(0);
+                    if elevation::relaunch_elevated() {
+                        PostQuitMessage(0);
+                    } else {
+                        show_toast(hwnd, "Elevation cancelled");
+                    }
                 }
+                return LRESULT(0);
+            }
 
-                // Check Now button
-                if point_in_rect(x, y, &state.ui.check_update_btn.rect)
-                    && !state.ui.check_update_btn.disabled
-                {
-                    state.ui.check_update_btn.disabled = true;
-                    state.ui.update_status_text = "Checking...".into();
-                    invalidate(hwnd);
-
-                    // Run update check in background thread
-                    let hwnd_val = hwnd.0 as isize;
-                    std::thread::spawn(move || {
-                        let result = updater::check_for_update(updater::APP_VERSION);
-                        match result {
-                            updater::UpdateResult::UpdateAvailable { version, download_url, .. } => {
-                                // Ask user if they want to auto-download
-                                if updater::prompt_update_dialog(&version) {
-                                    // Signal "downloading" to UI
-                                    unsafe {
-                                        let _ = PostMessageW(
-                                            Some(HWND(hwnd_val as *mut _)),
-                                            WM_APP + 10,
-                                            WPARAM(3), // 3 = downloading
-                                            LPARAM(0),
-                                        );
-                                    }
-                                    match updater::download_update(&download_url) {
-                                        Ok(path) => {
-                                            let _ = updater::apply_update_and_relaunch(&path);
-                                        }
-                                        Err(_e) => {
-                                            // Fallback: open releases page
-                                            updater::open_url("https://github.com/KDSPL/savemyeyes/releases");
-                                            unsafe {
-                                                let _ = PostMessageW(
-                                                    Some(HWND(hwnd_val as *mut _)),
-                                                    WM_APP + 10,
-                                                    WPARAM(4), // 4 = download failed
-                                                    LPARAM(0),
-                                                );
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    // User declined
-                                    unsafe {
-                                        let _ = PostMessageW(
-                                            Some(HWND(hwnd_val as *mut _)),
-                                            WM_APP + 10,
-                                            WPARAM(0),
-                                            LPARAM(0),
-                                        );
-                                    }
-                                }
-                            }
-                            updater::UpdateResult::NoUpdate => {
-                                unsafe {
-                                    let _ = PostMessageW(
-                                        Some(HWND(hwnd_val as *mut _)),
-                                        WM_APP + 10,
-                                        WPARAM(0),
-                                        LPARAM(0),
-                                    );
-                                }
-                            }
-                            updater::UpdateResult::Error(_) => {
-                                unsafe {
-                                    let _ = PostMessageW(
-                                        Some(HWND(hwnd_val as *mut _)),
-                                        WM_APP + 10,
-                                        WPARAM(2),
-                                        LPARAM(0),
-                                    );
-                                }
-                            }
-                        }
-                    });
-                    return LRESULT(0);
-                }
+            // Designer mode badge: click to exit
+            if state.ui.designer_mode && point_in_rect(x, y, &state.ui.designer_badge_rect) {
+                set_designer_mode(hwnd, false);
+                return LRESULT(0);
             }
 
             // Credit link
@@ -499,7 +2087,7 @@ unsafe extern "system" fn wnd_proc(
                     let val = state.ui.slider.value;
                     {
                         let mut cfg = state.config.lock().unwrap();
-                        cfg.opacity = val as f32 / 100.0;
+                        cfg.opacity = dimming::from_steps(val);
                         config::save_config(&cfg);
                         if overlay::is_visible() {
                             overlay::set_opacity(cfg.opacity);
@@ -516,36 +2104,44 @@ unsafe extern "system" fn wnd_proc(
                     invalidate(hwnd);
                 }
 
-                // Multi-monitor slider release
+                // Multi-monitor slider release (the monitors grouped with
+                // it follow)
                 for i in 0..state.ui.monitor_sliders.len() {
                     if state.ui.monitor_sliders[i].dragging {
                         state.ui.monitor_sliders[i].dragging = false;
                         let _ = ReleaseCapture();
-
                         let val = state.ui.monitor_sliders[i].value;
-                        let mon_idx = i as u32;
-                        {
-                            let mut cfg = state.config.lock().unwrap();
-                            let opacity = val as f32 / 100.0;
-                            cfg.per_monitor_opacity.insert(mon_idx, opacity);
-                            config::save_config(&cfg);
-                            if overlay::is_visible() {
-                                overlay::set_monitor_opacity(mon_idx, opacity);
-                            }
-                            // Auto-enable dimmer
-                            if !cfg.is_enabled && val > 0 {
-                                cfg.is_enabled = true;
-                                state.ui.enabled_toggle.checked = true;
-                                config::save_config(&cfg);
-                                overlay::set_per_monitor_opacities(&cfg.per_monitor_opacity);
-                                overlay::show_overlay(cfg.opacity, cfg.allow_capture);
-                            }
-                        }
-                        show_toast(hwnd, &format!("Monitor {} opacity updated", mon_idx + 1));
+                        let message = commit_linked(state, i, val);
+                        show_toast(hwnd, &message);
                         invalidate(hwnd);
                         break;
                     }
                 }
+
+                // Group slider release
+                if let Some(g) = state
+                    .ui
+                    .group_rows
+                    .iter()
+                    .position(|row| row.slider.dragging)
+                {
+                    let row = &mut state.ui.group_rows[g];
+                    row.slider.dragging = false;
+                    let _ = ReleaseCapture();
+                    let (first, val) = (row.members[0], row.slider.value);
+                    let message = commit_linked(state, first, val);
+                    show_toast(hwnd, &message);
+                    invalidate(hwnd);
+                }
+
+                // Backlight slider release
+                if let Some(row) = state.ui.backlights.iter_mut().find(|row| row.dragging) {
+                    row.dragging = false;
+                    let _ = ReleaseCapture();
+                    let message = format!("{} backlight: {}%", row.name, row.percent);
+                    show_toast(hwnd, &message);
+                    invalidate(hwnd);
+                }
             }
             DefWindowProcW(hwnd, msg, wparam, lparam)
         }
@@ -556,38 +2152,53 @@ unsafe extern "system" fn wnd_proc(
                 let x = (lparam.0 & 0xFFFF) as i16 as i32;
 
                 if state.ui.slider.dragging {
-                    let val = state.ui.slider.value_from_x(x);
+                    let val = state.ui.slider.value_from_x(x, fine_mode());
                     state.ui.slider.value = val;
 
                     // Live update overlay opacity while dragging
                     {
                         let cfg = state.config.lock().unwrap();
                         if overlay::is_visible() || cfg.is_enabled {
-                            overlay::set_opacity(val as f32 / 100.0);
+                            overlay::set_opacity(dimming::from_steps(val));
                         }
                     }
 
                     invalidate(hwnd);
                 }
 
-                // Multi-monitor slider drag
+                // Multi-monitor slider drag: live update the overlays of
+                // the monitor and the monitors grouped with it
                 for i in 0..state.ui.monitor_sliders.len() {
                     if state.ui.monitor_sliders[i].dragging {
-                        let val = state.ui.monitor_sliders[i].value_from_x(x);
-                        state.ui.monitor_sliders[i].value = val;
-
-                        // Live update per-monitor overlay opacity
-                        {
-                            let cfg = state.config.lock().unwrap();
-                            if overlay::is_visible() || cfg.is_enabled {
-                                overlay::set_monitor_opacity(i as u32, val as f32 / 100.0);
-                            }
-                        }
-
+                        let val = state.ui.monitor_sliders[i].value_from_x(x, fine_mode());
+                        preview_linked(state, i, val);
                         invalidate(hwnd);
                         break;
                     }
                 }
+
+                // Group slider drag
+                if let Some(g) = state
+                    .ui
+                    .group_rows
+                    .iter()
+                    .position(|row| row.slider.dragging)
+                {
+                    let row = &state.ui.group_rows[g];
+                    let (first, val) = (row.members[0], row.slider.value_from_x(x, fine_mode()));
+                    preview_linked(state, first, val);
+                    invalidate(hwnd);
+                }
+
+                // Backlight slider drag (ddcci.rs sends only the latest)
+                if let Some(row) = state.ui.backlights.iter_mut().find(|row| row.dragging) {
+                    let percent = row.percent_from_x(x);
+                    if percent != row.percent {
+                        row.percent = percent;
+                        ddcci::set(&row.id, percent);
+                        invalidate(hwnd);
+                    }
+                }
             }
             DefWindowProcW(hwnd, msg, wparam, lparam)
         }
@@ -597,28 +2208,90 @@ unsafe extern "system" fn wnd_proc(
             match cmd {
                 tray::IDM_TOGGLE => {
                     if !WND_STATE.is_null() {
+                        explain::note(Cause::Tray);
                         let state = &mut *WND_STATE;
                         let mut cfg = state.config.lock().unwrap();
+                        crate::movie_mode::forget(&cfg);
                         cfg.is_enabled = !cfg.is_enabled;
                         state.ui.enabled_toggle.checked = cfg.is_enabled;
                         config::save_config(&cfg);
                         if cfg.is_enabled {
-                            overlay::show_overlay(cfg.opacity, false);
+                            overlay::fade_in(cfg.opacity, false, &cfg.fade);
                         } else {
-                            overlay::hide_overlay();
+                            overlay::fade_out(&cfg.fade);
                         }
                         drop(cfg);
                         invalidate(hwnd);
                     }
                 }
+                tray::IDM_MOVIE if !WND_STATE.is_null() => {
+                    explain::note(Cause::Tray);
+                    let message = crate::movie_mode::toggle(&(*WND_STATE).config);
+                    hud::show(&message);
+                    show_toast(hwnd, &message);
+                }
+                tray::IDM_DESIGNER => {
+                    if !WND_STATE.is_null() {
+                        let on = !(*WND_STATE).ui.designer_mode;
+                        set_designer_mode(hwnd, on);
+                    }
+                }
+                tray::IDM_RESUME => {
+                    end_pause(hwnd);
+                }
+                tray::IDM_END_PRESENTATION => {
+                    end_presentation(hwnd);
+                }
+                tray::IDM_RECORDING => {
+                    let on = !overlay::recording();
+                    overlay::set_recording(on);
+                    let message = if on {
+                        "Dimming shows in recordings"
+                    } else {
+                        "Dimming hidden from recordings"
+                    };
+                    hud::show(message);
+                    show_toast(hwnd, message);
+                }
+                tray::IDM_EXPLAIN => {
+                    show_explainer(hwnd);
+                }
+                tray::IDM_UPDATES => {
+                    check_for_updates_now(hwnd);
+                }
                 tray::IDM_SETTINGS => {
                     show_window(hwnd);
                 }
                 tray::IDM_QUIT => {
-                    tray::remove_tray_icon(hwnd);
                     PostQuitMessage(0);
                 }
-                _ => {}
+                _ => {
+                    if let Some(level) = tray::preset_level(cmd) {
+                        if !WND_STATE.is_null() {
+                            explain::note(Cause::Tray);
+                            let message = crate::do_set_level(&(*WND_STATE).config, level);
+                            sync_from_config(hwnd);
+                            show_toast(hwnd, &message);
+                        }
+                    } else if let Some(index) = tray::profile_index(cmd) {
+                        explain::note(Cause::Tray);
+                        if let Some(message) = apply_profile_at(hwnd, index) {
+                            show_toast(hwnd, &message);
+                        }
+                    } else if let Some(minutes) = tray::pause_minutes(cmd) {
+                        start_pause(hwnd, minutes);
+                    } else if let Some(minutes) = tray::presentation_minutes(cmd) {
+                        start_presentation(hwnd, minutes);
+                    } else if let Some(index) = tray::duplicate_index(cmd) {
+                        if let Some(message) = duplicate_builtin(index) {
+                            show_toast(hwnd, &message);
+                        }
+                    } else if let Some(index) = tray::example_index(cmd) {
+                        if let Some(message) = use_theme_example(hwnd, index) {
+                            show_toast(hwnd, &message);
+                        }
+                    }
+                }
             }
             LRESULT(0)
         }
@@ -626,11 +2299,19 @@ unsafe extern "system" fn wnd_proc(
         WM_TRAY_CALLBACK => {
             let event = (lparam.0 & 0xFFFF) as u32;
             match event {
-                WM_LBUTTONUP => {
+                WM_LBUTTONUP | NIN_BALLOONUSERCLICK => {
                     show_window(hwnd);
                 }
-                WM_RBUTTONUP => {
-                    tray::show_context_menu(hwnd);
+                WM_RBUTTONUP if !WND_STATE.is_null() => {
+                    let state = &*WND_STATE;
+                    // A copy: hotkeys handled during the menu loop lock config
+                    let cfg = state.config.lock().unwrap().clone();
+                    tray::show_context_menu(
+                        hwnd,
+                        &cfg,
+                        state.ui.designer_mode,
+                        crate::movie_mode::is_active(),
+                    );
                 }
                 _ => {}
             }
@@ -639,22 +2320,63 @@ unsafe extern "system" fn wnd_proc(
 
         WM_HOTKEY => {
             if !WND_STATE.is_null() {
+                explain::note(Cause::Hotkey);
                 let state = &mut *WND_STATE;
                 let id = wparam.0 as i32;
                 match id {
                     crate::hotkeys::HOTKEY_TOGGLE => {
                         crate::do_toggle_dimmer(&state.config);
                         sync_from_config(hwnd);
+                        if state.config.lock().unwrap().level_chord {
+                            crate::hotkeys::start_level_chord();
+                            hud::show(&hotkeys::chord_prompt(None));
+                        }
                     }
-                    crate::hotkeys::HOTKEY_INCREASE => {
-                        crate::do_adjust_opacity(&state.config, 0.1);
-                        sync_from_config(hwnd);
+                    crate::hotkeys::HOTKEY_INCREASE | crate::hotkeys::HOTKEY_DECREASE => {
+                        let ramp = state.config.lock().unwrap().adjust_ramp.clone();
+                        let up = id == crate::hotkeys::HOTKEY_INCREASE;
+                        let delta = crate::hotkeys::ramp_step(&ramp, up);
+                        if delta != 0.0 {
+                            let message = crate::do_adjust_opacity(&state.config, delta);
+                            sync_from_config(hwnd);
+                            hud::show(&message);
+                            show_toast(hwnd, &message);
+                        }
                     }
-                    crate::hotkeys::HOTKEY_DECREASE => {
-                        crate::do_adjust_opacity(&state.config, -0.1);
-                        sync_from_config(hwnd);
+                    crate::hotkeys::HOTKEY_MOVIE => {
+                        let message = crate::movie_mode::toggle(&state.config);
+                        hud::show(&message);
+                        show_toast(hwnd, &message);
+                    }
+                    crate::hotkeys::HOTKEY_BOOST => {
+                        let message = crate::focus_boost::boost(&state.config);
+                        hud::show(&message);
+                        show_toast(hwnd, &message);
+                    }
+                    id => {
+                        if let Some(index) = crate::hotkeys::custom_action(id) {
+                            let message = crate::custom_actions::run(hwnd, &state.config, index);
+                            if let Some(message) = message {
+                                hud::show(&message);
+                                show_toast(hwnd, &message);
+                            }
+                        }
+                        // Level chord digits (only registered during a chord)
+                        else if let Some(digit) = crate::hotkeys::chord_digit(id) {
+                            match crate::hotkeys::push_chord_digit(digit) {
+                                ChordStep::Partial(first) => {
+                                    hud::show(&hotkeys::chord_prompt(Some(first)));
+                                }
+                                ChordStep::Complete(percent) => {
+                                    let message = crate::do_set_opacity(&state.config, percent);
+                                    sync_from_config(hwnd);
+                                    hud::show(&message);
+                                    show_toast(hwnd, &message);
+                                }
+                                ChordStep::Inactive => {}
+                            }
+                        }
                     }
-                    _ => {}
                 }
             }
             LRESULT(0)
@@ -670,13 +2392,31 @@ unsafe extern "system" fn wnd_proc(
                     let _ = KillTimer(Some(hwnd), TOAST_TIMER_ID);
                     invalidate(hwnd);
                 }
-            } else if timer_id == STATUS_CLEAR_TIMER_ID {
-                if !WND_STATE.is_null() {
-                    let state = &mut *WND_STATE;
-                    state.ui.update_status_text.clear();
-                    let _ = KillTimer(Some(hwnd), STATUS_CLEAR_TIMER_ID);
-                    invalidate(hwnd);
+            } else if timer_id == PAUSE_TIMER_ID {
+                if pause::take_expired() {
+                    resume_from_pause(hwnd);
+                } else {
+                    let _ = KillTimer(Some(hwnd), PAUSE_TIMER_ID);
+                }
+            } else if timer_id == PRESENTATION_TIMER_ID {
+                if presentation::take_expired() {
+                    resume_from_presentation(hwnd);
+                } else {
+                    let _ = KillTimer(Some(hwnd), PRESENTATION_TIMER_ID);
                 }
+            } else if timer_id == SUMMARY_TIMER_ID {
+                // Held back until presentation mode ends rather than dropped
+                if !WND_STATE.is_null() && !presentation::is_active() {
+                    let rule = (*WND_STATE).config.lock().unwrap().weekly_summary.clone();
+                    if let Some(text) = usage_stats::due_summary(&rule) {
+                        tray::show_info(hwnd, "Your week with SaveMyEyes", &text);
+                    }
+                }
+            } else if timer_id == STATUS_CLEAR_TIMER_ID && !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                state.ui.update_status_text.clear();
+                let _ = KillTimer(Some(hwnd), STATUS_CLEAR_TIMER_ID);
+                invalidate(hwnd);
             }
             LRESULT(0)
         }
@@ -718,14 +2458,208 @@ unsafe extern "system" fn wnd_proc(
             LRESULT(0)
         }
 
+        // Command from another process (events.rs)
+        WM_COPYDATA => {
+            if WND_STATE.is_null() {
+                return LRESULT(0);
+            }
+            let state = &mut *WND_STATE;
+            let Some(command) = crate::events::receive(lparam) else {
+                return LRESULT(0);
+            };
+            if let Some(message) = crate::events::dispatch(hwnd, &state.config, command) {
+                show_toast(hwnd, &message);
+            }
+            LRESULT(1)
+        }
+
+        WM_DISPLAYCHANGE => {
+            // Monitor connected, removed, rotated or resized
+            monitors::record_layout();
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                let before = state.ui.monitor_names.clone();
+                {
+                    // Indices may have moved: settings follow the monitor ids
+                    let mut cfg = state.config.lock().unwrap();
+                    apply_monitor_settings(&mut cfg);
+                    rebuild_monitor_cards(&mut state.ui, &cfg);
+                }
+                if state.ui.monitor_names != before {
+                    // Added, removed or swapped: the cards moved under the user
+                    show_toast(hwnd, "Display configuration changed");
+                }
+                invalidate(hwnd);
+            }
+            overlay::refit();
+            ddcci::refresh();
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+
+        // DDC/CI refresh done
+        x if x == ddcci::WM_BACKLIGHT => {
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                sync_backlights(&mut state.ui);
+                invalidate(hwnd);
+            }
+            LRESULT(0)
+        }
+
+        // OS switched between light and dark mode (WPARAM 1 = dark)
+        x if x == system_theme::WM_SYSTEM_THEME_CHANGED => {
+            let dark = wparam.0 != 0;
+            if let Some(name) = apply_theme_profile(hwnd, dark) {
+                show_toast(
+                    hwnd,
+                    &format!(
+                        "{} mode \u{2014} {} profile applied",
+                        if dark { "Dark" } else { "Light" },
+                        name
+                    ),
+                );
+            }
+            LRESULT(0)
+        }
+
+        x if x == crate::scheduler::WM_SCHEDULE => {
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                let level = crate::scheduler::level_from(wparam, lparam);
+                if let Some(message) = crate::scheduler::run(hwnd, &state.config, level) {
+                    show_toast(hwnd, &message);
+                }
+            }
+            LRESULT(0)
+        }
+
+        x if x == explainer::WM_EXPLAIN_FIX => {
+            if let Some(&fix) = Fix::ALL.get(wparam.0) {
+                apply_fix(hwnd, fix);
+            }
+            LRESULT(0)
+        }
+
+        WM_CONFIG_SAVE_FAILED => {
+            if let Some(failure) = config::last_save_failure() {
+                tray::show_warning(
+                    hwnd,
+                    "Settings could not be saved",
+                    &config::describe_save_failure(&failure),
+                );
+                show_toast(hwnd, "Settings could not be saved");
+            }
+            LRESULT(0)
+        }
+
+        // A monitor entered or left HDR; the overlay already re-applied levels
+        // Night boost hours started (WPARAM 1) or ended
+        x if x == overlay::WM_NIGHT_BOOST_CHANGED => {
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                state.ui.night_boost = overlay::night_boost();
+                invalidate(hwnd);
+            }
+            LRESULT(0)
+        }
+
+        x if x == overlay::WM_HDR_CHANGED => {
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                let flags: Vec<bool> = monitors::list().iter().map(|m| m.hdr).collect();
+                let entered = flags
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &on)| on && !state.ui.monitor_hdr.get(i).copied().unwrap_or(false));
+                state.ui.monitor_hdr = flags;
+                show_toast(
+                    hwnd,
+                    if entered {
+                        "HDR detected \u{2014} dimming adjusted"
+                    } else {
+                        "HDR turned off \u{2014} dimming restored"
+                    },
+                );
+            }
+            LRESULT(0)
+        }
+
+        // A gamma monitor's driver clamped its ramp; the overlay dims it now
+        x if x == overlay::WM_GAMMA_REFUSED => {
+            if !WND_STATE.is_null() {
+                let state = &mut *WND_STATE;
+                let list = monitors::list();
+                state.ui.capabilities = overlay::capabilities();
+                state.ui.monitor_gamma_refused = list
+                    .iter()
+                    .map(|m| gamma::refused(&m.device).is_some())
+                    .collect();
+                let name = list
+                    .get(wparam.0)
+                    .map_or_else(|| "A monitor".to_string(), |m| m.name.clone());
+                show_toast(
+                    hwnd,
+                    &format!("{}: driver refused gamma dimming, using the overlay", name),
+                );
+                invalidate(hwnd);
+            }
+            LRESULT(0)
+        }
+
+        // Cached fonts depend on DPI and font smoothing
+        WM_DPICHANGED | WM_THEMECHANGED | WM_SETTINGCHANGE => {
+            painting::invalidate_fonts();
+            invalidate(hwnd);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+
         WM_CLOSE => {
             // Hide to tray instead of quitting
             hide_window(hwnd);
             LRESULT(0)
         }
 
+        WM_WTSSESSION_CHANGE => {
+            crate::lock_screen::on_session_change(wparam);
+            LRESULT(0)
+        }
+
+        WM_TIMECHANGE => {
+            // Clock set, synced or moved to another timezone
+            time_change::notify();
+            LRESULT(0)
+        }
+
+        // Keys while recording a shortcut (Alt combos arrive as WM_SYSKEYDOWN)
+        WM_KEYDOWN | WM_SYSKEYDOWN => {
+            if WND_STATE.is_null() || (*WND_STATE).ui.recording_shortcut.is_none() {
+                return DefWindowProcW(hwnd, msg, wparam, lparam);
+            }
+            if let Some(message) = record_shortcut(hwnd, &mut *WND_STATE, wparam.0 as u16) {
+                show_toast(hwnd, &message);
+            }
+            LRESULT(0)
+        }
+
+        // Keep Alt from opening the system menu mid-recording
+        WM_KEYUP | WM_SYSKEYUP | WM_SYSCHAR => {
+            if WND_STATE.is_null() || (*WND_STATE).ui.recording_shortcut.is_none() {
+                return DefWindowProcW(hwnd, msg, wparam, lparam);
+            }
+            LRESULT(0)
+        }
+
+        WM_KILLFOCUS => {
+            if !WND_STATE.is_null() {
+                stop_recording(hwnd, &mut *WND_STATE);
+            }
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+
         WM_DESTROY => {
             // Cleanup
+            painting::invalidate_fonts();
+            crate::lock_screen::set_enabled(hwnd, false);
             if !WND_STATE.is_null() {
                 let _ = Box::from_raw(WND_STATE);
                 WND_STATE = std::ptr::null_mut();
@@ -2,7 +2,11 @@
 
 use super::controls::*;
 use super::theme::*;
+use crate::resources;
 use crate::updater;
+use savemyeyes_shared::capabilities::Feature;
+use savemyeyes_shared::{dimming, night_boost, scheduler};
+use std::cell::RefCell;
 use windows::Win32::Foundation::{COLORREF, RECT};
 use windows::Win32::Graphics::Gdi::*;
 
@@ -90,54 +94,121 @@ fn draw_circle(hdc: HDC, cx: i32, cy: i32, r: i32, color: COLORREF) {
 // ── Main paint function ─────────────────────────────────────────────────────
 
 pub fn paint(hdc: HDC, client: &RECT, state: &mut UiState) {
+    let dpi = unsafe { GetDeviceCaps(Some(hdc), LOGPIXELSY) } as u32;
+    FONTS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.as_ref().is_some_and(|fonts| fonts.dpi != dpi) {
+            if let Some(stale) = cache.take() {
+                stale.destroy();
+            }
+        }
+        let fonts = cache.get_or_insert_with(|| Fonts::create(dpi));
+        let fonts_before = fonts.created();
+        let gdi_before = resources::gdi_objects();
+
+        paint_with(hdc, client, state, fonts);
+
+        // Fonts cached during this paint are kept on purpose
+        resources::debug_check_gdi("settings paint", gdi_before + fonts.created() - fonts_before);
+    });
+}
+
+fn paint_with(hdc: HDC, client: &RECT, state: &mut UiState, fonts: &Fonts) {
     fill_rect_color(hdc, client, CLR_BACKGROUND);
+    state.tooltips.clear();
 
-    let fonts = Fonts::create();
     let mut y = PADDING;
 
-    y = draw_header(hdc, y, state, &fonts);
+    y = draw_header(hdc, y, state, fonts);
     y += GAP;
 
-    y = draw_tab_bar(hdc, y, state, &fonts);
+    y = draw_tab_bar(hdc, y, state, fonts);
     y += GAP;
 
     match state.active_tab {
-        Tab::Dimmer => draw_dimmer_tab(hdc, y, state, &fonts),
-        Tab::Settings => draw_settings_tab(hdc, y, state, &fonts),
-        Tab::Shortcuts => draw_shortcuts_tab(hdc, y, state, &fonts),
+        Tab::Dimmer => draw_dimmer_tab(hdc, y, state, fonts),
+        Tab::Settings => draw_settings_tab(hdc, y, state, fonts),
+        Tab::Shortcuts => draw_shortcuts_tab(hdc, y, state, fonts),
+        Tab::Advanced => draw_advanced_tab(hdc, y, state, fonts),
     };
 
     if state.toast_visible {
-        draw_toast(hdc, client, state, &fonts);
+        draw_toast(hdc, client, state, fonts);
     }
+}
 
-    fonts.destroy();
+/// Drop the cached fonts; the next paint creates them again. Call on DPI
+/// or theme/font-smoothing changes, and when the window is destroyed.
+pub fn invalidate_fonts() {
+    FONTS.with(|cache| {
+        if let Some(fonts) = cache.borrow_mut().take() {
+            fonts.destroy();
+        }
+    });
 }
 
 // ── Font cache ──────────────────────────────────────────────────────────────
+//
+// The six standard fonts live as long as the settings window (UI thread
+// only). Cards that need something else ask `Fonts::get` for it, which
+// creates each size/weight/family once and keeps it in the same cache.
+
+thread_local! {
+    static FONTS: RefCell<Option<Fonts>> = const { RefCell::new(None) };
+}
 
 struct Fonts {
+    /// DPI the fonts were created for (LOGPIXELSY of the paint DC)
+    dpi: u32,
     title: HFONT,
     small: HFONT,
     small_bold: HFONT,
     xs: HFONT,
     xxs: HFONT,
     mono: HFONT,
+    /// Fonts created on demand by `get`
+    extra: RefCell<Vec<(FontSpec, HFONT)>>,
 }
 
+/// Size, weight and family of a font
+type FontSpec = (i32, i32, &'static str);
+
+/// Number of standard fonts created by `Fonts::create`
+const STANDARD_FONTS: u32 = 6;
+
 impl Fonts {
-    fn create() -> Self {
+    fn create(dpi: u32) -> Self {
         Self {
+            dpi,
             title: create_font(FONT_SIZE_TITLE, 600, FONT_NAME),
             small: create_font(FONT_SIZE_SMALL, 400, FONT_NAME),
             small_bold: create_font(FONT_SIZE_SMALL, 500, FONT_NAME),
             xs: create_font(FONT_SIZE_XS, 400, FONT_NAME),
             xxs: create_font(FONT_SIZE_XXS, 400, FONT_NAME),
             mono: create_font(FONT_SIZE_XXS, 500, FONT_MONO_NAME),
+            extra: RefCell::new(Vec::new()),
         }
     }
 
-    fn destroy(&self) {
+    /// Cached font for any size/weight/family outside the standard six.
+    #[allow(dead_code)]
+    fn get(&self, size: i32, weight: i32, family: &'static str) -> HFONT {
+        let key = (size, weight, family);
+        let mut extra = self.extra.borrow_mut();
+        if let Some((_, font)) = extra.iter().find(|(k, _)| *k == key) {
+            return *font;
+        }
+        let font = create_font(size, weight, family);
+        extra.push((key, font));
+        font
+    }
+
+    /// Fonts this cache has created (and still owns)
+    fn created(&self) -> u32 {
+        STANDARD_FONTS + self.extra.borrow().len() as u32
+    }
+
+    fn destroy(self) {
         unsafe {
             let _ = DeleteObject(HGDIOBJ::from(self.title));
             let _ = DeleteObject(HGDIOBJ::from(self.small));
@@ -145,6 +216,9 @@ impl Fonts {
             let _ = DeleteObject(HGDIOBJ::from(self.xs));
             let _ = DeleteObject(HGDIOBJ::from(self.xxs));
             let _ = DeleteObject(HGDIOBJ::from(self.mono));
+            for (_, font) in self.extra.into_inner() {
+                let _ = DeleteObject(HGDIOBJ::from(font));
+            }
         }
     }
 }
@@ -210,6 +284,25 @@ fn draw_header(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) -> i32 {
     );
     draw_text_simple(hdc, "Screen Dimmer", text_x, y + 26, CLR_MUTED_FG, fonts.xs);
 
+    // Designer mode badge next to the title (click to exit)
+    if state.designer_mode {
+        let (title_w, _) = measure_text(hdc, "SaveMyEyes", fonts.title);
+        let label = "DESIGNER MODE";
+        let (label_w, label_h) = measure_text(hdc, label, fonts.xxs);
+        let badge = RECT {
+            left: text_x + title_w + 8,
+            top: y + 5,
+            right: text_x + title_w + 8 + label_w + 12,
+            bottom: y + 5 + label_h + 6,
+        };
+        draw_rounded_rect(hdc, &badge, 8, CLR_BRAND, CLR_BRAND);
+        draw_text_simple(hdc, label, badge.left + 6, badge.top + 3, CLR_FOREGROUND, fonts.xxs);
+        state.designer_badge_rect = badge;
+        state.tooltip(badge, "Dimming is suspended. Click to turn it back on");
+    } else {
+        state.designer_badge_rect = RECT::default();
+    }
+
     let credit_by = "An open-source project by";
     let credit_name = "KraftPixel";
     let (_, h1) = measure_text(hdc, credit_by, fonts.xxs);
@@ -240,7 +333,7 @@ fn draw_header(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) -> i32 {
 
 fn draw_tab_bar(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) -> i32 {
     let x = PADDING;
-    let tab_names = ["Dimmer", "Settings", "Shortcuts"];
+    let tab_names = ["Dimmer", "Settings", "Shortcuts", "Advanced"];
     let bar_rect = RECT {
         left: x,
         top: y,
@@ -251,7 +344,7 @@ fn draw_tab_bar(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) -> i32 {
     draw_rounded_rect(hdc, &bar_rect, CARD_RADIUS, CLR_SECONDARY, CLR_SECONDARY);
     state.tab_bar_rect = bar_rect;
 
-    let tab_width = CONTENT_WIDTH / 3;
+    let tab_width = CONTENT_WIDTH / tab_names.len() as i32;
     let tab_pad = 4;
 
     for (i, name) in tab_names.iter().enumerate() {
@@ -290,6 +383,124 @@ fn draw_tab_bar(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) -> i32 {
     bar_rect.bottom
 }
 
+/// Row of group `g` (state.group_rows) at `top`: its name, an Ungroup pill
+/// and one slider for all of its monitors. Returns where the next card goes.
+fn draw_group_row(hdc: HDC, top: i32, g: usize, state: &mut UiState, fonts: &Fonts) -> i32 {
+    let x = PADDING;
+    let inner_x = x + 16;
+    let inner_right = x + CONTENT_WIDTH - 16;
+    let card_h = 70i32;
+    let card = RECT {
+        left: x,
+        top,
+        right: x + CONTENT_WIDTH,
+        bottom: top + card_h,
+    };
+    draw_rounded_rect(hdc, &card, CARD_RADIUS, CLR_SECONDARY, CLR_BRAND);
+
+    // Name and member count
+    let row = &state.group_rows[g];
+    let title = format!("{} \u{00b7} {} monitors", row.name, row.members.len());
+    draw_text_simple(
+        hdc,
+        &title,
+        inner_x,
+        top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+
+    // Badge
+    let badge_text = dimming::percent_label(row.slider.level());
+    let (bw, bh) = measure_text(hdc, &badge_text, fonts.xs);
+    let badge_w = bw + 16;
+    let badge_h = bh + 4;
+    let badge_x = inner_right - badge_w;
+    let badge_y = top + 8;
+    let badge_rect = RECT {
+        left: badge_x,
+        top: badge_y,
+        right: badge_x + badge_w,
+        bottom: badge_y + badge_h,
+    };
+    draw_rounded_rect(hdc, &badge_rect, badge_h / 2, CLR_BRAND, CLR_BRAND);
+    draw_text_simple(
+        hdc,
+        &badge_text,
+        badge_x + (badge_w - bw) / 2,
+        badge_y + (badge_h - bh) / 2,
+        CLR_FOREGROUND,
+        fonts.xs,
+    );
+
+    // Ungroup pill left of the badge
+    let (uw, uh) = measure_text(hdc, "Ungroup", fonts.xs);
+    let pill_w = uw + 12;
+    let pill_h = uh + 4;
+    let pill_x = badge_x - 6 - pill_w;
+    let ungroup_rect = RECT {
+        left: pill_x,
+        top: badge_y,
+        right: pill_x + pill_w,
+        bottom: badge_y + pill_h,
+    };
+    draw_rounded_rect(
+        hdc,
+        &ungroup_rect,
+        pill_h / 2,
+        CLR_BACKGROUND,
+        CLR_BACKGROUND,
+    );
+    draw_text_simple(
+        hdc,
+        "Ungroup",
+        pill_x + (pill_w - uw) / 2,
+        badge_y + (pill_h - uh) / 2,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+
+    // Slider track across the card
+    let slider_y = top + 40;
+    let track_h = 8;
+    let thumb_r = 9;
+    let row = &mut state.group_rows[g];
+    row.ungroup_rect = ungroup_rect;
+    row.slider.rect = RECT {
+        left: inner_x,
+        top: slider_y,
+        right: inner_right,
+        bottom: slider_y + track_h,
+    };
+    draw_rounded_rect(hdc, &row.slider.rect, 4, CLR_BACKGROUND, CLR_BACKGROUND);
+    let fill_w = (row.slider.fraction() * (inner_right - inner_x) as f32) as i32;
+    if fill_w > 0 {
+        let fill_rect = RECT {
+            left: inner_x,
+            top: slider_y,
+            right: inner_x + fill_w,
+            bottom: slider_y + track_h,
+        };
+        draw_rounded_rect(hdc, &fill_rect, 4, CLR_BRAND, CLR_BRAND);
+    }
+    draw_circle(
+        hdc,
+        row.slider.thumb_x(),
+        slider_y + track_h / 2,
+        thumb_r,
+        CLR_FOREGROUND,
+    );
+    row.slider.thumb_rect = RECT {
+        left: inner_x - thumb_r,
+        top: slider_y - thumb_r - 4,
+        right: inner_right + thumb_r,
+        bottom: slider_y + track_h + thumb_r + 4,
+    };
+    state.tooltip(ungroup_rect, "Dim these monitors separately again");
+
+    card.bottom + GAP / 2
+}
+
 fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
     let x = PADDING;
     let inner_x = x + 16;
@@ -299,16 +510,41 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         // Multi-monitor mode: one slider per monitor
         let slider_card_height = 70i32;
         let mut card_top = y;
+        let boost = state.night_boost.filter(|_| state.enabled_toggle.checked);
 
         // Ensure we have enough sliders
         while state.monitor_sliders.len() < state.monitor_count as usize {
             let idx = state.monitor_sliders.len() as u32;
-            let mut s = SliderState::new(30);
+            let mut s = SliderState::new(300);
             s.monitor_index = Some(idx);
             state.monitor_sliders.push(s);
         }
 
+        // Each group's row above its monitors' cards, then the monitors in
+        // no group (monitor_groups.rs). Clicking a monitor's number ticks
+        // it for grouping once there are more than two.
+        let grouping = state.monitor_count > 2;
+        let mut order: Vec<(Option<usize>, usize)> = Vec::new();
+        for (g, row) in state.group_rows.iter().enumerate() {
+            for (k, &i) in row.members.iter().enumerate() {
+                order.push(((k == 0).then_some(g), i));
+            }
+        }
         for i in 0..state.monitor_count as usize {
+            if !state.group_rows.iter().any(|row| row.members.contains(&i)) {
+                order.push((None, i));
+            }
+        }
+        if state.monitor_label_rects.len() < state.monitor_count as usize {
+            state
+                .monitor_label_rects
+                .resize(state.monitor_count as usize, RECT::default());
+        }
+
+        for (group, i) in order {
+            if let Some(g) = group {
+                card_top = draw_group_row(hdc, card_top, g, state, fonts);
+            }
             let card = RECT {
                 left: x,
                 top: card_top,
@@ -334,7 +570,15 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
                 right: label_rect_x + label_rect_w,
                 bottom: label_rect_y + label_rect_h,
             };
-            draw_rounded_rect(hdc, &label_bg_rect, 4, CLR_SECONDARY, CLR_SECONDARY);
+            let selected = grouping && state.monitor_selected.get(i).copied().unwrap_or(false);
+            let label_bg = if selected { CLR_BRAND } else { CLR_SECONDARY };
+            draw_rounded_rect(hdc, &label_bg_rect, 4, label_bg, label_bg);
+            if grouping {
+                state.monitor_label_rects[i] = label_bg_rect;
+                state.tooltip(label_bg_rect, "Click to select this monitor for a group");
+            } else {
+                state.monitor_label_rects[i] = RECT::default();
+            }
             draw_text_simple(
                 hdc,
                 &mon_label,
@@ -348,7 +592,8 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
             let slider_left = label_rect_x + label_rect_w + 12;
 
             // Badge
-            let badge_text = format!("{}%", state.monitor_sliders[i].value);
+            let level = state.monitor_sliders[i].level();
+            let badge_text = dimming::percent_label(night_boost::boosted(level, boost));
             let (bw, bh) = measure_text(hdc, &badge_text, fonts.xs);
             let badge_w = bw + 16;
             let badge_h = bh + 4;
@@ -370,6 +615,181 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
                 fonts.xs,
             );
 
+            // Pills right to left, ending left of the badge
+            let mut pills_left = badge_x;
+
+            // Night boost pill (the badge already includes the boost)
+            if let Some(boost) = boost.filter(|_| state.monitor_sliders[i].value > 0) {
+                let night_text = format!("+{} night", dimming::percent_label(boost));
+                let (nw, nh) = measure_text(hdc, &night_text, fonts.xs);
+                let pill_w = nw + 12;
+                let pill_h = nh + 4;
+                let pill_x = pills_left - 6 - pill_w;
+                pills_left = pill_x;
+                let pill_rect = RECT {
+                    left: pill_x,
+                    top: badge_y,
+                    right: pill_x + pill_w,
+                    bottom: badge_y + pill_h,
+                };
+                draw_rounded_rect(hdc, &pill_rect, pill_h / 2, CLR_SECONDARY, CLR_SECONDARY);
+                draw_text_simple(
+                    hdc,
+                    &night_text,
+                    pill_x + (pill_w - nw) / 2,
+                    badge_y + (pill_h - nh) / 2,
+                    CLR_MUTED_FG,
+                    fonts.xs,
+                );
+            }
+
+            // HDR pill (HDR monitors get their own backend/offset)
+            if state.monitor_hdr.get(i).copied().unwrap_or(false) {
+                let (hw, hh) = measure_text(hdc, "HDR", fonts.xs);
+                let pill_w = hw + 12;
+                let pill_h = hh + 4;
+                let pill_x = pills_left - 6 - pill_w;
+                pills_left = pill_x;
+                let pill_rect = RECT {
+                    left: pill_x,
+                    top: badge_y,
+                    right: pill_x + pill_w,
+                    bottom: badge_y + pill_h,
+                };
+                draw_rounded_rect(hdc, &pill_rect, pill_h / 2, CLR_SECONDARY, CLR_SECONDARY);
+                draw_text_simple(
+                    hdc,
+                    "HDR",
+                    pill_x + (pill_w - hw) / 2,
+                    badge_y + (pill_h - hh) / 2,
+                    CLR_MUTED_FG,
+                    fonts.xs,
+                );
+            }
+
+            // Overlay pill: gamma backend, but the driver clamped the ramp
+            if state.monitor_gamma_refused.get(i).copied().unwrap_or(false) {
+                let (ow, oh) = measure_text(hdc, "Overlay", fonts.xs);
+                let pill_w = ow + 12;
+                let pill_h = oh + 4;
+                let pill_x = pills_left - 6 - pill_w;
+                pills_left = pill_x;
+                let pill_rect = RECT {
+                    left: pill_x,
+                    top: badge_y,
+                    right: pill_x + pill_w,
+                    bottom: badge_y + pill_h,
+                };
+                draw_rounded_rect(hdc, &pill_rect, pill_h / 2, CLR_SECONDARY, CLR_SECONDARY);
+                draw_text_simple(
+                    hdc,
+                    "Overlay",
+                    pill_x + (pill_w - ow) / 2,
+                    badge_y + (pill_h - oh) / 2,
+                    CLR_MUTED_FG,
+                    fonts.xs,
+                );
+                state.tooltip(pill_rect, Feature::Gamma.unsupported_reason());
+            }
+
+            // Capture pill: click to let this monitor's dimming show up in
+            // screenshots and recordings
+            // (always captured, and greyed out, where exclusion isn't supported)
+            let excludable = state.capabilities.capture_exclusion;
+            let captured = !excludable || state.monitor_capture.get(i).copied().unwrap_or(false);
+            let capture_text = if captured { "In capture" } else { "No capture" };
+            let (cw, ch) = measure_text(hdc, capture_text, fonts.xs);
+            let pill_w = cw + 12;
+            let pill_h = ch + 4;
+            let pill_x = pills_left - 6 - pill_w;
+            pills_left = pill_x;
+            let capture_rect = RECT {
+                left: pill_x,
+                top: badge_y,
+                right: pill_x + pill_w,
+                bottom: badge_y + pill_h,
+            };
+            let (pill_bg, pill_fg) = if captured && excludable {
+                (CLR_BRAND, CLR_FOREGROUND)
+            } else {
+                (CLR_SECONDARY, CLR_MUTED_FG)
+            };
+            draw_rounded_rect(hdc, &capture_rect, pill_h / 2, pill_bg, pill_bg);
+            draw_text_simple(
+                hdc,
+                capture_text,
+                pill_x + (pill_w - cw) / 2,
+                badge_y + (pill_h - ch) / 2,
+                pill_fg,
+                fonts.xs,
+            );
+            if state.monitor_capture_rects.len() <= i {
+                state.monitor_capture_rects.resize(i + 1, RECT::default());
+            }
+            state.monitor_capture_rects[i] = capture_rect;
+            let tip = state.capabilities.tooltip(
+                Feature::CaptureExclusion,
+                "Click to show or hide this monitor's dimming in captures",
+            );
+            state.tooltip(capture_rect, tip);
+
+            // Reading pill, while reading monitors are on: click to dim this
+            // monitor as one or not, whatever its orientation
+            if state.reading_monitor_toggle.checked {
+                let reading = state.monitor_reading.get(i).copied().unwrap_or(false);
+                let (rw, rh) = measure_text(hdc, "Reading", fonts.xs);
+                let pill_w = rw + 12;
+                let pill_h = rh + 4;
+                let pill_x = pills_left - 6 - pill_w;
+                pills_left = pill_x;
+                let reading_rect = RECT {
+                    left: pill_x,
+                    top: badge_y,
+                    right: pill_x + pill_w,
+                    bottom: badge_y + pill_h,
+                };
+                let (pill_bg, pill_fg) = if reading {
+                    (CLR_BRAND, CLR_FOREGROUND)
+                } else {
+                    (CLR_SECONDARY, CLR_MUTED_FG)
+                };
+                draw_rounded_rect(hdc, &reading_rect, pill_h / 2, pill_bg, pill_bg);
+                draw_text_simple(
+                    hdc,
+                    "Reading",
+                    pill_x + (pill_w - rw) / 2,
+                    badge_y + (pill_h - rh) / 2,
+                    pill_fg,
+                    fonts.xs,
+                );
+                if state.monitor_reading_rects.len() <= i {
+                    state.monitor_reading_rects.resize(i + 1, RECT::default());
+                }
+                state.monitor_reading_rects[i] = reading_rect;
+                state.tooltip(
+                    reading_rect,
+                    "Click to dim this monitor as a reading monitor or like the others",
+                );
+            }
+
+            // Friendly monitor name above the slider, shortened to fit
+            // left of the pills
+            if let Some(name) = state.monitor_names.get(i) {
+                let shown = shorten_to_fit(hdc, name, pills_left - slider_left - 8, fonts.xs);
+                draw_text_simple(hdc, &shown, slider_left, card_top + 10, CLR_MUTED_FG, fonts.xs);
+                if shown != *name {
+                    // Shortened: the full name on hover
+                    let (nw, nh) = measure_text(hdc, &shown, fonts.xs);
+                    let name_rect = RECT {
+                        left: slider_left,
+                        top: card_top + 10,
+                        right: slider_left + nw,
+                        bottom: card_top + 10 + nh,
+                    };
+                    state.tooltip(name_rect, name.clone());
+                }
+            }
+
             // Slider track
             let slider_y = card_top + 36;
             let track_h = 8;
@@ -386,7 +806,7 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
             draw_rounded_rect(hdc, &track_rect, 4, CLR_SECONDARY, CLR_SECONDARY);
 
             let track_width = inner_right - slider_left;
-            let fill_w = ((state.monitor_sliders[i].value as f32 / 90.0) * track_width as f32) as i32;
+            let fill_w = (state.monitor_sliders[i].fraction() * track_width as f32) as i32;
             if fill_w > 0 {
                 let fill_rect = RECT {
                     left: slider_left,
@@ -411,6 +831,53 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
             card_top = card.bottom + GAP / 2;
         }
 
+        // Card: Monitor groups (group the ticked monitors)
+        if grouping {
+            let groups_card = RECT {
+                left: x,
+                top: card_top,
+                right: x + CONTENT_WIDTH,
+                bottom: card_top + 56,
+            };
+            draw_rounded_rect(hdc, &groups_card, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+            draw_text_simple(
+                hdc,
+                "Monitor Groups",
+                inner_x,
+                card_top + 10,
+                CLR_FOREGROUND,
+                fonts.small_bold,
+            );
+            draw_text_simple(
+                hdc,
+                "Select monitors by number to dim them with one slider",
+                inner_x,
+                card_top + 30,
+                CLR_MUTED_FG,
+                fonts.xs,
+            );
+            let ticked = state
+                .monitor_selected
+                .iter()
+                .filter(|&&ticked| ticked)
+                .count();
+            state.group_btn.disabled = ticked < 2;
+            state.group_btn.text = if ticked >= 2 {
+                format!("Group {}", ticked)
+            } else {
+                "Group".to_string()
+            };
+            state.group_btn.rect =
+                draw_button(hdc, inner_right, card_top + 14, &state.group_btn, fonts);
+            state.tooltip(
+                state.group_btn.rect,
+                "Dim the selected monitors with one slider",
+            );
+            card_top = groups_card.bottom + GAP / 2;
+        } else {
+            state.group_btn.rect = RECT::default();
+        }
+
         // Card: Dimmer Enabled
         let card2_top = card_top + GAP / 2;
         let card2 = RECT {
@@ -441,7 +908,12 @@ fn draw_dimmer_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         let toggle_x = inner_right - 44;
         state.enabled_toggle.rect =
             draw_toggle(hdc, toggle_x, card2_top + 16, state.enabled_toggle.checked);
+        state.tooltip(
+            state.enabled_toggle.rect,
+            "Turn dimming on or off on every screen",
+        );
 
+        draw_backlight_card(hdc, card2.bottom + GAP, state, fonts);
     } else {
         // Single-monitor mode: original layout
         draw_dimmer_tab_single(hdc, y, state, fonts);
@@ -473,7 +945,8 @@ fn draw_dimmer_tab_single(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts)
     );
 
     // Badge
-    let badge_text = format!("{}%", state.slider.value);
+    let boost = state.night_boost.filter(|_| state.enabled_toggle.checked);
+    let badge_text = night_boost::level_label(state.slider.level(), boost);
     let (bw, bh) = measure_text(hdc, &badge_text, fonts.xs);
     let badge_w = bw + 20;
     let badge_h = bh + 4;
@@ -510,7 +983,7 @@ fn draw_dimmer_tab_single(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts)
     let track_rect = state.slider.rect;
     draw_rounded_rect(hdc, &track_rect, 4, CLR_SECONDARY, CLR_SECONDARY);
 
-    let fill_w = ((state.slider.value as f32 / 90.0) * (inner_right - inner_x) as f32) as i32;
+    let fill_w = (state.slider.fraction() * (inner_right - inner_x) as f32) as i32;
     if fill_w > 0 {
         let fill_rect = RECT {
             left: inner_x,
@@ -579,6 +1052,93 @@ fn draw_dimmer_tab_single(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts)
     let toggle_x = inner_right - 44;
     state.enabled_toggle.rect =
         draw_toggle(hdc, toggle_x, card2_top + 16, state.enabled_toggle.checked);
+    state.tooltip(
+        state.enabled_toggle.rect,
+        "Turn dimming on or off on every screen",
+    );
+
+    draw_backlight_card(hdc, card2.bottom + GAP, state, fonts);
+}
+
+/// Hardware brightness (DDC/CI) card, one slider per monitor that supports
+/// it; nothing without any
+fn draw_backlight_card(hdc: HDC, top: i32, state: &mut UiState, fonts: &Fonts) {
+    if state.backlights.is_empty() {
+        return;
+    }
+    let x = PADDING;
+    let inner_x = x + 16;
+    let inner_right = x + CONTENT_WIDTH - 16;
+    let row_h = 36;
+    let card = RECT {
+        left: x,
+        top,
+        right: x + CONTENT_WIDTH,
+        bottom: top + 40 + row_h * state.backlights.len() as i32,
+    };
+    draw_rounded_rect(hdc, &card, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Monitor Backlight",
+        inner_x,
+        top + 12,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+
+    let track_h = 8;
+    let thumb_r = 9;
+    let name_w = 120;
+    let value_w = 40;
+    for (i, row) in state.backlights.iter_mut().enumerate() {
+        let row_top = top + 40 + row_h * i as i32;
+        let shown = shorten_to_fit(hdc, &row.name, name_w - 8, fonts.xs);
+        draw_text_simple(hdc, &shown, inner_x, row_top + 4, CLR_MUTED_FG, fonts.xs);
+        draw_text_right(
+            hdc,
+            &format!("{}%", row.percent),
+            inner_right,
+            row_top + 4,
+            CLR_FOREGROUND,
+            fonts.xs,
+        );
+
+        let slider_left = inner_x + name_w;
+        let slider_right = inner_right - value_w;
+        let slider_y = row_top + 8;
+        row.rect = RECT {
+            left: slider_left,
+            top: slider_y,
+            right: slider_right,
+            bottom: slider_y + track_h,
+        };
+        draw_rounded_rect(hdc, &row.rect, 4, CLR_SECONDARY, CLR_SECONDARY);
+        let fill_w = (row.fraction() * (slider_right - slider_left) as f32) as i32;
+        if fill_w > 0 {
+            let fill_rect = RECT {
+                left: slider_left,
+                top: slider_y,
+                right: slider_left + fill_w,
+                bottom: slider_y + track_h,
+            };
+            draw_rounded_rect(hdc, &fill_rect, 4, CLR_BRAND, CLR_BRAND);
+        }
+        draw_circle(hdc, row.thumb_x(), slider_y + track_h / 2, thumb_r, CLR_FOREGROUND);
+        row.thumb_rect = RECT {
+            left: slider_left - thumb_r,
+            top: slider_y - thumb_r - 4,
+            right: slider_right + thumb_r,
+            bottom: slider_y + track_h + thumb_r + 4,
+        };
+    }
+    let tip_area = RECT {
+        bottom: top + 32,
+        ..card
+    };
+    state.tooltip(
+        tip_area,
+        "The monitor's own brightness over DDC/CI, set on top of the dimming",
+    );
 }
 
 fn draw_settings_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
@@ -627,6 +1187,10 @@ fn draw_settings_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         card1_top + 40,
         state.autostart_toggle.checked,
     );
+    state.tooltip(
+        state.autostart_toggle.rect,
+        "Start SaveMyEyes when you sign in to Windows",
+    );
 
     // Divider
     let div1_y = card1_top + 72;
@@ -661,61 +1225,515 @@ fn draw_settings_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         div1_y + 12,
         state.multi_monitor_toggle.checked,
     );
+    let tip = state.capabilities.tooltip(
+        Feature::PerMonitor,
+        "Give each monitor its own dimming level",
+    );
+    state.tooltip(state.multi_monitor_toggle.rect, tip);
 
-    // Card 2: Updates
-    let card2_top = card1.bottom + GAP;
-    let card2 = RECT {
+    // Card 2: Updates (left out of builds without the updater)
+    let card3_top = if updater::ENABLED {
+        let card2_top = card1.bottom + GAP;
+        let card2 = RECT {
+            left: x,
+            top: card2_top,
+            right: x + CONTENT_WIDTH,
+            bottom: card2_top + 130,
+        };
+        draw_rounded_rect(hdc, &card2, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+
+        draw_text_simple(
+            hdc,
+            "Updates",
+            inner_x,
+            card2_top + 12,
+            CLR_FOREGROUND,
+            fonts.small_bold,
+        );
+
+        // Show current version in tiny text next to "Updates" heading
+        let version_text = format!("v{}", updater::APP_VERSION);
+        draw_text_simple(
+            hdc,
+            &version_text,
+            inner_x + 60,
+            card2_top + 15,
+            CLR_MUTED_FG,
+            fonts.xxs,
+        );
+        draw_text_simple(
+            hdc,
+            "Auto-Update",
+            inner_x,
+            card2_top + 38,
+            CLR_FOREGROUND,
+            fonts.small_bold,
+        );
+        draw_text_simple(
+            hdc,
+            "Automatically download and install updates",
+            inner_x,
+            card2_top + 54,
+            CLR_MUTED_FG,
+            fonts.xs,
+        );
+        state.auto_update_toggle.rect = draw_toggle(
+            hdc,
+            toggle_x,
+            card2_top + 42,
+            state.auto_update_toggle.checked,
+        );
+        state.tooltip(
+            state.auto_update_toggle.rect,
+            "Download and install new versions in the background; when off, only Check Now updates",
+        );
+
+        // Divider
+        let div_y = card2_top + 74;
+        unsafe {
+            let pen = CreatePen(PS_SOLID, 1, CLR_BORDER);
+            let old = SelectObject(hdc, HGDIOBJ::from(pen));
+            let _ = MoveToEx(hdc, inner_x, div_y, None);
+            let _ = LineTo(hdc, inner_right, div_y);
+            SelectObject(hdc, old);
+            let _ = DeleteObject(HGDIOBJ::from(pen));
+        }
+
+        draw_text_simple(
+            hdc,
+            "Check for Updates",
+            inner_x,
+            div_y + 12,
+            CLR_FOREGROUND,
+            fonts.small_bold,
+        );
+
+        if !state.update_status_text.is_empty() {
+            let s = state.update_status_text.clone();
+            draw_text_simple(hdc, &s, inner_x, div_y + 28, CLR_BRAND, fonts.xs);
+        }
+
+        state.check_update_btn.rect =
+            draw_button(hdc, inner_right, div_y + 10, &state.check_update_btn, fonts);
+        state.tooltip(state.check_update_btn.rect, "Look for a new version now");
+
+        card2.bottom + GAP
+    } else {
+        state.auto_update_toggle.rect = RECT::default();
+        state.check_update_btn.rect = RECT::default();
+        card1.bottom + GAP
+    };
+
+    // Card 3: Pause while drawing
+    let card3 = RECT {
         left: x,
-        top: card2_top,
+        top: card3_top,
         right: x + CONTENT_WIDTH,
-        bottom: card2_top + 130,
+        bottom: card3_top + 56,
     };
-    draw_rounded_rect(hdc, &card2, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
-
+    draw_rounded_rect(hdc, &card3, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
     draw_text_simple(
         hdc,
-        "Updates",
+        "Pause While Drawing",
         inner_x,
-        card2_top + 12,
+        card3_top + 10,
         CLR_FOREGROUND,
         fonts.small_bold,
     );
-
-    // Show current version in tiny text next to "Updates" heading
-    let version_text = format!("v{}", updater::APP_VERSION);
     draw_text_simple(
         hdc,
-        &version_text,
-        inner_x + 60,
-        card2_top + 15,
+        &state.pen_pause_summary,
+        inner_x,
+        card3_top + 28,
         CLR_MUTED_FG,
-        fonts.xxs,
+        fonts.xs,
     );
-    draw_text_simple(
+    state.pen_pause_toggle.rect = draw_toggle(
         hdc,
-        "Auto-Update",
-        inner_x,
-        card2_top + 38,
-        CLR_FOREGROUND,
-        fonts.small_bold,
+        toggle_x,
+        card3_top + 16,
+        state.pen_pause_toggle.checked,
     );
-    draw_text_simple(
-        hdc,
-        "Automatically download and install updates",
+    state.tooltip(
+        state.pen_pause_toggle.rect,
+        "Lift dimming while a pen or tablet is drawing; it returns after a short idle",
+    );
+
+    // Card 4: Night boost
+    let card4_top = card3.bottom + GAP;
+    let card4 = RECT {
+        left: x,
+        top: card4_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card4_top + 56,
+    };
+    draw_rounded_rect(hdc, &card4, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Night Boost",
+        inner_x,
+        card4_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.night_boost_summary,
+        inner_x,
+        card4_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.night_boost_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card4_top + 16,
+        state.night_boost_toggle.checked,
+    );
+    state.tooltip(
+        state.night_boost_toggle.rect,
+        "Dim a little more during night hours, on top of your level",
+    );
+
+    // Card 5: Only dim bright panels
+    let card5_top = card4.bottom + GAP;
+    let card5 = RECT {
+        left: x,
+        top: card5_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card5_top + 56,
+    };
+    draw_rounded_rect(hdc, &card5, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Only Dim Bright Panels",
+        inner_x,
+        card5_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.brightness_rule_summary,
+        inner_x,
+        card5_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.brightness_rule_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card5_top + 16,
+        state.brightness_rule_toggle.checked,
+    );
+    state.tooltip(
+        state.brightness_rule_toggle.rect,
+        "Leave a laptop panel undimmed while its backlight is turned down; external monitors stay dimmed",
+    );
+
+    // Card 6: Contrast guard
+    let card6_top = card5.bottom + GAP;
+    let card6 = RECT {
+        left: x,
+        top: card6_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card6_top + 56,
+    };
+    draw_rounded_rect(hdc, &card6, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Contrast Guard",
+        inner_x,
+        card6_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.contrast_guard_summary,
+        inner_x,
+        card6_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.contrast_guard_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card6_top + 16,
+        state.contrast_guard_toggle.checked,
+    );
+    state.tooltip(
+        state.contrast_guard_toggle.rect,
+        "Limit dimming while the focused monitor already shows dark content, such as a dark editor theme",
+    );
+
+    // Card 7: Scheduled dimming (builds with the schedule thread)
+    let cards_bottom = if scheduler::ENABLED {
+        let card7_top = card6.bottom + GAP;
+        let card7 = RECT {
+            left: x,
+            top: card7_top,
+            right: x + CONTENT_WIDTH,
+            bottom: card7_top + 88,
+        };
+        draw_rounded_rect(hdc, &card7, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+        draw_text_simple(
+            hdc,
+            "Scheduled Dimming",
+            inner_x,
+            card7_top + 10,
+            CLR_FOREGROUND,
+            fonts.small_bold,
+        );
+        draw_text_simple(
+            hdc,
+            &state.schedule_summary,
+            inner_x,
+            card7_top + 28,
+            CLR_MUTED_FG,
+            fonts.xs,
+        );
+        state.schedule_toggle.rect = draw_toggle(
+            hdc,
+            toggle_x,
+            card7_top + 16,
+            state.schedule_toggle.checked,
+        );
+        state.tooltip(
+            state.schedule_toggle.rect,
+            "Turn dimming on and off at set times of day; toggling by hand lasts until the next one",
+        );
+        // Day chips, Monday first
+        let chip_w = (inner_right - inner_x - 6 * 6) / 7;
+        for (i, day) in scheduler::WEEK.iter().enumerate() {
+            let left = inner_x + i as i32 * (chip_w + 6);
+            let chip = RECT {
+                left,
+                top: card7_top + 54,
+                right: left + chip_w,
+                bottom: card7_top + 76,
+            };
+            let (fill, text_color) = if state.schedule_days[i] {
+                (CLR_BRAND, CLR_FOREGROUND)
+            } else {
+                (CLR_SECONDARY, CLR_MUTED_FG)
+            };
+            draw_rounded_rect(hdc, &chip, 11, fill, fill);
+            let label = day.to_string();
+            let (tw, th) = measure_text(hdc, &label, fonts.xs);
+            draw_text_simple(
+                hdc,
+                &label,
+                left + (chip_w - tw) / 2,
+                chip.top + (22 - th) / 2,
+                text_color,
+                fonts.xs,
+            );
+            state.schedule_day_rects[i] = chip;
+        }
+        card7.bottom
+    } else {
+        state.schedule_toggle.rect = RECT::default();
+        state.schedule_day_rects = [RECT::default(); 7];
+        card6.bottom
+    };
+
+    // Restore link
+    let link = "Restore settings from backup\u{2026}";
+    let (link_w, link_h) = measure_text(hdc, link, fonts.xs);
+    draw_text_simple(hdc, link, inner_x, cards_bottom + 10, CLR_BRAND, fonts.xs);
+    state.restore_backup_rect = RECT {
+        left: inner_x,
+        top: cards_bottom + 10,
+        right: inner_x + link_w,
+        bottom: cards_bottom + 10 + link_h,
+    };
+    state.tooltip(
+        state.restore_backup_rect,
+        "Go back to settings saved before an update or a monitor change",
+    );
+}
+
+fn draw_advanced_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
+    let x = PADDING;
+    let inner_x = x + 16;
+    let inner_right = x + CONTENT_WIDTH - 16;
+    let toggle_x = inner_right - 44;
+
+    // Card 1: Dimming
+    let card1_top = y;
+    let card1 = RECT {
+        left: x,
+        top: card1_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card1_top + 168,
+    };
+    draw_rounded_rect(hdc, &card1, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+
+    draw_text_simple(
+        hdc,
+        "Dimming",
+        inner_x,
+        card1_top + 12,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Perceptual Scale",
         inner_x,
-        card2_top + 54,
+        card1_top + 36,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Make slider steps look evenly spaced",
+        inner_x,
+        card1_top + 52,
         CLR_MUTED_FG,
         fonts.xs,
     );
-    state.auto_update_toggle.rect = draw_toggle(
+    state.perceptual_toggle.rect = draw_toggle(
         hdc,
         toggle_x,
-        card2_top + 42,
-        state.auto_update_toggle.checked,
+        card1_top + 40,
+        state.perceptual_toggle.checked,
+    );
+    state.tooltip(
+        state.perceptual_toggle.rect,
+        "Space slider steps by how dark they look rather than by overlay alpha",
     );
 
     // Divider
-    let div_y = card2_top + 74;
+    let div1_y = card1_top + 72;
+    unsafe {
+        let pen = CreatePen(PS_SOLID, 1, CLR_BORDER);
+        let old = SelectObject(hdc, HGDIOBJ::from(pen));
+        let _ = MoveToEx(hdc, inner_x, div1_y, None);
+        let _ = LineTo(hdc, inner_right, div1_y);
+        SelectObject(hdc, old);
+        let _ = DeleteObject(HGDIOBJ::from(pen));
+    }
+
+    draw_text_simple(
+        hdc,
+        "Magnifier Compatibility",
+        inner_x,
+        div1_y + 8,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Use gamma dimming while Magnifier is on",
+        inner_x,
+        div1_y + 24,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.zoom_compat_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        div1_y + 12,
+        state.zoom_compat_toggle.checked,
+    );
+    state.tooltip(
+        state.zoom_compat_toggle.rect,
+        "Magnifier can't zoom past the overlay; dim with gamma ramps while it runs",
+    );
+
+    // Divider
+    let div2_y = card1_top + 120;
+    unsafe {
+        let pen = CreatePen(PS_SOLID, 1, CLR_BORDER);
+        let old = SelectObject(hdc, HGDIOBJ::from(pen));
+        let _ = MoveToEx(hdc, inner_x, div2_y, None);
+        let _ = LineTo(hdc, inner_right, div2_y);
+        SelectObject(hdc, old);
+        let _ = DeleteObject(HGDIOBJ::from(pen));
+    }
+
+    draw_text_simple(
+        hdc,
+        "Gradient",
+        inner_x,
+        div2_y + 8,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Darker at the top",
+        inner_x,
+        div2_y + 24,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.gradient_toggle.rect =
+        draw_toggle(hdc, toggle_x, div2_y + 12, state.gradient_toggle.checked);
+    state.tooltip(
+        state.gradient_toggle.rect,
+        "Dim the toolbars at the top more than the content below (overlay monitors only)",
+    );
+    state.gradient_bottom_btn.rect = draw_button(
+        hdc,
+        toggle_x - 8,
+        div2_y + 10,
+        &state.gradient_bottom_btn,
+        fonts,
+    );
+    state.tooltip(
+        state.gradient_bottom_btn.rect,
+        "Level at the bottom edge, as a share of the chosen level. Click to cycle",
+    );
+    state.gradient_top_btn.rect = draw_button(
+        hdc,
+        state.gradient_bottom_btn.rect.left - 6,
+        div2_y + 10,
+        &state.gradient_top_btn,
+        fonts,
+    );
+    state.tooltip(
+        state.gradient_top_btn.rect,
+        "Level at the top edge, as a share of the chosen level. Click to cycle",
+    );
+
+    // Card 2: Diagnostics
+    let card2_top = card1.bottom + GAP;
+    let card2 = RECT {
+        left: x,
+        top: card2_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card2_top + 112,
+    };
+    draw_rounded_rect(hdc, &card2, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+
+    draw_text_simple(
+        hdc,
+        "Diagnostics",
+        inner_x,
+        card2_top + 14,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Copy display layout for bug reports",
+        inner_x,
+        card2_top + 32,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.copy_report_btn.rect =
+        draw_button(hdc, inner_right, card2_top + 16, &state.copy_report_btn, fonts);
+    state.tooltip(
+        state.copy_report_btn.rect,
+        "Copy monitor, HDR and overlay details to the clipboard",
+    );
+
+    // Divider
+    let div_y = card2_top + 60;
     unsafe {
         let pen = CreatePen(PS_SOLID, 1, CLR_BORDER);
         let old = SelectObject(hdc, HGDIOBJ::from(pen));
@@ -727,43 +1745,228 @@ fn draw_settings_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
 
     draw_text_simple(
         hdc,
-        "Check for Updates",
+        "Run Elevated",
+        inner_x,
+        div_y + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Cover admin windows (asks for UAC)",
+        inner_x,
+        div_y + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.elevate_btn.rect =
+        draw_button(hdc, inner_right, div_y + 12, &state.elevate_btn, fonts);
+    state.tooltip(
+        state.elevate_btn.rect,
+        "Restart as administrator so dimming also covers elevated windows",
+    );
+
+    // Card 3: Follow System Theme
+    let card3_top = card2.bottom + GAP;
+    let card3 = RECT {
+        left: x,
+        top: card3_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card3_top + 136,
+    };
+    draw_rounded_rect(hdc, &card3, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+
+    draw_text_simple(
+        hdc,
+        "Follow System Theme",
         inner_x,
-        div_y + 12,
+        card3_top + 14,
         CLR_FOREGROUND,
         fonts.small_bold,
     );
+    draw_text_simple(
+        hdc,
+        "Switch profiles with Windows dark mode",
+        inner_x,
+        card3_top + 32,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.theme_auto_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card3_top + 18,
+        state.theme_auto_toggle.checked,
+    );
+    state.tooltip(
+        state.theme_auto_toggle.rect,
+        "Apply one profile when Windows switches to dark mode and another in light mode",
+    );
 
-    if !state.update_status_text.is_empty() {
-        let s = state.update_status_text.clone();
-        draw_text_simple(hdc, &s, inner_x, div_y + 28, CLR_BRAND, fonts.xs);
+    // Divider
+    let div3_y = card3_top + 60;
+    unsafe {
+        let pen = CreatePen(PS_SOLID, 1, CLR_BORDER);
+        let old = SelectObject(hdc, HGDIOBJ::from(pen));
+        let _ = MoveToEx(hdc, inner_x, div3_y, None);
+        let _ = LineTo(hdc, inner_right, div3_y);
+        SelectObject(hdc, old);
+        let _ = DeleteObject(HGDIOBJ::from(pen));
     }
 
-    // Button
-    let btn_text = state.check_update_btn.text.clone();
-    let (bw, bh) = measure_text(hdc, &btn_text, fonts.xs);
+    let picker_fg = if state.theme_auto_toggle.checked {
+        CLR_FOREGROUND
+    } else {
+        CLR_MUTED_FG
+    };
+    draw_text_simple(hdc, "In dark mode", inner_x, div3_y + 14, picker_fg, fonts.small);
+    state.theme_dark_btn.rect =
+        draw_button(hdc, inner_right, div3_y + 8, &state.theme_dark_btn, fonts);
+    state.tooltip(
+        state.theme_dark_btn.rect,
+        "Click to cycle through saved profiles",
+    );
+    draw_text_simple(hdc, "In light mode", inner_x, div3_y + 48, picker_fg, fonts.small);
+    state.theme_light_btn.rect =
+        draw_button(hdc, inner_right, div3_y + 42, &state.theme_light_btn, fonts);
+    state.tooltip(
+        state.theme_light_btn.rect,
+        "Click to cycle through saved profiles",
+    );
+
+    // Card 4: Windows color filters
+    let card4_top = card3.bottom + GAP;
+    let card4 = RECT {
+        left: x,
+        top: card4_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card4_top + 56,
+    };
+    draw_rounded_rect(hdc, &card4, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Windows Color Filters",
+        inner_x,
+        card4_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.color_filters_summary,
+        inner_x,
+        card4_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.color_filters_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card4_top + 16,
+        state.color_filters_toggle.checked,
+    );
+    state.tooltip(
+        state.color_filters_toggle.rect,
+        "Switch the Windows color filter along with profiles that set one",
+    );
+
+    // Card 5: Reading monitors
+    let card5_top = card4.bottom + GAP;
+    let card5 = RECT {
+        left: x,
+        top: card5_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card5_top + 56,
+    };
+    draw_rounded_rect(hdc, &card5, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Reading Monitors",
+        inner_x,
+        card5_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.reading_monitor_summary,
+        inner_x,
+        card5_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.reading_monitor_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card5_top + 16,
+        state.reading_monitor_toggle.checked,
+    );
+    state.tooltip(
+        state.reading_monitor_toggle.rect,
+        "Dim monitors turned to portrait a little more; the Reading pill on each monitor overrides it",
+    );
+
+    // Card 6: Edge vignette
+    let card6_top = card5.bottom + GAP;
+    let card6 = RECT {
+        left: x,
+        top: card6_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card6_top + 56,
+    };
+    draw_rounded_rect(hdc, &card6, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Edge Vignette",
+        inner_x,
+        card6_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.vignette_summary,
+        inner_x,
+        card6_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.vignette_toggle.rect = draw_toggle(
+        hdc,
+        toggle_x,
+        card6_top + 16,
+        state.vignette_toggle.checked,
+    );
+    state.tooltip(
+        state.vignette_toggle.rect,
+        "Dim bright title bars and taskbars along the screen edges more (overlay monitors only)",
+    );
+}
+
+/// Draw a secondary push button right-aligned at `right_x`; returns its rect.
+fn draw_button(hdc: HDC, right_x: i32, y: i32, btn: &ButtonState, fonts: &Fonts) -> RECT {
+    let (bw, bh) = measure_text(hdc, &btn.text, fonts.xs);
     let btn_w = bw + 28;
     let btn_h = bh + 12;
-    let btn_x = inner_right - btn_w;
-    let btn_y = div_y + 10;
+    let btn_x = right_x - btn_w;
     let btn_rect = RECT {
         left: btn_x,
-        top: btn_y,
+        top: y,
         right: btn_x + btn_w,
-        bottom: btn_y + btn_h,
+        bottom: y + btn_h,
     };
 
-    let btn_bg = if state.check_update_btn.hover {
+    let btn_bg = if btn.hover {
         CLR_MUTED_FG
     } else {
         CLR_SECONDARY
     };
-    let btn_border = if state.check_update_btn.hover {
+    let btn_border = if btn.hover {
         CLR_MUTED_FG
     } else {
         CLR_BORDER
     };
-    let btn_fg = if state.check_update_btn.disabled {
+    let btn_fg = if btn.disabled {
         CLR_MUTED_FG
     } else {
         CLR_FOREGROUND
@@ -771,13 +1974,13 @@ fn draw_settings_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
     draw_rounded_rect(hdc, &btn_rect, CARD_RADIUS, btn_bg, btn_border);
     draw_text_simple(
         hdc,
-        &btn_text,
+        &btn.text,
         btn_x + (btn_w - bw) / 2,
-        btn_y + (btn_h - bh) / 2,
+        y + (btn_h - bh) / 2,
         btn_fg,
         fonts.xs,
     );
-    state.check_update_btn.rect = btn_rect;
+    btn_rect
 }
 
 fn draw_shortcuts_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
@@ -789,7 +1992,7 @@ fn draw_shortcuts_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         left: x,
         top: y,
         right: x + CONTENT_WIDTH,
-        bottom: y + 160,
+        bottom: y + 188,
     };
     draw_rounded_rect(hdc, &card, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
 
@@ -802,13 +2005,21 @@ fn draw_shortcuts_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
         fonts.small_bold,
     );
 
-    let labels = ["Toggle Dimmer", "Increase Dimming", "Decrease Dimming"];
+    let labels = [
+        "Toggle Dimmer",
+        "Increase Dimming",
+        "Decrease Dimming",
+        "Movie Mode",
+        "Boost Here",
+    ];
     let keys = state.shortcut_texts.clone();
 
     for (i, (label, key)) in labels.iter().zip(keys.iter()).enumerate() {
-        let row_y = y + 44 + (i as i32) * 38;
+        let row_y = y + 44 + (i as i32) * 28;
         draw_text_simple(hdc, label, inner_x, row_y + 4, CLR_MUTED_FG, fonts.small);
 
+        let recording = state.recording_shortcut == Some(i);
+        let key = if recording { "Press keys…" } else { key.as_str() };
         let (kw, kh) = measure_text(hdc, key, fonts.mono);
         let kbd_w = kw + 16;
         let kbd_h = kh + 8;
@@ -819,21 +2030,232 @@ fn draw_shortcuts_tab(hdc: HDC, y: i32, state: &mut UiState, fonts: &Fonts) {
             right: kbd_x + kbd_w,
             bottom: row_y + kbd_h,
         };
-        draw_rounded_rect(hdc, &kbd_rect, CARD_RADIUS - 2, CLR_SECONDARY, CLR_BORDER);
+        let (fill, border, text) = if recording {
+            (CLR_BACKGROUND, CLR_BRAND, CLR_FOREGROUND)
+        } else {
+            (CLR_SECONDARY, CLR_BORDER, CLR_MUTED_FG)
+        };
+        draw_rounded_rect(hdc, &kbd_rect, CARD_RADIUS - 2, fill, border);
         draw_text_simple(
             hdc,
             key,
             kbd_x + (kbd_w - kw) / 2,
             row_y + (kbd_h - kh) / 2,
-            CLR_MUTED_FG,
+            text,
             fonts.mono,
         );
+        if i < state.shortcut_rects.len() {
+            state.shortcut_rects[i] = kbd_rect;
+            state.tooltip(kbd_rect, "Click, then press the new shortcut");
+        } else if i == 3 {
+            state.tooltip(kbd_rect, "Movie mode's shortcut can't be changed");
+        } else {
+            state.tooltip(
+                kbd_rect,
+                "Less dimming while this window keeps the focus; can't be changed",
+            );
+        }
     }
 
-    let hint = "Press a key combo while focused on a shortcut to change it.";
+    let hint = "Click a shortcut, then press the new keys. Esc cancels.";
     let (hw, _) = measure_text(hdc, hint, fonts.xxs);
     let hint_x = PADDING + (CONTENT_WIDTH - hw) / 2;
     draw_text_simple(hdc, hint, hint_x, card.bottom + 8, CLR_MUTED_FG, fonts.xxs);
+
+    // Card 2: Mouse buttons
+    let card2_top = card.bottom + 32;
+    let card2 = RECT {
+        left: x,
+        top: card2_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card2_top + 56,
+    };
+    draw_rounded_rect(hdc, &card2, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Mouse Button Shortcuts",
+        inner_x,
+        card2_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        &state.mouse_hotkeys_summary,
+        inner_x,
+        card2_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.mouse_hotkeys_toggle.rect = draw_toggle(
+        hdc,
+        inner_right - 44,
+        card2_top + 16,
+        state.mouse_hotkeys_toggle.checked,
+    );
+    state.tooltip(
+        state.mouse_hotkeys_toggle.rect,
+        "Change dimming with mouse buttons while holding modifier keys",
+    );
+
+    // Card 3: Set level chord
+    let card3_top = card2.bottom + GAP;
+    let card3 = RECT {
+        left: x,
+        top: card3_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card3_top + 56,
+    };
+    draw_rounded_rect(hdc, &card3, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Set Level Chord",
+        inner_x,
+        card3_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "Toggle shortcut, then two digits (4, 5 = 45%)",
+        inner_x,
+        card3_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.level_chord_toggle.rect = draw_toggle(
+        hdc,
+        inner_right - 44,
+        card3_top + 16,
+        state.level_chord_toggle.checked,
+    );
+    state.tooltip(
+        state.level_chord_toggle.rect,
+        "Press the toggle shortcut, then two digits to jump to that level",
+    );
+
+    // Card 4: Hotkey-less mode
+    let card4_top = card3.bottom + GAP;
+    let card4 = RECT {
+        left: x,
+        top: card4_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card4_top + 56,
+    };
+    draw_rounded_rect(hdc, &card4, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Disable Global Shortcuts",
+        inner_x,
+        card4_top + 10,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    draw_text_simple(
+        hdc,
+        "No system-wide hotkeys or input hooks at all",
+        inner_x,
+        card4_top + 28,
+        CLR_MUTED_FG,
+        fonts.xs,
+    );
+    state.disable_shortcuts_toggle.rect = draw_toggle(
+        hdc,
+        inner_right - 44,
+        card4_top + 16,
+        state.disable_shortcuts_toggle.checked,
+    );
+    state.tooltip(
+        state.disable_shortcuts_toggle.rect,
+        "Register no hotkeys or input hooks; use this window and the tray instead",
+    );
+
+    // Card 5: Custom actions, one row each
+    let card5_top = card4.bottom + GAP;
+    let rows = state.custom_actions.len().max(1) as i32;
+    let card5 = RECT {
+        left: x,
+        top: card5_top,
+        right: x + CONTENT_WIDTH,
+        bottom: card5_top + 44 + rows * 36,
+    };
+    draw_rounded_rect(hdc, &card5, CARD_RADIUS, CLR_BACKGROUND, CLR_BORDER);
+    draw_text_simple(
+        hdc,
+        "Custom Actions",
+        inner_x,
+        card5_top + 14,
+        CLR_FOREGROUND,
+        fonts.small_bold,
+    );
+    state.new_action_btn.rect = draw_button(
+        hdc,
+        inner_right,
+        card5_top + 10,
+        &state.new_action_btn,
+        fonts,
+    );
+    state.tooltip(
+        state.new_action_btn.rect,
+        "Add an action that runs several steps from one hotkey",
+    );
+    if state.custom_actions.is_empty() {
+        draw_text_simple(
+            hdc,
+            "e.g. apply a profile, then pause, on one hotkey",
+            inner_x,
+            card5_top + 48,
+            CLR_MUTED_FG,
+            fonts.xs,
+        );
+    }
+    for i in 0..state.custom_actions.len() {
+        let row_y = card5_top + 44 + i as i32 * 36;
+        let btn_rect = draw_button(
+            hdc,
+            inner_right,
+            row_y + 2,
+            &state.custom_actions[i].edit_btn,
+            fonts,
+        );
+        let row = &state.custom_actions[i];
+        let hotkey = if row.hotkey.is_empty() {
+            "No hotkey"
+        } else {
+            &row.hotkey
+        };
+        let title = format!("{}  \u{00B7}  {}", row.name, hotkey);
+        let max_w = btn_rect.left - inner_x - 12;
+        let title = shorten_to_fit(hdc, &title, max_w, fonts.xs);
+        draw_text_simple(hdc, &title, inner_x, row_y, CLR_FOREGROUND, fonts.xs);
+        let summary = shorten_to_fit(hdc, &row.summary, max_w, fonts.xxs);
+        draw_text_simple(hdc, &summary, inner_x, row_y + 16, CLR_MUTED_FG, fonts.xxs);
+        let shortened = summary != row.summary;
+        let full = row.summary.clone();
+        state.custom_actions[i].edit_btn.rect = btn_rect;
+        state.tooltip(btn_rect, "Add or remove steps, pick the hotkey or delete");
+        if shortened {
+            let (sw, sh) = measure_text(hdc, &summary, fonts.xxs);
+            let summary_rect = RECT {
+                left: inner_x,
+                top: row_y + 16,
+                right: inner_x + sw,
+                bottom: row_y + 16 + sh,
+            };
+            state.tooltip(summary_rect, full);
+        }
+    }
+}
+
+/// `text`, cut short with an ellipsis until it is at most `max_w` wide
+fn shorten_to_fit(hdc: HDC, text: &str, max_w: i32, font: HFONT) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    let mut shown = text.to_string();
+    while chars.len() > 1 && measure_text(hdc, &shown, font).0 > max_w {
+        chars.pop();
+        shown = format!("{}…", chars.iter().collect::<String>().trim_end());
+    }
+    shown
 }
 
 fn draw_toggle(hdc: HDC, x: i32, y: i32, checked: bool) -> RECT {
@@ -1,4 +1,4 @@
-use windows::Win32::Foundation::COLORREF;
+use crate::sys::COLORREF;
 
 // ── Color palette matching the shadcn dark theme exactly ─────────────────────
 
@@ -27,7 +27,7 @@ pub const CLR_INPUT: COLORREF = COLORREF(0x003B291E);
 
 /// Main window client area dimensions
 pub const WINDOW_WIDTH: i32 = 400;
-pub const WINDOW_HEIGHT: i32 = 580;
+pub const WINDOW_HEIGHT: i32 = 848;
 
 /// Padding inside the window
 pub const PADDING: i32 = 24;
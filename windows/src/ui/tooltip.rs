@@ -0,0 +1,123 @@
+// Hover tooltips for the settings window.
+//
+// The controls are painted rather than child windows, so there is nothing
+// for a tooltip to attach to. Instead every paint registers the hit rect of
+// each control with its text (UiState::tooltip), and `sync` mirrors that list
+// into one comctl32 tooltip window as rectangle tools. TTF_SUBCLASS lets the
+// tooltip watch the mouse itself, so hover delay, hiding on mouse leave and
+// placement all follow the system. Tools are only replaced when the list
+// changed (tab switch, toggled text, resized card), not on every repaint.
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Controls::{
+    InitCommonControlsEx, ICC_WIN95_CLASSES, INITCOMMONCONTROLSEX, TOOLTIPS_CLASSW, TTF_SUBCLASS,
+    TTM_ADDTOOLW, TTM_DELTOOLW, TTM_SETMAXTIPWIDTH, TTS_ALWAYSTIP, TTS_NOPREFIX, TTTOOLINFOW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, SendMessageW, CW_USEDEFAULT, WINDOW_STYLE, WS_EX_TOPMOST, WS_POPUP,
+};
+
+use super::controls::Tooltip;
+
+/// Longest line before the text wraps (pixels)
+const MAX_WIDTH: isize = 280;
+
+/// Tooltip window (0 = not created)
+static TOOLTIP_HWND: AtomicIsize = AtomicIsize::new(0);
+/// Tools currently registered, as last passed to `sync`
+static SYNCED: Mutex<Vec<Tooltip>> = Mutex::new(Vec::new());
+
+/// Create the tooltip window for `parent` (once, at settings window creation).
+pub fn create(parent: HWND) {
+    unsafe {
+        let icc = INITCOMMONCONTROLSEX {
+            dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
+            dwICC: ICC_WIN95_CLASSES,
+        };
+        let _ = InitCommonControlsEx(&icc);
+
+        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+        let hwnd = match CreateWindowExW(
+            WS_EX_TOPMOST,
+            TOOLTIPS_CLASSW,
+            PCWSTR::null(),
+            WS_POPUP | WINDOW_STYLE(TTS_ALWAYSTIP | TTS_NOPREFIX),
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            Some(parent),
+            None,
+            Some(hinstance.into()),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(e) => {
+                eprintln!("[tooltip] could not create tooltip window: {}", e);
+                return;
+            }
+        };
+        SendMessageW(
+            hwnd,
+            TTM_SETMAXTIPWIDTH,
+            Some(WPARAM(0)),
+            Some(LPARAM(MAX_WIDTH)),
+        );
+        TOOLTIP_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+    }
+}
+
+/// Register `tips` as the tools of `parent`, replacing the previous ones if
+/// they differ (after every paint).
+pub fn sync(parent: HWND, tips: &[Tooltip]) {
+    let raw = TOOLTIP_HWND.load(Ordering::SeqCst);
+    if raw == 0 {
+        return;
+    }
+    let mut synced = SYNCED.lock().unwrap();
+    if synced.as_slice() == tips {
+        return;
+    }
+    let hwnd = HWND(raw as *mut std::ffi::c_void);
+    unsafe {
+        for id in 0..synced.len() {
+            let mut info = tool_info(parent, id);
+            SendMessageW(
+                hwnd,
+                TTM_DELTOOLW,
+                None,
+                Some(LPARAM(&mut info as *mut _ as isize)),
+            );
+        }
+        for (id, tip) in tips.iter().enumerate() {
+            // The tooltip copies the text
+            let mut text: Vec<u16> = tip.text.encode_utf16().chain(std::iter::once(0)).collect();
+            let mut info = tool_info(parent, id);
+            info.uFlags = TTF_SUBCLASS;
+            info.rect = tip.rect;
+            info.lpszText = PWSTR(text.as_mut_ptr());
+            SendMessageW(
+                hwnd,
+                TTM_ADDTOOLW,
+                None,
+                Some(LPARAM(&mut info as *mut _ as isize)),
+            );
+        }
+    }
+    *synced = tips.to_vec();
+}
+
+/// TTTOOLINFOW for tool `id` of `parent`. The size leaves out `lpReserved`:
+/// without a common controls v6 manifest comctl32 5.x rejects the full size.
+fn tool_info(parent: HWND, id: usize) -> TTTOOLINFOW {
+    TTTOOLINFOW {
+        cbSize: (std::mem::size_of::<TTTOOLINFOW>() - std::mem::size_of::<usize>()) as u32,
+        hwnd: parent,
+        uId: id,
+        ..Default::default()
+    }
+}
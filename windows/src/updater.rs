@@ -3,12 +3,25 @@
 // Re-export shared constants and types
 pub use savemyeyes_shared::updater::APP_VERSION;
 pub use savemyeyes_shared::updater::UpdateResult;
+pub use savemyeyes_shared::updater::set_endpoint;
+pub use savemyeyes_shared::updater::ENABLED;
+
+use std::sync::{Arc, Mutex};
+
+use savemyeyes_shared::updater::{now_secs, UpdateChoice};
+
+use crate::config::{self, AppConfig};
 
 /// Check for updates (looks for .exe assets)
 pub fn check_for_update(current_version: &str) -> UpdateResult {
     savemyeyes_shared::updater::check_for_update(current_version, ".exe")
 }
 
+/// Check for updates because the user asked (see shared check_for_update_now)
+pub fn check_for_update_now(current_version: &str) -> UpdateResult {
+    savemyeyes_shared::updater::check_for_update_now(current_version, ".exe")
+}
+
 /// Open a URL in the default browser (Win32 ShellExecuteW)
 pub fn open_url(url: &str) {
     let url_wide: Vec<u16> = url.encode_utf16().chain(std::iter::once(0)).collect();
@@ -49,6 +62,8 @@ pub fn apply_update_and_relaunch(downloaded_path: &std::path::Path) -> Result<()
         })?;
 
     let _ = std::fs::remove_file(downloaded_path);
+    // The new process puts the journaled dimming back before anything else
+    savemyeyes_shared::journal::hand_over();
 
     let _ = std::process::Command::new(&current_exe)
         .arg("--updated")
@@ -70,25 +85,90 @@ pub fn cleanup_old_exe() {
     }
 }
 
-/// Show a Win32 Yes/No message box asking the user to update.
-pub fn prompt_update_dialog(new_version: &str) -> bool {
+/// Whether the automatic startup check may prompt for `version`
+/// (not skipped, and any "remind me later" wait is over).
+pub fn should_auto_prompt(config: &Arc<Mutex<AppConfig>>, version: &str) -> bool {
+    config.lock().unwrap().update_snooze.should_prompt(version, now_secs())
+}
+
+/// Ask the user to update and remember the answer. Yes installs, No skips
+/// this version, Cancel (also Esc / the close button) reminds later.
+/// Returns true if the update should be installed now.
+pub fn prompt_update_dialog(new_version: &str, config: &Arc<Mutex<AppConfig>>) -> bool {
     use windows::core::PCWSTR;
-    use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, IDYES, MB_ICONINFORMATION, MB_YESNO};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDNO, IDYES, MB_ICONINFORMATION, MB_YESNOCANCEL,
+    };
 
+    let remind_days = config.lock().unwrap().update_snooze.remind_days();
     let message = format!(
-        "A new version (v{}) of SaveMyEyes is available!\n\nWould you like to download and install it now?\n\nThe app will restart automatically after updating.",
-        new_version
+        "A new version (v{}) of SaveMyEyes is available!\n\nWould you like to download and install it now?\n\nThe app will restart automatically after updating.\n\nYes \u{2014} install now\nNo \u{2014} skip this version\nCancel \u{2014} remind me in {} day{}",
+        new_version,
+        remind_days,
+        if remind_days == 1 { "" } else { "s" }
     );
     let msg_wide: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
     let title_wide: Vec<u16> = "SaveMyEyes Update".encode_utf16().chain(std::iter::once(0)).collect();
 
-    unsafe {
-        let result = MessageBoxW(
+    let result = unsafe {
+        MessageBoxW(
+            None,
+            PCWSTR(msg_wide.as_ptr()),
+            PCWSTR(title_wide.as_ptr()),
+            MB_YESNOCANCEL | MB_ICONINFORMATION,
+        )
+    };
+    let choice = if result == IDYES {
+        UpdateChoice::Install
+    } else if result == IDNO {
+        UpdateChoice::Skip
+    } else {
+        UpdateChoice::Later
+    };
+
+    let mut cfg = config.lock().unwrap();
+    cfg.update_snooze.record(choice, new_version, now_secs());
+    config::save_config(&cfg);
+    eprintln!("[updater] v{}: {:?}", new_version, choice);
+    choice == UpdateChoice::Install
+}
+
+/// "Check for Updates" found a staged release this machine isn't in yet
+/// (`percent` of machines have it): ask whether to get it now anyway. Yes
+/// installs; No leaves it to reach this machine with the rollout. Returns
+/// true if the update should be installed now.
+pub fn prompt_held_back_dialog(
+    new_version: &str,
+    percent: u8,
+    config: &Arc<Mutex<AppConfig>>,
+) -> bool {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDYES, MB_ICONINFORMATION, MB_YESNO,
+    };
+
+    let message = format!(
+        "SaveMyEyes v{} is being rolled out gradually and has reached {}% of computers so far, not this one yet.\n\nGet it now anyway?\n\nThe app will restart automatically after updating.",
+        new_version, percent
+    );
+    let msg_wide: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
+    let title_wide: Vec<u16> = "SaveMyEyes Update".encode_utf16().chain(std::iter::once(0)).collect();
+
+    let result = unsafe {
+        MessageBoxW(
             None,
             PCWSTR(msg_wide.as_ptr()),
             PCWSTR(title_wide.as_ptr()),
             MB_YESNO | MB_ICONINFORMATION,
-        );
-        result == IDYES
+        )
+    };
+    if result != IDYES {
+        eprintln!("[updater] v{}: waiting for the rollout", new_version);
+        return false;
     }
+    let mut cfg = config.lock().unwrap();
+    cfg.update_snooze.record(UpdateChoice::Install, new_version, now_secs());
+    config::save_config(&cfg);
+    eprintln!("[updater] v{}: installing ahead of the rollout", new_version);
+    true
 }
@@ -0,0 +1,78 @@
+// savemyeyes:// links (shared events.rs `from_url`) on Windows.
+//
+// The scheme is registered per user under HKCU\Software\Classes, so no admin
+// rights are needed. Opening a link starts the exe with the URL as its
+// argument; Command::from_args parses it and main.rs forwards it to the
+// running instance over WM_COPYDATA (events.rs), or runs it once the window
+// exists if SaveMyEyes wasn't running. The keys are written at every start
+// so they follow the exe if it moves.
+
+use windows::core::PCWSTR;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_WRITE,
+    REG_OPTION_NON_VOLATILE, REG_SZ,
+};
+
+use savemyeyes_shared::events::URL_SCHEME;
+
+/// Register the scheme for the current exe (UI thread, at startup).
+pub fn register() {
+    let Ok(exe) = std::env::current_exe() else {
+        eprintln!("[url_scheme] could not find our exe");
+        return;
+    };
+    let exe = exe.display().to_string();
+    let root = format!("Software\\Classes\\{}", URL_SCHEME);
+    let values = [
+        (root.clone(), None, "URL:SaveMyEyes".to_string()),
+        (root.clone(), Some("URL Protocol"), String::new()),
+        (
+            format!("{}\\DefaultIcon", root),
+            None,
+            format!("\"{}\",0", exe),
+        ),
+        (
+            format!("{}\\shell\\open\\command", root),
+            None,
+            format!("\"{}\" \"%1\"", exe),
+        ),
+    ];
+    for (key, name, data) in &values {
+        if !set_value(key, *name, data) {
+            eprintln!("[url_scheme] could not write HKCU\\{}", key);
+            return;
+        }
+    }
+    eprintln!("[url_scheme] {}:// registered", URL_SCHEME);
+}
+
+/// Set value `name` (the default value for None) of HKCU\`key` to `data`
+fn set_value(key: &str, name: Option<&str>, data: &str) -> bool {
+    let wide = |text: &str| -> Vec<u16> { text.encode_utf16().chain(std::iter::once(0)).collect() };
+    let key_path = wide(key);
+    let name = name.map(wide);
+    let data = wide(data);
+    let data_bytes =
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * 2) };
+    let mut hkey = HKEY::default();
+    unsafe {
+        let created = RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key_path.as_ptr()),
+            Some(0),
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_WRITE,
+            None,
+            &mut hkey,
+            None,
+        );
+        if created.is_err() {
+            return false;
+        }
+        let name = name.as_ref().map_or(PCWSTR::null(), |n| PCWSTR(n.as_ptr()));
+        let result = RegSetValueExW(hkey, name, Some(0), REG_SZ, Some(data_bytes));
+        let _ = RegCloseKey(hkey);
+        result.is_ok()
+    }
+}